@@ -63,7 +63,10 @@ fn default_config() -> cbindgen::Config {
                 ("VoidArg".into(), "void".into()),
                 ("KeyEventArg".into(), "KeyEvent".into()),
                 ("PointerEventArg".into(), "PointerEvent".into()),
+                ("ScrollEventArg".into(), "ScrollEvent".into()),
                 ("PointArg".into(), "Point".into()),
+                ("IntArg".into(), "int".into()),
+                ("StringArg".into(), "SharedString".into()),
                 ("FloatArg".into(), "float".into()),
                 ("Coord".into(), "float".into()),
             ]
@@ -119,6 +122,8 @@ fn gen_corelib(
         "ImageItem",
         "ClippedImage",
         "TouchArea",
+        "HoverArea",
+        "LifecycleHandler",
         "FocusScope",
         "Flickable",
         "Text",
@@ -130,6 +135,7 @@ fn gen_corelib(
         "Rotate",
         "Opacity",
         "Layer",
+        "CustomItem",
     ];
 
     config.export.include = [
@@ -146,11 +152,19 @@ fn gen_corelib(
         "FillRule",
         "MouseCursor",
         "InputType",
+        "PasswordEcho",
         "StandardButtonKind",
         "DialogButtonRole",
         "PointerEventKind",
         "PointerEventButton",
         "PointerEvent",
+        "ScrollEventPhase",
+        "ScrollEvent",
+        "OvershootBehavior",
+        "MessageLevel",
+        "AccessibleRole",
+        "FocusGroupPolicy",
+        "AccessibleStringProperty",
     ]
     .iter()
     .chain(items.iter())
@@ -187,18 +201,26 @@ fn gen_corelib(
         "slint_property_listener_scope_evaluate",
         "slint_property_listener_scope_is_dirty",
         "PropertyTrackerOpaque",
+        "ChangeTrackerOpaque",
         "CallbackOpaque",
         "WindowRc",
         "VoidArg",
         "KeyEventArg",
         "PointerEventArg",
         "PointArg",
+        "IntArg",
+        "StringArg",
         "Point",
         "slint_color_brighter",
         "slint_color_darker",
+        "slint_color_lighter",
+        "slint_color_saturate",
+        "slint_color_with_alpha",
+        "slint_color_mix",
         "slint_image_size",
         "slint_image_path",
         "Coord",
+        "AccessibilityAction",
     ]
     .iter()
     .chain(public_exported_types.iter())
@@ -263,12 +285,21 @@ fn gen_corelib(
                 "SharedPixelBuffer",
                 "SharedImageBuffer",
                 "StaticTextures",
+                "VideoStreamId",
             ],
             vec!["Color"],
             "slint_image_internal.h",
         ),
         (
-            vec!["Color", "slint_color_brighter", "slint_color_darker"],
+            vec![
+                "Color",
+                "slint_color_brighter",
+                "slint_color_darker",
+                "slint_color_lighter",
+                "slint_color_saturate",
+                "slint_color_with_alpha",
+                "slint_color_mix",
+            ],
             vec![],
             "slint_color_internal.h",
         ),
@@ -278,6 +309,7 @@ fn gen_corelib(
             "slint_pathdata_internal.h",
         ),
         (vec!["Brush", "LinearGradient", "GradientStop"], vec!["Color"], "slint_brush_internal.h"),
+        (vec!["AccessibilityAction"], vec![], "slint_accessibility_internal.h"),
     ]
     .iter()
     {
@@ -291,6 +323,11 @@ fn gen_corelib(
             "slint_windowrc_hide",
             "slint_windowrc_get_scale_factor",
             "slint_windowrc_set_scale_factor",
+            "slint_windowrc_get_window_item_width",
+            "slint_windowrc_get_window_item_height",
+            "slint_windowrc_get_default_font_size",
+            "slint_windowrc_get_text_scale_factor",
+            "slint_windowrc_set_text_scale_factor",
             "slint_windowrc_free_graphics_resources",
             "slint_windowrc_set_focus_item",
             "slint_windowrc_set_component",
@@ -298,12 +335,19 @@ fn gen_corelib(
             "slint_windowrc_set_rendering_notifier",
             "slint_windowrc_request_redraw",
             "slint_windowrc_on_close_requested",
+            "slint_windowrc_on_first_frame_rendered",
+            "slint_windowrc_set_show_after_first_frame",
             "slint_new_path_elements",
             "slint_new_path_events",
             "slint_color_brighter",
             "slint_color_darker",
+            "slint_color_lighter",
+            "slint_color_saturate",
+            "slint_color_with_alpha",
+            "slint_color_mix",
             "slint_image_size",
             "slint_image_path",
+            "slint_animation_tick",
         ]
         .iter()
         .filter(|exclusion| !rust_types.iter().any(|inclusion| inclusion == *exclusion))
@@ -336,6 +380,7 @@ fn gen_corelib(
             .with_src(crate_dir.join("graphics/brush.rs"))
             .with_src(crate_dir.join("graphics/image.rs"))
             .with_src(crate_dir.join("animations.rs"))
+            .with_src(crate_dir.join("accessibility.rs"))
             //            .with_src(crate_dir.join("input.rs"))
             .with_src(crate_dir.join("item_rendering.rs"))
             .with_src(crate_dir.join("window.rs"))
@@ -405,12 +450,31 @@ fn gen_corelib(
         "StandardListViewItem".to_owned(),
         "friend bool operator==(const StandardListViewItem&, const StandardListViewItem&) = default;".into(),
     );
+    config.export.body.insert(
+        "TableColumn".to_owned(),
+        "friend bool operator==(const TableColumn&, const TableColumn&) = default;".into(),
+    );
+    config.export.body.insert(
+        "TextSpan".to_owned(),
+        "friend bool operator==(const TextSpan&, const TextSpan&) = default;".into(),
+    );
     config
         .export
         .body
         .insert("Flickable".to_owned(), "    inline Flickable(); inline ~Flickable();".into());
     config.export.pre_body.insert("FlickableDataBox".to_owned(), "struct FlickableData;".into());
+    config
+        .export
+        .body
+        .insert("CustomItem".to_owned(), "    inline CustomItem(); inline ~CustomItem();".into());
+    config.export.pre_body.insert("CustomItemDataBox".to_owned(), "struct CustomItemData;".into());
+    config
+        .export
+        .body
+        .insert("TextInput".to_owned(), "    inline TextInput(); inline ~TextInput();".into());
+    config.export.pre_body.insert("TextInputDataBox".to_owned(), "struct TextInputData;".into());
     config.export.include.push("StandardListViewItem".into());
+    config.export.include.push("TableColumn".into());
     cbindgen::Builder::new()
         .with_config(config)
         .with_src(crate_dir.join("lib.rs"))
@@ -418,6 +482,7 @@ fn gen_corelib(
         .with_include("vtable.h")
         .with_include("slint_string.h")
         .with_include("slint_sharedvector.h")
+        .with_include("slint_accessibility_internal.h")
         .with_include("slint_properties.h")
         .with_include("slint_callbacks.h")
         .with_include("slint_color.h")
@@ -433,10 +498,11 @@ fn gen_corelib(
     namespace cbindgen_private {
         using slint::private_api::WindowRc;
         using namespace vtable;
-        struct KeyEvent; struct PointerEvent;
+        struct KeyEvent; struct PointerEvent; struct ScrollEvent;
         using private_api::Property;
         using private_api::PathData;
         using private_api::Point;
+        using types::AccessibilityAction;
     }
 }",
         )
@@ -14,7 +14,7 @@ function(SLINT_TARGET_SOURCES target)
 
             add_custom_command(
                 OUTPUT ${CMAKE_CURRENT_BINARY_DIR}/${_SLINT_BASE_NAME}.h
-                COMMAND Slint::slint-compiler ${_SLINT_ABSOLUTE}
+                COMMAND ${CMAKE_COMMAND} -E env ${SLINT_COMPILER_ENV} $<TARGET_FILE:Slint::slint-compiler> ${_SLINT_ABSOLUTE}
                     -o ${_SLINT_BASE_NAME_REL}.h  --depfile ${_SLINT_BASE_NAME_REL}.d
                     --style ${SLINT_STYLE}
                 DEPENDS Slint::slint-compiler ${_SLINT_ABSOLUTE}
@@ -27,7 +27,7 @@ function(SLINT_TARGET_SOURCES target)
             file(GLOB ALL_SLINTS "${_SLINT_DIR}/*.slint")
             add_custom_command(
                 OUTPUT ${CMAKE_CURRENT_BINARY_DIR}/${_SLINT_BASE_NAME}.h
-                COMMAND Slint::slint-compiler ${_SLINT_ABSOLUTE}
+                COMMAND ${CMAKE_COMMAND} -E env ${SLINT_COMPILER_ENV} $<TARGET_FILE:Slint::slint-compiler> ${_SLINT_ABSOLUTE}
                     -o ${CMAKE_CURRENT_BINARY_DIR}/${_SLINT_BASE_NAME}.h
                     --style ${SLINT_STYLE}
                 DEPENDS Slint::slint-compiler ${_SLINT_ABSOLUTE} ${ALL_SLINTS}
@@ -38,6 +38,12 @@ struct ItemVTable;
 /// \endrst
 namespace slint {
 
+/// The color scheme preferred by the user, as reported by the platform.
+using cbindgen_private::ColorScheme;
+
+/// The order in which the rows of a table are sorted by a column.
+using cbindgen_private::SortOrder;
+
 // Bring opaque structure in scope
 namespace private_api {
 using cbindgen_private::ComponentVTable;
@@ -60,7 +66,10 @@ using ItemArray = slint::cbindgen_private::Slice<ItemArrayEntry>;
 using cbindgen_private::KeyboardModifiers;
 using cbindgen_private::KeyEvent;
 using cbindgen_private::PointerEvent;
+using cbindgen_private::ScrollEvent;
 using cbindgen_private::StandardListViewItem;
+using cbindgen_private::TableColumn;
+using cbindgen_private::TextSpan;
 
 /// Internal function that checks that the API that must be called from the main
 /// thread is indeed called from the main thread, or abort the program otherwise
@@ -112,6 +121,16 @@ public:
     float scale_factor() const { return slint_windowrc_get_scale_factor(&inner); }
     void set_scale_factor(float value) const { slint_windowrc_set_scale_factor(&inner, value); }
 
+    float window_item_width() const { return slint_windowrc_get_window_item_width(&inner); }
+    float window_item_height() const { return slint_windowrc_get_window_item_height(&inner); }
+    float default_font_size() const { return slint_windowrc_get_default_font_size(&inner); }
+
+    float text_scale_factor() const { return slint_windowrc_get_text_scale_factor(&inner); }
+    void set_text_scale_factor(float value) const
+    {
+        slint_windowrc_set_text_scale_factor(&inner, value);
+    }
+
     template<typename Component, typename ItemArray>
     void free_graphics_resources(Component *c, ItemArray items) const
     {
@@ -125,6 +144,24 @@ public:
         cbindgen_private::slint_windowrc_set_focus_item(&inner, &item_rc);
     }
 
+    void text_input_undo(const ComponentRc &component_rc, uintptr_t item_index) const
+    {
+        cbindgen_private::ItemRc item_rc { component_rc, item_index };
+        cbindgen_private::slint_text_input_undo(&item_rc, &inner);
+    }
+
+    void text_input_redo(const ComponentRc &component_rc, uintptr_t item_index) const
+    {
+        cbindgen_private::ItemRc item_rc { component_rc, item_index };
+        cbindgen_private::slint_text_input_redo(&item_rc, &inner);
+    }
+
+    bool text_input_find(const ComponentRc &component_rc, uintptr_t item_index, bool forward) const
+    {
+        cbindgen_private::ItemRc item_rc { component_rc, item_index };
+        return cbindgen_private::slint_text_input_find(&item_rc, &inner, forward);
+    }
+
     template<typename Component, typename ItemArray>
     void init_items(Component *c, ItemArray items) const
     {
@@ -173,8 +210,39 @@ public:
                 new F(std::move(callback)));
     }
 
+    template<typename F>
+    void on_color_scheme_changed(F callback) const
+    {
+        auto actual_cb = [](void *data, cbindgen_private::ColorScheme scheme) {
+            (*reinterpret_cast<F *>(data))(scheme);
+        };
+        cbindgen_private::slint_windowrc_on_color_scheme_changed(
+                &inner, actual_cb, [](void *user_data) { delete reinterpret_cast<F *>(user_data); },
+                new F(std::move(callback)));
+    }
+
+    template<typename F>
+    void on_first_frame_rendered(F callback) const
+    {
+        auto actual_cb = [](void *data) { (*reinterpret_cast<F *>(data))(); };
+        cbindgen_private::slint_windowrc_on_first_frame_rendered(
+                &inner, actual_cb, [](void *user_data) { delete reinterpret_cast<F *>(user_data); },
+                new F(std::move(callback)));
+    }
+
+    void set_show_after_first_frame(bool value) const
+    {
+        cbindgen_private::slint_windowrc_set_show_after_first_frame(&inner, value);
+    }
+
     void request_redraw() const { cbindgen_private::slint_windowrc_request_redraw(&inner); }
 
+    void focus_next_item() const { cbindgen_private::slint_windowrc_focus_next_item(&inner); }
+    void focus_previous_item() const
+    {
+        cbindgen_private::slint_windowrc_focus_previous_item(&inner);
+    }
+
 private:
     cbindgen_private::WindowRcOpaque inner;
 };
@@ -201,6 +269,34 @@ inline ItemRef get_item_ref(ComponentRef component,
     return ItemRef { item.vtable, reinterpret_cast<char *>(component.instance) + item.offset };
 }
 
+inline void flickable_scroll_to(const ComponentRc &component_rc, uintptr_t item_index, float x,
+                                float y, bool animated)
+{
+    cbindgen_private::ItemRc item_rc { component_rc, item_index };
+    cbindgen_private::slint_flickable_scroll_to(&item_rc, x, y, animated);
+}
+
+inline void flickable_ensure_visible(const ComponentRc &component_rc, uintptr_t item_index,
+                                     const ComponentRc &target_component_rc,
+                                     uintptr_t target_item_index)
+{
+    cbindgen_private::ItemRc item_rc { component_rc, item_index };
+    cbindgen_private::ItemRc target_rc { target_component_rc, target_item_index };
+    cbindgen_private::slint_flickable_ensure_visible(&item_rc, &target_rc);
+}
+
+inline int text_input_search_match_count(const ComponentRc &component_rc, uintptr_t item_index)
+{
+    cbindgen_private::ItemRc item_rc { component_rc, item_index };
+    return cbindgen_private::slint_text_input_search_match_count(&item_rc);
+}
+
+inline int text_input_current_match(const ComponentRc &component_rc, uintptr_t item_index)
+{
+    cbindgen_private::ItemRc item_rc { component_rc, item_index };
+    return cbindgen_private::slint_text_input_current_match(&item_rc);
+}
+
 inline void dealloc(const ComponentVTable *, uint8_t *ptr, vtable::Layout layout)
 {
 #ifdef __cpp_sized_deallocation
@@ -258,6 +354,25 @@ class ComponentHandle
 public:
     /// internal constructor
     ComponentHandle(const vtable::VRc<private_api::ComponentVTable, T> &inner) : inner(inner) { }
+    ComponentHandle(const ComponentHandle &) = default;
+    ComponentHandle &operator=(const ComponentHandle &other)
+    {
+        if (this != &other) {
+            this->~ComponentHandle();
+            new (this) ComponentHandle(other);
+        }
+        return *this;
+    }
+
+    /// Calls the `will-destroy` callbacks of the elements of the component, when it is destroyed
+    /// with its last handle
+    ~ComponentHandle()
+    {
+        if (inner.strong_count() == 1) {
+            auto component = inner.into_dyn();
+            cbindgen_private::slint_component_will_destroy(&component);
+        }
+    }
 
     /// Arrow operator that implements pointer semantics.
     const T *operator->() const
@@ -359,9 +474,60 @@ public:
         return inner.on_close_requested(std::forward<F>(callback));
     }
 
+    /// Returns the color scheme preferred by the user, as reported by the windowing system.
+    ColorScheme color_scheme() const { return cbindgen_private::slint_color_scheme(); }
+
+    /// This function allows registering a callback that's invoked when the color scheme
+    /// preferred by the user changes. The callback receives the new ColorScheme.
+    template<typename F>
+    void on_color_scheme_changed(F &&callback) const
+    {
+        static_assert(std::is_invocable_v<F, ColorScheme>,
+                      "Functor callback must be callable with a ColorScheme");
+        inner.on_color_scheme_changed(std::forward<F>(callback));
+    }
+
+    /// This function allows registering a callback that's invoked once the first frame of the
+    /// window was presented on the screen, after the window is shown. This is the point where an
+    /// application can dismiss its splash screen. The callback is only invoked by the GL backend.
+    template<typename F>
+    void on_first_frame_rendered(F &&callback) const
+    {
+        static_assert(std::is_invocable_v<F>, "Functor callback must be callable");
+        inner.on_first_frame_rendered(std::forward<F>(callback));
+    }
+
+    /// Sets whether the window is kept invisible when it is shown, until its first frame is
+    /// rendered. This must be called before show(). It is only supported by the GL backend.
+    void set_show_after_first_frame(bool value) const { inner.set_show_after_first_frame(value); }
+
+    /// Returns the factor by which the size of all fonts in the window is multiplied.
+    /// The default is 1.0.
+    float text_scale_factor() const { return inner.text_scale_factor(); }
+
+    /// Sets the factor by which the size of all fonts in the window is multiplied, on top of the
+    /// scale factor provided by the windowing system. This can be used to let the user of an
+    /// application choose a bigger or smaller text size.
+    ///
+    /// Lengths that use the `rem` unit are also multiplied by this factor.
+    void set_text_scale_factor(float factor) const
+    {
+        inner.set_text_scale_factor(factor);
+        inner.request_redraw();
+    }
+
     /// This function issues a request to the windowing system to redraw the contents of the window.
     void request_redraw() const { inner.request_redraw(); }
 
+    /// Moves the keyboard focus to the next item that accepts it, as when pressing the Tab key.
+    ///
+    /// The items with a positive `tab-index` come first, by increasing `tab-index`, then the other
+    /// items in the order in which they are declared.
+    void focus_next_item() const { inner.focus_next_item(); }
+
+    /// Moves the keyboard focus to the previous item that accepts it, as when pressing Shift+Tab.
+    void focus_previous_item() const { inner.focus_previous_item(); }
+
     /// \private
     private_api::WindowRc &window_handle() { return inner; }
     /// \private
@@ -753,6 +919,19 @@ class Repeater
         };
         std::vector<ComponentWithState> data;
         private_api::Property<bool> is_dirty { true };
+        // The value last written to the first-visible-item property of the ListView
+        int reported_first_visible_item = 0;
+
+        // Calls the will-destroy callbacks of the components of the rows in [begin, end)
+        void will_destroy(std::size_t begin, std::size_t end)
+        {
+            for (std::size_t i = begin; i < end && i < data.size(); ++i) {
+                if (auto &c = data[i]; c.ptr) {
+                    auto component = c.ptr->into_dyn();
+                    cbindgen_private::slint_component_will_destroy(&component);
+                }
+            }
+        }
 
         void row_added(int index, int count) override
         {
@@ -768,6 +947,7 @@ class Repeater
         void row_removed(int index, int count) override
         {
             is_dirty.set(true);
+            will_destroy(index, index + count);
             data.erase(data.begin() + index, data.begin() + index + count);
             for (std::size_t i = index; i < data.size(); ++i) {
                 // all the indexes are dirty
@@ -776,6 +956,7 @@ class Repeater
         }
         void reset() override {
             is_dirty.set(true);
+            will_destroy(0, data.size());
             data.clear();
         }
     };
@@ -794,6 +975,9 @@ public:
     void ensure_updated(const Parent *parent) const
     {
         if (model.is_dirty()) {
+            if (inner) {
+                inner->will_destroy(0, inner->data.size());
+            }
             inner = std::make_shared<RepeaterInner>();
             if (auto m = model.get()) {
                 m->attach_peer(inner);
@@ -804,6 +988,7 @@ public:
             inner->is_dirty.set(false);
             if (auto m = model.get()) {
                 int count = m->row_count();
+                inner->will_destroy(count, inner->data.size());
                 inner->data.resize(count);
                 for (int i = 0; i < count; ++i) {
                     auto &c = inner->data[i];
@@ -815,6 +1000,7 @@ public:
                     }
                 }
             } else {
+                inner->will_destroy(0, inner->data.size());
                 inner->data.clear();
             }
         } else {
@@ -828,14 +1014,30 @@ public:
     void ensure_updated_listview(const Parent *parent,
                                  const private_api::Property<float> *viewport_width,
                                  const private_api::Property<float> *viewport_height,
-                                 [[maybe_unused]] const private_api::Property<float> *viewport_y,
-                                 float listview_width, [[maybe_unused]] float listview_height) const
+                                 const private_api::Property<float> *viewport_y,
+                                 float listview_width, float listview_height,
+                                 const private_api::Property<int> *first_visible_item) const
     {
         // TODO: the rust code in model.rs try to only allocate as many items as visible items
+        // and keeps the first visible item in place when rows are added or removed before it
         ensure_updated(parent);
 
-        float h = compute_layout_listview(viewport_width, listview_width);
+        std::vector<float> positions;
+        float h = compute_layout_listview(viewport_width, listview_width, &positions);
         viewport_height->set(h);
+        if (positions.empty())
+            return;
+
+        int requested = first_visible_item->get();
+        if (requested != inner->reported_first_visible_item && requested >= 0) {
+            // The application scrolls to a row
+            auto row = std::min(std::size_t(requested), positions.size() - 1);
+            viewport_y->set(-std::min(positions[row], std::max(0.f, h - listview_height)));
+        }
+        auto it = std::upper_bound(positions.begin(), positions.end(), -viewport_y->get());
+        int first = std::max(0, int(it - positions.begin()) - 1);
+        inner->reported_first_visible_item = first;
+        first_visible_item->set(first);
     }
 
     uintptr_t visit(TraversalOrder order, private_api::ItemVisitorRefMut visitor) const
@@ -868,13 +1070,16 @@ public:
     }
 
     float compute_layout_listview(const private_api::Property<float> *viewport_width,
-                                  float listview_width) const
+                                  float listview_width,
+                                  std::vector<float> *positions = nullptr) const
     {
         float offset = 0;
         viewport_width->set(listview_width);
         if (!inner)
             return offset;
         for (auto &x : inner->data) {
+            if (positions)
+                positions->push_back(offset);
             (*x.ptr)->listview_layout(&offset, viewport_width);
         }
         return offset;
@@ -910,6 +1115,22 @@ cbindgen_private::Flickable::~Flickable()
 {
     slint_flickable_data_free(&data);
 }
+cbindgen_private::TextInput::TextInput()
+{
+    slint_text_input_data_init(&data);
+}
+cbindgen_private::TextInput::~TextInput()
+{
+    slint_text_input_data_free(&data);
+}
+cbindgen_private::CustomItem::CustomItem()
+{
+    slint_custom_item_data_init(&data);
+}
+cbindgen_private::CustomItem::~CustomItem()
+{
+    slint_custom_item_data_free(&data);
+}
 
 cbindgen_private::NativeStyleMetrics::NativeStyleMetrics(void *)
 {
@@ -1065,6 +1286,22 @@ auto blocking_invoke_from_event_loop(Functor f) -> void
     cv.wait(lock, [&] { return ok; });
 }
 
+/// Changes the locale used by the `format-number`, `format-percent`, `format-date` and
+/// `format-time` functions of the `.slint` language. The \a name is a language tag such
+/// as `"de_DE"` or `"fr-CH"`. Bindings using these functions are re-evaluated.
+inline void set_locale(const SharedString &name)
+{
+    cbindgen_private::slint_locale_set(&name);
+}
+
+/// Changes the color scheme reported to the application, overriding the one of the platform.
+/// Bindings using `Platform.color-scheme` are re-evaluated, and the callbacks registered with
+/// Window::on_color_scheme_changed() are invoked.
+inline void set_color_scheme(ColorScheme scheme)
+{
+    cbindgen_private::slint_set_color_scheme(scheme);
+}
+
 namespace private_api {
 
 /// Registers a font by the specified path. The path must refer to an existing
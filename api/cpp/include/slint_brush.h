@@ -90,6 +90,31 @@ public:
     /// by the specified factor. This is done by calling Color::darker on
     /// all the colors of this brush.
     inline Brush darker(float factor) const;
+    /// Returns a new version of this brush with the perceived lightness changed by the
+    /// specified factor. This is done by calling Color::lighter on all the colors of this brush.
+    inline Brush lighter(float factor) const
+    {
+        return map_colors([=](const Color &c) { return c.lighter(factor); });
+    }
+    /// Returns a new version of this brush with the saturation changed by the specified
+    /// factor. This is done by calling Color::saturate on all the colors of this brush.
+    inline Brush saturate(float factor) const
+    {
+        return map_colors([=](const Color &c) { return c.saturate(factor); });
+    }
+    /// Returns a new version of this brush with the alpha channel of all its colors replaced
+    /// by \a alpha. See Color::with_alpha.
+    inline Brush with_alpha(float alpha) const
+    {
+        return map_colors([=](const Color &c) { return c.with_alpha(alpha); });
+    }
+    /// Returns a brush where all the colors of this brush are mixed with the color of
+    /// \a other. See Color::mix.
+    inline Brush mix(const Brush &other, float factor) const
+    {
+        Color other_color = other.color();
+        return map_colors([&](const Color &c) { return c.mix(other_color, factor); });
+    }
 
     /// Returns true if \a a is equal to \a b. If \a a holds a color, then \a b must also hold a
     /// color that is identical to \a a's color. If it holds a gradient, then the gradients must be
@@ -103,6 +128,29 @@ private:
     using Tag = cbindgen_private::types::Brush::Tag;
     using Inner = cbindgen_private::types::Brush;
     Inner data;
+
+    template<typename F>
+    Brush map_colors(F f) const
+    {
+        auto map = [&](const cbindgen_private::types::Color &in,
+                       cbindgen_private::types::Color &out) {
+            Color color;
+            color.inner = in;
+            out = f(color).inner;
+        };
+        Brush result = *this;
+        switch (data.tag) {
+        case Tag::SolidColor:
+            map(data.solid_color._0, result.data.solid_color._0);
+            break;
+        case Tag::LinearGradient:
+            for (std::size_t i = 1; i < data.linear_gradient._0.size(); ++i) {
+                map(data.linear_gradient._0[i].color, result.data.linear_gradient._0[i].color);
+            }
+            break;
+        }
+        return result;
+    }
 };
 
 Color Brush::color() const
@@ -146,6 +146,19 @@ public:
     /// result is converted back to RGB and the alpha channel is unchanged.
     /// So for example `darker(0.3)` will decrease the brightness by 30%.
     inline Color darker(float factor) const;
+    /// Returns a new version of this color with the perceived lightness changed by the
+    /// specified factor, computed in the OKLab color space. A positive factor moves the
+    /// color towards white by that fraction, a negative factor towards black.
+    inline Color lighter(float factor) const;
+    /// Returns a new version of this color that has its saturation multiplied by (1 + factor),
+    /// using the HSL color space. Negative factors desaturate the color.
+    inline Color saturate(float factor) const;
+    /// Returns a new version of this color with the alpha channel replaced by \a alpha,
+    /// a value between 0 (transparent) and 1 (opaque).
+    inline Color with_alpha(float alpha) const;
+    /// Returns a color that is a mix between this color and \a other, computed in the OKLab
+    /// color space. A \a factor of 0 returns this color, and a factor of 1 returns \a other.
+    inline Color mix(const Color &other, float factor) const;
 
     /// Returns true if \a lhs has the same values for the individual color channels as \a rhs;
     /// false otherwise.
@@ -186,6 +199,34 @@ inline Color Color::darker(float factor) const
     return result;
 }
 
+inline Color Color::lighter(float factor) const
+{
+    Color result;
+    cbindgen_private::types::slint_color_lighter(&inner, factor, &result.inner);
+    return result;
+}
+
+inline Color Color::saturate(float factor) const
+{
+    Color result;
+    cbindgen_private::types::slint_color_saturate(&inner, factor, &result.inner);
+    return result;
+}
+
+inline Color Color::with_alpha(float alpha) const
+{
+    Color result;
+    cbindgen_private::types::slint_color_with_alpha(&inner, alpha, &result.inner);
+    return result;
+}
+
+inline Color Color::mix(const Color &other, float factor) const
+{
+    Color result;
+    cbindgen_private::types::slint_color_mix(&inner, &other.inner, factor, &result.inner);
+    return result;
+}
+
 /// Constructs a new RgbaColor<uint8_t> from the color \a color.
 template<>
 inline RgbaColor<uint8_t>::RgbaColor(const Color &color)
@@ -4,6 +4,8 @@
 #pragma once
 #include <string_view>
 #include <memory>
+#include <optional>
+#include <type_traits>
 
 namespace slint::cbindgen_private {
 struct PropertyAnimation;
@@ -292,4 +294,51 @@ private:
     cbindgen_private::PropertyTrackerOpaque inner;
 };
 
+/// ChangeTracker calls a functor when the value returned by another functor changes.
+/// Unlike the bindings, the value is evaluated again as soon as one of the properties it depends on
+/// changes, and the functor is called before the code that changed the property continues.
+struct ChangeTracker
+{
+    /// Constructs a new change tracker instance.
+    ChangeTracker() { cbindgen_private::slint_change_tracker_construct(&inner); }
+    /// Destroys the change tracker.
+    ~ChangeTracker() { cbindgen_private::slint_change_tracker_drop(&inner); }
+    /// The copy constructor is intentionally deleted, change trackers cannot be copied.
+    ChangeTracker(const ChangeTracker &) = delete;
+    /// The assignment operator is intentionally deleted, change trackers cannot be copied.
+    ChangeTracker &operator=(const ChangeTracker &) = delete;
+
+    /// Starts tracking the value returned by \a eval, and calls \a notify when it changes.
+    /// Both functors are called with \a data. \a notify isn't called for the initial value.
+    template<typename Data, typename FnEval, typename FnNotify>
+    void init(Data data, FnEval eval, FnNotify notify) const
+    {
+        using Value = std::invoke_result_t<FnEval, Data &>;
+        struct Inner
+        {
+            Data data;
+            FnEval eval;
+            FnNotify notify;
+            std::optional<Value> value;
+        };
+        cbindgen_private::slint_change_tracker_init(
+                &inner, new Inner { std::move(data), std::move(eval), std::move(notify), {} },
+                [](void *user_data) { delete reinterpret_cast<Inner *>(user_data); },
+                [](void *user_data) {
+                    auto self = reinterpret_cast<Inner *>(user_data);
+                    Value value = self->eval(self->data);
+                    bool changed = !self->value || !(*self->value == value);
+                    self->value = std::move(value);
+                    return changed;
+                },
+                [](void *user_data) {
+                    auto self = reinterpret_cast<Inner *>(user_data);
+                    self->notify(self->data);
+                });
+    }
+
+private:
+    cbindgen_private::ChangeTrackerOpaque inner;
+};
+
 } // namespace slint::private_api
@@ -128,6 +128,9 @@ public:
 
     VRc<VTable, Dyn> into_dyn() const { return *reinterpret_cast<const VRc<VTable, Dyn> *>(this); }
 
+    /// The number of VRc that hold the object
+    int strong_count() const { return inner->strong_ref; }
+
     VRef<VTable> borrow() const { return { inner->vtable, inner->data_ptr() }; }
 
     friend bool operator==(const VRc &a, const VRc &b) {
@@ -259,7 +259,8 @@ fn to_js_value<'cx>(
             }
             &ImageInner::EmbeddedData { .. }
             | &ImageInner::EmbeddedImage { .. }
-            | &ImageInner::StaticTextures { .. } => JsNull::new().as_value(cx), // TODO: maybe pass around node buffers?
+            | &ImageInner::StaticTextures { .. }
+            | &ImageInner::VideoStream(_) => JsNull::new().as_value(cx), // TODO: maybe pass around node buffers?
         },
         Value::Model(model) => {
             if let Some(js_model) = model.as_any().downcast_ref::<js_model::JsModel>() {
@@ -40,6 +40,19 @@ fn main() {
     HelloWorld::new().run();
 }
 ```
+
+## Live reload
+
+With [`CompilerConfiguration::with_live_reload()`], debug builds load the `.slint` files at
+run-time and reload them when they are saved, so that the design can be tweaked while the
+program runs. This requires the `live-reload` feature of the `slint` crate:
+
+```ignore
+fn main() {
+    let config = slint_build::CompilerConfiguration::new().with_live_reload(true);
+    slint_build::compile_with_config("ui/hello.slint", config).unwrap();
+}
+```
 */
 #![doc(html_logo_url = "https://slint-ui.com/logo/slint-logo-square-light.svg")]
 #![warn(missing_docs)]
@@ -60,6 +73,8 @@ fn main() {
 /// The structure for configuring aspects of the compilation of `.slint` markup files to Rust.
 pub struct CompilerConfiguration {
     config: i_slint_compiler::CompilerConfiguration,
+    live_reload: bool,
+    software_renderer: bool,
 }
 
 impl Default for CompilerConfiguration {
@@ -68,6 +83,8 @@ fn default() -> Self {
             config: i_slint_compiler::CompilerConfiguration::new(
                 i_slint_compiler::generator::OutputFormat::Rust,
             ),
+            live_reload: false,
+            software_renderer: false,
         }
     }
 }
@@ -84,15 +101,97 @@ pub fn new() -> Self {
     pub fn with_include_paths(self, include_paths: Vec<std::path::PathBuf>) -> Self {
         let mut config = self.config;
         config.include_paths = include_paths;
-        Self { config }
+        Self { config, ..self }
     }
 
     /// Create a new configuration that selects the style to be used for widgets.
+    ///
+    /// The style is either the name of a builtin style, such as `"fluent"`, or the path of a
+    /// directory that contains the `std-widgets.slint` file of a custom style.
     #[must_use]
     pub fn with_style(self, style: String) -> Self {
         let mut config = self.config;
         config.style = Some(style);
-        Self { config }
+        Self { config, ..self }
+    }
+
+    /// Create a new configuration that embeds the specified font files in the program and
+    /// registers them when the first component is created, in addition to the fonts imported
+    /// in the `.slint` files. This is useful on systems that don't provide any fonts.
+    #[must_use]
+    pub fn with_embedded_fonts(self, font_paths: Vec<std::path::PathBuf>) -> Self {
+        let mut config = self.config;
+        config.embed_fonts = font_paths;
+        Self { config, ..self }
+    }
+
+    /// Create a new configuration that selects whether the embedded fonts are reduced to the glyphs
+    /// of the characters used in the string literals of the design, and the printable ASCII characters.
+    ///
+    /// Text coming from the program at run-time that uses other characters may not be rendered.
+    #[must_use]
+    pub fn with_font_subsetting(self, subset_fonts: bool) -> Self {
+        let mut config = self.config;
+        config.subset_fonts = subset_fonts;
+        Self { config, ..self }
+    }
+
+    /// Create a new configuration that rasterizes the glyphs of the given characters at the given
+    /// font sizes, in logical pixels, when the glyphs are embedded for the software renderer with
+    /// [`Self::with_software_renderer()`]. This is in addition to the printable ASCII characters and
+    /// the characters of the string literals of the design, and to the default font size and the
+    /// sizes set in the design.
+    ///
+    /// Use it for the text that only comes from the program at run-time, such as the names of the
+    /// files in a list: the software renderer only draws the glyphs that were embedded.
+    #[must_use]
+    pub fn with_embedded_glyphs(self, characters: &str, font_sizes: Vec<f32>) -> Self {
+        let mut config = self.config;
+        config.embed_glyphs_characters = characters.into();
+        config.embed_glyphs_sizes = font_sizes;
+        Self { config, ..self }
+    }
+
+    /// Create a new configuration that describes the capabilities of the GL backend the program is
+    /// built with, when some of the features of the `slint` crate that enable them are turned off:
+    /// the file extensions of the image formats it can decode, and whether it shapes the text.
+    /// The compiler then warns about the images and the strings of the design that the backend
+    /// can't display correctly.
+    ///
+    /// This can also be set with the `SLINT_IMAGE_FORMATS` (a comma separated list of extensions)
+    /// and `SLINT_TEXT_SHAPING` environment variables.
+    #[must_use]
+    pub fn with_backend_capabilities(
+        self,
+        supported_image_formats: Option<Vec<String>>,
+        text_shaping: bool,
+    ) -> Self {
+        let mut config = self.config;
+        config.supported_image_formats = supported_image_formats;
+        config.text_shaping = text_shaping;
+        Self { config, ..self }
+    }
+
+    /// Create a new configuration that selects whether the live-reload mode is enabled.
+    ///
+    /// In that mode, debug builds of the program load the `.slint` files at run-time with the
+    /// interpreter, and load them again when they are saved, so that changes in the design show
+    /// up without recompiling the program. Release builds use the compiled code.
+    /// This requires the `live-reload` feature of the `slint` crate.
+    ///
+    /// Only the properties and callbacks known when the program was compiled are accessible
+    /// from Rust. Their values and handlers are applied again after each reload.
+    #[must_use]
+    pub fn with_live_reload(self, live_reload: bool) -> Self {
+        Self { live_reload, ..self }
+    }
+
+    /// Create a new configuration that selects whether the images and the glyphs of the fonts are
+    /// embedded in the program in the format of the software renderer, which has no image or font
+    /// loader. This is needed to draw the windows with `slint::platform::swrenderer`.
+    #[must_use]
+    pub fn with_software_renderer(self, software_renderer: bool) -> Self {
+        Self { software_renderer, ..self }
     }
 }
 
@@ -175,6 +274,10 @@ fn flush(&mut self) -> std::io::Result<()> {
 /// result to make sure that cargo make the compilation fail in case there were
 /// errors when generating the code.
 ///
+/// When the `SLINT_DIAGNOSTICS_FORMAT` environment variable is set to `json`, the errors
+/// and warnings are instead printed in `stderr` as one JSON object per line, with the
+/// location, the message and the suggested fixes, so that they can be processed by tools.
+///
 /// Please check out the documentation of the `slint` crate for more information
 /// about how to use the generated code.
 pub fn compile(path: impl AsRef<std::path::Path>) -> Result<(), CompileError> {
@@ -194,13 +297,16 @@ pub fn compile_with_config(
 
     if diag.has_error() {
         let vec = diag.to_string_vec();
-        diag.print();
+        print_errors(diag);
         return Err(CompileError::CompileError(vec));
     }
 
     let mut compiler_config = config.config;
 
-    if env::var_os("DEP_I_SLINT_BACKEND_MCU_EMBED_TEXTURES").is_some() {
+    if config.software_renderer
+        || env::var_os("DEP_I_SLINT_BACKEND_MCU_EMBED_TEXTURES").is_some()
+        || env::var_os("DEP_I_SLINT_BACKEND_LINUXKMS_EMBED_TEXTURES").is_some()
+    {
         compiler_config.embed_resources = EmbedResourcesKind::EmbedTextures;
     } else if let (Ok(target), Ok(host)) = (env::var("TARGET"), env::var("HOST")) {
         if target != host {
@@ -227,13 +333,26 @@ pub fn compile_with_config(
 
     let syntax_node = syntax_node.expect("diags contained no compilation errors");
 
+    // In live-reload mode, the generated code needs the configuration to load the file at run-time
+    let live_reload = (config.live_reload && env::var("PROFILE").map_or(false, |p| p == "debug"))
+        .then(|| {
+            // The include paths are relative to the directory of the build script
+            let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").unwrap_or_default();
+            let include_paths = compiler_config
+                .include_paths
+                .iter()
+                .map(|p| Path::new(&manifest_dir).join(p))
+                .collect::<Vec<_>>();
+            (include_paths, compiler_config.style.clone())
+        });
+
     // 'spin_on' is ok here because the compiler in single threaded and does not block if there is no blocking future
     let (doc, diag) =
         spin_on::spin_on(i_slint_compiler::compile_syntax_node(syntax_node, diag, compiler_config));
 
     if diag.has_error() {
         let vec = diag.to_string_vec();
-        diag.print();
+        print_errors(diag);
         return Err(CompileError::CompileError(vec));
     }
 
@@ -247,7 +366,21 @@ pub fn compile_with_config(
 
     let file = std::fs::File::create(&output_file_path).map_err(CompileError::SaveError)?;
     let mut code_formatter = CodeFormatter { indentation: 0, in_string: false, sink: file };
-    let generated = i_slint_compiler::generator::rust::generate(&doc);
+    let generated = match &live_reload {
+        Some((include_paths, style)) => {
+            let watched_files = std::iter::once(path.clone())
+                .chain(diag.all_loaded_files.iter().filter(|x| x.is_absolute()).cloned())
+                .collect::<Vec<_>>();
+            i_slint_compiler::generator::rust::generate_live_reload(
+                &doc,
+                &path,
+                include_paths,
+                style.as_deref(),
+                &watched_files,
+            )
+        }
+        None => i_slint_compiler::generator::rust::generate(&doc),
+    };
 
     for x in &diag.all_loaded_files {
         if x.is_absolute() {
@@ -256,11 +389,15 @@ pub fn compile_with_config(
     }
 
     // print warnings
-    diag.diagnostics_as_string().lines().for_each(|w| {
-        if !w.is_empty() {
-            println!("cargo:warning={}", w.strip_prefix("warning: ").unwrap_or(w))
-        }
-    });
+    if json_diagnostics() {
+        diag.iter().for_each(|d| eprintln!("{}", d.to_json()));
+    } else {
+        diag.diagnostics_as_string().lines().for_each(|w| {
+            if !w.is_empty() {
+                println!("cargo:warning={}", w.strip_prefix("warning: ").unwrap_or(w))
+            }
+        });
+    }
 
     write!(code_formatter, "{}", generated).map_err(CompileError::SaveError)?;
     println!("{}\ncargo:rerun-if-changed={}", rerun_if_changed, path.display());
@@ -274,12 +411,28 @@ pub fn compile_with_config(
     println!("cargo:rerun-if-env-changed=SIXTYFPS_STYLE");
     println!("cargo:rerun-if-env-changed=SLINT_FONT_SIZES");
     println!("cargo:rerun-if-env-changed=SLINT_SCALE_FACTOR");
+    println!("cargo:rerun-if-env-changed=SLINT_IMAGE_FORMATS");
+    println!("cargo:rerun-if-env-changed=SLINT_TEXT_SHAPING");
 
     println!("cargo:rustc-env=SLINT_INCLUDE_GENERATED={}", output_file_path.display());
 
     Ok(())
 }
 
+/// Returns true if the diagnostics should be printed as JSON, as requested with the
+/// `SLINT_DIAGNOSTICS_FORMAT` environment variable
+fn json_diagnostics() -> bool {
+    env::var("SLINT_DIAGNOSTICS_FORMAT").map_or(false, |format| format == "json")
+}
+
+fn print_errors(diag: BuildDiagnostics) {
+    if json_diagnostics() {
+        diag.iter().for_each(|d| eprintln!("{}", d.to_json()));
+    } else {
+        diag.print();
+    }
+}
+
 /// This function is for use the application's build script, in order to print any device specific
 /// build flags reported by the backend
 pub fn print_rustc_flags() -> std::io::Result<()> {
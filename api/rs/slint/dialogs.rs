@@ -0,0 +1,115 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Standard message boxes.
+
+[`message_box()`] shows a message with one or more standard buttons, and returns a future that resolves to the
+button that the user clicked. The message box uses the native dialogs of the platform when the backend has some,
+as with the Qt backend. Otherwise it is shown in its own window with the `MessageBox` widget of the current style.
+
+```rust,no_run
+use slint::dialogs::{message_box, MessageLevel, StandardButtonKind};
+
+async fn confirm_quit() -> bool {
+    let button = message_box(
+        MessageLevel::question,
+        "Quit",
+        "Do you really want to quit?",
+        &[StandardButtonKind::yes, StandardButtonKind::no],
+    )
+    .await;
+    button == StandardButtonKind::yes
+}
+```
+*/
+
+use crate::{ComponentHandle, ModelRc, Timer, VecModel};
+use alloc::rc::Rc;
+use core::cell::RefCell;
+
+pub use i_slint_core::dialogs::{MessageBoxFuture, MessageLevel};
+pub use i_slint_core::items::StandardButtonKind;
+
+mod message_box_window {
+    #![allow(missing_docs)]
+    crate::slint! {
+        import { MessageBox } from "std-widgets.slint";
+        MessageBoxWindow := Window {
+            title <=> message-box.title;
+            property <MessageLevel> level <=> message-box.level;
+            property <string> text <=> message-box.text;
+            property <[StandardButtonKind]> buttons <=> message-box.buttons;
+            callback clicked <=> message-box.clicked;
+
+            VerticalLayout {
+                message-box := MessageBox {
+                    background: transparent;
+                }
+            }
+        }
+    }
+}
+
+/// Shows a message box with the given `title` and `text`, and an icon for the `level`.
+///
+/// The `buttons` are shown in the order of the platform, according to their role: for example
+/// [`StandardButtonKind::ok`] is on the right on macOS and GNOME, and on the left on Windows.
+/// The returned future resolves to the button that the user clicked. If the message box is
+/// closed without clicking a button, it resolves to the first button that rejects the message
+/// box, such as `cancel` or `no`, or to the last button.
+///
+/// This function doesn't block: the message box is shown while the event loop runs.
+pub fn message_box(
+    level: MessageLevel,
+    title: &str,
+    text: &str,
+    buttons: &[StandardButtonKind],
+) -> MessageBoxFuture {
+    let buttons = i_slint_core::dialogs::sort_dialog_buttons(buttons);
+    let (future, on_close) = MessageBoxFuture::new();
+    if let Err(on_close) =
+        i_slint_backend_selector::backend().show_message_box(level, title, text, &buttons, on_close)
+    {
+        show_message_box_window(level, title, text, buttons, on_close);
+    }
+    future
+}
+
+/// Shows the message box with the `MessageBox` widget in a new window
+fn show_message_box_window(
+    level: MessageLevel,
+    title: &str,
+    text: &str,
+    buttons: alloc::vec::Vec<StandardButtonKind>,
+    on_close: i_slint_core::dialogs::MessageBoxCallback,
+) {
+    let escape_button = i_slint_core::dialogs::escape_button(&buttons);
+    let window = message_box_window::MessageBoxWindow::new();
+    window.set_title(title.into());
+    window.set_level(level);
+    window.set_text(text.into());
+    window.set_buttons(ModelRc::new(VecModel::from(buttons)));
+
+    // The window is kept alive until one of its buttons is clicked, or until it is closed
+    let handle = Rc::new(RefCell::new(Some(window.clone_strong())));
+    let on_close = RefCell::new(Some(on_close));
+    window.on_clicked(move |button| {
+        if let Some(on_close) = on_close.borrow_mut().take() {
+            on_close(button);
+        }
+        if let Some(window) = handle.borrow_mut().take() {
+            window.hide();
+            // Don't drop the component while its callback runs
+            Timer::single_shot(Default::default(), move || drop(window));
+        }
+    });
+    let weak = window.as_weak();
+    window.window().on_close_requested(move || {
+        if let Some(window) = weak.upgrade() {
+            window.invoke_clicked(escape_button);
+        }
+        crate::CloseRequestResponse::HideWindow
+    });
+    window.show();
+}
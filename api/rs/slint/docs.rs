@@ -80,6 +80,19 @@ pub fn new() -> Self {
             unimplemented!()
         }
 
+        /// Creates a new instance like [`Self::new()`], and calls `init_globals` before the
+        /// bindings of the component are initialized. Use it to set the properties and callback
+        /// handlers of the exported global singletons, so that the component never sees their
+        /// default values:
+        /// ```ignore
+        ///     let sample = SampleComponent::new_with_globals(|sample| {
+        ///         sample.global::<Settings>().set_user_name("Jane".into());
+        ///     });
+        /// ```
+        pub fn new_with_globals(init_globals: impl FnOnce(&Self)) -> Self {
+            unimplemented!()
+        }
+
         /// A getter is generated for each property declared at the root of the component.
         /// In this case, this is the getter that returns the value of the `counter`
         /// property declared in the `.slint` design markup.
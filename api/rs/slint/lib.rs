@@ -231,6 +231,9 @@ struct MyStruct {
 #![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
+// So that the code generated by the slint! macro for the message boxes can refer to this crate
+#[cfg(feature = "std")]
+extern crate self as slint;
 
 #[cfg(not(feature = "compat-0-2-0"))]
 compile_error!(
@@ -241,16 +244,152 @@ struct MyStruct {
 pub use slint_macros::slint;
 
 pub use i_slint_core::api::*;
+pub use i_slint_core::color_scheme::set_color_scheme;
 pub use i_slint_core::graphics::{
-    Brush, Color, Image, LoadImageError, Rgb8Pixel, Rgba8Pixel, RgbaColor, SharedPixelBuffer,
+    Brush, Color, Image, LoadImageError, RenderingRotation, Rgb8Pixel, Rgba8Pixel, RgbaColor,
+    SharedPixelBuffer,
 };
+#[cfg(feature = "std")]
+pub use i_slint_core::graphics::{InvalidVideoFrame, VideoFrame, VideoSink};
+pub use i_slint_core::items::TextSpan;
+pub use i_slint_core::locale::set_locale;
+#[cfg(feature = "std")]
+pub use i_slint_core::model::{ChannelModel, StreamModel, StreamModelOptions};
 pub use i_slint_core::model::{
     FilterModel, MapModel, Model, ModelExt, ModelNotify, ModelPeer, ModelRc, ModelTracker,
-    StandardListViewItem, VecModel,
+    SelectionMode, SelectionModel, SortModel, StandardListViewItem, TableColumn, VecModel,
+};
+#[cfg(feature = "std")]
+pub use i_slint_core::parallel_binding::{
+    set_parallel_evaluation_threads, wait_for_parallel_bindings, ParallelBinding,
 };
 pub use i_slint_core::sharedvector::SharedVector;
 pub use i_slint_core::string::SharedString;
 pub use i_slint_core::timers::{Timer, TimerMode};
+pub use i_slint_core::two_way_binding::{SharedValue, TwoWayBinding};
+
+/// The types used to implement the content of the `CustomItem` element in Rust.
+///
+/// Register a [`CustomItemHandler`](custom_item::CustomItemHandler) for a type name with
+/// [`register_custom_item()`](custom_item::register_custom_item), and use that type name in
+/// the `type-name` property of `CustomItem` elements.
+pub mod custom_item {
+    pub use i_slint_core::graphics::Point;
+    pub use i_slint_core::input::{
+        InputEventResult, KeyEvent, KeyEventResult, KeyEventType, KeyboardModifiers, MouseEvent,
+    };
+    pub use i_slint_core::items::{
+        register_custom_item, CustomItemHandle, CustomItemHandler, PointerEventButton,
+    };
+    pub use i_slint_core::layout::{LayoutInfo, Orientation};
+}
+
+#[cfg(feature = "std")]
+pub mod dialogs;
+
+/// The API to integrate Slint with a custom platform, for example to run it inside the
+/// event loop of a game engine, or of another toolkit.
+///
+/// The application implements [`Platform`](platform::Platform) and installs it with
+/// [`set_platform()`](platform::set_platform) before creating a component. Each window is
+/// backed by a [`WindowAdapter`](platform::WindowAdapter), which selects the
+/// [`Renderer`](platform::Renderer) measuring the text. The application then forwards the input
+/// with [`Window::dispatch_event()`], calls
+/// [`update_timers_and_animations()`](platform::update_timers_and_animations) on each iteration of
+/// its event loop, which also calls the events posted from other threads, and draws the windows
+/// that requested it with [`render()`](platform::render). A platform that owns the event loop
+/// implements [`Platform::run_event_loop()`](platform::Platform::run_event_loop) and the functions
+/// next to it instead.
+///
+/// With the `software-renderer` feature, the renderer of the windows can be a
+/// [`SoftwareRenderer`](platform::swrenderer::SoftwareRenderer), which draws a window in a frame
+/// buffer with the CPU, independently of any windowing system. This is how a game engine shows a
+/// Slint menu or HUD: it uploads the pixels of the region returned by `SoftwareRenderer::render()`
+/// to a texture, composites that texture in its scene, and forwards the mouse and keyboard events
+/// that hit it with [`Window::dispatch_event()`]. With [`Rgba8Pixel`] and a transparent
+/// background, the pixels have a premultiplied alpha.
+///
+/// ```no_run
+/// use slint::platform::{Platform, Renderer, WindowAdapter, WindowWeak};
+/// # use slint::platform::{Coord, FontRequest, Point, Rect, Size, TextInput};
+/// # use core::pin::Pin;
+/// # struct MyRenderer;
+/// # impl Renderer for MyRenderer {
+/// #     fn text_size(&self, _: FontRequest, _: &str, _: Option<Coord>, _: f32) -> Size { Size::default() }
+/// #     fn text_input_byte_offset_for_position(&self, _: Pin<&TextInput>, _: Point, _: f32) -> usize { 0 }
+/// #     fn text_input_cursor_rect_for_byte_offset(&self, _: Pin<&TextInput>, _: usize, _: f32) -> Rect { Rect::default() }
+/// # }
+///
+/// struct MyWindowAdapter {
+///     renderer: MyRenderer,
+///     window: WindowWeak,
+/// }
+///
+/// impl WindowAdapter for MyWindowAdapter {
+///     fn renderer(&self) -> &dyn Renderer {
+///         &self.renderer
+///     }
+/// }
+///
+/// struct MyPlatform;
+///
+/// impl Platform for MyPlatform {
+///     fn create_window_adapter(&self, window: WindowWeak) -> std::rc::Rc<dyn WindowAdapter> {
+///         std::rc::Rc::new(MyWindowAdapter { renderer: MyRenderer, window })
+///     }
+/// }
+///
+/// slint::platform::set_platform(Box::new(MyPlatform)).unwrap();
+///
+/// loop {
+///     // ... wait for the events of the application, and forward them with dispatch_event()
+///     slint::platform::update_timers_and_animations();
+///     // ... call slint::platform::render() for the windows that requested a redraw
+///     slint::platform::invoke_idle_callbacks();
+/// #   break;
+/// }
+/// ```
+pub mod platform {
+    pub use i_slint_core::event_loop::invoke_idle_callbacks;
+    pub use i_slint_core::graphics::{FontRequest, Point, Rect, Size};
+    pub use i_slint_core::input::{key_codes, KeyboardModifiers};
+    pub use i_slint_core::item_rendering::ItemRenderer;
+    pub use i_slint_core::items::{ItemRef, PointerEventButton, TextInput};
+    pub use i_slint_core::platform::*;
+    #[cfg(feature = "software-renderer")]
+    pub use i_slint_core::swrenderer;
+    pub use i_slint_core::Coord;
+}
+
+/// Functions specific to the GL backend, which uses the `winit` crate for the windowing system
+/// integration. They have no effect when another backend, like Qt, is selected at run-time.
+///
+/// The [`GLRenderer`](backend_gl::GLRenderer) draws a window of a [custom platform](platform) with
+/// OpenGL, on a surface owned by the application. This is used to embed Slint in an existing
+/// native application or in a plugin window: the renderer either uses the OpenGL context of the
+/// application through the [`OpenGLInterface`](backend_gl::OpenGLInterface) trait, or a
+/// [`NativeOpenGLContext`](backend_gl::NativeOpenGLContext) created for the raw window handle of
+/// the native window. A game engine draws a Slint menu or HUD into one of its textures or frame
+/// buffers with [`GLRenderer::render_to()`](backend_gl::GLRenderer::render_to) instead, composites
+/// it in its scene, and forwards the input that hits it with [`Window::dispatch_event()`].
+///
+/// ```no_run
+/// use slint::backend_gl::{set_event_filter, winit, EventFilterResult};
+///
+/// set_event_filter(|event| match event {
+///     winit::event::Event::DeviceEvent {
+///         event: winit::event::DeviceEvent::Key(input), ..
+///     } => {
+///         println!("scan code {} pressed or released", input.scancode);
+///         EventFilterResult::Propagate
+///     }
+///     _ => EventFilterResult::Propagate,
+/// });
+/// ```
+#[cfg(any(feature = "backend-gl-all", feature = "backend-gl-x11", feature = "backend-gl-wayland"))]
+pub mod backend_gl {
+    pub use i_slint_backend_selector::backend_gl::*;
+}
 
 /// This function can be used to register a custom TrueType font with Slint,
 /// for use with the `font-family` property. The provided slice must be a valid TrueType
@@ -272,6 +411,24 @@ pub fn register_font_from_path<P: AsRef<std::path::Path>>(
     i_slint_backend_selector::backend().register_font_from_path(path.as_ref())
 }
 
+/// Rasterizes the glyphs of the characters in `charset`, with the font described by `font_request`,
+/// into the glyph atlas of the GL backend before they are displayed. The fields of the request that
+/// are not set are taken from the default font of each window.
+///
+/// Rasterizing the glyphs of a large font size or of a new script can take a while on embedded GPUs,
+/// so call this at start-up to avoid a hitch in the first frame that shows them. The glyphs are
+/// rasterized when the windows are drawn next. Other backends ignore this.
+///
+/// ```no_run
+/// slint::preload_glyphs(
+///     slint::platform::FontRequest { pixel_size: Some(64.), ..Default::default() },
+///     "0123456789:",
+/// );
+/// ```
+pub fn preload_glyphs(font_request: platform::FontRequest, charset: &str) {
+    i_slint_backend_selector::backend().preload_glyphs(font_request, charset)
+}
+
 /// internal re_exports used by the macro generated
 #[doc(hidden)]
 pub mod re_exports {
@@ -283,25 +440,31 @@ pub mod re_exports {
     pub use const_field_offset::{self, FieldOffsets, PinnedDrop};
     pub use core::iter::FromIterator;
     pub use i_slint_backend_selector::native_widgets::*;
-    pub use i_slint_core::animations::EasingCurve;
+    pub use i_slint_core::accessibility::{AccessibilityAction, AccessibleStringProperty};
+    pub use i_slint_core::animations::{animation_tick, EasingCurve};
     pub use i_slint_core::callbacks::Callback;
+    pub use i_slint_core::color_scheme;
     pub use i_slint_core::component::{
-        free_component_item_graphics_resources, init_component_items, Component, ComponentRefPin,
-        ComponentVTable, ComponentWeak, IndexRange,
+        free_component_item_graphics_resources, init_component_items, will_destroy_component,
+        Component, ComponentRefPin, ComponentVTable, ComponentWeak, IndexRange,
     };
     pub use i_slint_core::graphics::*;
     pub use i_slint_core::input::{
         FocusEvent, InputEventResult, KeyEvent, KeyEventResult, KeyboardModifiers, MouseEvent,
     };
     pub use i_slint_core::item_tree::{
-        visit_item_tree, ItemTreeNode, ItemVisitorRefMut, ItemVisitorVTable, ItemWeak,
+        visit_item_tree, ItemRc, ItemTreeNode, ItemVisitorRefMut, ItemVisitorVTable, ItemWeak,
         TraversalOrder, VisitChildrenResult,
     };
     pub use i_slint_core::items::*;
     pub use i_slint_core::layout::*;
+    pub use i_slint_core::locale;
     pub use i_slint_core::model::*;
-    pub use i_slint_core::properties::{set_state_binding, Property, PropertyTracker, StateInfo};
+    pub use i_slint_core::properties::{
+        set_state_binding, ChangeTracker, Property, PropertyTracker, StateInfo,
+    };
     pub use i_slint_core::slice::Slice;
+    pub use i_slint_core::string;
     pub use i_slint_core::window::{Window, WindowHandleAccess, WindowRc};
     pub use i_slint_core::Color;
     pub use i_slint_core::ComponentVTable_static;
@@ -312,6 +475,8 @@ pub mod re_exports {
     pub use once_cell::race::OnceBox;
     pub use once_cell::unsync::OnceCell;
     pub use pin_weak::rc::PinWeak;
+    #[cfg(feature = "live-reload")]
+    pub use slint_interpreter::live_reload;
     pub use vtable::{self, *};
 }
 
@@ -438,6 +603,23 @@ pub fn set_callback_handler<
         })
     }
 
+    pub fn init_change_tracker<T: PartialEq + 'static, StrongRef: StrongComponentRef + 'static>(
+        tracker: &ChangeTracker,
+        component_strong: &StrongRef,
+        eval: fn(StrongRef) -> T,
+        notify: fn(StrongRef),
+    ) {
+        tracker.init(
+            component_strong.to_weak(),
+            move |weak| <StrongRef as StrongComponentRef>::from_weak(weak).map(eval),
+            move |weak| {
+                if let Some(component) = <StrongRef as StrongComponentRef>::from_weak(weak) {
+                    notify(component)
+                }
+            },
+        )
+    }
+
     /// This function can be used to register a pre-rendered, embedded bitmap font with Slint,
     /// for use with the `font-family` property.
     #[doc(hidden)]
@@ -535,6 +717,28 @@ pub fn set_window_scale_factor<
         let component = component.clone_strong().into();
         component.window_handle().set_scale_factor(factor)
     }
+
+    pub use i_slint_core::recording::{
+        start_recording as start_event_recording, stop_recording as stop_event_recording,
+        EventRecording, RecordedEntry, RecordedEvent, RecordingError,
+    };
+
+    /// Feeds the events of a recording, written by [`start_event_recording()`] or with the
+    /// `SLINT_RECORD_EVENTS` environment variable, to the window of the component.
+    ///
+    /// The events are processed as fast as possible, but the timers and the animations see the
+    /// time of the recording, so that the replay gives the same result every time.
+    ///
+    /// ```no_run
+    /// # slint::slint!{ App := Window {} }
+    /// let app = App::new();
+    /// let recording = slint::testing::EventRecording::load("session.txt").unwrap();
+    /// slint::testing::replay_events(&app, &recording);
+    /// ```
+    pub fn replay_events(component: &impl ComponentHandle, recording: &EventRecording) {
+        use crate::re_exports::WindowHandleAccess;
+        i_slint_core::recording::replay(component.window().window_handle(), recording)
+    }
 }
 
 /// Include the code generated with the slint-build crate from the build script. After calling `slint_build::compile`
@@ -0,0 +1,379 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Exposes the accessible items of a window to the platform accessibility APIs
+(UI Automation on Windows, NSAccessibility on macOS and AT-SPI on Linux) with AccessKit.
+
+Every item with an `accessible-role` other than `none` is a node of the AccessKit tree, as a child
+of the node of the window. The items within an accessible item are not exposed: they are part of the
+implementation of a widget. The tree is rebuilt when any of the properties that were read while building
+it changes, and the action requests from the assistive technologies are dispatched through the event loop
+to the `accessible-action-*` callbacks of the items.
+*/
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::num::NonZeroU128;
+use std::pin::Pin;
+use std::rc::Weak;
+use std::sync::Arc;
+
+use accesskit::{
+    Action, ActionData, ActionHandler, ActionRequest, CheckedState, DefaultActionVerb, Node,
+    NodeId, Rect, Role, Tree, TreeUpdate,
+};
+use corelib::accessibility::{AccessibilityAction, AccessibleRole, AccessibleStringProperty};
+use corelib::graphics::Point;
+use corelib::item_tree::{ItemRc, ItemWeak};
+use corelib::items::{ItemRef, WindowItem};
+use corelib::properties::PropertyTracker;
+use i_slint_core as corelib;
+
+fn root_node_id() -> NodeId {
+    NodeId(NonZeroU128::new(1).unwrap())
+}
+
+/// The node id of an accessible item, from the address of its component and its index in the
+/// component, so that it stays the same when the tree is rebuilt
+fn item_node_id(item: &ItemRc) -> NodeId {
+    let component_address = vtable::VRc::borrow(&item.component()).as_ptr() as usize as u128;
+    NodeId(NonZeroU128::new(component_address << 64 | item.index() as u128).unwrap())
+}
+
+/// The accessibility adapter of a mapped GLWindow
+pub struct AccessKitAdapter {
+    inner: Option<platform::Adapter>,
+    window_weak: Weak<corelib::window::Window>,
+    /// The accessible items of the last tree sent to the platform, by node id
+    items: RefCell<HashMap<NodeId, ItemWeak>>,
+    focus_item: RefCell<ItemWeak>,
+    tracker: Pin<Box<PropertyTracker<Box<dyn Fn()>>>>,
+}
+
+impl AccessKitAdapter {
+    /// Creates the adapter for the given winit window, which should not be visible yet.
+    /// `request_redraw` is called when the accessible properties change: the tree is then
+    /// updated by the next call to `update_if_dirty`.
+    pub fn new(
+        winit_window: &winit::window::Window,
+        window_weak: Weak<corelib::window::Window>,
+        request_redraw: Box<dyn Fn()>,
+    ) -> Self {
+        let mut adapter = Self {
+            inner: None,
+            window_weak,
+            items: Default::default(),
+            focus_item: Default::default(),
+            tracker: Box::pin(PropertyTracker::new_with_change_handler(request_redraw)),
+        };
+        let initial_tree = adapter.build_tree();
+        adapter.inner = platform::Adapter::new(
+            winit_window,
+            move || initial_tree,
+            Box::new(ActionForwarder { window_id: winit_window.id() }),
+        );
+        adapter
+    }
+
+    /// Sends a new tree to the platform if any of the accessible properties changed.
+    pub fn update_if_dirty(&self) {
+        if !self.tracker.is_dirty() {
+            return;
+        }
+        let update = self.build_tree();
+        if let Some(inner) = &self.inner {
+            inner.update(update);
+        }
+    }
+
+    pub fn handle_focus_change(&self, new: Option<ItemRc>) {
+        *self.focus_item.borrow_mut() = new.map(|item| item.downgrade()).unwrap_or_default();
+        if let Some(inner) = &self.inner {
+            inner.update(TreeUpdate {
+                nodes: vec![],
+                tree: None,
+                focus: Some(self.focus_node_id()),
+            });
+        }
+    }
+
+    /// Returns the item targeted by an action request of an assistive technology, and the action
+    /// to invoke on it with `ItemRc::accessibility_action`.
+    pub fn resolve_action_request(
+        &self,
+        request: ActionRequest,
+    ) -> Option<(ItemRc, AccessibilityAction)> {
+        let item = self.items.borrow().get(&request.target).and_then(|item| item.upgrade())?;
+        let action = match (request.action, request.data) {
+            (Action::Default, _) => AccessibilityAction::Default,
+            (Action::Increment, _) => AccessibilityAction::Increment,
+            (Action::Decrement, _) => AccessibilityAction::Decrement,
+            (Action::SetValue, Some(ActionData::Value(value))) => {
+                AccessibilityAction::SetValue(value.as_ref().into())
+            }
+            (Action::SetValue, Some(ActionData::NumericValue(value))) => {
+                AccessibilityAction::SetValue(value.to_string().into())
+            }
+            _ => return None,
+        };
+        Some((item, action))
+    }
+
+    /// The node of the accessible item that contains the focus item, or the window.
+    fn focus_node_id(&self) -> NodeId {
+        let items = self.items.borrow();
+        let mut item = self.focus_item.borrow().upgrade();
+        while let Some(current) = item {
+            let id = item_node_id(&current);
+            if items.contains_key(&id) {
+                return id;
+            }
+            item = current.parent_item();
+        }
+        root_node_id()
+    }
+
+    fn build_tree(&self) -> TreeUpdate {
+        let window = match self.window_weak.upgrade() {
+            Some(window) => window,
+            None => return TreeUpdate { nodes: vec![], tree: None, focus: None },
+        };
+        let scale_factor = window.scale_factor() as f64;
+        let mut items = HashMap::new();
+        let mut nodes = Vec::new();
+
+        let root_node = self.tracker.as_ref().evaluate_as_dependency_root(|| {
+            let root_item = ItemRc::new(window.component(), 0);
+            let title = ItemRef::downcast_pin::<WindowItem>(root_item.borrow())
+                .map(|window_item| window_item.title())
+                .unwrap_or_default();
+            let size = window.window_item_size();
+            let mut children = Vec::new();
+            collect_accessible_children(
+                &root_item,
+                Point::default(),
+                scale_factor,
+                &mut items,
+                &mut nodes,
+                &mut children,
+            );
+            Node {
+                role: Role::Window,
+                name: (!title.is_empty()).then(|| title.as_str().into()),
+                bounds: Some(Rect::new(
+                    0.,
+                    0.,
+                    size.width as f64 * scale_factor,
+                    size.height as f64 * scale_factor,
+                )),
+                children,
+                ..Default::default()
+            }
+        });
+        nodes.push((root_node_id(), Arc::new(root_node)));
+
+        *self.items.borrow_mut() = items;
+        TreeUpdate {
+            nodes,
+            tree: Some(Tree::new(root_node_id())),
+            focus: Some(self.focus_node_id()),
+        }
+    }
+}
+
+/// Adds the nodes of the accessible items among the descendants of `parent` to `nodes`, and their ids
+/// to `children`. `parent_origin` is the position of the parent item in the window, in logical pixels.
+fn collect_accessible_children(
+    parent: &ItemRc,
+    parent_origin: Point,
+    scale_factor: f64,
+    items: &mut HashMap<NodeId, ItemWeak>,
+    nodes: &mut Vec<(NodeId, Arc<Node>)>,
+    children: &mut Vec<NodeId>,
+) {
+    let mut child = parent.first_child();
+    while let Some(item) = child {
+        child = item.next_sibling();
+        if !item.is_visible() {
+            continue;
+        }
+        let geometry = item.borrow().as_ref().geometry();
+        let origin = parent_origin + geometry.origin.to_vector();
+        match item.accessible_role() {
+            AccessibleRole::none => {
+                collect_accessible_children(&item, origin, scale_factor, items, nodes, children)
+            }
+            role => {
+                let bounds = Rect::new(
+                    origin.x as f64 * scale_factor,
+                    origin.y as f64 * scale_factor,
+                    (origin.x + geometry.width()) as f64 * scale_factor,
+                    (origin.y + geometry.height()) as f64 * scale_factor,
+                );
+                let id = item_node_id(&item);
+                nodes.push((id, Arc::new(accessible_node(&item, role, bounds))));
+                items.insert(id, item.downgrade());
+                children.push(id);
+            }
+        }
+    }
+}
+
+fn accessible_node(item: &ItemRc, role: AccessibleRole, bounds: Rect) -> Node {
+    let string_property = |what| item.accessible_string_property(what);
+    let optional_string = |what| {
+        let value = string_property(what);
+        (!value.is_empty()).then(|| value.as_str().into())
+    };
+    let number = |what| string_property(what).parse::<f64>().ok();
+
+    let checked_state =
+        (string_property(AccessibleStringProperty::Checkable) == "true").then(|| {
+            if string_property(AccessibleStringProperty::Checked) == "true" {
+                CheckedState::True
+            } else {
+                CheckedState::False
+            }
+        });
+
+    let mut node = Node {
+        role: match role {
+            AccessibleRole::none => Role::Unknown,
+            AccessibleRole::button => Role::Button,
+            AccessibleRole::checkbox => Role::CheckBox,
+            AccessibleRole::combobox => Role::PopupButton,
+            AccessibleRole::slider => Role::Slider,
+            AccessibleRole::spinbox => Role::SpinButton,
+            AccessibleRole::tab => Role::Tab,
+            AccessibleRole::text => Role::StaticText,
+            AccessibleRole::text_input => Role::TextField,
+        },
+        bounds: Some(bounds),
+        name: optional_string(AccessibleStringProperty::Label),
+        description: optional_string(AccessibleStringProperty::Description),
+        checked_state,
+        focusable: role != AccessibleRole::text,
+        default_action_verb: matches!(
+            role,
+            AccessibleRole::button
+                | AccessibleRole::checkbox
+                | AccessibleRole::combobox
+                | AccessibleRole::tab
+        )
+        .then(|| DefaultActionVerb::Click),
+        ..Default::default()
+    };
+    if matches!(role, AccessibleRole::slider | AccessibleRole::spinbox) {
+        node.numeric_value = number(AccessibleStringProperty::Value);
+        node.min_numeric_value = number(AccessibleStringProperty::ValueMinimum);
+        node.max_numeric_value = number(AccessibleStringProperty::ValueMaximum);
+        node.numeric_value_step = number(AccessibleStringProperty::ValueStep);
+    } else {
+        node.value = optional_string(AccessibleStringProperty::Value);
+    }
+    node
+}
+
+/// The platform adapters call the action handler from their own thread: forward the requests
+/// to the event loop, which invokes it with `WinitWindow::handle_accessibility_action`.
+struct ActionForwarder {
+    window_id: winit::window::WindowId,
+}
+
+impl ActionHandler for ActionForwarder {
+    fn do_action(&self, request: ActionRequest) {
+        crate::event_loop::GLOBAL_PROXY.get_or_init(Default::default).lock().unwrap().send_event(
+            crate::event_loop::CustomEvent::AccessibilityAction(self.window_id, request),
+        );
+    }
+}
+
+mod platform {
+    use accesskit::{ActionHandler, TreeUpdate};
+
+    cfg_if::cfg_if! {
+        if #[cfg(target_os = "windows")] {
+            pub struct Adapter(accesskit_windows::SubclassingAdapter);
+
+            impl Adapter {
+                pub fn new(
+                    window: &winit::window::Window,
+                    source: impl 'static + FnOnce() -> TreeUpdate + Send,
+                    action_handler: Box<dyn ActionHandler>,
+                ) -> Option<Self> {
+                    use winit::platform::windows::WindowExtWindows;
+                    let hwnd = accesskit_windows::HWND(window.hwnd() as _);
+                    Some(Self(accesskit_windows::SubclassingAdapter::new(hwnd, source, action_handler)))
+                }
+
+                pub fn update(&self, update: TreeUpdate) {
+                    self.0.update(update).raise();
+                }
+            }
+        } else if #[cfg(target_os = "macos")] {
+            pub struct Adapter(accesskit_macos::SubclassingAdapter);
+
+            impl Adapter {
+                pub fn new(
+                    window: &winit::window::Window,
+                    source: impl 'static + FnOnce() -> TreeUpdate + Send,
+                    action_handler: Box<dyn ActionHandler>,
+                ) -> Option<Self> {
+                    use winit::platform::macos::WindowExtMacOS;
+                    // Safety: the view is valid as long as the window, which outlives the adapter
+                    Some(Self(unsafe {
+                        accesskit_macos::SubclassingAdapter::new(window.ns_view(), source, action_handler)
+                    }))
+                }
+
+                pub fn update(&self, update: TreeUpdate) {
+                    self.0.update(update).raise();
+                }
+            }
+        } else if #[cfg(not(any(target_os = "ios", target_os = "android")))] {
+            pub struct Adapter(accesskit_unix::Adapter);
+
+            impl Adapter {
+                pub fn new(
+                    _window: &winit::window::Window,
+                    source: impl 'static + FnOnce() -> TreeUpdate + Send,
+                    action_handler: Box<dyn ActionHandler>,
+                ) -> Option<Self> {
+                    // Returns None when there is no accessibility bus, for example when no
+                    // assistive technology is running.
+                    let app_name = std::env::current_exe()
+                        .ok()
+                        .and_then(|path| Some(path.file_stem()?.to_string_lossy().into_owned()))
+                        .unwrap_or_default();
+                    accesskit_unix::Adapter::new(
+                        app_name,
+                        "Slint".into(),
+                        env!("CARGO_PKG_VERSION").into(),
+                        source,
+                        action_handler,
+                    )
+                    .map(Self)
+                }
+
+                pub fn update(&self, update: TreeUpdate) {
+                    self.0.update(update);
+                }
+            }
+        } else {
+            pub struct Adapter;
+
+            impl Adapter {
+                pub fn new(
+                    _window: &winit::window::Window,
+                    _source: impl 'static + FnOnce() -> TreeUpdate + Send,
+                    _action_handler: Box<dyn ActionHandler>,
+                ) -> Option<Self> {
+                    None
+                }
+
+                pub fn update(&self, _update: TreeUpdate) {}
+            }
+        }
+    }
+}
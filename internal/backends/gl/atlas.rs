@@ -0,0 +1,244 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+A texture atlas for the small images, like icons: they are packed into a few large textures instead
+of one texture each, so that drawing many of them doesn't require binding as many textures, and the
+GPU memory isn't fragmented by many small allocations.
+
+The images are packed into shelves, which are rows as high as their tallest image. The region of an
+image is freed when its [`AtlasRegion`] is dropped, and can then be reused by another image that fits
+in it. The pages without any image are deleted by [`ImageAtlas::drain()`].
+*/
+
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::Rc;
+
+use i_slint_core::items::ImageRendering;
+
+use crate::glrenderer::CanvasRc;
+
+/// The width and the height of the textures of the atlas
+const PAGE_SIZE: u32 = 1024;
+/// The images up to this width and height are packed into the atlas
+const MAX_IMAGE_SIZE: u32 = 128;
+/// The pixels around each image, which repeat its edges, so that the neighbor images don't bleed into
+/// it when it's scaled smoothly
+const PADDING: u32 = 1;
+
+type AtlasRect = euclid::default::Rect<u32>;
+
+/// A row of a page, which holds images up to its height
+struct Shelf {
+    y: u32,
+    height: u32,
+    /// The free horizontal ranges of the shelf, sorted
+    free: Vec<Range<u32>>,
+}
+
+impl Shelf {
+    fn allocate(&mut self, width: u32) -> Option<u32> {
+        let index = self.free.iter().position(|range| range.end - range.start >= width)?;
+        let range = &mut self.free[index];
+        let x = range.start;
+        range.start += width;
+        if range.is_empty() {
+            self.free.remove(index);
+        }
+        Some(x)
+    }
+
+    fn free(&mut self, range: Range<u32>) {
+        let index = self.free.partition_point(|free| free.start < range.start);
+        self.free.insert(index, range);
+        if index + 1 < self.free.len() && self.free[index].end == self.free[index + 1].start {
+            self.free[index].end = self.free.remove(index + 1).end;
+        }
+        if index > 0 && self.free[index - 1].end == self.free[index].start {
+            self.free[index - 1].end = self.free.remove(index).end;
+        }
+    }
+}
+
+/// A texture of the atlas
+struct AtlasPage {
+    id: femtovg::ImageId,
+    canvas: CanvasRc,
+    shelves: RefCell<Vec<Shelf>>,
+}
+
+impl AtlasPage {
+    fn new(canvas: &CanvasRc, flags: femtovg::ImageFlags) -> Option<Self> {
+        let id = canvas
+            .borrow_mut()
+            .create_image_empty(
+                PAGE_SIZE as usize,
+                PAGE_SIZE as usize,
+                femtovg::PixelFormat::Rgba8,
+                flags,
+            )
+            .ok()?;
+        Some(Self { id, canvas: canvas.clone(), shelves: Default::default() })
+    }
+
+    /// Returns a free rectangle of the given size, in the lowest shelf that can hold it, or in a new shelf
+    fn allocate(&self, width: u32, height: u32) -> Option<AtlasRect> {
+        let mut shelves = self.shelves.borrow_mut();
+        let mut candidates =
+            (0..shelves.len()).filter(|index| shelves[*index].height >= height).collect::<Vec<_>>();
+        candidates.sort_by_key(|index| shelves[*index].height);
+        for index in candidates {
+            let shelf = &mut shelves[index];
+            if let Some(x) = shelf.allocate(width) {
+                return Some(AtlasRect::new([x, shelf.y].into(), [width, height].into()));
+            }
+        }
+
+        let y = shelves.last().map_or(0, |shelf| shelf.y + shelf.height);
+        if y + height > PAGE_SIZE || width > PAGE_SIZE {
+            return None;
+        }
+        shelves.push(Shelf { y, height, free: vec![width..PAGE_SIZE] });
+        Some(AtlasRect::new([0, y].into(), [width, height].into()))
+    }
+
+    fn free(&self, rect: AtlasRect) {
+        if let Some(shelf) =
+            self.shelves.borrow_mut().iter_mut().find(|shelf| shelf.y == rect.min_y())
+        {
+            shelf.free(rect.min_x()..rect.max_x());
+        }
+    }
+}
+
+impl Drop for AtlasPage {
+    fn drop(&mut self) {
+        self.canvas.borrow_mut().delete_image(self.id);
+    }
+}
+
+/// The region of an image in a page of the atlas, which is freed when it's dropped
+pub struct AtlasRegion {
+    page: Rc<AtlasPage>,
+    /// The allocated rectangle, including the padding
+    allocation: AtlasRect,
+}
+
+impl AtlasRegion {
+    /// The texture of the page that holds the image
+    pub fn texture(&self) -> femtovg::ImageId {
+        self.page.id
+    }
+
+    /// The rectangle of the image in the texture
+    pub fn rect(&self) -> AtlasRect {
+        AtlasRect::new(
+            self.allocation.origin + euclid::vec2(PADDING, PADDING),
+            self.allocation.size - euclid::size2(2 * PADDING, 2 * PADDING),
+        )
+    }
+
+    /// The size of the texture of the page
+    pub fn texture_size(&self) -> euclid::default::Size2D<u32> {
+        [PAGE_SIZE, PAGE_SIZE].into()
+    }
+}
+
+impl Drop for AtlasRegion {
+    fn drop(&mut self) {
+        self.page.free(self.allocation);
+    }
+}
+
+impl std::fmt::Debug for AtlasRegion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "AtlasRegion({:?} in {:?})", self.rect(), self.page.id.0)
+    }
+}
+
+/// The pages of the atlas of a window, which are only valid with its GL context
+#[derive(Default)]
+pub struct ImageAtlas {
+    pages: Vec<(femtovg::ImageFlags, Rc<AtlasPage>)>,
+}
+
+impl ImageAtlas {
+    /// Uploads the image into a page of the atlas with the same flags, and returns its region, or
+    /// None if the image is too large to be packed.
+    pub fn insert(
+        &mut self,
+        canvas: &CanvasRc,
+        image: &image::DynamicImage,
+        premultiplied_alpha: bool,
+        scaling: ImageRendering,
+    ) -> Option<AtlasRegion> {
+        use image::GenericImageView;
+        let (width, height) = image.dimensions();
+        if width == 0 || height == 0 || width > MAX_IMAGE_SIZE || height > MAX_IMAGE_SIZE {
+            return None;
+        }
+
+        let mut flags = match scaling {
+            ImageRendering::smooth => femtovg::ImageFlags::empty(),
+            ImageRendering::pixelated => femtovg::ImageFlags::NEAREST,
+        };
+        if premultiplied_alpha {
+            flags |= femtovg::ImageFlags::PREMULTIPLIED;
+        }
+
+        let padded = pad_image(&image.to_rgba8());
+        let (padded_width, padded_height) = padded.dimensions();
+        let existing = self.pages.iter().filter(|(page_flags, _)| *page_flags == flags).find_map(
+            |(_, page)| page.allocate(padded_width, padded_height).map(|rect| (page.clone(), rect)),
+        );
+        let (page, allocation) = match existing {
+            Some(existing) => existing,
+            None => {
+                let page = Rc::new(AtlasPage::new(canvas, flags)?);
+                let allocation = page.allocate(padded_width, padded_height)?;
+                self.pages.push((flags, page.clone()));
+                (page, allocation)
+            }
+        };
+
+        // The region is freed if the upload fails
+        let region = AtlasRegion { page, allocation };
+        use rgb::FromSlice;
+        let source = imgref::Img::new(
+            padded.as_raw().as_rgba(),
+            padded_width as usize,
+            padded_height as usize,
+        );
+        canvas
+            .borrow_mut()
+            .update_image(
+                region.texture(),
+                source,
+                allocation.min_x() as usize,
+                allocation.min_y() as usize,
+            )
+            .ok()?;
+
+        Some(region)
+    }
+
+    /// Deletes the pages that don't hold any image anymore
+    pub fn drain(&mut self) {
+        self.pages.retain(|(_, page)| Rc::strong_count(page) > 1);
+    }
+
+    pub fn clear(&mut self) {
+        self.pages.clear();
+    }
+}
+
+/// Returns the image surrounded by `PADDING` pixels that repeat its edges
+fn pad_image(image: &image::RgbaImage) -> image::RgbaImage {
+    let (width, height) = image.dimensions();
+    image::RgbaImage::from_fn(width + 2 * PADDING, height + 2 * PADDING, |x, y| {
+        let x = x.saturating_sub(PADDING).min(width - 1);
+        let y = y.saturating_sub(PADDING).min(height - 1);
+        *image.get_pixel(x, y)
+    })
+}
@@ -0,0 +1,92 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Ordered dithering of the gradients, to avoid the visible banding of large and subtle gradients on
+the displays with 6 or 8 bits per channel. femtovg interpolates the gradients in its shaders without
+dithering, so a dithered gradient is rendered on the CPU into a texture instead, which is then used
+as an image pattern.
+
+The dithering is enabled with the `SLINT_DITHER_GRADIENTS` environment variable, whose value is
+the number of bits per channel of the display, between 1 and 8. Any other value means 8.
+With the `deterministic-rendering` feature, the gradients are always rendered on the CPU, dithered
+to 8 bits, so that they don't depend on the precision of the shaders of the GPU.
+*/
+
+use i_slint_core::graphics::{GradientStop, LinearGradientBrush};
+use i_slint_core::Color;
+
+/// The 8x8 Bayer matrix, with the thresholds in the order they are reached
+const BAYER_MATRIX: [[u8; 8]; 8] = [
+    [0, 32, 8, 40, 2, 34, 10, 42],
+    [48, 16, 56, 24, 50, 18, 58, 26],
+    [12, 44, 4, 36, 14, 46, 6, 38],
+    [60, 28, 52, 20, 62, 30, 54, 22],
+    [3, 35, 11, 43, 1, 33, 9, 41],
+    [51, 19, 59, 27, 49, 17, 57, 25],
+    [15, 47, 7, 39, 13, 45, 5, 37],
+    [63, 31, 55, 23, 61, 29, 53, 21],
+];
+
+/// The height of the dithered gradient textures, which repeat vertically
+pub const PATTERN_SIZE: usize = BAYER_MATRIX.len();
+
+thread_local! {
+    static DITHER_BITS: Option<u8> = std::env::var("SLINT_DITHER_GRADIENTS").ok().map(|value| {
+        match value.parse::<u8>() {
+            Ok(bits @ 1..=8) => bits,
+            _ => 8,
+        }
+    }).or(cfg!(feature = "deterministic-rendering").then(|| 8));
+}
+
+/// Returns the number of bits per channel the gradients are dithered to, or None if they are not
+/// dithered.
+pub fn gradient_dither_bits() -> Option<u8> {
+    DITHER_BITS.with(|bits| *bits)
+}
+
+/// Renders `gradient` along a horizontal line of `length` pixels, and dithers it to `bits` per channel.
+/// The angle of the gradient is ignored, the texture is meant to be rotated along the gradient line.
+/// The colors are not premultiplied.
+pub fn dithered_linear_gradient(
+    gradient: &LinearGradientBrush,
+    length: usize,
+    bits: u8,
+) -> imgref::ImgVec<rgb::RGBA8> {
+    let stops = gradient.stops().collect::<Vec<_>>();
+    let levels = ((1u32 << bits) - 1) as f32;
+    let mut pixels = Vec::with_capacity(length * PATTERN_SIZE);
+    for row in BAYER_MATRIX.iter() {
+        for x in 0..length {
+            let [red, green, blue, alpha] = color_at(&stops, (x as f32 + 0.5) / length as f32);
+            let threshold = (row[x % PATTERN_SIZE] as f32 + 0.5) / 64.;
+            let dither = |channel: f32| {
+                let quantized = (channel / 255. * levels + threshold).floor().min(levels);
+                (quantized * 255. / levels).round() as u8
+            };
+            pixels.push(rgb::RGBA8::new(dither(red), dither(green), dither(blue), dither(alpha)));
+        }
+    }
+    imgref::ImgVec::new(pixels, length, PATTERN_SIZE)
+}
+
+/// Returns the red, green, blue and alpha channels of the gradient at `position`, between 0 and 1,
+/// with the stops sorted by position. The channels are between 0 and 255, but not rounded, so that
+/// the dithering can represent the values between two levels.
+fn color_at(stops: &[&GradientStop], position: f32) -> [f32; 4] {
+    let channels =
+        |color: Color| [color.red(), color.green(), color.blue(), color.alpha()].map(f32::from);
+    let next = match stops.iter().position(|stop| stop.position >= position) {
+        Some(0) => return channels(stops[0].color),
+        Some(next) => next,
+        None => return channels(stops.last().map_or(Color::default(), |stop| stop.color)),
+    };
+    let (before, after) = (channels(stops[next - 1].color), channels(stops[next].color));
+    let range = stops[next].position - stops[next - 1].position;
+    if range <= 0. {
+        return after;
+    }
+    let factor = (position - stops[next - 1].position) / range;
+    [0, 1, 2, 3].map(|i| before[i] + (after[i] - before[i]) * factor)
+}
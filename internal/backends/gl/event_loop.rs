@@ -7,10 +7,10 @@
     [PlatformWindow] trait used by the generated code and the run-time to change
     aspects of windows on the screen.
 */
-use corelib::items::PointerEventButton;
+use corelib::items::{ColorScheme, PointerEventButton, ScrollEventPhase};
 use i_slint_core as corelib;
 
-use corelib::graphics::Point;
+use corelib::graphics::{Point, RenderingRotation};
 use corelib::input::{KeyEvent, KeyEventType, KeyboardModifiers, MouseEvent};
 use corelib::{window::*, Color};
 use corelib::{Coord, SharedString};
@@ -24,6 +24,7 @@
 pub trait WinitWindow: PlatformWindow {
     fn runtime_window(&self) -> Rc<corelib::window::Window>;
     fn currently_pressed_key_code(&self) -> &Cell<Option<winit::event::VirtualKeyCode>>;
+    fn pressed_keys(&self) -> &RefCell<PressedKeys>;
     fn current_keyboard_modifiers(&self) -> &Cell<KeyboardModifiers>;
     fn draw(self: Rc<Self>);
     fn with_window_handle(&self, callback: &mut dyn FnMut(&winit::window::Window));
@@ -34,6 +35,12 @@ fn set_constraints(
     );
     fn set_background_color(&self, color: Color);
     fn set_icon(&self, icon: corelib::graphics::Image);
+    #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+    fn handle_accessibility_action(&self, _request: accesskit::ActionRequest) {}
+    /// The clockwise rotation of the rendering on the winit window
+    fn rendering_rotation(&self) -> RenderingRotation {
+        RenderingRotation::NoRotation
+    }
 
     fn apply_constraints(
         &self,
@@ -53,6 +60,13 @@ fn apply_constraints(
                 let min_height = constraints_vertical.min.min(constraints_vertical.max) as f32;
                 let max_width = constraints_horizontal.max.max(constraints_horizontal.min) as f32;
                 let max_height = constraints_vertical.max.max(constraints_vertical.min) as f32;
+                // The constraints are the ones of the Slint window, the winit window is rotated
+                let (min_width, min_height, max_width, max_height) =
+                    if self.rendering_rotation().is_transpose() {
+                        (min_height, min_width, max_height, max_width)
+                    } else {
+                        (min_width, min_height, max_width, max_height)
+                    };
 
                 let sf = self.runtime_window().scale_factor();
 
@@ -118,8 +132,10 @@ fn apply_window_properties(
                 winit_window.set_decorations(true);
             }
 
-            let existing_size =
-                winit_window.inner_size().to_logical(self.runtime_window().scale_factor() as f64);
+            let existing_size = rotate_size(
+                self.rendering_rotation(),
+                winit_window.inner_size().to_logical(self.runtime_window().scale_factor() as f64),
+            );
 
             if width <= 0. {
                 width = existing_size.width;
@@ -137,7 +153,10 @@ fn apply_window_properties(
                 // size we've been assigned to from the windowing system. Weston/Wayland don't like it
                 // when we create a surface that's bigger than the screen due to constraints (#532).
                 if winit_window.fullscreen().is_none() {
-                    winit_window.set_inner_size(winit::dpi::LogicalSize::new(width, height));
+                    winit_window.set_inner_size(rotate_size(
+                        self.rendering_rotation(),
+                        winit::dpi::LogicalSize::new(width, height),
+                    ));
                 }
             }
         });
@@ -153,6 +172,32 @@ fn input_method_focused(&self) -> bool {
     }
 }
 
+/// Maps the size of the winit window to the size of the Slint window, or the other way around, by
+/// swapping the width and the height when the rendering is rotated by a quarter turn.
+pub(crate) fn rotate_size<P>(
+    rotation: RenderingRotation,
+    size: winit::dpi::LogicalSize<P>,
+) -> winit::dpi::LogicalSize<P> {
+    if rotation.is_transpose() {
+        winit::dpi::LogicalSize::new(size.height, size.width)
+    } else {
+        size
+    }
+}
+
+/// Maps a position in the winit window, in physical pixels, to the Slint window, in logical pixels.
+fn window_position(window: &dyn WinitWindow, position: winit::dpi::PhysicalPosition<f64>) -> Point {
+    let mut display_size = euclid::default::Size2D::default();
+    window.with_window_handle(&mut |winit_window| {
+        let size = winit_window.inner_size();
+        display_size = euclid::size2(size.width as f64, size.height as f64);
+    });
+    let position = window
+        .rendering_rotation()
+        .map_point_to_window(euclid::point2(position.x, position.y), display_size);
+    (position / window.runtime_window().scale_factor() as f64).cast()
+}
+
 struct NotRunningEventLoop {
     instance: winit::event_loop::EventLoop<CustomEvent>,
     event_loop_proxy: winit::event_loop::EventLoopProxy<CustomEvent>,
@@ -276,6 +321,66 @@ fn window_by_id(id: winit::window::WindowId) -> Option<Rc<dyn WinitWindow>> {
     ALL_WINDOWS.with(|windows| windows.borrow().get(&id).and_then(|weakref| weakref.upgrade()))
 }
 
+/// The value returned by the filter installed with [`set_event_filter`], telling whether
+/// Slint should process the event.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EventFilterResult {
+    /// The event is processed by Slint as usual.
+    Propagate,
+    /// The event was handled by the filter and is not seen by Slint.
+    Consume,
+}
+
+type EventFilter = Rc<RefCell<dyn FnMut(&winit::event::Event<()>) -> EventFilterResult>>;
+
+thread_local! {
+    static EVENT_FILTER: RefCell<Option<EventFilter>> = RefCell::new(None);
+}
+
+/// Installs a filter that is called with every event received from winit, including
+/// device events and the raw keyboard scan codes, before Slint processes it.
+/// If the filter returns [`EventFilterResult::Consume`], Slint does not see the event.
+/// Note that consuming events like `RedrawRequested` or `NewEvents` prevents the windows
+/// from being drawn and the timers from running.
+///
+/// Any previously installed filter is replaced.
+pub fn set_event_filter(
+    filter: impl FnMut(&winit::event::Event<()>) -> EventFilterResult + 'static,
+) {
+    EVENT_FILTER.with(|f| *f.borrow_mut() = Some(Rc::new(RefCell::new(filter))));
+}
+
+/// Removes the filter installed with [`set_event_filter`].
+pub fn remove_event_filter() {
+    EVENT_FILTER.with(|f| *f.borrow_mut() = None);
+}
+
+/// Passes the event to the filter, and returns it if Slint should process it.
+/// Slint's own events are never passed to the filter.
+fn filter_event(
+    event: winit::event::Event<CustomEvent>,
+) -> Option<winit::event::Event<CustomEvent>> {
+    let filter = match EVENT_FILTER.with(|f| f.borrow().clone()) {
+        Some(filter) => filter,
+        None => return Some(event),
+    };
+    let event = match event.map_nonuser_event::<()>() {
+        Ok(event) => event,
+        Err(event) => return Some(event),
+    };
+    let result = match filter.try_borrow_mut() {
+        Ok(mut filter) => filter(&event),
+        // The filter is re-entered from a nested event loop
+        Err(_) => EventFilterResult::Propagate,
+    };
+    match result {
+        EventFilterResult::Propagate => {
+            Some(event.map_nonuser_event().unwrap_or_else(|_| unreachable!()))
+        }
+        EventFilterResult::Consume => None,
+    }
+}
+
 /// This enum captures run-time specific events that can be dispatched to the event loop in
 /// addition to the winit events.
 pub enum CustomEvent {
@@ -287,11 +392,21 @@ pub enum CustomEvent {
     /// so that the event loop can run
     #[cfg(target_arch = "wasm32")]
     WakeEventLoopWorkaround,
+    /// The canvas element of the window was resized, or the device pixel ratio changed: the
+    /// size and the scale factor of the window need to be updated.
+    #[cfg(target_arch = "wasm32")]
+    CanvasResized(winit::window::WindowId),
     UpdateWindowProperties(winit::window::WindowId),
+    /// Draw the first frame of a window that stays invisible until then, since the windowing
+    /// system doesn't send redraw requests to invisible windows
+    DrawFirstFrame(winit::window::WindowId),
     UserEvent(Box<dyn FnOnce() + Send>),
     /// Called from `GLWindow::hide` so that we can check if we should quit the event loop
     WindowHidden,
     Exit,
+    /// An action requested by an assistive technology, forwarded from the accessibility adapter
+    #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+    AccessibilityAction(winit::window::WindowId, accesskit::ActionRequest),
 }
 
 impl std::fmt::Debug for CustomEvent {
@@ -301,16 +416,22 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             Self::RedrawAllWindows => write!(f, "RedrawAllWindows"),
             #[cfg(target_arch = "wasm32")]
             Self::WakeEventLoopWorkaround => write!(f, "WakeEventLoopWorkaround"),
+            #[cfg(target_arch = "wasm32")]
+            Self::CanvasResized(id) => write!(f, "CanvasResized({:?})", id),
             Self::UpdateWindowProperties(e) => write!(f, "UpdateWindowProperties({:?})", e),
+            Self::DrawFirstFrame(e) => write!(f, "DrawFirstFrame({:?})", e),
             Self::UserEvent(_) => write!(f, "UserEvent"),
             Self::WindowHidden => write!(f, "WindowHidden"),
             Self::Exit => write!(f, "Exit"),
+            #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+            Self::AccessibilityAction(id, request) => {
+                write!(f, "AccessibilityAction({:?}, {:?})", id, request)
+            }
         }
     }
 }
 
-#[cfg(target_arch = "wasm32")]
-fn redraw_all_windows() {
+pub(crate) fn redraw_all_windows() {
     let all_windows_weak =
         ALL_WINDOWS.with(|windows| windows.borrow().values().cloned().collect::<Vec<_>>());
     for window_weak in all_windows_weak {
@@ -336,6 +457,39 @@ pub fn winit_key_to_string(virtual_keycode: winit::event::VirtualKeyCode) -> Opt
     i_slint_common::for_each_special_keys!(winit_key_to_string_fn);
 }
 
+/// The keys that are held down, so that the key release events carry the text of the key press,
+/// and that the presses repeated by the system are flagged as such.
+#[derive(Default)]
+pub struct PressedKeys {
+    /// The scan code of the last key pressed, to which the following `ReceivedCharacter` belongs
+    last_scan_code: Option<u32>,
+    /// The scan codes of the keys held down, with the text sent when they were pressed
+    keys: Vec<(u32, SharedString)>,
+}
+
+impl PressedKeys {
+    /// Records that the key was pressed with the given text. Returns false if it was already held down.
+    fn press(&mut self, scan_code: u32, text: &SharedString) -> bool {
+        if self.keys.iter().any(|(code, _)| *code == scan_code) {
+            return false;
+        }
+        self.keys.push((scan_code, text.clone()));
+        true
+    }
+
+    /// Forgets the key, and returns the texts for which a key release event must be sent
+    fn release(&mut self, scan_code: u32) -> Vec<SharedString> {
+        if self.last_scan_code == Some(scan_code) {
+            self.last_scan_code = None;
+        }
+        let (released, held) = core::mem::take(&mut self.keys)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(code, _)| *code == scan_code);
+        self.keys = held;
+        released.into_iter().map(|(_, text)| text).collect()
+    }
+}
+
 fn process_window_event(
     window: Rc<dyn WinitWindow>,
     event: WindowEvent,
@@ -347,7 +501,7 @@ fn key_event(
         text: SharedString,
         modifiers: KeyboardModifiers,
     ) -> KeyEvent {
-        let mut event = KeyEvent { event_type, text, modifiers };
+        let mut event = KeyEvent { event_type, text, modifiers, ..Default::default() };
 
         let tab = String::from(corelib::input::key_codes::Tab);
 
@@ -362,7 +516,10 @@ fn key_event(
     let runtime_window = window.runtime_window();
     match event {
         WindowEvent::Resized(size) => {
-            let size = size.to_logical(runtime_window.scale_factor() as f64);
+            let size = rotate_size(
+                window.rendering_rotation(),
+                size.to_logical(runtime_window.scale_factor() as f64),
+            );
             runtime_window.set_window_item_geometry(size.width, size.height);
         }
         WindowEvent::CloseRequested => {
@@ -395,11 +552,35 @@ fn key_event(
 
             let mut event = key_event(KeyEventType::KeyPressed, text, modifiers);
 
-            runtime_window.clone().process_key_input(&event);
-            event.event_type = KeyEventType::KeyReleased;
-            runtime_window.process_key_input(&event);
+            let mut pressed_keys = window.pressed_keys().borrow_mut();
+            match pressed_keys.last_scan_code {
+                Some(scan_code) => {
+                    // The key release event is sent when the key is released
+                    event.scan_code = scan_code as i32;
+                    event.is_auto_repeat = !pressed_keys.press(scan_code, &event.text);
+                    drop(pressed_keys);
+                    runtime_window.process_key_input(&event);
+                }
+                None => {
+                    // The text doesn't come from a key that is held down
+                    drop(pressed_keys);
+                    runtime_window.clone().process_key_input(&event);
+                    event.event_type = KeyEventType::KeyReleased;
+                    runtime_window.process_key_input(&event);
+                }
+            }
         }
         WindowEvent::Focused(have_focus) => {
+            if !have_focus {
+                // The key release events won't be received by this window anymore
+                let released = core::mem::take(&mut *window.pressed_keys().borrow_mut());
+                let modifiers = window.current_keyboard_modifiers().get();
+                for (scan_code, text) in released.keys {
+                    let mut event = key_event(KeyEventType::KeyReleased, text, modifiers);
+                    event.scan_code = scan_code as i32;
+                    runtime_window.clone().process_key_input(&event);
+                }
+            }
             let have_focus = have_focus || window.input_method_focused();
             // We don't render popups as separate windows yet, so treat
             // focus to be the same as being active.
@@ -409,33 +590,45 @@ fn key_event(
             }
         }
         WindowEvent::KeyboardInput { ref input, .. } => {
-            window.currently_pressed_key_code().set(match input.state {
-                winit::event::ElementState::Pressed => input.virtual_keycode,
-                _ => None,
-            });
-            if let Some(text) = input.virtual_keycode.and_then(key_codes::winit_key_to_string) {
-                #[allow(unused_mut)]
-                let mut modifiers = window.current_keyboard_modifiers().get();
-                // On wasm, the WindowEvent::ModifiersChanged event is not received
-                #[cfg(target_arch = "wasm32")]
-                #[allow(deprecated)]
-                {
-                    modifiers.shift |= input.modifiers.shift();
-                    modifiers.control |= input.modifiers.ctrl();
-                    modifiers.meta |= input.modifiers.logo();
-                    modifiers.alt |= input.modifiers.alt();
+            let pressed = input.state == winit::event::ElementState::Pressed;
+            window.currently_pressed_key_code().set(input.virtual_keycode.filter(|_| pressed));
+            #[allow(unused_mut)]
+            let mut modifiers = window.current_keyboard_modifiers().get();
+            // On wasm, the WindowEvent::ModifiersChanged event is not received
+            #[cfg(target_arch = "wasm32")]
+            #[allow(deprecated)]
+            {
+                modifiers.shift |= input.modifiers.shift();
+                modifiers.control |= input.modifiers.ctrl();
+                modifiers.meta |= input.modifiers.logo();
+                modifiers.alt |= input.modifiers.alt();
+            }
+            let special_key_text = input.virtual_keycode.and_then(key_codes::winit_key_to_string);
+
+            let mut pressed_keys = window.pressed_keys().borrow_mut();
+            if pressed {
+                // The keys producing text are sent when the ReceivedCharacter event follows
+                pressed_keys.last_scan_code = Some(input.scancode);
+                if let Some(text) = special_key_text {
+                    let mut event = key_event(KeyEventType::KeyPressed, text, modifiers);
+                    event.scan_code = input.scancode as i32;
+                    event.is_auto_repeat = !pressed_keys.press(input.scancode, &event.text);
+                    drop(pressed_keys);
+                    runtime_window.process_key_input(&event);
                 }
-
-                let event = key_event(
-                    match input.state {
-                        winit::event::ElementState::Pressed => KeyEventType::KeyPressed,
-                        winit::event::ElementState::Released => KeyEventType::KeyReleased,
-                    },
-                    text,
-                    modifiers,
-                );
-                runtime_window.process_key_input(&event);
-            };
+            } else {
+                let mut released = pressed_keys.release(input.scancode);
+                drop(pressed_keys);
+                if released.is_empty() {
+                    // The key was pressed before the window got the focus
+                    released.extend(special_key_text);
+                }
+                for text in released {
+                    let mut event = key_event(KeyEventType::KeyReleased, text, modifiers);
+                    event.scan_code = input.scancode as i32;
+                    runtime_window.clone().process_key_input(&event);
+                }
+            }
         }
         WindowEvent::ModifiersChanged(state) => {
             // To provide an easier cross-platform behavior, we map the command key to control
@@ -449,8 +642,7 @@ fn key_event(
             window.current_keyboard_modifiers().set(modifiers);
         }
         WindowEvent::CursorMoved { position, .. } => {
-            let position = position.to_logical(runtime_window.scale_factor() as f64);
-            *cursor_pos = euclid::point2(position.x, position.y);
+            *cursor_pos = window_position(&*window, position);
             runtime_window.process_mouse_input(MouseEvent::MouseMoved { pos: *cursor_pos });
         }
         WindowEvent::CursorLeft { .. } => {
@@ -460,18 +652,30 @@ fn key_event(
                 runtime_window.process_mouse_input(MouseEvent::MouseExit);
             }
         }
-        WindowEvent::MouseWheel { delta, .. } => {
-            let delta = match delta {
+        WindowEvent::MouseWheel { delta, phase, .. } => {
+            let (delta, phase) = match delta {
                 winit::event::MouseScrollDelta::LineDelta(lx, ly) => {
-                    euclid::point2(lx * 60., ly * 60.)
+                    (euclid::point2(lx * 60., ly * 60.), ScrollEventPhase::none)
                 }
                 winit::event::MouseScrollDelta::PixelDelta(d) => {
                     let d = d.to_logical(runtime_window.scale_factor() as f64);
-                    euclid::point2(d.x, d.y)
+                    // Pixel deltas come from touchpads, which report the phase of the gesture
+                    let phase = match phase {
+                        winit::event::TouchPhase::Started => ScrollEventPhase::begin,
+                        winit::event::TouchPhase::Moved => ScrollEventPhase::update,
+                        winit::event::TouchPhase::Ended | winit::event::TouchPhase::Cancelled => {
+                            ScrollEventPhase::end
+                        }
+                    };
+                    (euclid::point2(d.x, d.y), phase)
                 }
-            }
-            .cast::<Coord>();
-            runtime_window.process_mouse_input(MouseEvent::MouseWheel { pos: *cursor_pos, delta });
+            };
+            let delta = delta.cast::<Coord>();
+            runtime_window.process_mouse_input(MouseEvent::MouseWheel {
+                pos: *cursor_pos,
+                delta,
+                phase,
+            });
         }
         WindowEvent::MouseInput { state, button, .. } => {
             let button = match button {
@@ -493,8 +697,7 @@ fn key_event(
             runtime_window.process_mouse_input(ev);
         }
         WindowEvent::Touch(touch) => {
-            let location = touch.location.to_logical(runtime_window.scale_factor() as f64);
-            let pos = euclid::point2(location.x, location.y);
+            let pos = window_position(&*window, touch.location);
             let ev = match touch.phase {
                 winit::event::TouchPhase::Started => {
                     *pressed = true;
@@ -509,12 +712,18 @@ fn key_event(
             runtime_window.process_mouse_input(ev);
         }
         WindowEvent::ScaleFactorChanged { scale_factor, new_inner_size: size } => {
-            if std::env::var("SLINT_SCALE_FACTOR").is_err() {
-                let size = size.to_logical(scale_factor);
+            if crate::glwindow::scale_factor_override_from_env().is_none() {
+                let size = rotate_size(window.rendering_rotation(), size.to_logical(scale_factor));
                 runtime_window.set_window_item_geometry(size.width, size.height);
                 runtime_window.set_scale_factor(scale_factor as f32);
             }
         }
+        WindowEvent::ThemeChanged(theme) => {
+            corelib::color_scheme::set_color_scheme(match theme {
+                winit::window::Theme::Light => ColorScheme::light,
+                winit::window::Theme::Dark => ColorScheme::dark,
+            });
+        }
         _ => {}
     }
 }
@@ -542,6 +751,7 @@ pub fn run(quit_behavior: i_slint_core::backend::EventLoopQuitBehavior) {
     });
 
     let mut winit_loop = not_running_loop_instance.instance;
+    let _running_guard = corelib::event_loop::enter_event_loop();
 
     // last seen cursor position, (physical coordinate)
     let mut cursor_pos = Point::default();
@@ -554,57 +764,100 @@ pub fn run(quit_behavior: i_slint_core::backend::EventLoopQuitBehavior) {
         CURRENT_WINDOW_TARGET.set(&running_instance, || {
             *control_flow = ControlFlow::Wait;
 
-            match event {
-                winit::event::Event::WindowEvent { event, window_id } => {
-                    if let Some(window) = window_by_id(window_id) {
-                        process_window_event(window, event, &mut cursor_pos, &mut pressed);
-                    };
-                }
+            // The event loop waits for the next event after the redraws
+            let about_to_wait = matches!(event, Event::RedrawEventsCleared);
 
-                winit::event::Event::RedrawRequested(id) => {
-                    if let Some(window) = window_by_id(id) {
-                        window.draw();
+            if let Some(event) = filter_event(event) {
+                match event {
+                    winit::event::Event::WindowEvent { event, window_id } => {
+                        if let Some(window) = window_by_id(window_id) {
+                            process_window_event(window, event, &mut cursor_pos, &mut pressed);
+                        };
                     }
-                }
 
-                winit::event::Event::UserEvent(CustomEvent::UpdateWindowProperties(window_id)) => {
-                    if let Some(window) = window_by_id(window_id) {
-                        window.runtime_window().update_window_properties();
+                    winit::event::Event::RedrawRequested(id) => {
+                        if let Some(window) = window_by_id(id) {
+                            window.draw();
+                        }
                     }
-                }
-                winit::event::Event::UserEvent(CustomEvent::WindowHidden) => match quit_behavior {
-                    corelib::backend::EventLoopQuitBehavior::QuitOnLastWindowClosed => {
-                        let window_count = ALL_WINDOWS.with(|windows| windows.borrow().len());
-                        if window_count == 0 {
-                            *control_flow = winit::event_loop::ControlFlow::Exit;
+
+                    winit::event::Event::UserEvent(CustomEvent::UpdateWindowProperties(
+                        window_id,
+                    )) => {
+                        if let Some(window) = window_by_id(window_id) {
+                            window.runtime_window().update_window_properties();
+                        }
+                    }
+                    winit::event::Event::UserEvent(CustomEvent::DrawFirstFrame(window_id)) => {
+                        if let Some(window) = window_by_id(window_id) {
+                            window.draw();
+                        }
+                    }
+                    winit::event::Event::UserEvent(CustomEvent::WindowHidden) => {
+                        match quit_behavior {
+                            corelib::backend::EventLoopQuitBehavior::QuitOnLastWindowClosed => {
+                                let window_count =
+                                    ALL_WINDOWS.with(|windows| windows.borrow().len());
+                                if window_count == 0 {
+                                    *control_flow = winit::event_loop::ControlFlow::Exit;
+                                }
+                            }
+                            corelib::backend::EventLoopQuitBehavior::QuitOnlyExplicitly => {}
                         }
                     }
-                    corelib::backend::EventLoopQuitBehavior::QuitOnlyExplicitly => {}
-                },
 
-                winit::event::Event::UserEvent(CustomEvent::Exit) => {
-                    *control_flow = winit::event_loop::ControlFlow::Exit;
-                }
+                    winit::event::Event::UserEvent(CustomEvent::Exit) => {
+                        *control_flow = winit::event_loop::ControlFlow::Exit;
+                    }
 
-                winit::event::Event::UserEvent(CustomEvent::UserEvent(user)) => {
-                    user();
-                }
+                    winit::event::Event::UserEvent(CustomEvent::UserEvent(user)) => {
+                        user();
+                    }
 
-                #[cfg(target_arch = "wasm32")]
-                winit::event::Event::UserEvent(CustomEvent::RedrawAllWindows) => {
-                    redraw_all_windows()
-                }
+                    #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+                    winit::event::Event::UserEvent(CustomEvent::AccessibilityAction(
+                        window_id,
+                        request,
+                    )) => {
+                        if let Some(window) = window_by_id(window_id) {
+                            window.handle_accessibility_action(request);
+                        }
+                    }
 
-                #[cfg(target_arch = "wasm32")]
-                winit::event::Event::UserEvent(CustomEvent::WakeEventLoopWorkaround) => {
-                    *control_flow = winit::event_loop::ControlFlow::Poll;
-                }
+                    #[cfg(target_arch = "wasm32")]
+                    winit::event::Event::UserEvent(CustomEvent::RedrawAllWindows) => {
+                        redraw_all_windows()
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    winit::event::Event::UserEvent(CustomEvent::CanvasResized(window_id)) => {
+                        if let Some(window) = window_by_id(window_id) {
+                            let runtime_window = window.runtime_window();
+                            window.with_window_handle(&mut |winit_window| {
+                                let scale_factor = winit_window.scale_factor();
+                                let size = rotate_size(
+                                    window.rendering_rotation(),
+                                    winit_window.inner_size().to_logical(scale_factor),
+                                );
+                                if crate::glwindow::scale_factor_override_from_env().is_none() {
+                                    runtime_window.set_scale_factor(scale_factor as f32);
+                                }
+                                runtime_window.set_window_item_geometry(size.width, size.height);
+                            });
+                        }
+                    }
+
+                    #[cfg(target_arch = "wasm32")]
+                    winit::event::Event::UserEvent(CustomEvent::WakeEventLoopWorkaround) => {
+                        *control_flow = winit::event_loop::ControlFlow::Poll;
+                    }
 
-                winit::event::Event::NewEvents(_) => {
-                    corelib::timers::TimerList::maybe_activate_timers();
-                    corelib::animations::update_animations();
+                    winit::event::Event::NewEvents(_) => {
+                        corelib::timers::TimerList::maybe_activate_timers();
+                        corelib::animations::update_animations();
+                    }
+                    _ => (),
                 }
-                _ => (),
             }
 
             if *control_flow != winit::event_loop::ControlFlow::Exit
@@ -614,6 +867,10 @@ pub fn run(quit_behavior: i_slint_core::backend::EventLoopQuitBehavior) {
                 *control_flow = ControlFlow::Poll;
             }
 
+            if about_to_wait && *control_flow == winit::event_loop::ControlFlow::Wait {
+                corelib::event_loop::invoke_idle_callbacks();
+            }
+
             if *control_flow == winit::event_loop::ControlFlow::Wait {
                 if let Some(next_timer) = corelib::timers::TimerList::next_timeout() {
                     *control_flow = winit::event_loop::ControlFlow::WaitUntil(next_timer.into());
@@ -626,6 +883,7 @@ pub fn run(quit_behavior: i_slint_core::backend::EventLoopQuitBehavior) {
     {
         winit_loop.run_return(run_fn);
 
+        drop(_running_guard);
         *GLOBAL_PROXY.get_or_init(Default::default).lock().unwrap() = Default::default();
     }
 
@@ -0,0 +1,521 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+This module contains the [`GLRenderer`], which draws a Slint window on an OpenGL surface that
+is owned by the application instead of a window created by the GL backend. It is used with the
+custom platform API of `i_slint_core::platform` to embed Slint in an existing native application,
+or in the window that a plugin host gives to a plugin.
+
+The application either renders with its own OpenGL context, by implementing [`OpenGLInterface`],
+or lets Slint create a context for its native window with [`NativeOpenGLContext`]. A game engine can
+also draw a Slint menu or HUD into one of its textures or frame buffers with
+[`GLRenderer::render_to()`], and composite it in its scene.
+*/
+
+use std::cell::{Cell, RefCell};
+use std::num::NonZeroU32;
+use std::pin::Pin;
+use std::rc::Rc;
+
+use glow::HasContext;
+
+use i_slint_core::graphics::{FontRequest, Point, Rect, Size};
+use i_slint_core::items::{ItemRef, TextInput};
+use i_slint_core::platform::{Renderer, WindowWeak};
+use i_slint_core::window::{WindowHandleAccess, WindowRc};
+use i_slint_core::{Color, Coord};
+
+use crate::glrenderer::{CanvasRc, GLItemRenderer, GraphicsWindow, ItemGraphicsCache};
+use crate::images::{CachedImage, TextureCache};
+
+/// The OpenGL context that a [`GLRenderer`] renders with, and the surface it renders to.
+///
+/// When the application implements this trait for its own context, Slint creates its textures and
+/// frame buffers in that context, so both can use the resources of each other. Slint calls
+/// [`Self::ensure_current()`] before every use of the context, so the application may make it
+/// current on other surfaces in between.
+pub trait OpenGLInterface {
+    /// Makes the context current on the surface of the window.
+    fn ensure_current(&self);
+    /// Presents the frame at the end of [`GLRenderer::render()`]. Applications that draw more
+    /// on top of the Slint window can implement it as a no-op and swap the buffers themselves.
+    fn swap_buffers(&self);
+    /// Called before rendering when the size of the surface changed, with the new size in physical
+    /// pixels. The default implementation does nothing.
+    fn resize(&self, _size: winit::dpi::PhysicalSize<u32>) {}
+    /// Returns the address of the OpenGL function with the given name, or null if there is none.
+    fn get_proc_address(&self, name: &str) -> *const std::ffi::c_void;
+}
+
+/// An OpenGL object of the application that [`GLRenderer::render_to()`] draws into, instead of
+/// the surface.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RenderTarget {
+    /// The name of a `GL_TEXTURE_2D` texture with the `GL_RGBA` format, as large as the size given
+    /// to `render_to()`. As in a frame buffer, its first row is the bottom of the window, and the
+    /// colors have a premultiplied alpha.
+    Texture(NonZeroU32),
+    /// The name of a framebuffer object, like the one a game engine draws its scene into. Slint
+    /// draws into a texture of its own, and copies it to the bottom left corner of the frame buffer
+    /// with `glBlitFramebuffer()`, which requires OpenGL 3.0 or OpenGL ES 3.0.
+    Framebuffer(NonZeroU32),
+}
+
+/// The caches of the GPU resources of the items drawn on the surface.
+struct Surface {
+    window: WindowWeak,
+    graphics_cache: RefCell<ItemGraphicsCache>,
+    texture_cache: RefCell<TextureCache>,
+}
+
+impl GraphicsWindow for Surface {
+    fn item_graphics_cache(&self) -> &RefCell<ItemGraphicsCache> {
+        &self.graphics_cache
+    }
+
+    fn texture_cache(&self) -> &RefCell<TextureCache> {
+        &self.texture_cache
+    }
+
+    fn window(&self) -> WindowRc {
+        self.window.upgrade().unwrap().window_handle().clone()
+    }
+}
+
+/// A renderer that draws a Slint window with OpenGL, on a surface owned by the application.
+///
+/// It is created by the [`WindowAdapter`](i_slint_core::platform::WindowAdapter) of a custom
+/// platform, which returns it from its `renderer()` function, and draws with [`Self::render()`]
+/// whenever the window requested a redraw.
+pub struct GLRenderer {
+    // Declared first so that the canvas is dropped before the context
+    canvas: CanvasRc,
+    surface: Rc<Surface>,
+    size: Cell<winit::dpi::PhysicalSize<u32>>,
+    preloaded_glyphs: crate::fonts::PreloadedGlyphs,
+    /// The texture drawn into by `render_to()` with a [`RenderTarget::Framebuffer`], the frame
+    /// buffer object that reads it, and its size
+    blit_source: RefCell<Option<(CachedImage, glow::Framebuffer, winit::dpi::PhysicalSize<u32>)>>,
+    gl: glow::Context,
+    opengl_interface: Box<dyn OpenGLInterface>,
+}
+
+impl GLRenderer {
+    /// Creates a renderer for the `window` given to
+    /// [`Platform::create_window_adapter()`](i_slint_core::platform::Platform::create_window_adapter),
+    /// that renders with the given OpenGL context.
+    pub fn new(
+        opengl_interface: impl OpenGLInterface + 'static,
+        window: WindowWeak,
+    ) -> Result<Self, String> {
+        opengl_interface.ensure_current();
+        let renderer = femtovg::renderer::OpenGl::new_from_function(|name| {
+            opengl_interface.get_proc_address(name) as *const _
+        })
+        .map_err(|err| format!("could not initialize the OpenGL renderer: {:?}", err))?;
+        let canvas = femtovg::Canvas::new_with_text_context(
+            renderer,
+            crate::fonts::FONT_CACHE.with(|cache| cache.borrow().text_context.clone()),
+        )
+        .map_err(|err| format!("could not create the OpenGL canvas: {:?}", err))?;
+
+        let mut texture_cache = TextureCache::default();
+        texture_cache.init_video_renderer(|name| opengl_interface.get_proc_address(name));
+        let gl = unsafe {
+            glow::Context::from_loader_function(|name| opengl_interface.get_proc_address(name))
+        };
+
+        Ok(Self {
+            canvas: Rc::new(RefCell::new(canvas)),
+            surface: Rc::new(Surface {
+                window,
+                graphics_cache: Default::default(),
+                texture_cache: RefCell::new(texture_cache),
+            }),
+            size: Default::default(),
+            preloaded_glyphs: Default::default(),
+            blit_source: Default::default(),
+            gl,
+            opengl_interface: Box::new(opengl_interface),
+        })
+    }
+
+    /// Draws the contents of `window` on the surface, which is `size` physical pixels large,
+    /// and presents the frame. `background` is the color drawn below the items.
+    pub fn render(
+        &self,
+        window: &i_slint_core::api::Window,
+        size: winit::dpi::PhysicalSize<u32>,
+        background: Color,
+    ) {
+        self.opengl_interface.ensure_current();
+        if self.size.replace(size) != size {
+            self.opengl_interface.resize(size);
+        }
+        self.draw(window, size, background, femtovg::RenderTarget::Screen);
+        self.opengl_interface.swap_buffers();
+    }
+
+    /// Draws the contents of `window` into `target`, a texture or a frame buffer of the
+    /// application that is `size` physical pixels large, instead of the surface. The previous
+    /// contents of the target are replaced, so a transparent `background` leaves the pixels that
+    /// are not covered by the items transparent.
+    ///
+    /// This is how a game engine shows a Slint menu or HUD: it calls this function when the window
+    /// requested a redraw, composites the texture in its scene, and forwards the mouse and keyboard
+    /// events that hit it with `Window::dispatch_event()`. The buffers are not swapped, and the
+    /// frame buffer that was bound is bound again.
+    pub fn render_to(
+        &self,
+        window: &i_slint_core::api::Window,
+        target: RenderTarget,
+        size: winit::dpi::PhysicalSize<u32>,
+        background: Color,
+    ) -> Result<(), String> {
+        if size.width == 0 || size.height == 0 {
+            return Ok(());
+        }
+        self.opengl_interface.ensure_current();
+        let gl = &self.gl;
+        let previous_framebuffer = unsafe { gl.get_parameter_i32(glow::FRAMEBUFFER_BINDING) };
+
+        match target {
+            RenderTarget::Texture(texture) => {
+                let image_id = self
+                    .canvas
+                    .borrow_mut()
+                    .create_image_from_native_texture(
+                        glow::NativeTexture(texture),
+                        femtovg::ImageInfo::new(
+                            femtovg::ImageFlags::PREMULTIPLIED | femtovg::ImageFlags::FLIP_Y,
+                            size.width as usize,
+                            size.height as usize,
+                            femtovg::PixelFormat::Rgba8,
+                        ),
+                    )
+                    .map_err(|err| format!("could not render into the texture: {:?}", err))?;
+                self.draw(window, size, background, femtovg::RenderTarget::Image(image_id));
+                // femtovg doesn't delete the textures it didn't create
+                self.canvas.borrow_mut().delete_image(image_id);
+            }
+            RenderTarget::Framebuffer(framebuffer) => {
+                let mut blit_source = self.blit_source.borrow_mut();
+                if blit_source.as_ref().map_or(true, |(_, _, source_size)| *source_size != size) {
+                    if let Some((_, source_framebuffer, _)) = blit_source.take() {
+                        unsafe { gl.delete_framebuffer(source_framebuffer) };
+                    }
+                    let image =
+                        CachedImage::new_empty_on_gpu(&self.canvas, size.width, size.height)
+                            .ok_or("could not create the texture to render into")?;
+                    let texture = self
+                        .canvas
+                        .borrow()
+                        .get_native_texture(image.texture_id().unwrap())
+                        .map_err(|err| format!("could not render into a texture: {:?}", err))?;
+                    let source_framebuffer = unsafe {
+                        let source_framebuffer = gl.create_framebuffer()?;
+                        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(source_framebuffer));
+                        gl.framebuffer_texture_2d(
+                            glow::FRAMEBUFFER,
+                            glow::COLOR_ATTACHMENT0,
+                            glow::TEXTURE_2D,
+                            Some(texture),
+                            0,
+                        );
+                        source_framebuffer
+                    };
+                    *blit_source = Some((image, source_framebuffer, size));
+                }
+                let (image, source_framebuffer, _) = blit_source.as_ref().unwrap();
+                self.draw(window, size, background, image.as_render_target());
+
+                let (width, height) = (size.width as i32, size.height as i32);
+                unsafe {
+                    gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(*source_framebuffer));
+                    gl.bind_framebuffer(
+                        glow::DRAW_FRAMEBUFFER,
+                        Some(glow::NativeFramebuffer(framebuffer)),
+                    );
+                    gl.blit_framebuffer(
+                        0,
+                        0,
+                        width,
+                        height,
+                        0,
+                        0,
+                        width,
+                        height,
+                        glow::COLOR_BUFFER_BIT,
+                        glow::NEAREST,
+                    );
+                }
+            }
+        }
+
+        unsafe {
+            gl.bind_framebuffer(
+                glow::FRAMEBUFFER,
+                NonZeroU32::new(previous_framebuffer as u32).map(glow::NativeFramebuffer),
+            );
+        }
+        Ok(())
+    }
+
+    /// Draws the contents of `window` into the `target` of the canvas.
+    fn draw(
+        &self,
+        window: &i_slint_core::api::Window,
+        size: winit::dpi::PhysicalSize<u32>,
+        background: Color,
+        target: femtovg::RenderTarget,
+    ) {
+        let runtime_window = window.window_handle().clone();
+        runtime_window.update_window_properties();
+        let scale_factor = runtime_window.scale_factor();
+        let text_scale_factor = runtime_window.text_scale_factor();
+
+        runtime_window.draw_contents(|components| {
+            {
+                let mut canvas = self.canvas.borrow_mut();
+                // The scaling to physical pixels is done by the GLItemRenderer, see GLWindow::draw
+                canvas.set_size(size.width, size.height, 1.0);
+                canvas.set_render_target(target);
+                canvas.clear_rect(
+                    0,
+                    0,
+                    size.width,
+                    size.height,
+                    crate::glrenderer::to_femtovg_color(&background),
+                );
+                self.preloaded_glyphs.rasterize(
+                    &mut canvas,
+                    scale_factor,
+                    text_scale_factor,
+                    &runtime_window.default_font_properties(),
+                );
+            }
+
+            let mut renderer = GLItemRenderer::new(
+                self.canvas.clone(),
+                self.surface.clone(),
+                scale_factor,
+                text_scale_factor,
+                size,
+            );
+            renderer.set_render_target(target);
+
+            for (component, origin) in components {
+                i_slint_core::item_rendering::render_component_items(
+                    component,
+                    &mut renderer,
+                    *origin,
+                );
+            }
+            renderer.flush_fill_batch();
+
+            renderer.canvas.borrow_mut().flush();
+
+            // Delete the images and layers that are not used anymore while the context is current
+            self.surface.texture_cache.borrow_mut().drain();
+        });
+    }
+
+    fn font_request_of(&self, text_input: Pin<&TextInput>) -> FontRequest {
+        text_input.unresolved_font_request().merge(&self.surface.window().default_font_properties())
+    }
+}
+
+impl Renderer for GLRenderer {
+    fn text_size(
+        &self,
+        font_request: FontRequest,
+        text: &str,
+        max_width: Option<Coord>,
+        scale_factor: f32,
+    ) -> Size {
+        crate::fonts::text_size(
+            &font_request,
+            scale_factor,
+            self.surface.window().text_scale_factor(),
+            text,
+            max_width,
+        )
+    }
+
+    fn text_min_content_width(
+        &self,
+        font_request: FontRequest,
+        text: &str,
+        scale_factor: f32,
+    ) -> Coord {
+        crate::fonts::text_min_content_width(
+            &font_request,
+            scale_factor,
+            self.surface.window().text_scale_factor(),
+            text,
+        )
+    }
+
+    fn text_input_byte_offset_for_position(
+        &self,
+        text_input: Pin<&TextInput>,
+        pos: Point,
+        scale_factor: f32,
+    ) -> usize {
+        crate::fonts::text_input_byte_offset_for_position(
+            text_input,
+            pos,
+            self.font_request_of(text_input),
+            scale_factor,
+            self.surface.window().text_scale_factor(),
+        )
+    }
+
+    fn text_input_cursor_rect_for_byte_offset(
+        &self,
+        text_input: Pin<&TextInput>,
+        byte_offset: usize,
+        scale_factor: f32,
+    ) -> Rect {
+        crate::fonts::text_input_cursor_rect_for_byte_offset(
+            text_input,
+            byte_offset,
+            self.font_request_of(text_input),
+            scale_factor,
+            self.surface.window().text_scale_factor(),
+        )
+    }
+
+    fn free_graphics_resources(&self, items: &mut dyn Iterator<Item = Pin<ItemRef<'_>>>) {
+        let mut cache_entries_to_clear = items
+            .flat_map(|item| {
+                let cached_rendering_data = item.cached_rendering_data_offset();
+                cached_rendering_data.release(&mut *self.surface.graphics_cache.borrow_mut())
+            })
+            .peekable();
+        if cache_entries_to_clear.peek().is_some() {
+            self.opengl_interface.ensure_current();
+            cache_entries_to_clear.for_each(drop);
+        }
+    }
+}
+
+impl Drop for GLRenderer {
+    fn drop(&mut self) {
+        // The textures and the canvas must be destroyed with the context current
+        self.opengl_interface.ensure_current();
+        self.surface.graphics_cache.borrow_mut().clear();
+        self.surface.texture_cache.borrow_mut().clear();
+        if let Some((_, source_framebuffer, _)) = self.blit_source.borrow_mut().take() {
+            unsafe { self.gl.delete_framebuffer(source_framebuffer) };
+        }
+    }
+}
+
+/// An OpenGL context that Slint creates for a native window of the application, identified
+/// by its [raw window handle](raw_window_handle::HasRawWindowHandle). Windows of the Win32, Xlib
+/// and Wayland windowing systems are supported.
+pub struct NativeOpenGLContext(RefCell<Option<glutin::RawContext<glutin::PossiblyCurrent>>>);
+
+impl NativeOpenGLContext {
+    /// Creates an OpenGL context for `window`, whose surface is `size` physical pixels large.
+    ///
+    /// # Safety
+    ///
+    /// The window must stay alive as long as the returned context.
+    #[cfg_attr(not(feature = "wayland"), allow(unused_variables))]
+    pub unsafe fn new(
+        window: &impl raw_window_handle::HasRawWindowHandle,
+        size: winit::dpi::PhysicalSize<u32>,
+    ) -> Result<Self, String> {
+        use raw_window_handle::RawWindowHandle;
+
+        let builder = glutin::ContextBuilder::new().with_vsync(true);
+        let context = match window.raw_window_handle() {
+            #[cfg(target_os = "windows")]
+            RawWindowHandle::Win32(handle) => {
+                use glutin::platform::windows::RawContextExt;
+                builder.build_raw_context(handle.hwnd)
+            }
+            #[cfg(all(
+                feature = "x11",
+                any(
+                    target_os = "linux",
+                    target_os = "dragonfly",
+                    target_os = "freebsd",
+                    target_os = "netbsd",
+                    target_os = "openbsd"
+                )
+            ))]
+            RawWindowHandle::Xlib(handle) => {
+                use glutin::platform::unix::RawContextExt;
+                // X11 windows can be used from any connection to the server that created them
+                let xconn = winit::platform::unix::x11::XConnection::new(None)
+                    .map_err(|err| format!("could not connect to the X server: {}", err))?;
+                builder.build_raw_x11_context(std::sync::Arc::new(xconn), handle.window)
+            }
+            #[cfg(all(
+                feature = "wayland",
+                any(
+                    target_os = "linux",
+                    target_os = "dragonfly",
+                    target_os = "freebsd",
+                    target_os = "netbsd",
+                    target_os = "openbsd"
+                )
+            ))]
+            RawWindowHandle::Wayland(handle) => {
+                use glutin::platform::unix::RawContextExt;
+                builder.build_raw_wayland_context(
+                    handle.display as *mut _,
+                    handle.surface,
+                    size.width,
+                    size.height,
+                )
+            }
+            _ => {
+                return Err(
+                    "OpenGL contexts can only be created for Win32, Xlib and Wayland windows"
+                        .into(),
+                )
+            }
+        }
+        .map_err(|err| format!("could not create the OpenGL context: {}", err))?;
+
+        let context = context
+            .make_current()
+            .map_err(|(_, err)| format!("could not make the OpenGL context current: {}", err))?;
+        Ok(Self(RefCell::new(Some(context))))
+    }
+}
+
+impl OpenGLInterface for NativeOpenGLContext {
+    fn ensure_current(&self) {
+        let mut context = self.0.borrow_mut();
+        if context.as_ref().unwrap().is_current() {
+            return;
+        }
+        *context = Some(match unsafe { context.take().unwrap().make_current() } {
+            Ok(context) => context,
+            Err((context, err)) => {
+                i_slint_core::debug_log!("Could not make the OpenGL context current: {}", err);
+                context
+            }
+        });
+    }
+
+    fn swap_buffers(&self) {
+        if let Err(err) = self.0.borrow().as_ref().unwrap().swap_buffers() {
+            i_slint_core::debug_log!("Could not present the frame: {}", err);
+        }
+    }
+
+    fn resize(&self, size: winit::dpi::PhysicalSize<u32>) {
+        self.0.borrow().as_ref().unwrap().resize(size);
+    }
+
+    fn get_proc_address(&self, name: &str) -> *const std::ffi::c_void {
+        self.0.borrow().as_ref().unwrap().get_proc_address(name)
+    }
+}
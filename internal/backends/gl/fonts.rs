@@ -4,32 +4,45 @@
 // cspell:ignore Noto fontconfig
 
 use femtovg::TextContext;
-use i_slint_core::graphics::{FontRequest, Point, Size};
-use i_slint_core::items::{TextHorizontalAlignment, TextOverflow, TextVerticalAlignment, TextWrap};
+use i_slint_core::graphics::{FontRequest, Point, Rect, Size};
+use i_slint_core::items::{
+    TextHorizontalAlignment, TextInput, TextOverflow, TextVerticalAlignment, TextWrap,
+};
+use i_slint_core::textlayout::font_fallback::{self, GlyphCoverageCache};
+use i_slint_core::textlayout::{paragraph_break_count, ParagraphBreaks};
+use i_slint_core::window::DEFAULT_FONT_SIZE;
 use i_slint_core::{SharedString, SharedVector};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
+use std::pin::Pin;
+use unicode_bidi::BidiClass;
+
+use crate::glwindow::PASSWORD_CHARACTER;
 
-pub const DEFAULT_FONT_SIZE: f32 = 12.;
 pub const DEFAULT_FONT_WEIGHT: i32 = 400; // CSS normal
 
-#[cfg(not(any(
-    target_family = "windows",
-    target_os = "macos",
-    target_os = "ios",
-    target_arch = "wasm32"
-)))]
+#[cfg(all(
+    feature = "font-fallback",
+    not(feature = "deterministic-rendering"),
+    not(any(
+        target_family = "windows",
+        target_os = "macos",
+        target_os = "ios",
+        target_arch = "wasm32"
+    ))
+))]
 mod fontconfig;
+#[cfg(not(target_arch = "wasm32"))]
+mod layout_thread;
 
 /// This function can be used to register a custom TrueType font with Slint,
 /// for use with the `font-family` property. The provided slice must be a valid TrueType
 /// font.
 pub fn register_font_from_memory(data: &'static [u8]) -> Result<(), Box<dyn std::error::Error>> {
     FONT_CACHE.with(|cache| {
-        cache
-            .borrow_mut()
-            .available_fonts
-            .load_font_source(fontdb::Source::Binary(std::sync::Arc::new(data)))
+        let mut cache = cache.borrow_mut();
+        cache.available_fonts.load_font_source(fontdb::Source::Binary(std::sync::Arc::new(data)));
+        cache.fonts_registered();
     });
     Ok(())
 }
@@ -49,7 +62,10 @@ pub fn register_font_from_path(path: &std::path::Path) -> Result<(), Box<dyn std
             }
         }
 
-        cache.borrow_mut().available_fonts.load_font_file(requested_path).map_err(|e| e.into())
+        let mut cache = cache.borrow_mut();
+        cache.available_fonts.load_font_file(requested_path)?;
+        cache.fonts_registered();
+        Ok(())
     })
 }
 
@@ -62,6 +78,112 @@ pub fn register_font_from_path(_path: &std::path::Path) -> Result<(), Box<dyn st
     .into());
 }
 
+/// Downloads the TrueType font at `url` and registers it, for use with the `font-family`
+/// property. The text laid out with a fallback font before the download completed is laid out
+/// again with the new font, which is also used as a fallback for the characters that the other
+/// fonts don't cover.
+#[cfg(target_arch = "wasm32")]
+pub async fn register_font_from_url(url: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    let response: web_sys::Response =
+        JsFuture::from(web_sys::window().unwrap().fetch_with_str(url))
+            .await
+            .map_err(|err| js_error(&format!("could not fetch {}", url), err))?
+            .unchecked_into();
+    if !response.ok() {
+        return Err(format!("could not fetch {}: HTTP status {}", url, response.status()).into());
+    }
+    let data = JsFuture::from(response.array_buffer().map_err(|err| js_error(url, err))?)
+        .await
+        .map_err(|err| js_error(&format!("could not download {}", url), err))?;
+    register_font_data(js_sys::Uint8Array::new(&data).to_vec());
+    Ok(())
+}
+
+/// Registers the fonts of the given families that are installed on the system, with the Local
+/// Font Access API of the browser, and returns the number of font faces that were registered.
+/// The browser asks the user for the permission, so this must be called from the handler of a
+/// user action, like a click. An error is returned when the browser doesn't support the API.
+#[cfg(target_arch = "wasm32")]
+pub async fn register_local_fonts(families: &[&str]) -> Result<usize, Box<dyn std::error::Error>> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_futures::JsFuture;
+
+    // The API is not part of web-sys yet
+    let window = web_sys::window().unwrap();
+    let query_local_fonts = js_sys::Reflect::get(&window, &"queryLocalFonts".into())
+        .ok()
+        .and_then(|function| function.dyn_into::<js_sys::Function>().ok())
+        .ok_or("the Local Font Access API is not supported by this browser")?;
+    let promise = query_local_fonts
+        .call0(&window)
+        .map_err(|err| js_error("could not query the local fonts", err))?;
+    let fonts: js_sys::Array = JsFuture::from(js_sys::Promise::from(promise))
+        .await
+        .map_err(|err| js_error("could not query the local fonts", err))?
+        .unchecked_into();
+
+    let mut registered = 0;
+    for font in fonts.iter() {
+        let family = js_sys::Reflect::get(&font, &"family".into())
+            .ok()
+            .and_then(|family| family.as_string())
+            .unwrap_or_default();
+        if !families.iter().any(|requested| requested.eq_ignore_ascii_case(&family)) {
+            continue;
+        }
+        let blob = js_sys::Reflect::get(&font, &"blob".into())
+            .ok()
+            .and_then(|function| function.dyn_into::<js_sys::Function>().ok())
+            .ok_or("the local font has no data")?
+            .call0(&font)
+            .map_err(|err| js_error(&family, err))?;
+        let blob: web_sys::Blob = JsFuture::from(js_sys::Promise::from(blob))
+            .await
+            .map_err(|err| js_error(&format!("could not read the font {}", family), err))?
+            .unchecked_into();
+        let data = JsFuture::from(blob.array_buffer())
+            .await
+            .map_err(|err| js_error(&format!("could not read the font {}", family), err))?;
+        register_font_data(js_sys::Uint8Array::new(&data).to_vec());
+        registered += 1;
+    }
+    Ok(registered)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn register_font_data(data: Vec<u8>) {
+    FONT_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        let face_count = cache.available_fonts.len();
+        cache.available_fonts.load_font_data(data);
+        let families = cache.available_fonts.faces()[face_count..]
+            .iter()
+            .map(|face| SharedString::from(face.family.as_str()))
+            .collect::<Vec<_>>();
+        for family in families {
+            if !cache.fallback_families.contains(&family) {
+                cache.fallback_families.push(family);
+            }
+        }
+        cache.fonts_registered();
+    });
+
+    // The font cache is not used within a window, so the event loop needs to be woken up
+    crate::event_loop::GLOBAL_PROXY.with(|global_proxy| {
+        if let Some(proxy) = &mut *global_proxy.borrow_mut() {
+            proxy.send_event(crate::event_loop::CustomEvent::RedrawAllWindows)
+        }
+    });
+}
+
+#[cfg(target_arch = "wasm32")]
+fn js_error(context: &str, err: wasm_bindgen::JsValue) -> Box<dyn std::error::Error> {
+    format!("{}: {:?}", context, err).into()
+}
+
 #[derive(Clone, PartialEq, Eq, Hash)]
 struct FontCacheKey {
     family: SharedString,
@@ -73,6 +195,8 @@ pub struct Font {
     fonts: SharedVector<femtovg::FontId>,
     pixel_size: f32,
     text_context: TextContext,
+    /// The fonts of `fonts`, in the same order, starting with the requested one
+    loaded_fonts: Vec<LoadedFont>,
 }
 
 impl Font {
@@ -87,6 +211,22 @@ pub fn init_paint(&self, letter_spacing: f32, mut paint: femtovg::Paint) -> femt
     pub fn text_size(&self, letter_spacing: f32, text: &str, max_width: Option<f32>) -> Size {
         let paint = self.init_paint(letter_spacing, femtovg::Paint::default());
         let font_metrics = self.text_context.measure_font(paint).unwrap();
+        // Until the worker thread has measured a long text, its size is estimated
+        #[cfg(not(target_arch = "wasm32"))]
+        if text.len() >= layout_thread::MIN_TEXT_LENGTH {
+            let (width, lines) = layout_thread::measure(
+                &self.loaded_fonts,
+                self.pixel_size,
+                letter_spacing,
+                text,
+                max_width,
+            )
+            .map_or_else(
+                || self.estimated_text_size(paint, text, max_width),
+                |measurement| (measurement.width, measurement.line_count),
+            );
+            return euclid::size2(width, lines as f32 * font_metrics.height());
+        }
         let mut lines = 0;
         let mut width = 0.;
         let mut start = 0;
@@ -112,24 +252,421 @@ pub fn text_size(&self, letter_spacing: f32, text: &str, max_width: Option<f32>)
         }
         euclid::size2(width, lines as f32 * font_metrics.height())
     }
+
+    /// Estimates the width of the longest line and the number of lines of a long text from the
+    /// average width of the characters at its start, while the worker thread measures it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn estimated_text_size(
+        &self,
+        paint: femtovg::Paint,
+        text: &str,
+        max_width: Option<f32>,
+    ) -> (f32, usize) {
+        const SAMPLE_LENGTH: usize = 256;
+        let sample = text.lines().find(|line| !line.trim().is_empty()).unwrap_or_default();
+        let sample_end = (0..=sample.len().min(SAMPLE_LENGTH))
+            .rev()
+            .find(|index| sample.is_char_boundary(*index))
+            .unwrap_or_default();
+        let sample = &sample[..sample_end];
+        let width_per_byte = match sample.len() {
+            0 => 0.,
+            len => {
+                self.text_context.measure_text(0., 0., sample, paint).unwrap().width() / len as f32
+            }
+        };
+
+        let mut width = 0f32;
+        let mut lines = 0;
+        for line in text.lines() {
+            let line_width = line.len() as f32 * width_per_byte;
+            match max_width {
+                Some(max_width) if max_width > 0. => {
+                    lines += ((line_width / max_width).ceil() as usize).max(1);
+                    width = width.max(line_width.min(max_width));
+                }
+                _ => {
+                    lines += 1;
+                    width = width.max(line_width);
+                }
+            }
+        }
+        (width, lines.max(1))
+    }
+
+    /// Returns the lines of a long text wrapped to `max_width` by the worker thread, or None while
+    /// it wraps them.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn wrapped_lines(
+        &self,
+        letter_spacing: f32,
+        text: &str,
+        max_width: f32,
+    ) -> Option<std::sync::Arc<Vec<std::ops::Range<usize>>>> {
+        layout_thread::measure(
+            &self.loaded_fonts,
+            self.pixel_size,
+            letter_spacing,
+            text,
+            Some(max_width),
+        )
+        .map(|measurement| measurement.lines)
+    }
+
+    /// Returns the width of the widest word of the text, which is the narrowest width the text
+    /// can be wrapped to without breaking a word.
+    pub fn min_content_width(&self, letter_spacing: f32, text: &str) -> f32 {
+        #[cfg(not(target_arch = "wasm32"))]
+        if text.len() >= layout_thread::MIN_TEXT_LENGTH {
+            return layout_thread::measure(
+                &self.loaded_fonts,
+                self.pixel_size,
+                letter_spacing,
+                text,
+                None,
+            )
+            .map_or(0., |measurement| measurement.min_content_width);
+        }
+        let paint = self.init_paint(letter_spacing, femtovg::Paint::default());
+        text.split_whitespace()
+            .map(|word| self.text_context.measure_text(0., 0., word, paint).unwrap().width())
+            .fold(0., f32::max)
+    }
+
+    /// Returns the outlines of the glyphs of a line of text, with the top of the line at y = 0. The
+    /// coordinates are divided by `scale_factor`.
+    pub fn text_outline(
+        &self,
+        letter_spacing: f32,
+        text: &str,
+        scale_factor: f32,
+    ) -> lyon_path::Path {
+        let paint = self.init_paint(letter_spacing, femtovg::Paint::default());
+        let metrics = self.text_context.measure_text(0., 0., text, paint).unwrap();
+        let mut faces: Vec<(femtovg::FontId, Option<ttf_parser::Face>)> = Vec::new();
+        let mut builder = GlyphOutlineBuilder {
+            builder: lyon_path::Path::builder().with_svg(),
+            origin: Default::default(),
+            scale: 1.,
+        };
+        for glyph in &metrics.glyphs {
+            let face = match faces.iter().position(|(font_id, _)| *font_id == glyph.font_id) {
+                Some(index) => &faces[index].1,
+                None => {
+                    let face = self
+                        .loaded_fonts
+                        .iter()
+                        .find(|font| font.femtovg_font_id == glyph.font_id)
+                        .and_then(|font| {
+                            ttf_parser::Face::from_slice(
+                                font.face_data.as_ref().as_ref(),
+                                font.face_index,
+                            )
+                            .ok()
+                        });
+                    faces.push((glyph.font_id, face));
+                    &faces.last().unwrap().1
+                }
+            };
+            let face = match face {
+                Some(face) => face,
+                None => continue,
+            };
+            // The position of a glyph is the one of the top left corner of its bitmap
+            builder.origin = euclid::point2(
+                (glyph.x - glyph.bearing_x) / scale_factor,
+                (glyph.y + glyph.bearing_y) / scale_factor,
+            );
+            builder.scale = self.pixel_size / face.units_per_em() as f32 / scale_factor;
+            face.outline_glyph(ttf_parser::GlyphId(glyph.codepoint as u16), &mut builder);
+        }
+        builder.builder.build()
+    }
+}
+
+/// Adds the outline of a glyph, in font units with y going up, to a path in pixels with y going down.
+struct GlyphOutlineBuilder {
+    builder: lyon_path::builder::WithSvg<lyon_path::path::Builder>,
+    /// The position of the origin of the glyph on the baseline
+    origin: lyon_path::math::Point,
+    /// The size of a font unit in pixels
+    scale: f32,
+}
+
+impl GlyphOutlineBuilder {
+    fn point(&self, x: f32, y: f32) -> lyon_path::math::Point {
+        lyon_path::math::point(self.origin.x + x * self.scale, self.origin.y - y * self.scale)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for GlyphOutlineBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.builder.move_to(to);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = self.point(x, y);
+        self.builder.line_to(to);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (ctrl, to) = (self.point(x1, y1), self.point(x, y));
+        self.builder.quadratic_bezier_to(ctrl, to);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (ctrl1, ctrl2, to) = (self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+        self.builder.cubic_bezier_to(ctrl1, ctrl2, to);
+    }
+
+    fn close(&mut self) {
+        self.builder.close();
+    }
+}
+
+pub(crate) fn text_outline(
+    font_request: &i_slint_core::graphics::FontRequest,
+    scale_factor: f32,
+    text_scale_factor: f32,
+    text: &str,
+) -> lyon_path::Path {
+    let font = FONT_CACHE.with(|cache| {
+        cache.borrow_mut().font(font_request.clone(), scale_factor, text_scale_factor, text)
+    });
+    let letter_spacing = font_request.letter_spacing.unwrap_or_default();
+    font.text_outline(letter_spacing * scale_factor, text, scale_factor)
 }
 
 pub(crate) fn text_size(
     font_request: &i_slint_core::graphics::FontRequest,
     scale_factor: f32,
+    text_scale_factor: f32,
     text: &str,
     max_width: Option<f32>,
 ) -> Size {
-    let font =
-        FONT_CACHE.with(|cache| cache.borrow_mut().font(font_request.clone(), scale_factor, text));
+    let font = FONT_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        // Measure again when fonts are registered later
+        cache.registered_fonts_generation.as_ref().get();
+        cache.font(font_request.clone(), scale_factor, text_scale_factor, text)
+    });
     let letter_spacing = font_request.letter_spacing.unwrap_or_default();
     font.text_size(letter_spacing, text, max_width.map(|x| x * scale_factor)) / scale_factor
 }
 
-#[derive(Copy, Clone)]
+pub(crate) fn text_min_content_width(
+    font_request: &i_slint_core::graphics::FontRequest,
+    scale_factor: f32,
+    text_scale_factor: f32,
+    text: &str,
+) -> f32 {
+    let font = FONT_CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        // Measure again when fonts are registered later
+        cache.registered_fonts_generation.as_ref().get();
+        cache.font(font_request.clone(), scale_factor, text_scale_factor, text)
+    });
+    let letter_spacing = font_request.letter_spacing.unwrap_or_default();
+    font.min_content_width(letter_spacing, text) / scale_factor
+}
+
+thread_local! {
+    /// The fonts and the characters passed to preload_glyphs(), in the order of the calls
+    static PRELOAD_REQUESTS: RefCell<Vec<(FontRequest, SharedString)>> = RefCell::default();
+}
+
+pub fn preload_glyphs(font_request: FontRequest, charset: &str) {
+    PRELOAD_REQUESTS.with(|requests| requests.borrow_mut().push((font_request, charset.into())));
+    crate::event_loop::redraw_all_windows();
+}
+
+/// Tracks which of the preload_glyphs() requests were rasterized in the glyph atlas of a canvas.
+#[derive(Default)]
+pub(crate) struct PreloadedGlyphs {
+    count: Cell<usize>,
+    /// The glyphs are rasterized at the size in physical pixels, so they are rasterized again
+    /// when the scale factors change
+    scale_factors: Cell<(f32, f32)>,
+}
+
+impl PreloadedGlyphs {
+    /// Rasterizes the glyphs of the requests that are new since the last call. `default_font` is
+    /// the default font of the window the canvas belongs to.
+    pub fn rasterize(
+        &self,
+        canvas: &mut femtovg::Canvas<femtovg::renderer::OpenGl>,
+        scale_factor: f32,
+        text_scale_factor: f32,
+        default_font: &FontRequest,
+    ) {
+        if self.scale_factors.replace((scale_factor, text_scale_factor))
+            != (scale_factor, text_scale_factor)
+        {
+            self.count.set(0);
+        }
+        PRELOAD_REQUESTS.with(|requests| {
+            let requests = requests.borrow();
+            for (font_request, charset) in requests.iter().skip(self.count.get()) {
+                let font = FONT_CACHE.with(|cache| {
+                    cache.borrow_mut().font(
+                        font_request.clone().merge(default_font),
+                        scale_factor,
+                        text_scale_factor,
+                        charset,
+                    )
+                });
+                // Filling transparent text renders nothing visible, but femtovg shapes the text
+                // and rasterizes the missing glyphs into its atlas
+                let paint = font.init_paint(
+                    font_request.letter_spacing.unwrap_or_default() * scale_factor,
+                    femtovg::Paint::color(femtovg::Color::rgba(0, 0, 0, 0)),
+                );
+                canvas.fill_text(0., 0., charset.as_str(), paint).ok();
+            }
+            self.count.set(requests.len());
+        });
+    }
+}
+
+/// Returns the byte offset in the text of `text_input` that is the nearest to `pos`, in logical
+/// pixels. `font_request` is the font of the text input, merged with the default font of its window.
+pub(crate) fn text_input_byte_offset_for_position(
+    text_input: Pin<&TextInput>,
+    pos: Point,
+    font_request: FontRequest,
+    scale_factor: f32,
+    text_scale_factor: f32,
+) -> usize {
+    let pos = pos * scale_factor;
+    let text = text_input.text();
+
+    let mut result = text.len();
+
+    let width = text_input.width() * scale_factor;
+    let height = text_input.height() * scale_factor;
+    if width <= 0. || height <= 0. || pos.y < 0. {
+        return 0;
+    }
+
+    let font = FONT_CACHE.with(|cache| {
+        cache.borrow_mut().font(font_request, scale_factor, text_scale_factor, &text_input.text())
+    });
+
+    let password_mask = text_input.password_mask();
+    let password_string;
+    let actual_text = if let Some(mask) = &password_mask {
+        password_string = mask.mask(&text, PASSWORD_CHARACTER);
+        password_string.as_str()
+    } else {
+        text.as_str()
+    };
+
+    let paint = font.init_paint(text_input.letter_spacing() * scale_factor, Default::default());
+    let text_context = FONT_CACHE.with(|cache| cache.borrow().text_context.clone());
+    let font_height = text_context.measure_font(paint).unwrap().height();
+    layout_text_lines(
+        actual_text,
+        &font,
+        Size::new(width, height),
+        (text_input.horizontal_alignment(), text_input.vertical_alignment()),
+        text_input.wrap(),
+        TextOverflow::clip,
+        "",
+        text_input.single_line(),
+        0.,
+        paint,
+        |line_text, line_pos, start, metrics| {
+            if (line_pos.y..(line_pos.y + font_height)).contains(&pos.y) {
+                let mut current_x = 0.;
+                for glyph in &metrics.glyphs {
+                    if line_pos.x + current_x + glyph.advance_x / 2. >= pos.x {
+                        result = start + glyph.byte_index;
+                        return;
+                    }
+                    current_x += glyph.advance_x;
+                }
+                result = start + line_text.trim_end().len();
+            }
+        },
+    );
+
+    match password_mask {
+        Some(mask) => mask.from_masked_offset(&text, PASSWORD_CHARACTER, result),
+        None => result,
+    }
+}
+
+/// Returns the rectangle of the cursor placed before `byte_offset` in the text of `text_input`,
+/// in logical pixels.
+pub(crate) fn text_input_cursor_rect_for_byte_offset(
+    text_input: Pin<&TextInput>,
+    byte_offset: usize,
+    font_request: FontRequest,
+    scale_factor: f32,
+    text_scale_factor: f32,
+) -> Rect {
+    let text = text_input.text();
+
+    let font_size = font_request.pixel_size.unwrap_or(DEFAULT_FONT_SIZE) * text_scale_factor;
+
+    let mut result = Point::default();
+
+    let width = text_input.width() * scale_factor;
+    let height = text_input.height() * scale_factor;
+    if width <= 0. || height <= 0. {
+        return Rect::new(result, Size::new(1.0, font_size));
+    }
+
+    let font = FONT_CACHE.with(|cache| {
+        cache.borrow_mut().font(font_request, scale_factor, text_scale_factor, &text_input.text())
+    });
+
+    let (text, byte_offset) = match text_input.password_mask() {
+        Some(mask) => (
+            mask.mask(&text, PASSWORD_CHARACTER).into(),
+            mask.to_masked_offset(&text, PASSWORD_CHARACTER, byte_offset),
+        ),
+        None => (text, byte_offset),
+    };
+
+    let paint = font.init_paint(text_input.letter_spacing() * scale_factor, Default::default());
+    layout_text_lines(
+        text.as_str(),
+        &font,
+        Size::new(width, height),
+        (text_input.horizontal_alignment(), text_input.vertical_alignment()),
+        text_input.wrap(),
+        TextOverflow::clip,
+        "",
+        text_input.single_line(),
+        0.,
+        paint,
+        |line_text, line_pos, start, metrics| {
+            if (start..=(start + line_text.len())).contains(&byte_offset) {
+                for glyph in &metrics.glyphs {
+                    if glyph.byte_index == (byte_offset - start) {
+                        result = line_pos + euclid::vec2(glyph.x, 0.0);
+                        return;
+                    }
+                }
+                if let Some(last) = metrics.glyphs.last() {
+                    result = line_pos + euclid::vec2(last.x + last.advance_x, last.y);
+                }
+            }
+        },
+    );
+
+    Rect::new(result / scale_factor, Size::new(1.0, font_size))
+}
+
+#[derive(Clone)]
 struct LoadedFont {
     femtovg_font_id: femtovg::FontId,
     fontdb_face_id: fontdb::ID,
+    // Shared with the thread that measures the long texts
+    face_data: std::sync::Arc<dyn AsRef<[u8]> + Send + Sync>,
+    face_index: u32,
 }
 
 struct SharedFontData(std::sync::Arc<dyn AsRef<[u8]>>);
@@ -139,31 +676,23 @@ fn as_ref(&self) -> &[u8] {
     }
 }
 
-#[derive(Default)]
-struct GlyphCoverage {
-    // Used to express script support for all scripts except Unknown, Common and Inherited
-    // For those the detailed glyph_coverage is used instead
-    supported_scripts: HashMap<unicode_script::Script, bool>,
-    // Especially in characters mapped to the common script, the support varies. For example
-    // '✓' and the digit '1' map to Common, but not all fonts providing digits also support the
-    // check mark glyph.
-    exact_glyph_coverage: HashMap<char, bool>,
-}
-
-enum GlyphCoverageCheckResult {
-    Incomplete,
-    Improved,
-    Complete,
-}
-
 pub struct FontCache {
     loaded_fonts: HashMap<FontCacheKey, LoadedFont>,
     // for a given fontdb face id, this tells us what we've learned about the script
     // coverage of the font.
-    loaded_font_coverage: HashMap<fontdb::ID, GlyphCoverage>,
+    loaded_font_coverage: GlyphCoverageCache<fontdb::ID>,
     pub(crate) text_context: TextContext,
     pub(crate) available_fonts: fontdb::Database,
     available_families: HashSet<SharedString>,
+    /// Incremented when fonts are registered, read when measuring text so that the layouts
+    /// depend on the set of registered fonts
+    registered_fonts_generation: Pin<Box<i_slint_core::Property<u32>>>,
+    /// The font selections that were printed, when the `SLINT_DEBUG_FONTS` environment variable
+    /// is set: each text is only reported once for each family and weight.
+    reported_font_selections: Option<HashSet<(FontCacheKey, String)>>,
+    /// The families of the fonts registered at run-time, tried after the default font
+    #[cfg(target_arch = "wasm32")]
+    fallback_families: Vec<SharedString>,
     #[cfg(not(any(
         target_family = "windows",
         target_os = "macos",
@@ -183,15 +712,27 @@ fn default() -> Self {
             target_os = "ios",
             target_arch = "wasm32"
         )))]
+        #[cfg_attr(feature = "deterministic-rendering", allow(unused_mut))]
         let mut fontconfig_fallback_families;
 
-        #[cfg(target_arch = "wasm32")]
+        // With the deterministic rendering, only the bundled font and the fonts registered by the
+        // program are used, so that the text is rendered the same way on every system
+        #[cfg(any(target_arch = "wasm32", feature = "deterministic-rendering"))]
         {
             let data = include_bytes!("fonts/DejaVuSans.ttf");
             font_db.load_font_data(data.to_vec());
             font_db.set_sans_serif_family("DejaVu Sans");
+            #[cfg(not(any(
+                target_family = "windows",
+                target_os = "macos",
+                target_os = "ios",
+                target_arch = "wasm32"
+            )))]
+            {
+                fontconfig_fallback_families = Vec::new();
+            }
         }
-        #[cfg(not(target_arch = "wasm32"))]
+        #[cfg(not(any(target_arch = "wasm32", feature = "deterministic-rendering")))]
         {
             font_db.load_system_fonts();
             #[cfg(any(
@@ -207,10 +748,27 @@ fn default() -> Self {
                 target_os = "ios",
                 target_arch = "wasm32"
             )))]
+            #[cfg(feature = "font-fallback")]
             let default_sans_serif_family = {
                 fontconfig_fallback_families = fontconfig::find_families("sans-serif");
                 fontconfig_fallback_families.remove(0)
             };
+            #[cfg(not(any(
+                target_family = "windows",
+                target_os = "macos",
+                target_os = "ios",
+                target_arch = "wasm32",
+                feature = "font-fallback"
+            )))]
+            let default_sans_serif_family = {
+                fontconfig_fallback_families = Vec::new();
+                // Without fontconfig, pick the first of the common families that is installed
+                let families = ["DejaVu Sans", "Liberation Sans", "Noto Sans", "FreeSans"];
+                families
+                    .iter()
+                    .find(|family| font_db.faces().iter().any(|face| face.family == **family))
+                    .map_or(families[0], |family| *family)
+            };
             font_db.set_sans_serif_family(default_sans_serif_family);
         }
         let available_families =
@@ -218,10 +776,15 @@ fn default() -> Self {
 
         Self {
             loaded_fonts: HashMap::new(),
-            loaded_font_coverage: HashMap::new(),
+            loaded_font_coverage: Default::default(),
             text_context: Default::default(),
             available_fonts: font_db,
             available_families,
+            registered_fonts_generation: Box::pin(Default::default()),
+            reported_font_selections: std::env::var_os("SLINT_DEBUG_FONTS")
+                .map(|_| Default::default()),
+            #[cfg(target_arch = "wasm32")]
+            fallback_families: Vec::new(),
             #[cfg(not(any(
                 target_family = "windows",
                 target_os = "macos",
@@ -238,6 +801,25 @@ fn default() -> Self {
 }
 
 impl FontCache {
+    /// Updates the caches after fonts were added to `available_fonts`: the requests that fell
+    /// back to another family are resolved again, and the text is measured again.
+    fn fonts_registered(&mut self) {
+        self.available_families =
+            self.available_fonts.faces().iter().map(|face| face.family.as_str().into()).collect();
+        let available_fonts = &self.available_fonts;
+        self.loaded_fonts.retain(|key, loaded_font| {
+            key.family.is_empty()
+                || available_fonts
+                    .face(loaded_font.fontdb_face_id)
+                    .map_or(false, |face| face.family.eq_ignore_ascii_case(&key.family))
+        });
+        if let Some(reported_font_selections) = &mut self.reported_font_selections {
+            reported_font_selections.clear();
+        }
+        let generation = self.registered_fonts_generation.as_ref();
+        generation.set(generation.get().wrapping_add(1));
+    }
+
     fn load_single_font(&mut self, request: &FontRequest) -> LoadedFont {
         let text_context = self.text_context.clone();
         let cache_key = FontCacheKey {
@@ -246,7 +828,7 @@ fn load_single_font(&mut self, request: &FontRequest) -> LoadedFont {
         };
 
         if let Some(loaded_font) = self.loaded_fonts.get(&cache_key) {
-            return *loaded_font;
+            return loaded_font.clone();
         }
 
         let family = request
@@ -301,12 +883,13 @@ fn load_single_font(&mut self, request: &FontRequest) -> LoadedFont {
             .expect("invalid fontdb face id");
 
         let femtovg_font_id = text_context
-            .add_shared_font_with_index(SharedFontData(shared_data), face_index)
+            .add_shared_font_with_index(SharedFontData(shared_data.clone()), face_index)
             .unwrap();
 
         //println!("Loaded {:#?} in {}ms.", request, now.elapsed().as_millis());
-        let new_font = LoadedFont { femtovg_font_id, fontdb_face_id };
-        self.loaded_fonts.insert(cache_key, new_font);
+        let new_font =
+            LoadedFont { femtovg_font_id, fontdb_face_id, face_data: shared_data, face_index };
+        self.loaded_fonts.insert(cache_key, new_font.clone());
         new_font
     }
 
@@ -314,77 +897,36 @@ pub fn font(
         &mut self,
         mut request: FontRequest,
         scale_factor: f32,
+        text_scale_factor: f32,
         reference_text: &str,
     ) -> Font {
-        request.pixel_size = Some(request.pixel_size.unwrap_or(DEFAULT_FONT_SIZE) * scale_factor);
+        request.pixel_size = Some(
+            request.pixel_size.unwrap_or(DEFAULT_FONT_SIZE) * text_scale_factor * scale_factor,
+        );
         request.weight = request.weight.or(Some(DEFAULT_FONT_WEIGHT));
 
-        let primary_font = self.load_single_font(&request);
-
-        use unicode_script::{Script, UnicodeScript};
-        // map from required script to sample character
-        let mut scripts_required: HashMap<unicode_script::Script, char> = Default::default();
-        let mut chars_required: HashSet<char> = Default::default();
-        for ch in reference_text.chars() {
-            if ch.is_control() || ch.is_whitespace() {
-                continue;
-            }
-            let script = ch.script();
-            if script == Script::Common || script == Script::Inherited || script == Script::Unknown
-            {
-                chars_required.insert(ch);
-            } else {
-                scripts_required.insert(script, ch);
-            }
+        let mut coverage = std::mem::take(&mut self.loaded_font_coverage);
+        let selection = font_fallback::select_fonts(self, &mut coverage, request, reference_text);
+        self.loaded_font_coverage = coverage;
+        if self.reported_font_selections.is_some() {
+            self.report_font_selection(&selection, reference_text);
         }
 
-        let mut coverage_result = self.check_and_update_script_coverage(
-            &mut scripts_required,
-            &mut chars_required,
-            primary_font.fontdb_face_id,
-        );
-
-        //eprintln!(
-        //    "coverage for {} after checking primary font: {:#?}",
-        //    reference_text, scripts_required
-        //);
-
-        let fallbacks = if !matches!(coverage_result, GlyphCoverageCheckResult::Complete) {
-            self.font_fallbacks_for_request(&request, &primary_font, reference_text)
-        } else {
-            Vec::new()
-        };
-
-        let fonts = core::iter::once(primary_font.femtovg_font_id)
-            .chain(fallbacks.iter().filter_map(|fallback_request| {
-                if matches!(coverage_result, GlyphCoverageCheckResult::Complete) {
-                    return None;
-                }
-
-                let fallback_font = self.load_single_font(fallback_request);
-
-                coverage_result = self.check_and_update_script_coverage(
-                    &mut scripts_required,
-                    &mut chars_required,
-                    fallback_font.fontdb_face_id,
-                );
+        let requests = selection.fonts;
+        let pixel_size = requests[0].pixel_size.unwrap();
+        let loaded_fonts =
+            requests.iter().map(|request| self.load_single_font(request)).collect::<Vec<_>>();
+        let fonts =
+            loaded_fonts.iter().map(|font| font.femtovg_font_id).collect::<SharedVector<_>>();
 
-                if matches!(coverage_result, GlyphCoverageCheckResult::Improved) {
-                    Some(fallback_font.femtovg_font_id)
-                } else {
-                    None
-                }
-            }))
-            .collect::<SharedVector<_>>();
-
-        Font {
-            fonts,
-            text_context: self.text_context.clone(),
-            pixel_size: request.pixel_size.unwrap(),
-        }
+        Font { fonts, text_context: self.text_context.clone(), pixel_size, loaded_fonts }
     }
 
-    #[cfg(target_os = "macos")]
+    #[cfg(all(
+        target_os = "macos",
+        feature = "font-fallback",
+        not(feature = "deterministic-rendering")
+    ))]
     fn font_fallbacks_for_request(
         &self,
         _request: &FontRequest,
@@ -414,7 +956,11 @@ fn font_fallbacks_for_request(
         .collect::<Vec<_>>()
     }
 
-    #[cfg(target_os = "windows")]
+    #[cfg(all(
+        target_os = "windows",
+        feature = "font-fallback",
+        not(feature = "deterministic-rendering")
+    ))]
     fn font_fallbacks_for_request(
         &self,
         request: &FontRequest,
@@ -518,6 +1064,19 @@ fn font_fallbacks_for_request(
             .collect()
     }
 
+    #[cfg(all(
+        any(target_os = "macos", target_os = "windows"),
+        any(not(feature = "font-fallback"), feature = "deterministic-rendering")
+    ))]
+    fn font_fallbacks_for_request(
+        &self,
+        _request: &FontRequest,
+        _primary_font: &LoadedFont,
+        _reference_text: &str,
+    ) -> Vec<FontRequest> {
+        Vec::new()
+    }
+
     #[cfg(target_arch = "wasm32")]
     fn font_fallbacks_for_request(
         &self,
@@ -525,16 +1084,16 @@ fn font_fallbacks_for_request(
         _primary_font: &LoadedFont,
         _reference_text: &str,
     ) -> Vec<FontRequest> {
-        [FontRequest {
-            family: Some("DejaVu Sans".into()),
-            weight: _request.weight,
-            pixel_size: _request.pixel_size,
-            letter_spacing: _request.letter_spacing,
-        }]
-        .iter()
-        .filter(|request| self.is_known_family(request))
-        .cloned()
-        .collect()
+        core::iter::once(&SharedString::from("DejaVu Sans"))
+            .chain(self.fallback_families.iter())
+            .map(|family| FontRequest {
+                family: Some(family.clone()),
+                weight: _request.weight,
+                pixel_size: _request.pixel_size,
+                letter_spacing: _request.letter_spacing,
+            })
+            .filter(|request| self.is_known_family(request))
+            .collect()
     }
 
     fn is_known_family(&self, request: &FontRequest) -> bool {
@@ -545,80 +1104,87 @@ fn is_known_family(&self, request: &FontRequest) -> bool {
             .unwrap_or(false)
     }
 
-    // From the set of script without coverage, remove all entries that are known to be covered by
-    // the given face_id. Any yet unknown script coverage for the face_id is updated (hence
-    // mutable self).
-    fn check_and_update_script_coverage(
+    /// Prints to stderr which faces were selected for the text, why the requested family was
+    /// not used, and the characters that none of the faces has a glyph for.
+    fn report_font_selection(
         &mut self,
-        scripts_without_coverage: &mut HashMap<unicode_script::Script, char>,
-        chars_without_coverage: &mut HashSet<char>,
-        face_id: fontdb::ID,
-    ) -> GlyphCoverageCheckResult {
-        //eprintln!("required scripts {:#?}", required_scripts);
-        let coverage = self.loaded_font_coverage.entry(face_id).or_default();
-
-        let mut scripts_that_need_checking = Vec::new();
-        let mut chars_that_need_checking = Vec::new();
-
-        let old_uncovered_scripts_count = scripts_without_coverage.len();
-        let old_uncovered_chars_count = chars_without_coverage.len();
-
-        scripts_without_coverage.retain(|script, sample| {
-            coverage.supported_scripts.get(script).map_or_else(
-                || {
-                    scripts_that_need_checking.push((*script, *sample));
-                    true // this may or may not be supported, so keep it in scripts_without_coverage
-                },
-                |has_coverage| !has_coverage,
-            )
-        });
-
-        chars_without_coverage.retain(|ch| {
-            coverage.exact_glyph_coverage.get(ch).map_or_else(
-                || {
-                    chars_that_need_checking.push(*ch);
-                    true // this may or may not be supported, so keep it in chars_without_coverage
-                },
-                |has_coverage| !has_coverage,
-            )
-        });
-
-        if !scripts_that_need_checking.is_empty() || !chars_that_need_checking.is_empty() {
-            self.available_fonts.with_face_data(face_id, |face_data, face_index| {
-                let face = ttf_parser::Face::from_slice(face_data, face_index).unwrap();
+        selection: &font_fallback::FontSelection<FontRequest>,
+        text: &str,
+    ) {
+        let primary = &selection.fonts[0];
+        let key = FontCacheKey {
+            family: primary.family.clone().unwrap_or_default(),
+            weight: primary.weight.unwrap(),
+        };
+        let reported = self.reported_font_selections.as_mut().unwrap();
+        if !reported.insert((key, text.to_owned())) {
+            return;
+        }
 
-                for (unchecked_script, sample_char) in scripts_that_need_checking {
-                    let glyph_coverage = face.glyph_index(sample_char).is_some();
-                    coverage.supported_scripts.insert(unchecked_script, glyph_coverage);
+        let mut excerpt = text.chars().take(40).collect::<String>();
+        if excerpt.len() < text.len() {
+            excerpt.push('…');
+        }
+        let requested_family =
+            primary.family.as_ref().map_or("<default>", |family| family.as_str());
+        let faces = selection
+            .fonts
+            .iter()
+            .map(|request| {
+                let face_id = self.load_single_font(request).fontdb_face_id;
+                self.available_fonts.face(face_id).map_or_else(
+                    || "<unknown>".to_owned(),
+                    |face| format!("{} ({})", face.family, face.post_script_name),
+                )
+            })
+            .collect::<Vec<_>>();
+        eprintln!(
+            "SLINT_DEBUG_FONTS: {:?} with family {} and weight {}: {}",
+            excerpt,
+            requested_family,
+            primary.weight.unwrap(),
+            faces.join(", ")
+        );
 
-                    if glyph_coverage {
-                        scripts_without_coverage.remove(&unchecked_script);
-                    }
-                }
+        if primary.family.is_some() && !self.is_known_family(primary) {
+            eprintln!(
+                "    the family {} is not installed or registered, the default family is used instead",
+                requested_family
+            );
+        }
+        if !selection.missing_characters.is_empty() {
+            let missing_characters = selection
+                .missing_characters
+                .iter()
+                .map(|ch| {
+                    format!("{:?} U+{:04X} ({})", ch, *ch as u32, font_fallback::script_name(*ch))
+                })
+                .collect::<Vec<_>>();
+            eprintln!("    no glyph for {}", missing_characters.join(", "));
+        }
+    }
+}
 
-                for unchecked_char in chars_that_need_checking {
-                    let glyph_coverage = face.glyph_index(unchecked_char).is_some();
-                    coverage.exact_glyph_coverage.insert(unchecked_char, glyph_coverage);
+impl font_fallback::FontSource for FontCache {
+    type Font = FontRequest;
+    type FaceId = fontdb::ID;
 
-                    if glyph_coverage {
-                        chars_without_coverage.remove(&unchecked_char);
-                    }
-                }
-            });
-        }
+    fn face_id(&mut self, font: &FontRequest) -> fontdb::ID {
+        self.load_single_font(font).fontdb_face_id
+    }
 
-        let remaining_required_script_coverage = scripts_without_coverage.len();
-        let remaining_required_char_coverage = chars_without_coverage.len();
+    fn fallback_fonts(&mut self, primary: &FontRequest, text: &str) -> Vec<FontRequest> {
+        let primary_font = self.load_single_font(primary);
+        self.font_fallbacks_for_request(primary, &primary_font, text)
+    }
 
-        if remaining_required_script_coverage < old_uncovered_scripts_count
-            || remaining_required_char_coverage < old_uncovered_chars_count
-        {
-            GlyphCoverageCheckResult::Improved
-        } else if scripts_without_coverage.is_empty() && chars_without_coverage.is_empty() {
-            GlyphCoverageCheckResult::Complete
-        } else {
-            GlyphCoverageCheckResult::Incomplete
-        }
+    fn has_glyphs(&mut self, face: fontdb::ID, chars: &[char]) -> Vec<bool> {
+        self.available_fonts
+            .with_face_data(face, |face_data, face_index| {
+                let face = ttf_parser::Face::from_slice(face_data, face_index).unwrap();
+                chars.iter().map(|ch| face.glyph_index(*ch).is_some()).collect()
+            })
+            .unwrap_or_else(|| vec![false; chars.len()])
     }
 }
 
@@ -633,7 +1199,9 @@ pub(crate) fn layout_text_lines(
     (horizontal_alignment, vertical_alignment): (TextHorizontalAlignment, TextVerticalAlignment),
     wrap: TextWrap,
     overflow: TextOverflow,
+    ellipsis: &str,
     single_line: bool,
+    paragraph_spacing: f32,
     paint: femtovg::Paint,
     mut layout_line: impl FnMut(&str, Point, usize, &femtovg::TextMetrics),
 ) -> f32 {
@@ -644,6 +1212,8 @@ pub(crate) fn layout_text_lines(
     let font_metrics = text_context.measure_font(paint).unwrap();
     let font_height = font_metrics.height();
 
+    let ellipsis = if elide { elision_token(&text_context, ellipsis, paint) } else { "" };
+
     let text_height = || {
         if single_line {
             font_height
@@ -655,6 +1225,7 @@ pub(crate) fn layout_text_lines(
                 if wrap { Some(max_width) } else { None },
             )
             .height
+                + paragraph_spacing * paragraph_break_count(string) as f32
         }
     };
 
@@ -677,9 +1248,47 @@ pub(crate) fn layout_text_lines(
         TextVerticalAlignment::center => max_height / 2. - text_height() / 2.,
         TextVerticalAlignment::bottom => max_height - text_height(),
     };
+
+    // The long wrapped texts are drawn from the lines wrapped by the worker thread, once it has
+    #[cfg(not(target_arch = "wasm32"))]
+    if wrap && !elide && !single_line && string.len() >= layout_thread::MIN_TEXT_LENGTH {
+        let lines = match font.wrapped_lines(paint.letter_spacing(), string, max_width) {
+            Some(lines) => lines,
+            None => return baseline_y,
+        };
+        let mut y = baseline_y;
+        let mut paragraph_breaks = ParagraphBreaks::default();
+        for range in lines.iter() {
+            if y + font_height > max_height {
+                break;
+            }
+            let line = &string[range.clone()];
+            if paragraph_breaks.is_new_paragraph(
+                line.trim().is_empty(),
+                string[..range.start].ends_with('\u{2029}'),
+            ) {
+                y += paragraph_spacing;
+            }
+            let text_metrics = text_context.measure_text(0., 0., line, paint).unwrap();
+            process_line(line, y, range.start, &text_metrics);
+            y += font_height;
+        }
+        return baseline_y;
+    }
+
     let mut y = baseline_y;
     let mut start = 0;
+    let mut paragraph_breaks = ParagraphBreaks::default();
     'lines: while start < string.len() && y + font_height <= max_height {
+        if !single_line {
+            let is_blank = string[start..]
+                .split(|c| matches!(c, '\n' | '\u{2028}' | '\u{2029}'))
+                .next()
+                .map_or(true, |line| line.trim().is_empty());
+            if paragraph_breaks.is_new_paragraph(is_blank, string[..start].ends_with('\u{2029}')) {
+                y += paragraph_spacing;
+            }
+        }
         if wrap && (!elide || y + 2. * font_height <= max_height) {
             let index = text_context.break_text(max_width, &string[start..], paint).unwrap();
             if index == 0 {
@@ -705,7 +1314,7 @@ pub(crate) fn layout_text_lines(
             if text_metrics.width() > max_width || elide_last_line {
                 let w = max_width
                     - if elide {
-                        text_context.measure_text(0., 0., "…", paint).unwrap().width()
+                        text_context.measure_text(0., 0., ellipsis, paint).unwrap().width()
                     } else {
                         0.
                     };
@@ -713,9 +1322,9 @@ pub(crate) fn layout_text_lines(
                 for glyph in &text_metrics.glyphs {
                     current_x += glyph.advance_x;
                     if current_x >= w {
-                        let txt = &line[..glyph.byte_index];
+                        let txt = &line[..grapheme_boundary_before(line, glyph.byte_index)];
                         if elide {
-                            let elided = format!("{}…", txt);
+                            let elided = format!("{}{}", txt, ellipsis);
                             process_line(&elided, y, start, &text_metrics);
                         } else {
                             process_line(txt, y, start, &text_metrics);
@@ -726,7 +1335,7 @@ pub(crate) fn layout_text_lines(
                     }
                 }
                 if elide_last_line {
-                    let elided = format!("{}…", line);
+                    let elided = format!("{}{}", line, ellipsis);
                     process_line(&elided, y, start, &text_metrics);
                     y += font_height;
                     start = index;
@@ -740,3 +1349,219 @@ pub(crate) fn layout_text_lines(
     }
     baseline_y
 }
+
+/// Returns the text to draw at the end of elided lines: `ellipsis`, or "..." if the fonts of
+/// `paint` have no glyph for one of its characters.
+fn elision_token<'a>(
+    text_context: &TextContext,
+    ellipsis: &'a str,
+    paint: femtovg::Paint,
+) -> &'a str {
+    let metrics = text_context.measure_text(0., 0., ellipsis, paint).unwrap();
+    if metrics.glyphs.iter().any(|glyph| glyph.codepoint == 0) {
+        "..."
+    } else {
+        ellipsis
+    }
+}
+
+/// Returns the largest byte offset that is at a grapheme boundary in `text`, and not after `index`.
+fn grapheme_boundary_before(text: &str, index: usize) -> usize {
+    use unicode_segmentation::UnicodeSegmentation;
+    text.grapheme_indices(true)
+        .map(|(boundary, _)| boundary)
+        .take_while(|boundary| *boundary <= index)
+        .last()
+        .unwrap_or(0)
+}
+
+/// A cluster of shaped glyphs, which can't be split when drawing: several characters might be
+/// drawn with a single glyph (ligatures), or a character with several glyphs.
+struct GlyphCluster {
+    /// The bytes of the characters of the cluster within the line
+    bytes: std::ops::Range<usize>,
+    /// The horizontal extent of the glyphs of the cluster
+    x: std::ops::Range<f32>,
+    /// Whether the characters of the cluster are laid out from right to left
+    rtl: bool,
+    /// The number of characters in the cluster
+    char_count: usize,
+}
+
+impl GlyphCluster {
+    /// The position of the given byte offset within the cluster, assuming that all the
+    /// characters of a ligature have the same width.
+    fn x_at(&self, line: &str, offset: usize) -> f32 {
+        let chars_before = line[self.bytes.start..offset].chars().count();
+        let advance = (self.x.end - self.x.start) * chars_before as f32 / self.char_count as f32;
+        if self.rtl {
+            self.x.end - advance
+        } else {
+            self.x.start + advance
+        }
+    }
+
+    /// The position after the last character of the cluster, in the reading direction.
+    fn x_after(&self) -> f32 {
+        if self.rtl {
+            self.x.start
+        } else {
+            self.x.end
+        }
+    }
+}
+
+/// The geometry of a shaped line of text, to find where to draw the caret and the selection of a
+/// range of the text. The positions are derived from the clusters of shaped glyphs, so that they
+/// match ligatures and the text laid out from right to left.
+pub(crate) struct LineGeometry<'a> {
+    line: &'a str,
+    /// The clusters, in visual order from left to right
+    clusters: Vec<GlyphCluster>,
+}
+
+impl<'a> LineGeometry<'a> {
+    /// Creates the geometry of `line` from the metrics obtained by measuring it.
+    pub fn new(line: &'a str, metrics: &femtovg::TextMetrics) -> Self {
+        Self::from_glyphs(
+            line,
+            metrics
+                .glyphs
+                .iter()
+                .map(|glyph| (glyph.byte_index, glyph.x - glyph.bearing_x, glyph.advance_x)),
+        )
+    }
+
+    /// Creates the geometry of `line` from the byte index of the first character, the position
+    /// and the advance of each of its glyphs, in visual order.
+    fn from_glyphs(line: &'a str, glyphs: impl Iterator<Item = (usize, f32, f32)>) -> Self {
+        let mut clusters: Vec<GlyphCluster> = Vec::new();
+        for (byte_index, x, advance) in glyphs {
+            match clusters.last_mut() {
+                Some(cluster) if cluster.bytes.start == byte_index => {
+                    cluster.x.start = cluster.x.start.min(x);
+                    cluster.x.end = cluster.x.end.max(x + advance);
+                }
+                _ => clusters.push(GlyphCluster {
+                    bytes: byte_index..line.len(),
+                    x: x..x + advance,
+                    rtl: false,
+                    char_count: 0,
+                }),
+            }
+        }
+
+        // A cluster ends where the next one in the logical order starts
+        let mut starts = clusters.iter().map(|cluster| cluster.bytes.start).collect::<Vec<_>>();
+        starts.sort_unstable();
+        for cluster in &mut clusters {
+            let next = starts.partition_point(|start| *start <= cluster.bytes.start);
+            cluster.bytes.end = starts.get(next).copied().unwrap_or(line.len());
+            cluster.char_count = line[cluster.bytes.clone()].chars().count().max(1);
+        }
+
+        // The characters with a strong direction give the direction of their cluster, and the
+        // numbers are laid out from left to right. The other characters, like spaces, follow the
+        // order in which the shaper placed them.
+        for index in 0..clusters.len() {
+            let first_char = line[clusters[index].bytes.clone()].chars().next();
+            clusters[index].rtl = match first_char.map(unicode_bidi::bidi_class) {
+                Some(BidiClass::R | BidiClass::AL) => true,
+                Some(BidiClass::L | BidiClass::EN | BidiClass::AN) => false,
+                _ => {
+                    let start = clusters[index].bytes.start;
+                    clusters.get(index + 1).map_or(false, |next| next.bytes.start < start)
+                        || index
+                            .checked_sub(1)
+                            .map_or(false, |previous| clusters[previous].bytes.start > start)
+                }
+            };
+        }
+
+        Self { line, clusters }
+    }
+
+    /// Returns the horizontal extents covered by the characters of the given byte range of the
+    /// line, from left to right. A range that is contiguous in the text is split in several
+    /// extents when it spans runs of text laid out in different directions.
+    pub fn extents(&self, range: std::ops::Range<usize>) -> Vec<std::ops::Range<f32>> {
+        let mut extents: Vec<std::ops::Range<f32>> = Vec::new();
+        for cluster in &self.clusters {
+            let start = range.start.max(cluster.bytes.start);
+            let end = range.end.min(cluster.bytes.end);
+            if start >= end {
+                continue;
+            }
+            let (x1, x2) = (cluster.x_at(self.line, start), cluster.x_at(self.line, end));
+            let extent = x1.min(x2)..x1.max(x2);
+            match extents.last_mut() {
+                Some(last) if (last.end - extent.start).abs() < 0.5 => last.end = extent.end,
+                _ => extents.push(extent),
+            }
+        }
+        extents
+    }
+
+    /// Returns the position of the caret before the character at the given byte offset.
+    /// Where text laid out from left to right meets text laid out from right to left, the end
+    /// of the previous character and the start of the next one are at different positions: the
+    /// second position is then the one after the previous character.
+    pub fn caret(&self, offset: usize) -> (f32, Option<f32>) {
+        let before_next = self
+            .clusters
+            .iter()
+            .find(|cluster| cluster.bytes.contains(&offset))
+            .map(|cluster| cluster.x_at(self.line, offset));
+        let after_previous = self
+            .clusters
+            .iter()
+            .find(|cluster| cluster.bytes.end == offset)
+            .map(|cluster| cluster.x_after());
+        match (before_next, after_previous) {
+            (Some(next), Some(previous)) if (next - previous).abs() >= 0.5 => {
+                (next, Some(previous))
+            }
+            (Some(x), _) | (None, Some(x)) => (x, None),
+            (None, None) => (0., None),
+        }
+    }
+}
+
+#[test]
+fn line_geometry_of_ligatures() {
+    // "ffi" is drawn with a single glyph
+    let geometry = LineGeometry::from_glyphs(
+        "office",
+        [(0, 0., 10.), (1, 10., 30.), (4, 40., 10.), (5, 50., 10.)].into_iter(),
+    );
+    assert_eq!(geometry.extents(0..6), vec![0. ..60.]);
+    assert_eq!(geometry.extents(1..4), vec![10. ..40.]);
+    // The characters of the ligature share its width
+    assert_eq!(geometry.extents(2..3), vec![20. ..30.]);
+    assert_eq!(geometry.caret(2), (20., None));
+    assert_eq!(geometry.caret(4), (40., None));
+    assert_eq!(geometry.caret(6), (60., None));
+}
+
+#[test]
+fn line_geometry_of_mixed_directions() {
+    // The Hebrew letters alef and bet, two bytes each, are laid out from right to left after
+    // "ab ": the glyph of bet is on the left of the one of alef
+    let line = "ab \u{5d0}\u{5d1}1";
+    let geometry = LineGeometry::from_glyphs(
+        line,
+        [(0, 0., 10.), (1, 10., 10.), (2, 20., 5.), (5, 25., 10.), (3, 35., 10.), (7, 45., 10.)]
+            .into_iter(),
+    );
+    // "b", " " and alef: the selection is split where the direction changes
+    assert_eq!(geometry.extents(1..5), vec![10. ..25., 35. ..45.]);
+    // Alef and bet
+    assert_eq!(geometry.extents(3..7), vec![25. ..45.]);
+    // Before alef, the caret is on its right, and after the space on the left of bet
+    assert_eq!(geometry.caret(3), (45., Some(25.)));
+    // Between alef and bet
+    assert_eq!(geometry.caret(5), (35., None));
+    // Before the digit, which is laid out from left to right, and after bet
+    assert_eq!(geometry.caret(7), (45., Some(25.)));
+    assert_eq!(geometry.caret(line.len()), (55., None));
+}
@@ -0,0 +1,365 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Measures and wraps the long texts on a worker thread, so that shaping and wrapping them doesn't
+//! block the event loop when they are displayed for the first time. The worker shapes the text with
+//! the same fonts as the renderer, the requested one and its fallbacks, whose data is shared with
+//! it. Until the result lands, the size of the text is estimated and the wrapped text isn't drawn;
+//! the layouts that depend on it are invalidated, and the text is drawn from the wrapped lines, when
+//! it does.
+//! Without the `shaping` feature, the worker maps each character to the glyph of the font instead.
+
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, Mutex};
+
+use i_slint_core::textlayout::{TextLineBreaker, TextShaper};
+use i_slint_core::{Property, SharedString};
+
+use super::LoadedFont;
+
+/// The texts that have at least this many bytes are measured on the worker thread
+pub const MIN_TEXT_LENGTH: usize = 4096;
+
+/// The number of measurements that are kept, the oldest ones are dropped first
+const CACHE_SIZE: usize = 64;
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct MeasurementKey {
+    // The texts are not kept, only their hash and their length
+    text_hash: u64,
+    text_len: usize,
+    /// The hash of the ids of the faces of the requested font and of its fallbacks
+    faces_hash: u64,
+    // The bits of the f32 values, so that the key can be hashed
+    pixel_size: u32,
+    letter_spacing: u32,
+    max_width: Option<u32>,
+}
+
+/// The metrics of a text, in physical pixels, and its lines
+#[derive(Clone, Default)]
+pub struct Measurement {
+    /// The width of the longest line
+    pub width: f32,
+    pub line_count: usize,
+    /// The width of the widest word, only measured when the text isn't wrapped
+    pub min_content_width: f32,
+    /// The byte ranges of the lines, without their trailing whitespace. A blank line is an empty
+    /// range at the end of the previous line.
+    pub lines: Arc<Vec<Range<usize>>>,
+}
+
+struct MeasurementRequest {
+    key: MeasurementKey,
+    text: SharedString,
+    fonts: Vec<LoadedFont>,
+    pixel_size: f32,
+    letter_spacing: f32,
+    max_width: Option<f32>,
+}
+
+#[derive(Default)]
+struct MeasuredTexts {
+    /// The value is None while the text is measured
+    measurements: HashMap<MeasurementKey, Option<Measurement>>,
+    /// The keys of the measurements, from the oldest to the newest
+    order: std::collections::VecDeque<MeasurementKey>,
+}
+
+thread_local! {
+    static MEASURED_TEXTS: RefCell<MeasuredTexts> = RefCell::default();
+    /// Incremented when a measurement lands, read when a long text is measured so that its
+    /// layout depends on it
+    static MEASUREMENTS_GENERATION: Pin<Box<Property<u32>>> = Box::pin(Default::default());
+}
+
+static WORKER: once_cell::sync::OnceCell<Mutex<mpsc::Sender<MeasurementRequest>>> =
+    once_cell::sync::OnceCell::new();
+
+/// Returns the metrics of `text` shaped with `fonts`, the requested font followed by its fallbacks,
+/// or None if it is being measured on the worker thread. `pixel_size`, `letter_spacing` and
+/// `max_width` are in physical pixels.
+pub fn measure(
+    fonts: &[LoadedFont],
+    pixel_size: f32,
+    letter_spacing: f32,
+    text: &str,
+    max_width: Option<f32>,
+) -> Option<Measurement> {
+    MEASUREMENTS_GENERATION.with(|generation| generation.as_ref().get());
+
+    let key = measurement_key(fonts, pixel_size, letter_spacing, text, max_width);
+    let cached = MEASURED_TEXTS.with(|texts| texts.borrow().measurements.get(&key).cloned());
+    if let Some(measurement) = cached {
+        return measurement;
+    }
+
+    MEASURED_TEXTS.with(|texts| {
+        let mut texts = texts.borrow_mut();
+        while texts.order.len() >= CACHE_SIZE {
+            let oldest = texts.order.pop_front().unwrap();
+            texts.measurements.remove(&oldest);
+        }
+        texts.measurements.insert(key, None);
+        texts.order.push_back(key);
+    });
+
+    let request = MeasurementRequest {
+        key,
+        text: text.into(),
+        fonts: fonts.to_vec(),
+        pixel_size,
+        letter_spacing,
+        max_width,
+    };
+    WORKER.get_or_init(spawn_worker).lock().unwrap().send(request).ok();
+    None
+}
+
+fn measurement_key(
+    fonts: &[LoadedFont],
+    pixel_size: f32,
+    letter_spacing: f32,
+    text: &str,
+    max_width: Option<f32>,
+) -> MeasurementKey {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    let text_hash = hasher.finish();
+    let mut hasher = DefaultHasher::new();
+    for font in fonts {
+        font.fontdb_face_id.hash(&mut hasher);
+    }
+    MeasurementKey {
+        text_hash,
+        text_len: text.len(),
+        faces_hash: hasher.finish(),
+        pixel_size: pixel_size.to_bits(),
+        letter_spacing: letter_spacing.to_bits(),
+        max_width: max_width.map(f32::to_bits),
+    }
+}
+
+fn spawn_worker() -> Mutex<mpsc::Sender<MeasurementRequest>> {
+    let (sender, receiver) = mpsc::channel::<MeasurementRequest>();
+    std::thread::Builder::new()
+        .name("slint text layout".into())
+        .spawn(move || {
+            for request in receiver {
+                let key = request.key;
+                let measurement = request.measure();
+                let event = crate::event_loop::CustomEvent::UserEvent(Box::new(move || {
+                    measurement_landed(key, measurement)
+                }));
+                crate::event_loop::GLOBAL_PROXY
+                    .get_or_init(Default::default)
+                    .lock()
+                    .unwrap()
+                    .send_event(event);
+            }
+        })
+        .expect("unable to spawn the text layout thread");
+    Mutex::new(sender)
+}
+
+fn measurement_landed(key: MeasurementKey, measurement: Measurement) {
+    let still_needed = MEASURED_TEXTS.with(|texts| {
+        match texts.borrow_mut().measurements.get_mut(&key) {
+            Some(entry) => {
+                *entry = Some(measurement);
+                true
+            }
+            // Dropped from the cache in the meantime
+            None => false,
+        }
+    });
+    if still_needed {
+        MEASUREMENTS_GENERATION.with(|generation| {
+            let generation = generation.as_ref();
+            generation.set(generation.get().wrapping_add(1));
+        });
+    }
+}
+
+impl MeasurementRequest {
+    fn measure(&self) -> Measurement {
+        let faces = self
+            .fonts
+            .iter()
+            .filter_map(|font| {
+                #[cfg(feature = "shaping")]
+                let face =
+                    rustybuzz::Face::from_slice(font.face_data.as_ref().as_ref(), font.face_index);
+                #[cfg(not(feature = "shaping"))]
+                let face =
+                    ttf_parser::Face::from_slice(font.face_data.as_ref().as_ref(), font.face_index)
+                        .ok();
+                face.map(|face| (self.pixel_size / face.units_per_em() as f32, face))
+            })
+            .collect::<Vec<_>>();
+        if faces.is_empty() {
+            return Measurement::default();
+        }
+        let font = ShapingFont { faces, letter_spacing: self.letter_spacing };
+
+        let text = self.text.as_str();
+        let mut width = 0f32;
+        let mut lines = Vec::new();
+        let glyphs = RefCell::new(Vec::new());
+        for line in TextLineBreaker::new(text, &font, &glyphs, self.max_width) {
+            width = width.max(line.width());
+            let range = line.byte_range();
+            lines.push(if range.is_empty() {
+                let end = lines.last().map_or(0, |previous: &Range<usize>| previous.end);
+                end..end
+            } else {
+                range
+            });
+        }
+        let min_content_width = match self.max_width {
+            Some(_) => 0.,
+            None => i_slint_core::textlayout::min_content_width(&font, text),
+        };
+        Measurement { width, line_count: lines.len(), min_content_width, lines: Arc::new(lines) }
+    }
+}
+
+/// The fonts that can be used on the worker thread, with the size of their units in pixels. Each
+/// character is shaped with the first font that has a glyph for it. The glyphs are their advance.
+struct ShapingFont<'a> {
+    #[cfg(feature = "shaping")]
+    faces: Vec<(f32, rustybuzz::Face<'a>)>,
+    #[cfg(not(feature = "shaping"))]
+    faces: Vec<(f32, ttf_parser::Face<'a>)>,
+    letter_spacing: f32,
+}
+
+impl ShapingFont<'_> {
+    /// Returns the index of the first font that has a glyph for `ch`, or the one of the requested
+    /// font if none does.
+    fn font_index_for_char(&self, ch: char) -> usize {
+        self.faces.iter().position(|(_, face)| face.glyph_index(ch).is_some()).unwrap_or(0)
+    }
+}
+
+impl TextShaper for ShapingFont<'_> {
+    type LengthPrimitive = f32;
+    type Length = f32;
+    type Glyph = f32;
+
+    fn shape_text<GlyphStorage: std::iter::Extend<(f32, usize)>>(
+        &self,
+        text: &str,
+        glyphs: &mut GlyphStorage,
+    ) {
+        // The runs of characters that are shaped with the same font. The whitespace and the
+        // control characters stay in the run of the previous character.
+        let mut runs: Vec<(usize, Range<usize>)> = Vec::new();
+        for (byte_index, ch) in text.char_indices() {
+            let end = byte_index + ch.len_utf8();
+            let font_index =
+                (!ch.is_whitespace() && !ch.is_control()).then(|| self.font_index_for_char(ch));
+            match (runs.last_mut(), font_index) {
+                (Some((_, run)), None) => run.end = end,
+                (Some((run_font_index, run)), Some(font_index))
+                    if *run_font_index == font_index =>
+                {
+                    run.end = end
+                }
+                _ => runs.push((font_index.unwrap_or(0), byte_index..end)),
+            }
+        }
+
+        for (font_index, run) in runs {
+            let (scale, face) = &self.faces[font_index];
+            #[cfg(feature = "shaping")]
+            {
+                let mut buffer = rustybuzz::UnicodeBuffer::new();
+                buffer.push_str(&text[run.clone()]);
+                let glyph_buffer = rustybuzz::shape(face, &[], buffer);
+                let glyphs_iter =
+                    glyph_buffer.glyph_infos().iter().zip(glyph_buffer.glyph_positions());
+                glyphs.extend(glyphs_iter.map(|(info, position)| {
+                    (
+                        position.x_advance as f32 * scale + self.letter_spacing,
+                        run.start + info.cluster as usize,
+                    )
+                }));
+            }
+            #[cfg(not(feature = "shaping"))]
+            glyphs.extend(text[run.clone()].char_indices().map(|(byte_index, ch)| {
+                let advance = face
+                    .glyph_index(ch)
+                    .and_then(|glyph_id| face.glyph_hor_advance(glyph_id))
+                    .map_or(0., |advance| advance as f32 * scale);
+                (advance + self.letter_spacing, run.start + byte_index)
+            }));
+        }
+    }
+
+    fn glyph_for_char(&self, ch: char) -> Option<f32> {
+        let (scale, face) = &self.faces[self.font_index_for_char(ch)];
+        let glyph_id = face.glyph_index(ch)?;
+        let advance = face.glyph_hor_advance(glyph_id)?;
+        Some(advance as f32 * scale + self.letter_spacing)
+    }
+
+    fn glyph_advance_x(&self, glyph: &f32) -> f32 {
+        *glyph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled_fonts() -> Vec<LoadedFont> {
+        let data: &'static [u8] = include_bytes!("DejaVuSans.ttf");
+        let mut database = fontdb::Database::new();
+        database.load_font_data(data.to_vec());
+        let text_context = femtovg::TextContext::default();
+        vec![LoadedFont {
+            femtovg_font_id: text_context.add_font_mem(data).unwrap(),
+            fontdb_face_id: database.faces()[0].id,
+            face_data: Arc::new(data),
+            face_index: 0,
+        }]
+    }
+
+    #[test]
+    fn layout_is_invalidated_when_the_measurement_lands() {
+        let fonts = bundled_fonts();
+        let text = "Lorem ipsum dolor sit amet. ".repeat(MIN_TEXT_LENGTH / 16);
+        let max_width = Some(200.);
+
+        let line_count = Box::pin(Property::<usize>::default());
+        line_count.as_ref().set_binding({
+            let (fonts, text) = (fonts.clone(), text.clone());
+            move || measure(&fonts, 12., 0., &text, max_width).map_or(0, |m| m.line_count)
+        });
+        assert_eq!(line_count.as_ref().get(), 0);
+        assert!(!line_count.as_ref().is_dirty());
+
+        // Land the measurement as the worker does, through the event loop
+        let request = MeasurementRequest {
+            key: measurement_key(&fonts, 12., 0., &text, max_width),
+            text: text.as_str().into(),
+            fonts,
+            pixel_size: 12.,
+            letter_spacing: 0.,
+            max_width,
+        };
+        let measurement = request.measure();
+        assert!(measurement.line_count > 1);
+        assert_eq!(measurement.lines.len(), measurement.line_count);
+        measurement_landed(request.key, measurement.clone());
+
+        assert!(line_count.as_ref().is_dirty());
+        assert_eq!(line_count.as_ref().get(), measurement.line_count);
+    }
+}
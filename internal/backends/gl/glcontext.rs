@@ -220,6 +220,23 @@ pub fn new_context_and_renderer(
 
             use winit::platform::web::WindowBuilderExtWebSys;
 
+            // winit sets the size of the canvas element in its style. Find out which dimensions
+            // of the element are set by the style sheets of the page instead of the size of the
+            // drawing buffer, to hand them back to the page after resizing.
+            let sized_by_page = {
+                let (client_width, client_height) = (canvas.client_width(), canvas.client_height());
+                let (width, height) = (canvas.width(), canvas.height());
+                canvas.set_width(width + 1);
+                canvas.set_height(height + 1);
+                let sized_by_page = (
+                    canvas.client_width() == client_width,
+                    canvas.client_height() == client_height,
+                );
+                canvas.set_width(width);
+                canvas.set_height(height);
+                sized_by_page
+            };
+
             let existing_canvas_size = winit::dpi::LogicalSize::new(
                 canvas.client_width() as u32,
                 canvas.client_height() as u32,
@@ -234,35 +251,6 @@ pub fn new_context_and_renderer(
 
             // Try to maintain the existing size of the canvas element. A window created with winit
             // on the web will always have 1024x768 as size otherwise.
-
-            let resize_canvas = {
-                let window = window.clone();
-                let canvas = canvas.clone();
-                move |_: web_sys::Event| {
-                    let existing_canvas_size = winit::dpi::LogicalSize::new(
-                        canvas.client_width() as u32,
-                        canvas.client_height() as u32,
-                    );
-
-                    window.set_inner_size(existing_canvas_size);
-                    window.request_redraw();
-                    crate::event_loop::with_window_target(|event_loop| {
-                        event_loop
-                            .event_loop_proxy()
-                            .send_event(crate::event_loop::CustomEvent::RedrawAllWindows)
-                            .ok();
-                    })
-                }
-            };
-
-            let resize_closure =
-                wasm_bindgen::closure::Closure::wrap(Box::new(resize_canvas) as Box<dyn FnMut(_)>);
-            web_sys::window()
-                .unwrap()
-                .add_event_listener_with_callback("resize", resize_closure.as_ref().unchecked_ref())
-                .unwrap();
-            resize_closure.forget();
-
             {
                 let default_size = window.inner_size().to_logical(window.scale_factor());
                 let new_size = winit::dpi::LogicalSize::new(
@@ -280,8 +268,42 @@ pub fn new_context_and_renderer(
                 if new_size != default_size {
                     window.set_inner_size(new_size);
                 }
+                release_canvas_size(&canvas, sized_by_page);
             }
 
+            // Resize the drawing buffer when the canvas element is resized or when the device
+            // pixel ratio changes, so that the rendering is not scaled by the browser.
+            let resize_canvas: Rc<dyn Fn()> = {
+                let window = window.clone();
+                let canvas = canvas.clone();
+                Rc::new(move || {
+                    let existing_canvas_size = winit::dpi::LogicalSize::new(
+                        canvas.client_width() as u32,
+                        canvas.client_height() as u32,
+                    );
+                    // The canvas is hidden
+                    if existing_canvas_size.width == 0 || existing_canvas_size.height == 0 {
+                        return;
+                    }
+
+                    // winit computes the size of the drawing buffer with the current device pixel ratio
+                    window.set_inner_size(existing_canvas_size);
+                    release_canvas_size(&canvas, sized_by_page);
+                    window.request_redraw();
+                    let window_id = window.id();
+                    crate::event_loop::with_window_target(|event_loop| {
+                        let proxy = event_loop.event_loop_proxy();
+                        proxy
+                            .send_event(crate::event_loop::CustomEvent::CanvasResized(window_id))
+                            .ok();
+                        proxy.send_event(crate::event_loop::CustomEvent::RedrawAllWindows).ok();
+                    })
+                })
+            };
+
+            observe_canvas_resize(&canvas, resize_canvas.clone());
+            watch_device_pixel_ratio(resize_canvas);
+
             (Self(RefCell::new(Some(OpenGLContextState::Current { window, canvas }))), renderer)
         }
     }
@@ -294,3 +316,87 @@ pub fn get_proc_address(&self, name: &str) -> *const std::ffi::c_void {
         }
     }
 }
+
+/// Removes the size that winit set in the style of the canvas element, for the dimensions that
+/// are set by the style sheets of the page, so that the element follows the layout of the page.
+#[cfg(target_arch = "wasm32")]
+fn release_canvas_size(canvas: &web_sys::HtmlCanvasElement, sized_by_page: (bool, bool)) {
+    let style = canvas.style();
+    if sized_by_page.0 {
+        style.remove_property("width").ok();
+    }
+    if sized_by_page.1 {
+        style.remove_property("height").ok();
+    }
+}
+
+/// Calls `on_resize` when the size of the canvas element changes, with a `ResizeObserver`, or
+/// when the browser window is resized if the browser doesn't support it.
+#[cfg(target_arch = "wasm32")]
+fn observe_canvas_resize(canvas: &web_sys::HtmlCanvasElement, on_resize: Rc<dyn Fn()>) {
+    use wasm_bindgen::closure::Closure;
+    use wasm_bindgen::JsCast;
+
+    let html_window = web_sys::window().unwrap();
+
+    // ResizeObserver is only available with the unstable APIs of web-sys
+    let resize_observer_callback = {
+        let on_resize = on_resize.clone();
+        Closure::wrap(Box::new(move |_: wasm_bindgen::JsValue| on_resize())
+            as Box<dyn FnMut(wasm_bindgen::JsValue)>)
+    };
+    let resize_observer = js_sys::Reflect::get(&html_window, &"ResizeObserver".into())
+        .ok()
+        .and_then(|constructor| constructor.dyn_into::<js_sys::Function>().ok())
+        .and_then(|constructor| {
+            js_sys::Reflect::construct(
+                &constructor,
+                &js_sys::Array::of1(resize_observer_callback.as_ref()),
+            )
+            .ok()
+        });
+    if let Some(resize_observer) = resize_observer {
+        let observe = js_sys::Reflect::get(&resize_observer, &"observe".into())
+            .ok()
+            .and_then(|observe| observe.dyn_into::<js_sys::Function>().ok());
+        if let Some(observe) = observe {
+            if observe.call1(&resize_observer, canvas).is_ok() {
+                resize_observer_callback.forget();
+                return;
+            }
+        }
+    }
+
+    let resize_closure =
+        Closure::wrap(Box::new(move |_: web_sys::Event| on_resize()) as Box<dyn FnMut(_)>);
+    html_window
+        .add_event_listener_with_callback("resize", resize_closure.as_ref().unchecked_ref())
+        .unwrap();
+    resize_closure.forget();
+}
+
+/// Calls `on_change` when the device pixel ratio changes, for example when the page is zoomed or
+/// moved to a monitor with a different resolution. The browser has no event for that, so this
+/// listens to the media query that matches the current ratio, and to a new one when it changes.
+#[cfg(target_arch = "wasm32")]
+fn watch_device_pixel_ratio(on_change: Rc<dyn Fn()>) {
+    use wasm_bindgen::JsCast;
+
+    let html_window = web_sys::window().unwrap();
+    let query = format!("(resolution: {}dppx)", html_window.device_pixel_ratio());
+    let media_query_list = match html_window.match_media(&query) {
+        Ok(Some(media_query_list)) => media_query_list,
+        _ => return,
+    };
+    let callback = wasm_bindgen::closure::Closure::once_into_js(move |_: web_sys::Event| {
+        on_change();
+        watch_device_pixel_ratio(on_change);
+    });
+    media_query_list
+        .add_event_listener_with_callback_and_add_event_listener_options(
+            "change",
+            callback.unchecked_ref(),
+            web_sys::AddEventListenerOptions::new().once(true),
+        )
+        .ok();
+}
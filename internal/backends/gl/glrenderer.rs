@@ -7,19 +7,21 @@
 
 use euclid::approxeq::ApproxEq;
 use i_slint_core::graphics::rendering_metrics_collector::RenderingMetrics;
-use i_slint_core::graphics::{Image, IntRect, Point, Rect, RenderingCache, Size};
+use i_slint_core::graphics::{
+    Image, IntRect, Point, Rect, RenderingCache, RenderingRotation, Size,
+};
 use i_slint_core::item_rendering::{CachedRenderingData, ItemRenderer};
 use i_slint_core::items::{
-    Clip, FillRule, ImageFit, ImageRendering, InputType, Item, ItemRc, Layer, Opacity,
-    RenderingResult,
+    Clip, FillRule, ImageFit, ImageRendering, Item, ItemRc, Layer, Opacity, RenderingResult,
+    TextSpan,
 };
+use i_slint_core::properties::PropertyTracker;
 use i_slint_core::window::WindowRc;
-use i_slint_core::{Brush, Color, ImageInner, Property, SharedString};
+use i_slint_core::{Brush, Color, ImageInner, Property};
 
-use crate::event_loop::WinitWindow;
 use crate::fonts;
-use crate::glwindow::{GLWindow, PASSWORD_CHARACTER};
-use crate::images::{CachedImage, TextureCacheKey};
+use crate::glwindow::PASSWORD_CHARACTER;
+use crate::images::{CachedImage, TextureCache, TextureCacheKey};
 
 pub type Canvas = femtovg::Canvas<femtovg::renderer::OpenGl>;
 pub type CanvasRc = Rc<RefCell<Canvas>>;
@@ -49,6 +51,20 @@ fn is_colorized_image(&self) -> bool {
 
 pub type ItemGraphicsCache = RenderingCache<Option<ItemGraphicsCacheEntry>>;
 
+/// The surface that a [`GLItemRenderer`] draws into: a window of the GL backend, or a surface
+/// owned by the application. It keeps the GPU resources of the items between frames, which are
+/// only valid with the GL context of that surface.
+pub trait GraphicsWindow {
+    fn item_graphics_cache(&self) -> &RefCell<ItemGraphicsCache>;
+    fn texture_cache(&self) -> &RefCell<TextureCache>;
+    fn window(&self) -> WindowRc;
+    /// Returns the tracker of the visibility of the text cursor of the focused text input, when the
+    /// window redraws only the cursor when it blinks
+    fn text_cursor_tracker(&self) -> Option<Pin<&PropertyTracker<Box<dyn Fn()>>>> {
+        None
+    }
+}
+
 const KAPPA90: f32 = 0.55228;
 
 #[derive(Clone)]
@@ -56,6 +72,40 @@ struct State {
     scissor: Rect,
     global_alpha: f32,
     current_render_target: femtovg::RenderTarget,
+    /// The translation from the coordinates of the frame, in physical pixels, or None once the
+    /// coordinates are rotated
+    offset: Option<Point>,
+    /// False while drawing into a layer, which isn't redrawn with the frame
+    draws_frame: bool,
+}
+
+impl State {
+    /// Returns the clip in physical pixels in the coordinates of the frame, unless the coordinates
+    /// are rotated
+    fn frame_clip(&self, scale_factor: f32) -> Option<Rect> {
+        self.offset.map(|offset| (self.scissor * scale_factor).translate(offset.to_vector()))
+    }
+}
+
+/// Plain rectangles filled with the same color, with the same clip and opacity, which are drawn
+/// with a single fill of a path made of all of them instead of one fill each.
+struct FillBatch {
+    color: Color,
+    path: femtovg::Path,
+    /// The offset of the coordinates of the path from the coordinates of the frame
+    offset: Point,
+    clip: Rect,
+    global_alpha: f32,
+    fills: usize,
+}
+
+impl FillBatch {
+    /// Returns whether a rectangle filled with `color` with the `global_alpha` opacity can be part of
+    /// a batch. The rectangles of a batch are a single path, which fills the area where they overlap
+    /// only once, so only the opaque ones look the same as when they are filled one after the other.
+    fn can_contain(color: Color, global_alpha: f32) -> bool {
+        color.alpha() == 255 && global_alpha == 1.0
+    }
 }
 
 pub struct GLItemRenderer {
@@ -64,11 +114,18 @@ pub struct GLItemRenderer {
     // because that can only happen after calling `flush`. Otherwise femtovg ends up processing
     // `set_render_target` commands with image ids that have been deleted.
     layer_images_to_delete_after_flush: Vec<CachedImage>,
-    pub graphics_window: Rc<GLWindow>,
+    // The textures of the dithered gradients, which are created for every frame
+    gradient_images_to_delete_after_flush: RefCell<Vec<CachedImage>>,
+    pub graphics_window: Rc<dyn GraphicsWindow>,
     scale_factor: f32,
+    text_scale_factor: f32,
     /// track the state manually since femtovg don't have accessor for its state
     state: Vec<State>,
+    /// The fills that are not drawn yet, see [`GLItemRenderer::flush_fill_batch()`]
+    fill_batch: Option<FillBatch>,
     metrics: RenderingMetrics,
+    /// The area of the frame, in physical pixels, covered by the tracked text cursor
+    pub text_cursor_rect: Option<Rect>,
 }
 
 fn rect_with_radius_to_path(rect: Rect, border_radius: f32) -> femtovg::Path {
@@ -88,7 +145,7 @@ fn rect_with_radius_to_path(rect: Rect, border_radius: f32) -> femtovg::Path {
     path
 }
 
-fn rect_to_path(r: Rect) -> femtovg::Path {
+pub(crate) fn rect_to_path(r: Rect) -> femtovg::Path {
     rect_with_radius_to_path(r, 0.)
 }
 
@@ -152,6 +209,12 @@ fn draw_rectangle(&mut self, rect: std::pin::Pin<&i_slint_core::items::Rectangle
         if geometry.is_empty() {
             return;
         }
+        if let Brush::SolidColor(color) = rect.background() {
+            if self.add_to_fill_batch(geometry, color) {
+                return;
+            }
+        }
+        self.flush_fill_batch();
         // TODO: cache path in item to avoid re-tesselation
         let mut path = rect_to_path(geometry);
         let paint = match self.brush_to_paint(rect.background(), &mut path) {
@@ -168,11 +231,22 @@ fn draw_border_rectangle(
         &mut self,
         rect: std::pin::Pin<&i_slint_core::items::BorderRectangle>,
     ) {
+        self.flush_fill_batch();
         let mut geometry = item_rect(rect, self.scale_factor);
         if geometry.is_empty() {
             return;
         }
 
+        if self.draw_border_rectangle_with_shader(
+            geometry,
+            rect.border_radius() * self.scale_factor,
+            rect.border_width() * self.scale_factor,
+            rect.background(),
+            rect.border_color(),
+        ) {
+            return;
+        }
+
         let mut border_width = rect.border_width() * self.scale_factor;
         // In CSS the border is entirely towards the inside of the boundary
         // geometry, while in femtovg the line with for a stroke is 50% in-
@@ -199,6 +273,7 @@ fn draw_border_rectangle(
     }
 
     fn draw_image(&mut self, image: std::pin::Pin<&i_slint_core::items::ImageItem>) {
+        self.flush_fill_batch();
         self.draw_image_impl(
             &image.cached_rendering_data,
             i_slint_core::items::ImageItem::FIELD_OFFSETS.source.apply_pin(image),
@@ -215,10 +290,8 @@ fn draw_clipped_image(
         &mut self,
         clipped_image: std::pin::Pin<&i_slint_core::items::ClippedImage>,
     ) {
-        let source_clip_rect = IntRect::new(
-            [clipped_image.source_clip_x(), clipped_image.source_clip_y()].into(),
-            [clipped_image.source_clip_width(), clipped_image.source_clip_height()].into(),
-        );
+        self.flush_fill_batch();
+        let source_clip_rect = clipped_image.source_clip();
 
         self.draw_image_impl(
             &clipped_image.cached_rendering_data,
@@ -235,6 +308,7 @@ fn draw_clipped_image(
     }
 
     fn draw_text(&mut self, text: std::pin::Pin<&i_slint_core::items::Text>) {
+        self.flush_fill_batch();
         let max_width = text.width() * self.scale_factor;
         let max_height = text.height() * self.scale_factor;
 
@@ -244,12 +318,19 @@ fn draw_text(&mut self, text: std::pin::Pin<&i_slint_core::items::Text>) {
 
         let string = text.text();
         let string = string.as_str();
+        let ellipsis = text.ellipsis();
+        // Load the fonts for the glyphs of the ellipsis too, in case the line is elided
+        let reference_text = match text.overflow() {
+            i_slint_core::items::TextOverflow::elide => format!("{}{}", string, ellipsis),
+            i_slint_core::items::TextOverflow::clip => string.into(),
+        };
         let font = fonts::FONT_CACHE.with(|cache| {
             cache.borrow_mut().font(
                 text.unresolved_font_request()
-                    .merge(&self.graphics_window.default_font_properties()),
+                    .merge(&self.graphics_window.window().default_font_properties()),
                 self.scale_factor,
-                &text.text(),
+                self.text_scale_factor,
+                &reference_text,
             )
         });
 
@@ -260,6 +341,7 @@ fn draw_text(&mut self, text: std::pin::Pin<&i_slint_core::items::Text>) {
             None => return,
         };
 
+        let offset = self.state.last().unwrap().offset;
         let mut canvas = self.canvas.borrow_mut();
         fonts::layout_text_lines(
             string,
@@ -268,28 +350,35 @@ fn draw_text(&mut self, text: std::pin::Pin<&i_slint_core::items::Text>) {
             (text.horizontal_alignment(), text.vertical_alignment()),
             text.wrap(),
             text.overflow(),
+            &ellipsis,
             false,
+            text.paragraph_spacing() * self.scale_factor,
             paint,
             |to_draw, pos, _, _| {
+                let pos = snap_text_position(pos, offset);
                 canvas.fill_text(pos.x, pos.y, to_draw.trim_end(), paint).unwrap();
             },
         );
     }
 
     fn draw_text_input(&mut self, text_input: std::pin::Pin<&i_slint_core::items::TextInput>) {
+        self.flush_fill_batch();
         let width = text_input.width() * self.scale_factor;
         let height = text_input.height() * self.scale_factor;
         if width <= 0. || height <= 0. {
             return;
         }
 
+        let mut visual_representation = text_input.visual_representation();
+
         let font = fonts::FONT_CACHE.with(|cache| {
             cache.borrow_mut().font(
                 text_input
                     .unresolved_font_request()
-                    .merge(&self.graphics_window.default_font_properties()),
+                    .merge(&self.graphics_window.window().default_font_properties()),
                 self.scale_factor,
-                &text_input.text(),
+                self.text_scale_factor,
+                &visual_representation.text,
             )
         });
 
@@ -301,22 +390,38 @@ fn draw_text_input(&mut self, text_input: std::pin::Pin<&i_slint_core::items::Te
             None => return,
         };
 
-        let (mut min_select, mut max_select) = text_input.selection_anchor_and_cursor();
-        let cursor_pos = text_input.cursor_position();
-        let cursor_visible = cursor_pos >= 0 && text_input.cursor_visible() && text_input.enabled();
-        let mut cursor_pos = cursor_pos as usize;
+        visual_representation.apply_password_character(PASSWORD_CHARACTER);
+
+        let (min_select, max_select) = visual_representation.selection();
+        let state = self.state.last().unwrap();
+        let offset = state.offset;
+        let frame_clip = state.frame_clip(self.scale_factor);
+        // The blinking of the cursor of the focused text input is tracked separately, so that the
+        // window only redraws the area of the cursor
+        let cursor_tracker = (state.draws_frame && offset.is_some() && text_input.has_focus())
+            .then(|| self.graphics_window.text_cursor_tracker())
+            .flatten();
+        let has_cursor = text_input.cursor_position() >= 0 && text_input.enabled();
+        let cursor_visible = has_cursor
+            && match cursor_tracker {
+                Some(tracker) => {
+                    tracker.evaluate_as_dependency_root(|| text_input.cursor_visible())
+                }
+                None => text_input.cursor_visible(),
+            };
+        let cursor_tracked = has_cursor && cursor_tracker.is_some();
+        let needs_cursor_geometry = cursor_visible || cursor_tracked;
+        let cursor_pos = visual_representation.cursor_position;
+        let text = visual_representation.text;
+        let spans = visual_representation.spans;
+
         let mut canvas = self.canvas.borrow_mut();
-        let font_height = canvas.measure_font(paint).unwrap().height();
-        let mut text = text_input.text();
-
-        if let InputType::password = text_input.input_type() {
-            min_select = text[..min_select].chars().count() * PASSWORD_CHARACTER.len();
-            max_select = text[..max_select].chars().count() * PASSWORD_CHARACTER.len();
-            cursor_pos = text[..cursor_pos].chars().count() * PASSWORD_CHARACTER.len();
-            text = SharedString::from(PASSWORD_CHARACTER.repeat(text.chars().count()));
-        };
+        let font_metrics = canvas.measure_font(paint).unwrap();
+        let font_height = font_metrics.height();
+        let line_thickness = (font_height / 16.).max(1.);
+        let selection = (min_select != max_select).then(|| min_select..max_select);
 
-        let mut cursor_point: Option<Point> = None;
+        let mut cursor_points: Option<(Point, Option<Point>)> = None;
 
         let baseline_y = fonts::layout_text_lines(
             text.as_str(),
@@ -325,117 +430,170 @@ fn draw_text_input(&mut self, text_input: std::pin::Pin<&i_slint_core::items::Te
             (text_input.horizontal_alignment(), text_input.vertical_alignment()),
             text_input.wrap(),
             i_slint_core::items::TextOverflow::clip,
+            "",
             text_input.single_line(),
+            0.,
             paint,
             |to_draw, pos, start, metrics| {
+                let pos = snap_text_position(pos, offset);
                 let range = start..(start + to_draw.len());
-                if min_select != max_select
-                    && (range.contains(&min_select)
-                        || range.contains(&max_select)
-                        || (min_select..max_select).contains(&start))
+
+                // Split the line where the selection or a span starts or ends, and draw each
+                // segment with its own attributes.
+                let mut boundaries = vec![range.start, range.end];
+                for r in spans
+                    .iter()
+                    .map(|span| span.start as usize..span.end as usize)
+                    .chain(selection.clone())
                 {
-                    let mut selection_start_x = 0.;
-                    let mut selection_end_x = 0.;
-                    let mut after_selection_x = 0.;
-                    // Determine the first and last (inclusive) glyph of the selection. The anchor
-                    // will always be at the start of a grapheme boundary, so there's at ShapedGlyph
-                    // that has a matching byte index. For the selection end we have to look for the
-                    // visual end of glyph before the cursor, because due to for example ligatures
-                    // (or generally glyph substitution) there may not be a dedicated glyph.
-                    // FIXME: in the case of ligature, there is currently no way to know the exact
-                    // position of the split. When we know it, we might need to draw in two
-                    // steps with clip to draw each part of the ligature in a different color
-                    for glyph in &metrics.glyphs {
-                        if glyph.byte_index == min_select.saturating_sub(start) {
-                            selection_start_x = glyph.x - glyph.bearing_x;
+                    boundaries.extend([r.start, r.end].into_iter().filter(|b| range.contains(b)));
+                }
+                boundaries.sort_unstable();
+                boundaries.dedup();
+
+                let geometry = fonts::LineGeometry::new(to_draw, metrics);
+
+                let segments = boundaries
+                    .windows(2)
+                    .map(|w| {
+                        let span = TextSpan::attributes_at(&spans, w[0]);
+                        let selected = selection.as_ref().map_or(false, |s| s.contains(&w[0]));
+                        let extents = geometry
+                            .extents(w[0] - start..w[1] - start)
+                            .into_iter()
+                            .map(|extent| pos.x + extent.start..pos.x + extent.end)
+                            .collect::<Vec<_>>();
+                        (w[0]..w[1], extents, span, selected)
+                    })
+                    .collect::<Vec<_>>();
+
+                for (_, extents, span, selected) in &segments {
+                    let background = if *selected {
+                        Some(text_input.selection_background_color())
+                    } else {
+                        span.as_ref().map(|span| span.background).filter(|color| color.alpha() > 0)
+                    };
+                    if let Some(background) = background {
+                        for extent in extents {
+                            let rect = Rect::new(
+                                Point::new(extent.start, pos.y),
+                                Size::new(extent.end - extent.start, font_height),
+                            );
+                            canvas.fill_path(
+                                &mut rect_to_path(rect),
+                                femtovg::Paint::color(to_femtovg_color(&background)),
+                            );
                         }
-                        if glyph.byte_index == max_select - start
-                            || glyph.byte_index >= to_draw.len()
-                        {
-                            after_selection_x = glyph.x - glyph.bearing_x;
-                            break;
+                    }
+                }
+
+                for (segment, extents, span, selected) in segments {
+                    let mut segment_paint = paint;
+                    if selected {
+                        segment_paint
+                            .set_color(to_femtovg_color(&text_input.selection_foreground_color()));
+                    } else if let Some(color) =
+                        span.as_ref().map(|span| span.color).filter(|color| color.alpha() > 0)
+                    {
+                        segment_paint.set_color(to_femtovg_color(&color));
+                    }
+                    // The extents are from left to right, so the segment starts at the first one.
+                    // FIXME: the characters of a ligature that are in different segments are
+                    // drawn as separate glyphs, drawing each part of the ligature with a clip
+                    // would keep it intact.
+                    let x = match extents.first() {
+                        Some(extent) => extent.start,
+                        None => continue,
+                    };
+                    let to_draw = to_draw[segment.start - start..segment.end - start].trim_end();
+                    canvas.fill_text(x, pos.y, to_draw, segment_paint).unwrap();
+
+                    if let Some(span) = span {
+                        // The glyphs keep the advance of the font of the TextInput, so heavier
+                        // weights are rendered by emboldening the glyphs with a stroke
+                        if span.font_weight > 400 {
+                            segment_paint.set_line_width(
+                                (span.font_weight - 400) as f32 / 300. * line_thickness,
+                            );
+                            canvas.stroke_text(x, pos.y, to_draw, segment_paint).unwrap();
+                        }
+                        if span.underline {
+                            let mut underline = femtovg::Path::new();
+                            for extent in &extents {
+                                underline.rect(
+                                    extent.start,
+                                    pos.y + font_metrics.ascender() + line_thickness,
+                                    extent.end - extent.start,
+                                    line_thickness,
+                                );
+                            }
+                            canvas.fill_path(&mut underline, segment_paint);
                         }
-                        selection_end_x = glyph.x + glyph.advance_x;
                     }
+                }
 
-                    let selection_rect = Rect::new(
-                        pos + euclid::vec2(selection_start_x, 0.),
-                        Size::new(selection_end_x - selection_start_x, font_height),
-                    );
-                    canvas.fill_path(
-                        &mut rect_to_path(selection_rect),
-                        femtovg::Paint::color(to_femtovg_color(
-                            &text_input.selection_background_color(),
-                        )),
-                    );
-                    let mut selected_paint = paint;
-                    selected_paint
-                        .set_color(to_femtovg_color(&text_input.selection_foreground_color()));
-                    canvas
-                        .fill_text(
-                            pos.x,
-                            pos.y,
-                            &to_draw[..min_select.saturating_sub(start)].trim_end(),
-                            paint,
-                        )
-                        .unwrap();
-                    canvas
-                        .fill_text(
-                            pos.x + selection_start_x,
-                            pos.y,
-                            &to_draw[min_select.saturating_sub(start)
-                                ..(max_select - start).min(to_draw.len())]
-                                .trim_end(),
-                            selected_paint,
-                        )
-                        .unwrap();
-                    canvas
-                        .fill_text(
-                            pos.x + after_selection_x,
-                            pos.y,
-                            &to_draw[(max_select - start).min(to_draw.len())..].trim_end(),
-                            paint,
-                        )
-                        .unwrap();
-                } else {
-                    // no selection on this line
-                    canvas.fill_text(pos.x, pos.y, to_draw.trim_end(), paint).unwrap();
-                };
-                if cursor_visible
+                if needs_cursor_geometry
                     && (range.contains(&cursor_pos)
                         || (cursor_pos == range.end && cursor_pos == text.len()))
                 {
-                    let cursor_x = metrics
-                        .glyphs
-                        .iter()
-                        .find_map(|glyph| {
-                            if glyph.byte_index == (cursor_pos as usize - start) {
-                                Some(glyph.x)
-                            } else {
-                                None
-                            }
-                        })
-                        .unwrap_or_else(|| metrics.width());
-                    cursor_point = Some([pos.x + cursor_x, pos.y].into());
+                    let (cursor_x, split_cursor_x) = geometry.caret(cursor_pos - start);
+                    cursor_points = Some((
+                        Point::new(pos.x + cursor_x, pos.y),
+                        split_cursor_x.map(|x| Point::new(pos.x + x, pos.y)),
+                    ));
                 }
             },
         );
 
-        if let Some(cursor_point) =
-            cursor_point.or_else(|| cursor_visible.then(|| [0., baseline_y].into()))
+        if let Some((cursor_point, split_cursor_point)) =
+            cursor_points.or_else(|| needs_cursor_geometry.then(|| ([0., baseline_y].into(), None)))
         {
+            let cursor_width = text_input.text_cursor_width() * self.scale_factor;
+            if cursor_tracked {
+                let cursor_size = Size::new(cursor_width, font_height);
+                let local_rect = split_cursor_point.map_or(
+                    Rect::new(cursor_point, cursor_size),
+                    |split_cursor_point| {
+                        Rect::new(cursor_point, cursor_size)
+                            .union(&Rect::new(split_cursor_point, cursor_size))
+                    },
+                );
+                // Include the anti-aliased edges
+                self.text_cursor_rect = local_rect
+                    .translate(offset.unwrap_or_default().to_vector())
+                    .inflate(1., 1.)
+                    .round_out()
+                    .intersection(&frame_clip.unwrap_or_default());
+            }
+            if !cursor_visible {
+                return;
+            }
             let mut cursor_rect = femtovg::Path::new();
-            cursor_rect.rect(
-                cursor_point.x,
-                cursor_point.y,
-                text_input.text_cursor_width() * self.scale_factor,
-                font_height,
-            );
+            match split_cursor_point {
+                // At the boundary between two directions, the caret is split: the top half shows
+                // where the next character is inserted, the bottom half the end of the previous one
+                Some(split_cursor_point) => {
+                    cursor_rect.rect(
+                        cursor_point.x,
+                        cursor_point.y,
+                        cursor_width,
+                        font_height / 2.,
+                    );
+                    cursor_rect.rect(
+                        split_cursor_point.x,
+                        split_cursor_point.y + font_height / 2.,
+                        cursor_width,
+                        font_height / 2.,
+                    );
+                }
+                None => cursor_rect.rect(cursor_point.x, cursor_point.y, cursor_width, font_height),
+            }
             canvas.fill_path(&mut cursor_rect, paint);
         }
     }
 
     fn draw_path(&mut self, path: std::pin::Pin<&i_slint_core::items::Path>) {
+        self.flush_fill_batch();
         let elements = path.elements();
         if matches!(elements, i_slint_core::PathData::None) {
             return;
@@ -550,6 +708,7 @@ fn add_point(&mut self, p: Point) {
     ///  * Fill the image with the shadow color and SourceIn as composition mode
     ///  * Draw the shadow image
     fn draw_box_shadow(&mut self, box_shadow: std::pin::Pin<&i_slint_core::items::BoxShadow>) {
+        self.flush_fill_batch();
         if box_shadow.color().alpha() == 0
             || (box_shadow.blur() == 0.0
                 && box_shadow.offset_x() == 0.
@@ -559,7 +718,7 @@ fn draw_box_shadow(&mut self, box_shadow: std::pin::Pin<&i_slint_core::items::Bo
         }
 
         let cache_entry = box_shadow.cached_rendering_data.get_or_update(
-            &self.graphics_window.clone().graphics_cache,
+            self.graphics_window.clone().item_graphics_cache(),
             || {
                 ItemGraphicsCacheEntry::Image({
                     let blur = box_shadow.blur() * self.scale_factor;
@@ -682,7 +841,7 @@ fn visit_opacity(&mut self, opacity_item: Pin<&Opacity>, self_rc: &ItemRc) -> Re
             self.apply_opacity(opacity);
             opacity_item
                 .cached_rendering_data
-                .release(&mut self.graphics_window.graphics_cache.borrow_mut());
+                .release(&mut self.graphics_window.item_graphics_cache().borrow_mut());
             RenderingResult::ContinueRenderingChildren
         }
     }
@@ -733,7 +892,7 @@ fn visit_clip(&mut self, clip_item: Pin<&Clip>, self_rc: &ItemRc) -> RenderingRe
         } else {
             clip_item
                 .cached_rendering_data
-                .release(&mut self.graphics_window.graphics_cache.borrow_mut());
+                .release(&mut self.graphics_window.item_graphics_cache().borrow_mut());
 
             self.combine_clip(
                 euclid::rect(0., 0., geometry.width(), geometry.height()),
@@ -745,6 +904,8 @@ fn visit_clip(&mut self, clip_item: Pin<&Clip>, self_rc: &ItemRc) -> RenderingRe
     }
 
     fn combine_clip(&mut self, clip_rect: Rect, radius: f32, border_width: f32) {
+        // The scissor of femtovg applies to the pending fills too
+        self.flush_fill_batch();
         let clip = &mut self.state.last_mut().unwrap().scissor;
         match clip.intersection(&clip_rect) {
             Some(r) => {
@@ -782,6 +943,17 @@ fn save_state(&mut self) {
     }
 
     fn restore_state(&mut self) {
+        // The pending fills are kept if the restored clip and opacity are the same
+        let keep_fill_batch = match (&self.fill_batch, self.state.iter().rev().nth(1)) {
+            (Some(batch), Some(restored)) => {
+                restored.frame_clip(self.scale_factor) == Some(batch.clip)
+                    && restored.global_alpha == batch.global_alpha
+            }
+            _ => true,
+        };
+        if !keep_fill_batch {
+            self.flush_fill_batch();
+        }
         self.state.pop();
         self.canvas.borrow_mut().restore();
     }
@@ -795,23 +967,25 @@ fn draw_cached_pixmap(
         item_cache: &CachedRenderingData,
         update_fn: &dyn Fn(&mut dyn FnMut(u32, u32, &[u8])),
     ) {
+        self.flush_fill_batch();
         let canvas = &self.canvas;
 
-        let cache_entry = item_cache.get_or_update(&self.graphics_window.graphics_cache, || {
-            let mut cached_image = None;
-            update_fn(&mut |width: u32, height: u32, data: &[u8]| {
-                use rgb::FromSlice;
-                let img = imgref::Img::new(data.as_rgba(), width as usize, height as usize);
-                if let Ok(image_id) =
-                    canvas.borrow_mut().create_image(img, femtovg::ImageFlags::PREMULTIPLIED)
-                {
-                    cached_image = Some(ItemGraphicsCacheEntry::Image(Rc::new(
-                        CachedImage::new_on_gpu(canvas, image_id),
-                    )))
-                };
+        let cache_entry =
+            item_cache.get_or_update(self.graphics_window.item_graphics_cache(), || {
+                let mut cached_image = None;
+                update_fn(&mut |width: u32, height: u32, data: &[u8]| {
+                    use rgb::FromSlice;
+                    let img = imgref::Img::new(data.as_rgba(), width as usize, height as usize);
+                    if let Ok(image_id) =
+                        canvas.borrow_mut().create_image(img, femtovg::ImageFlags::PREMULTIPLIED)
+                    {
+                        cached_image = Some(ItemGraphicsCacheEntry::Image(Rc::new(
+                            CachedImage::new_on_gpu(canvas, image_id),
+                        )))
+                    };
+                });
+                cached_image
             });
-            cached_image
-        });
         let image_id = match cache_entry {
             Some(ItemGraphicsCacheEntry::Image(image)) => image.ensure_uploaded_to_gpu(self, None),
             Some(ItemGraphicsCacheEntry::ColorizedImage { .. }) => unreachable!(),
@@ -828,10 +1002,12 @@ fn draw_cached_pixmap(
     }
 
     fn draw_string(&mut self, string: &str, color: Color) {
+        self.flush_fill_batch();
         let font = fonts::FONT_CACHE.with(|cache| {
             cache.borrow_mut().font(
-                self.graphics_window.default_font_properties(),
+                self.graphics_window.window().default_font_properties(),
                 self.scale_factor,
+                self.text_scale_factor,
                 string,
             )
         });
@@ -841,20 +1017,27 @@ fn draw_string(&mut self, string: &str, color: Color) {
     }
 
     fn window(&self) -> WindowRc {
-        self.graphics_window.runtime_window()
+        self.graphics_window.window()
     }
 
     fn as_any(&mut self) -> &mut dyn std::any::Any {
+        // The caller may draw on the canvas directly
+        self.flush_fill_batch();
         self
     }
 
     fn translate(&mut self, x: f32, y: f32) {
         self.canvas.borrow_mut().translate(x * self.scale_factor, y * self.scale_factor);
-        let clip = &mut self.state.last_mut().unwrap().scissor;
-        *clip = clip.translate((-x, -y).into())
+        let state = self.state.last_mut().unwrap();
+        state.scissor = state.scissor.translate((-x, -y).into());
+        if let Some(offset) = &mut state.offset {
+            *offset += euclid::vec2(x * self.scale_factor, y * self.scale_factor);
+        }
     }
 
     fn rotate(&mut self, angle_in_degrees: f32) {
+        self.flush_fill_batch();
+        self.state.last_mut().unwrap().offset = None;
         let angle_in_radians = angle_in_degrees.to_radians();
         self.canvas.borrow_mut().rotate(angle_in_radians);
         let clip = &mut self.state.last_mut().unwrap().scissor;
@@ -881,6 +1064,7 @@ fn rotate(&mut self, angle_in_degrees: f32) {
     }
 
     fn apply_opacity(&mut self, opacity: f32) {
+        self.flush_fill_batch();
         let state = &mut self.state.last_mut().unwrap().global_alpha;
         *state *= opacity;
         self.canvas.borrow_mut().set_global_alpha(*state);
@@ -894,15 +1078,18 @@ fn metrics(&self) -> RenderingMetrics {
 impl GLItemRenderer {
     pub fn new(
         canvas: CanvasRc,
-        graphics_window: Rc<GLWindow>,
+        graphics_window: Rc<dyn GraphicsWindow>,
         scale_factor: f32,
+        text_scale_factor: f32,
         size: winit::dpi::PhysicalSize<u32>,
     ) -> Self {
         Self {
             canvas,
             layer_images_to_delete_after_flush: Default::default(),
+            gradient_images_to_delete_after_flush: Default::default(),
             graphics_window,
             scale_factor,
+            text_scale_factor,
             state: vec![State {
                 scissor: Rect::new(
                     Point::default(),
@@ -910,9 +1097,91 @@ pub fn new(
                 ),
                 global_alpha: 1.,
                 current_render_target: femtovg::RenderTarget::Screen,
+                offset: Some(Point::default()),
+                draws_frame: true,
             }],
-            metrics: RenderingMetrics { layers_created: Some(0) },
+            fill_batch: None,
+            metrics: RenderingMetrics {
+                layers_created: Some(0),
+                batched_fills: Some(0),
+                fill_batches: Some(0),
+            },
+            text_cursor_rect: None,
+        }
+    }
+
+    /// Draws the frame into `target` instead of the screen.
+    pub fn set_render_target(&mut self, target: femtovg::RenderTarget) {
+        self.state[0].current_render_target = target;
+    }
+
+    /// Skips the items outside of the `region` of the frame, in physical pixels. The canvas must
+    /// be clipped to the region already.
+    pub fn restrict_to_region(&mut self, region: Rect) {
+        let state = &mut self.state[0];
+        state.scissor =
+            state.scissor.intersection(&(region / self.scale_factor)).unwrap_or_default();
+    }
+
+    /// Adds a plain rectangle filled with `color` to the batch of fills, after drawing the pending
+    /// batch if the color, the clip or the opacity differ. Returns false if the rectangle can't be
+    /// batched because the coordinates are rotated, or because it is translucent.
+    fn add_to_fill_batch(&mut self, rect: Rect, color: Color) -> bool {
+        let state = self.state.last().unwrap();
+        let (offset, clip) = match (state.offset, state.frame_clip(self.scale_factor)) {
+            (Some(offset), Some(clip)) => (offset, clip),
+            _ => return false,
+        };
+        let global_alpha = state.global_alpha;
+        if color.alpha() == 0 || global_alpha == 0.0 {
+            return true;
+        }
+        if !FillBatch::can_contain(color, global_alpha) {
+            return false;
         }
+        let compatible = self.fill_batch.as_ref().map_or(false, |batch| {
+            batch.color == color && batch.clip == clip && batch.global_alpha == global_alpha
+        });
+        if !compatible {
+            self.flush_fill_batch();
+            self.fill_batch = Some(FillBatch {
+                color,
+                path: femtovg::Path::new(),
+                offset,
+                clip,
+                global_alpha,
+                fills: 0,
+            });
+        }
+        let batch = self.fill_batch.as_mut().unwrap();
+        let origin = rect.origin + (offset - batch.offset);
+        batch.path.rect(origin.x, origin.y, rect.width(), rect.height());
+        batch.fills += 1;
+        true
+    }
+
+    /// Draws the pending batch of fills with a single fill of femtovg. This must be called before
+    /// anything else is drawn, and before the clip, the opacity or the render target change, so
+    /// that the fills are drawn in order and with their own clip.
+    pub fn flush_fill_batch(&mut self) {
+        let batch = match self.fill_batch.take() {
+            Some(batch) => batch,
+            None => return,
+        };
+        // A batch is flushed when the coordinates are rotated, so they are only translated here
+        let offset = self.state.last().unwrap().offset.unwrap_or_default();
+        let delta = batch.offset - offset;
+        let mut path = batch.path;
+        let mut canvas = self.canvas.borrow_mut();
+        canvas.save();
+        canvas.translate(delta.x, delta.y);
+        canvas.fill_path(
+            &mut path,
+            femtovg::Paint::color(to_femtovg_color(&batch.color)).with_anti_alias(false),
+        );
+        canvas.restore();
+        *self.metrics.batched_fills.get_or_insert(0) += batch.fills;
+        *self.metrics.fill_batches.get_or_insert(0) += 1;
     }
 
     fn render_layer(
@@ -921,8 +1190,9 @@ fn render_layer(
         item_rc: &ItemRc,
         layer_logical_size_fn: &dyn Fn() -> Size,
     ) -> Option<Rc<CachedImage>> {
+        self.flush_fill_batch();
         let cache_entry =
-            item_cache.get_or_update(&self.graphics_window.clone().graphics_cache, || {
+            item_cache.get_or_update(self.graphics_window.clone().item_graphics_cache(), || {
                 ItemGraphicsCacheEntry::Image({
                     let size = layer_logical_size_fn() * self.scale_factor;
 
@@ -959,6 +1229,8 @@ fn render_layer(
                         ),
                         global_alpha: 1.,
                         current_render_target: layer_image.as_render_target(),
+                        offset: Some(Point::default()),
+                        draws_frame: false,
                     };
 
                     i_slint_core::item_rendering::render_item_children(
@@ -966,6 +1238,7 @@ fn render_layer(
                         &item_rc.component(),
                         item_rc.index() as isize,
                     );
+                    self.flush_fill_batch();
 
                     {
                         let mut canvas = self.canvas.borrow_mut();
@@ -1042,7 +1315,7 @@ fn colorize_image(
             }
         };
 
-        let image_id = original_image.ensure_uploaded_to_gpu(self, Some(scaling));
+        original_image.ensure_uploaded_to_gpu(self, Some(scaling));
         let colorized_image = self
             .canvas
             .borrow_mut()
@@ -1071,15 +1344,7 @@ fn colorize_image(
             canvas.global_composite_operation(femtovg::CompositeOperation::Copy);
             canvas.fill_path(
                 &mut image_rect,
-                femtovg::Paint::image(
-                    image_id,
-                    0.,
-                    0.,
-                    image_size.width,
-                    image_size.height,
-                    0.,
-                    1.0,
-                ),
+                original_image.as_paint_at(0., 0., image_size.width, image_size.height, 1.0),
             );
 
             canvas.global_composite_operation(femtovg::CompositeOperation::SourceIn);
@@ -1094,6 +1359,24 @@ fn colorize_image(
         }
     }
 
+    /// Returns the texture with the frame of the video stream that the image shows, or None if the
+    /// image isn't a video stream or if its frames can't be converted on the GPU. The inner None
+    /// means that no frame was shown yet.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn video_frame_image(
+        &self,
+        source_property: std::pin::Pin<&Property<Image>>,
+        image_rendering: ImageRendering,
+    ) -> Option<Option<Rc<CachedImage>>> {
+        let stream = match (&source_property.get()).into() {
+            ImageInner::VideoStream(stream) => *stream,
+            _ => return None,
+        };
+        let mut texture_cache = self.graphics_window.texture_cache().borrow_mut();
+        let video_renderer = texture_cache.video_renderer()?;
+        Some(video_renderer.frame_image(&self.canvas, stream, image_rendering))
+    }
+
     fn draw_image_impl(
         &mut self,
         item_cache: &CachedRenderingData,
@@ -1112,78 +1395,90 @@ fn draw_image_impl(
             return;
         }
 
-        let cached_image = loop {
-            let image_cache_entry =
-                item_cache.get_or_update(&self.graphics_window.graphics_cache, || {
-                    let image = source_property.get();
-                    let image_inner: &ImageInner = (&image).into();
-
-                    let target_size_for_scalable_source = image_inner.is_svg().then(|| {
-                        // get the scale factor as a property again, to ensure the cache is invalidated when the scale factor changes
-                        let scale_factor = self.window().scale_factor();
-                        [
-                            (target_width.get() * scale_factor) as u32,
-                            (target_height.get() * scale_factor) as u32,
-                        ]
-                        .into()
+        #[cfg(not(target_arch = "wasm32"))]
+        let video_frame = self.video_frame_image(source_property, image_rendering);
+        #[cfg(target_arch = "wasm32")]
+        let video_frame = None;
+
+        let cached_image = match video_frame {
+            Some(Some(frame_image)) => frame_image,
+            Some(None) => return,
+            None => loop {
+                let image_cache_entry =
+                    item_cache.get_or_update(self.graphics_window.item_graphics_cache(), || {
+                        let image = source_property.get();
+                        let image_inner: &ImageInner = (&image).into();
+
+                        let target_size_for_scalable_source = image_inner.is_svg().then(|| {
+                            // get the scale factor as a property again, to ensure the cache is invalidated when the scale factor changes
+                            let scale_factor = self.window().scale_factor();
+                            [
+                                (target_width.get() * scale_factor) as u32,
+                                (target_height.get() * scale_factor) as u32,
+                            ]
+                            .into()
+                        });
+
+                        TextureCacheKey::new(
+                            image_inner,
+                            target_size_for_scalable_source,
+                            image_rendering,
+                        )
+                        .and_then(|cache_key| {
+                            self.graphics_window
+                                .texture_cache()
+                                .borrow_mut()
+                                .lookup_image_in_cache_or_create(cache_key, |atlas| {
+                                    crate::IMAGE_CACHE
+                                        .with(|global_cache| {
+                                            global_cache
+                                                .borrow_mut()
+                                                .load_image_resource(image_inner)
+                                        })
+                                        .and_then(|image| {
+                                            image
+                                                .upload_to_gpu(
+                                                    self, // The condition at the entry of the function ensures that width/height are positive
+                                                    atlas,
+                                                    target_size_for_scalable_source,
+                                                    image_rendering,
+                                                )
+                                                .map(Rc::new)
+                                        })
+                                })
+                        })
+                        .or_else(|| CachedImage::new_from_resource(image_inner).map(Rc::new))
+                        .map(ItemGraphicsCacheEntry::Image)
+                        .map(|cache_entry| {
+                            self.colorize_image(cache_entry, colorize_property, image_rendering)
+                        })
                     });
 
-                    TextureCacheKey::new(
-                        image_inner,
-                        target_size_for_scalable_source,
-                        image_rendering,
-                    )
-                    .and_then(|cache_key| {
-                        self.graphics_window
-                            .texture_cache
-                            .borrow_mut()
-                            .lookup_image_in_cache_or_create(cache_key, || {
-                                crate::IMAGE_CACHE
-                                    .with(|global_cache| {
-                                        global_cache.borrow_mut().load_image_resource(image_inner)
-                                    })
-                                    .and_then(|image| {
-                                        image
-                                            .upload_to_gpu(
-                                                self, // The condition at the entry of the function ensures that width/height are positive
-                                                target_size_for_scalable_source,
-                                                image_rendering,
-                                            )
-                                            .map(Rc::new)
-                                    })
-                            })
-                    })
-                    .or_else(|| CachedImage::new_from_resource(image_inner).map(Rc::new))
-                    .map(ItemGraphicsCacheEntry::Image)
-                    .map(|cache_entry| {
-                        self.colorize_image(cache_entry, colorize_property, image_rendering)
-                    })
-                });
+                // Check if the image in the cache is loaded. If not, don't draw any image and we'll return
+                // later when the callback from load_html_image has issued a repaint
+                let cached_image = match image_cache_entry {
+                    Some(entry) if entry.as_image().size().is_some() => entry,
+                    _ => {
+                        return;
+                    }
+                };
 
-            // Check if the image in the cache is loaded. If not, don't draw any image and we'll return
-            // later when the callback from load_html_image has issued a repaint
-            let cached_image = match image_cache_entry {
-                Some(entry) if entry.as_image().size().is_some() => entry,
-                _ => {
-                    return;
+                // It's possible that our item cache contains an image but it's not colorized yet because it was only
+                // placed there via the `image_size` function (which doesn't colorize). So we may have to invalidate our
+                // item cache and try again.
+                if colorize_property.map_or(false, |prop| !prop.get().is_transparent())
+                    && !cached_image.is_colorized_image()
+                {
+                    let mut cache = self.graphics_window.item_graphics_cache().borrow_mut();
+                    item_cache.release(&mut cache);
+                    continue;
                 }
-            };
-
-            // It's possible that our item cache contains an image but it's not colorized yet because it was only
-            // placed there via the `image_size` function (which doesn't colorize). So we may have to invalidate our
-            // item cache and try again.
-            if colorize_property.map_or(false, |prop| !prop.get().is_transparent())
-                && !cached_image.is_colorized_image()
-            {
-                let mut cache = self.graphics_window.graphics_cache.borrow_mut();
-                item_cache.release(&mut cache);
-                continue;
-            }
 
-            break cached_image.as_image().clone();
+                break cached_image.as_image().clone();
+            },
         };
 
-        let image_id = cached_image.ensure_uploaded_to_gpu(self, Some(image_rendering));
+        cached_image.ensure_uploaded_to_gpu(self, Some(image_rendering));
         let image_size = cached_image.size().unwrap_or_default().cast();
 
         let (source_width, source_height) = if source_clip_rect.is_empty() {
@@ -1227,18 +1522,11 @@ fn draw_image_impl(
             }
         };
 
-        let fill_paint = femtovg::Paint::image(
-            image_id,
-            -source_x,
-            -source_y,
-            image_size.width,
-            image_size.height,
-            0.0,
-            1.0,
-        )
-        // We preserve the rectangular shape of the image, so there's no need to apply anti-aliasing
-        // at the edges
-        .with_anti_alias(false);
+        let fill_paint = cached_image
+            .as_paint_at(-source_x, -source_y, image_size.width, image_size.height, 1.0)
+            // We preserve the rectangular shape of the image, so there's no need to apply anti-aliasing
+            // at the edges
+            .with_anti_alias(false);
 
         let mut path = femtovg::Path::new();
         path.rect(0., 0., source_width, source_height);
@@ -1252,6 +1540,62 @@ fn draw_image_impl(
         })
     }
 
+    /// Draws a rectangle with rounded corners and a border without tessellating its corners, which is
+    /// expensive for the many cards and buttons of a scene on embedded GPUs. Returns false when the
+    /// rectangle must be drawn with paths instead: with gradients, or with a border around a
+    /// translucent background, which would require filling a ring.
+    ///
+    /// The result matches the stroked path: the border is entirely inside `geometry`, and the radius
+    /// is the one of the middle of the border.
+    fn draw_border_rectangle_with_shader(
+        &mut self,
+        geometry: Rect,
+        radius: f32,
+        border_width: f32,
+        background: Brush,
+        border_color: Brush,
+    ) -> bool {
+        let solid_color = |brush: &Brush| match brush {
+            Brush::SolidColor(color) => Some(*color),
+            _ => None,
+        };
+        let (background, border_color) =
+            match (solid_color(&background), solid_color(&border_color)) {
+                (Some(background), Some(border_color)) => (background, border_color),
+                _ => return false,
+            };
+        let global_alpha = self.state.last().unwrap().global_alpha;
+        if global_alpha == 0.0 {
+            return true;
+        }
+
+        let border_width = border_width.max(0.).min(geometry.width().min(geometry.height()) / 2.);
+        let has_border = border_width > 0. && border_color.alpha() > 0;
+        if has_border && (background.alpha() < 255 || global_alpha < 1.) {
+            // The background is drawn on top of the border, which would show through it
+            return false;
+        }
+
+        // The radius of the path at the middle of the border, clamped like femtovg does
+        let middle = geometry.inflate(-border_width / 2., -border_width / 2.);
+        let radius = radius.max(0.).min(middle.width().min(middle.height()) / 2.);
+
+        let mut canvas = self.canvas.borrow_mut();
+        if has_border {
+            let outer_radius = if radius > 0. { radius + border_width / 2. } else { 0. };
+            fill_rounded_rect_with_shader(&mut canvas, geometry, outer_radius, border_color);
+            fill_rounded_rect_with_shader(
+                &mut canvas,
+                geometry.inflate(-border_width, -border_width),
+                (radius - border_width / 2.).max(0.),
+                background,
+            );
+        } else {
+            fill_rounded_rect_with_shader(&mut canvas, middle, radius, background);
+        }
+        true
+    }
+
     fn brush_to_paint(&self, brush: Brush, path: &mut femtovg::Path) -> Option<femtovg::Paint> {
         if brush.is_transparent() {
             return None;
@@ -1275,6 +1619,12 @@ fn brush_to_paint(&self, brush: Brush, path: &mut femtovg::Path) -> Option<femto
                 let start: Point = transform.transform_point(start);
                 let end: Point = transform.transform_point(end);
 
+                if let Some(bits) = crate::dither::gradient_dither_bits() {
+                    if let Some(paint) = self.dithered_gradient_paint(&gradient, start, end, bits) {
+                        return Some(paint);
+                    }
+                }
+
                 let stops = gradient
                     .stops()
                     .map(|stop| (stop.position, to_femtovg_color(&stop.color)))
@@ -1285,11 +1635,125 @@ fn brush_to_paint(&self, brush: Brush, path: &mut femtovg::Path) -> Option<femto
         })
     }
 
+    /// Returns an image pattern with the gradient dithered along the line from `start` to `end`.
+    fn dithered_gradient_paint(
+        &self,
+        gradient: &i_slint_core::graphics::LinearGradientBrush,
+        start: Point,
+        end: Point,
+        bits: u8,
+    ) -> Option<femtovg::Paint> {
+        let line = end - start;
+        let length = line.length();
+        // The texture has one pixel per pixel of the gradient line
+        let pixel_length = length.ceil() as usize;
+        if pixel_length == 0 {
+            return None;
+        }
+        let pattern = crate::dither::dithered_linear_gradient(gradient, pixel_length, bits);
+        let image_id = self
+            .canvas
+            .borrow_mut()
+            .create_image(
+                pattern.as_ref(),
+                femtovg::ImageFlags::NEAREST | femtovg::ImageFlags::REPEAT_Y,
+            )
+            .ok()?;
+        self.gradient_images_to_delete_after_flush
+            .borrow_mut()
+            .push(CachedImage::new_on_gpu(&self.canvas, image_id));
+        // The pattern is rotated around the start of the line, and the texture is clamped to its
+        // edge beyond the ends of the line, like the gradient
+        Some(femtovg::Paint::image(
+            image_id,
+            start.x,
+            start.y,
+            length,
+            crate::dither::PATTERN_SIZE as f32,
+            line.y.atan2(line.x),
+            1.0,
+        ))
+    }
+
     fn current_render_target(&self) -> femtovg::RenderTarget {
         self.state.last().unwrap().current_render_target
     }
 }
 
+/// With the deterministic rendering, moves the position of a line of text to a whole physical pixel
+/// of the frame, given the `offset` of the coordinates from the frame, so that the glyphs are
+/// rasterized the same way wherever the text is.
+fn snap_text_position(pos: Point, offset: Option<Point>) -> Point {
+    if !cfg!(feature = "deterministic-rendering") {
+        return pos;
+    }
+    match offset {
+        Some(offset) => (pos + offset.to_vector()).round() - offset.to_vector(),
+        // Rotated, so the pixels of the frame are not aligned with the coordinates
+        None => pos,
+    }
+}
+
+/// Fills `rect` with rounded corners of `radius`, by filling the plain rectangle with a box gradient
+/// from `color` to transparent over one pixel: the fragment shader of femtovg computes the coverage
+/// of each pixel from its signed distance to the rounded rectangle, so the corners are anti-aliased
+/// without tessellating a path.
+fn fill_rounded_rect_with_shader(canvas: &mut Canvas, rect: Rect, radius: f32, color: Color) {
+    if rect.is_empty() || color.alpha() == 0 {
+        return;
+    }
+    let transparent = femtovg::Color::rgba(color.red(), color.green(), color.blue(), 0);
+    let paint = femtovg::Paint::box_gradient(
+        rect.origin.x,
+        rect.origin.y,
+        rect.size.width,
+        rect.size.height,
+        radius,
+        1.,
+        to_femtovg_color(&color),
+        transparent,
+    )
+    // The edges of the rectangle are anti-aliased by the shader
+    .with_anti_alias(false);
+    canvas.fill_path(&mut rect_to_path(rect), paint);
+}
+
 pub fn to_femtovg_color(col: &Color) -> femtovg::Color {
     femtovg::Color::rgba(col.red(), col.green(), col.blue(), col.alpha())
 }
+
+/// Rotates the canvas, whose size is `size`, so that the scene is drawn with the given rotation,
+/// and returns the size of the scene.
+pub(crate) fn apply_rendering_rotation(
+    canvas: &mut Canvas,
+    rotation: RenderingRotation,
+    size: winit::dpi::PhysicalSize<u32>,
+) -> winit::dpi::PhysicalSize<u32> {
+    let (width, height) = (size.width as f32, size.height as f32);
+    match rotation {
+        RenderingRotation::NoRotation => return size,
+        RenderingRotation::Rotate90 => canvas.translate(width, 0.),
+        RenderingRotation::Rotate180 => canvas.translate(width, height),
+        RenderingRotation::Rotate270 => canvas.translate(0., height),
+    }
+    canvas.rotate((rotation.degrees() as f32).to_radians());
+    if rotation.is_transpose() {
+        winit::dpi::PhysicalSize::new(size.height, size.width)
+    } else {
+        size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overlapping_translucent_rectangles_are_not_batched() {
+        // Where two translucent rectangles overlap, the second one is blended over the first one,
+        // which a single fill of both doesn't do
+        assert!(!FillBatch::can_contain(Color::from_argb_u8(128, 255, 0, 0), 1.0));
+        assert!(!FillBatch::can_contain(Color::from_argb_u8(255, 255, 0, 0), 0.5));
+        assert!(FillBatch::can_contain(Color::from_argb_u8(255, 255, 0, 0), 1.0));
+    }
+}
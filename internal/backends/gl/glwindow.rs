@@ -9,10 +9,10 @@
 use core::pin::Pin;
 use std::rc::{Rc, Weak};
 
-use super::TextureCache;
+use super::{CachedImage, TextureCache};
 use crate::event_loop::WinitWindow;
 use crate::glcontext::OpenGLContext;
-use crate::glrenderer::{CanvasRc, ItemGraphicsCache};
+use crate::glrenderer::{CanvasRc, GraphicsWindow, ItemGraphicsCache};
 use const_field_offset::FieldOffsets;
 use corelib::api::{GraphicsAPI, RenderingNotifier, RenderingState, SetRenderingNotifierError};
 use corelib::component::ComponentRc;
@@ -20,6 +20,7 @@
 use corelib::input::KeyboardModifiers;
 use corelib::items::{ItemRef, MouseCursor};
 use corelib::layout::Orientation;
+use corelib::properties::PropertyTracker;
 use corelib::window::{PlatformWindow, PopupWindow, PopupWindowLocation};
 use corelib::Property;
 use corelib::{graphics::*, Coord};
@@ -28,6 +29,16 @@
 
 pub const PASSWORD_CHARACTER: &str = "●";
 
+/// Returns the scale factor set with the `SLINT_SCALE_FACTOR` environment variable, or 1 with the
+/// `deterministic-rendering` feature. When it's set, the scale factor of the windowing system is ignored.
+pub(crate) fn scale_factor_override_from_env() -> Option<f64> {
+    std::env::var("SLINT_SCALE_FACTOR")
+        .ok()
+        .and_then(|x| x.parse::<f64>().ok())
+        .filter(|f| *f > 0.)
+        .or(cfg!(feature = "deterministic-rendering").then(|| 1.))
+}
+
 /// GraphicsWindow is an implementation of the [PlatformWindow][`crate::eventloop::PlatformWindow`] trait. This is
 /// typically instantiated by entry factory functions of the different graphics back ends.
 pub struct GLWindow {
@@ -35,6 +46,7 @@ pub struct GLWindow {
     map_state: RefCell<GraphicsWindowBackendState>,
     keyboard_modifiers: std::cell::Cell<KeyboardModifiers>,
     currently_pressed_key_code: std::cell::Cell<Option<winit::event::VirtualKeyCode>>,
+    pressed_keys: RefCell<crate::event_loop::PressedKeys>,
 
     pub(crate) graphics_cache: RefCell<ItemGraphicsCache>,
     // This cache only contains textures. The cache for decoded CPU side images is in crate::IMAGE_CACHE.
@@ -43,6 +55,14 @@ pub struct GLWindow {
     rendering_metrics_collector: Option<Rc<RenderingMetricsCollector>>,
 
     rendering_notifier: RefCell<Option<Box<dyn RenderingNotifier>>>,
+    rendering_rotation: Cell<RenderingRotation>,
+
+    /// True when the whole frame must be drawn again, false when only the text cursor blinked
+    full_redraw_pending: Cell<bool>,
+    /// Tracks the visibility of the text cursor of the focused text input, see [`Self::keeps_frame()`]
+    text_cursor_tracker: once_cell::unsync::OnceCell<Pin<Box<PropertyTracker<Box<dyn Fn()>>>>>,
+    /// The area of the frame, in physical pixels, covered by the tracked text cursor
+    text_cursor_rect: Cell<Option<Rect>>,
 
     #[cfg(target_arch = "wasm32")]
     canvas_id: String,
@@ -62,20 +82,49 @@ pub(crate) fn new(
         window_weak: &Weak<corelib::window::Window>,
         #[cfg(target_arch = "wasm32")] canvas_id: String,
     ) -> Rc<Self> {
-        Rc::new(Self {
+        let window = Rc::new(Self {
             self_weak: window_weak.clone(),
             map_state: RefCell::new(GraphicsWindowBackendState::Unmapped),
             keyboard_modifiers: Default::default(),
             currently_pressed_key_code: Default::default(),
+            pressed_keys: Default::default(),
             graphics_cache: Default::default(),
             texture_cache: Default::default(),
             rendering_metrics_collector: RenderingMetricsCollector::new(window_weak.clone()),
             rendering_notifier: Default::default(),
+            rendering_rotation: Cell::new(
+                std::env::var("SLINT_ROTATION")
+                    .ok()
+                    .and_then(|x| x.parse().ok())
+                    .and_then(RenderingRotation::from_degrees)
+                    .unwrap_or_default(),
+            ),
+            full_redraw_pending: Cell::new(true),
+            text_cursor_tracker: Default::default(),
+            text_cursor_rect: Default::default(),
             #[cfg(target_arch = "wasm32")]
             canvas_id,
             #[cfg(target_arch = "wasm32")]
             virtual_keyboard_helper: Default::default(),
-        })
+        });
+
+        // The blinking of the text cursor only redraws its area, without requesting a full redraw
+        let self_weak = Rc::downgrade(&window);
+        let text_cursor_tracker: Box<dyn Fn()> = Box::new(move || {
+            if let Some(mapped_window) =
+                self_weak.upgrade().as_ref().and_then(|window| window.borrow_mapped_window())
+            {
+                mapped_window.opengl_context.window().request_redraw();
+            }
+        });
+        // We need to use a OnceCell only so we can have a cycle with the Weak.
+        window
+            .text_cursor_tracker
+            .set(Box::pin(PropertyTracker::new_with_change_handler(text_cursor_tracker)))
+            .ok()
+            .unwrap();
+
+        window
     }
 
     fn with_current_context<T>(&self, cb: impl FnOnce(&OpenGLContext) -> T) -> Option<T> {
@@ -152,6 +201,33 @@ fn invoke_rendering_notifier(&self, state: RenderingState, opengl_context: &Open
     fn has_rendering_notifier(&self) -> bool {
         self.rendering_notifier.borrow().is_some()
     }
+
+    /// Returns true if the frame is drawn into an image that's kept, so that only the area of the
+    /// text cursor is drawn again when it blinks. The rendering notifier draws on the screen
+    /// directly, and the rotated coordinates aren't tracked, so these always draw the whole frame.
+    fn keeps_frame(&self) -> bool {
+        !self.has_rendering_notifier()
+            && self.rendering_rotation.get() == RenderingRotation::NoRotation
+            && self.rendering_metrics_collector.is_none()
+    }
+}
+
+impl GraphicsWindow for GLWindow {
+    fn item_graphics_cache(&self) -> &RefCell<ItemGraphicsCache> {
+        &self.graphics_cache
+    }
+
+    fn texture_cache(&self) -> &RefCell<TextureCache> {
+        &self.texture_cache
+    }
+
+    fn window(&self) -> corelib::window::WindowRc {
+        self.self_weak.upgrade().unwrap()
+    }
+
+    fn text_cursor_tracker(&self) -> Option<Pin<&PropertyTracker<Box<dyn Fn()>>>> {
+        self.keeps_frame().then(|| self.text_cursor_tracker.get()).flatten().map(|t| t.as_ref())
+    }
 }
 
 impl WinitWindow for GLWindow {
@@ -163,15 +239,38 @@ fn currently_pressed_key_code(&self) -> &Cell<Option<winit::event::VirtualKeyCod
         &self.currently_pressed_key_code
     }
 
+    fn pressed_keys(&self) -> &RefCell<crate::event_loop::PressedKeys> {
+        &self.pressed_keys
+    }
+
     fn current_keyboard_modifiers(&self) -> &Cell<KeyboardModifiers> {
         &self.keyboard_modifiers
     }
 
+    fn rendering_rotation(&self) -> RenderingRotation {
+        self.rendering_rotation.get()
+    }
+
     /// Draw the items of the specified `component` in the given window.
     fn draw(self: Rc<Self>) {
         let runtime_window = self.self_weak.upgrade().unwrap();
         let scale_factor = runtime_window.scale_factor();
-        runtime_window.clone().draw_contents(|components| {
+        let text_scale_factor = runtime_window.text_scale_factor();
+        let mut presented = false;
+
+        // The region of the kept frame that's drawn again, in physical pixels: None for the whole
+        // frame, or the area of the text cursor when only the cursor blinked. When the region is
+        // empty, the kept frame is only presented again.
+        let mut region = if self.full_redraw_pending.take() || !self.keeps_frame() {
+            None
+        } else if self.text_cursor_tracker().map_or(false, |tracker| tracker.is_dirty()) {
+            self.text_cursor_rect.get()
+        } else {
+            Some(Rect::default())
+        };
+        let draws_whole_frame = region.is_none();
+
+        let draw_fn = |components: &[(&ComponentRc, Point)]| {
             let window = match self.borrow_mapped_window() {
                 Some(window) => window,
                 None => return, // caller bug, doesn't make sense to call draw() when not mapped
@@ -182,18 +281,50 @@ fn draw(self: Rc<Self>) {
             window.opengl_context.make_current();
             window.opengl_context.ensure_resized();
 
+            let frame_image = if self.keeps_frame() {
+                let mut frame_image = window.frame_image.borrow_mut();
+                if frame_image.as_ref().and_then(|image| image.size())
+                    != Some(IntSize::new(size.width, size.height))
+                {
+                    *frame_image = CachedImage::new_empty_on_gpu(
+                        window.canvas.as_ref().unwrap(),
+                        size.width,
+                        size.height,
+                    )
+                    .map(Rc::new);
+                    region = None;
+                }
+                frame_image.clone()
+            } else {
+                None
+            };
+            if frame_image.is_none() {
+                region = None;
+            }
+
             {
                 let mut canvas = window.canvas.as_ref().unwrap().borrow_mut();
                 // We pass 1.0 as dpi / device pixel ratio as femtovg only uses this factor to scale
                 // text metrics. Since we do the entire translation from logical pixels to physical
                 // pixels on our end, we don't need femtovg to scale a second time.
                 canvas.set_size(size.width, size.height, 1.0);
-                canvas.clear_rect(
-                    0,
-                    0,
-                    size.width,
-                    size.height,
-                    crate::glrenderer::to_femtovg_color(&window.clear_color),
+                if let Some(frame_image) = &frame_image {
+                    canvas.set_render_target(frame_image.as_render_target());
+                }
+                if region.is_none() {
+                    canvas.clear_rect(
+                        0,
+                        0,
+                        size.width,
+                        size.height,
+                        crate::glrenderer::to_femtovg_color(&window.clear_color),
+                    );
+                }
+                window.preloaded_glyphs.rasterize(
+                    &mut canvas,
+                    scale_factor,
+                    text_scale_factor,
+                    &runtime_window.default_font_properties(),
                 );
                 // For the BeforeRendering rendering notifier callback it's important that this happens *after* clearing
                 // the back buffer, in order to allow the callback to provide its own rendering of the background.
@@ -209,26 +340,91 @@ fn draw(self: Rc<Self>) {
                 }
             }
 
+            let scene_size = {
+                let mut canvas = window.canvas.as_ref().unwrap().borrow_mut();
+                canvas.save();
+                if let Some(region) = region {
+                    // Only the region is drawn again, over the kept frame
+                    canvas.scissor(region.min_x(), region.min_y(), region.width(), region.height());
+                    canvas.global_composite_operation(femtovg::CompositeOperation::Copy);
+                    canvas.fill_path(
+                        &mut crate::glrenderer::rect_to_path(region),
+                        femtovg::Paint::color(crate::glrenderer::to_femtovg_color(
+                            &window.clear_color,
+                        ))
+                        .with_anti_alias(false),
+                    );
+                    canvas.global_composite_operation(femtovg::CompositeOperation::SourceOver);
+                }
+                crate::glrenderer::apply_rendering_rotation(
+                    &mut canvas,
+                    self.rendering_rotation.get(),
+                    size,
+                )
+            };
+
             let mut renderer = crate::glrenderer::GLItemRenderer::new(
                 window.canvas.as_ref().unwrap().clone(),
                 self.clone(),
                 scale_factor,
-                size,
+                text_scale_factor,
+                scene_size,
             );
+            if let Some(frame_image) = &frame_image {
+                renderer.set_render_target(frame_image.as_render_target());
+            }
+            if let Some(region) = region {
+                renderer.restrict_to_region(region);
+            }
+
+            if region.map_or(true, |region| !region.is_empty()) {
+                if let Some(tracker) = self.text_cursor_tracker() {
+                    // Forget the cursor of a text input that isn't drawn anymore
+                    tracker.evaluate_as_dependency_root(|| ());
+                }
 
-            for (component, origin) in components {
-                corelib::item_rendering::render_component_items(component, &mut renderer, *origin);
+                for (component, origin) in components {
+                    corelib::item_rendering::render_component_items(
+                        component,
+                        &mut renderer,
+                        *origin,
+                    );
+                }
+                renderer.flush_fill_batch();
+
+                if frame_image.is_some() {
+                    self.text_cursor_rect.set(renderer.text_cursor_rect);
+                    if region.is_some() && renderer.text_cursor_rect.is_none() {
+                        // The cursor isn't in the region anymore, so track it again with a full redraw
+                        self.full_redraw_pending.set(true);
+                        window.opengl_context.window().request_redraw();
+                    }
+                }
             }
 
             if let Some(collector) = &self.rendering_metrics_collector {
                 collector.measure_frame_rendered(&mut renderer);
             }
 
+            renderer.canvas.borrow_mut().restore();
+
+            if let Some(frame_image) = &frame_image {
+                let mut canvas = renderer.canvas.borrow_mut();
+                canvas.set_render_target(femtovg::RenderTarget::Screen);
+                canvas.save_with(|canvas| {
+                    canvas.reset();
+                    canvas.global_composite_operation(femtovg::CompositeOperation::Copy);
+                    let mut frame_path = femtovg::Path::new();
+                    frame_path.rect(0., 0., size.width as f32, size.height as f32);
+                    canvas.fill_path(&mut frame_path, frame_image.as_paint());
+                });
+            }
+
             renderer.canvas.borrow_mut().flush();
 
             // Delete any images and layer images (and their FBOs) before making the context not current anymore, to
             // avoid GPU memory leaks.
-            renderer.graphics_window.texture_cache.borrow_mut().drain();
+            self.texture_cache.borrow_mut().drain();
 
             drop(renderer);
 
@@ -236,7 +432,29 @@ fn draw(self: Rc<Self>) {
 
             window.opengl_context.swap_buffers();
             window.opengl_context.make_not_current();
-        });
+            presented = true;
+        };
+
+        if draws_whole_frame {
+            runtime_window.clone().draw_contents(draw_fn);
+        } else {
+            // The dependencies of the whole frame are kept from the last time it was drawn
+            runtime_window.clone().draw_contents_untracked(draw_fn);
+        }
+
+        if presented && runtime_window.first_frame_pending() {
+            if runtime_window.show_after_first_frame() {
+                if let Some(window) = self.borrow_mapped_window() {
+                    window.opengl_context.window().set_visible(true);
+                }
+            }
+            runtime_window.frame_presented();
+        }
+
+        #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+        if let Some(window) = self.borrow_mapped_window() {
+            window.accessibility.update_if_dirty();
+        }
     }
 
     fn with_window_handle(&self, callback: &mut dyn FnMut(&winit::window::Window)) {
@@ -262,6 +480,7 @@ fn set_background_color(&self, color: Color) {
         if let Some(mut window) = self.borrow_mapped_window_mut() {
             window.clear_color = color;
         }
+        self.full_redraw_pending.set(true);
     }
 
     fn set_icon(&self, icon: corelib::graphics::Image) {
@@ -278,7 +497,19 @@ fn set_icon(&self, icon: corelib::graphics::Image) {
         };
     }
 
+    #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+    fn handle_accessibility_action(&self, request: accesskit::ActionRequest) {
+        // Don't keep the window borrowed while invoking the callback, which may hide the window
+        let item_and_action = self
+            .borrow_mapped_window()
+            .and_then(|window| window.accessibility.resolve_action_request(request));
+        if let Some((item, action)) = item_and_action {
+            item.accessibility_action(&action);
+        }
+    }
+
     #[cfg(target_arch = "wasm32")]
+
     fn input_method_focused(&self) -> bool {
         match self.virtual_keyboard_helper.try_borrow() {
             Ok(vkh) => vkh.as_ref().map_or(false, |h| h.has_focus()),
@@ -291,6 +522,7 @@ fn input_method_focused(&self) -> bool {
 
 impl PlatformWindow for GLWindow {
     fn request_redraw(&self) {
+        self.full_redraw_pending.set(true);
         match &*self.map_state.borrow() {
             GraphicsWindowBackendState::Unmapped => {}
             GraphicsWindowBackendState::Mapped(window) => {
@@ -418,10 +650,7 @@ fn show(self: Rc<Self>) {
         let scale_factor_override = if scale_factor_override > 1. {
             Some(scale_factor_override as f64)
         } else {
-            std::env::var("SLINT_SCALE_FACTOR")
-                .ok()
-                .and_then(|x| x.parse::<f64>().ok())
-                .filter(|f| *f > 0.)
+            scale_factor_override_from_env()
         };
 
         let window_builder = if std::env::var("SLINT_FULLSCREEN").is_ok() {
@@ -438,6 +667,7 @@ fn show(self: Rc<Self>) {
                 // Make sure that the window's inner size is in sync with the root window item's
                 // width/height.
                 runtime_window.set_window_item_geometry(s.width, s.height);
+                let s = crate::event_loop::rotate_size(self.rendering_rotation.get(), s);
                 if let Some(f) = scale_factor_override {
                     window_builder.with_inner_size(s.to_physical::<f32>(f))
                 } else {
@@ -451,6 +681,18 @@ fn show(self: Rc<Self>) {
         let window_builder =
             if no_frame { window_builder.with_decorations(false) } else { window_builder };
 
+        // The window stays invisible until its first frame is drawn, if requested
+        let show_after_first_frame = runtime_window.show_after_first_frame();
+        let window_builder = if show_after_first_frame {
+            window_builder.with_visible(false)
+        } else {
+            window_builder
+        };
+
+        // The accessibility adapter must be created before the window is shown
+        #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+        let window_builder = window_builder.with_visible(false);
+
         #[cfg(target_arch = "wasm32")]
         let (opengl_context, renderer) =
             crate::OpenGLContext::new_context_and_renderer(window_builder, &self.canvas_id);
@@ -464,6 +706,11 @@ fn show(self: Rc<Self>) {
         )
         .unwrap();
 
+        #[cfg(not(target_arch = "wasm32"))]
+        self.texture_cache
+            .borrow_mut()
+            .init_video_renderer(|name| opengl_context.get_proc_address(name));
+
         self.invoke_rendering_notifier(RenderingState::RenderingSetup, &opengl_context);
 
         opengl_context.make_not_current();
@@ -514,14 +761,48 @@ fn show(self: Rc<Self>) {
 
         drop(platform_window);
 
+        #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+        let accessibility = {
+            let self_weak = Rc::downgrade(&self);
+            crate::accessibility::AccessKitAdapter::new(
+                &*opengl_context.window(),
+                self.self_weak.clone(),
+                Box::new(move || {
+                    if let Some(window) = self_weak.upgrade() {
+                        window.request_redraw();
+                    }
+                }),
+            )
+        };
+
         self.map_state.replace(GraphicsWindowBackendState::Mapped(MappedWindow {
+            #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+            accessibility,
             canvas: Some(canvas),
+            frame_image: Default::default(),
             opengl_context,
             clear_color: RgbaColor { red: 255_u8, green: 255, blue: 255, alpha: 255 }.into(),
             constraints: Default::default(),
+            preloaded_glyphs: Default::default(),
         }));
 
+        #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+        if !show_after_first_frame {
+            if let Some(window) = self.borrow_mapped_window() {
+                window.opengl_context.window().set_visible(true);
+            }
+        }
+
         crate::event_loop::register_window(id, self);
+
+        if show_after_first_frame {
+            crate::event_loop::with_window_target(|event_loop| {
+                event_loop
+                    .event_loop_proxy()
+                    .send_event(crate::event_loop::CustomEvent::DrawFirstFrame(id))
+            })
+            .unwrap();
+        }
     }
 
     fn hide(self: Rc<Self>) {
@@ -585,83 +866,61 @@ fn text_size(
     ) -> Size {
         let font_request = font_request.merge(&self.default_font_properties());
 
+        let runtime_window = self.self_weak.upgrade().unwrap();
         crate::fonts::text_size(
             &font_request,
-            self.self_weak.upgrade().unwrap().scale_factor(),
+            runtime_window.scale_factor(),
+            runtime_window.text_scale_factor(),
             text,
             max_width,
         )
     }
 
-    fn text_input_byte_offset_for_position(
+    fn text_min_content_width(
         &self,
-        text_input: Pin<&i_slint_core::items::TextInput>,
-        pos: Point,
-    ) -> usize {
-        let scale_factor = self.self_weak.upgrade().unwrap().scale_factor();
-        let pos = pos * scale_factor;
-        let text = text_input.text();
-
-        let mut result = text.len();
-
-        let width = text_input.width() * scale_factor;
-        let height = text_input.height() * scale_factor;
-        if width <= 0. || height <= 0. || pos.y < 0. {
-            return 0;
-        }
+        font_request: corelib::graphics::FontRequest,
+        text: &str,
+    ) -> Coord {
+        let font_request = font_request.merge(&self.default_font_properties());
 
-        let font = crate::fonts::FONT_CACHE.with(|cache| {
-            cache.borrow_mut().font(
-                text_input.unresolved_font_request().merge(&self.default_font_properties()),
-                scale_factor,
-                &text_input.text(),
-            )
-        });
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        crate::fonts::text_min_content_width(
+            &font_request,
+            runtime_window.scale_factor(),
+            runtime_window.text_scale_factor(),
+            text,
+        )
+    }
 
-        let is_password = matches!(text_input.input_type(), corelib::items::InputType::password);
-        let password_string;
-        let actual_text = if is_password {
-            password_string = PASSWORD_CHARACTER.repeat(text.chars().count());
-            password_string.as_str()
-        } else {
-            text.as_str()
-        };
+    fn text_outline(
+        &self,
+        font_request: corelib::graphics::FontRequest,
+        text: &str,
+    ) -> Option<lyon_path::Path> {
+        let font_request = font_request.merge(&self.default_font_properties());
 
-        let paint = font.init_paint(text_input.letter_spacing() * scale_factor, Default::default());
-        let text_context =
-            crate::fonts::FONT_CACHE.with(|cache| cache.borrow().text_context.clone());
-        let font_height = text_context.measure_font(paint).unwrap().height();
-        crate::fonts::layout_text_lines(
-            actual_text,
-            &font,
-            Size::new(width, height),
-            (text_input.horizontal_alignment(), text_input.vertical_alignment()),
-            text_input.wrap(),
-            i_slint_core::items::TextOverflow::clip,
-            text_input.single_line(),
-            paint,
-            |line_text, line_pos, start, metrics| {
-                if (line_pos.y..(line_pos.y + font_height)).contains(&pos.y) {
-                    let mut current_x = 0.;
-                    for glyph in &metrics.glyphs {
-                        if line_pos.x + current_x + glyph.advance_x / 2. >= pos.x {
-                            result = start + glyph.byte_index;
-                            return;
-                        }
-                        current_x += glyph.advance_x;
-                    }
-                    result = start + line_text.trim_end().len();
-                }
-            },
-        );
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        Some(crate::fonts::text_outline(
+            &font_request,
+            runtime_window.scale_factor(),
+            runtime_window.text_scale_factor(),
+            text,
+        ))
+    }
 
-        if is_password {
-            text.char_indices()
-                .nth(result / PASSWORD_CHARACTER.len())
-                .map_or(text.len(), |(r, _)| r)
-        } else {
-            result
-        }
+    fn text_input_byte_offset_for_position(
+        &self,
+        text_input: Pin<&i_slint_core::items::TextInput>,
+        pos: Point,
+    ) -> usize {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        crate::fonts::text_input_byte_offset_for_position(
+            text_input,
+            pos,
+            text_input.unresolved_font_request().merge(&self.default_font_properties()),
+            runtime_window.scale_factor(),
+            runtime_window.text_scale_factor(),
+        )
     }
 
     fn text_input_cursor_rect_for_byte_offset(
@@ -669,57 +928,14 @@ fn text_input_cursor_rect_for_byte_offset(
         text_input: Pin<&corelib::items::TextInput>,
         byte_offset: usize,
     ) -> Rect {
-        let scale_factor = self.self_weak.upgrade().unwrap().scale_factor();
-        let text = text_input.text();
-
-        let font_size = text_input
-            .unresolved_font_request()
-            .merge(&self.default_font_properties())
-            .pixel_size
-            .unwrap_or(super::fonts::DEFAULT_FONT_SIZE);
-
-        let mut result = Point::default();
-
-        let width = text_input.width() * scale_factor;
-        let height = text_input.height() * scale_factor;
-        if width <= 0. || height <= 0. {
-            return Rect::new(result, Size::new(1.0, font_size));
-        }
-
-        let font = crate::fonts::FONT_CACHE.with(|cache| {
-            cache.borrow_mut().font(
-                text_input.unresolved_font_request().merge(&self.default_font_properties()),
-                scale_factor,
-                &text_input.text(),
-            )
-        });
-
-        let paint = font.init_paint(text_input.letter_spacing() * scale_factor, Default::default());
-        crate::fonts::layout_text_lines(
-            text.as_str(),
-            &font,
-            Size::new(width, height),
-            (text_input.horizontal_alignment(), text_input.vertical_alignment()),
-            text_input.wrap(),
-            i_slint_core::items::TextOverflow::clip,
-            text_input.single_line(),
-            paint,
-            |line_text, line_pos, start, metrics| {
-                if (start..=(start + line_text.len())).contains(&byte_offset) {
-                    for glyph in &metrics.glyphs {
-                        if glyph.byte_index == (byte_offset - start) {
-                            result = line_pos + euclid::vec2(glyph.x, 0.0);
-                            return;
-                        }
-                    }
-                    if let Some(last) = metrics.glyphs.last() {
-                        result = line_pos + euclid::vec2(last.x + last.advance_x, last.y);
-                    }
-                }
-            },
-        );
-
-        Rect::new(result / scale_factor, Size::new(1.0, font_size))
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        crate::fonts::text_input_cursor_rect_for_byte_offset(
+            text_input,
+            byte_offset,
+            text_input.unresolved_font_request().merge(&self.default_font_properties()),
+            runtime_window.scale_factor(),
+            runtime_window.text_scale_factor(),
+        )
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -740,6 +956,38 @@ fn hide_virtual_keyboard(&self) {
         }
     }
 
+    #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+    fn handle_focus_change(
+        &self,
+        _old: Option<corelib::items::ItemRc>,
+        new: Option<corelib::items::ItemRc>,
+    ) {
+        if let Some(window) = self.borrow_mapped_window() {
+            window.accessibility.handle_focus_change(new);
+        }
+    }
+
+    fn set_rendering_rotation(&self, rotation: RenderingRotation) {
+        if self.rendering_rotation.replace(rotation) == rotation {
+            return;
+        }
+        let runtime_window = self.runtime_window();
+        if let Some(window) = self.borrow_mapped_window() {
+            // Force apply_constraints to set the rotated constraints on the winit window
+            window.constraints.set(Default::default());
+            let size = crate::event_loop::rotate_size(
+                rotation,
+                window
+                    .opengl_context
+                    .window()
+                    .inner_size()
+                    .to_logical(runtime_window.scale_factor() as f64),
+            );
+            drop(window);
+            runtime_window.set_window_item_geometry(size.width, size.height);
+        }
+    }
+
     fn as_any(&self) -> &dyn std::any::Any {
         self
     }
@@ -752,14 +1000,24 @@ fn drop(&mut self) {
 }
 
 struct MappedWindow {
+    // Declared first so that the adapter is dropped before the window
+    #[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+    accessibility: crate::accessibility::AccessKitAdapter,
     canvas: Option<CanvasRc>,
+    /// The image the frame is drawn into, if [`GLWindow::keeps_frame()`]
+    frame_image: RefCell<Option<Rc<CachedImage>>>,
     opengl_context: crate::OpenGLContext,
     clear_color: Color,
     constraints: Cell<(corelib::layout::LayoutInfo, corelib::layout::LayoutInfo)>,
+    preloaded_glyphs: crate::fonts::PreloadedGlyphs,
 }
 
 impl Drop for MappedWindow {
     fn drop(&mut self) {
+        // The texture of the frame refers to the canvas, and must be deleted with the context current
+        let frame_image = self.frame_image.take();
+        self.opengl_context.with_current_context(|_| drop(frame_image));
+
         if let Some(canvas) = self.canvas.take().map(|canvas| Rc::try_unwrap(canvas).ok()) {
             // The canvas must be destructed with a GL context current, in order to clean up correctly
             self.opengl_context.with_current_context(|_| {
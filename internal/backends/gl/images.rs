@@ -10,6 +10,7 @@
 use i_slint_core::Property;
 use i_slint_core::{items::ImageRendering, slice::Slice, ImageInner, SharedString};
 
+use super::atlas::{AtlasRegion, ImageAtlas};
 use super::glrenderer::{CanvasRc, GLItemRenderer};
 
 struct Texture {
@@ -88,6 +89,8 @@ fn size(&self) -> Option<IntSize> {
 #[derive(derive_more::From)]
 enum ImageData {
     Texture(Texture),
+    /// A small image packed with others into a texture of the atlas
+    AtlasRegion(AtlasRegion),
     DecodedImage {
         image: image::DynamicImage,
         premultiplied_alpha: bool,
@@ -105,6 +108,9 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
             ImageData::Texture(t) => {
                 write!(f, "ImageData::Texture({:?})", t.id.0)
             }
+            ImageData::AtlasRegion(region) => {
+                write!(f, "ImageData::{:?}", region)
+            }
             ImageData::DecodedImage { image, premultiplied_alpha } => {
                 write!(
                     f,
@@ -175,6 +181,11 @@ pub fn new_from_resource(resource: &ImageInner) -> Option<Self> {
                 Some(Self(RefCell::new(ImageData::EmbeddedImage(buffer.clone()))))
             }
             ImageInner::StaticTextures { .. } => todo!(),
+            // Converted on the CPU when the frames can't be converted by the VideoRenderer
+            ImageInner::VideoStream(stream) => {
+                i_slint_core::graphics::shown_video_frame_to_rgb(*stream)
+                    .map(|buffer| Self(RefCell::new(ImageData::EmbeddedImage(buffer))))
+            }
         }
     }
 
@@ -290,6 +301,7 @@ pub fn ensure_uploaded_to_gpu(
 
         match &img {
             ImageData::Texture(Texture { id, .. }) => *id,
+            ImageData::AtlasRegion(region) => region.texture(),
             _ => unreachable!(),
         }
     }
@@ -297,9 +309,11 @@ pub fn ensure_uploaded_to_gpu(
     // Upload the image to the GPU. This function could take just a canvas as parameter,
     // but since an upload requires a current context, this is "enforced" by taking
     // a renderer instead (which implies a current context).
+    // Small images are packed into the atlas instead of getting their own texture.
     pub fn upload_to_gpu(
         &self,
         current_renderer: &GLItemRenderer,
+        atlas: &mut ImageAtlas,
         target_size_for_scalable_source: Option<euclid::default::Size2D<u32>>,
 
         scaling: ImageRendering,
@@ -312,8 +326,13 @@ pub fn upload_to_gpu(
         };
 
         match &*self.0.borrow() {
-            ImageData::Texture(_) => None, // internal error: Cannot call upload_to_gpu on previously uploaded image,
+            ImageData::Texture(_) | ImageData::AtlasRegion(_) => None, // internal error: Cannot call upload_to_gpu on previously uploaded image,
             ImageData::DecodedImage { image: decoded_image, premultiplied_alpha } => {
+                if let Some(region) =
+                    atlas.insert(canvas, decoded_image, *premultiplied_alpha, scaling)
+                {
+                    return Some(Self(RefCell::new(region.into())));
+                }
                 let image_id = match femtovg::ImageSource::try_from(&*decoded_image) {
                     Ok(image_source) => canvas.borrow_mut().create_image(image_source, image_flags),
                     Err(_) => {
@@ -348,7 +367,7 @@ pub fn upload_to_gpu(
                         // resvg creates images with pre-multipled alpha
                         true,
                     )
-                    .upload_to_gpu(current_renderer, None, scaling),
+                    .upload_to_gpu(current_renderer, atlas, None, scaling),
                     Err(err) => {
                         eprintln!("Error rendering SVG: {}", err);
                         None
@@ -377,6 +396,7 @@ pub fn size(&self) -> Option<IntSize> {
 
         match &*self.0.borrow() {
             ImageData::Texture(texture) => texture.size(),
+            ImageData::AtlasRegion(region) => Some(region.rect().size),
             ImageData::DecodedImage { image: decoded_image, .. } => {
                 Some(decoded_image.dimensions().into())
             }
@@ -393,6 +413,14 @@ pub fn size(&self) -> Option<IntSize> {
         }
     }
 
+    /// Returns the id of the texture, if the image has its own texture.
+    pub(crate) fn texture_id(&self) -> Option<femtovg::ImageId> {
+        match &*self.0.borrow() {
+            ImageData::Texture(tex) => Some(tex.id),
+            _ => None,
+        }
+    }
+
     pub(crate) fn as_render_target(&self) -> femtovg::RenderTarget {
         match &*self.0.borrow() {
             ImageData::Texture(tex) => femtovg::RenderTarget::Image(tex.id),
@@ -428,17 +456,39 @@ pub(crate) fn as_paint(&self) -> femtovg::Paint {
     }
 
     pub(crate) fn as_paint_with_alpha(&self, alpha_tint: f32) -> femtovg::Paint {
+        let size = self
+            .size()
+            .expect("internal error: CachedImage::as_paint() called on zero-sized texture");
+        self.as_paint_at(0., 0., size.width as f32, size.height as f32, alpha_tint)
+    }
+
+    /// Returns a paint that draws the uploaded image scaled to `width` x `height`, with its top-left
+    /// corner at `(x, y)`. For an image of the atlas, the paint covers the whole texture of the atlas,
+    /// which is offset so that the region of the image lands there. The path filled with it must not
+    /// exceed the image.
+    pub(crate) fn as_paint_at(
+        &self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        alpha_tint: f32,
+    ) -> femtovg::Paint {
         match &*self.0.borrow() {
             ImageData::Texture(tex) => {
-                let size = tex
-                    .size()
-                    .expect("internal error: CachedImage::as_paint() called on zero-sized texture");
+                femtovg::Paint::image(tex.id, x, y, width, height, 0., alpha_tint)
+            }
+            ImageData::AtlasRegion(region) => {
+                let rect = region.rect();
+                let texture_size = region.texture_size();
+                let scale_x = width / rect.width() as f32;
+                let scale_y = height / rect.height() as f32;
                 femtovg::Paint::image(
-                    tex.id,
-                    0.,
-                    0.,
-                    size.width as f32,
-                    size.height as f32,
+                    region.texture(),
+                    x - rect.min_x() as f32 * scale_x,
+                    y - rect.min_y() as f32 * scale_y,
+                    texture_size.width as f32 * scale_x,
+                    texture_size.height as f32 * scale_y,
                     0.,
                     alpha_tint,
                 )
@@ -448,7 +498,7 @@ pub(crate) fn as_paint_with_alpha(&self, alpha_tint: f32) -> femtovg::Paint {
     }
 
     pub(crate) fn is_on_gpu(&self) -> bool {
-        matches!(&*self.0.borrow(), ImageData::Texture(_))
+        matches!(&*self.0.borrow(), ImageData::Texture(_) | ImageData::AtlasRegion(_))
     }
 
     pub(crate) fn to_rgba(&self) -> Option<image::RgbaImage> {
@@ -481,6 +531,7 @@ pub fn new(resource: &ImageInner) -> Option<Self> {
             }
             ImageInner::EmbeddedImage { .. } => return None,
             ImageInner::StaticTextures { .. } => return None,
+            ImageInner::VideoStream(_) => return None,
         })
     }
 }
@@ -546,22 +597,29 @@ pub fn new(
 // Cache used to avoid repeatedly decoding images from disk. Entries with a count
 // of 1 are drained after flushing the renderer commands to the screen.
 #[derive(Default)]
-pub struct TextureCache(HashMap<TextureCacheKey, Rc<CachedImage>>);
+pub struct TextureCache {
+    images: HashMap<TextureCacheKey, Rc<CachedImage>>,
+    // The textures into which the small images are packed
+    atlas: ImageAtlas,
+    // Converts the frames of the video streams on the GPU, once the OpenGL context is known
+    #[cfg(not(target_arch = "wasm32"))]
+    video_renderer: Option<crate::video::VideoRenderer>,
+}
 
 impl TextureCache {
     // Look up the given image cache key in the image cache and upgrade the weak reference to a strong one if found,
-    // otherwise a new image is created/loaded from the given callback.
+    // otherwise a new image is created/loaded from the given callback, which may pack it into the atlas.
     pub(crate) fn lookup_image_in_cache_or_create(
         &mut self,
         cache_key: TextureCacheKey,
-        image_create_fn: impl Fn() -> Option<Rc<CachedImage>>,
+        image_create_fn: impl Fn(&mut ImageAtlas) -> Option<Rc<CachedImage>>,
     ) -> Option<Rc<CachedImage>> {
-        Some(match self.0.entry(cache_key) {
+        Some(match self.images.entry(cache_key) {
             std::collections::hash_map::Entry::Occupied(existing_entry) => {
                 existing_entry.get().clone()
             }
             std::collections::hash_map::Entry::Vacant(vacant_entry) => {
-                let new_image = image_create_fn()?;
+                let new_image = image_create_fn(&mut self.atlas)?;
                 debug_assert!(new_image.is_on_gpu());
                 vacant_entry.insert(new_image.clone());
                 new_image
@@ -570,7 +628,7 @@ pub(crate) fn lookup_image_in_cache_or_create(
     }
 
     pub(crate) fn drain(&mut self) {
-        self.0.retain(|_, cached_image| {
+        self.images.retain(|_, cached_image| {
             // * Retain images that are used by elements, so that they can be effectively
             // shared (one image element refers to foo.png, another element is created
             // and refers to the same -> share).
@@ -581,10 +639,38 @@ pub(crate) fn drain(&mut self) {
             // image again, etc.
             Rc::strong_count(cached_image) > 1 || cached_image.size().is_none()
         });
+        // The regions of the images dropped above are free now
+        self.atlas.drain();
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(video_renderer) = &mut self.video_renderer {
+            video_renderer.drain();
+        }
     }
 
     pub(crate) fn clear(&mut self) {
-        self.0.clear();
+        self.images.clear();
+        self.atlas.clear();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.video_renderer = None;
+        }
+    }
+
+    /// Creates the renderer of the video frames with the functions of the OpenGL context that is
+    /// current. Without it, the video frames are converted on the CPU.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn init_video_renderer(
+        &mut self,
+        get_proc_address: impl FnMut(&str) -> *const std::ffi::c_void,
+    ) {
+        self.video_renderer = crate::video::VideoRenderer::new(get_proc_address)
+            .map_err(|err| eprintln!("Error compiling the shader of the video frames: {}", err))
+            .ok();
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn video_renderer(&mut self) -> Option<&mut crate::video::VideoRenderer> {
+        self.video_renderer.as_mut()
     }
 }
 
@@ -9,16 +9,29 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
-use i_slint_core::graphics::{Image, IntSize};
+use i_slint_core::graphics::{FontRequest, Image, IntSize};
 use i_slint_core::window::Window;
 
 mod glwindow;
 use glwindow::*;
+#[cfg(all(feature = "accessibility", not(target_arch = "wasm32")))]
+mod accessibility;
 mod glcontext;
 use glcontext::*;
+#[cfg(not(target_arch = "wasm32"))]
+mod external_surface;
+#[cfg(not(target_arch = "wasm32"))]
+pub use external_surface::{GLRenderer, NativeOpenGLContext, OpenGLInterface, RenderTarget};
 pub(crate) mod event_loop;
+pub use event_loop::{remove_event_filter, set_event_filter, EventFilterResult};
+#[cfg(target_arch = "wasm32")]
+pub use fonts::{register_font_from_url, register_local_fonts};
+mod atlas;
+mod dither;
 mod images;
 mod svg;
+#[cfg(not(target_arch = "wasm32"))]
+mod video;
 #[cfg(target_arch = "wasm32")]
 pub(crate) mod wasm_input_helper;
 use images::*;
@@ -29,6 +42,10 @@
 
 mod glrenderer;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use raw_window_handle;
+pub use winit;
+
 #[cfg(target_arch = "wasm32")]
 pub fn create_gl_window_with_canvas_id(canvas_id: String) -> Rc<Window> {
     i_slint_core::window::Window::new(|window| GLWindow::new(window, canvas_id))
@@ -52,7 +69,9 @@ pub mod native_widgets {
 // TODO: We can't connect to the wayland clipboard yet because
 // it requires an external connection.
 cfg_if::cfg_if! {
-    if #[cfg(all(
+    if #[cfg(target_arch = "wasm32")] {
+        // The clipboard is accessed with the APIs of the browser, see wasm_input_helper
+    } else if #[cfg(all(
              unix,
              not(any(
                  target_os = "macos",
@@ -68,6 +87,7 @@ pub mod native_widgets {
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
 thread_local!(pub(crate) static CLIPBOARD : RefCell<ClipboardBackend> = std::cell::RefCell::new(ClipboardBackend::new().unwrap()));
 
 thread_local!(pub(crate) static IMAGE_CACHE: RefCell<images::ImageCache> = Default::default());
@@ -75,6 +95,12 @@ pub mod native_widgets {
 pub struct Backend;
 impl i_slint_core::backend::Backend for Backend {
     fn create_window(&'static self) -> Rc<Window> {
+        // Report the color scheme of the system once, later changes are reported by
+        // the ThemeChanged event of winit.
+        static DETECT_COLOR_SCHEME: std::sync::Once = std::sync::Once::new();
+        DETECT_COLOR_SCHEME.call_once(|| {
+            i_slint_core::color_scheme::set_color_scheme(stylemetrics::detect_color_scheme())
+        });
         i_slint_core::window::Window::new(|window| {
             GLWindow::new(
                 window,
@@ -88,6 +114,10 @@ fn run_event_loop(&'static self, behavior: i_slint_core::backend::EventLoopQuitB
         crate::event_loop::run(behavior);
     }
 
+    fn cursor_blink_interval(&'static self) -> Option<core::time::Duration> {
+        stylemetrics::cursor_blink_interval()
+    }
+
     fn quit_event_loop(&'static self) {
         crate::event_loop::with_window_target(|event_loop| {
             event_loop.event_loop_proxy().send_event(crate::event_loop::CustomEvent::Exit).ok();
@@ -101,6 +131,10 @@ fn register_font_from_memory(
         self::fonts::register_font_from_memory(data)
     }
 
+    fn preload_glyphs(&'static self, font_request: FontRequest, charset: &str) {
+        self::fonts::preload_glyphs(font_request, charset)
+    }
+
     fn register_font_from_path(
         &'static self,
         path: &std::path::Path,
@@ -108,16 +142,30 @@ fn register_font_from_path(
         self::fonts::register_font_from_path(path)
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     fn set_clipboard_text(&'static self, text: String) {
         use copypasta::ClipboardProvider;
         CLIPBOARD.with(|clipboard| clipboard.borrow_mut().set_contents(text).ok());
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     fn clipboard_text(&'static self) -> Option<String> {
         use copypasta::ClipboardProvider;
         CLIPBOARD.with(|clipboard| clipboard.borrow_mut().get_contents().ok())
     }
 
+    #[cfg(target_arch = "wasm32")]
+    fn set_clipboard_text(&'static self, text: String) {
+        wasm_input_helper::write_clipboard_text(&text);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn clipboard_text(&'static self) -> Option<String> {
+        // The browser only gives the text of the clipboard asynchronously, with the "paste" event
+        // that the input helper handles.
+        None
+    }
+
     fn post_event(&'static self, event: Box<dyn FnOnce() + Send>) {
         let e = crate::event_loop::CustomEvent::UserEvent(event);
         #[cfg(not(target_arch = "wasm32"))]
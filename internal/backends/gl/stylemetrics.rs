@@ -9,7 +9,10 @@
 use core::pin::Pin;
 #[cfg(feature = "rtti")]
 use i_slint_core::rtti::*;
-use i_slint_core::{items::LayoutAlignment, Color, Property};
+use i_slint_core::{
+    items::{ColorScheme, LayoutAlignment},
+    Color, Property,
+};
 use i_slint_core_macros::*;
 
 #[repr(C)]
@@ -72,13 +75,86 @@ pub fn init<T>(self: Pin<Rc<Self>>, _root: T) {
     // actually uses is dark_style.
     // The actual colors are defined in the theme's .slint file.
     pub fn init_impl(self: Pin<&Self>) {
-        use dark_light::Mode;
+        self.dark_style.set(detect_color_scheme() == ColorScheme::dark);
+    }
+}
+
+/// Returns the color scheme currently configured in the system settings.
+pub fn detect_color_scheme() -> ColorScheme {
+    match dark_light::detect() {
+        dark_light::Mode::Light => ColorScheme::light,
+        dark_light::Mode::Dark => ColorScheme::dark,
+    }
+}
+
+thread_local! {
+    static CURSOR_BLINK_INTERVAL: Pin<Box<Property<Option<core::time::Duration>>>> =
+        Box::pin(Property::new_named(
+            Some(i_slint_core::input::DEFAULT_CURSOR_BLINK_INTERVAL),
+            "i_slint_backend_gl::CURSOR_BLINK_INTERVAL",
+        ));
+}
 
-        self.dark_style.set(match dark_light::detect() {
-            Mode::Light => false,
-            Mode::Dark => true,
+/// Returns the time between two toggles of the text cursor configured in the system settings, or
+/// None if the blinking is disabled. The first call starts the detection, which runs an external
+/// process on Linux, so it's done in a thread and the default interval is returned until it's
+/// finished. Reading the value is tracked, so the cursor picks up the detected interval.
+pub fn cursor_blink_interval() -> Option<core::time::Duration> {
+    static DETECTION: std::sync::Once = std::sync::Once::new();
+    DETECTION.call_once(|| {
+        #[cfg(any(target_family = "windows", target_os = "macos", target_arch = "wasm32"))]
+        CURSOR_BLINK_INTERVAL
+            .with(|interval| interval.as_ref().set(detect_cursor_blink_interval()));
+
+        #[cfg(not(any(target_family = "windows", target_os = "macos", target_arch = "wasm32")))]
+        std::thread::spawn(|| {
+            let detected = detect_cursor_blink_interval();
+            i_slint_core::api::invoke_from_event_loop(move || {
+                CURSOR_BLINK_INTERVAL.with(|interval| interval.as_ref().set(detected))
+            });
         });
+    });
+    CURSOR_BLINK_INTERVAL.with(|interval| interval.as_ref().get())
+}
+
+/// Returns the time between two toggles of the text cursor configured in the system settings, or
+/// None if the blinking is disabled, for example for accessibility. This may block, use
+/// [`cursor_blink_interval()`] on the UI thread.
+fn detect_cursor_blink_interval() -> Option<core::time::Duration> {
+    let default = Some(i_slint_core::input::DEFAULT_CURSOR_BLINK_INTERVAL);
+
+    #[cfg(target_family = "windows")]
+    {
+        // The time between two toggles, INFINITE if the caret doesn't blink, or 0 on error
+        return match unsafe { winapi::um::winuser::GetCaretBlinkTime() } {
+            u32::MAX => None,
+            0 => default,
+            time => Some(core::time::Duration::from_millis(time as u64)),
+        };
     }
+
+    #[cfg(not(any(target_family = "windows", target_os = "macos", target_arch = "wasm32")))]
+    {
+        let gsettings = |key: &str| {
+            std::process::Command::new("gsettings")
+                .args(["get", "org.gnome.desktop.interface", key])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        };
+        if gsettings("cursor-blink").as_deref() == Some("false") {
+            return None;
+        }
+        // The blink time is a whole period of the blinking
+        if let Some(time) = gsettings("cursor-blink-time").and_then(|time| time.parse::<u64>().ok())
+        {
+            return Some(core::time::Duration::from_millis(time / 2));
+        }
+    }
+
+    #[allow(unreachable_code)]
+    default
 }
 
 #[cfg(feature = "rtti")]
@@ -0,0 +1,431 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+This module contains the [`VideoRenderer`], which draws the frames of the video sinks. Each stream
+is drawn from one texture, which is updated in place when a new frame is shown: the planes of the
+YUV frames are uploaded to textures, and converted to RGB into that texture by a shader.
+*/
+
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::rc::Rc;
+
+use glow::HasContext;
+use i_slint_core::graphics::{VideoFrame, VideoStreamId};
+use i_slint_core::items::ImageRendering;
+use rgb::FromSlice;
+
+use crate::glrenderer::CanvasRc;
+use crate::images::CachedImage;
+
+const VERTEX_SHADER: &str = r#"
+attribute vec2 position;
+varying vec2 tex_coord;
+void main() {
+    tex_coord = position * 0.5 + 0.5;
+    gl_Position = vec4(position, 0.0, 1.0);
+}
+"#;
+
+/// Converts from YUV with the BT.601 coefficients and the limited range, like the conversion on
+/// the CPU in i_slint_core::graphics::VideoFrame. The first row of the planes is written to the
+/// first row of the target texture, so the texture isn't flipped.
+const FRAGMENT_SHADER: &str = r#"
+#ifdef GL_ES
+precision mediump float;
+#endif
+varying vec2 tex_coord;
+uniform int format;
+uniform float width;
+uniform sampler2D plane0;
+uniform sampler2D plane1;
+uniform sampler2D plane2;
+void main() {
+    float y;
+    float u;
+    float v;
+    if (format == 0) {
+        y = texture2D(plane0, tex_coord).r;
+        u = texture2D(plane1, tex_coord).r;
+        v = texture2D(plane2, tex_coord).r;
+    } else if (format == 1) {
+        y = texture2D(plane0, tex_coord).r;
+        vec4 uv = texture2D(plane1, tex_coord);
+        u = uv.r;
+        v = uv.a;
+    } else {
+        // Each texel holds two pixels: luma, blue-difference, luma, red-difference
+        vec4 pair = texture2D(plane0, tex_coord);
+        y = mod(floor(tex_coord.x * width), 2.0) < 0.5 ? pair.r : pair.b;
+        u = pair.g;
+        v = pair.a;
+    }
+    y = 1.1644 * (y - 0.0627);
+    u = u - 0.502;
+    v = v - 0.502;
+    vec3 rgb = vec3(y + 1.5960 * v, y - 0.3917 * u - 0.8125 * v, y + 2.0172 * u);
+    gl_FragColor = vec4(clamp(rgb, 0.0, 1.0), 1.0);
+}
+"#;
+
+/// The value of the `format` uniform of the shader
+const FORMAT_I420: i32 = 0;
+const FORMAT_NV12: i32 = 1;
+const FORMAT_YUYV: i32 = 2;
+
+/// The texture that shows the frames of a stream
+struct StreamTexture {
+    image: Rc<CachedImage>,
+    /// The generation of the frame in the texture
+    generation: u64,
+    size: (u32, u32),
+    pixel_format: femtovg::PixelFormat,
+    flags: femtovg::ImageFlags,
+    /// Whether the texture was drawn since the last call to [`VideoRenderer::drain()`]
+    used: bool,
+}
+
+/// Draws the frames of the video streams, with the OpenGL context of a surface. The textures and
+/// the shader are deleted when it's dropped, so that must happen while the context is current.
+pub struct VideoRenderer {
+    gl: glow::Context,
+    program: glow::Program,
+    format_location: Option<glow::UniformLocation>,
+    width_location: Option<glow::UniformLocation>,
+    vertex_buffer: glow::Buffer,
+    /// Only with the contexts that require one to draw
+    vertex_array: Option<glow::VertexArray>,
+    framebuffer: glow::Framebuffer,
+    /// The planes of the YUV frame that is converted
+    planes: [glow::Texture; 3],
+    streams: HashMap<VideoStreamId, StreamTexture>,
+}
+
+impl VideoRenderer {
+    /// Compiles the shader with the functions of the OpenGL context that is current.
+    pub fn new(
+        get_proc_address: impl FnMut(&str) -> *const std::ffi::c_void,
+    ) -> Result<Self, String> {
+        let gl = unsafe { glow::Context::from_loader_function(get_proc_address) };
+        unsafe {
+            let state = SavedState::save(&gl);
+            let program = gl.create_program()?;
+            for (kind, source) in
+                [(glow::VERTEX_SHADER, VERTEX_SHADER), (glow::FRAGMENT_SHADER, FRAGMENT_SHADER)]
+            {
+                let shader = gl.create_shader(kind)?;
+                gl.shader_source(shader, source);
+                gl.compile_shader(shader);
+                if !gl.get_shader_compile_status(shader) {
+                    let log = gl.get_shader_info_log(shader);
+                    gl.delete_shader(shader);
+                    gl.delete_program(program);
+                    return Err(log);
+                }
+                gl.attach_shader(program, shader);
+                // Only deleted once the program is deleted
+                gl.delete_shader(shader);
+            }
+            gl.bind_attrib_location(program, 0, "position");
+            gl.link_program(program);
+            if !gl.get_program_link_status(program) {
+                let log = gl.get_program_info_log(program);
+                gl.delete_program(program);
+                return Err(log);
+            }
+            gl.use_program(Some(program));
+            for (index, name) in ["plane0", "plane1", "plane2"].iter().enumerate() {
+                gl.uniform_1_i32(gl.get_uniform_location(program, name).as_ref(), index as i32);
+            }
+
+            let vertex_array =
+                (gl.version().major >= 3).then(|| gl.create_vertex_array()).transpose()?;
+            let vertex_buffer = gl.create_buffer()?;
+            gl.bind_buffer(glow::ARRAY_BUFFER, Some(vertex_buffer));
+            let quad: [f32; 8] = [-1., -1., 1., -1., -1., 1., 1., 1.];
+            let quad_bytes: Vec<u8> = quad.iter().flat_map(|value| value.to_ne_bytes()).collect();
+            gl.buffer_data_u8_slice(glow::ARRAY_BUFFER, &quad_bytes, glow::STATIC_DRAW);
+
+            let mut planes = [None; 3];
+            for plane in planes.iter_mut() {
+                let texture = gl.create_texture()?;
+                gl.bind_texture(glow::TEXTURE_2D, Some(texture));
+                // The size of the planes is usually not a power of two
+                for (parameter, value) in [
+                    (glow::TEXTURE_MIN_FILTER, glow::NEAREST),
+                    (glow::TEXTURE_MAG_FILTER, glow::NEAREST),
+                    (glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+                    (glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+                ] {
+                    gl.tex_parameter_i32(glow::TEXTURE_2D, parameter, value as i32);
+                }
+                *plane = Some(texture);
+            }
+            let framebuffer = gl.create_framebuffer()?;
+            state.restore(&gl);
+
+            Ok(Self {
+                format_location: gl.get_uniform_location(program, "format"),
+                width_location: gl.get_uniform_location(program, "width"),
+                gl,
+                program,
+                vertex_buffer,
+                vertex_array,
+                framebuffer,
+                planes: planes.map(Option::unwrap),
+                streams: Default::default(),
+            })
+        }
+    }
+
+    /// Returns the image that shows the frame of the stream that is shown, uploading and
+    /// converting the frame into the texture of the stream if it changed since the last call.
+    /// Returns None if no frame was shown yet.
+    pub fn frame_image(
+        &mut self,
+        canvas: &CanvasRc,
+        stream: VideoStreamId,
+        scaling: ImageRendering,
+    ) -> Option<Rc<CachedImage>> {
+        // Registers a dependency, so that the window is redrawn when the next frame is shown
+        let generation = i_slint_core::graphics::shown_video_frame_generation(stream)?;
+        let flags = match scaling {
+            ImageRendering::smooth => femtovg::ImageFlags::empty(),
+            ImageRendering::pixelated => femtovg::ImageFlags::NEAREST,
+        };
+        if let Some(texture) = self.streams.get_mut(&stream) {
+            texture.used = true;
+            if texture.generation == generation && texture.flags == flags {
+                return Some(texture.image.clone());
+            }
+        }
+        let texture = i_slint_core::graphics::with_shown_video_frame(stream, |frame| {
+            self.update_texture(canvas, stream, frame, flags)
+        })??;
+        let image = texture.image.clone();
+        self.streams.insert(stream, StreamTexture { generation, ..texture });
+        Some(image)
+    }
+
+    /// Copies the frame into the texture of the stream, which is created again only if the size
+    /// or the format of the frames changed.
+    fn update_texture(
+        &mut self,
+        canvas: &CanvasRc,
+        stream: VideoStreamId,
+        frame: &VideoFrame<'_>,
+        flags: femtovg::ImageFlags,
+    ) -> Option<StreamTexture> {
+        let size = (frame.width(), frame.height());
+        if size.0 == 0 || size.1 == 0 {
+            return None;
+        }
+        let pixel_format = match frame {
+            VideoFrame::Rgb8 { .. } => femtovg::PixelFormat::Rgb8,
+            _ => femtovg::PixelFormat::Rgba8,
+        };
+        let image = match self.streams.remove(&stream) {
+            Some(texture)
+                if texture.size == size
+                    && texture.pixel_format == pixel_format
+                    && texture.flags == flags =>
+            {
+                texture.image
+            }
+            _ => {
+                let image_id = canvas
+                    .borrow_mut()
+                    .create_image_empty(size.0 as usize, size.1 as usize, pixel_format, flags)
+                    .ok()?;
+                Rc::new(CachedImage::new_on_gpu(canvas, image_id))
+            }
+        };
+        let image_id = image.texture_id()?;
+
+        // The planes of the shown frames are not padded
+        let (width, height) = (size.0 as usize, size.1 as usize);
+        match *frame {
+            VideoFrame::Rgb8 { data, .. } => canvas
+                .borrow_mut()
+                .update_image(image_id, imgref::ImgRef::new(data.as_rgb(), width, height), 0, 0)
+                .ok()?,
+            VideoFrame::Rgba8 { data, .. } => canvas
+                .borrow_mut()
+                .update_image(image_id, imgref::ImgRef::new(data.as_rgba(), width, height), 0, 0)
+                .ok()?,
+            _ => {
+                let target = canvas.borrow().get_native_texture(image_id).ok()?;
+                unsafe { self.convert_yuv(frame, target) };
+            }
+        }
+        Some(StreamTexture { image, generation: 0, size, pixel_format, flags, used: true })
+    }
+
+    /// Uploads the planes of the YUV frame and draws them into the target texture with the shader.
+    unsafe fn convert_yuv(&self, frame: &VideoFrame<'_>, target: glow::Texture) {
+        let gl = &self.gl;
+        let (width, height) = (frame.width() as i32, frame.height() as i32);
+        let (chroma_width, chroma_height) = ((width + 1) / 2, (height + 1) / 2);
+        let state = SavedState::save(gl);
+
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+        let upload = |index: usize, format: u32, width: i32, height: i32, data: &[u8]| {
+            gl.active_texture(glow::TEXTURE0 + index as u32);
+            gl.bind_texture(glow::TEXTURE_2D, Some(self.planes[index]));
+            gl.tex_image_2d(
+                glow::TEXTURE_2D,
+                0,
+                format as i32,
+                width,
+                height,
+                0,
+                format,
+                glow::UNSIGNED_BYTE,
+                Some(data),
+            );
+        };
+        let format = match *frame {
+            VideoFrame::I420 { y, u, v, .. } => {
+                upload(0, glow::LUMINANCE, width, height, y.0);
+                upload(1, glow::LUMINANCE, chroma_width, chroma_height, u.0);
+                upload(2, glow::LUMINANCE, chroma_width, chroma_height, v.0);
+                FORMAT_I420
+            }
+            VideoFrame::Nv12 { y, uv, .. } => {
+                upload(0, glow::LUMINANCE, width, height, y.0);
+                upload(1, glow::LUMINANCE_ALPHA, chroma_width, chroma_height, uv.0);
+                FORMAT_NV12
+            }
+            VideoFrame::Yuyv { data, .. } => {
+                upload(0, glow::RGBA, chroma_width, height, data);
+                FORMAT_YUYV
+            }
+            VideoFrame::Rgb8 { .. } | VideoFrame::Rgba8 { .. } => {
+                unreachable!("the RGB frames are uploaded by femtovg")
+            }
+        };
+
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+        gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(target),
+            0,
+        );
+        gl.viewport(0, 0, width, height);
+        for capability in SavedState::CAPABILITIES {
+            gl.disable(capability);
+        }
+        gl.use_program(Some(self.program));
+        gl.uniform_1_i32(self.format_location.as_ref(), format);
+        gl.uniform_1_f32(self.width_location.as_ref(), width as f32);
+        if let Some(vertex_array) = self.vertex_array {
+            gl.bind_vertex_array(Some(vertex_array));
+        }
+        gl.bind_buffer(glow::ARRAY_BUFFER, Some(self.vertex_buffer));
+        gl.enable_vertex_attrib_array(0);
+        gl.vertex_attrib_pointer_f32(0, 2, glow::FLOAT, false, 0, 0);
+        gl.draw_arrays(glow::TRIANGLE_STRIP, 0, 4);
+        gl.disable_vertex_attrib_array(0);
+        gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            None,
+            0,
+        );
+
+        state.restore(gl);
+    }
+
+    /// Deletes the textures of the streams that were not drawn since the last call.
+    pub fn drain(&mut self) {
+        self.streams.retain(|_, texture| std::mem::replace(&mut texture.used, false));
+    }
+}
+
+impl Drop for VideoRenderer {
+    fn drop(&mut self) {
+        unsafe {
+            self.gl.delete_program(self.program);
+            self.gl.delete_buffer(self.vertex_buffer);
+            if let Some(vertex_array) = self.vertex_array {
+                self.gl.delete_vertex_array(vertex_array);
+            }
+            self.gl.delete_framebuffer(self.framebuffer);
+            for texture in self.planes {
+                self.gl.delete_texture(texture);
+            }
+        }
+    }
+}
+
+/// The state of the context that femtovg expects to find, saved before the conversion changes it
+struct SavedState {
+    framebuffer: i32,
+    viewport: [i32; 4],
+    program: i32,
+    array_buffer: i32,
+    vertex_array: Option<i32>,
+    active_texture: i32,
+    textures: [i32; 3],
+    unpack_alignment: i32,
+    capabilities: [bool; 5],
+}
+
+impl SavedState {
+    const CAPABILITIES: [u32; 5] =
+        [glow::BLEND, glow::SCISSOR_TEST, glow::CULL_FACE, glow::STENCIL_TEST, glow::DEPTH_TEST];
+
+    unsafe fn save(gl: &glow::Context) -> Self {
+        let mut viewport = [0; 4];
+        gl.get_parameter_i32_slice(glow::VIEWPORT, &mut viewport);
+        let active_texture = gl.get_parameter_i32(glow::ACTIVE_TEXTURE);
+        let textures = [0, 1, 2].map(|index| {
+            gl.active_texture(glow::TEXTURE0 + index);
+            gl.get_parameter_i32(glow::TEXTURE_BINDING_2D)
+        });
+        gl.active_texture(active_texture as u32);
+        Self {
+            framebuffer: gl.get_parameter_i32(glow::FRAMEBUFFER_BINDING),
+            viewport,
+            program: gl.get_parameter_i32(glow::CURRENT_PROGRAM),
+            array_buffer: gl.get_parameter_i32(glow::ARRAY_BUFFER_BINDING),
+            vertex_array: (gl.version().major >= 3)
+                .then(|| gl.get_parameter_i32(glow::VERTEX_ARRAY_BINDING)),
+            active_texture,
+            textures,
+            unpack_alignment: gl.get_parameter_i32(glow::UNPACK_ALIGNMENT),
+            capabilities: Self::CAPABILITIES.map(|capability| gl.is_enabled(capability)),
+        }
+    }
+
+    unsafe fn restore(&self, gl: &glow::Context) {
+        fn name(id: i32) -> Option<NonZeroU32> {
+            NonZeroU32::new(id as u32)
+        }
+        gl.bind_framebuffer(glow::FRAMEBUFFER, name(self.framebuffer).map(glow::NativeFramebuffer));
+        let [x, y, width, height] = self.viewport;
+        gl.viewport(x, y, width, height);
+        gl.use_program(name(self.program).map(glow::NativeProgram));
+        gl.bind_buffer(glow::ARRAY_BUFFER, name(self.array_buffer).map(glow::NativeBuffer));
+        if let Some(vertex_array) = self.vertex_array {
+            gl.bind_vertex_array(name(vertex_array).map(glow::NativeVertexArray));
+        }
+        for (index, texture) in self.textures.iter().enumerate() {
+            gl.active_texture(glow::TEXTURE0 + index as u32);
+            gl.bind_texture(glow::TEXTURE_2D, name(*texture).map(glow::NativeTexture));
+        }
+        gl.active_texture(self.active_texture as u32);
+        gl.pixel_store_i32(glow::UNPACK_ALIGNMENT, self.unpack_alignment);
+        for (capability, enabled) in Self::CAPABILITIES.iter().zip(self.capabilities) {
+            if enabled {
+                gl.enable(*capability);
+            } else {
+                gl.disable(*capability);
+            }
+        }
+    }
+}
@@ -13,9 +13,9 @@
 //! that do not interact with the composing input. For anything else we
 //! check that we get input event when no normal key are pressed, and we send
 //! that as text.
-//! Since the slint core lib doesn't support composition yet, when we get
-//! composition event, we just send that as key, and if the composition changes,
-//! we just simulate a few backspaces.
+//! The composition events of the input methods are forwarded as composition key
+//! events, so that the TextInput shows the text being composed at the cursor.
+//! The text pasted in the input is inserted in the same way as a committed composition.
 
 use std::cell::RefCell;
 use std::rc::{Rc, Weak};
@@ -35,32 +35,6 @@ pub struct WasmInputHelper {
 struct WasmInputState {
     /// If there was a "keydown" event recieved not part of a composition
     has_key_down: bool,
-    /// The current composing text
-    composition: String,
-}
-
-impl WasmInputState {
-    /// Update the composition text and return the number of character to rollback and the string to add
-    fn text_from_compose(&mut self, data: String, is_end: bool) -> (SharedString, usize) {
-        let mut data_iter = data.char_indices().peekable();
-        let mut composition_iter = self.composition.chars().peekable();
-        // Skip common prefix
-        while let (Some(c), Some((_, d))) = (composition_iter.peek(), data_iter.peek()) {
-            if c != d {
-                break;
-            }
-            composition_iter.next();
-            data_iter.next();
-        }
-        let to_delete = composition_iter.count();
-        let result = if let Some((idx, _)) = data_iter.next() {
-            SharedString::from(&data[idx..])
-        } else {
-            SharedString::default()
-        };
-        self.composition = if is_end { String::new() } else { data };
-        (result, to_delete)
-    }
 }
 
 impl WasmInputHelper {
@@ -104,13 +78,20 @@ pub fn new(
         let win = window.clone();
         let shared_state2 = shared_state.clone();
         h.add_event_listener("keydown", move |e: web_sys::KeyboardEvent| {
+            if is_paste_shortcut(&e) {
+                // Let the browser fire the "paste" event, which carries the text of the clipboard
+                return;
+            }
             if let (Some(window), Some(text)) = (win.upgrade(), event_text(&e)) {
                 e.prevent_default();
                 shared_state2.borrow_mut().has_key_down = true;
+                // The browser doesn't give access to the scan code of the key
                 window.process_key_input(&KeyEvent {
                     modifiers: modifiers(&e),
                     text,
                     event_type: KeyEventType::KeyPressed,
+                    is_auto_repeat: e.repeat(),
+                    ..Default::default()
                 });
             }
         });
@@ -118,6 +99,9 @@ pub fn new(
         let win = window.clone();
         let shared_state2 = shared_state.clone();
         h.add_event_listener("keyup", move |e: web_sys::KeyboardEvent| {
+            if is_paste_shortcut(&e) {
+                return;
+            }
             if let (Some(window), Some(text)) = (win.upgrade(), event_text(&e)) {
                 e.prevent_default();
                 shared_state2.borrow_mut().has_key_down = false;
@@ -125,6 +109,7 @@ pub fn new(
                     modifiers: modifiers(&e),
                     text,
                     event_type: KeyEventType::KeyReleased,
+                    ..Default::default()
                 });
             }
         });
@@ -141,11 +126,13 @@ pub fn new(
                             modifiers: Default::default(),
                             text: text.clone(),
                             event_type: KeyEventType::KeyPressed,
+                            ..Default::default()
                         });
                         window.process_key_input(&KeyEvent {
                             modifiers: Default::default(),
                             text,
                             event_type: KeyEventType::KeyReleased,
+                            ..Default::default()
                         });
                         shared_state2.borrow_mut().has_key_down = false;
                     }
@@ -154,45 +141,41 @@ pub fn new(
             }
         });
 
-        for event in ["compositionend", "compositionupdate"] {
-            let win = window.clone();
-            let shared_state2 = shared_state.clone();
-            let input = h.input.clone();
-            h.add_event_listener(event, move |e: web_sys::CompositionEvent| {
-                if let (Some(window), Some(data)) = (win.upgrade(), e.data()) {
-                    let is_end = event == "compositionend";
-                    let (text, to_delete) =
-                        shared_state2.borrow_mut().text_from_compose(data, is_end);
-                    if to_delete > 0 {
-                        let mut buffer = [0; 6];
-                        let backspace = SharedString::from(
-                            i_slint_core::input::key_codes::Backspace.encode_utf8(&mut buffer)
-                                as &str,
-                        );
-                        for _ in 0..to_delete {
-                            window.clone().process_key_input(&KeyEvent {
-                                modifiers: Default::default(),
-                                text: backspace.clone(),
-                                event_type: KeyEventType::KeyPressed,
-                            });
-                        }
-                    }
-                    window.clone().process_key_input(&KeyEvent {
-                        modifiers: Default::default(),
-                        text: text.clone(),
-                        event_type: KeyEventType::KeyPressed,
-                    });
-                    window.process_key_input(&KeyEvent {
-                        modifiers: Default::default(),
-                        text,
-                        event_type: KeyEventType::KeyReleased,
-                    });
-                    if is_end {
-                        input.set_value("");
-                    }
-                }
-            });
-        }
+        let win = window.clone();
+        h.add_event_listener("compositionstart", move |_: web_sys::CompositionEvent| {
+            if let Some(window) = win.upgrade() {
+                send_composition(&window, KeyEventType::UpdateComposition, String::new());
+            }
+        });
+
+        let win = window.clone();
+        h.add_event_listener("compositionupdate", move |e: web_sys::CompositionEvent| {
+            if let (Some(window), Some(data)) = (win.upgrade(), e.data()) {
+                send_composition(&window, KeyEventType::UpdateComposition, data);
+            }
+        });
+
+        let win = window.clone();
+        let input = h.input.clone();
+        h.add_event_listener("compositionend", move |e: web_sys::CompositionEvent| {
+            if let Some(window) = win.upgrade() {
+                send_composition(
+                    &window,
+                    KeyEventType::CommitComposition,
+                    e.data().unwrap_or_default(),
+                );
+                input.set_value("");
+            }
+        });
+
+        let win = window.clone();
+        h.add_event_listener("paste", move |e: web_sys::ClipboardEvent| {
+            let text = e.clipboard_data().and_then(|data| data.get_data("text/plain").ok());
+            if let (Some(window), Some(text)) = (win.upgrade(), text) {
+                e.prevent_default();
+                send_composition(&window, KeyEventType::CommitComposition, text);
+            }
+        });
 
         h
     }
@@ -237,6 +220,49 @@ fn add_event_listener<Arg: FromWasmAbi + 'static>(
     }
 }
 
+fn send_composition(
+    window: &Rc<i_slint_core::window::Window>,
+    event_type: KeyEventType,
+    text: String,
+) {
+    window.clone().process_key_input(&KeyEvent {
+        modifiers: Default::default(),
+        text: text.into(),
+        event_type,
+        ..Default::default()
+    });
+}
+
+fn is_paste_shortcut(e: &web_sys::KeyboardEvent) -> bool {
+    (e.ctrl_key() || e.meta_key()) && !e.alt_key() && e.key().eq_ignore_ascii_case("v")
+}
+
+/// Puts the text on the clipboard with the asynchronous Clipboard API of the browser, which is
+/// only available in secure contexts. `navigator.clipboard` is accessed through `js_sys::Reflect`
+/// because its web-sys binding requires the unstable web-sys APIs.
+pub fn write_clipboard_text(text: &str) {
+    let navigator = web_sys::window().unwrap().navigator();
+    let promise = js_sys::Reflect::get(&navigator, &"clipboard".into())
+        .ok()
+        .filter(|clipboard| !clipboard.is_undefined())
+        .and_then(|clipboard| {
+            let write_text = js_sys::Reflect::get(&clipboard, &"writeText".into()).ok()?;
+            write_text.dyn_into::<js_sys::Function>().ok()?.call1(&clipboard, &text.into()).ok()
+        })
+        .and_then(|promise| promise.dyn_into::<js_sys::Promise>().ok());
+
+    match promise {
+        Some(promise) => {
+            let on_error = Closure::once(|err: wasm_bindgen::JsValue| {
+                web_sys::console::warn_2(&"Could not write to the clipboard:".into(), &err)
+            });
+            let _ = promise.catch(&on_error);
+            on_error.forget();
+        }
+        None => web_sys::console::warn_1(&"The clipboard API is not available".into()),
+    }
+}
+
 fn event_text(e: &web_sys::KeyboardEvent) -> Option<SharedString> {
     if e.is_composing() {
         return None;
@@ -0,0 +1,48 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Sets the brightness of the display with a backlight device of the kernel, in
+//! `/sys/class/backlight`.
+
+use std::path::PathBuf;
+
+pub struct Backlight {
+    /// The directory of the device in `/sys/class/backlight`
+    path: PathBuf,
+    max_brightness: u32,
+}
+
+impl Backlight {
+    /// Opens the device named by the `SLINT_BACKLIGHT_DEVICE` environment variable, or else the
+    /// first device in `/sys/class/backlight`. Returns None if there is no backlight device.
+    pub fn open() -> Option<Self> {
+        let path = match std::env::var("SLINT_BACKLIGHT_DEVICE") {
+            Ok(name) => PathBuf::from("/sys/class/backlight").join(name),
+            Err(_) => {
+                let mut devices = std::fs::read_dir("/sys/class/backlight")
+                    .ok()?
+                    .filter_map(|entry| Some(entry.ok()?.path()))
+                    .collect::<Vec<_>>();
+                devices.sort();
+                devices.into_iter().next()?
+            }
+        };
+        let max_brightness = std::fs::read_to_string(path.join("max_brightness"))
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .filter(|max_brightness| *max_brightness > 0)?;
+        Some(Self { path, max_brightness })
+    }
+
+    /// Sets the brightness to `fraction` of the maximum brightness, between 0 and 1.
+    pub fn set_brightness(&self, fraction: f32) {
+        let brightness = (fraction.clamp(0., 1.) * self.max_brightness as f32).round() as u32;
+        if let Err(err) = std::fs::write(self.path.join("brightness"), brightness.to_string()) {
+            i_slint_core::debug_log!(
+                "Could not set the brightness of {}: {}",
+                self.path.display(),
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,8 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+fn main() {
+    // The software renderer can only draw the pre-rendered glyphs and textures that the
+    // compiler embeds in the binary
+    println!("cargo:EMBED_TEXTURES=1");
+}
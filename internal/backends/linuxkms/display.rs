@@ -0,0 +1,300 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Presents the frames on the first connected display of a DRM device, with two dumb buffers
+//! that are swapped with page flips.
+
+use std::os::unix::io::{AsFd, BorrowedFd};
+
+use drm::buffer::{Buffer as _, DrmFourcc};
+use drm::control::dumbbuffer::DumbBuffer;
+use drm::control::{connector, crtc, framebuffer, Device as ControlDevice, Mode, PageFlipFlags};
+use i_slint_core::lengths::PhysicalSize;
+use i_slint_core::swrenderer::LineBufferProvider;
+
+struct Card(std::fs::File);
+
+impl AsFd for Card {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl drm::Device for Card {}
+impl ControlDevice for Card {}
+
+struct Buffer {
+    dumb_buffer: DumbBuffer,
+    framebuffer: framebuffer::Handle,
+}
+
+pub struct Display {
+    card: Card,
+    connector: connector::Handle,
+    crtc: crtc::Handle,
+    mode: Mode,
+    /// The configuration of the CRTC before we took over, restored when dropped
+    saved_crtc: crtc::Info,
+    buffers: [Buffer; 2],
+    /// The index in `buffers` of the buffer that is not displayed
+    back_buffer: usize,
+    /// True between a page flip and the event telling that it happened
+    flip_pending: bool,
+    /// False while the display is turned off with [`Self::set_powered_on()`]
+    powered_on: bool,
+    line_buffer: Vec<rgb::RGB8>,
+}
+
+impl Display {
+    /// Opens the device named by the `SLINT_DRM_DEVICE` environment variable, or else the first
+    /// `/dev/dri/card*` device with a connected display.
+    pub fn open() -> Result<Self, String> {
+        if let Ok(path) = std::env::var("SLINT_DRM_DEVICE") {
+            return Self::open_device(std::path::Path::new(&path));
+        }
+
+        let mut devices = std::fs::read_dir("/dev/dri")
+            .map_err(|err| format!("could not list the DRM devices in /dev/dri: {}", err))?
+            .filter_map(|entry| Some(entry.ok()?.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| name.starts_with("card"))
+            })
+            .collect::<Vec<_>>();
+        devices.sort();
+
+        let mut errors = Vec::new();
+        for path in devices {
+            match Self::open_device(&path) {
+                Ok(display) => return Ok(display),
+                Err(err) => errors.push(err),
+            }
+        }
+        Err(if errors.is_empty() {
+            "no DRM device found in /dev/dri".into()
+        } else {
+            errors.join("\n")
+        })
+    }
+
+    fn open_device(path: &std::path::Path) -> Result<Self, String> {
+        let card = Card(
+            std::fs::OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(path)
+                .map_err(|err| format!("could not open {}: {}", path.display(), err))?,
+        );
+
+        let resources = card.resource_handles().map_err(|err| {
+            format!("could not get the resources of the DRM device {}: {}", path.display(), err)
+        })?;
+
+        let connector = resources
+            .connectors()
+            .iter()
+            .filter_map(|handle| card.get_connector(*handle, false).ok())
+            .find(|connector| connector.state() == connector::State::Connected)
+            .ok_or_else(|| format!("no connected display found on {}", path.display()))?;
+
+        let mode = *connector
+            .modes()
+            .iter()
+            .find(|mode| mode.mode_type().contains(drm::control::ModeTypeFlags::PREFERRED))
+            .or_else(|| connector.modes().first())
+            .ok_or_else(|| format!("the display connected to {} has no mode", path.display()))?;
+
+        // Prefer the CRTC that already drives the connector, to avoid a mode set when possible
+        let crtc = connector
+            .current_encoder()
+            .and_then(|encoder| card.get_encoder(encoder).ok())
+            .and_then(|encoder| encoder.crtc())
+            .or_else(|| {
+                connector
+                    .encoders()
+                    .iter()
+                    .filter_map(|encoder| card.get_encoder(*encoder).ok())
+                    .flat_map(|encoder| resources.filter_crtcs(encoder.possible_crtcs()))
+                    .next()
+            })
+            .ok_or_else(|| format!("no CRTC available for the display on {}", path.display()))?;
+
+        let saved_crtc = card.get_crtc(crtc).map_err(|err| {
+            format!("could not get the CRTC of the DRM device {}: {}", path.display(), err)
+        })?;
+
+        let (width, height) = mode.size();
+        let create_buffer = || -> Result<Buffer, String> {
+            let mut dumb_buffer = card
+                .create_dumb_buffer((width.into(), height.into()), DrmFourcc::Xrgb8888, 32)
+                .map_err(|err| {
+                    format!("could not create a buffer of {}x{}: {}", width, height, err)
+                })?;
+            // Start with a black screen
+            card.map_dumb_buffer(&mut dumb_buffer)
+                .map_err(|err| format!("could not map the frame buffer: {}", err))?
+                .fill(0);
+            let framebuffer = card
+                .add_framebuffer(&dumb_buffer, 24, 32)
+                .map_err(|err| format!("could not create the frame buffer: {}", err))?;
+            Ok(Buffer { dumb_buffer, framebuffer })
+        };
+        let buffers = [create_buffer()?, create_buffer()?];
+
+        card.set_crtc(
+            crtc,
+            Some(buffers[0].framebuffer),
+            (0, 0),
+            &[connector.handle()],
+            Some(mode),
+        )
+        .map_err(|err| {
+            format!("could not set the mode of the display on {}: {}", path.display(), err)
+        })?;
+
+        Ok(Self {
+            card,
+            connector: connector.handle(),
+            crtc,
+            mode,
+            saved_crtc,
+            buffers,
+            back_buffer: 1,
+            flip_pending: false,
+            powered_on: true,
+            line_buffer: vec![Default::default(); width as usize],
+        })
+    }
+
+    pub fn size(&self) -> PhysicalSize {
+        let (width, height) = self.mode.size();
+        PhysicalSize::new(width as i16, height as i16)
+    }
+
+    /// The file descriptor that becomes readable when a page flip is done
+    pub fn fd(&self) -> BorrowedFd<'_> {
+        self.card.as_fd()
+    }
+
+    /// Returns false while the previous frame is not displayed yet, in which case the back
+    /// buffer is still being scanned out and must not be drawn to.
+    pub fn ready_for_frame(&self) -> bool {
+        !self.flip_pending
+    }
+
+    /// Returns false while the display is turned off, in which case no frame must be presented
+    pub fn is_powered_on(&self) -> bool {
+        self.powered_on
+    }
+
+    /// Turns the display on or off with the DPMS property of the connector. There is no vertical
+    /// blank while the display is off, so there are no page flips either.
+    pub fn set_powered_on(&mut self, on: bool) {
+        if self.powered_on == on {
+            return;
+        }
+        // The values of the DPMS property, see DRM_MODE_DPMS_ON and DRM_MODE_DPMS_OFF
+        let value = if on { 0 } else { 3 };
+        let dpms = self.card.get_properties(self.connector).ok().and_then(|properties| {
+            let (handles, _) = properties.as_props_and_values();
+            handles.iter().copied().find(|handle| {
+                self.card
+                    .get_property(*handle)
+                    .map_or(false, |info| info.name().to_bytes() == b"DPMS")
+            })
+        });
+        match dpms {
+            Some(dpms) => {
+                if let Err(err) = self.card.set_property(self.connector, dpms, value) {
+                    i_slint_core::debug_log!("Could not set the power of the display: {}", err);
+                }
+            }
+            None => i_slint_core::debug_log!("The display can't be turned off, it has no DPMS"),
+        }
+        self.powered_on = on;
+    }
+
+    /// Reads the events of the DRM device, to be called when its file descriptor is readable.
+    pub fn process_events(&mut self) {
+        match self.card.receive_events() {
+            Ok(events) => {
+                for event in events {
+                    if let drm::control::Event::PageFlip(_) = event {
+                        self.flip_pending = false;
+                    }
+                }
+            }
+            Err(err) => i_slint_core::debug_log!("Could not read the DRM events: {}", err),
+        }
+    }
+
+    /// Calls `render` to draw in the back buffer, and then shows the back buffer on the next
+    /// vertical blank.
+    pub fn present_frame(&mut self, render: impl FnOnce(&mut DisplayLineBuffer)) {
+        let buffer = &mut self.buffers[self.back_buffer];
+        let pitch = buffer.dumb_buffer.pitch() as usize;
+        match self.card.map_dumb_buffer(&mut buffer.dumb_buffer) {
+            Ok(mut mapping) => render(&mut DisplayLineBuffer {
+                frame_buffer: mapping.as_mut(),
+                pitch,
+                line_buffer: &mut self.line_buffer,
+            }),
+            Err(err) => {
+                i_slint_core::debug_log!("Could not map the frame buffer: {}", err);
+                return;
+            }
+        }
+
+        match self.card.page_flip(self.crtc, buffer.framebuffer, PageFlipFlags::EVENT, None) {
+            Ok(()) => {
+                self.flip_pending = true;
+                self.back_buffer = 1 - self.back_buffer;
+            }
+            Err(err) => i_slint_core::debug_log!("Could not show the frame: {}", err),
+        }
+    }
+}
+
+impl Drop for Display {
+    fn drop(&mut self) {
+        // Give the display back to whatever was showing on it before, like the console
+        let _ = self.card.set_crtc(
+            self.crtc,
+            self.saved_crtc.framebuffer(),
+            self.saved_crtc.position(),
+            &[self.connector],
+            self.saved_crtc.mode(),
+        );
+        for buffer in &self.buffers {
+            let _ = self.card.destroy_framebuffer(buffer.framebuffer);
+            let _ = self.card.destroy_dumb_buffer(buffer.dumb_buffer);
+        }
+    }
+}
+
+/// Copies the lines drawn by the renderer into the mapped frame buffer, in the XRGB8888 format
+pub struct DisplayLineBuffer<'a> {
+    frame_buffer: &'a mut [u8],
+    pitch: usize,
+    line_buffer: &'a mut [rgb::RGB8],
+}
+
+impl LineBufferProvider for &mut DisplayLineBuffer<'_> {
+    type TargetPixel = rgb::RGB8;
+
+    fn process_line(
+        &mut self,
+        line: usize,
+        range: core::ops::Range<usize>,
+        render_fn: impl FnOnce(&mut [rgb::RGB8]),
+    ) {
+        let pixels = &mut self.line_buffer[range.clone()];
+        render_fn(pixels);
+        let start = line * self.pitch + range.start * 4;
+        let destination = &mut self.frame_buffer[start..start + range.len() * 4];
+        for (destination, pixel) in destination.chunks_exact_mut(4).zip(pixels.iter()) {
+            destination.copy_from_slice(&[pixel.b, pixel.g, pixel.r, 0xff]);
+        }
+    }
+}
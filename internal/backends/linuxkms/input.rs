@@ -0,0 +1,371 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Reads the events of the mice, touch screens and keyboards with libinput, and translates the
+//! key codes to text with xkbcommon.
+
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::OpenOptionsExt;
+use std::os::unix::io::{AsFd, BorrowedFd, OwnedFd};
+use std::path::Path;
+use std::rc::{Rc, Weak};
+
+use i_slint_core::graphics::Point;
+use i_slint_core::input::{key_codes, KeyEvent, KeyEventType, KeyboardModifiers, MouseEvent};
+use i_slint_core::items::{PointerEventButton, ScrollEventPhase};
+use i_slint_core::lengths::PhysicalSize;
+use i_slint_core::timers::{Timer, TimerMode};
+use i_slint_core::window::Window;
+use i_slint_core::SharedString;
+use input::event::keyboard::{KeyState, KeyboardEventTrait};
+use input::event::pointer::{Axis, AxisSource, ButtonState, PointerEvent};
+use input::event::touch::{TouchEvent, TouchEventPosition, TouchEventSlot};
+use input::event::{Event, KeyboardEvent};
+use input::{Libinput, LibinputInterface};
+use nix::libc::{O_ACCMODE, O_RDONLY, O_RDWR, O_WRONLY};
+use xkbcommon::xkb;
+
+/// The delay before a held key starts repeating
+const KEY_REPEAT_DELAY: core::time::Duration = core::time::Duration::from_millis(500);
+/// The interval between two repetitions of a held key
+const KEY_REPEAT_INTERVAL: core::time::Duration = core::time::Duration::from_millis(33);
+
+// The button codes from linux/input-event-codes.h
+const BTN_LEFT: u32 = 0x110;
+const BTN_RIGHT: u32 = 0x111;
+const BTN_MIDDLE: u32 = 0x112;
+
+/// Opens the input devices on behalf of libinput
+struct Interface;
+
+impl LibinputInterface for Interface {
+    fn open_restricted(&mut self, path: &Path, flags: i32) -> Result<OwnedFd, i32> {
+        let access_mode = flags & O_ACCMODE;
+        OpenOptions::new()
+            .custom_flags(flags & !O_ACCMODE)
+            .read(access_mode == O_RDONLY || access_mode == O_RDWR)
+            .write(access_mode == O_WRONLY || access_mode == O_RDWR)
+            .open(path)
+            .map(OwnedFd::from)
+            .map_err(|err| err.raw_os_error().unwrap_or(nix::libc::EIO))
+    }
+
+    fn close_restricted(&mut self, fd: OwnedFd) {
+        drop(File::from(fd));
+    }
+}
+
+pub struct Input {
+    libinput: Libinput,
+    xkb_state: xkb::State,
+    screen_size: PhysicalSize,
+    /// The position of the mouse cursor, in physical pixels
+    cursor_position: Point,
+    /// The touch point that is turned into mouse events. The other ones are ignored.
+    touch_slot: Option<u32>,
+    /// Whether a touchpad scroll gesture is in progress
+    finger_scrolling: bool,
+    key_repeat_timer: Rc<Timer>,
+}
+
+impl Input {
+    /// Starts listening to the input devices of the `seat0` seat. The keyboard layout is chosen
+    /// with the usual `XKB_DEFAULT_LAYOUT`, `XKB_DEFAULT_VARIANT`, ... environment variables.
+    pub fn new(screen_size: PhysicalSize) -> Result<Self, String> {
+        let mut libinput = Libinput::new_with_udev(Interface);
+        libinput
+            .udev_assign_seat("seat0")
+            .map_err(|()| "could not assign the seat0 seat to libinput".to_string())?;
+
+        let context = xkb::Context::new(xkb::CONTEXT_NO_FLAGS);
+        let keymap = xkb::Keymap::new_from_names(
+            &context,
+            "",
+            "",
+            "",
+            "",
+            None,
+            xkb::KEYMAP_COMPILE_NO_FLAGS,
+        )
+        .ok_or_else(|| "could not create the keymap".to_string())?;
+
+        Ok(Self {
+            libinput,
+            xkb_state: xkb::State::new(&keymap),
+            screen_size,
+            cursor_position: Point::new(screen_size.width as f32, screen_size.height as f32) / 2.,
+            touch_slot: None,
+            finger_scrolling: false,
+            key_repeat_timer: Default::default(),
+        })
+    }
+
+    /// The file descriptor that becomes readable when there are new events
+    pub fn fd(&self) -> BorrowedFd<'_> {
+        self.libinput.as_fd()
+    }
+
+    /// Reads the pending input events and sends them to the `window`.
+    pub fn process_events(&mut self, window: &Rc<Window>) {
+        if let Err(err) = self.libinput.dispatch() {
+            i_slint_core::debug_log!("Could not read the input events: {}", err);
+            return;
+        }
+        while let Some(event) = self.libinput.next() {
+            match event {
+                Event::Pointer(event) => self.process_pointer_event(event, window),
+                Event::Touch(event) => self.process_touch_event(event, window),
+                Event::Keyboard(event) => self.process_keyboard_event(event, window),
+                _ => {}
+            }
+        }
+    }
+
+    /// Reads the pending input events and drops them, when there is no window to send them to.
+    pub fn discard_events(&mut self) {
+        if self.libinput.dispatch().is_ok() {
+            self.libinput.by_ref().for_each(drop);
+        }
+    }
+
+    fn process_pointer_event(&mut self, event: PointerEvent, window: &Rc<Window>) {
+        let event = match event {
+            PointerEvent::Motion(event) => {
+                self.move_cursor(Point::new(
+                    self.cursor_position.x + event.dx() as f32,
+                    self.cursor_position.y + event.dy() as f32,
+                ));
+                MouseEvent::MouseMoved { pos: self.cursor_position }
+            }
+            PointerEvent::MotionAbsolute(event) => {
+                self.move_cursor(Point::new(
+                    event.absolute_x_transformed(self.screen_size.width as u32) as f32,
+                    event.absolute_y_transformed(self.screen_size.height as u32) as f32,
+                ));
+                MouseEvent::MouseMoved { pos: self.cursor_position }
+            }
+            PointerEvent::Button(event) => {
+                let button = match event.button() {
+                    BTN_LEFT => PointerEventButton::left,
+                    BTN_RIGHT => PointerEventButton::right,
+                    BTN_MIDDLE => PointerEventButton::middle,
+                    _ => PointerEventButton::none,
+                };
+                match event.button_state() {
+                    ButtonState::Pressed => {
+                        MouseEvent::MousePressed { pos: self.cursor_position, button }
+                    }
+                    ButtonState::Released => {
+                        MouseEvent::MouseReleased { pos: self.cursor_position, button }
+                    }
+                }
+            }
+            PointerEvent::Axis(event) => {
+                // A wheel click is usually 15 degrees, scroll it by 60 pixels like the GL backend
+                // does for a line
+                let scale = match event.axis_source() {
+                    AxisSource::Wheel => 4.,
+                    _ => 1.,
+                };
+                let axis_value = |axis| {
+                    if event.has_axis(axis) {
+                        event.axis_value(axis) as f32 * scale
+                    } else {
+                        0.
+                    }
+                };
+                // libinput's values are positive when scrolling down, and Slint's when scrolling up
+                let delta = Point::new(-axis_value(Axis::Horizontal), -axis_value(Axis::Vertical));
+                // libinput ends a finger scroll with an event where both axes are zero
+                let phase = if event.axis_source() != AxisSource::Finger {
+                    ScrollEventPhase::none
+                } else if delta == Point::default() {
+                    self.finger_scrolling = false;
+                    ScrollEventPhase::end
+                } else if !self.finger_scrolling {
+                    self.finger_scrolling = true;
+                    ScrollEventPhase::begin
+                } else {
+                    ScrollEventPhase::update
+                };
+                MouseEvent::MouseWheel { pos: self.cursor_position, delta, phase }
+            }
+            _ => return,
+        };
+        self.dispatch_mouse_event(window, event);
+    }
+
+    fn process_touch_event(&mut self, event: TouchEvent, window: &Rc<Window>) {
+        // Only the first finger that touches the screen acts as a mouse
+        let event = match event {
+            TouchEvent::Down(event) if self.touch_slot.is_none() => {
+                self.touch_slot = Some(event.seat_slot());
+                self.cursor_position = Point::new(
+                    event.x_transformed(self.screen_size.width as u32) as f32,
+                    event.y_transformed(self.screen_size.height as u32) as f32,
+                );
+                self.dispatch_mouse_event(
+                    window,
+                    MouseEvent::MouseMoved { pos: self.cursor_position },
+                );
+                MouseEvent::MousePressed {
+                    pos: self.cursor_position,
+                    button: PointerEventButton::left,
+                }
+            }
+            TouchEvent::Motion(event) if self.touch_slot == Some(event.seat_slot()) => {
+                self.cursor_position = Point::new(
+                    event.x_transformed(self.screen_size.width as u32) as f32,
+                    event.y_transformed(self.screen_size.height as u32) as f32,
+                );
+                MouseEvent::MouseMoved { pos: self.cursor_position }
+            }
+            TouchEvent::Up(event) if self.touch_slot == Some(event.seat_slot()) => {
+                self.touch_slot = None;
+                MouseEvent::MouseReleased {
+                    pos: self.cursor_position,
+                    button: PointerEventButton::left,
+                }
+            }
+            TouchEvent::Cancel(_) if self.touch_slot.is_some() => {
+                self.touch_slot = None;
+                MouseEvent::MouseExit
+            }
+            _ => return,
+        };
+        self.dispatch_mouse_event(window, event);
+    }
+
+    fn process_keyboard_event(&mut self, event: KeyboardEvent, window: &Rc<Window>) {
+        // The evdev key codes are offset by 8 in the X11 and xkb key codes
+        let key_code = xkb::Keycode::new(event.key() + 8);
+        let text = key_text(&self.xkb_state, key_code);
+        let (event_type, direction) = match event.key_state() {
+            KeyState::Pressed => (KeyEventType::KeyPressed, xkb::KeyDirection::Down),
+            KeyState::Released => (KeyEventType::KeyReleased, xkb::KeyDirection::Up),
+        };
+        self.xkb_state.update_key(key_code, direction);
+
+        let text = match text {
+            Some(text) => text,
+            None => return,
+        };
+        let event = KeyEvent {
+            modifiers: self.keyboard_modifiers(),
+            text,
+            event_type,
+            scan_code: event.key() as i32,
+            is_auto_repeat: false,
+        };
+        window.clone().process_key_input(&event);
+
+        // libinput doesn't repeat the keys that are held down, so do it with a timer
+        match event_type {
+            KeyEventType::KeyPressed if self.xkb_state.get_keymap().key_repeats(key_code) => {
+                let window = Rc::downgrade(window);
+                let timer = Rc::downgrade(&self.key_repeat_timer);
+                self.key_repeat_timer.start(TimerMode::SingleShot, KEY_REPEAT_DELAY, move || {
+                    repeat_key(&timer, &window, &event)
+                });
+            }
+            _ => self.key_repeat_timer.stop(),
+        }
+    }
+
+    fn keyboard_modifiers(&self) -> KeyboardModifiers {
+        let is_active = |name| self.xkb_state.mod_name_is_active(name, xkb::STATE_MODS_EFFECTIVE);
+        KeyboardModifiers {
+            alt: is_active(xkb::MOD_NAME_ALT),
+            control: is_active(xkb::MOD_NAME_CTRL),
+            meta: is_active(xkb::MOD_NAME_LOGO),
+            shift: is_active(xkb::MOD_NAME_SHIFT),
+        }
+    }
+
+    /// Sends the mouse event, with the position mapped from the display to the window, which may be
+    /// rotated, and converted to logical pixels
+    fn dispatch_mouse_event(&self, window: &Rc<Window>, mut event: MouseEvent) {
+        if let Some(position) = event.pos() {
+            let rotation = window
+                .as_any()
+                .downcast_ref::<crate::KmsWindow>()
+                .map_or(Default::default(), |kms_window| kms_window.renderer.rendering_rotation());
+            let window_position =
+                rotation.map_point_to_window(position, self.screen_size.cast().to_untyped());
+            event.translate(window_position / window.scale_factor() - position);
+        }
+        window.clone().process_mouse_input(event);
+    }
+
+    fn move_cursor(&mut self, position: Point) {
+        self.cursor_position = Point::new(
+            position.x.clamp(0., self.screen_size.width as f32 - 1.),
+            position.y.clamp(0., self.screen_size.height as f32 - 1.),
+        );
+    }
+}
+
+fn repeat_key(timer: &Weak<Timer>, window: &Weak<Window>, event: &KeyEvent) {
+    let (timer, window) = match (timer.upgrade(), window.upgrade()) {
+        (Some(timer), Some(window)) => (timer, window),
+        _ => return,
+    };
+    let event = KeyEvent { is_auto_repeat: true, ..event.clone() };
+    window.clone().process_key_input(&event);
+    let window = Rc::downgrade(&window);
+    timer.start(TimerMode::Repeated, KEY_REPEAT_INTERVAL, move || {
+        if let Some(window) = window.upgrade() {
+            window.process_key_input(&event)
+        }
+    });
+}
+
+/// Returns the text of the key, or None for the keys that don't produce any text, like the
+/// modifiers. The special keys are mapped to the characters of [`key_codes`].
+#[allow(non_upper_case_globals)] // the keysym constants keep the names of xkbcommon
+fn key_text(state: &xkb::State, key_code: xkb::Keycode) -> Option<SharedString> {
+    use xkb::keysyms::*;
+    let keysym = state.key_get_one_sym(key_code);
+    let special_key = match keysym.raw() {
+        KEY_BackSpace => key_codes::Backspace,
+        KEY_Tab => key_codes::Tab,
+        KEY_ISO_Left_Tab => key_codes::Backtab,
+        KEY_Return | KEY_KP_Enter => key_codes::Return,
+        KEY_Escape => key_codes::Escape,
+        KEY_Delete | KEY_KP_Delete => key_codes::Delete,
+        KEY_Up | KEY_KP_Up => key_codes::UpArrow,
+        KEY_Down | KEY_KP_Down => key_codes::DownArrow,
+        KEY_Left | KEY_KP_Left => key_codes::LeftArrow,
+        KEY_Right | KEY_KP_Right => key_codes::RightArrow,
+        KEY_Insert | KEY_KP_Insert => key_codes::Insert,
+        KEY_Home | KEY_KP_Home => key_codes::Home,
+        KEY_End | KEY_KP_End => key_codes::End,
+        KEY_Page_Up | KEY_KP_Page_Up => key_codes::PageUp,
+        KEY_Page_Down | KEY_KP_Page_Down => key_codes::PageDown,
+        KEY_Menu => key_codes::Menu,
+        KEY_F1 => key_codes::F1,
+        KEY_F2 => key_codes::F2,
+        KEY_F3 => key_codes::F3,
+        KEY_F4 => key_codes::F4,
+        KEY_F5 => key_codes::F5,
+        KEY_F6 => key_codes::F6,
+        KEY_F7 => key_codes::F7,
+        KEY_F8 => key_codes::F8,
+        KEY_F9 => key_codes::F9,
+        KEY_F10 => key_codes::F10,
+        KEY_F11 => key_codes::F11,
+        KEY_F12 => key_codes::F12,
+        _ => {
+            let text = state.key_get_utf8(key_code);
+            if !text.is_empty() && !text.starts_with(char::is_control) {
+                return Some(text.as_str().into());
+            }
+            // With the Control modifier, xkb produces control characters. Use the character of
+            // the key instead, so that shortcuts like Control+C work.
+            let character = char::from_u32(xkb::keysym_to_utf32(keysym))
+                .filter(|character| *character != '\0' && !character.is_control())?;
+            return Some(String::from(character).into());
+        }
+    };
+    Some(String::from(special_key).into())
+}
@@ -0,0 +1,418 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+#![doc = include_str!("README.md")]
+#![doc(html_logo_url = "https://slint-ui.com/logo/slint-logo-square-light.svg")]
+
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::sync::Mutex;
+
+use i_slint_core::api::DisplayPower;
+use i_slint_core::component::ComponentRc;
+use i_slint_core::graphics::{Color, Point, Rect, RenderingRotation, Size};
+use i_slint_core::items::{Item, ItemRef, WindowItem};
+use i_slint_core::swrenderer::{RepaintBufferType, SoftwareRenderer};
+use i_slint_core::window::{PlatformWindow, Window};
+use i_slint_core::{Coord, ImageInner, StaticTextures};
+
+mod backlight;
+mod display;
+mod input;
+
+thread_local! {
+    static DISPLAY: RefCell<Option<display::Display>> = RefCell::new(None);
+    static WINDOW: RefCell<Option<Rc<KmsWindow>>> = RefCell::new(None);
+}
+
+/// Calls `f` with the display, which is opened the first time
+fn with_display<T>(f: impl FnOnce(&mut display::Display) -> T) -> T {
+    DISPLAY.with(|display| {
+        let mut display = display.borrow_mut();
+        let display = display.get_or_insert_with(|| {
+            display::Display::open()
+                .unwrap_or_else(|err| panic!("Could not open the display: {}", err))
+        });
+        f(display)
+    })
+}
+
+/// The window covering the whole screen
+pub struct KmsWindow {
+    self_weak: Weak<Window>,
+    background_color: Cell<Color>,
+    needs_redraw: Cell<bool>,
+    renderer: SoftwareRenderer,
+    backlight: Option<backlight::Backlight>,
+}
+
+impl PlatformWindow for KmsWindow {
+    fn show(self: Rc<Self>) {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        runtime_window.set_scale_factor(
+            std::env::var("SLINT_SCALE_FACTOR").ok().and_then(|x| x.parse().ok()).unwrap_or(1.),
+        );
+        runtime_window.scale_factor_property().set_constant();
+        if let Some(rotation) = std::env::var("SLINT_ROTATION")
+            .ok()
+            .and_then(|x| x.parse().ok())
+            .and_then(RenderingRotation::from_degrees)
+        {
+            self.renderer.set_rendering_rotation(rotation);
+        }
+        self.update_window_geometry();
+        self.needs_redraw.set(true);
+        WINDOW.with(|window| *window.borrow_mut() = Some(self))
+    }
+
+    fn hide(self: Rc<Self>) {
+        WINDOW.with(|window| *window.borrow_mut() = None)
+    }
+
+    fn request_redraw(&self) {
+        self.needs_redraw.set(true)
+    }
+
+    fn free_graphics_resources<'a>(&self, items: &mut dyn Iterator<Item = Pin<ItemRef<'a>>>) {
+        self.renderer.free_graphics_resources(items)
+    }
+
+    fn show_popup(&self, popup: &ComponentRc, position: Point) {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        let size = runtime_window.set_active_popup(i_slint_core::window::PopupWindow {
+            location: i_slint_core::window::PopupWindowLocation::ChildWindow(position),
+            component: popup.clone(),
+        });
+
+        let popup = ComponentRc::borrow_pin(popup);
+        let popup_root = popup.as_ref().get_item_ref(0);
+        if let Some(window_item) = ItemRef::downcast_pin(popup_root) {
+            let width_property = WindowItem::FIELD_OFFSETS.width.apply_pin(window_item);
+            let height_property = WindowItem::FIELD_OFFSETS.height.apply_pin(window_item);
+            width_property.set(size.width);
+            height_property.set(size.height);
+        }
+    }
+
+    fn close_popup(&self, popup: &i_slint_core::window::PopupWindow) {
+        if let i_slint_core::window::PopupWindowLocation::ChildWindow(offset) = popup.location {
+            let popup_component = ComponentRc::borrow_pin(&popup.component);
+            let popup_root = popup_component.as_ref().get_item_ref(0);
+            if let Some(window_item) = ItemRef::downcast_pin::<WindowItem>(popup_root) {
+                let popup_region =
+                    i_slint_core::properties::evaluate_no_tracking(|| window_item.geometry())
+                        .translate(offset.to_vector());
+
+                if !popup_region.is_empty() {
+                    self.renderer.mark_dirty_region(popup_region.to_box2d());
+                }
+            }
+        }
+    }
+
+    fn request_window_properties_update(&self) {}
+
+    fn apply_window_properties(&self, window_item: Pin<&WindowItem>) {
+        self.background_color.set(window_item.background());
+    }
+
+    fn apply_geometry_constraint(
+        &self,
+        _constraints_horizontal: i_slint_core::layout::LayoutInfo,
+        _constraints_vertical: i_slint_core::layout::LayoutInfo,
+    ) {
+    }
+
+    fn set_mouse_cursor(&self, _cursor: i_slint_core::items::MouseCursor) {}
+
+    fn text_size(
+        &self,
+        font_request: i_slint_core::graphics::FontRequest,
+        text: &str,
+        max_width: Option<Coord>,
+    ) -> Size {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        i_slint_core::platform::Renderer::text_size(
+            &self.renderer,
+            font_request.merge(&runtime_window.default_font_properties()),
+            text,
+            max_width,
+            runtime_window.scale_factor(),
+        )
+    }
+
+    fn text_min_content_width(
+        &self,
+        font_request: i_slint_core::graphics::FontRequest,
+        text: &str,
+    ) -> Coord {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        i_slint_core::platform::Renderer::text_min_content_width(
+            &self.renderer,
+            font_request.merge(&runtime_window.default_font_properties()),
+            text,
+            runtime_window.scale_factor(),
+        )
+    }
+
+    fn text_input_byte_offset_for_position(
+        &self,
+        _text_input: Pin<&i_slint_core::items::TextInput>,
+        _pos: Point,
+    ) -> usize {
+        0
+    }
+
+    fn text_input_cursor_rect_for_byte_offset(
+        &self,
+        _text_input: Pin<&i_slint_core::items::TextInput>,
+        _byte_offset: usize,
+    ) -> Rect {
+        Default::default()
+    }
+
+    fn set_rendering_rotation(&self, rotation: RenderingRotation) {
+        self.renderer.set_rendering_rotation(rotation);
+        self.update_window_geometry();
+        self.needs_redraw.set(true);
+    }
+
+    fn set_display_power(&self, power: DisplayPower) {
+        let brightness = match power {
+            DisplayPower::On => 1.,
+            DisplayPower::Dimmed(brightness) => brightness,
+            DisplayPower::Off => 0.,
+        };
+        if let Some(backlight) = &self.backlight {
+            backlight.set_brightness(brightness);
+        }
+        with_display(|display| display.set_powered_on(power != DisplayPower::Off));
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+}
+
+impl KmsWindow {
+    /// Sets the size of the window to the size of the display, rotated like the rendering
+    fn update_window_geometry(&self) {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        let display_size = with_display(|display| display.size());
+        let size = self.renderer.rendering_rotation().map_size(display_size).to_f32()
+            / runtime_window.scale_factor();
+        runtime_window.set_window_item_geometry(size.width as _, size.height as _);
+    }
+
+    fn draw(&self, display: &mut display::Display) {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        runtime_window.update_window_properties();
+        let size = display.size();
+        let background = self.background_color.get();
+        display.present_frame(|line_buffer| {
+            self.renderer.render_by_line(&runtime_window.into(), size, background, line_buffer);
+        });
+    }
+}
+
+enum KmsEvent {
+    Custom(Box<dyn FnOnce() + Send>),
+    Quit,
+}
+
+/// The backend that shows the window full screen with DRM/KMS, and reads the input with libinput
+pub struct Backend {
+    event_queue: Mutex<VecDeque<KmsEvent>>,
+    clipboard: Mutex<String>,
+    /// A byte is written to the first socket to wake up the event loop, which polls the second one
+    wake_up_sockets: (UnixStream, UnixStream),
+}
+
+impl Backend {
+    fn new() -> Self {
+        let wake_up_sockets = UnixStream::pair().expect("Could not create the wake up sockets");
+        for socket in [&wake_up_sockets.0, &wake_up_sockets.1] {
+            socket.set_nonblocking(true).expect("Could not set up the wake up sockets");
+        }
+        Self { event_queue: Default::default(), clipboard: Default::default(), wake_up_sockets }
+    }
+
+    fn post_kms_event(&self, event: KmsEvent) {
+        self.event_queue.lock().unwrap().push_back(event);
+        // If the socket is full, the event loop will wake up anyway
+        let _ = (&self.wake_up_sockets.0).write(&[0]);
+    }
+}
+
+impl i_slint_core::backend::Backend for Backend {
+    fn create_window(&'static self) -> Rc<Window> {
+        Window::new(|window| {
+            Rc::new(KmsWindow {
+                self_weak: window.clone(),
+                background_color: Color::from_rgb_u8(0, 0, 0).into(),
+                needs_redraw: Cell::new(true),
+                // The two buffers of the display are swapped after each frame
+                renderer: SoftwareRenderer::new(RepaintBufferType::SwappedBuffers),
+                backlight: backlight::Backlight::open(),
+            })
+        })
+    }
+
+    fn run_event_loop(&'static self, behavior: i_slint_core::backend::EventLoopQuitBehavior) {
+        use nix::poll::{PollFd, PollFlags};
+
+        let mut input = input::Input::new(with_display(|display| display.size()))
+            .unwrap_or_else(|err| panic!("Could not read the input devices: {}", err));
+        let _running_guard = i_slint_core::event_loop::enter_event_loop();
+
+        loop {
+            i_slint_core::platform::update_timers_and_animations();
+
+            loop {
+                let event = self.event_queue.lock().unwrap().pop_front();
+                match event {
+                    Some(KmsEvent::Quit) => return,
+                    Some(KmsEvent::Custom(event)) => event(),
+                    None => break,
+                }
+            }
+
+            let window = WINDOW.with(|window| window.borrow().clone());
+            if window.is_none()
+                && matches!(
+                    behavior,
+                    i_slint_core::backend::EventLoopQuitBehavior::QuitOnLastWindowClosed
+                )
+            {
+                return;
+            }
+
+            let (display_fd, ready_for_frame, powered_on) = with_display(|display| {
+                if let Some(window) = &window {
+                    // The window is drawn when the display is turned on again
+                    if display.is_powered_on()
+                        && display.ready_for_frame()
+                        && window.needs_redraw.take()
+                    {
+                        window.draw(display);
+                    }
+                }
+                (display.fd().as_raw_fd(), display.ready_for_frame(), display.is_powered_on())
+            });
+
+            if i_slint_core::platform::duration_until_next_timer_update()
+                .map_or(true, |timeout| !timeout.is_zero())
+            {
+                i_slint_core::event_loop::invoke_idle_callbacks();
+            }
+
+            // While the previous frame is still on its way to the screen, the animations wait
+            // for it instead of spinning
+            let timeout = match i_slint_core::platform::duration_until_next_timer_update() {
+                Some(timeout) if timeout.is_zero() && !ready_for_frame => -1,
+                // There are no frames while the display is off, so the animations go on at about
+                // 60 steps per second
+                Some(timeout) if timeout.is_zero() && !powered_on => 16,
+                Some(timeout) => timeout.as_millis().try_into().unwrap_or(i32::MAX),
+                None => -1,
+            };
+
+            let mut poll_fds = [
+                PollFd::new(display_fd, PollFlags::POLLIN),
+                PollFd::new(input.fd().as_raw_fd(), PollFlags::POLLIN),
+                PollFd::new(self.wake_up_sockets.1.as_raw_fd(), PollFlags::POLLIN),
+            ];
+            match nix::poll::poll(&mut poll_fds, timeout) {
+                Ok(_) => {}
+                Err(nix::errno::Errno::EINTR) => continue,
+                Err(err) => panic!("Could not wait for the events: {}", err),
+            }
+            let is_readable = |poll_fd: &PollFd| {
+                poll_fd.revents().map_or(false, |revents| revents.contains(PollFlags::POLLIN))
+            };
+
+            if is_readable(&poll_fds[0]) {
+                with_display(|display| display.process_events());
+            }
+            if is_readable(&poll_fds[1]) {
+                // The events may open or close windows, so the display must not be borrowed
+                match window.and_then(|window| window.self_weak.upgrade()) {
+                    Some(runtime_window) => input.process_events(&runtime_window),
+                    None => input.discard_events(),
+                }
+            }
+            if is_readable(&poll_fds[2]) {
+                let mut buffer = [0; 16];
+                while matches!((&self.wake_up_sockets.1).read(&mut buffer), Ok(n) if n > 0) {}
+            }
+        }
+    }
+
+    fn quit_event_loop(&'static self) {
+        self.post_kms_event(KmsEvent::Quit)
+    }
+
+    fn register_font_from_memory(
+        &'static self,
+        _data: &'static [u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("The software renderer can only draw the fonts embedded by the compiler".into())
+    }
+
+    fn register_font_from_path(
+        &'static self,
+        _path: &std::path::Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("The software renderer can only draw the fonts embedded by the compiler".into())
+    }
+
+    fn register_bitmap_font(&'static self, font_data: &'static i_slint_core::graphics::BitmapFont) {
+        i_slint_core::swrenderer::register_bitmap_font(font_data);
+    }
+
+    fn set_clipboard_text(&'static self, text: String) {
+        *self.clipboard.lock().unwrap() = text;
+    }
+
+    fn clipboard_text(&'static self) -> Option<String> {
+        let clipboard = self.clipboard.lock().unwrap();
+        (!clipboard.is_empty()).then(|| clipboard.clone())
+    }
+
+    fn post_event(&'static self, event: Box<dyn FnOnce() + Send>) {
+        self.post_kms_event(KmsEvent::Custom(event))
+    }
+
+    fn image_size(
+        &'static self,
+        image: &i_slint_core::graphics::Image,
+    ) -> i_slint_core::graphics::IntSize {
+        let inner: &ImageInner = image.into();
+        match inner {
+            ImageInner::None => Default::default(),
+            ImageInner::AbsoluteFilePath(_) | ImageInner::EmbeddedData { .. } => {
+                unimplemented!(
+                    "The software renderer can only draw the images embedded by the compiler"
+                )
+            }
+            ImageInner::EmbeddedImage(buffer) => buffer.size(),
+            ImageInner::StaticTextures(StaticTextures { original_size, .. }) => *original_size,
+            ImageInner::VideoStream(_) => image.size(),
+        }
+    }
+}
+
+pub type NativeWidgets = ();
+pub type NativeGlobals = ();
+pub mod native_widgets {}
+pub const HAS_NATIVE_STYLE: bool = false;
+
+/// Installs this backend. The display is opened when the first window is shown.
+pub fn init() {
+    i_slint_core::backend::instance_or_init(|| Box::new(Backend::new()));
+}
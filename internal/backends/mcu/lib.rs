@@ -27,20 +27,20 @@
 #[cfg(feature = "simulator")]
 use simulator::event_loop;
 
-mod fonts;
-mod lengths;
-mod renderer;
+#[cfg(feature = "screenshots")]
+pub mod screenshots;
 
-use lengths::*;
+use i_slint_core::lengths::*;
+use i_slint_core::swrenderer::{LineBufferProvider, RepaintBufferType, SoftwareRenderer};
 
 /// The Pixel type of the backing store
-pub type TargetPixel = embedded_graphics::pixelcolor::Rgb565;
+pub type TargetPixel = i_slint_core::swrenderer::Rgb565Pixel;
 
 pub trait Devices {
     fn screen_size(&self) -> PhysicalSize;
-    /// Called before the frame is being drawn, with the dirty region. Return the actual dirty region
-    fn prepare_frame(&mut self, dirty_region: PhysicalRect) -> PhysicalRect {
-        dirty_region
+    /// Tells whether the frame buffer is the same for every frame, or swapped after each frame
+    fn repaint_buffer_type(&self) -> RepaintBufferType {
+        RepaintBufferType::ReusedBuffer
     }
     fn fill_region(&mut self, region: PhysicalRect, pixels: &[TargetPixel]);
     fn flush_frame(&mut self) {}
@@ -56,7 +56,7 @@ fn time(&self) -> core::time::Duration {
 impl<T: embedded_graphics::draw_target::DrawTarget> crate::Devices for T
 where
     T::Error: core::fmt::Debug,
-    T::Color: core::convert::From<TargetPixel>,
+    T::Color: core::convert::From<embedded_graphics::pixelcolor::Rgb565>,
 {
     fn screen_size(&self) -> PhysicalSize {
         let s = self.bounding_box().size;
@@ -70,7 +70,11 @@ fn fill_region(&mut self, region: PhysicalRect, pixels: &[TargetPixel]) {
                     Point::new(region.origin.x as i32, region.origin.y as i32),
                     Size::new(region.size.width as u32, region.size.height as u32),
                 ),
-                pixels.iter().copied(),
+                pixels.iter().map(|pixel| {
+                    embedded_graphics::pixelcolor::Rgb565::from(
+                        embedded_graphics::pixelcolor::raw::RawU16::new(pixel.0),
+                    )
+                }),
             )
             .unwrap()
     }
@@ -80,7 +84,10 @@ fn debug(&mut self, text: &str) {
             mono_font::{ascii, MonoTextStyle},
             text::Text,
         };
-        let style = MonoTextStyle::new(&ascii::FONT_8X13, TargetPixel::RED.into());
+        let style = MonoTextStyle::new(
+            &ascii::FONT_8X13,
+            embedded_graphics::pixelcolor::Rgb565::RED.into(),
+        );
         thread_local! { static LINE: core::cell::Cell<i16>  = core::cell::Cell::new(0) }
         LINE.with(|l| {
             let line = (l.get() + 1) % (self.screen_size().height / 13 - 2);
@@ -91,7 +98,48 @@ fn debug(&mut self, text: &str) {
 }
 
 thread_local! { static DEVICES: RefCell<Option<Box<dyn Devices + 'static>>> = RefCell::new(None) }
-thread_local! { static PARTIAL_RENDERING_CACHE: RefCell<i_slint_core::item_rendering::PartialRenderingCache> = RefCell::new(Default::default()) }
+
+/// Sends the lines drawn by the renderer to the devices
+struct DevicesLineBuffer<'a> {
+    devices: &'a mut dyn Devices,
+    line_buffer: alloc::vec::Vec<TargetPixel>,
+}
+
+impl LineBufferProvider for DevicesLineBuffer<'_> {
+    type TargetPixel = TargetPixel;
+
+    fn process_line(
+        &mut self,
+        line: usize,
+        range: core::ops::Range<usize>,
+        render_fn: impl FnOnce(&mut [TargetPixel]),
+    ) {
+        let region = euclid::rect(range.start as i16, line as i16, range.len() as i16, 1);
+        let pixels = &mut self.line_buffer[range];
+        render_fn(pixels);
+        self.devices.fill_region(region, pixels);
+    }
+}
+
+/// Renders the dirty region of the window to the devices, and flushes the frame
+fn render_window_frame(
+    renderer: &SoftwareRenderer,
+    runtime_window: alloc::rc::Rc<i_slint_core::window::Window>,
+    background: i_slint_core::Color,
+    devices: &mut dyn Devices,
+) {
+    let profiler = profiler::Timer::new(devices);
+    let size = devices.screen_size();
+    let line_buffer = alloc::vec![TargetPixel::default(); size.width as usize];
+    renderer.render_by_line(
+        &runtime_window.into(),
+        size,
+        background,
+        DevicesLineBuffer { devices: &mut *devices, line_buffer },
+    );
+    profiler.stop_profiling(devices, "render");
+    devices.flush_frame();
+}
 
 mod the_backend {
     use super::*;
@@ -114,7 +162,7 @@ pub struct McuWindow {
         backend: &'static MCUBackend,
         self_weak: Weak<Window>,
         background_color: Cell<Color>,
-        initial_dirty_region_for_next_frame: Cell<i_slint_core::item_rendering::DirtyRegion>,
+        renderer: SoftwareRenderer,
     }
 
     impl PlatformWindow for McuWindow {
@@ -136,13 +184,7 @@ fn free_graphics_resources<'a>(
             &self,
             items: &mut dyn Iterator<Item = Pin<i_slint_core::items::ItemRef<'a>>>,
         ) {
-            super::PARTIAL_RENDERING_CACHE.with(|cache| {
-                for item in items {
-                    let cache_entry =
-                        item.cached_rendering_data_offset().release(&mut cache.borrow_mut());
-                    drop(cache_entry);
-                }
-            });
+            self.renderer.free_graphics_resources(items)
         }
 
         fn show_popup(&self, popup: &ComponentRc, position: i_slint_core::graphics::Point) {
@@ -177,11 +219,7 @@ fn close_popup(&self, popup: &i_slint_core::window::PopupWindow) {
                         .translate(offset.to_vector());
 
                         if !popup_region.is_empty() {
-                            self.initial_dirty_region_for_next_frame.set(
-                                self.initial_dirty_region_for_next_frame
-                                    .get()
-                                    .union(&popup_region.to_box2d()),
-                            );
+                            self.renderer.mark_dirty_region(popup_region.to_box2d());
                         }
                     }
                 }
@@ -206,13 +244,27 @@ fn text_size(
             max_width: Option<Coord>,
         ) -> Size {
             let runtime_window = self.self_weak.upgrade().unwrap();
-            crate::fonts::text_size(
+            i_slint_core::platform::Renderer::text_size(
+                &self.renderer,
                 font_request.merge(&runtime_window.default_font_properties()),
                 text,
                 max_width,
-                ScaleFactor::new(runtime_window.scale_factor()),
+                runtime_window.scale_factor(),
+            )
+        }
+
+        fn text_min_content_width(
+            &self,
+            font_request: i_slint_core::graphics::FontRequest,
+            text: &str,
+        ) -> Coord {
+            let runtime_window = self.self_weak.upgrade().unwrap();
+            i_slint_core::platform::Renderer::text_min_content_width(
+                &self.renderer,
+                font_request.merge(&runtime_window.default_font_properties()),
+                text,
+                runtime_window.scale_factor(),
             )
-            .to_untyped()
         }
 
         fn text_input_byte_offset_for_position(
@@ -285,17 +337,12 @@ fn draw(&self, window: Rc<McuWindow>) {
                 let devices = devices.as_mut().unwrap();
                 let size = devices.screen_size().to_f32() / runtime_window.scale_factor();
                 runtime_window.set_window_item_geometry(size.width as _, size.height as _);
-                let background =
-                    crate::renderer::to_rgb888_color_discard_alpha(window.background_color.get());
-                PARTIAL_RENDERING_CACHE.with(|cache| {
-                    crate::renderer::render_window_frame(
-                        runtime_window,
-                        background.into(),
-                        &mut **devices,
-                        window.initial_dirty_region_for_next_frame.take(),
-                        &mut cache.borrow_mut(),
-                    )
-                });
+                crate::render_window_frame(
+                    &window.renderer,
+                    runtime_window,
+                    window.background_color.get(),
+                    &mut **devices,
+                );
             });
         }
     }
@@ -307,12 +354,18 @@ fn create_window(&'static self) -> Rc<i_slint_core::window::Window> {
                     backend: self,
                     self_weak: window.clone(),
                     background_color: Color::from_rgb_u8(0, 0, 0).into(),
-                    initial_dirty_region_for_next_frame: Default::default(),
+                    renderer: SoftwareRenderer::new(DEVICES.with(|devices| {
+                        devices
+                            .borrow()
+                            .as_ref()
+                            .map_or(Default::default(), |devices| devices.repaint_buffer_type())
+                    })),
                 })
             })
         }
 
         fn run_event_loop(&'static self, behavior: i_slint_core::backend::EventLoopQuitBehavior) {
+            let _running_guard = i_slint_core::event_loop::enter_event_loop();
             loop {
                 i_slint_core::timers::TimerList::maybe_activate_timers();
                 i_slint_core::animations::update_animations();
@@ -325,6 +378,7 @@ fn run_event_loop(&'static self, behavior: i_slint_core::backend::EventLoopQuitB
                         }
                     }
                     None => {
+                        i_slint_core::event_loop::invoke_idle_callbacks();
                         // TODO: sleep();
                     }
                 }
@@ -360,7 +414,7 @@ fn register_bitmap_font(
             &'static self,
             font_data: &'static i_slint_core::graphics::BitmapFont,
         ) {
-            crate::fonts::register_bitmap_font(font_data);
+            i_slint_core::swrenderer::register_bitmap_font(font_data);
         }
 
         fn set_clipboard_text(&'static self, text: String) {
@@ -388,6 +442,7 @@ fn image_size(
                 }
                 ImageInner::EmbeddedImage(buffer) => buffer.size(),
                 ImageInner::StaticTextures(StaticTextures { original_size, .. }) => *original_size,
+                ImageInner::VideoStream(_) => image.size(),
             }
         }
 
@@ -0,0 +1,237 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Helpers for screenshot based regression tests.
+
+[`grab_window`] renders a window with the software renderer of this crate into an image, at a fixed
+size and scale factor. The text is rendered with the bitmap fonts that the compiler embeds when the
+resources are embedded (as for the MCU), so the result does not depend on the fonts installed on the
+machine running the tests.
+
+[`assert_screenshot`] compares such a screenshot with a reference PNG image. When they differ by more than
+the tolerance, the screenshot and an image highlighting the differing pixels are written next to the
+reference image, with the `.actual.png` and `.diff.png` extensions. Set the `SLINT_CREATE_SCREENSHOTS`
+environment variable to create or update the reference images instead.
+*/
+
+use crate::{Devices, TargetPixel};
+use i_slint_core::lengths::{PhysicalRect, PhysicalSize};
+use i_slint_core::swrenderer::SoftwareRenderer;
+use i_slint_core::window::WindowHandleAccess;
+use image::{Rgb, RgbImage};
+use std::path::{Path, PathBuf};
+
+/// The options of [`assert_screenshot`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenshotOptions {
+    /// The width of the screenshot, in physical pixels
+    pub width: u32,
+    /// The height of the screenshot, in physical pixels
+    pub height: u32,
+    /// The scale factor used to render the window
+    pub scale_factor: f32,
+    /// The maximum difference, per color channel, for two pixels to be considered the same
+    pub tolerance: u8,
+    /// The number of pixels that may differ before the screenshot is considered different
+    pub max_differing_pixels: usize,
+}
+
+impl Default for ScreenshotOptions {
+    fn default() -> Self {
+        Self { width: 320, height: 240, scale_factor: 1., tolerance: 2, max_differing_pixels: 0 }
+    }
+}
+
+/// A device that stores the rendered pixels in an image
+struct ImageDevice {
+    image: RgbImage,
+}
+
+impl Devices for ImageDevice {
+    fn screen_size(&self) -> PhysicalSize {
+        PhysicalSize::new(self.image.width() as i16, self.image.height() as i16)
+    }
+
+    fn fill_region(&mut self, region: PhysicalRect, pixels: &[TargetPixel]) {
+        let width = region.size.width.max(1) as usize;
+        for (i, pixel) in pixels.iter().enumerate() {
+            let x = region.origin.x as u32 + (i % width) as u32;
+            let y = region.origin.y as u32 + (i / width) as u32;
+            let color = rgb::RGB8::from(*pixel);
+            self.image.put_pixel(x, y, Rgb([color.r, color.g, color.b]));
+        }
+    }
+
+    fn debug(&mut self, text: &str) {
+        eprintln!("{}", text);
+    }
+}
+
+/// Renders the window into an image of the given size, in physical pixels, with the given scale factor.
+///
+/// The whole window is rendered, regardless of what was rendered before.
+pub fn grab_window(
+    window: &i_slint_core::api::Window,
+    width: u32,
+    height: u32,
+    scale_factor: f32,
+) -> RgbImage {
+    let runtime_window = window.window_handle().clone();
+    if runtime_window.scale_factor() != scale_factor {
+        runtime_window.set_scale_factor(scale_factor);
+    }
+    runtime_window.set_window_item_geometry(
+        (width as f32 / scale_factor) as _,
+        (height as f32 / scale_factor) as _,
+    );
+
+    let background = runtime_window
+        .try_component()
+        .and_then(|component| {
+            let component = i_slint_core::component::ComponentRc::borrow_pin(&component);
+            let root_item = component.as_ref().get_item_ref(0);
+            i_slint_core::items::ItemRef::downcast_pin::<i_slint_core::items::WindowItem>(root_item)
+                .map(|window_item| window_item.background())
+        })
+        .unwrap_or_default();
+
+    let mut device = ImageDevice { image: RgbImage::new(width, height) };
+    let whole_window = i_slint_core::item_rendering::DirtyRegion::new(
+        euclid::point2(0 as _, 0 as _),
+        euclid::point2((width as f32 / scale_factor) as _, (height as f32 / scale_factor) as _),
+    );
+    let renderer = SoftwareRenderer::default();
+    renderer.mark_dirty_region(whole_window);
+    crate::render_window_frame(&renderer, runtime_window, background, &mut device);
+    device.image
+}
+
+/// The difference between a screenshot and its reference image, as computed by [`compare_images`]
+pub struct ImageDifference {
+    /// The number of pixels that differ by more than the tolerance
+    pub differing_pixels: usize,
+    /// An image showing the differing pixels in red over a faded version of the reference image
+    pub diff_image: RgbImage,
+}
+
+/// Compares two images, pixel by pixel. Two pixels are considered the same if none of their color
+/// channels differs by more than `tolerance`. If the images don't have the same size, the pixels
+/// that are only in one of them are different.
+pub fn compare_images(actual: &RgbImage, reference: &RgbImage, tolerance: u8) -> ImageDifference {
+    let width = actual.width().max(reference.width());
+    let height = actual.height().max(reference.height());
+    let mut diff_image = RgbImage::new(width, height);
+    let mut differing_pixels = 0;
+    for y in 0..height {
+        for x in 0..width {
+            let actual_pixel = actual.get_pixel_checked(x, y);
+            let reference_pixel = reference.get_pixel_checked(x, y);
+            let same = match (actual_pixel, reference_pixel) {
+                (Some(a), Some(r)) => {
+                    a.0.iter()
+                        .zip(r.0.iter())
+                        .all(|(a, r)| (*a as i16 - *r as i16).abs() <= tolerance as i16)
+                }
+                _ => false,
+            };
+            let diff_pixel = if same {
+                let Rgb([r, g, b]) = *reference_pixel.unwrap();
+                let luma = ((r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000) as u8;
+                let faded = 255 - (255 - luma) / 4;
+                Rgb([faded, faded, faded])
+            } else {
+                differing_pixels += 1;
+                Rgb([255, 0, 0])
+            };
+            diff_image.put_pixel(x, y, diff_pixel);
+        }
+    }
+    ImageDifference { differing_pixels, diff_image }
+}
+
+fn with_extension_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(suffix);
+    path.with_file_name(file_name)
+}
+
+/// Renders the window with [`grab_window`] and compares the result with the PNG image at `reference_path`.
+///
+/// Panics if the reference image cannot be loaded, or if more than `options.max_differing_pixels` pixels
+/// differ by more than `options.tolerance`. In that case, the screenshot and the difference are written
+/// next to the reference image, as `<name>.actual.png` and `<name>.diff.png`.
+///
+/// If the `SLINT_CREATE_SCREENSHOTS` environment variable is set, the screenshot is saved as the
+/// reference image instead.
+pub fn assert_screenshot(
+    window: &i_slint_core::api::Window,
+    reference_path: impl AsRef<Path>,
+    options: ScreenshotOptions,
+) {
+    let reference_path = reference_path.as_ref();
+    let actual = grab_window(window, options.width, options.height, options.scale_factor);
+
+    if std::env::var_os("SLINT_CREATE_SCREENSHOTS").is_some() {
+        if let Some(parent) = reference_path.parent() {
+            std::fs::create_dir_all(parent).unwrap_or_else(|err| {
+                panic!("Could not create the directory {}: {}", parent.display(), err)
+            });
+        }
+        actual.save(reference_path).unwrap_or_else(|err| {
+            panic!("Could not save the screenshot to {}: {}", reference_path.display(), err)
+        });
+        return;
+    }
+
+    let reference = image::open(reference_path)
+        .unwrap_or_else(|err| {
+            panic!(
+                "Could not load the reference image {}: {}. Set SLINT_CREATE_SCREENSHOTS=1 to create it",
+                reference_path.display(),
+                err
+            )
+        })
+        .into_rgb8();
+
+    let difference = compare_images(&actual, &reference, options.tolerance);
+    if difference.differing_pixels > options.max_differing_pixels {
+        let actual_path = with_extension_suffix(reference_path, ".actual.png");
+        let diff_path = with_extension_suffix(reference_path, ".diff.png");
+        // Don't let a failure to write the images hide the comparison failure
+        let _ = actual.save(&actual_path);
+        let _ = difference.diff_image.save(&diff_path);
+        panic!(
+            "The screenshot differs from {}: {} pixels differ (at most {} allowed, with a tolerance of {}). \
+             The screenshot was written to {} and the difference to {}",
+            reference_path.display(),
+            difference.differing_pixels,
+            options.max_differing_pixels,
+            options.tolerance,
+            actual_path.display(),
+            diff_path.display()
+        );
+    }
+}
+
+#[test]
+fn image_comparison() {
+    let mut reference = RgbImage::from_pixel(4, 3, Rgb([10, 20, 30]));
+    reference.put_pixel(1, 1, Rgb([200, 200, 200]));
+
+    let mut actual = reference.clone();
+    assert_eq!(compare_images(&actual, &reference, 0).differing_pixels, 0);
+
+    actual.put_pixel(0, 0, Rgb([12, 20, 30]));
+    assert_eq!(compare_images(&actual, &reference, 2).differing_pixels, 0);
+    let difference = compare_images(&actual, &reference, 1);
+    assert_eq!(difference.differing_pixels, 1);
+    assert_eq!(*difference.diff_image.get_pixel(0, 0), Rgb([255, 0, 0]));
+    assert_ne!(*difference.diff_image.get_pixel(1, 0), Rgb([255, 0, 0]));
+
+    // The pixels outside of the smaller image are different
+    let smaller = RgbImage::from_pixel(4, 2, Rgb([10, 20, 30]));
+    let difference = compare_images(&smaller, &reference, 0);
+    assert_eq!(difference.differing_pixels, 5);
+    assert_eq!(difference.diff_image.dimensions(), (4, 3));
+}
@@ -9,9 +9,9 @@
 use i_slint_core::component::ComponentRc;
 use i_slint_core::graphics::{Image, ImageInner, StaticTextures};
 use i_slint_core::input::KeyboardModifiers;
-use i_slint_core::item_rendering::DirtyRegion;
 use i_slint_core::items::{Item, ItemRef, WindowItem};
 use i_slint_core::layout::Orientation;
+use i_slint_core::swrenderer::SoftwareRenderer;
 use i_slint_core::window::{PlatformWindow, Window};
 use i_slint_core::{Color, Coord};
 use rgb::FromSlice;
@@ -35,7 +35,7 @@ pub struct SimulatorWindow {
     visible: Cell<bool>,
     background_color: Cell<Color>,
     frame_buffer: RefCell<Option<SimulatorDisplay<embedded_graphics::pixelcolor::Rgb888>>>,
-    initial_dirty_region_for_next_frame: Cell<DirtyRegion>,
+    renderer: SoftwareRenderer,
 }
 
 impl SimulatorWindow {
@@ -64,7 +64,7 @@ pub(crate) fn new(window_weak: &Weak<i_slint_core::window::Window>) -> Rc<Self>
             visible: Default::default(),
             background_color: Color::from_rgb_u8(0, 0, 0).into(),
             frame_buffer: RefCell::default(),
-            initial_dirty_region_for_next_frame: Default::default(),
+            renderer: Default::default(),
         });
 
         let runtime_window = window_weak.upgrade().unwrap();
@@ -74,6 +74,10 @@ pub(crate) fn new(window_weak: &Weak<i_slint_core::window::Window>) -> Rc<Self>
     }
 }
 
+fn to_rgb888_color_discard_alpha(col: Color) -> embedded_graphics::pixelcolor::Rgb888 {
+    embedded_graphics::pixelcolor::Rgb888::new(col.red(), col.green(), col.blue())
+}
+
 impl Drop for SimulatorWindow {
     fn drop(&mut self) {
         crate::event_loop::unregister_window(self.opengl_context.window().id());
@@ -141,13 +145,7 @@ fn free_graphics_resources<'a>(
         &self,
         items: &mut dyn Iterator<Item = std::pin::Pin<i_slint_core::items::ItemRef<'a>>>,
     ) {
-        super::PARTIAL_RENDERING_CACHE.with(|cache| {
-            for item in items {
-                let cache_entry =
-                    item.cached_rendering_data_offset().release(&mut cache.borrow_mut());
-                drop(cache_entry);
-            }
-        });
+        self.renderer.free_graphics_resources(items)
     }
 
     fn show_popup(
@@ -185,11 +183,7 @@ fn close_popup(&self, popup: &i_slint_core::window::PopupWindow) {
                             .translate(offset.to_vector());
 
                     if !popup_region.is_empty() {
-                        self.initial_dirty_region_for_next_frame.set(
-                            self.initial_dirty_region_for_next_frame
-                                .get()
-                                .union(&popup_region.to_box2d()),
-                        );
+                        self.renderer.mark_dirty_region(popup_region.to_box2d());
                     }
                 }
             }
@@ -230,13 +224,27 @@ fn text_size(
         max_width: Option<Coord>,
     ) -> i_slint_core::graphics::Size {
         let runtime_window = self.self_weak.upgrade().unwrap();
-        crate::fonts::text_size(
-            font_request.merge(&self.self_weak.upgrade().unwrap().default_font_properties()),
+        i_slint_core::platform::Renderer::text_size(
+            &self.renderer,
+            font_request.merge(&runtime_window.default_font_properties()),
             text,
             max_width,
-            crate::ScaleFactor::new(runtime_window.scale_factor()),
+            runtime_window.scale_factor(),
+        )
+    }
+
+    fn text_min_content_width(
+        &self,
+        font_request: i_slint_core::graphics::FontRequest,
+        text: &str,
+    ) -> Coord {
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        i_slint_core::platform::Renderer::text_min_content_width(
+            &self.renderer,
+            font_request.merge(&runtime_window.default_font_properties()),
+            text,
+            runtime_window.scale_factor(),
         )
-        .to_untyped()
     }
 
     fn text_input_byte_offset_for_position(
@@ -289,8 +297,7 @@ fn draw(self: Rc<Self>) {
                 canvas.set_size(size.width, size.height, 1.0);
             }
 
-            let background =
-                crate::renderer::to_rgb888_color_discard_alpha(self.background_color.get());
+            let background = to_rgb888_color_discard_alpha(self.background_color.get());
 
             let mut frame_buffer = self.frame_buffer.borrow_mut();
             let display = match frame_buffer.as_mut() {
@@ -304,23 +311,28 @@ fn draw(self: Rc<Self>) {
                         width: size.width,
                         height: size.height,
                     }));
-                    super::PARTIAL_RENDERING_CACHE.with(|cache| {
-                        *cache.borrow_mut() = Default::default();
-                    });
+                    // The new buffer doesn't contain anything from the previous frames
+                    let scale_factor = runtime_window.scale_factor();
+                    self.renderer.mark_dirty_region(
+                        euclid::rect(
+                            0.,
+                            0.,
+                            (size.width as f32 / scale_factor) as Coord,
+                            (size.height as f32 / scale_factor) as Coord,
+                        )
+                        .to_box2d(),
+                    );
                     buffer.clear(background).unwrap();
                     buffer
                 }
             };
 
-            super::PARTIAL_RENDERING_CACHE.with(|cache| {
-                crate::renderer::render_window_frame(
-                    runtime_window,
-                    background.into(),
-                    &mut *display,
-                    self.initial_dirty_region_for_next_frame.take(),
-                    &mut cache.borrow_mut(),
-                );
-            });
+            crate::render_window_frame(
+                &self.renderer,
+                runtime_window,
+                self.background_color.get(),
+                &mut *display,
+            );
 
             let output_image = display
                 .to_rgb_output_image(&embedded_graphics_simulator::OutputSettings::default());
@@ -411,7 +423,7 @@ fn register_font_from_path(
     }
 
     fn register_bitmap_font(&'static self, font_data: &'static i_slint_core::graphics::BitmapFont) {
-        crate::fonts::register_bitmap_font(font_data);
+        i_slint_core::swrenderer::register_bitmap_font(font_data);
     }
 
     fn set_clipboard_text(&'static self, _text: String) {
@@ -437,6 +449,7 @@ fn image_size(&'static self, image: &Image) -> i_slint_core::graphics::IntSize {
             ImageInner::AbsoluteFilePath(_) | ImageInner::EmbeddedData { .. } => unimplemented!(),
             ImageInner::EmbeddedImage(buffer) => buffer.size(),
             ImageInner::StaticTextures(StaticTextures { original_size, .. }) => *original_size,
+            ImageInner::VideoStream(_) => image.size(),
         }
     }
 }
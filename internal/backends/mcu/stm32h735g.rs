@@ -6,7 +6,6 @@
 use super::TargetPixel;
 pub use cortex_m_rt::entry;
 use embedded_display_controller::{DisplayController, DisplayControllerLayer};
-use embedded_graphics::prelude::RgbColor;
 use hal::delay::Delay;
 use hal::gpio::Speed::High;
 use hal::ltdc::LtdcLayer1;
@@ -107,10 +106,10 @@ pub fn init() {
 
     #[link_section = ".frame_buffer"]
     static mut FB1: [TargetPixel; DISPLAY_WIDTH * DISPLAY_HEIGHT] =
-        [TargetPixel::BLACK; DISPLAY_WIDTH * DISPLAY_HEIGHT];
+        [TargetPixel(0); DISPLAY_WIDTH * DISPLAY_HEIGHT];
     #[link_section = ".frame_buffer"]
     static mut FB2: [TargetPixel; DISPLAY_WIDTH * DISPLAY_HEIGHT] =
-        [TargetPixel::BLACK; DISPLAY_WIDTH * DISPLAY_HEIGHT];
+        [TargetPixel(0); DISPLAY_WIDTH * DISPLAY_HEIGHT];
     // SAFETY the init function is only called once (as enforced by Peripherals::take)
     let (fb1, fb2) = unsafe { (&mut FB1, &mut FB2) };
 
@@ -212,7 +211,6 @@ pub fn init() {
         delay,
         touch_i2c,
         last_touch: Default::default(),
-        prev_dirty: Default::default(),
     });
 }
 
@@ -224,9 +222,6 @@ struct StmDevices {
     delay: Delay,
     touch_i2c: TouchI2C,
     last_touch: Option<i_slint_core::graphics::Point>,
-
-    /// When using double frame buffer, this is the part still dirty in the other buffer
-    prev_dirty: PhysicalRect,
 }
 
 impl Devices for StmDevices {
@@ -234,8 +229,9 @@ fn screen_size(&self) -> PhysicalSize {
         PhysicalSize::new(DISPLAY_WIDTH as _, DISPLAY_HEIGHT as _)
     }
 
-    fn prepare_frame(&mut self, dirty_region: PhysicalRect) -> PhysicalRect {
-        dirty_region.union(&core::mem::replace(&mut self.prev_dirty, dirty_region))
+    fn repaint_buffer_type(&self) -> i_slint_core::swrenderer::RepaintBufferType {
+        // The frame buffers are swapped after each frame
+        i_slint_core::swrenderer::RepaintBufferType::SwappedBuffers
     }
 
     fn fill_region(&mut self, region: PhysicalRect, pixels: &[super::TargetPixel]) {
@@ -9,9 +9,11 @@
 
 extern crate alloc;
 
+use i_slint_core::dialogs::{MessageBoxCallback, MessageLevel};
 use i_slint_core::graphics::{Image, IntSize};
 #[cfg(not(no_qt))]
 use i_slint_core::items::ImageFit;
+use i_slint_core::items::StandardButtonKind;
 use i_slint_core::window::Window;
 #[cfg(not(no_qt))]
 use i_slint_core::ImageInner;
@@ -144,6 +146,12 @@ fn create_window(&'static self) -> std::rc::Rc<Window> {
         panic!("The Qt backend needs Qt");
         #[cfg(not(no_qt))]
         {
+            // Report the color scheme of the palette once, later changes are reported
+            // when the native style metrics receive a PaletteChange event.
+            static DETECT_COLOR_SCHEME: std::sync::Once = std::sync::Once::new();
+            DETECT_COLOR_SCHEME.call_once(|| {
+                i_slint_core::color_scheme::set_color_scheme(qt_widgets::detect_color_scheme())
+            });
             i_slint_core::window::Window::new(|window| qt_window::QtWindow::new(window))
         }
     }
@@ -157,11 +165,24 @@ fn run_event_loop(&'static self, _behavior: i_slint_core::backend::EventLoopQuit
             };
             // Schedule any timers with Qt that were set up before this event loop start.
             crate::qt_window::timer_event();
+            let _running_guard = i_slint_core::event_loop::enter_event_loop();
             use cpp::cpp;
             cpp! {unsafe [quit_on_last_window_closed as "bool"] {
                 ensure_initialized(true);
                 qApp->setQuitOnLastWindowClosed(quit_on_last_window_closed);
+                auto idle_connection = QObject::connect(QAbstractEventDispatcher::instance(),
+                        &QAbstractEventDispatcher::aboutToBlock, qApp, [] {
+                    rust!(Slint_aboutToBlock [] {
+                        // The event loop doesn't wait while the animations run
+                        if !i_slint_core::animations::CURRENT_ANIMATION_DRIVER
+                            .with(|driver| driver.has_active_animations())
+                        {
+                            i_slint_core::event_loop::invoke_idle_callbacks();
+                        }
+                    });
+                });
                 qApp->exec();
+                QObject::disconnect(idle_connection);
             } }
         };
     }
@@ -252,6 +273,21 @@ fn clipboard_text(&'static self) -> Option<String> {
         None
     }
 
+    fn cursor_blink_interval(&'static self) -> Option<core::time::Duration> {
+        #[cfg(not(no_qt))]
+        {
+            use cpp::cpp;
+            // The flash time is a whole period of the blinking, 0 or less when it's disabled
+            let flash_time = cpp! {unsafe [] -> i32 as "int" {
+                ensure_initialized();
+                return QGuiApplication::styleHints()->cursorFlashTime();
+            } };
+            (flash_time > 0).then(|| core::time::Duration::from_millis(flash_time as u64 / 2))
+        }
+        #[cfg(no_qt)]
+        Some(i_slint_core::input::DEFAULT_CURSOR_BLINK_INTERVAL)
+    }
+
     fn post_event(&'static self, _event: Box<dyn FnOnce() + Send>) {
         #[cfg(not(no_qt))]
         {
@@ -310,4 +346,104 @@ fn image_size(&'static self, _image: &Image) -> IntSize {
         #[cfg(no_qt)]
         Default::default()
     }
+
+    fn show_message_box(
+        &'static self,
+        _level: MessageLevel,
+        _title: &str,
+        _text: &str,
+        _buttons: &[StandardButtonKind],
+        on_close: MessageBoxCallback,
+    ) -> Result<(), MessageBoxCallback> {
+        #[cfg(no_qt)]
+        return Err(on_close);
+        #[cfg(not(no_qt))]
+        {
+            use cpp::cpp;
+            cpp! {{
+               struct MessageBoxCallbackHolder {
+                   TraitObject callback = {nullptr, nullptr};
+                   ~MessageBoxCallbackHolder() {
+                       if (callback.a != nullptr || callback.b != nullptr) {
+                           rust!(Slint_delete_message_box_callback [callback: *mut dyn FnOnce(StandardButtonKind) as "TraitObject"] {
+                               drop(Box::from_raw(callback))
+                           });
+                       }
+                   }
+                   MessageBoxCallbackHolder(TraitObject f) : callback(f)  {}
+                   MessageBoxCallbackHolder(const MessageBoxCallbackHolder&) = delete;
+                   MessageBoxCallbackHolder& operator=(const MessageBoxCallbackHolder&) = delete;
+                   // The index of the clicked button in the list of buttons given to show_message_box
+                   void call(int index) {
+                        if (callback.a != nullptr || callback.b != nullptr) {
+                            TraitObject callback = std::move(this->callback);
+                            this->callback = {nullptr, nullptr};
+                            rust!(Slint_call_message_box_callback [callback: *mut dyn FnOnce(StandardButtonKind) as "TraitObject", index: i32 as "int"] {
+                               let b = Box::from_raw(callback);
+                               b(message_box_button_from_qt_index(index));
+                            });
+                        }
+                   }
+               };
+            }};
+
+            let level = match _level {
+                MessageLevel::information => 0,
+                MessageLevel::warning => 1,
+                MessageLevel::error => 2,
+                MessageLevel::question => 3,
+            };
+            let title: qttypes::QString = _title.into();
+            let text: qttypes::QString = _text.into();
+            // The StandardButtonKind values, in the order of the enum, as an index in the table of the
+            // QMessageBox buttons below
+            let buttons = _buttons.iter().map(|b| *b as i32).collect::<Vec<_>>();
+            let buttons_ptr = buttons.as_ptr();
+            let buttons_len = buttons.len();
+            // The button reported when the message box is closed without clicking a button
+            let escape = i_slint_core::dialogs::escape_button(_buttons) as i32;
+            let callback = Box::into_raw(on_close);
+            cpp! {unsafe [level as "int", title as "QString", text as "QString", buttons_ptr as "const int *",
+                          buttons_len as "size_t", escape as "int", callback as "TraitObject"] {
+                ensure_initialized(true);
+                static const QMessageBox::StandardButton qt_buttons[] = {
+                    QMessageBox::Ok, QMessageBox::Cancel, QMessageBox::Apply, QMessageBox::Close,
+                    QMessageBox::Reset, QMessageBox::Help, QMessageBox::Yes, QMessageBox::No,
+                    QMessageBox::Abort, QMessageBox::Retry, QMessageBox::Ignore
+                };
+                static const QMessageBox::Icon icons[] = {
+                    QMessageBox::Information, QMessageBox::Warning, QMessageBox::Critical, QMessageBox::Question
+                };
+                QMessageBox::StandardButtons standard_buttons;
+                for (size_t i = 0; i < buttons_len; ++i) {
+                    standard_buttons |= qt_buttons[buttons_ptr[i]];
+                }
+                auto box = new QMessageBox(icons[level], title, text, standard_buttons);
+                box->setAttribute(Qt::WA_DeleteOnClose);
+                auto holder = std::make_shared<MessageBoxCallbackHolder>(callback);
+                QObject::connect(box, &QMessageBox::finished, box, [box, holder, escape] {
+                    auto clicked = box->standardButton(box->clickedButton());
+                    int index = escape;
+                    for (int i = 0; i < int(sizeof(qt_buttons) / sizeof(qt_buttons[0])); ++i) {
+                        if (qt_buttons[i] == clicked) {
+                            index = i;
+                        }
+                    }
+                    holder->call(index);
+                });
+                box->open();
+            }};
+            Ok(())
+        }
+    }
+}
+
+/// Returns the StandardButtonKind at the given index in the enum
+#[cfg(not(no_qt))]
+fn message_box_button_from_qt_index(index: i32) -> StandardButtonKind {
+    use StandardButtonKind::*;
+    [ok, cancel, apply, close, reset, help, yes, no, abort, retry, ignore]
+        .get(index as usize)
+        .copied()
+        .unwrap_or_default()
 }
@@ -141,6 +141,7 @@ pub fn draw(&self, callback: &mut dyn FnMut(u32, u32, &[u8])) {
     #include <QtWidgets/QStyleFactory>
     #include <QtGui/QPainter>
     #include <QtGui/QClipboard>
+    #include <QtGui/QStyleHints>
     #include <QtCore/QMimeData>
     #include <QtCore/QDebug>
     #include <QtCore/QScopeGuard>
@@ -273,7 +273,7 @@ fn key_event(self: Pin<&Self>, event: &KeyEvent, _window: &WindowRc) -> KeyEvent
                 Self::FIELD_OFFSETS.clicked.apply_pin(self).call(&());
                 KeyEventResult::EventAccepted
             }
-            KeyEventType::KeyReleased => KeyEventResult::EventIgnored,
+            _ => KeyEventResult::EventIgnored,
         }
     }
 
@@ -18,6 +18,8 @@ pub struct NativeCheckBox {
     pub toggled: Callback<VoidArg>,
     pub text: Property<SharedString>,
     pub checked: Property<bool>,
+    pub partially_checked: Property<bool>,
+    pub tristate: Property<bool>,
     pub cached_rendering_data: CachedRenderingData,
 }
 
@@ -71,8 +73,7 @@ fn input_event(
         }
         if let MouseEvent::MouseReleased { pos, .. } = event {
             if euclid::rect(0., 0., self.width(), self.height()).contains(pos) {
-                Self::FIELD_OFFSETS.checked.apply_pin(self).set(!self.checked());
-                Self::FIELD_OFFSETS.toggled.apply_pin(self).call(&())
+                self.toggle();
             }
         }
         InputEventResult::EventAccepted
@@ -81,12 +82,10 @@ fn input_event(
     fn key_event(self: Pin<&Self>, event: &KeyEvent, _window: &WindowRc) -> KeyEventResult {
         match event.event_type {
             KeyEventType::KeyPressed if event.text == " " || event.text == "\n" => {
-                Self::FIELD_OFFSETS.checked.apply_pin(self).set(!self.checked());
-                Self::FIELD_OFFSETS.toggled.apply_pin(self).call(&());
+                self.toggle();
                 KeyEventResult::EventAccepted
             }
-            KeyEventType::KeyPressed => KeyEventResult::EventIgnored,
-            KeyEventType::KeyReleased => KeyEventResult::EventIgnored,
+            _ => KeyEventResult::EventIgnored,
         }
     }
 
@@ -104,6 +103,7 @@ fn focus_event(self: Pin<&Self>, event: &FocusEvent, _window: &WindowRc) -> Focu
 
     fn_render! { this dpr size painter widget initial_state =>
         let checked: bool = this.checked();
+        let partially_checked: bool = this.partially_checked();
         let enabled = this.enabled();
         let has_focus = this.has_focus();
         let text: qttypes::QString = this.text().as_str().into();
@@ -115,6 +115,7 @@ fn focus_event(self: Pin<&Self>, event: &FocusEvent, _window: &WindowRc) -> Focu
             text as "QString",
             size as "QSize",
             checked as "bool",
+            partially_checked as "bool",
             has_focus as "bool",
             dpr as "float",
             initial_state as "int"
@@ -123,7 +124,8 @@ fn focus_event(self: Pin<&Self>, event: &FocusEvent, _window: &WindowRc) -> Focu
             option.state |= QStyle::State(initial_state);
             option.text = std::move(text);
             option.rect = QRect(QPoint(), size / dpr);
-            option.state |= checked ? QStyle::State_On : QStyle::State_Off;
+            option.state |= partially_checked ? QStyle::State_NoChange
+                : checked ? QStyle::State_On : QStyle::State_Off;
             if (enabled) {
                 option.state |= QStyle::State_Enabled;
             } else {
@@ -137,6 +139,21 @@ fn focus_event(self: Pin<&Self>, event: &FocusEvent, _window: &WindowRc) -> Focu
     }
 }
 
+impl NativeCheckBox {
+    /// Go to the next state when clicked: from partially checked to checked, and from unchecked to
+    /// partially checked in the tristate mode
+    fn toggle(self: Pin<&Self>) {
+        let (checked, partially_checked) = match (self.checked(), self.partially_checked()) {
+            (_, true) => (true, false),
+            (false, false) if self.tristate() => (false, true),
+            (checked, false) => (!checked, false),
+        };
+        Self::FIELD_OFFSETS.checked.apply_pin(self).set(checked);
+        Self::FIELD_OFFSETS.partially_checked.apply_pin(self).set(partially_checked);
+        Self::FIELD_OFFSETS.toggled.apply_pin(self).call(&())
+    }
+}
+
 impl ItemConsts for NativeCheckBox {
     const cached_rendering_data_offset: const_field_offset::FieldOffset<Self, CachedRenderingData> =
         Self::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
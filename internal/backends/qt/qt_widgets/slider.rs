@@ -30,14 +30,22 @@ pub struct NativeSlider {
     pub value: Property<f32>,
     pub minimum: Property<f32>,
     pub maximum: Property<f32>,
+    pub step: Property<f32>,
+    pub tick_interval: Property<f32>,
+    pub pressed: Property<bool>,
     pub cached_rendering_data: CachedRenderingData,
     data: Property<NativeSliderData>,
     pub changed: Callback<FloatArg>,
 }
 
 cpp! {{
-void initQSliderOptions(QStyleOptionSlider &option, bool pressed, bool enabled, int active_controls, int minimum, int maximum, int value) {
+void initQSliderOptions(QStyleOptionSlider &option, bool pressed, bool enabled, int active_controls, int minimum, int maximum, int value, int tick_interval) {
     option.subControls = QStyle::SC_SliderGroove | QStyle::SC_SliderHandle;
+    if (tick_interval > 0) {
+        option.subControls |= QStyle::SC_SliderTickmarks;
+        option.tickPosition = QSlider::TicksBelow;
+        option.tickInterval = tick_interval;
+    }
     option.activeSubControls = { active_controls };
     option.orientation = Qt::Horizontal;
     option.maximum = maximum;
@@ -68,6 +76,7 @@ fn layout_info(self: Pin<&Self>, orientation: Orientation, _window: &WindowRc) -
         let value = self.value() as i32;
         let min = self.minimum() as i32;
         let max = self.maximum() as i32;
+        let tick_interval = self.qt_tick_interval();
         let data = self.data();
         let active_controls = data.active_controls;
         let pressed = data.pressed;
@@ -77,12 +86,13 @@ fn layout_info(self: Pin<&Self>, orientation: Orientation, _window: &WindowRc) -
             value as "int",
             min as "int",
             max as "int",
+            tick_interval as "int",
             active_controls as "int",
             pressed as "bool"
         ] -> qttypes::QSize as "QSize" {
             ensure_initialized();
             QStyleOptionSlider option;
-            initQSliderOptions(option, pressed, enabled, active_controls, min, max, value);
+            initQSliderOptions(option, pressed, enabled, active_controls, min, max, value, tick_interval);
             auto style = qApp->style();
             auto thick = style->pixelMetric(QStyle::PM_SliderThickness, &option, nullptr);
             return style->sizeFromContents(QStyle::CT_Slider, &option, QSize(0, thick), nullptr);
@@ -119,6 +129,7 @@ fn input_event(
         let value = self.value() as f32;
         let min = self.minimum() as f32;
         let max = self.maximum() as f32;
+        let tick_interval = self.qt_tick_interval();
         let mut data = self.data();
         let active_controls = data.active_controls;
         let pressed: bool = data.pressed != 0;
@@ -132,12 +143,13 @@ fn input_event(
             value as "float",
             min as "float",
             max as "float",
+            tick_interval as "int",
             active_controls as "int",
             pressed as "bool"
         ] -> u32 as "int" {
             ensure_initialized();
             QStyleOptionSlider option;
-            initQSliderOptions(option, pressed, enabled, active_controls, min, max, value);
+            initQSliderOptions(option, pressed, enabled, active_controls, min, max, value, tick_interval);
             auto style = qApp->style();
             option.rect = { QPoint{}, size };
             return style->hitTestComplexControl(QStyle::CC_Slider, &option, pos, nullptr);
@@ -145,17 +157,20 @@ fn input_event(
         let result = match event {
             _ if !enabled => {
                 data.pressed = 0;
+                self.pressed.set(false);
                 InputEventResult::EventIgnored
             }
             MouseEvent::MousePressed { pos, button: PointerEventButton::left } => {
                 data.pressed_x = pos.x as f32;
                 data.pressed = 1;
                 data.pressed_val = value;
+                self.pressed.set(true);
                 InputEventResult::GrabMouse
             }
             MouseEvent::MouseExit
             | MouseEvent::MouseReleased { button: PointerEventButton::left, .. } => {
                 data.pressed = 0;
+                self.pressed.set(false);
                 InputEventResult::EventAccepted
             }
             MouseEvent::MouseMoved { pos } => {
@@ -163,17 +178,22 @@ fn input_event(
                     // FIXME: use QStyle::subControlRect to find out the actual size of the groove
                     let new_val = data.pressed_val
                         + ((pos.x as f32) - data.pressed_x) * (max - min) / size.width as f32;
-                    let new_val = new_val.max(min).min(max);
-                    self.value.set(new_val);
-                    Self::FIELD_OFFSETS.changed.apply_pin(self).call(&(new_val,));
+                    let new_val = self.snap(new_val);
+                    if new_val != value {
+                        self.value.set(new_val);
+                        Self::FIELD_OFFSETS.changed.apply_pin(self).call(&(new_val,));
+                    }
                     InputEventResult::GrabMouse
                 } else {
                     InputEventResult::EventIgnored
                 }
             }
             MouseEvent::MouseWheel { delta, .. } => {
-                let new_val = value + delta.x + delta.y;
-                let new_val = new_val.max(min).min(max);
+                let step = match self.step() {
+                    step if step > 0. => step * (delta.x + delta.y).signum(),
+                    _ => delta.x + delta.y,
+                };
+                let new_val = self.snap(value + step);
                 self.value.set(new_val);
                 Self::FIELD_OFFSETS.changed.apply_pin(self).call(&(new_val,));
                 InputEventResult::EventAccepted
@@ -202,6 +222,7 @@ fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEve
         let value = this.value() as i32;
         let min = this.minimum() as i32;
         let max = this.maximum() as i32;
+        let tick_interval = this.qt_tick_interval();
         let data = this.data();
         let active_controls = data.active_controls;
         let pressed = data.pressed;
@@ -213,6 +234,7 @@ fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEve
             value as "int",
             min as "int",
             max as "int",
+            tick_interval as "int",
             size as "QSize",
             active_controls as "int",
             pressed as "bool",
@@ -222,13 +244,30 @@ fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEve
             QStyleOptionSlider option;
             option.state |= QStyle::State(initial_state);
             option.rect = QRect(QPoint(), size / dpr);
-            initQSliderOptions(option, pressed, enabled, active_controls, min, max, value);
+            initQSliderOptions(option, pressed, enabled, active_controls, min, max, value, tick_interval);
             auto style = qApp->style();
             style->drawComplexControl(QStyle::CC_Slider, &option, painter->get(), widget);
         });
     }
 }
 
+impl NativeSlider {
+    /// Clamp the value between the minimum and the maximum, and round it to a multiple of the step
+    fn snap(self: Pin<&Self>, value: f32) -> f32 {
+        let (min, max, step) = (self.minimum(), self.maximum(), self.step());
+        let value = if step > 0. { min + ((value - min) / step).round() * step } else { value };
+        value.max(min).min(max)
+    }
+
+    /// The interval between the tick marks, in the integer units of the QSlider, or 0 for no tick marks
+    fn qt_tick_interval(self: Pin<&Self>) -> i32 {
+        match self.tick_interval() {
+            interval if interval > 0. => (interval.round() as i32).max(1),
+            _ => 0,
+        }
+    }
+}
+
 impl ItemConsts for NativeSlider {
     const cached_rendering_data_offset: const_field_offset::FieldOffset<Self, CachedRenderingData> =
         Self::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
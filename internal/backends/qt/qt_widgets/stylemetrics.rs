@@ -3,7 +3,7 @@
 
 // cSpell: ignore deinit
 
-use i_slint_core::items::LayoutAlignment;
+use i_slint_core::items::{ColorScheme, LayoutAlignment};
 
 use super::*;
 
@@ -143,13 +143,9 @@ fn init_impl(self: Pin<&Self>) {
         });
         self.placeholder_color_disabled.set(Color::from_argb_encoded(placeholder_color_disabled));
 
-        self.dark_style.set(
-            (window_background.red() as u32
-                + window_background.green() as u32
-                + window_background.blue() as u32)
-                / 3
-                < 128,
-        );
+        let color_scheme = color_scheme_from_background(window_background);
+        self.dark_style.set(color_scheme == ColorScheme::dark);
+        i_slint_core::color_scheme::set_color_scheme(color_scheme);
 
         let tab_bar_alignment = cpp!(unsafe[] -> u32 as "uint32_t" {
             switch (qApp->style()->styleHint(QStyle::SH_TabBar_Alignment)) {
@@ -187,3 +183,24 @@ pub fn native_style_metrics_deinit(self_: Pin<&mut NativeStyleMetrics>) {
     cpp!(unsafe [scl as "StyleChangeListener*"] { delete scl; });
     self_.style_change_listener.set(core::ptr::null());
 }
+
+fn color_scheme_from_background(window_background: Color) -> ColorScheme {
+    let lightness = (window_background.red() as u32
+        + window_background.green() as u32
+        + window_background.blue() as u32)
+        / 3;
+    if lightness < 128 {
+        ColorScheme::dark
+    } else {
+        ColorScheme::light
+    }
+}
+
+/// Returns the color scheme of the application palette, which follows the system settings.
+pub fn detect_color_scheme() -> ColorScheme {
+    let window_background = cpp!(unsafe[] -> u32 as "QRgb" {
+        ensure_initialized();
+        return qApp->palette().color(QPalette::Window).rgba();
+    });
+    color_scheme_from_background(Color::from_argb_encoded(window_background))
+}
@@ -320,6 +320,7 @@ pub struct NativeTab {
     pub current_focused: Property<i32>,
     pub tab_index: Property<i32>,
     pub num_tabs: Property<i32>,
+    pub closable: Property<bool>,
     pub cached_rendering_data: CachedRenderingData,
 }
 
@@ -340,11 +341,13 @@ fn layout_info(self: Pin<&Self>, orientation: Orientation, _window: &WindowRc) -
         .unwrap_or_default();
         let tab_index: i32 = self.tab_index();
         let num_tabs: i32 = self.num_tabs();
+        let closable: bool = self.closable();
         let size = cpp!(unsafe [
             text as "QString",
             icon as "QPixmap",
             tab_index as "int",
-            num_tabs as "int"
+            num_tabs as "int",
+            closable as "bool"
         ] -> qttypes::QSize as "QSize" {
             ensure_initialized();
             QStyleOptionTab option;
@@ -363,6 +366,12 @@ fn layout_info(self: Pin<&Self>, orientation: Orientation, _window: &WindowRc) -
             int textWidth = option.fontMetrics.size(Qt::TextShowMnemonic, text).width();
             auto iconSize = icon.isNull() ? 0 : style->pixelMetric(QStyle::PM_TabBarIconSize, nullptr, nullptr);
             QSize csz = QSize(textWidth + iconSize + hframe + padding, qMax(option.fontMetrics.height(), iconSize) + vframe);
+            if (closable) {
+                // Reserve a square at the end of the tab for the close button, which is where the
+                // TabImpl handles the clicks on it
+                option.rightButtonSize = QSize(csz.height(), csz.height());
+                csz.rwidth() += csz.height();
+            }
             return style->sizeFromContents(QStyle::CT_TabBarTab, &option, csz, nullptr);
         });
         LayoutInfo {
@@ -447,6 +456,7 @@ fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEve
         let current_focused: i32 = this.current_focused();
         let tab_index: i32 = this.tab_index();
         let num_tabs: i32 = this.num_tabs();
+        let closable: bool = this.closable();
 
         cpp!(unsafe [
             painter as "QPainterPtr*",
@@ -461,6 +471,7 @@ fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEve
             current as "int",
             current_focused as "int",
             num_tabs as "int",
+            closable as "bool",
             initial_state as "int"
         ] {
             ensure_initialized();
@@ -492,7 +503,20 @@ fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEve
                 option.state |= QStyle::State_HasFocus | QStyle::State_KeyboardFocusChange | QStyle::State_Item;
             }
             option.features |= QStyleOptionTab::HasFrame;
+            if (closable)
+                option.rightButtonSize = QSize(option.rect.height(), option.rect.height());
             qApp->style()->drawControl(QStyle::CE_TabBarTab, &option, painter->get(), widget);
+            if (closable) {
+                QStyleOption close_option;
+                int w = qApp->style()->pixelMetric(QStyle::PM_TabCloseIndicatorWidth, nullptr, widget);
+                int h = qApp->style()->pixelMetric(QStyle::PM_TabCloseIndicatorHeight, nullptr, widget);
+                close_option.rect = QRect(option.rect.right() - (option.rect.height() + w) / 2,
+                    (option.rect.height() - h) / 2, w, h);
+                close_option.state = QStyle::State(initial_state) | QStyle::State_Raised;
+                if (enabled)
+                    close_option.state |= QStyle::State_Enabled;
+                qApp->style()->drawPrimitive(QStyle::PE_IndicatorTabClose, &close_option, painter->get(), widget);
+            }
         });
     }
 }
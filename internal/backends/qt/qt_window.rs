@@ -14,8 +14,8 @@
 use i_slint_core::input::{KeyEvent, KeyEventType, MouseEvent};
 use i_slint_core::item_rendering::{CachedRenderingData, ItemRenderer};
 use i_slint_core::items::{
-    self, FillRule, ImageRendering, InputType, ItemRc, ItemRef, Layer, MouseCursor, Opacity,
-    PointerEventButton, RenderingResult, TextOverflow, TextWrap,
+    self, FillRule, ImageRendering, ItemRc, ItemRef, Layer, MouseCursor, Opacity,
+    PointerEventButton, RenderingResult, ScrollEventPhase, TextOverflow, TextWrap,
 };
 use i_slint_core::layout::Orientation;
 use i_slint_core::window::{PlatformWindow, PopupWindow, PopupWindowLocation, WindowRc};
@@ -43,6 +43,7 @@
     #include <QtGui/QTextLayout>
     #include <QtGui/QImageReader>
     #include <QtGui/QCursor>
+    #include <QtCore/QAbstractEventDispatcher>
     #include <QtCore/QBasicTimer>
     #include <QtCore/QTimer>
     #include <QtCore/QPointer>
@@ -54,6 +55,16 @@
 
     using QPainterPtr = std::unique_ptr<QPainter>;
 
+    // Same layout as QtTextSpan in Rust
+    struct QtTextSpan {
+        int start;
+        int length;
+        QRgb color;
+        QRgb background;
+        int font_weight;
+        bool underline;
+    };
+
     struct TimerHandler : QObject {
         QBasicTimer timer;
         static TimerHandler& instance() {
@@ -152,10 +163,12 @@ struct SlintWidget : QWidget {
             if (delta.isNull()) {
                 delta = event->angleDelta();
             }
-            rust!(Slint_mouseWheelEvent [rust_window: &QtWindow as "void*", pos: qttypes::QPointF as "QPointF", delta: qttypes::QPoint as "QPoint"] {
+            int phase = event->phase();
+            rust!(Slint_mouseWheelEvent [rust_window: &QtWindow as "void*", pos: qttypes::QPointF as "QPointF", delta: qttypes::QPoint as "QPoint", phase: u32 as "int"] {
                 let pos = Point::new(pos.x as _, pos.y as _);
                 let delta = Point::new(delta.x as _, delta.y as _);
-                rust_window.mouse_event(MouseEvent::MouseWheel{pos, delta})
+                let phase = from_qt_scroll_phase(phase);
+                rust_window.mouse_event(MouseEvent::MouseWheel{pos, delta, phase})
             });
         }
         void leaveEvent(QEvent *) override {
@@ -168,16 +181,22 @@ struct SlintWidget : QWidget {
             uint modifiers = uint(event->modifiers());
             QString text =  event->text();
             int key = event->key();
-            rust!(Slint_keyPress [rust_window: &QtWindow as "void*", key: i32 as "int", text: qttypes::QString as "QString", modifiers: u32 as "uint"] {
-                rust_window.key_event(key, text.clone(), modifiers, false);
+            uint scan_code = event->nativeScanCode();
+            bool repeat = event->isAutoRepeat();
+            rust!(Slint_keyPress [rust_window: &QtWindow as "void*", key: i32 as "int", text: qttypes::QString as "QString", modifiers: u32 as "uint", scan_code: u32 as "uint", repeat: bool as "bool"] {
+                rust_window.key_event(key, text.clone(), modifiers, scan_code, repeat, false);
             });
         }
         void keyReleaseEvent(QKeyEvent *event) override {
+            // On X11, the auto-repeated key presses are preceded by a key release
+            if (event->isAutoRepeat())
+                return;
             uint modifiers = uint(event->modifiers());
             QString text =  event->text();
             int key = event->key();
-            rust!(Slint_keyRelease [rust_window: &QtWindow as "void*", key: i32 as "int", text: qttypes::QString as "QString", modifiers: u32 as "uint"] {
-                rust_window.key_event(key, text.clone(), modifiers, true);
+            uint scan_code = event->nativeScanCode();
+            rust!(Slint_keyRelease [rust_window: &QtWindow as "void*", key: i32 as "int", text: qttypes::QString as "QString", modifiers: u32 as "uint", scan_code: u32 as "uint"] {
+                rust_window.key_event(key, text.clone(), modifiers, scan_code, false, true);
             });
         }
 
@@ -383,6 +402,16 @@ fn from_qt_button(qt_button: u32) -> PointerEventButton {
     }
 }
 
+fn from_qt_scroll_phase(qt_phase: u32) -> ScrollEventPhase {
+    match qt_phase {
+        1 => ScrollEventPhase::begin,
+        // The momentum phase is the inertia after the fingers were lifted
+        2 | 4 => ScrollEventPhase::update,
+        3 => ScrollEventPhase::end,
+        _ => ScrollEventPhase::none,
+    }
+}
+
 /// Given a position offset and an object of a given type that has x,y,width,height properties,
 /// create a QRectF that fits it.
 macro_rules! get_geometry {
@@ -467,11 +496,12 @@ fn draw_image(&mut self, image: Pin<&items::ImageItem>) {
 
     fn draw_clipped_image(&mut self, image: Pin<&items::ClippedImage>) {
         let dest_rect: qttypes::QRectF = get_geometry!(items::ClippedImage, image);
+        let source_clip = image.source_clip();
         let source_rect = qttypes::QRectF {
-            x: image.source_clip_x() as _,
-            y: image.source_clip_y() as _,
-            width: image.source_clip_width() as _,
-            height: image.source_clip_height() as _,
+            x: source_clip.min_x() as _,
+            y: source_clip.min_y() as _,
+            width: source_clip.width() as _,
+            height: source_clip.height() as _,
         };
         self.draw_image_impl(
             &image.cached_rendering_data,
@@ -505,24 +535,45 @@ fn draw_text(&mut self, text: std::pin::Pin<&items::Text>) {
             TextWrap::word_wrap => key_generated::Qt_TextFlag_TextWordWrap,
         };
         let elide = text.overflow() == TextOverflow::elide;
+        let ellipsis: qttypes::QString = text.ellipsis().as_str().into();
         let painter: &mut QPainterPtr = &mut self.painter;
-        cpp! { unsafe [painter as "QPainterPtr*", rect as "QRectF", fill_brush as "QBrush", mut string as "QString", flags as "int", font as "QFont", elide as "bool"] {
+        cpp! { unsafe [painter as "QPainterPtr*", rect as "QRectF", fill_brush as "QBrush", mut string as "QString", flags as "int", font as "QFont", elide as "bool", mut ellipsis as "QString"] {
             (*painter)->setFont(font);
             (*painter)->setPen(QPen(fill_brush, 0));
             (*painter)->setBrush(Qt::NoBrush);
+            QFontMetricsF fm(font);
+            for (uint ch : ellipsis.toUcs4()) {
+                if (!fm.inFontUcs4(ch)) {
+                    ellipsis = QStringLiteral("...");
+                    break;
+                }
+            }
+            // Cut the line on a grapheme boundary so that it fits with the ellipsis. With `force`,
+            // the ellipsis is added even if the line fits.
+            auto elide_line = [&](const QString &line, bool force) -> QString {
+                if (!force && fm.horizontalAdvance(line) <= rect.width())
+                    return line;
+                QTextBoundaryFinder graphemes(QTextBoundaryFinder::Grapheme, line);
+                graphemes.toEnd();
+                for (int pos = graphemes.position(); pos > 0; pos = graphemes.toPreviousBoundary()) {
+                    QString elided = line.left(pos) % ellipsis;
+                    if (fm.horizontalAdvance(elided) <= rect.width())
+                        return elided;
+                }
+                return ellipsis;
+            };
             if (!elide) {
                 (*painter)->drawText(rect, flags, string);
             } else if (!(flags & Qt::TextWordWrap)) {
                 QString elided;
-                QFontMetrics fm(font);
                 while (!string.isEmpty()) {
                     int pos = string.indexOf('\n');
                     if (pos < 0) {
-                        elided += fm.elidedText(string, Qt::ElideRight, rect.width());
+                        elided += elide_line(string, false);
                         break;
                     }
                     QString line = string.left(pos);
-                    elided += fm.elidedText(line, Qt::ElideRight, rect.width());
+                    elided += elide_line(line, false);
                     elided += '\n';
                     string = string.mid(pos + 1);
                 }
@@ -531,14 +582,13 @@ fn draw_text(&mut self, text: std::pin::Pin<&items::Text>) {
                 // elide and word wrap: we need to add the ellipsis manually on the last line
                 string.replace(QChar('\n'), QChar::LineSeparator);
                 QString elided = string;
-                QFontMetrics fm(font);
                 QTextLayout layout(string, font);
                 QTextOption options;
                 options.setWrapMode(QTextOption::WordWrap);
                 layout.setTextOption(options);
                 layout.setCacheEnabled(true);
                 layout.beginLayout();
-                int leading = fm.leading();
+                qreal leading = fm.leading();
                 qreal height = 0;
                 int last_line_begin = 0, last_line_size = 0;
                 while (true) {
@@ -557,8 +607,7 @@ fn draw_text(&mut self, text: std::pin::Pin<&items::Text>) {
                 }
                 if (last_line_begin < string.size()) {
                     elided = string.left(last_line_begin);
-                    QString to_elide = QStringView(string).mid(last_line_begin, last_line_size).trimmed() % QStringView(QT_UNICODE_LITERAL("…"));
-                    elided += fm.elidedText(to_elide, Qt::ElideRight, rect.width());
+                    elided += elide_line(string.mid(last_line_begin, last_line_size).trimmed(), true);
                 }
                 (*painter)->drawText(rect, flags, elided);
             }
@@ -573,14 +622,12 @@ fn draw_text_input(&mut self, text_input: std::pin::Pin<&items::TextInput>) {
         let selection_background_color: u32 =
             text_input.selection_background_color().as_argb_encoded();
 
-        let text = text_input.text();
-        let mut string: qttypes::QString = text.as_str().into();
-
-        if let InputType::password = text_input.input_type() {
-            cpp! { unsafe [mut string as "QString"] {
-                string.fill(QChar(qApp->style()->styleHint(QStyle::SH_LineEdit_PasswordCharacter, nullptr, nullptr)));
-            }}
+        let mut visual_representation = text_input.visual_representation();
+        if visual_representation.password_mask.is_some() {
+            visual_representation.apply_password_character(&password_character());
         }
+        let text = &visual_representation.text;
+        let mut string: qttypes::QString = text.as_str().into();
 
         let font: QFont =
             get_font(text_input.unresolved_font_request().merge(&self.default_font_properties));
@@ -599,20 +646,12 @@ fn draw_text_input(&mut self, text_input: std::pin::Pin<&items::TextInput>) {
 
         // convert byte offsets to offsets in Qt UTF-16 encoded string, as that's
         // what QTextLayout expects.
-        let cursor_position_as_offset: i32 = text_input.cursor_position();
-        let anchor_position_as_offset: i32 = text_input.anchor_position();
-        let cursor_position: i32 = if cursor_position_as_offset > 0 {
-            utf8_byte_offset_to_utf16_units(text.as_str(), cursor_position_as_offset as usize)
-                as i32
-        } else {
-            0
-        };
-        let anchor_position: i32 = if anchor_position_as_offset > 0 {
-            utf8_byte_offset_to_utf16_units(text.as_str(), anchor_position_as_offset as usize)
-                as i32
-        } else {
-            0
-        };
+        let cursor_position: i32 =
+            utf8_byte_offset_to_utf16_units(text.as_str(), visual_representation.cursor_position)
+                as i32;
+        let anchor_position: i32 =
+            utf8_byte_offset_to_utf16_units(text.as_str(), visual_representation.anchor_position)
+                as i32;
 
         let text_cursor_width: f32 = if text_input.cursor_visible() && text_input.enabled() {
             text_input.text_cursor_width()
@@ -622,6 +661,25 @@ fn draw_text_input(&mut self, text_input: std::pin::Pin<&items::TextInput>) {
 
         let single_line: bool = text_input.single_line();
 
+        let spans = visual_representation
+            .spans
+            .iter()
+            .map(|span| {
+                let start = utf8_byte_offset_to_utf16_units(text.as_str(), span.start as usize);
+                let end = utf8_byte_offset_to_utf16_units(text.as_str(), span.end as usize);
+                QtTextSpan {
+                    start: start as i32,
+                    length: (end - start) as i32,
+                    color: span.color.as_argb_encoded(),
+                    background: span.background.as_argb_encoded(),
+                    font_weight: span.font_weight,
+                    underline: span.underline,
+                }
+            })
+            .collect::<Vec<_>>();
+        let spans_ptr = spans.as_ptr();
+        let spans_len = spans.len() as i32;
+
         let painter: &mut QPainterPtr = &mut self.painter;
         cpp! { unsafe [
                 painter as "QPainterPtr*",
@@ -635,7 +693,9 @@ fn draw_text_input(&mut self, text_input: std::pin::Pin<&items::TextInput>) {
                 font as "QFont",
                 cursor_position as "int",
                 anchor_position as "int",
-                text_cursor_width as "float"] {
+                text_cursor_width as "float",
+                spans_ptr as "const QtTextSpan*",
+                spans_len as "int"] {
             if (!single_line) {
                 string.replace(QChar('\n'), QChar::LineSeparator);
             }
@@ -643,6 +703,27 @@ fn draw_text_input(&mut self, text_input: std::pin::Pin<&items::TextInput>) {
             do_text_layout(layout, flags, rect);
             (*painter)->setPen(QPen(fill_brush, 0));
             QVector<QTextLayout::FormatRange> selections;
+            for (int i = 0; i < spans_len; ++i) {
+                const QtTextSpan &span = spans_ptr[i];
+                QTextCharFormat fmt;
+                if (qAlpha(span.color)) {
+                    fmt.setForeground(QColor::fromRgba(span.color));
+                }
+                if (qAlpha(span.background)) {
+                    fmt.setBackground(QColor::fromRgba(span.background));
+                }
+                // The glyphs keep the advance of the font of the TextInput, so heavier weights
+                // are rendered by emboldening the glyphs with an outline
+                if (span.font_weight > 400) {
+                    QBrush outline = qAlpha(span.color) ? QBrush(QColor::fromRgba(span.color)) : fill_brush;
+                    fmt.setTextOutline(QPen(outline, (span.font_weight - 400) / 300.));
+                }
+                if (span.underline) {
+                    fmt.setFontUnderline(true);
+                }
+                selections << QTextLayout::FormatRange{ span.start, span.length, fmt };
+            }
+            // The selection is added last so that it is drawn over the spans
             if (anchor_position != cursor_position) {
                 QTextCharFormat fmt;
                 fmt.setBackground(QColor::fromRgba(selection_background_color));
@@ -980,6 +1061,14 @@ pub(crate) fn load_image_from_resource(
             return Some(pixmap);
         }
         ImageInner::StaticTextures { .. } => todo!(),
+        ImageInner::VideoStream(stream) => {
+            let frame = i_slint_core::graphics::shown_video_frame_to_rgb(*stream)?;
+            return load_image_from_resource(
+                &ImageInner::EmbeddedImage(frame),
+                source_size,
+                image_fit,
+            );
+        }
     };
     let size_requested = is_svg(resource) && source_size.is_some();
     let source_size = source_size.unwrap_or_default();
@@ -1077,6 +1166,7 @@ fn is_svg(resource: &ImageInner) -> bool {
         }
         ImageInner::EmbeddedImage { .. } => false,
         ImageInner::StaticTextures { .. } => false,
+        ImageInner::VideoStream(_) => false,
     }
 }
 
@@ -1309,7 +1399,7 @@ fn paint_event(&self, painter: QPainterPtr) {
                 cache,
                 default_font_properties: self.default_font_properties(),
                 window: runtime_window,
-                metrics: RenderingMetrics { layers_created: Some(0) },
+                metrics: RenderingMetrics { layers_created: Some(0), ..Default::default() },
             };
 
             for (component, origin) in components {
@@ -1353,7 +1443,15 @@ fn mouse_event(&self, event: MouseEvent) {
         timer_event();
     }
 
-    fn key_event(&self, key: i32, text: qttypes::QString, qt_modifiers: u32, released: bool) {
+    fn key_event(
+        &self,
+        key: i32,
+        text: qttypes::QString,
+        qt_modifiers: u32,
+        scan_code: u32,
+        is_auto_repeat: bool,
+        released: bool,
+    ) {
         i_slint_core::animations::update_animations();
         let text: String = text.into();
         let modifiers = i_slint_core::input::KeyboardModifiers {
@@ -1365,10 +1463,17 @@ fn key_event(&self, key: i32, text: qttypes::QString, qt_modifiers: u32, release
 
         let text = qt_key_to_string(key as key_generated::Qt_Key, text);
 
+        // With xcb and wayland, the native scan code is the xkb key code, offset by 8 from the
+        // evdev key code used by the other backends
+        #[cfg(target_os = "linux")]
+        let scan_code = scan_code.saturating_sub(8);
+
         let event = KeyEvent {
             event_type: if released { KeyEventType::KeyReleased } else { KeyEventType::KeyPressed },
             text,
             modifiers,
+            scan_code: scan_code as i32,
+            is_auto_repeat,
         };
         self.self_weak.upgrade().unwrap().process_key_input(&event);
 
@@ -1527,9 +1632,10 @@ fn show_popup(&self, popup: &i_slint_core::component::ComponentRc, position: Poi
         let window = i_slint_core::window::Window::new(|window| QtWindow::new(window));
         let popup_window: &QtWindow =
             <dyn std::any::Any>::downcast_ref(window.as_ref().as_any()).unwrap();
+        let runtime_window = self.self_weak.upgrade().unwrap();
+        window.set_popup_parent(&runtime_window);
         window.set_component(popup);
 
-        let runtime_window = self.self_weak.upgrade().unwrap();
         let size = runtime_window.set_active_popup(PopupWindow {
             location: PopupWindowLocation::TopLevel(window.clone()),
             component: popup.clone(),
@@ -1607,7 +1713,14 @@ fn text_input_byte_offset_for_position(
         let pos = qttypes::QPointF { x: pos.x as _, y: pos.y as _ };
         let font: QFont =
             get_font(text_input.unresolved_font_request().merge(&self.default_font_properties()));
-        let string = qttypes::QString::from(text_input.text().as_str());
+        let text = text_input.text();
+        let password = text_input.password_mask().map(|mask| (mask, password_character()));
+        let string = match &password {
+            Some((mask, password_character)) => {
+                qttypes::QString::from(mask.mask(&text, password_character).as_str())
+            }
+            None => qttypes::QString::from(text.as_str()),
+        };
         let flags = match text_input.horizontal_alignment() {
             TextHorizontalAlignment::left => key_generated::Qt_AlignmentFlag_AlignLeft,
             TextHorizontalAlignment::center => key_generated::Qt_AlignmentFlag_AlignHCenter,
@@ -1621,14 +1734,10 @@ fn text_input_byte_offset_for_position(
             TextWrap::word_wrap => key_generated::Qt_TextFlag_TextWordWrap,
         };
         let single_line: bool = text_input.single_line();
-        let is_password: bool = matches!(text_input.input_type(), InputType::password);
-        cpp! { unsafe [font as "QFont", string as "QString", pos as "QPointF", flags as "int",
-                rect as "QRectF", single_line as "bool", is_password as "bool"] -> usize as "size_t" {
+        let result = cpp! { unsafe [font as "QFont", string as "QString", pos as "QPointF", flags as "int",
+                rect as "QRectF", single_line as "bool"] -> usize as "size_t" {
             // we need to do the \n replacement in a copy because the original need to be kept to know the utf8 offset
             auto copy = string;
-            if (is_password) {
-                copy.fill(QChar(qApp->style()->styleHint(QStyle::SH_LineEdit_PasswordCharacter, nullptr, nullptr)));
-            }
             if (!single_line) {
                 copy.replace(QChar('\n'), QChar::LineSeparator);
             }
@@ -1652,7 +1761,13 @@ fn text_input_byte_offset_for_position(
                 cur++;
             // convert to an utf8 pos;
             return QStringView(string).left(cur).toUtf8().size();
-        }}
+        }};
+        match password {
+            Some((mask, password_character)) => {
+                mask.from_masked_offset(&text, &password_character, result)
+            }
+            None => result,
+        }
     }
 
     fn text_input_cursor_rect_for_byte_offset(
@@ -1706,6 +1821,14 @@ fn as_any(&self) -> &dyn std::any::Any {
     }
 }
 
+/// Returns the character the style uses to hide the characters of a password
+fn password_character() -> String {
+    cpp! { unsafe [] -> qttypes::QString as "QString" {
+        return QString(QChar(qApp->style()->styleHint(QStyle::SH_LineEdit_PasswordCharacter, nullptr, nullptr)));
+    }}
+    .into()
+}
+
 fn get_font(request: FontRequest) -> QFont {
     let family: qttypes::QString = request.family.unwrap_or_default().as_str().into();
     let pixel_size: f32 = request.pixel_size.unwrap_or(0.);
@@ -1927,6 +2050,17 @@ pub extern "C" fn slint_qt_get_widget(window: &i_slint_core::window::WindowRc) -
     }
 }
 
+/// A `TextSpan` with offsets in UTF-16 units and encoded colors, passed to the C++ code
+#[repr(C)]
+struct QtTextSpan {
+    start: i32,
+    length: i32,
+    color: u32,
+    background: u32,
+    font_weight: i32,
+    underline: bool,
+}
+
 fn utf8_byte_offset_to_utf16_units(str: &str, byte_offset: usize) -> usize {
     let mut current_offset = 0;
     let mut utf16_units = 0;
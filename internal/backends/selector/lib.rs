@@ -67,6 +67,18 @@ pub struct NativeStyleMetrics{}
     }
 }
 
+/// Functions specific to the GL backend, which uses winit for the windowing system integration.
+#[cfg(feature = "i-slint-backend-gl")]
+pub mod backend_gl {
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use i_slint_backend_gl::{
+        raw_window_handle, GLRenderer, NativeOpenGLContext, OpenGLInterface, RenderTarget,
+    };
+    #[cfg(target_arch = "wasm32")]
+    pub use i_slint_backend_gl::{register_font_from_url, register_local_fonts};
+    pub use i_slint_backend_gl::{remove_event_filter, set_event_filter, winit, EventFilterResult};
+}
+
 #[doc(hidden)]
 #[cold]
 #[cfg(not(target_arch = "wasm32"))]
@@ -61,6 +61,15 @@ fn image_size(&'static self, image: &Image) -> IntSize {
         match inner {
             ImageInner::None => Default::default(),
             ImageInner::EmbeddedImage(buffer) => buffer.size(),
+            ImageInner::AbsoluteFilePath(path) if path.ends_with(".svg") => {
+                std::fs::read(path.as_str())
+                    .ok()
+                    .and_then(|data| svg_size(&data))
+                    .unwrap_or_default()
+            }
+            ImageInner::EmbeddedData { data, format } if format.as_slice() == b"svg" => {
+                svg_size(data.as_slice()).unwrap_or_default()
+            }
             ImageInner::AbsoluteFilePath(path) => image::open(Path::new(path.as_str()))
                 .map(|img| img.dimensions().into())
                 .unwrap_or_default(),
@@ -72,15 +81,24 @@ fn image_size(&'static self, image: &Image) -> IntSize {
             .map(|img| img.dimensions().into())
             .unwrap_or_default(),
             ImageInner::StaticTextures(StaticTextures { original_size, .. }) => *original_size,
+            ImageInner::VideoStream(_) => image.size(),
         }
     }
 
     fn duration_since_start(&'static self) -> core::time::Duration {
-        // The slint::testing::mock_elapsed_time updates the animation tick directly
+        // Only used in the threads that didn't call init(), which enables the mocked clock: the time
+        // advances with slint::testing::mock_elapsed_time, that updates the animation tick
         core::time::Duration::from_millis(i_slint_core::animations::current_tick().0)
     }
 }
 
+/// The size of an SVG document, as the renderers of the other backends compute it
+fn svg_size(data: &[u8]) -> Option<IntSize> {
+    let tree = usvg::Tree::from_data(data, &usvg::Options::default().to_ref()).ok()?;
+    let size = tree.svg_node().size.to_screen_size();
+    Some((size.width(), size.height()).into())
+}
+
 #[derive(Default)]
 pub struct TestingWindow {}
 
@@ -153,4 +171,82 @@ fn as_any(&self) -> &dyn std::any::Any {
 /// Calling it when the rendering backend is already initialized will have no effects
 pub fn init() {
     i_slint_core::backend::instance_or_init(|| Box::new(TestingBackend::default()));
+    // The time only advances with slint::testing::mock_elapsed_time
+    i_slint_core::animations::enable_mocked_clock();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i_slint_core::input::FocusEvent;
+    use i_slint_core::items::{Item, TextInput};
+    use i_slint_core::platform::duration_until_next_timer_update;
+    use i_slint_core::tests::slint_mock_elapsed_time;
+
+    /// Returns a new window and a text input that blinks with the `interval` in milliseconds, or
+    /// the default of the backend if it's 0
+    fn blinking_text_input(interval: i64) -> (Rc<Window>, Pin<Box<TextInput>>) {
+        init();
+        let window = Window::new(|_| Rc::new(TestingWindow::default()));
+        let text_input = Box::pin(TextInput::default());
+        text_input.cursor_blinking.set(true);
+        text_input.cursor_blink_interval.set(interval);
+        (window, text_input)
+    }
+
+    #[test]
+    fn text_cursor_blink_interval() {
+        let (window, text_input) = blinking_text_input(200);
+        let text_input = text_input.as_ref();
+        text_input.focus_event(&FocusEvent::FocusIn, &window);
+        assert!(text_input.cursor_visible());
+        slint_mock_elapsed_time(199);
+        assert!(text_input.cursor_visible());
+        slint_mock_elapsed_time(1);
+        assert!(!text_input.cursor_visible());
+        slint_mock_elapsed_time(200);
+        assert!(text_input.cursor_visible());
+
+        // Without an interval, the cursor blinks with the setting of the backend
+        text_input.focus_event(&FocusEvent::FocusOut, &window);
+        text_input.cursor_blink_interval.set(0);
+        text_input.focus_event(&FocusEvent::FocusIn, &window);
+        assert_eq!(
+            duration_until_next_timer_update(),
+            Some(i_slint_core::input::DEFAULT_CURSOR_BLINK_INTERVAL)
+        );
+        slint_mock_elapsed_time(499);
+        assert!(text_input.cursor_visible());
+        slint_mock_elapsed_time(1);
+        assert!(!text_input.cursor_visible());
+    }
+
+    #[test]
+    fn text_cursor_without_blinking() {
+        let (window, text_input) = blinking_text_input(200);
+        let text_input = text_input.as_ref();
+        text_input.cursor_blinking.set(false);
+        text_input.focus_event(&FocusEvent::FocusIn, &window);
+        assert!(text_input.cursor_visible());
+        // A steady cursor doesn't need a timer
+        assert_eq!(duration_until_next_timer_update(), None);
+        slint_mock_elapsed_time(1000);
+        assert!(text_input.cursor_visible());
+    }
+
+    #[test]
+    fn text_cursor_blinking_stops_on_focus_out() {
+        let (window, text_input) = blinking_text_input(200);
+        let text_input = text_input.as_ref();
+        text_input.focus_event(&FocusEvent::FocusIn, &window);
+        slint_mock_elapsed_time(200);
+        assert!(!text_input.cursor_visible());
+        assert!(duration_until_next_timer_update().is_some());
+
+        text_input.focus_event(&FocusEvent::FocusOut, &window);
+        assert!(!text_input.cursor_visible());
+        assert_eq!(duration_until_next_timer_update(), None);
+        slint_mock_elapsed_time(1000);
+        assert!(!text_input.cursor_visible());
+    }
 }
@@ -55,6 +55,18 @@ enum PointerEventButton {
                 middle,
             }
 
+            /// The phase of a scroll gesture on a touchpad.
+            enum ScrollEventPhase {
+                /// The event is not part of a gesture, for example when turning a mouse wheel.
+                none,
+                /// The fingers started moving on the touchpad.
+                begin,
+                /// The fingers moved.
+                update,
+                /// The fingers were lifted from the touchpad.
+                end,
+            }
+
             enum MouseCursor {
                 default,
                 none,
@@ -109,13 +121,26 @@ enum FillRule {
                 evenodd,
             }
 
-            /// This enum defines the input type in a text input which for now only distinguishes a normal
-            /// input from a password input
+            /// This enum defines the input type in a text input, which restricts the text the user can
+            /// enter or changes how it is shown
             enum InputType {
                 /// This type is used for a normal text input
                 text,
                 /// This type is used for password inputs where the characters are represented as *'s
                 password,
+                /// This type only accepts an integer number: digits with an optional minus sign in front
+                number,
+                /// This type only accepts a decimal number, with the decimal separator of the locale.
+                /// Both the point and the comma typed by the user are replaced by that separator.
+                decimal,
+            }
+
+            /// This enum defines whether the characters typed in a password input are shown
+            enum PasswordEcho {
+                /// The characters are always hidden
+                hidden,
+                /// The last typed character is shown for a short time, or until the next one is typed
+                last_character,
             }
             enum TextHorizontalAlignment {
                 left,
@@ -175,6 +200,86 @@ enum KeyEventType {
                 KeyPressed,
                 /// A key on a keyboard was released.
                 KeyReleased,
+                /// The text being composed by an input method changed. The text of the event is
+                /// the composition, which is shown at the cursor until it is committed.
+                UpdateComposition,
+                /// The input method finished the composition, and the text of the event is inserted.
+                CommitComposition,
+            }
+
+            /// The color scheme preferred by the user, as reported by the platform.
+            enum ColorScheme {
+                /// The platform doesn't report a preference.
+                unknown,
+                /// The user prefers dark text on a light background.
+                light,
+                /// The user prefers light text on a dark background.
+                dark,
+            }
+
+            /// The order in which the rows of a table are sorted by a column.
+            enum SortOrder {
+                /// The rows are not sorted by this column.
+                unsorted,
+                /// The rows are sorted by this column, from the smallest to the largest value.
+                ascending,
+                /// The rows are sorted by this column, from the largest to the smallest value.
+                descending,
+            }
+
+            /// What a Flickable does when it is dragged or flicked beyond the edges of its viewport.
+            enum OvershootBehavior {
+                /// The viewport stops at its edges.
+                none,
+                /// The viewport moves beyond its edges, and bounces back when it is released.
+                bounce,
+                /// The viewport stops at its edges, and the distance by which it is pulled beyond
+                /// them is reported by the Flickable, for example to draw a glow.
+                glow,
+            }
+
+            /// The severity of the message of a MessageBox, which decides its icon.
+            enum MessageLevel {
+                /// A neutral information.
+                information,
+                /// A warning about a possible problem.
+                warning,
+                /// The report of an error.
+                error,
+                /// A question for the user, answered with the buttons.
+                question,
+            }
+
+            /// The role of an element for assistive technologies, such as screen readers.
+            enum AccessibleRole {
+                /// The element isn't accessible.
+                none,
+                /// The element is a button.
+                button,
+                /// The element is a check box, or a switch.
+                checkbox,
+                /// The element is a combo box.
+                combobox,
+                /// The element is a slider.
+                slider,
+                /// The element is a spin box.
+                spinbox,
+                /// The element is a tab of a tab widget.
+                tab,
+                /// The element is a text, that is not editable.
+                text,
+                /// The element is a text input.
+                text_input,
+            }
+
+            /// How the keyboard focus moves within the children of a `FocusScope`.
+            enum FocusGroupPolicy {
+                /// The children are part of the focus chain of the window.
+                none,
+                /// Once one of the children has the focus, Tab and Backtab cycle through the children.
+                trap,
+                /// Tab and Backtab skip the children. They can still get the focus with the mouse or `focus()`.
+                skip,
             }
         ];
     };
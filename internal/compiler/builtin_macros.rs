@@ -54,6 +54,10 @@ pub fn lower_macro(
             expr
         }
         BuiltinMacroFunction::Rgb => rgb_macro(n, sub_expr.collect(), diag),
+        BuiltinMacroFunction::Format => format_macro(n, sub_expr.collect(), diag),
+        BuiltinMacroFunction::Clamp => clamp_macro(n, sub_expr.collect(), diag),
+        BuiltinMacroFunction::Lerp => lerp_macro(n, sub_expr.collect(), diag),
+        BuiltinMacroFunction::MapRange => map_range_macro(n, sub_expr.collect(), diag),
     }
 }
 
@@ -69,16 +73,9 @@ fn min_max_macro(
     }
     let mut args = args.into_iter();
     let (mut base, arg_node) = args.next().unwrap();
-    let ty = match base.ty() {
-        Type::Float32 => Type::Float32,
-        // In case there are other floats, we don't want to convert the result to int
-        Type::Int32 => Type::Float32,
-        Type::PhysicalLength => Type::PhysicalLength,
-        Type::LogicalLength => Type::LogicalLength,
-        Type::Duration => Type::Duration,
-        Type::Angle => Type::Angle,
-        Type::Percent => Type::Float32,
-        _ => {
+    let ty = match numeric_macro_type(&base.ty()) {
+        Some(ty) => ty,
+        None => {
             diag.push_error("Invalid argument type".into(), &arg_node);
             return Expression::Invalid;
         }
@@ -90,6 +87,152 @@ fn min_max_macro(
     base
 }
 
+/// The type in which min, max, clamp, lerp and map-range compute their result, from the
+/// type of their first argument
+fn numeric_macro_type(ty: &Type) -> Option<Type> {
+    match ty {
+        Type::Float32 => Some(Type::Float32),
+        // In case there are other floats, we don't want to convert the result to int
+        Type::Int32 => Some(Type::Float32),
+        Type::PhysicalLength => Some(Type::PhysicalLength),
+        Type::LogicalLength => Some(Type::LogicalLength),
+        Type::Duration => Some(Type::Duration),
+        Type::Angle => Some(Type::Angle),
+        Type::Percent => Some(Type::Float32),
+        _ => None,
+    }
+}
+
+/// Convert the arguments of a numeric macro to the type of the first one, or report an error
+/// if there isn't the expected count of arguments.
+fn numeric_macro_args(
+    node: &Option<NodeOrToken>,
+    args: Vec<(Expression, Option<NodeOrToken>)>,
+    count: usize,
+    diag: &mut BuildDiagnostics,
+) -> Option<Vec<Expression>> {
+    if args.len() != count {
+        diag.push_error(format!("Needs {} arguments", count), node);
+        return None;
+    }
+    let ty = match numeric_macro_type(&args[0].0.ty()) {
+        Some(ty) => ty,
+        None => {
+            if args[0].0.ty() != Type::Invalid {
+                diag.push_error("Invalid argument type".into(), &args[0].1);
+            }
+            return None;
+        }
+    };
+    let args = args.into_iter().map(|(e, n)| e.maybe_convert_to(ty.clone(), &n, diag)).collect();
+    Some(args)
+}
+
+/// Store `value` in a local variable, so it is evaluated only once even if the returned
+/// expression that reads it is used several times
+fn store_in_local_variable(
+    value: Expression,
+    name: &str,
+    statements: &mut Vec<Expression>,
+) -> Expression {
+    let name = format!("{}{}", name, COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+    let ty = value.ty();
+    statements.push(Expression::StoreLocalVariable { name: name.clone(), value: Box::new(value) });
+    Expression::ReadLocalVariable { name, ty }
+}
+
+fn clamp_macro(
+    node: Option<NodeOrToken>,
+    args: Vec<(Expression, Option<NodeOrToken>)>,
+    diag: &mut BuildDiagnostics,
+) -> Expression {
+    let mut args = match numeric_macro_args(&node, args, 3, diag) {
+        Some(args) => args.into_iter(),
+        None => return Expression::Invalid,
+    };
+    let (value, min, max) = (args.next().unwrap(), args.next().unwrap(), args.next().unwrap());
+    min_max_expression(min_max_expression(value, min, '>'), max, '<')
+}
+
+/// `lerp(a, b, t)` is `a + (b - a) * t`
+fn lerp_macro(
+    node: Option<NodeOrToken>,
+    mut args: Vec<(Expression, Option<NodeOrToken>)>,
+    diag: &mut BuildDiagnostics,
+) -> Expression {
+    if args.len() != 3 {
+        diag.push_error("Needs 3 arguments".into(), &node);
+        return Expression::Invalid;
+    }
+    let (factor, factor_node) = args.pop().unwrap();
+    let factor = factor.maybe_convert_to(Type::Float32, &factor_node, diag);
+    let mut args = match numeric_macro_args(&node, args, 2, diag) {
+        Some(args) => args.into_iter(),
+        None => return Expression::Invalid,
+    };
+    let (from, to) = (args.next().unwrap(), args.next().unwrap());
+    let mut statements = Vec::new();
+    let from = store_in_local_variable(from, "lerp_from", &mut statements);
+    statements.push(Expression::BinaryExpression {
+        lhs: Box::new(from.clone()),
+        op: '+',
+        rhs: Box::new(Expression::BinaryExpression {
+            lhs: Box::new(Expression::BinaryExpression {
+                lhs: Box::new(to),
+                op: '-',
+                rhs: Box::new(from),
+            }),
+            op: '*',
+            rhs: Box::new(factor),
+        }),
+    });
+    Expression::CodeBlock(statements)
+}
+
+/// `map-range(value, from-min, from-max, to-min, to-max)` is
+/// `to-min + (value - from-min) / (from-max - from-min) * (to-max - to-min)`
+fn map_range_macro(
+    node: Option<NodeOrToken>,
+    mut args: Vec<(Expression, Option<NodeOrToken>)>,
+    diag: &mut BuildDiagnostics,
+) -> Expression {
+    if args.len() != 5 {
+        diag.push_error("Needs 5 arguments".into(), &node);
+        return Expression::Invalid;
+    }
+    let to_args = args.split_off(3);
+    let (mut from_args, mut to_args) = match (
+        numeric_macro_args(&node, args, 3, diag),
+        numeric_macro_args(&node, to_args, 2, diag),
+    ) {
+        (Some(from_args), Some(to_args)) => (from_args.into_iter(), to_args.into_iter()),
+        _ => return Expression::Invalid,
+    };
+    let (value, from_min, from_max) =
+        (from_args.next().unwrap(), from_args.next().unwrap(), from_args.next().unwrap());
+    let (to_min, to_max) = (to_args.next().unwrap(), to_args.next().unwrap());
+    let mut statements = Vec::new();
+    let from_min = store_in_local_variable(from_min, "map_range_from", &mut statements);
+    let to_min = store_in_local_variable(to_min, "map_range_to", &mut statements);
+    let sub =
+        |lhs, rhs| Expression::BinaryExpression { lhs: Box::new(lhs), op: '-', rhs: Box::new(rhs) };
+    let ratio = Expression::BinaryExpression {
+        lhs: Box::new(sub(value, from_min.clone())),
+        op: '/',
+        rhs: Box::new(sub(from_max, from_min)),
+    };
+    statements.push(Expression::BinaryExpression {
+        lhs: Box::new(to_min.clone()),
+        op: '+',
+        rhs: Box::new(Expression::BinaryExpression {
+            lhs: Box::new(ratio),
+            op: '*',
+            rhs: Box::new(sub(to_max, to_min)),
+        }),
+    });
+    Expression::CodeBlock(statements)
+}
+
 fn rgb_macro(
     node: Option<NodeOrToken>,
     args: Vec<(Expression, Option<NodeOrToken>)>,
@@ -291,6 +434,294 @@ fn to_debug_string(
     }
 }
 
+/// The position of a formatted value within the width of its placeholder.
+/// The discriminant is the `alignment` argument of the `StringPad` builtin function.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum FormatAlignment {
+    Left = 0,
+    Right = 1,
+    Center = 2,
+    /// Pad numbers with zeros between their sign and their digits
+    AfterSign = 3,
+}
+
+/// A `{...}` placeholder of a format string
+#[derive(Debug, PartialEq)]
+struct FormatPlaceholder {
+    argument: usize,
+    fill: char,
+    alignment: Option<FormatAlignment>,
+    zero: bool,
+    width: Option<u32>,
+    precision: Option<u32>,
+}
+
+#[derive(Debug, PartialEq)]
+enum FormatPiece {
+    Literal(String),
+    Placeholder(FormatPlaceholder),
+}
+
+/// Parse a format string such as `"{:>8.2} {}"`. The syntax of the placeholders is
+/// `{[argument][:[[fill]alignment][0][width][.precision]]}`, like in Rust.
+fn parse_format_string(format_string: &str) -> Result<Vec<FormatPiece>, String> {
+    let mut pieces = Vec::new();
+    let mut literal = String::new();
+    let mut next_argument = 0;
+    let mut chars = format_string.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("Invalid format string: unmatched '}'".into()),
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => return Err("Invalid format string: unmatched '{'".into()),
+                    }
+                }
+                if !literal.is_empty() {
+                    pieces.push(FormatPiece::Literal(std::mem::take(&mut literal)));
+                }
+                pieces.push(FormatPiece::Placeholder(parse_format_placeholder(
+                    &placeholder,
+                    &mut next_argument,
+                )?));
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        pieces.push(FormatPiece::Literal(literal));
+    }
+    Ok(pieces)
+}
+
+fn parse_format_placeholder(
+    placeholder: &str,
+    next_argument: &mut usize,
+) -> Result<FormatPlaceholder, String> {
+    let error = || format!("Invalid format placeholder '{{{}}}'", placeholder);
+    let (argument, spec) = placeholder.split_once(':').unwrap_or((placeholder, ""));
+    let argument = if argument.is_empty() {
+        *next_argument += 1;
+        *next_argument - 1
+    } else {
+        argument.parse::<usize>().map_err(|_| error())?
+    };
+
+    let spec = spec.chars().collect::<Vec<_>>();
+    let mut pos = 0;
+    let alignment = |c: Option<&char>| match c {
+        Some('<') => Some(FormatAlignment::Left),
+        Some('>') => Some(FormatAlignment::Right),
+        Some('^') => Some(FormatAlignment::Center),
+        _ => None,
+    };
+    let (fill, alignment) = if let Some(a) = alignment(spec.get(1)) {
+        pos = 2;
+        (spec[0], Some(a))
+    } else if let Some(a) = alignment(spec.first()) {
+        pos = 1;
+        (' ', Some(a))
+    } else {
+        (' ', None)
+    };
+    let zero = spec.get(pos) == Some(&'0');
+    if zero {
+        pos += 1;
+    }
+    let number = |pos: &mut usize| {
+        let start = *pos;
+        while spec.get(*pos).map_or(false, char::is_ascii_digit) {
+            *pos += 1;
+        }
+        (*pos > start).then(|| spec[start..*pos].iter().collect::<String>().parse::<u32>())
+    };
+    let width = number(&mut pos).transpose().map_err(|_| error())?;
+    let precision = if spec.get(pos) == Some(&'.') {
+        pos += 1;
+        Some(number(&mut pos).ok_or_else(error)?.map_err(|_| error())?)
+    } else {
+        None
+    };
+    if pos != spec.len() {
+        return Err(error());
+    }
+    Ok(FormatPlaceholder { argument, fill, alignment, zero, width, precision })
+}
+
+/// The `"...".format(args...)` function: the format string is parsed at compile time, and
+/// the placeholders are replaced by the formatted arguments
+fn format_macro(
+    node: Option<NodeOrToken>,
+    args: Vec<(Expression, Option<NodeOrToken>)>,
+    diag: &mut BuildDiagnostics,
+) -> Expression {
+    let mut args = args.into_iter();
+    let format_string = match args.next() {
+        Some((Expression::StringLiteral(s), _)) => s,
+        Some((e, _)) if e.ty() == Type::Invalid => return Expression::Invalid,
+        _ => {
+            diag.push_error("The format string must be a string literal".into(), &node);
+            return Expression::Invalid;
+        }
+    };
+    let pieces = match parse_format_string(&format_string) {
+        Ok(pieces) => pieces,
+        Err(err) => {
+            diag.push_error(err, &node);
+            return Expression::Invalid;
+        }
+    };
+    let args = args.collect::<Vec<_>>();
+    let mut used = vec![false; args.len()];
+
+    let sl = node.as_ref().map(|n| n.to_source_location());
+    let call = |function, arguments| Expression::FunctionCall {
+        function: Box::new(Expression::BuiltinFunctionReference(function, sl.clone())),
+        arguments,
+        source_location: sl.clone(),
+    };
+
+    let mut result = None;
+    for piece in pieces {
+        let value = match piece {
+            FormatPiece::Literal(literal) => Expression::StringLiteral(literal),
+            FormatPiece::Placeholder(placeholder) => {
+                let (arg, arg_node) = match args.get(placeholder.argument) {
+                    Some(arg) => arg,
+                    None => {
+                        diag.push_error(
+                            format!(
+                                "Missing argument {} for the format string",
+                                placeholder.argument
+                            ),
+                            &node,
+                        );
+                        return Expression::Invalid;
+                    }
+                };
+                used[placeholder.argument] = true;
+                let ty = arg.ty();
+                let is_number = matches!(ty, Type::Float32 | Type::Int32);
+                if (placeholder.precision.is_some() || placeholder.zero) && !is_number {
+                    if ty != Type::Invalid {
+                        diag.push_error(
+                            format!(
+                                "The precision and the '0' flag of the format string can only be used with numbers, not {}",
+                                ty
+                            ),
+                            arg_node,
+                        );
+                    }
+                    return Expression::Invalid;
+                }
+                let mut value = match (placeholder.precision, &ty) {
+                    (Some(precision), _) => call(
+                        BuiltinFunction::StringFormatFloat,
+                        vec![
+                            arg.clone().maybe_convert_to(Type::Float32, arg_node, diag),
+                            Expression::NumberLiteral(precision as _, Unit::None),
+                        ],
+                    ),
+                    (None, Type::Bool | Type::Enumeration(_)) => {
+                        to_debug_string(arg.clone(), arg_node.clone(), diag)
+                    }
+                    (None, _) if ty != Type::Invalid && !ty.can_convert(&Type::String) => {
+                        // Reports the error, and the result must not be converted again
+                        let _ = arg.clone().maybe_convert_to(Type::String, arg_node, diag);
+                        return Expression::Invalid;
+                    }
+                    (None, _) => arg.clone().maybe_convert_to(Type::String, arg_node, diag),
+                };
+                if let Some(width) = placeholder.width {
+                    let (fill, alignment) = if placeholder.zero {
+                        ('0', FormatAlignment::AfterSign)
+                    } else {
+                        let default_alignment =
+                            if is_number { FormatAlignment::Right } else { FormatAlignment::Left };
+                        (placeholder.fill, placeholder.alignment.unwrap_or(default_alignment))
+                    };
+                    value = call(
+                        BuiltinFunction::StringPad,
+                        vec![
+                            value,
+                            Expression::NumberLiteral(width as _, Unit::None),
+                            Expression::StringLiteral(fill.into()),
+                            Expression::NumberLiteral(alignment as i32 as _, Unit::None),
+                        ],
+                    );
+                }
+                value
+            }
+        };
+        result = Some(match result {
+            None => value,
+            Some(result) => Expression::BinaryExpression {
+                lhs: Box::new(result),
+                op: '+',
+                rhs: Box::new(value),
+            },
+        });
+    }
+
+    for ((_, arg_node), used) in args.iter().zip(used) {
+        if !used {
+            diag.push_error("This argument is not used in the format string".into(), arg_node);
+        }
+    }
+    result.unwrap_or_else(|| Expression::StringLiteral(String::new()))
+}
+
+#[test]
+fn test_parse_format_string() {
+    let placeholder = |argument, fill, alignment, zero, width, precision| {
+        FormatPiece::Placeholder(FormatPlaceholder {
+            argument,
+            fill,
+            alignment,
+            zero,
+            width,
+            precision,
+        })
+    };
+    assert_eq!(
+        parse_format_string("{}°C"),
+        Ok(vec![placeholder(0, ' ', None, false, None, None), FormatPiece::Literal("°C".into())])
+    );
+    assert_eq!(
+        parse_format_string("{{{1:*^10.2}}} {:08}"),
+        Ok(vec![
+            FormatPiece::Literal("{".into()),
+            placeholder(1, '*', Some(FormatAlignment::Center), false, Some(10), Some(2)),
+            FormatPiece::Literal("} ".into()),
+            placeholder(0, ' ', None, true, Some(8), None),
+        ])
+    );
+    assert_eq!(
+        parse_format_string("{:<5}{:.1}"),
+        Ok(vec![
+            placeholder(0, ' ', Some(FormatAlignment::Left), false, Some(5), None),
+            placeholder(1, ' ', None, false, None, Some(1)),
+        ])
+    );
+    assert!(parse_format_string("{").is_err());
+    assert!(parse_format_string("}").is_err());
+    assert!(parse_format_string("{:.}").is_err());
+    assert!(parse_format_string("{:x}").is_err());
+    assert!(parse_format_string("{a}").is_err());
+}
+
 /// Generate an expression which is like `min(lhs, rhs)` if op is '<' or `max(lhs, rhs)` if op is '>'.
 /// counter is an unique id.
 /// The rhs and lhs of the expression must have the same numerical type
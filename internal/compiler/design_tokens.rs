@@ -0,0 +1,332 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Import of design tokens from JSON files.
+
+A `.json` file in an import statement is read as a design-token file, either in the format of
+the W3C Design Tokens Community Group (tokens are objects with a `$value` and an optional `$type`,
+which can also be set on a group) or as plain nested objects whose leaves are the values.
+The file is converted to the source of a `.slint` document exporting a global called
+[`DESIGN_TOKENS_GLOBAL`], with one property per token. The name of the property is the path of
+the token, joined with `-`, so that `{"color": {"primary": ...}}` becomes `color-primary`.
+References to other tokens such as `"{color.primary}"` become bindings to the other property.
+*/
+
+use crate::expression_tree::Unit;
+use crate::langtype::Type;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// The name of the global exported by an imported design-token file
+pub const DESIGN_TOKENS_GLOBAL: &str = "DesignTokens";
+
+enum TokenValue {
+    /// The type and the `.slint` literal of the value
+    Literal(Type, String),
+    /// A reference to the token with that property name
+    Alias(String),
+}
+
+struct Token {
+    name: String,
+    value: TokenValue,
+    description: Option<String>,
+}
+
+/// Convert the content of a design-token file to `.slint` source code.
+///
+/// Returns the source code and warnings for the tokens that were ignored, or an error if the
+/// file is not a valid JSON object.
+pub fn to_slint_source(json: &str) -> Result<(String, Vec<String>), String> {
+    let root: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    if !root.is_object() {
+        return Err("the root of a design-token file must be an object".into());
+    }
+    let mut tokens = Vec::new();
+    let mut warnings = Vec::new();
+    collect_tokens(&root, &mut Vec::new(), None, &mut tokens, &mut warnings);
+
+    let mut types = HashMap::new();
+    for token in &tokens {
+        if let TokenValue::Literal(ty, _) = &token.value {
+            if types.insert(token.name.clone(), ty.clone()).is_some() {
+                warnings.push(format!("Design token '{}' is defined more than once", token.name));
+            }
+        }
+    }
+
+    let mut source = format!("export global {} := {{\n", DESIGN_TOKENS_GLOBAL);
+    for token in &tokens {
+        let (ty, value) = match &token.value {
+            TokenValue::Literal(ty, value) => (ty.clone(), value.clone()),
+            TokenValue::Alias(target) => match resolve_alias(target, &tokens, &types) {
+                Some(ty) => (ty, target.clone()),
+                None => {
+                    warnings.push(format!(
+                        "Design token '{}' refers to the unknown token '{}'",
+                        token.name, target
+                    ));
+                    continue;
+                }
+            },
+        };
+        if let Some(description) = &token.description {
+            for line in description.lines() {
+                let _ = writeln!(source, "    // {}", line);
+            }
+        }
+        let _ = writeln!(source, "    property <{}> {}: {};", ty, token.name, value);
+    }
+    source.push_str("}\n");
+    Ok((source, warnings))
+}
+
+fn collect_tokens(
+    value: &Value,
+    path: &mut Vec<String>,
+    group_type: Option<&str>,
+    tokens: &mut Vec<Token>,
+    warnings: &mut Vec<String>,
+) {
+    let object = match value {
+        Value::Object(object) if !object.contains_key("$value") => object,
+        _ => {
+            let name = property_name(path);
+            let ty = value.get("$type").and_then(Value::as_str).or(group_type);
+            let raw_value = value.get("$value").unwrap_or(value);
+            match token_value(raw_value, ty) {
+                Ok(token_value) => tokens.push(Token {
+                    name,
+                    value: token_value,
+                    description: value
+                        .get("$description")
+                        .and_then(Value::as_str)
+                        .map(str::to_owned),
+                }),
+                Err(reason) => {
+                    warnings.push(format!("Design token '{}' was ignored: {}", name, reason))
+                }
+            }
+            return;
+        }
+    };
+    let group_type = object.get("$type").and_then(Value::as_str).or(group_type);
+    for (key, child) in object {
+        if key.starts_with('$') {
+            continue;
+        }
+        path.push(key.clone());
+        collect_tokens(child, path, group_type, tokens, warnings);
+        path.pop();
+    }
+}
+
+fn token_value(value: &Value, ty: Option<&str>) -> Result<TokenValue, String> {
+    if let Some(reference) =
+        value.as_str().and_then(|s| s.strip_prefix('{')).and_then(|s| s.strip_suffix('}'))
+    {
+        let path = reference.split('.').map(str::to_owned).collect::<Vec<_>>();
+        return Ok(TokenValue::Alias(property_name(&path)));
+    }
+    let (ty, literal) = match (ty, value) {
+        (Some("color"), Value::String(s)) => {
+            (Type::Color, color_literal(s).ok_or_else(|| format!("invalid color '{}'", s))?)
+        }
+        (Some("dimension"), _) => dimension_literal(value, Unit::Px)?,
+        (Some("duration"), _) => dimension_literal(value, Unit::Ms)?,
+        (Some("number"), Value::Number(n)) => (Type::Float32, n.to_string()),
+        (Some("fontWeight"), Value::Number(n)) => (Type::Int32, n.to_string()),
+        (Some("fontWeight"), Value::String(s)) => (
+            Type::Int32,
+            font_weight(s).ok_or_else(|| format!("unknown font weight '{}'", s))?.to_string(),
+        ),
+        (Some("fontFamily"), Value::String(s)) => (Type::String, string_literal(s)),
+        (Some("fontFamily"), Value::Array(families)) => (
+            Type::String,
+            families
+                .first()
+                .and_then(Value::as_str)
+                .map(string_literal)
+                .ok_or_else(|| "empty list of font families".to_owned())?,
+        ),
+        (Some(ty @ ("color" | "number" | "fontWeight" | "fontFamily")), _) => {
+            return Err(format!("invalid value for the type '{}'", ty));
+        }
+        (Some(ty), _) => return Err(format!("the type '{}' is not supported", ty)),
+        (None, Value::Bool(b)) => (Type::Bool, b.to_string()),
+        (None, Value::Number(n)) => (Type::Float32, n.to_string()),
+        (None, Value::String(s)) => infer_string_literal(s),
+        (None, _) => {
+            return Err("values that are not a string, a number or a boolean need a $type".into())
+        }
+    };
+    Ok(TokenValue::Literal(ty, literal))
+}
+
+/// The type of the token at the end of a chain of aliases
+fn resolve_alias(target: &str, tokens: &[Token], types: &HashMap<String, Type>) -> Option<Type> {
+    let mut target = target;
+    // Stop after as many steps as there are tokens, in case the aliases form a loop
+    for _ in 0..tokens.len() {
+        if let Some(ty) = types.get(target) {
+            return Some(ty.clone());
+        }
+        target = tokens.iter().find_map(|t| match &t.value {
+            TokenValue::Alias(next) if t.name == target => Some(next.as_str()),
+            _ => None,
+        })?;
+    }
+    None
+}
+
+/// Returns the property name for the path of a token
+fn property_name(path: &[String]) -> String {
+    let name = path
+        .join("-")
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '-' })
+        .collect::<String>();
+    if name.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        name
+    } else {
+        format!("token-{}", name)
+    }
+}
+
+/// A `dimension` or `duration` token, either as a string such as `"16px"` or as an
+/// object such as `{"value": 16, "unit": "px"}`. Numbers without unit use `default_unit`.
+fn dimension_literal(value: &Value, default_unit: Unit) -> Result<(Type, String), String> {
+    let (number, unit) = match value {
+        Value::Number(n) => (n.as_f64().unwrap_or_default(), default_unit),
+        Value::String(s) => split_unit(s).ok_or_else(|| format!("invalid dimension '{}'", s))?,
+        Value::Object(o) => {
+            let number = o.get("value").and_then(Value::as_f64);
+            let unit = o.get("unit").and_then(Value::as_str).and_then(|u| u.parse::<Unit>().ok());
+            number.zip(unit).ok_or_else(|| "invalid dimension".to_owned())?
+        }
+        _ => return Err("invalid dimension".into()),
+    };
+    check_unit(unit)?;
+    Ok((unit.ty(), format!("{}{}", number, unit)))
+}
+
+/// Infer the type of a token without `$type` from the syntax of its value
+fn infer_string_literal(s: &str) -> (Type, String) {
+    let looks_like_color =
+        ["#", "rgb(", "rgba(", "hsl(", "hsla("].iter().any(|prefix| s.trim().starts_with(prefix));
+    if let Some(color) = color_literal(s).filter(|_| looks_like_color) {
+        return (Type::Color, color);
+    }
+    match split_unit(s) {
+        Some((number, unit)) if unit != Unit::None && check_unit(unit).is_ok() => {
+            (unit.ty(), format!("{}{}", number, unit))
+        }
+        _ => (Type::String, string_literal(s)),
+    }
+}
+
+fn split_unit(s: &str) -> Option<(f64, Unit)> {
+    let s = s.trim();
+    let unit_start = s.find(|c: char| c.is_alphabetic() || c == '%').unwrap_or(s.len());
+    let number = s[..unit_start].trim().parse::<f64>().ok()?;
+    let unit = s[unit_start..].parse::<Unit>().ok()?;
+    Some((number, unit))
+}
+
+/// The units relative to the window cannot be used in a global
+fn check_unit(unit: Unit) -> Result<(), String> {
+    match unit {
+        Unit::Vw | Unit::Vh | Unit::Rem => {
+            Err(format!("the unit '{}' is relative to the window and cannot be used", unit))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn color_literal(s: &str) -> Option<String> {
+    let c = s.trim().parse::<css_color_parser2::Color>().ok()?;
+    Some(format!("#{:02x}{:02x}{:02x}{:02x}", c.r, c.g, c.b, (c.a * 255.).round() as u8))
+}
+
+fn font_weight(name: &str) -> Option<i32> {
+    Some(match name.to_ascii_lowercase().replace(['-', ' ', '_'], "").as_str() {
+        "thin" | "hairline" => 100,
+        "extralight" | "ultralight" => 200,
+        "light" => 300,
+        "normal" | "regular" | "book" => 400,
+        "medium" => 500,
+        "semibold" | "demibold" => 600,
+        "bold" => 700,
+        "extrabold" | "ultrabold" => 800,
+        "black" | "heavy" => 900,
+        "extrablack" | "ultrablack" => 950,
+        _ => return None,
+    })
+}
+
+fn string_literal(s: &str) -> String {
+    let mut result = String::from('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+#[test]
+fn test_design_tokens_to_slint() {
+    let (source, warnings) = to_slint_source(
+        r##"{
+            "color": {
+                "$type": "color",
+                "primary": { "$value": "#0078d4", "$description": "The brand color" },
+                "button": { "$value": "{color.primary}" }
+            },
+            "spacing": {
+                "small": { "$value": "4px", "$type": "dimension" },
+                "large": { "$value": { "value": 1, "unit": "cm" }, "$type": "dimension" },
+                "relative": { "$value": "2rem", "$type": "dimension" }
+            },
+            "animation": { "$value": "0.2s", "$type": "duration" },
+            "font weight": { "$value": "semi-bold", "$type": "fontWeight" },
+            "shadow": { "$value": { "blur": "2px" }, "$type": "shadow" },
+            "simple": { "opacity": 0.5, "title": "Hello \"world\"", "border": "1px", "accent": "rgb(255, 0, 0)" },
+            "100": "#fff"
+        }"##,
+    )
+    .unwrap();
+    assert_eq!(
+        source,
+        r##"export global DesignTokens := {
+    // The brand color
+    property <color> color-primary: #0078d4ff;
+    property <color> color-button: color-primary;
+    property <length> spacing-small: 4px;
+    property <length> spacing-large: 1cm;
+    property <duration> animation: 0.2s;
+    property <int> font-weight: 600;
+    property <float> simple-opacity: 0.5;
+    property <string> simple-title: "Hello \"world\"";
+    property <length> simple-border: 1px;
+    property <color> simple-accent: #ff0000ff;
+    property <color> token-100: #ffffffff;
+}
+"##
+    );
+    assert_eq!(
+        warnings,
+        [
+            "Design token 'spacing-relative' was ignored: the unit 'rem' is relative to the window and cannot be used",
+            "Design token 'shadow' was ignored: the type 'shadow' is not supported",
+        ]
+    );
+    assert!(to_slint_source("[1, 2]").is_err());
+    assert!(to_slint_source("{ \"a\": ").is_err());
+}
@@ -91,6 +91,22 @@ pub fn from_path_only(path: PathBuf) -> Rc<Self> {
         Rc::new(Self { path, ..Default::default() })
     }
 
+    /// Returns a tuple with the line (starting at 1) and column number (starting at 0) of
+    /// the given byte offset
+    pub fn line_column(&self, offset: usize) -> (usize, usize) {
+        let line_offsets = self.line_offsets();
+        line_offsets.binary_search(&offset).map_or_else(
+            |line| {
+                if line == 0 {
+                    (line + 1, offset)
+                } else {
+                    (line + 1, line_offsets.get(line - 1).map_or(0, |x| offset - x))
+                }
+            },
+            |line| (line + 1, 0),
+        )
+    }
+
     fn line_offsets(&self) -> &[usize] {
         self.line_offsets.get_or_init(|| {
             self.source
@@ -126,6 +142,7 @@ pub fn load_from_path(path: &Path) -> Result<String, Diagnostic> {
             span: Default::default(),
         },
         level: DiagnosticLevel::Error,
+        fix_its: Vec::new(),
     })
 }
 
@@ -181,6 +198,31 @@ fn from(l: DiagnosticLevel) -> Self {
     }
 }
 
+impl DiagnosticLevel {
+    fn as_str(self) -> &'static str {
+        match self {
+            DiagnosticLevel::Error => "error",
+            DiagnosticLevel::Warning => "warning",
+        }
+    }
+}
+
+/// A change of the source code suggested to fix a diagnostic, such as the replacement of a
+/// misspelled property name.
+///
+/// The change applies to the source file of the diagnostic.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixIt {
+    /// Byte offset of the text to replace
+    pub offset: usize,
+    /// Length in bytes of the text to replace
+    pub length: usize,
+    /// The text to insert instead
+    pub replacement: String,
+    /// A short description of the change, such as "did you mean `background`?"
+    pub message: String,
+}
+
 /// This structure represent a diagnostic emitted while compiling .slint code.
 ///
 /// It is basically a message, a level (warning or error), attached to a
@@ -190,6 +232,7 @@ pub struct Diagnostic {
     message: String,
     span: SourceLocation,
     level: DiagnosticLevel,
+    fix_its: Vec<FixIt>,
 }
 
 impl Diagnostic {
@@ -205,27 +248,121 @@ pub fn message(&self) -> &str {
 
     /// Returns a tuple with the line (starting at 1) and column number (starting at 0)
     pub fn line_column(&self) -> (usize, usize) {
-        let offset = self.span.span.offset;
-        let line_offsets = match &self.span.source_file {
-            None => return (0, 0),
-            Some(sl) => sl.line_offsets(),
-        };
-        line_offsets.binary_search(&offset).map_or_else(
-            |line| {
-                if line == 0 {
-                    (line + 1, offset)
-                } else {
-                    (line + 1, line_offsets.get(line - 1).map_or(0, |x| offset - x))
-                }
-            },
-            |line| (line + 1, 0),
-        )
+        match &self.span.source_file {
+            None => (0, 0),
+            Some(sf) => sf.line_column(self.span.span.offset),
+        }
     }
 
     /// return the path of the source file where this error is attached
     pub fn source_file(&self) -> Option<&Path> {
         self.span.source_file().map(|sf| sf.path())
     }
+
+    /// Return the changes of the source code suggested to fix this diagnostic
+    pub fn fix_its(&self) -> &[FixIt] {
+        &self.fix_its
+    }
+
+    /// Returns the line (starting at 1) and column (starting at 0) of the start and of the end
+    /// of the text replaced by the fix-it, which must be one of this diagnostic's fix-its
+    pub fn fix_it_range(&self, fix_it: &FixIt) -> ((usize, usize), (usize, usize)) {
+        match &self.span.source_file {
+            None => ((0, 0), (0, 0)),
+            Some(sf) => {
+                (sf.line_column(fix_it.offset), sf.line_column(fix_it.offset + fix_it.length))
+            }
+        }
+    }
+
+    /// Serialize this diagnostic as a JSON object, for tools that consume the diagnostics.
+    ///
+    /// The object has the following fields: `file` (or `null`), `line` and `column` (as returned
+    /// by [`Self::line_column`]), `level` (`"error"` or `"warning"`), `message`, and `fix_its`,
+    /// an array of objects with the `line`, `column`, `end_line` and `end_column` of the
+    /// text to replace, along with the `replacement` and a `message`.
+    pub fn to_json(&self) -> String {
+        let (line, column) = self.line_column();
+        let file =
+            self.source_file().map_or_else(|| "null".into(), |p| json_string(&p.to_string_lossy()));
+        let fix_its = self
+            .fix_its
+            .iter()
+            .map(|fix_it| {
+                let ((line, column), (end_line, end_column)) = self.fix_it_range(fix_it);
+                format!(
+                    r#"{{"line":{},"column":{},"end_line":{},"end_column":{},"replacement":{},"message":{}}}"#,
+                    line,
+                    column,
+                    end_line,
+                    end_column,
+                    json_string(&fix_it.replacement),
+                    json_string(&fix_it.message)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            r#"{{"file":{},"line":{},"column":{},"level":"{}","message":{},"fix_its":[{}]}}"#,
+            file,
+            line,
+            column,
+            self.level.as_str(),
+            json_string(&self.message),
+            fix_its
+        )
+    }
+}
+
+/// Quote and escape a string for JSON
+fn json_string(s: &str) -> String {
+    let mut result = String::with_capacity(s.len() + 2);
+    result.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// Returns the candidate that is the most similar to `name`, if it is similar enough to be
+/// a likely misspelling of it. Used to suggest a fix for unknown names.
+pub fn closest_match<S: AsRef<str>>(
+    name: &str,
+    candidates: impl IntoIterator<Item = S>,
+) -> Option<S> {
+    let max_distance = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|c| c.as_ref() != name)
+        .map(|c| (edit_distance(name, c.as_ref()), c))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, c)| c)
+}
+
+/// The Levenshtein distance between the two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
 }
 
 impl std::fmt::Display for Diagnostic {
@@ -270,7 +407,7 @@ pub fn push_diagnostic_with_span(
             "Error message should not end with a period: ({:?})",
             message
         );
-        self.inner.push(Diagnostic { message, span, level });
+        self.inner.push(Diagnostic { message, span, level, fix_its: Vec::new() });
     }
     pub fn push_error_with_span(&mut self, message: String, span: SourceLocation) {
         self.push_diagnostic_with_span(message, span, DiagnosticLevel::Error)
@@ -284,6 +421,29 @@ pub fn push_warning_with_span(&mut self, message: String, span: SourceLocation)
     pub fn push_warning(&mut self, message: String, source: &dyn Spanned) {
         self.push_warning_with_span(message, source.to_source_location());
     }
+
+    /// Report an error about an unknown `name`, written by the `token`, and suggest to
+    /// replace it with the most similar of the `candidates`, if any
+    pub fn push_error_with_suggestion<S: AsRef<str>>(
+        &mut self,
+        message: String,
+        token: &crate::parser::SyntaxToken,
+        candidates: impl IntoIterator<Item = S>,
+    ) {
+        let name = crate::parser::normalize_identifier(token.text());
+        self.push_error(message, token);
+        if let Some(suggestion) = closest_match(&name, candidates) {
+            let suggestion = suggestion.as_ref();
+            let diag = self.inner.last_mut().unwrap();
+            diag.fix_its.push(FixIt {
+                offset: diag.span.span.offset,
+                length: token.text().len(),
+                replacement: suggestion.into(),
+                message: format!("did you mean `{}`?", suggestion),
+            });
+        }
+    }
+
     pub fn push_compiler_error(&mut self, error: Diagnostic) {
         self.inner.push(error);
     }
@@ -356,7 +516,7 @@ fn call_diagnostics<Output>(
                         span: file_span
                             .subspan(d.span.span.offset as u64, d.span.span.offset as u64),
                         style: codemap_diagnostic::SpanStyle::Primary,
-                        label: None,
+                        label: d.fix_its.first().map(|fix_it| fix_it.message.clone()),
                     };
                     vec![s]
                 } else {
@@ -454,6 +614,12 @@ pub fn to_string_vec(&self) -> Vec<String> {
         self.inner.iter().map(|d| d.to_string()).collect()
     }
 
+    /// Serialize all the diagnostics as a JSON array of the objects described in
+    /// [`Diagnostic::to_json`]
+    pub fn to_json(&self) -> String {
+        format!("[{}]", self.inner.iter().map(|d| d.to_json()).collect::<Vec<_>>().join(","))
+    }
+
     pub fn push_diagnostic(
         &mut self,
         message: String,
@@ -490,3 +656,37 @@ pub fn print_warnings_and_exit_on_error(self) {
         }
     }
 }
+
+#[test]
+fn test_closest_match() {
+    let candidates = ["background", "border-color", "border-width", "x"];
+    assert_eq!(closest_match("backgroud", candidates), Some("background"));
+    assert_eq!(closest_match("border-colour", candidates), Some("border-color"));
+    assert_eq!(closest_match("y", candidates), Some("x"));
+    assert_eq!(closest_match("foreground", candidates), None);
+    assert_eq!(closest_match("background", candidates), None);
+}
+
+#[test]
+fn test_diagnostic_to_json() {
+    let source_file = Rc::new(SourceFileInner::new(
+        "foo.slint".into(),
+        "A := Rectangle {\n  backgroud: red;\n}".into(),
+    ));
+    let diag = Diagnostic {
+        message: "Unknown property backgroud in Rectangle".into(),
+        span: SourceLocation { source_file: Some(source_file), span: Span::new(19) },
+        level: DiagnosticLevel::Error,
+        fix_its: vec![FixIt {
+            offset: 19,
+            length: 9,
+            replacement: "background".into(),
+            message: "did you mean `background`?".into(),
+        }],
+    };
+    assert_eq!(
+        diag.to_json(),
+        r#"{"file":"foo.slint","line":2,"column":3,"level":"error","message":"Unknown property backgroud in Rectangle","fix_its":[{"line":2,"column":3,"end_line":2,"end_column":12,"replacement":"background","message":"did you mean `background`?"}]}"#
+    );
+    assert_eq!(json_string("a\"b\\c\n"), r#""a\"b\\c\n""#);
+}
@@ -82,6 +82,8 @@ pub struct BitmapFont {
 pub enum EmbeddedResourcesKind {
     /// Just put the file content as a resource
     RawData,
+    /// The file was processed by the compiler, for example a downscaled image or a subset font
+    ProcessedData(Vec<u8>),
     /// The data has been processed in a texture
     TextureData(#[cfg(not(target_arch = "wasm32"))] Texture),
     /// A set of pre-rendered glyphs of a TrueType font
@@ -18,6 +18,12 @@
 /// A function built into the run-time
 pub enum BuiltinFunction {
     GetWindowScaleFactor,
+    /// The width of the window, used by the `vw` unit
+    GetWindowWidth,
+    /// The height of the window, used by the `vh` unit
+    GetWindowHeight,
+    /// The default font size of the window, used by the `rem` unit
+    GetWindowDefaultFontSize,
     Debug,
     Mod,
     Round,
@@ -25,6 +31,8 @@ pub enum BuiltinFunction {
     Floor,
     Abs,
     Sqrt,
+    /// The `snap(length)` function, which rounds a length to the nearest physical pixel
+    Snap,
     Cos,
     Sin,
     Tan,
@@ -35,12 +43,44 @@ pub enum BuiltinFunction {
     Pow,
     SetFocusItem,
     ShowPopupWindow,
+    /// The `scroll-to(x, y, animated)` function of a Flickable
+    ScrollTo,
+    /// The `ensure-visible(element)` function of a Flickable
+    EnsureVisible,
+    /// The `undo()` function of a TextInput
+    TextInputUndo,
+    /// The `redo()` function of a TextInput
+    TextInputRedo,
+    /// The `find-next()` function of a TextInput
+    TextInputFindNext,
+    /// The `find-previous()` function of a TextInput
+    TextInputFindPrevious,
+    /// The `search-match-count()` function of a TextInput
+    TextInputSearchMatchCount,
+    /// The `current-match()` function of a TextInput
+    TextInputCurrentMatch,
     /// the "42".to_float()
     StringToFloat,
     /// the "42".is_float()
     StringIsFloat,
+    /// The `{:.2}` placeholders of "{:.2}".format(x)
+    StringFormatFloat,
+    /// The width of the `{:>8}` placeholders of "{:>8}".format(x)
+    StringPad,
     ColorBrighter,
     ColorDarker,
+    ColorLighter,
+    ColorSaturate,
+    ColorWithAlpha,
+    ColorMix,
+    FormatNumber,
+    FormatPercent,
+    FormatDate,
+    FormatTime,
+    /// The color scheme preferred by the user, used by `Platform.color-scheme`
+    GetColorScheme,
+    /// The time elapsed since the start of the animation driver, used by `animation-tick()`
+    AnimationTick,
     ImageSize,
     ArrayLength,
     Rgb,
@@ -58,6 +98,10 @@ pub enum BuiltinMacroFunction {
     CubicBezier,
     Rgb,
     Debug,
+    Format,
+    Clamp,
+    Lerp,
+    MapRange,
 }
 
 impl BuiltinFunction {
@@ -67,6 +111,11 @@ pub fn ty(&self) -> Type {
                 return_type: Box::new(Type::UnitProduct(vec![(Unit::Phx, 1), (Unit::Px, -1)])),
                 args: vec![],
             },
+            BuiltinFunction::GetWindowWidth
+            | BuiltinFunction::GetWindowHeight
+            | BuiltinFunction::GetWindowDefaultFontSize => {
+                Type::Function { return_type: Box::new(Type::LogicalLength), args: vec![] }
+            }
             BuiltinFunction::Debug => {
                 Type::Function { return_type: Box::new(Type::Void), args: vec![Type::String] }
             }
@@ -80,6 +129,10 @@ pub fn ty(&self) -> Type {
             BuiltinFunction::Sqrt | BuiltinFunction::Abs => {
                 Type::Function { return_type: Box::new(Type::Float32), args: vec![Type::Float32] }
             }
+            BuiltinFunction::Snap => Type::Function {
+                return_type: Box::new(Type::LogicalLength),
+                args: vec![Type::LogicalLength],
+            },
             BuiltinFunction::Cos | BuiltinFunction::Sin | BuiltinFunction::Tan => {
                 Type::Function { return_type: Box::new(Type::Float32), args: vec![Type::Angle] }
             }
@@ -98,12 +151,49 @@ pub fn ty(&self) -> Type {
                 return_type: Box::new(Type::Void),
                 args: vec![Type::ElementReference],
             },
+            BuiltinFunction::ScrollTo => Type::Function {
+                return_type: Box::new(Type::Void),
+                args: vec![
+                    Type::ElementReference,
+                    Type::LogicalLength,
+                    Type::LogicalLength,
+                    Type::Bool,
+                ],
+            },
+            BuiltinFunction::EnsureVisible => Type::Function {
+                return_type: Box::new(Type::Void),
+                args: vec![Type::ElementReference, Type::ElementReference],
+            },
+            BuiltinFunction::TextInputUndo | BuiltinFunction::TextInputRedo => Type::Function {
+                return_type: Box::new(Type::Void),
+                args: vec![Type::ElementReference],
+            },
+            BuiltinFunction::TextInputFindNext | BuiltinFunction::TextInputFindPrevious => {
+                Type::Function {
+                    return_type: Box::new(Type::Bool),
+                    args: vec![Type::ElementReference],
+                }
+            }
+            BuiltinFunction::TextInputSearchMatchCount | BuiltinFunction::TextInputCurrentMatch => {
+                Type::Function {
+                    return_type: Box::new(Type::Int32),
+                    args: vec![Type::ElementReference],
+                }
+            }
             BuiltinFunction::StringToFloat => {
                 Type::Function { return_type: Box::new(Type::Float32), args: vec![Type::String] }
             }
             BuiltinFunction::StringIsFloat => {
                 Type::Function { return_type: Box::new(Type::Bool), args: vec![Type::String] }
             }
+            BuiltinFunction::StringFormatFloat => Type::Function {
+                return_type: Box::new(Type::String),
+                args: vec![Type::Float32, Type::Int32],
+            },
+            BuiltinFunction::StringPad => Type::Function {
+                return_type: Box::new(Type::String),
+                args: vec![Type::String, Type::Int32, Type::String, Type::Int32],
+            },
             BuiltinFunction::ImplicitLayoutInfo(_) => Type::Function {
                 return_type: Box::new(crate::layout::layout_info_type()),
                 args: vec![Type::ElementReference],
@@ -116,6 +206,32 @@ pub fn ty(&self) -> Type {
                 return_type: Box::new(Type::Brush),
                 args: vec![Type::Brush, Type::Float32],
             },
+            BuiltinFunction::ColorLighter
+            | BuiltinFunction::ColorSaturate
+            | BuiltinFunction::ColorWithAlpha => Type::Function {
+                return_type: Box::new(Type::Brush),
+                args: vec![Type::Brush, Type::Float32],
+            },
+            BuiltinFunction::ColorMix => Type::Function {
+                return_type: Box::new(Type::Brush),
+                args: vec![Type::Brush, Type::Brush, Type::Float32],
+            },
+            BuiltinFunction::FormatNumber | BuiltinFunction::FormatPercent => Type::Function {
+                return_type: Box::new(Type::String),
+                args: vec![Type::Float32, Type::Int32],
+            },
+            BuiltinFunction::FormatDate | BuiltinFunction::FormatTime => {
+                Type::Function { return_type: Box::new(Type::String), args: vec![Type::Int32] }
+            }
+            BuiltinFunction::GetColorScheme => Type::Function {
+                return_type: Box::new(Type::Enumeration(
+                    crate::typeregister::BUILTIN_ENUMS.with(|e| e.ColorScheme.clone()),
+                )),
+                args: vec![],
+            },
+            BuiltinFunction::AnimationTick => {
+                Type::Function { return_type: Box::new(Type::Duration), args: vec![] }
+            }
             BuiltinFunction::ImageSize => Type::Function {
                 return_type: Box::new(Type::Struct {
                     fields: IntoIterator::into_iter([
@@ -125,6 +241,7 @@ pub fn ty(&self) -> Type {
                     .collect(),
                     name: Some("Size".to_string()),
                     node: None,
+                    defaults: Default::default(),
                 }),
                 args: vec![Type::Image],
             },
@@ -151,6 +268,11 @@ pub fn ty(&self) -> Type {
     fn is_pure(&self) -> bool {
         match self {
             BuiltinFunction::GetWindowScaleFactor => false,
+            BuiltinFunction::GetWindowWidth
+            | BuiltinFunction::GetWindowHeight
+            | BuiltinFunction::GetWindowDefaultFontSize => false,
+            // The result depends on the scale factor of the window
+            BuiltinFunction::Snap => false,
             // Even if it is not pure, we optimize it away anyway
             BuiltinFunction::Debug => true,
             BuiltinFunction::Mod
@@ -169,8 +291,29 @@ fn is_pure(&self) -> bool {
             | BuiltinFunction::ATan => true,
             BuiltinFunction::SetFocusItem => false,
             BuiltinFunction::ShowPopupWindow => false,
+            BuiltinFunction::ScrollTo | BuiltinFunction::EnsureVisible => false,
+            BuiltinFunction::TextInputUndo | BuiltinFunction::TextInputRedo => false,
+            BuiltinFunction::TextInputFindNext | BuiltinFunction::TextInputFindPrevious => false,
+            // Only depends on properties of the TextInput, which are tracked
+            BuiltinFunction::TextInputSearchMatchCount | BuiltinFunction::TextInputCurrentMatch => {
+                true
+            }
             BuiltinFunction::StringToFloat | BuiltinFunction::StringIsFloat => true,
+            BuiltinFunction::StringFormatFloat | BuiltinFunction::StringPad => true,
             BuiltinFunction::ColorBrighter | BuiltinFunction::ColorDarker => true,
+            BuiltinFunction::ColorLighter
+            | BuiltinFunction::ColorSaturate
+            | BuiltinFunction::ColorWithAlpha
+            | BuiltinFunction::ColorMix => true,
+            // The result depends on the current locale, which can change at run-time
+            BuiltinFunction::FormatNumber
+            | BuiltinFunction::FormatPercent
+            | BuiltinFunction::FormatDate
+            | BuiltinFunction::FormatTime => false,
+            // The color scheme is reported by the platform and can change at run-time
+            BuiltinFunction::GetColorScheme => false,
+            // The result changes with every frame
+            BuiltinFunction::AnimationTick => false,
             // ImageSize is pure, except when loading images via the network. Then the initial size will be 0/0 and
             // we need to make sure that calls to this function stay within a binding, so that the property
             // notification when updating kicks in. Only the online editor (wasm-interpreter) loads images via the network,
@@ -269,6 +412,12 @@ pub fn normalize(self, x: f64) -> f64 {
     In = "in" -> LogicalLength * 96,
     /// Points
     Pt = "pt" -> LogicalLength * 96./72.,
+    /// Percentage of the window width
+    Vw = "vw" -> LogicalLength,
+    /// Percentage of the window height
+    Vh = "vh" -> LogicalLength,
+    /// Multiple of the window's default font size
+    Rem = "rem" -> LogicalLength,
 
     // durations
 
@@ -426,6 +575,8 @@ pub enum Expression {
     ImageReference {
         resource_ref: ImageReference,
         source_location: Option<SourceLocation>,
+        /// The options given to `@image-url`, used when embedding the image
+        options: ImageProcessingOptions,
     },
 
     Condition {
@@ -891,13 +1042,14 @@ pub fn maybe_convert_to(
                 },
                 (
                     Type::Struct { fields: ref left, .. },
-                    Type::Struct { fields: right, name, node: n },
+                    Type::Struct { fields: right, name, node: n, .. },
                 ) if left != right => {
+                    let mut defaults = struct_default_values(&target_type);
                     if let Expression::Struct { mut values, .. } = self {
                         let mut new_values = HashMap::new();
                         for (key, ty) in right {
                             let (key, expression) = values.remove_entry(key).map_or_else(
-                                || (key.clone(), Expression::default_value_for_type(ty)),
+                                || (key.clone(), defaults.remove(key).unwrap_or_default()),
                                 |(k, e)| (k, e.maybe_convert_to(ty.clone(), node, diag)),
                             );
                             new_values.insert(key, expression);
@@ -915,13 +1067,14 @@ pub fn maybe_convert_to(
                                         fields: left.clone(),
                                         name: name.clone(),
                                         node: n.clone(),
+                                        defaults: Default::default(),
                                     },
                                 }),
                                 name: key.clone(),
                             }
                             .maybe_convert_to(ty.clone(), node, diag)
                         } else {
-                            Expression::default_value_for_type(ty)
+                            defaults.remove(key).unwrap_or_default()
                         };
                         new_values.insert(key.clone(), expression);
                     }
@@ -946,6 +1099,7 @@ pub fn maybe_convert_to(
                             .collect(),
                         name: None,
                         node: None,
+                        defaults: Default::default(),
                     };
                     self.maybe_convert_to(struct_type_for_component, node, diag)
                 }
@@ -980,9 +1134,10 @@ pub fn maybe_convert_to(
             };
             Expression::Cast { from: Box::new(from), to: target_type }
         } else if matches!((&ty, &target_type, &self), (Type::Array(left), Type::Array(right), Expression::Array{..})
-            if left.can_convert(right) || **left == Type::Invalid)
+            if left.can_convert(right) || **left == Type::Invalid
+                || matches!((&**left, &**right), (Type::Array(_), Type::Array(_))))
         {
-            // Special case for converting array literals
+            // Special case for converting array literals, which may contain array literals
             match (self, target_type) {
                 (Expression::Array { values, .. }, Type::Array(target_type)) => Expression::Array {
                     values: values
@@ -1007,8 +1162,10 @@ pub fn maybe_convert_to(
                     return self;
                 }
             }
-            for (f, t) in fields {
-                new_values.insert(f, Expression::default_value_for_type(&t));
+            let mut defaults = struct_default_values(&target_type);
+            for (f, _) in fields {
+                let value = defaults.remove(&f).unwrap_or_default();
+                new_values.insert(f, value);
             }
             Expression::Struct { ty: target_type, values: new_values }
         } else {
@@ -1068,6 +1225,7 @@ pub fn default_value_for_type(ty: &Type) -> Expression {
             Type::Image => Expression::ImageReference {
                 resource_ref: ImageReference::None,
                 source_location: None,
+                options: Default::default(),
             },
             Type::Bool => Expression::BoolLiteral(false),
             Type::Model => Expression::Invalid,
@@ -1075,11 +1233,17 @@ pub fn default_value_for_type(ty: &Type) -> Expression {
             Type::Array(element_ty) => {
                 Expression::Array { element_ty: (**element_ty).clone(), values: vec![] }
             }
-            Type::Struct { fields, .. } => Expression::Struct {
+            Type::Struct { fields, defaults, .. } => Expression::Struct {
                 ty: ty.clone(),
                 values: fields
                     .iter()
-                    .map(|(k, v)| (k.clone(), Expression::default_value_for_type(v)))
+                    .map(|(k, v)| {
+                        let value = defaults
+                            .get(k)
+                            .cloned()
+                            .unwrap_or_else(|| Expression::default_value_for_type(v));
+                        (k.clone(), value)
+                    })
                     .collect(),
             },
             Type::Easing => Expression::EasingCurve(EasingCurve::default()),
@@ -1110,6 +1274,15 @@ pub fn try_set_rw(&mut self) -> bool {
     }
 }
 
+/// Return the default value of each field of a struct type, taking into account the
+/// default values specified in the struct declaration
+fn struct_default_values(ty: &Type) -> HashMap<String, Expression> {
+    match Expression::default_value_for_type(ty) {
+        Expression::Struct { values, .. } => values,
+        _ => HashMap::new(),
+    }
+}
+
 /// The expression in the Element::binding hash table
 #[derive(Debug, Clone, derive_more::Deref, derive_more::DerefMut)]
 pub struct BindingExpression {
@@ -1266,6 +1439,20 @@ pub enum ImageReference {
     EmbeddedTexture { resource_id: usize },
 }
 
+/// Processing of an image file done by the compiler when it is embedded in the program,
+/// as specified by the options of `@image-url`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImageProcessingOptions {
+    /// The image is downscaled so that neither its width nor its height exceeds this many pixels
+    pub max_size: Option<u32>,
+    /// The image is decoded and encoded again as a PNG file with the best lossless compression
+    pub recompress: bool,
+    /// The scale factor this image file was made for, if it is a variant such as `foo@2x.png`
+    pub scale_factor: Option<u32>,
+    /// The image is used as an alpha mask and filled with this color, encoded as ARGB
+    pub colorize: Option<u32>,
+}
+
 /// Print the expression as a .slint code (not necessarily valid .slint)
 pub fn pretty_print(f: &mut dyn std::fmt::Write, expression: &Expression) -> std::fmt::Result {
     match expression {
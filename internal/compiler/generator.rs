@@ -11,9 +11,10 @@
 use std::rc::{Rc, Weak};
 
 use crate::expression_tree::{BindingExpression, Expression};
-use crate::langtype::Type;
+use crate::langtype::{Enumeration, Type};
 use crate::namedreference::NamedReference;
 use crate::object_tree::{Component, Document, ElementRc};
+use crate::parser::syntax_nodes;
 
 #[cfg(feature = "cpp")]
 mod cpp;
@@ -21,6 +22,8 @@
 #[cfg(feature = "rust")]
 pub mod rust;
 
+mod interface;
+
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum OutputFormat {
     #[cfg(feature = "cpp")]
@@ -29,6 +32,8 @@ pub enum OutputFormat {
     Rust,
     Interpreter,
     Llr,
+    /// A JSON description of the public API of the exported component, with the documentation
+    Interface,
 }
 
 impl OutputFormat {
@@ -38,6 +43,7 @@ pub fn guess_from_extension(path: &std::path::Path) -> Option<Self> {
             Some("cpp") | Some("cxx") | Some("h") | Some("hpp") => Some(Self::Cpp),
             #[cfg(feature = "rust")]
             Some("rs") => Some(Self::Rust),
+            Some("json") => Some(Self::Interface),
             _ => None,
         }
     }
@@ -52,6 +58,7 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             #[cfg(feature = "rust")]
             "rust" => Ok(Self::Rust),
             "llr" => Ok(Self::Llr),
+            "interface" => Ok(Self::Interface),
             _ => Err(format!("Unknown outpout format {}", s)),
         }
     }
@@ -94,10 +101,40 @@ pub fn generate(
                 crate::llr::lower_to_item_tree::lower_to_item_tree(&doc.root_component)
             )?;
         }
+        OutputFormat::Interface => {
+            let output = interface::generate(doc);
+            writeln!(destination, "{:#}", output)?;
+        }
     }
     Ok(())
 }
 
+/// Returns the `///` comments of the declaration of a struct
+pub(crate) fn struct_doc_comment(node: &syntax_nodes::ObjectType) -> Option<String> {
+    crate::parser::doc_comment(&node.parent()?)
+}
+
+/// Returns the `///` comments of the declaration of a field of a struct
+pub(crate) fn struct_field_doc_comment(
+    node: &syntax_nodes::ObjectType,
+    field: &str,
+) -> Option<String> {
+    let member = node
+        .ObjectTypeMember()
+        .find(|m| crate::parser::identifier_text(m).map_or(false, |name| name == field))?;
+    crate::parser::doc_comment(&member)
+}
+
+/// Returns the `///` comments of the declaration of a value of an enum
+pub(crate) fn enum_value_doc_comment(en: &Enumeration, value: &str) -> Option<String> {
+    let node = en
+        .node
+        .as_ref()?
+        .EnumValue()
+        .find(|v| crate::parser::identifier_text(v).map_or(false, |name| name == value))?;
+    crate::parser::doc_comment(&node)
+}
+
 /// A reference to this trait is passed to the [`build_item_tree`] function.
 /// It can be used to build the array for the item tree.
 pub trait ItemTreeBuilder {
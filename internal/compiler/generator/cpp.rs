@@ -104,6 +104,7 @@ pub enum Declaration {
         Function(Function),
         Var(Var),
         TypeAlias(TypeAlias),
+        Enum(Enum),
     }
 
     #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -262,6 +263,26 @@ fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         }
     }
 
+    /// An `enum class` declaration
+    #[derive(Default, Debug)]
+    pub struct Enum {
+        pub name: String,
+        pub values: Vec<String>,
+    }
+
+    impl Display for Enum {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            indent(f)?;
+            writeln!(f, "enum class {} {{", self.name)?;
+            for value in &self.values {
+                indent(f)?;
+                writeln!(f, "    {},", value)?;
+            }
+            indent(f)?;
+            writeln!(f, "}};")
+        }
+    }
+
     pub trait CppType {
         fn cpp_type(&self) -> Option<String>;
     }
@@ -333,6 +354,9 @@ fn cpp_type(&self) -> Option<String> {
             Type::Array(i) => Some(format!("std::shared_ptr<slint::Model<{}>>", i.cpp_type()?)),
             Type::Image => Some("slint::Image".to_owned()),
             Type::Builtin(elem) => elem.native_class.cpp_type.clone(),
+            Type::Enumeration(enumeration) if enumeration.node.is_some() => {
+                Some(ident(&enumeration.name))
+            }
             Type::Enumeration(enumeration) => {
                 Some(format!("slint::cbindgen_private::{}", ident(&enumeration.name)))
             }
@@ -481,46 +505,53 @@ pub fn generate(doc: &Document) -> impl std::fmt::Display {
 
     file.declarations.extend(doc.root_component.embedded_file_resources.borrow().iter().map(
         |(path, er)| {
-            match &er.kind {
+            let data = match &er.kind {
                 crate::embedded_resources::EmbeddedResourcesKind::RawData => {
                     let file = crate::fileaccess::load_file(std::path::Path::new(path)).unwrap(); // embedding pass ensured that the file exists
-                    let data = file.read();
-
-                    let mut init = "{ ".to_string();
-
-                    for (index, byte) in data.iter().enumerate() {
-                        if index > 0 {
-                            init.push(',');
-                        }
-                        write!(&mut init, "0x{:x}", byte).unwrap();
-                        if index % 16 == 0 {
-                            init.push('\n');
-                        }
-                    }
-
-                    init.push('}');
-
-                    Declaration::Var(Var {
-                        ty: "inline uint8_t".into(),
-                        name: format!("slint_embedded_resource_{}", er.id),
-                        array_size: Some(data.len()),
-                        init: Some(init),
-                    })
+                    file.read()
+                }
+                crate::embedded_resources::EmbeddedResourcesKind::ProcessedData(data) => {
+                    std::borrow::Cow::Borrowed(data.as_slice())
                 }
                 crate::embedded_resources::EmbeddedResourcesKind::TextureData(_) => todo!(),
                 crate::embedded_resources::EmbeddedResourcesKind::BitmapFontData(_) => todo!(),
+            };
+
+            let mut init = "{ ".to_string();
+
+            for (index, byte) in data.iter().enumerate() {
+                if index > 0 {
+                    init.push(',');
+                }
+                write!(&mut init, "0x{:x}", byte).unwrap();
+                if index % 16 == 0 {
+                    init.push('\n');
+                }
             }
+
+            init.push('}');
+
+            Declaration::Var(Var {
+                ty: "inline uint8_t".into(),
+                name: format!("slint_embedded_resource_{}", er.id),
+                array_size: Some(data.len()),
+                init: Some(init),
+            })
         },
     ));
 
+    let llr = llr::lower_to_item_tree::lower_to_item_tree(&doc.root_component);
+
     for ty in doc.root_component.used_types.borrow().structs.iter() {
-        if let Type::Struct { fields, name: Some(name), node: Some(_) } = ty {
-            generate_struct(&mut file, name, fields);
+        match ty {
+            Type::Struct { fields, name: Some(name), node: Some(_), defaults } => {
+                generate_struct(&mut file, name, fields, defaults, &llr);
+            }
+            Type::Enumeration(en) => generate_enum(&mut file, en),
+            _ => (),
         }
     }
 
-    let llr = llr::lower_to_item_tree::lower_to_item_tree(&doc.root_component);
-
     // Forward-declare the root so that sub-components can access singletons, the window, etc.
     file.declarations.push(Declaration::Struct(Struct {
         name: ident(&llr.item_tree.root.name),
@@ -565,7 +596,15 @@ pub fn generate(doc: &Document) -> impl std::fmt::Display {
     file
 }
 
-fn generate_struct(file: &mut File, name: &str, fields: &BTreeMap<String, Type>) {
+fn generate_struct(
+    file: &mut File,
+    name: &str,
+    fields: &BTreeMap<String, Type>,
+    defaults: &BTreeMap<String, crate::expression_tree::Expression>,
+    root: &llr::PublicComponent,
+) {
+    let defaults = llr::lower_expression::lower_struct_field_defaults(defaults);
+    let ctx = EvaluationContext::new_sub_component(root, &root.item_tree.root, String::new(), None);
     let mut members = fields
         .iter()
         .map(|(name, t)| {
@@ -574,6 +613,7 @@ fn generate_struct(file: &mut File, name: &str, fields: &BTreeMap<String, Type>)
                 Declaration::Var(Var {
                     ty: t.cpp_type().unwrap(),
                     name: ident(name),
+                    init: defaults.get(name).map(|e| compile_expression(e, &ctx)),
                     ..Default::default()
                 }),
             )
@@ -601,6 +641,17 @@ fn generate_struct(file: &mut File, name: &str, fields: &BTreeMap<String, Type>)
     }))
 }
 
+fn generate_enum(file: &mut File, en: &crate::langtype::Enumeration) {
+    file.declarations.push(Declaration::Enum(Enum {
+        name: ident(&en.name),
+        values: en
+            .values
+            .iter()
+            .map(|value| ident(i_slint_common::enums::cpp_escape_keyword(value)))
+            .collect(),
+    }))
+}
+
 /// Generate the component in `file`.
 ///
 /// `sub_components`, if Some, will be filled with all the sub component which needs to be added as friends
@@ -1022,6 +1073,54 @@ fn generate_item_tree(
         }),
     ));
 
+    target_struct.members.push((
+        Access::Private,
+        Declaration::Function(Function {
+            name: "accessible_role".into(),
+            signature:
+                "([[maybe_unused]] slint::private_api::ComponentRef component, uintptr_t index) -> slint::cbindgen_private::AccessibleRole"
+                    .into(),
+            is_static: true,
+            statements: Some(vec![format!(
+                "return reinterpret_cast<const {}*>(component.instance)->accessible_role(index);",
+                item_tree_class_name
+            )]),
+            ..Default::default()
+        }),
+    ));
+
+    target_struct.members.push((
+        Access::Private,
+        Declaration::Function(Function {
+            name: "accessible_string_property".into(),
+            signature:
+                "([[maybe_unused]] slint::private_api::ComponentRef component, uintptr_t index, slint::cbindgen_private::AccessibleStringProperty what, slint::SharedString *result) -> void"
+                    .into(),
+            is_static: true,
+            statements: Some(vec![format!(
+                "*result = reinterpret_cast<const {}*>(component.instance)->accessible_string_property(index, what);",
+                item_tree_class_name
+            )]),
+            ..Default::default()
+        }),
+    ));
+
+    target_struct.members.push((
+        Access::Private,
+        Declaration::Function(Function {
+            name: "accessibility_action".into(),
+            signature:
+                "([[maybe_unused]] slint::private_api::ComponentRef component, uintptr_t index, const slint::cbindgen_private::AccessibilityAction *action) -> void"
+                    .into(),
+            is_static: true,
+            statements: Some(vec![format!(
+                "reinterpret_cast<const {}*>(component.instance)->accessibility_action(index, *action);",
+                item_tree_class_name
+            )]),
+            ..Default::default()
+        }),
+    ));
+
     target_struct.members.push((
         Access::Public,
         Declaration::Var(Var {
@@ -1035,7 +1134,7 @@ fn generate_item_tree(
         ty: "const slint::private_api::ComponentVTable".to_owned(),
         name: format!("{}::static_vtable", item_tree_class_name),
         init: Some(format!(
-            "{{ visit_children, get_item_ref, get_subtree_range, get_subtree_component, get_item_tree, parent_node, subtree_index, layout_info, slint::private_api::drop_in_place<{}>, slint::private_api::dealloc }}",
+            "{{ visit_children, get_item_ref, get_subtree_range, get_subtree_component, get_item_tree, parent_node, subtree_index, layout_info, accessible_role, accessible_string_property, accessibility_action, slint::private_api::drop_in_place<{}>, slint::private_api::dealloc }}",
             item_tree_class_name)
         ),
         ..Default::default()
@@ -1068,26 +1167,60 @@ fn generate_item_tree(
         )]);
     }
 
+    create_code.push(format!(
+        "{}->m_window.window_handle().init_items(self, self->item_array());",
+        root_access
+    ));
+    if parent_ctx.is_none() {
+        // The bindings of the component are initialized after the globals, so that they see their values
+        create_code.push("init_globals(*self);".into());
+    }
     create_code.extend([
-        format!("{}->m_window.window_handle().init_items(self, self->item_array());", root_access),
         format!("self->init({}, self->self_weak, 0, 1 {});", root_access, init_parent_parameters),
         format!("return slint::ComponentHandle<{0}>{{ self_rc }};", target_struct.name),
     ]);
 
-    target_struct.members.push((
-        Access::Public,
-        Declaration::Function(Function {
-            name: "create".into(),
-            signature: format!(
-                "({}) -> slint::ComponentHandle<{}>",
-                create_parameters.join(","),
-                target_struct.name
-            ),
-            statements: Some(create_code),
-            is_static: true,
-            ..Default::default()
-        }),
-    ));
+    if parent_ctx.is_none() {
+        target_struct.members.push((
+            Access::Public,
+            Declaration::Function(Function {
+                name: "create".into(),
+                signature: format!("() -> slint::ComponentHandle<{}>", target_struct.name),
+                statements: Some(vec!["return create_with_globals([](const auto &) {});".into()]),
+                is_static: true,
+                ..Default::default()
+            }),
+        ));
+        target_struct.members.push((
+            Access::Public,
+            Declaration::Function(Function {
+                name: "create_with_globals".into(),
+                signature: format!(
+                    "(InitGlobals init_globals) -> slint::ComponentHandle<{}>",
+                    target_struct.name
+                ),
+                statements: Some(create_code),
+                is_static: true,
+                template_parameters: Some("typename InitGlobals".into()),
+                ..Default::default()
+            }),
+        ));
+    } else {
+        target_struct.members.push((
+            Access::Public,
+            Declaration::Function(Function {
+                name: "create".into(),
+                signature: format!(
+                    "({}) -> slint::ComponentHandle<{}>",
+                    create_parameters.join(","),
+                    target_struct.name
+                ),
+                statements: Some(create_code),
+                is_static: true,
+                ..Default::default()
+            }),
+        ));
+    }
 
     let mut destructor = vec!["auto self = this;".to_owned()];
 
@@ -1224,6 +1357,28 @@ fn generate_sub_component(
     let mut subtrees_ranges_cases = Vec::new();
     let mut subtrees_components_cases = Vec::new();
 
+    let mut accessible_role_cases = Vec::new();
+    let mut accessible_string_cases = Vec::new();
+    let mut accessibility_action_cases = Vec::new();
+
+    for ((index, what), expr) in &component.accessible_prop {
+        let expr = compile_expression(&expr.borrow(), &ctx);
+        if what == "Role" {
+            accessible_role_cases.push(format!("if (index == {}) return {};", index, expr));
+        } else if let Some(what) = what.strip_prefix("Action") {
+            let arg = if what == "SetValue" { "auto arg_0 = action.set_value._0; " } else { "" };
+            accessibility_action_cases.push(format!(
+                "if (index == {} && action.tag == slint::cbindgen_private::AccessibilityAction::Tag::{}) {{ {}{}; return; }}",
+                index, what, arg, expr
+            ));
+        } else {
+            accessible_string_cases.push(format!(
+                "if (index == {} && what == slint::cbindgen_private::AccessibleStringProperty::{}) return {};",
+                index, what, expr
+            ));
+        }
+    }
+
     let mut subcomponent_init_code = Vec::new();
     for sub in &component.sub_components {
         let field_name = ident(&sub.name);
@@ -1248,6 +1403,32 @@ fn generate_sub_component(
             field_name, global_index, global_children
         ));
 
+        // The items of the sub-component are forwarded to it, with its own local index.
+        let mut sub_index_conditions = vec![(format!("index == {}", local_tree_index), "0".into())];
+        let sub_item_count = sub.ty.item_count();
+        if sub_item_count > 1 {
+            let first = sub.index_of_first_child_in_tree;
+            let last = first + sub_item_count - 2;
+            sub_index_conditions.push((
+                format!("index >= {} && index <= {}", first, last),
+                format!("index - {} + 1", first),
+            ));
+        }
+        for (condition, sub_index) in sub_index_conditions {
+            accessible_role_cases.push(format!(
+                "if ({}) return self->{}.accessible_role({});",
+                condition, field_name, sub_index
+            ));
+            accessible_string_cases.push(format!(
+                "if ({}) return self->{}.accessible_string_property({}, what);",
+                condition, field_name, sub_index
+            ));
+            accessibility_action_cases.push(format!(
+                "if ({}) {{ self->{}.accessibility_action({}, action); return; }}",
+                condition, field_name, sub_index
+            ));
+        }
+
         let sub_component_repeater_count = sub.ty.repeater_count();
         if sub_component_repeater_count > 0 {
             let mut case_code = String::new();
@@ -1362,10 +1543,11 @@ fn generate_sub_component(
             let lv_h = access_member(&listview.listview_height, &ctx);
             let vp_w = access_member(&listview.viewport_width, &ctx);
             let lv_w = access_member(&listview.listview_width, &ctx);
+            let first_visible = access_member(&listview.first_visible_item, &ctx);
 
             format!(
-                "self->{}.ensure_updated_listview(self, &{}, &{}, &{}, {}.get(), {}.get());",
-                repeater_id, vp_w, vp_h, vp_y, lv_w, lv_h
+                "self->{}.ensure_updated_listview(self, &{}, &{}, &{}, {}.get(), {}.get(), &{});",
+                repeater_id, vp_w, vp_h, vp_y, lv_w, lv_h, first_visible
             )
         } else {
             format!("self->{id}.ensure_updated(self);", id = repeater_id)
@@ -1418,6 +1600,29 @@ fn generate_sub_component(
     init.extend(properties_init_code);
     init.extend(component.init_code.iter().map(|e| compile_expression(&e.borrow(), &ctx)));
 
+    for (idx, (prop, handler)) in component.change_callbacks.iter().enumerate() {
+        let tracker = format!("change_tracker{}", idx);
+        let prop = compile_expression(&llr::Expression::PropertyReference(prop.clone()), &ctx);
+        let handler = compile_expression(&handler.borrow(), &ctx);
+        init.push(format!(
+            "self->{tracker}.init(self,
+                []([[maybe_unused]] auto self) {{ return {prop}; }},
+                []([[maybe_unused]] auto self) {{ {handler}; }});",
+            tracker = tracker,
+            prop = prop,
+            handler = handler,
+        ));
+        init.push(format!("{};", handler));
+        target_struct.members.push((
+            Access::Private,
+            Declaration::Var(Var {
+                ty: "slint::private_api::ChangeTracker".into(),
+                name: tracker,
+                ..Default::default()
+            }),
+        ));
+    }
+
     target_struct.members.push((
         field_access,
         Declaration::Function(Function {
@@ -1446,6 +1651,50 @@ fn generate_sub_component(
         }),
     ));
 
+    target_struct.members.push((
+        field_access,
+        Declaration::Function(Function {
+            name: "accessible_role".into(),
+            signature: "([[maybe_unused]] uintptr_t index) const -> slint::cbindgen_private::AccessibleRole".into(),
+            statements: Some(
+                std::iter::once("[[maybe_unused]] auto self = this;".into())
+                    .chain(accessible_role_cases)
+                    .chain(std::iter::once("return {};".into()))
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+    ));
+
+    target_struct.members.push((
+        field_access,
+        Declaration::Function(Function {
+            name: "accessible_string_property".into(),
+            signature: "([[maybe_unused]] uintptr_t index, [[maybe_unused]] slint::cbindgen_private::AccessibleStringProperty what) const -> slint::SharedString".into(),
+            statements: Some(
+                std::iter::once("[[maybe_unused]] auto self = this;".into())
+                    .chain(accessible_string_cases)
+                    .chain(std::iter::once("return {};".into()))
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+    ));
+
+    target_struct.members.push((
+        field_access,
+        Declaration::Function(Function {
+            name: "accessibility_action".into(),
+            signature: "([[maybe_unused]] uintptr_t index, [[maybe_unused]] const slint::cbindgen_private::AccessibilityAction &action) const -> void".into(),
+            statements: Some(
+                std::iter::once("[[maybe_unused]] auto self = this;".into())
+                    .chain(accessibility_action_cases)
+                    .collect(),
+            ),
+            ..Default::default()
+        }),
+    ));
+
     if !children_visitor_cases.is_empty() {
         target_struct.members.push((
             field_access,
@@ -1676,7 +1925,7 @@ fn generate_public_api_for_properties(
     ctx: &EvaluationContext,
 ) -> Vec<Declaration> {
     let mut declarations = Vec::new();
-    for (p, (ty, r)) in public_properties.iter() {
+    for (p, llr::PublicProperty { ty, prop: r, read_only, .. }) in public_properties.iter() {
         let prop_ident = ident(p);
 
         let access = access_member(r, ctx);
@@ -1729,6 +1978,9 @@ fn generate_public_api_for_properties(
                 ..Default::default()
             }));
 
+            if *read_only {
+                continue;
+            }
             let prop_setter: Vec<String> = vec![
                 "[[maybe_unused]] auto self = this;".into(),
                 property_set_value_code(r, "value", ctx) + ";",
@@ -2180,8 +2432,8 @@ fn compile_expression(expr: &llr::Expression, ctx: &EvaluationContext) -> String
         }
         Expression::EnumerationValue(value) => {
             format!(
-                "slint::cbindgen_private::{}::{}",
-                value.enumeration.name,
+                "{}::{}",
+                Type::Enumeration(value.enumeration.clone()).cpp_type().unwrap(),
                 ident(i_slint_common::enums::cpp_escape_keyword(&value.to_string())),
             )
         }
@@ -2246,6 +2498,18 @@ fn compile_builtin_function_call(
             let window = access_window_field(ctx);
             format!("{}.scale_factor()", window)
         }
+        BuiltinFunction::GetWindowWidth => {
+            let window = access_window_field(ctx);
+            format!("{}.window_item_width()", window)
+        }
+        BuiltinFunction::GetWindowHeight => {
+            let window = access_window_field(ctx);
+            format!("{}.window_item_height()", window)
+        }
+        BuiltinFunction::GetWindowDefaultFontSize => {
+            let window = access_window_field(ctx);
+            format!("{}.default_font_size()", window)
+        }
         BuiltinFunction::Debug => {
             format!("std::cout << {} << std::endl;", a.join("<<"))
         }
@@ -2259,6 +2523,14 @@ fn compile_builtin_function_call(
         BuiltinFunction::Floor => format!("std::floor({})", a.next().unwrap()),
         BuiltinFunction::Sqrt => format!("std::sqrt({})", a.next().unwrap()),
         BuiltinFunction::Abs => format!("std::abs({})", a.next().unwrap()),
+        BuiltinFunction::Snap => {
+            let window = access_window_field(ctx);
+            format!(
+                "[](float length, float scale_factor) {{ return std::round(length * scale_factor) / scale_factor; }}({}, {}.scale_factor())",
+                a.next().unwrap(),
+                window
+            )
+        }
         BuiltinFunction::Log => {
             format!("std::log({}) / std::log({})", a.next().unwrap(), a.next().unwrap())
         }
@@ -2280,6 +2552,68 @@ fn compile_builtin_function_call(
                 panic!("internal error: invalid args to SetFocusItem {:?}", arguments)
             }
         }
+        BuiltinFunction::ScrollTo => {
+            if let [llr::Expression::PropertyReference(pr), x, y, animated] = arguments {
+                let flickable = access_item_rc(pr, ctx);
+                format!(
+                    "slint::private_api::flickable_scroll_to({}, {}, {}, {});",
+                    flickable,
+                    compile_expression(x, ctx),
+                    compile_expression(y, ctx),
+                    compile_expression(animated, ctx)
+                )
+            } else {
+                panic!("internal error: invalid args to ScrollTo {:?}", arguments)
+            }
+        }
+        BuiltinFunction::EnsureVisible => {
+            if let [llr::Expression::PropertyReference(pr), llr::Expression::PropertyReference(target)] =
+                arguments
+            {
+                let flickable = access_item_rc(pr, ctx);
+                let target = access_item_rc(target, ctx);
+                format!("slint::private_api::flickable_ensure_visible({}, {});", flickable, target)
+            } else {
+                panic!("internal error: invalid args to EnsureVisible {:?}", arguments)
+            }
+        }
+        BuiltinFunction::TextInputUndo | BuiltinFunction::TextInputRedo => {
+            if let [llr::Expression::PropertyReference(pr)] = arguments {
+                let window = access_window_field(ctx);
+                let text_input = access_item_rc(pr, ctx);
+                let function = if matches!(function, BuiltinFunction::TextInputUndo) {
+                    "text_input_undo"
+                } else {
+                    "text_input_redo"
+                };
+                format!("{}.{}({});", window, function, text_input)
+            } else {
+                panic!("internal error: invalid args to {:?} {:?}", function, arguments)
+            }
+        }
+        BuiltinFunction::TextInputFindNext | BuiltinFunction::TextInputFindPrevious => {
+            if let [llr::Expression::PropertyReference(pr)] = arguments {
+                let window = access_window_field(ctx);
+                let text_input = access_item_rc(pr, ctx);
+                let forward = matches!(function, BuiltinFunction::TextInputFindNext);
+                format!("{}.text_input_find({}, {})", window, text_input, forward)
+            } else {
+                panic!("internal error: invalid args to {:?} {:?}", function, arguments)
+            }
+        }
+        BuiltinFunction::TextInputSearchMatchCount | BuiltinFunction::TextInputCurrentMatch => {
+            if let [llr::Expression::PropertyReference(pr)] = arguments {
+                let text_input = access_item_rc(pr, ctx);
+                let function = if matches!(function, BuiltinFunction::TextInputSearchMatchCount) {
+                    "text_input_search_match_count"
+                } else {
+                    "text_input_current_match"
+                };
+                format!("slint::private_api::{}({})", function, text_input)
+            } else {
+                panic!("internal error: invalid args to {:?} {:?}", function, arguments)
+            }
+        }
         /*  std::from_chars is unfortunately not yet implemented in gcc
         BuiltinFunction::StringIsFloat => {
             "[](const auto &a){ double v; auto r = std::from_chars(std::begin(a), std::end(a), v); return r.ptr == std::end(a); }"
@@ -2295,12 +2629,62 @@ fn compile_builtin_function_call(
         BuiltinFunction::StringToFloat => {
             format!("[](const auto &a){{ auto e1 = std::end(a); auto e2 = const_cast<char*>(e1); auto r = std::strtod(std::begin(a), &e2); return e1 == e2 ? r : 0; }}({})", a.next().unwrap())
         }
+        BuiltinFunction::StringFormatFloat => {
+            format!(
+                "[](double value, int precision) {{ slint::SharedString out; slint::cbindgen_private::slint_shared_string_format_float(value, precision, &out); return out; }}({}, {})",
+                a.next().unwrap(),
+                a.next().unwrap()
+            )
+        }
+        BuiltinFunction::StringPad => {
+            format!(
+                "[](const slint::SharedString &string, int width, const slint::SharedString &fill, int alignment) {{ slint::SharedString out; slint::cbindgen_private::slint_shared_string_pad(&string, width, &fill, alignment, &out); return out; }}({}, {}, {}, {})",
+                a.next().unwrap(),
+                a.next().unwrap(),
+                a.next().unwrap(),
+                a.next().unwrap()
+            )
+        }
         BuiltinFunction::ColorBrighter => {
             format!("{}.brighter({})", a.next().unwrap(), a.next().unwrap())
         }
         BuiltinFunction::ColorDarker => {
             format!("{}.darker({})", a.next().unwrap(), a.next().unwrap())
         }
+        BuiltinFunction::ColorLighter => {
+            format!("{}.lighter({})", a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::ColorSaturate => {
+            format!("{}.saturate({})", a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::ColorWithAlpha => {
+            format!("{}.with_alpha({})", a.next().unwrap(), a.next().unwrap())
+        }
+        BuiltinFunction::ColorMix => {
+            format!(
+                "slint::Brush({}).mix({}, {})",
+                a.next().unwrap(),
+                a.next().unwrap(),
+                a.next().unwrap()
+            )
+        }
+        BuiltinFunction::FormatNumber | BuiltinFunction::FormatPercent => {
+            format!(
+                "[](double value, int decimals) {{ slint::SharedString out; slint::cbindgen_private::slint_locale_{}(value, decimals, &out); return out; }}({}, {})",
+                if matches!(function, BuiltinFunction::FormatNumber) { "format_number" } else { "format_percent" },
+                a.next().unwrap(),
+                a.next().unwrap()
+            )
+        }
+        BuiltinFunction::FormatDate | BuiltinFunction::FormatTime => {
+            format!(
+                "[](int64_t timestamp) {{ slint::SharedString out; slint::cbindgen_private::slint_locale_{}(timestamp, &out); return out; }}({})",
+                if matches!(function, BuiltinFunction::FormatDate) { "format_date" } else { "format_time" },
+                a.next().unwrap()
+            )
+        }
+        BuiltinFunction::GetColorScheme => "slint::cbindgen_private::slint_color_scheme()".into(),
+        BuiltinFunction::AnimationTick => "slint::cbindgen_private::slint_animation_tick()".into(),
         BuiltinFunction::ImageSize => {
             format!("{}.size()", a.next().unwrap())
         }
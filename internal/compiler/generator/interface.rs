@@ -0,0 +1,171 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*! module for the JSON description of the public API
+
+The description lists the properties and the callbacks of the exported component and of the
+exported globals, as well as the structs and the enums that they use, with the types as written
+in the .slint language and the `///` comments of their declarations. Tools can use it to generate
+the documentation or the bindings for other languages.
+*/
+
+use crate::langtype::Type;
+use crate::llr::{self, PublicProperties};
+use crate::object_tree::Document;
+use serde_json::{json, Map, Value};
+
+/// Returns the JSON description of the public API of the document
+pub fn generate(doc: &Document) -> Value {
+    let llr = crate::llr::lower_to_item_tree::lower_to_item_tree(&doc.root_component);
+
+    let mut component = Map::new();
+    component.insert("name".into(), doc.root_component.id.clone().into());
+    component.insert("doc".into(), llr.doc.clone().into());
+    public_api(&llr.public_properties, &mut component);
+
+    let globals = llr
+        .globals
+        .iter()
+        .filter(|g| g.exported && !g.is_builtin)
+        .map(|g| {
+            let mut global = Map::new();
+            global.insert("name".into(), g.name.clone().into());
+            global.insert("aliases".into(), g.aliases.clone().into());
+            global.insert("doc".into(), g.doc.clone().into());
+            public_api(&g.public_properties, &mut global);
+            Value::Object(global)
+        })
+        .collect::<Vec<_>>();
+
+    let mut structs = Vec::new();
+    let mut enums = Vec::new();
+    for ty in doc.root_component.used_types.borrow().structs.iter() {
+        match ty {
+            Type::Struct { fields, name: Some(name), node: Some(node), .. } => {
+                structs.push(json!({
+                    "name": name,
+                    "doc": super::struct_doc_comment(node),
+                    "fields": fields.iter().map(|(field, ty)| json!({
+                        "name": field,
+                        "type": ty.to_string(),
+                        "doc": super::struct_field_doc_comment(node, field),
+                    })).collect::<Vec<_>>(),
+                }))
+            }
+            Type::Enumeration(en) => enums.push(json!({
+                "name": en.name,
+                "doc": en.node.as_ref().and_then(|n| crate::parser::doc_comment(n)),
+                "values": en.values.iter().map(|value| json!({
+                    "name": value,
+                    "doc": super::enum_value_doc_comment(en, value),
+                })).collect::<Vec<_>>(),
+            })),
+            _ => {}
+        }
+    }
+
+    json!({
+        "component": component,
+        "globals": globals,
+        "structs": structs,
+        "enums": enums,
+    })
+}
+
+/// Adds the "properties" and the "callbacks" of the public API to `object`
+fn public_api(public_properties: &PublicProperties, object: &mut Map<String, Value>) {
+    let mut properties = Vec::new();
+    let mut callbacks = Vec::new();
+    for (name, llr::PublicProperty { ty, read_only, doc, .. }) in public_properties {
+        if let Type::Callback { args, return_type } = ty {
+            callbacks.push(json!({
+                "name": name,
+                "arguments": args.iter().map(|ty| ty.to_string()).collect::<Vec<_>>(),
+                "return_type": return_type.as_ref().map(|ty| ty.to_string()),
+                "doc": doc,
+            }));
+        } else {
+            properties.push(json!({
+                "name": name,
+                "type": ty.to_string(),
+                "access": if *read_only { "out" } else { "in-out" },
+                "doc": doc,
+            }));
+        }
+    }
+    object.insert("properties".into(), properties.into());
+    object.insert("callbacks".into(), callbacks.into());
+}
+
+#[test]
+fn test_interface() {
+    let mut compiler_config =
+        crate::CompilerConfiguration::new(crate::generator::OutputFormat::Interface);
+    compiler_config.style = Some("fluent".into());
+    let source = r#"
+/// The state of the light
+export enum LightState {
+    off,
+    /// Blinking at one Hz
+    blinking,
+}
+
+export struct Point := {
+    /// The horizontal position
+    x: int,
+    y: int,
+}
+
+/// The settings that are shared
+export global Settings := {
+    /// The name of the user
+    property <string> user-name;
+}
+
+/// The main window
+///
+/// Shows the light.
+export Demo := Window {
+    /// The state of the light
+    property <LightState> state;
+    // Not a doc comment
+    property <Point> position;
+
+    /// Invoked when the light is clicked
+    callback clicked(int) -> bool;
+
+    Text { text: Settings.user-name; }
+}
+"#;
+    let (doc, diag) = spin_on::spin_on(crate::compile_syntax_node(
+        crate::parser::parse(source.into(), None, &mut Default::default()),
+        crate::diagnostics::BuildDiagnostics::default(),
+        compiler_config,
+    ));
+    assert!(!diag.has_error(), "{:?}", diag.to_string_vec());
+
+    let interface = generate(&doc);
+    let component = &interface["component"];
+    assert_eq!(component["name"], "Demo");
+    assert_eq!(component["doc"], "The main window\n\nShows the light.");
+    assert_eq!(
+        component["properties"],
+        json!([
+            { "name": "position", "type": "Point", "access": "in-out", "doc": null },
+            { "name": "state", "type": "enum LightState", "access": "in-out", "doc": "The state of the light" },
+        ])
+    );
+    assert_eq!(
+        component["callbacks"],
+        json!([{
+            "name": "clicked", "arguments": ["int"], "return_type": "bool",
+            "doc": "Invoked when the light is clicked"
+        }])
+    );
+    assert_eq!(interface["globals"][0]["doc"], "The settings that are shared");
+    assert_eq!(interface["globals"][0]["properties"][0]["doc"], "The name of the user");
+    assert_eq!(interface["structs"][0]["fields"][0]["doc"], "The horizontal position");
+    assert_eq!(interface["structs"][0]["fields"][1]["doc"], Value::Null);
+    assert_eq!(interface["enums"][0]["doc"], "The state of the light");
+    assert_eq!(interface["enums"][0]["values"][1]["doc"], "Blinking at one Hz");
+}
@@ -20,6 +20,7 @@
     TypeResolutionContext as _,
 };
 use crate::object_tree::Document;
+use crate::parser::syntax_nodes;
 use itertools::Either;
 use proc_macro2::{Ident, TokenStream};
 use quote::{format_ident, quote};
@@ -82,6 +83,7 @@ fn rust_type(ty: &Type) -> Option<proc_macro2::TokenStream> {
             let inner = rust_type(o)?;
             Some(quote!(slint::re_exports::ModelRc<#inner>))
         }
+        Type::Enumeration(e) if e.node.is_some() => Some(struct_name_to_tokens(&e.name)),
         Type::Enumeration(e) => {
             let e = ident(&e.name);
             Some(quote!(slint::re_exports::#e))
@@ -101,23 +103,10 @@ pub fn generate(doc: &Document) -> TokenStream {
         return TokenStream::default();
     }
 
-    let (structs_ids, structs): (Vec<_>, Vec<_>) = doc
-        .root_component
-        .used_types
-        .borrow()
-        .structs
-        .iter()
-        .filter_map(|ty| {
-            if let Type::Struct { fields, name: Some(name), node: Some(_) } = ty {
-                Some((ident(name), generate_struct(name, fields)))
-            } else {
-                None
-            }
-        })
-        .unzip();
-
     let llr = crate::llr::lower_to_item_tree::lower_to_item_tree(&doc.root_component);
 
+    let (structs_ids, structs) = generate_structs(doc, &llr);
+
     let sub_compos = llr
         .sub_components
         .iter()
@@ -151,6 +140,10 @@ pub fn generate(doc: &Document) -> TokenStream {
                     let data = embedded_file_tokens(path);
                     quote!(const #symbol: &'static [u8] = #data;)
                 }
+                crate::embedded_resources::EmbeddedResourcesKind::ProcessedData(data) => {
+                    let data = proc_macro2::Literal::byte_string(data);
+                    quote!(const #symbol: &'static [u8] = #data;)
+                }
                 crate::embedded_resources::EmbeddedResourcesKind::TextureData(crate::embedded_resources::Texture {
                     data, format, rect,
                     total_size: crate::embedded_resources::Size{width, height},
@@ -241,6 +234,316 @@ mod #compo_module {
     }
 }
 
+/// Generate the code for the live-reload mode: the public component is a wrapper around a
+/// `LiveReloadingComponent` that loads `main_file` with the interpreter at run-time, and
+/// loads it again when one of the `watched_files` changes.
+pub fn generate_live_reload(
+    doc: &Document,
+    main_file: &std::path::Path,
+    include_paths: &[std::path::PathBuf],
+    style: Option<&str>,
+    watched_files: &[std::path::PathBuf],
+) -> TokenStream {
+    if matches!(doc.root_component.root_element.borrow().base_type, Type::Invalid | Type::Void) {
+        // empty document, nothing to generate
+        return TokenStream::default();
+    }
+
+    let llr = crate::llr::lower_to_item_tree::lower_to_item_tree(&doc.root_component);
+
+    let (structs_ids, structs) = generate_structs(doc, &llr);
+
+    let compo_id = public_component_id(&llr.item_tree.root);
+    let compo_module = format_ident!("slint_generated{}", compo_id);
+    let version_check = format_ident!(
+        "VersionCheck_{}_{}_{}",
+        env!("CARGO_PKG_VERSION_MAJOR"),
+        env!("CARGO_PKG_VERSION_MINOR"),
+        env!("CARGO_PKG_VERSION_PATCH"),
+    );
+
+    let property_and_callback_accessors = live_reload_public_api(&llr.public_properties, "");
+    let compo_doc = doc_attribute(&llr.doc);
+
+    let exported_globals =
+        llr.globals.iter().filter(|glob| glob.exported && !glob.is_builtin).collect::<Vec<_>>();
+    let globals = exported_globals.iter().map(|glob| {
+        let global_id = ident(&glob.name);
+        let property_and_callback_accessors =
+            live_reload_public_api(&glob.public_properties, &glob.name);
+        let aliases = glob.aliases.iter().map(|name| ident(name));
+        let doc = doc_attribute(&glob.doc);
+        quote!(
+            #doc
+            pub struct #global_id<'a>(&'a slint::re_exports::live_reload::LiveReloadingComponent);
+
+            impl<'a> #global_id<'a> {
+                #property_and_callback_accessors
+            }
+
+            #(pub type #aliases<'a> = #global_id<'a>;)*
+
+            impl<'a> slint::Global<'a, #compo_id> for #global_id<'a> {
+                fn get(component: &'a #compo_id) -> Self {
+                    Self(&component.0)
+                }
+            }
+        )
+    });
+    let globals_ids = exported_globals.iter().flat_map(|glob| {
+        std::iter::once(ident(&glob.name)).chain(glob.aliases.iter().map(|x| ident(x)))
+    });
+
+    let main_file = main_file.to_string_lossy();
+    let include_paths = include_paths.iter().map(|p| p.to_string_lossy());
+    let style = style.map_or(quote!(None), |style| quote!(Some(#style)));
+    let watched_files = watched_files.iter().map(|p| p.to_string_lossy());
+
+    quote! {
+        #[allow(non_snake_case)]
+        #[allow(non_camel_case_types)]
+        #[allow(clippy::style)]
+        #[allow(clippy::complexity)]
+        mod #compo_module {
+            use slint::re_exports::*;
+            #(#structs)*
+            #(#globals)*
+
+            #compo_doc
+            pub struct #compo_id(slint::re_exports::live_reload::LiveReloadingComponent);
+
+            impl #compo_id {
+                pub fn new() -> Self {
+                    Self(slint::re_exports::live_reload::LiveReloadingComponent::new(
+                        #main_file,
+                        &[#(#include_paths),*],
+                        #style,
+                        &[#(#watched_files),*],
+                    ))
+                }
+
+                pub fn new_with_globals(init_globals: impl FnOnce(&Self)) -> Self {
+                    // The interpreter instance already exists, so the globals are set after its initialization.
+                    // They are set before the initialization of the instances created when reloading.
+                    let component = Self::new();
+                    init_globals(&component);
+                    component
+                }
+
+                #property_and_callback_accessors
+            }
+
+            impl slint::ComponentHandle for #compo_id {
+                type Inner = slint::re_exports::live_reload::Inner;
+                fn as_weak(&self) -> slint::Weak<Self> {
+                    slint::Weak::new(&self.0.inner())
+                }
+
+                fn clone_strong(&self) -> Self {
+                    Self(self.0.clone_strong())
+                }
+
+                fn from_inner(inner: vtable::VRc<slint::re_exports::ComponentVTable, Self::Inner>) -> Self {
+                    Self(slint::re_exports::live_reload::LiveReloadingComponent::from_inner(inner))
+                }
+
+                fn run(&self) {
+                    self.show();
+                    slint::run_event_loop();
+                    self.hide();
+                }
+
+                fn show(&self) {
+                    self.window().show();
+                }
+
+                fn hide(&self) {
+                    self.window().hide()
+                }
+
+                fn window(&self) -> &slint::Window {
+                    self.0.window()
+                }
+
+                fn global<'a, T: slint::Global<'a, Self>>(&'a self) -> T {
+                    T::get(&self)
+                }
+            }
+
+            const _THE_SAME_VERSION_MUST_BE_USED_FOR_THE_COMPILER_AND_THE_RUNTIME : slint::#version_check = slint::#version_check;
+        }
+        pub use #compo_module::{#compo_id #(,#structs_ids)* #(,#globals_ids)* };
+        pub use slint::{ComponentHandle, Global, ModelExt as _};
+    }
+}
+
+/// Public API of the live-reload mode for the root component, or for the global
+/// with the given name if `global` is not empty
+fn live_reload_public_api(public_properties: &llr::PublicProperties, global: &str) -> TokenStream {
+    let mut property_and_callback_accessors: Vec<TokenStream> = vec![];
+    for (p, llr::PublicProperty { ty, read_only, doc, .. }) in public_properties {
+        let prop_ident = ident(p);
+        let doc = doc_attribute(doc);
+
+        if let Type::Callback { args, return_type } = ty {
+            let callback_args = args.iter().map(|a| rust_type(a).unwrap()).collect::<Vec<_>>();
+            let return_type_tokens =
+                return_type.as_ref().map_or(quote!(()), |a| rust_type(a).unwrap());
+            let args_name = (0..args.len()).map(|i| format_ident!("arg_{}", i)).collect::<Vec<_>>();
+            let args_to_value = args
+                .iter()
+                .zip(&args_name)
+                .map(|(ty, name)| live_reload_to_value(ty, quote!(#name)));
+            let return_from_value = match return_type {
+                Some(ty) => live_reload_from_value(ty, quote!(result)),
+                None => quote!(()),
+            };
+            let caller_ident = format_ident!("invoke_{}", prop_ident);
+            property_and_callback_accessors.push(quote!(
+                #doc
+                #[allow(dead_code)]
+                pub fn #caller_ident(&self, #(#args_name : #callback_args,)*) -> #return_type_tokens {
+                    #[allow(unused)]
+                    let result = self.0.invoke_callback(#global, #p, &[#(#args_to_value),*]);
+                    #return_from_value
+                }
+            ));
+
+            let args_from_value = args.iter().enumerate().map(|(i, ty)| {
+                live_reload_from_value(ty, quote!(args.get(#i).cloned().unwrap_or_default()))
+            });
+            let return_to_value = match return_type {
+                Some(ty) => live_reload_to_value(ty, quote!(result)),
+                None => quote!(slint::re_exports::live_reload::Value::Void),
+            };
+            let on_ident = format_ident!("on_{}", prop_ident);
+            property_and_callback_accessors.push(quote!(
+                #doc
+                #[allow(dead_code)]
+                pub fn #on_ident(&self, f: impl FnMut(#(#callback_args),*) -> #return_type_tokens + 'static) {
+                    let f = ::core::cell::RefCell::new(f);
+                    self.0.set_callback(#global, #p, move |args| {
+                        #[allow(unused)]
+                        let result = (&mut *f.borrow_mut())(#(#args_from_value),*);
+                        #return_to_value
+                    })
+                }
+            ));
+        } else {
+            let rust_property_type = rust_type(ty).unwrap();
+
+            let getter_ident = format_ident!("get_{}", prop_ident);
+            let setter_ident = format_ident!("set_{}", prop_ident);
+
+            let from_value = live_reload_from_value(ty, quote!(self.0.get_property(#global, #p)));
+            property_and_callback_accessors.push(quote!(
+                #doc
+                #[allow(dead_code)]
+                pub fn #getter_ident(&self) -> #rust_property_type {
+                    #from_value
+                }
+            ));
+
+            if *read_only {
+                continue;
+            }
+            let to_value = live_reload_to_value(ty, quote!(value));
+            property_and_callback_accessors.push(quote!(
+                #doc
+                #[allow(dead_code)]
+                pub fn #setter_ident(&self, value: #rust_property_type) {
+                    self.0.set_property(#global, #p, #to_value)
+                }
+            ));
+        }
+    }
+
+    quote!(#(#property_and_callback_accessors)*)
+}
+
+/// Returns the code that converts the `value` of type `ty` to a `slint_interpreter::Value`
+fn live_reload_to_value(ty: &Type, value: TokenStream) -> TokenStream {
+    match ty {
+        Type::Struct { fields, name, node, .. } if name.is_none() || node.is_some() => {
+            let field_names = fields.keys();
+            let field_values = fields.iter().enumerate().map(|(i, (field_name, ty))| {
+                let field = if name.is_none() {
+                    let i = proc_macro2::Literal::usize_unsuffixed(i);
+                    quote!(value.#i)
+                } else {
+                    let field = ident(field_name);
+                    quote!(value.#field)
+                };
+                live_reload_to_value(ty, field)
+            });
+            quote!({
+                let value = #value;
+                let mut s = slint::re_exports::live_reload::Struct::default();
+                #(s.set_field(#field_names.into(), #field_values);)*
+                slint::re_exports::live_reload::Value::Struct(s)
+            })
+        }
+        Type::Array(inner) => {
+            let to_value = live_reload_to_value(inner, quote!(x));
+            let from_value = live_reload_from_value(inner, quote!(x));
+            quote!(slint::re_exports::live_reload::model_to_value(#value, |x| #to_value, |x| #from_value))
+        }
+        Type::Enumeration(en) if en.node.is_some() => {
+            let enum_id = struct_name_to_tokens(&en.name);
+            let enum_name = &en.name;
+            let values = en.values.iter().map(|value| ident(value));
+            let value_names = en.values.iter();
+            quote!(slint::re_exports::live_reload::Value::EnumerationValue(
+                #enum_name.into(),
+                match #value { #(#enum_id::#values => #value_names,)* }.into(),
+            ))
+        }
+        _ => quote!(slint::re_exports::live_reload::Value::from(#value)),
+    }
+}
+
+/// Returns the code that converts the `slint_interpreter::Value` in `value` to the rust type of `ty`.
+/// Values of the wrong type, for example after a type change in the reloaded file, give the default value.
+fn live_reload_from_value(ty: &Type, value: TokenStream) -> TokenStream {
+    match ty {
+        Type::Struct { fields, name, node, .. } if name.is_none() || node.is_some() => {
+            let field_values = fields.iter().map(|(name, ty)| {
+                live_reload_from_value(ty, quote!(s.get_field(#name).cloned().unwrap_or_default()))
+            });
+            let result = match name {
+                None => quote!((#(#field_values,)*)),
+                Some(name) => {
+                    let struct_id = struct_name_to_tokens(name);
+                    let field_ids = fields.keys().map(|name| ident(name));
+                    quote!(#struct_id { #(#field_ids: #field_values),* })
+                }
+            };
+            quote!(match #value {
+                slint::re_exports::live_reload::Value::Struct(s) => #result,
+                _ => Default::default(),
+            })
+        }
+        Type::Array(inner) => {
+            let to_value = live_reload_to_value(inner, quote!(x));
+            let from_value = live_reload_from_value(inner, quote!(x));
+            quote!(slint::re_exports::live_reload::model_from_value(#value, |x| #from_value, |x| #to_value))
+        }
+        Type::Enumeration(en) if en.node.is_some() => {
+            let enum_id = struct_name_to_tokens(&en.name);
+            let values = en.values.iter().map(|value| ident(value));
+            let value_names = en.values.iter();
+            quote!(match #value {
+                slint::re_exports::live_reload::Value::EnumerationValue(_, value) => match value.as_str() {
+                    #(#value_names => #enum_id::#values,)*
+                    _ => Default::default(),
+                },
+                _ => Default::default(),
+            })
+        }
+        _ => quote!(::core::convert::TryInto::try_into(#value).unwrap_or_default()),
+    }
+}
+
 fn generate_public_component(llr: &llr::PublicComponent) -> TokenStream {
     let public_component_id = public_component_id(&llr.item_tree.root);
     let inner_component_id = inner_component_id(&llr.item_tree.root);
@@ -264,16 +567,26 @@ fn generate_public_component(llr: &llr::PublicComponent) -> TokenStream {
     let global_names =
         llr.globals.iter().map(|g| format_ident!("global_{}", ident(&g.name))).collect::<Vec<_>>();
     let global_types = llr.globals.iter().map(global_inner_name).collect::<Vec<_>>();
+    let doc = doc_attribute(&llr.doc);
 
     quote!(
         #component
+        #doc
         pub struct #public_component_id(vtable::VRc<slint::re_exports::ComponentVTable, #inner_component_id>);
 
         impl #public_component_id {
             pub fn new() -> Self {
+                Self::new_with_globals(|_| {})
+            }
+
+            pub fn new_with_globals(init_globals: impl FnOnce(&Self)) -> Self {
                 let inner = #inner_component_id::new();
                 #(inner.globals.#global_names.clone().init(&inner);)*
-                Self(inner)
+                let component = Self(inner);
+                // The bindings of the component are initialized after the globals, so that they see their values
+                init_globals(&component);
+                #inner_component_id::init(slint::re_exports::VRc::map(component.0.clone(), |x| x), &component.0, 0, 1);
+                component
             }
 
             #property_and_callback_accessors
@@ -281,7 +594,16 @@ pub fn new() -> Self {
 
         impl From<#public_component_id> for vtable::VRc<slint::re_exports::ComponentVTable, #inner_component_id> {
             fn from(value: #public_component_id) -> Self {
-                value.0
+                value.0.clone()
+            }
+        }
+
+        impl Drop for #public_component_id {
+            fn drop(&mut self) {
+                // The component is destroyed with its last handle
+                if vtable::VRc::strong_count(&self.0) == 1 {
+                    slint::re_exports::will_destroy_component(&vtable::VRc::into_dyn(self.0.clone()));
+                }
             }
         }
 
@@ -336,15 +658,98 @@ fn default() -> Self {
     )
 }
 
-fn generate_struct(name: &str, fields: &BTreeMap<String, Type>) -> TokenStream {
+/// Generate the named structures and enumerations used by the document, and returns their
+/// identifiers and their code.
+fn generate_structs(doc: &Document, llr: &llr::PublicComponent) -> (Vec<Ident>, Vec<TokenStream>) {
+    doc.root_component
+        .used_types
+        .borrow()
+        .structs
+        .iter()
+        .filter_map(|ty| match ty {
+            Type::Struct { fields, name: Some(name), node: Some(node), defaults } => {
+                Some((ident(name), generate_struct(name, fields, node, defaults, llr)))
+            }
+            Type::Enumeration(en) => Some((ident(&en.name), generate_enum(en))),
+            _ => None,
+        })
+        .unzip()
+}
+
+fn generate_struct(
+    name: &str,
+    fields: &BTreeMap<String, Type>,
+    node: &syntax_nodes::ObjectType,
+    defaults: &BTreeMap<String, crate::expression_tree::Expression>,
+    root: &llr::PublicComponent,
+) -> TokenStream {
     let component_id = struct_name_to_tokens(name);
     let (declared_property_vars, declared_property_types): (Vec<_>, Vec<_>) =
         fields.iter().map(|(name, ty)| (ident(name), rust_type(ty).unwrap())).unzip();
+    let doc = doc_attribute(&super::struct_doc_comment(node));
+    let field_docs = fields
+        .keys()
+        .map(|name| doc_attribute(&super::struct_field_doc_comment(node, name)))
+        .collect::<Vec<_>>();
+
+    let defaults = crate::llr::lower_expression::lower_struct_field_defaults(defaults);
+    if defaults.is_empty() {
+        return quote! {
+            #doc
+            #[derive(Default, PartialEq, Debug, Clone)]
+            pub struct #component_id {
+                #(#field_docs pub #declared_property_vars : #declared_property_types),*
+            }
+        };
+    }
+
+    let ctx = EvaluationContext::new_sub_component(root, &root.item_tree.root, quote!(), None);
+    let default_values = fields.keys().map(|name| match defaults.get(name) {
+        Some(e) => {
+            let e = compile_expression(e, &ctx);
+            quote!((#e) as _)
+        }
+        None => quote!(Default::default()),
+    });
 
     quote! {
-        #[derive(Default, PartialEq, Debug, Clone)]
+        #doc
+        #[derive(PartialEq, Debug, Clone)]
         pub struct #component_id {
-            #(pub #declared_property_vars : #declared_property_types),*
+            #(#field_docs pub #declared_property_vars : #declared_property_types),*
+        }
+
+        impl Default for #component_id {
+            fn default() -> Self {
+                Self { #(#declared_property_vars : #default_values),* }
+            }
+        }
+    }
+}
+
+fn generate_enum(en: &crate::langtype::Enumeration) -> TokenStream {
+    let enum_id = struct_name_to_tokens(&en.name);
+    let values = en.values.iter().map(|value| ident(value)).collect::<Vec<_>>();
+    let default_value = &values[en.default_value];
+    let doc = doc_attribute(&en.node.as_ref().and_then(|n| crate::parser::doc_comment(n)));
+    let value_docs = en
+        .values
+        .iter()
+        .map(|value| doc_attribute(&super::enum_value_doc_comment(en, value)))
+        .collect::<Vec<_>>();
+
+    quote! {
+        #doc
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #[allow(non_camel_case_types)]
+        pub enum #enum_id {
+            #(#value_docs #values),*
+        }
+
+        impl Default for #enum_id {
+            fn default() -> Self {
+                Self::#default_value
+            }
         }
     }
 }
@@ -452,8 +857,9 @@ fn public_api(
     ctx: &EvaluationContext,
 ) -> TokenStream {
     let mut property_and_callback_accessors: Vec<TokenStream> = vec![];
-    for (p, (ty, r)) in public_properties {
+    for (p, llr::PublicProperty { ty, prop: r, read_only, doc }) in public_properties {
         let prop_ident = ident(p);
+        let doc = doc_attribute(doc);
         let prop = access_member(r, ctx);
 
         if let Type::Callback { args, return_type } = ty {
@@ -462,6 +868,7 @@ fn public_api(
             let args_name = (0..args.len()).map(|i| format_ident!("arg_{}", i)).collect::<Vec<_>>();
             let caller_ident = format_ident!("invoke_{}", prop_ident);
             property_and_callback_accessors.push(quote!(
+                #doc
                 #[allow(dead_code)]
                 pub fn #caller_ident(&self, #(#args_name : #callback_args,)*) -> #return_type {
                     let _self = #self_init;
@@ -471,6 +878,7 @@ pub fn #caller_ident(&self, #(#args_name : #callback_args,)*) -> #return_type {
             let on_ident = format_ident!("on_{}", prop_ident);
             let args_index = (0..callback_args.len()).map(proc_macro2::Literal::usize_unsuffixed);
             property_and_callback_accessors.push(quote!(
+                #doc
                 #[allow(dead_code)]
                 pub fn #on_ident(&self, mut f: impl FnMut(#(#callback_args),*) -> #return_type + 'static) {
                     let _self = #self_init;
@@ -488,6 +896,7 @@ pub fn #on_ident(&self, mut f: impl FnMut(#(#callback_args),*) -> #return_type +
             let setter_ident = format_ident!("set_{}", prop_ident);
 
             property_and_callback_accessors.push(quote!(
+                #doc
                 #[allow(dead_code)]
                 pub fn #getter_ident(&self) -> #rust_property_type {
                     #[allow(unused_imports)]
@@ -497,8 +906,12 @@ pub fn #getter_ident(&self) -> #rust_property_type {
                 }
             ));
 
+            if *read_only {
+                continue;
+            }
             let set_value = property_set_value_tokens(r, quote!(value), ctx);
             property_and_callback_accessors.push(quote!(
+                #doc
                 #[allow(dead_code)]
                 pub fn #setter_ident(&self, value: #rust_property_type) {
                     #[allow(unused_imports)]
@@ -513,6 +926,14 @@ pub fn #setter_ident(&self, value: #rust_property_type) {
     quote!(#(#property_and_callback_accessors)*)
 }
 
+/// Returns the `#[doc]` attribute with the `///` comments of a declaration, if it has some
+fn doc_attribute(doc: &Option<String>) -> TokenStream {
+    match doc {
+        Some(doc) => quote!(#[doc = #doc]),
+        None => quote!(),
+    }
+}
+
 /// Generate the rust code for the given component.
 fn generate_sub_component(
     component: &llr::SubComponent,
@@ -630,11 +1051,12 @@ fn generate_sub_component(
             let lv_h = access_member(&listview.listview_height, &ctx);
             let vp_w = access_member(&listview.viewport_width, &ctx);
             let lv_w = access_member(&listview.listview_width, &ctx);
+            let first_visible = access_member(&listview.first_visible_item, &ctx);
 
             quote! {
                 #inner_component_id::FIELD_OFFSETS.#repeater_id.apply_pin(_self).ensure_updated_listview(
                     || { #rep_inner_component_id::new(_self.self_weak.get().unwrap().clone()).into() },
-                    #vp_w, #vp_h, #vp_y, #lv_w.get(), #lv_h
+                    #vp_w, #vp_h, #vp_y, #lv_w.get(), #lv_h, #first_visible
                 );
             }
         } else {
@@ -667,6 +1089,35 @@ fn generate_sub_component(
         repeated_element_components.push(rep_inner_component_id);
     }
 
+    let mut accessible_role_branch = vec![];
+    let mut accessible_string_property_branch = vec![];
+    let mut accessibility_action_branch = vec![];
+
+    for ((index, what), expr) in &component.accessible_prop {
+        let index = *index as usize;
+        let expr = compile_expression(&expr.borrow(), &ctx);
+        if what == "Role" {
+            accessible_role_branch.push(quote!(#index => #expr,));
+        } else if let Some(what) = what.strip_prefix("Action") {
+            let what = ident(what);
+            if what == "SetValue" {
+                accessibility_action_branch.push(quote!(
+                    (#index, AccessibilityAction::SetValue(value)) => {
+                        let args = (value.clone(),);
+                        #expr;
+                    }
+                ));
+            } else {
+                accessibility_action_branch
+                    .push(quote!((#index, AccessibilityAction::#what) => { #expr; }));
+            }
+        } else {
+            let what = ident(what);
+            accessible_string_property_branch
+                .push(quote!((#index, AccessibleStringProperty::#what) => #expr,));
+        }
+    }
+
     let mut sub_component_names: Vec<Ident> = vec![];
     let mut sub_component_types: Vec<Ident> = vec![];
 
@@ -698,6 +1149,31 @@ fn generate_sub_component(
             #global_index, #global_children
         );));
 
+        // The items of the sub-component are forwarded to it, with its own local index.
+        let sub_index = sub.index_in_tree;
+        accessible_role_branch
+            .push(quote!(#sub_index => #sub_compo_field.apply_pin(_self).accessible_role(0),));
+        accessible_string_property_branch.push(quote!(
+            (#sub_index, _) => #sub_compo_field.apply_pin(_self).accessible_string_property(0, what),
+        ));
+        accessibility_action_branch.push(quote!(
+            (#sub_index, _) => #sub_compo_field.apply_pin(_self).accessibility_action(0, action),
+        ));
+        let sub_item_count = sub.ty.item_count();
+        if sub_item_count > 1 {
+            let first = sub.index_of_first_child_in_tree;
+            let last = first + sub_item_count - 2;
+            accessible_role_branch.push(quote!(
+                #first..=#last => #sub_compo_field.apply_pin(_self).accessible_role(index - #first + 1),
+            ));
+            accessible_string_property_branch.push(quote!(
+                (#first..=#last, _) => #sub_compo_field.apply_pin(_self).accessible_string_property(index - #first + 1, what),
+            ));
+            accessibility_action_branch.push(quote!(
+                (#first..=#last, _) => #sub_compo_field.apply_pin(_self).accessibility_action(index - #first + 1, action),
+            ));
+        }
+
         let sub_component_repeater_count = sub.ty.repeater_count();
         if sub_component_repeater_count > 0 {
             let repeater_offset = sub.repeater_offset;
@@ -759,6 +1235,27 @@ fn generate_sub_component(
 
     init.extend(component.init_code.iter().map(|e| compile_expression(&e.borrow(), &ctx)));
 
+    let mut change_tracker_names = vec![];
+    for (idx, (prop, handler)) in component.change_callbacks.iter().enumerate() {
+        let tracker = format_ident!("change_tracker{}", idx);
+        let prop = compile_expression(&llr::Expression::PropertyReference(prop.clone()), &ctx);
+        let handler = compile_expression(&handler.borrow(), &ctx);
+        init.push(quote!(
+            slint::internal::init_change_tracker(&_self.#tracker, &self_rc,
+                |self_rc| {
+                    let _self = self_rc.as_pin_ref();
+                    #prop
+                },
+                |self_rc| {
+                    let _self = self_rc.as_pin_ref();
+                    #handler;
+                }
+            );
+            #handler;
+        ));
+        change_tracker_names.push(tracker);
+    }
+
     let layout_info_h = compile_expression(&component.layout_info_h.borrow(), &ctx);
     let layout_info_v = compile_expression(&component.layout_info_v.borrow(), &ctx);
 
@@ -788,6 +1285,7 @@ struct #inner_component_id {
             #(#declared_property_vars : slint::re_exports::Property<#declared_property_types>,)*
             #(#declared_callbacks : slint::re_exports::Callback<(#(#declared_callbacks_types,)*), #declared_callbacks_ret>,)*
             #(#repeated_element_names : slint::re_exports::Repeater<#repeated_element_components>,)*
+            #(#change_tracker_names : slint::re_exports::ChangeTracker,)*
             self_weak : slint::re_exports::OnceCell<slint::re_exports::VWeakMapped<slint::re_exports::ComponentVTable, #inner_component_id>>,
             #(parent : #parent_component_type,)*
             // FIXME: Do we really need a window all the time?
@@ -863,6 +1361,40 @@ fn index_property(self: ::core::pin::Pin<&Self>) -> usize {
                 let _self = self;
                 #subtree_index_function
             }
+
+            fn accessible_role(self: ::core::pin::Pin<&Self>, index: usize) -> slint::re_exports::AccessibleRole {
+                #![allow(unused)]
+                use slint::re_exports::*;
+                let _self = self;
+                match index {
+                    #(#accessible_role_branch)*
+                    _ => AccessibleRole::default(),
+                }
+            }
+
+            fn accessible_string_property(
+                self: ::core::pin::Pin<&Self>,
+                index: usize,
+                what: slint::re_exports::AccessibleStringProperty,
+            ) -> slint::re_exports::SharedString {
+                #![allow(unused)]
+                use slint::re_exports::*;
+                let _self = self;
+                match (index, what) {
+                    #(#accessible_string_property_branch)*
+                    _ => Default::default(),
+                }
+            }
+
+            fn accessibility_action(self: ::core::pin::Pin<&Self>, index: usize, action: &slint::re_exports::AccessibilityAction) {
+                #![allow(unused)]
+                use slint::re_exports::*;
+                let _self = self;
+                match (index, action) {
+                    #(#accessibility_action_branch)*
+                    _ => (),
+                }
+            }
         }
 
         #(#extra_components)*
@@ -939,7 +1471,9 @@ fn generate_global(global: &llr::GlobalComponent, root: &llr::PublicComponent) -
         let global_id = format_ident!("global_{}", public_component_id);
 
         let aliases = global.aliases.iter().map(|name| ident(name));
+        let doc = doc_attribute(&global.doc);
         quote!(
+            #doc
             pub struct #public_component_id<'a>(&'a ::core::pin::Pin<slint::re_exports::Rc<#inner_component_id>>);
 
             impl<'a> #public_component_id<'a> {
@@ -1009,16 +1543,24 @@ fn generate_item_tree(
     } else {
         quote!(&self_rc)
     };
-    let (create_window, init_window) = if parent_ctx.is_none() {
+    let (create_window, init_window, init_component) = if parent_ctx.is_none() {
         (
             Some(quote!(let window = slint::create_window().into();)),
             Some(quote! {
                 _self.window.set(window);
                 _self.window.get().unwrap().window_handle().set_component(&VRc::into_dyn(self_rc.clone()));
             }),
+            // The root component is initialized by the public component, after its globals
+            None,
         )
     } else {
-        (None, None)
+        (
+            None,
+            None,
+            Some(
+                quote!(Self::init(slint::re_exports::VRc::map(self_rc.clone(), |x| x), #root_token, 0, 1);),
+            ),
+        )
     };
 
     let parent_item_expression = parent_ctx.and_then(|parent| {
@@ -1100,7 +1642,7 @@ pub fn new(#(parent: #parent_component_type)*)
                 let _self = self_rc.as_pin_ref();
                 #init_window
                 slint::re_exports::init_component_items(_self, Self::item_array(), #root_token.window.get().unwrap().window_handle());
-                Self::init(slint::re_exports::VRc::map(self_rc.clone(), |x| x), #root_token, 0, 1);
+                #init_component
                 self_rc
             }
 
@@ -1185,6 +1727,23 @@ fn parent_node(self: ::core::pin::Pin<&Self>, _result: &mut slint::re_exports::I
             fn layout_info(self: ::core::pin::Pin<&Self>, orientation: slint::re_exports::Orientation) -> slint::re_exports::LayoutInfo {
                 self.layout_info(orientation)
             }
+
+            fn accessible_role(self: ::core::pin::Pin<&Self>, index: usize) -> slint::re_exports::AccessibleRole {
+                self.accessible_role(index)
+            }
+
+            fn accessible_string_property(
+                self: ::core::pin::Pin<&Self>,
+                index: usize,
+                what: slint::re_exports::AccessibleStringProperty,
+                result: &mut slint::re_exports::SharedString,
+            ) {
+                *result = self.accessible_string_property(index, what);
+            }
+
+            fn accessibility_action(self: ::core::pin::Pin<&Self>, index: usize, action: &slint::re_exports::AccessibilityAction) {
+                self.accessibility_action(index, action)
+            }
         }
 
 
@@ -1807,9 +2366,9 @@ fn compile_expression(expr: &Expression, ctx: &EvaluationContext) -> TokenStream
             ))
         }
         Expression::EnumerationValue(value) => {
-            let base_ident = ident(&value.enumeration.name);
+            let base = rust_type(&Type::Enumeration(value.enumeration.clone())).unwrap();
             let value_ident = ident(&value.to_string());
-            quote!(slint::re_exports::#base_ident::#value_ident)
+            quote!(#base::#value_ident)
         }
         Expression::ReturnStatement(expr) => {
             let return_expr = expr.as_ref().map(|expr| compile_expression(expr, ctx));
@@ -1877,6 +2436,73 @@ fn compile_builtin_function_call(
                 panic!("internal error: invalid args to SetFocusItem {:?}", arguments)
             }
         }
+        BuiltinFunction::ScrollTo => {
+            if let [Expression::PropertyReference(pr), x, y, animated] = arguments {
+                let flickable = access_item_rc(pr, ctx);
+                let x = compile_expression(x, ctx);
+                let y = compile_expression(y, ctx);
+                let animated = compile_expression(animated, ctx);
+                quote!(
+                    slint::re_exports::flickable_scroll_to(#flickable, #x as _, #y as _, #animated);
+                )
+            } else {
+                panic!("internal error: invalid args to ScrollTo {:?}", arguments)
+            }
+        }
+        BuiltinFunction::EnsureVisible => {
+            if let [Expression::PropertyReference(pr), Expression::PropertyReference(target)] =
+                arguments
+            {
+                let flickable = access_item_rc(pr, ctx);
+                let target = access_item_rc(target, ctx);
+                quote!(
+                    slint::re_exports::flickable_ensure_visible(#flickable, #target);
+                )
+            } else {
+                panic!("internal error: invalid args to EnsureVisible {:?}", arguments)
+            }
+        }
+        BuiltinFunction::TextInputUndo | BuiltinFunction::TextInputRedo => {
+            if let [Expression::PropertyReference(pr)] = arguments {
+                let window_tokens = access_window_field(ctx);
+                let text_input = access_item_rc(pr, ctx);
+                let function = if matches!(function, BuiltinFunction::TextInputUndo) {
+                    quote!(text_input_undo)
+                } else {
+                    quote!(text_input_redo)
+                };
+                quote!(
+                    slint::re_exports::#function(#text_input, #window_tokens);
+                )
+            } else {
+                panic!("internal error: invalid args to {:?} {:?}", function, arguments)
+            }
+        }
+        BuiltinFunction::TextInputFindNext | BuiltinFunction::TextInputFindPrevious => {
+            if let [Expression::PropertyReference(pr)] = arguments {
+                let window_tokens = access_window_field(ctx);
+                let text_input = access_item_rc(pr, ctx);
+                let forward = matches!(function, BuiltinFunction::TextInputFindNext);
+                quote!(
+                    slint::re_exports::text_input_find(#text_input, #window_tokens, #forward)
+                )
+            } else {
+                panic!("internal error: invalid args to {:?} {:?}", function, arguments)
+            }
+        }
+        BuiltinFunction::TextInputSearchMatchCount | BuiltinFunction::TextInputCurrentMatch => {
+            if let [Expression::PropertyReference(pr)] = arguments {
+                let text_input = access_item_rc(pr, ctx);
+                let function = if matches!(function, BuiltinFunction::TextInputSearchMatchCount) {
+                    quote!(text_input_search_match_count)
+                } else {
+                    quote!(text_input_current_match)
+                };
+                quote!(slint::re_exports::#function(#text_input))
+            } else {
+                panic!("internal error: invalid args to {:?} {:?}", function, arguments)
+            }
+        }
         BuiltinFunction::ShowPopupWindow => {
             if let [Expression::NumberLiteral(popup_index), x, y, Expression::PropertyReference(parent_ref)] =
                 arguments
@@ -1949,6 +2575,18 @@ fn compile_builtin_function_call(
             let window_tokens = access_window_field(ctx);
             quote!(#window_tokens.scale_factor())
         }
+        BuiltinFunction::GetWindowWidth => {
+            let window_tokens = access_window_field(ctx);
+            quote!(#window_tokens.window_item_size().width)
+        }
+        BuiltinFunction::GetWindowHeight => {
+            let window_tokens = access_window_field(ctx);
+            quote!(#window_tokens.window_item_size().height)
+        }
+        BuiltinFunction::GetWindowDefaultFontSize => {
+            let window_tokens = access_window_field(ctx);
+            quote!(#window_tokens.default_font_size())
+        }
         BuiltinFunction::Debug => quote!(println!("{:?}", #(#a)*)),
         BuiltinFunction::Mod => quote!((#(#a as i32)%*)),
         BuiltinFunction::Round => quote!((#(#a)* as f64).round()),
@@ -1956,6 +2594,13 @@ fn compile_builtin_function_call(
         BuiltinFunction::Floor => quote!((#(#a)* as f64).floor()),
         BuiltinFunction::Sqrt => quote!((#(#a)* as f64).sqrt()),
         BuiltinFunction::Abs => quote!((#(#a)* as f64).abs()),
+        BuiltinFunction::Snap => {
+            let window_tokens = access_window_field(ctx);
+            quote!({
+                let scale_factor = #window_tokens.scale_factor() as f64;
+                ((#(#a)* as f64 * scale_factor).round() / scale_factor) as slint::re_exports::Coord
+            })
+        }
         BuiltinFunction::Sin => quote!((#(#a)* as f64).to_radians().sin()),
         BuiltinFunction::Cos => quote!((#(#a)* as f64).to_radians().cos()),
         BuiltinFunction::Tan => quote!((#(#a)* as f64).to_radians().tan()),
@@ -1974,6 +2619,15 @@ fn compile_builtin_function_call(
             quote!(#(#a)*.as_str().parse::<f64>().unwrap_or_default())
         }
         BuiltinFunction::StringIsFloat => quote!(#(#a)*.as_str().parse::<f64>().is_ok()),
+        BuiltinFunction::StringFormatFloat => {
+            let (value, precision) = (a.next().unwrap(), a.next().unwrap());
+            quote!(slint::re_exports::string::format_float(#value as f64, #precision as i32))
+        }
+        BuiltinFunction::StringPad => {
+            let (string, width, fill, alignment) =
+                (a.next().unwrap(), a.next().unwrap(), a.next().unwrap(), a.next().unwrap());
+            quote!(slint::re_exports::string::pad_string(&#string, #width as i32, &#fill, #alignment as i32))
+        }
         BuiltinFunction::ColorBrighter => {
             let x = a.next().unwrap();
             let factor = a.next().unwrap();
@@ -1984,6 +2638,45 @@ fn compile_builtin_function_call(
             let factor = a.next().unwrap();
             quote!(#x.darker(#factor as f32))
         }
+        BuiltinFunction::ColorLighter => {
+            let x = a.next().unwrap();
+            let factor = a.next().unwrap();
+            quote!(#x.lighter(#factor as f32))
+        }
+        BuiltinFunction::ColorSaturate => {
+            let x = a.next().unwrap();
+            let factor = a.next().unwrap();
+            quote!(#x.saturate(#factor as f32))
+        }
+        BuiltinFunction::ColorWithAlpha => {
+            let x = a.next().unwrap();
+            let alpha = a.next().unwrap();
+            quote!(#x.with_alpha(#alpha as f32))
+        }
+        BuiltinFunction::ColorMix => {
+            let (x, y, factor) = (a.next().unwrap(), a.next().unwrap(), a.next().unwrap());
+            quote!(#x.mix(&#y, #factor as f32))
+        }
+        BuiltinFunction::FormatNumber => {
+            let (value, decimals) = (a.next().unwrap(), a.next().unwrap());
+            quote!(slint::re_exports::locale::format_number(#value as f64, #decimals as i32))
+        }
+        BuiltinFunction::FormatPercent => {
+            let (value, decimals) = (a.next().unwrap(), a.next().unwrap());
+            quote!(slint::re_exports::locale::format_percent(#value as f64, #decimals as i32))
+        }
+        BuiltinFunction::FormatDate => {
+            quote!(slint::re_exports::locale::format_date(#(#a)* as i64))
+        }
+        BuiltinFunction::FormatTime => {
+            quote!(slint::re_exports::locale::format_time(#(#a)* as i64))
+        }
+        BuiltinFunction::GetColorScheme => {
+            quote!(slint::re_exports::color_scheme::color_scheme())
+        }
+        BuiltinFunction::AnimationTick => {
+            quote!(slint::re_exports::animation_tick() as i64)
+        }
         BuiltinFunction::ImageSize => quote!( #(#a)*.size()),
         BuiltinFunction::ArrayLength => {
             quote!(match &#(#a)* { x => {
@@ -61,6 +61,9 @@ pub enum Type {
         name: Option<String>,
         /// When declared in .slint, this is the node of the declaration.
         node: Option<syntax_nodes::ObjectType>,
+        /// The default values of the fields declared as `foo: int = 42`. These are constant
+        /// expressions resolved once, when the struct is declared.
+        defaults: BTreeMap<String, Expression>,
     },
     Enumeration(Rc<Enumeration>),
 
@@ -107,8 +110,8 @@ fn eq(&self, other: &Self) -> bool {
             Type::Easing => matches!(other, Type::Easing),
             Type::Brush => matches!(other, Type::Brush),
             Type::Array(a) => matches!(other, Type::Array(b) if a == b),
-            Type::Struct { fields, name, node: _ } => {
-                matches!(other, Type::Struct{fields: f, name: n, node: _} if fields == f && name == n)
+            Type::Struct { fields, name, .. } => {
+                matches!(other, Type::Struct{fields: f, name: n, ..} if fields == f && name == n)
             }
             Type::Enumeration(lhs) => matches!(other, Type::Enumeration(rhs) if lhs == rhs),
             Type::UnitProduct(a) => matches!(other, Type::UnitProduct(b) if a == b),
@@ -645,6 +648,8 @@ pub struct Enumeration {
     pub name: String,
     pub values: Vec<String>,
     pub default_value: usize, // index in values
+    /// When declared in a .slint file, this is the declaration node
+    pub node: Option<syntax_nodes::EnumDeclaration>,
 }
 
 impl PartialEq for Enumeration {
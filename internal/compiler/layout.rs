@@ -411,12 +411,25 @@ fn init_fake_property(
     }
 }
 
+/// The sizing policy of a column or a row in a GridLayout, as set with a `Column` or `Row` element
+#[derive(Debug, Clone)]
+pub struct GridLayoutPolicy {
+    /// Horizontal for a column, Vertical for a row
+    pub orientation: Orientation,
+    /// The index of the column or row
+    pub index: u16,
+    pub constraints: LayoutConstraints,
+}
+
 /// Internal representation of a grid layout
 #[derive(Debug, Clone)]
 pub struct GridLayout {
     /// All the elements will be layout within that element.
     pub elems: Vec<GridLayoutElement>,
 
+    /// The sizing policies of the columns and rows
+    pub policies: Vec<GridLayoutPolicy>,
+
     pub geometry: LayoutGeometry,
 
     /// When this GridLayout is actually the layout of a Dialog, then the cells start with all the buttons,
@@ -429,6 +442,9 @@ fn visit_named_references(&mut self, visitor: &mut impl FnMut(&mut NamedReferenc
         for cell in &mut self.elems {
             cell.item.constraints.visit_named_references(visitor);
         }
+        for policy in &mut self.policies {
+            policy.constraints.visit_named_references(visitor);
+        }
         self.geometry.visit_named_references(visitor);
     }
 }
@@ -481,6 +497,7 @@ pub fn layout_info_type() -> Type {
             .collect(),
         name: Some("LayoutInfo".into()),
         node: None,
+        defaults: Default::default(),
     }
 }
 
@@ -138,7 +138,7 @@ pub fn lex_number(text: &str, _: &mut LexState) -> usize {
     let mut had_period = false;
     while let Some(c) = chars.next() {
         if !c.is_ascii_digit() {
-            if !had_period && c == '.' && len > 0 {
+            if !had_period && c == '.' && len > 0 && !text[len + 1..].starts_with('.') {
                 had_period = true;
             } else {
                 if len > 0 {
@@ -251,6 +251,15 @@ fn compare(source: &str, expected: &[(crate::parser::SyntaxKind, &str)]) {
             (crate::parser::SyntaxKind::NumberLiteral, "0.7%"),
         ],
     );
+    compare(
+        r#"0..10"#,
+        &[
+            (crate::parser::SyntaxKind::NumberLiteral, "0"),
+            (crate::parser::SyntaxKind::Dot, "."),
+            (crate::parser::SyntaxKind::Dot, "."),
+            (crate::parser::SyntaxKind::NumberLiteral, "10"),
+        ],
+    );
     compare(
         r#"aa_a.b1,c"#,
         &[
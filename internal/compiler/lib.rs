@@ -15,6 +15,7 @@
 use std::rc::Rc;
 
 pub mod builtin_macros;
+pub mod design_tokens;
 pub mod diagnostics;
 pub mod embedded_resources;
 pub mod expression_tree;
@@ -54,7 +55,8 @@ pub struct CompilerConfiguration {
     pub embed_resources: EmbedResourcesKind,
     /// The compiler will look in these paths for components used in the file to compile.
     pub include_paths: Vec<std::path::PathBuf>,
-    /// the name of the style. (eg: "native")
+    /// the name of the style (eg: "native"), or the path of the directory of a custom style, which
+    /// contains a std-widgets.slint file
     pub style: Option<String>,
 
     /// Callback to load import files which is called if the file could not be found
@@ -73,6 +75,29 @@ pub struct CompilerConfiguration {
 
     /// Compile time scale factor to apply to embedded resources such as images and glyphs.
     pub scale_factor: f64,
+
+    /// Font files to embed in the program and register at startup, in addition to the fonts
+    /// imported from the .slint files.
+    pub embed_fonts: Vec<std::path::PathBuf>,
+
+    /// Reduce the embedded fonts to the glyphs used by the design.
+    pub subset_fonts: bool,
+
+    /// The characters whose glyphs are rasterized and embedded for the software renderer, in
+    /// addition to the printable ASCII characters and the characters of the string literals.
+    pub embed_glyphs_characters: String,
+
+    /// The font sizes, in logical pixels, at which the glyphs are rasterized and embedded for the
+    /// software renderer, in addition to the default font size and the sizes set in the design.
+    pub embed_glyphs_sizes: Vec<f32>,
+
+    /// The file extensions of the image formats that the backend can decode, or None if it can
+    /// decode all of them. The compiler warns about the images in the other formats.
+    pub supported_image_formats: Option<Vec<String>>,
+
+    /// Whether the backend shapes the text. If it only maps each character to a glyph, the compiler
+    /// warns about the strings that can't be displayed correctly that way.
+    pub text_shaping: bool,
 }
 
 impl CompilerConfiguration {
@@ -115,6 +140,23 @@ pub fn new(output_format: crate::generator::OutputFormat) -> Self {
             .filter(|f| *f > 0.)
             .unwrap_or(1.);
 
+        let supported_image_formats = std::env::var("SLINT_IMAGE_FORMATS").ok().map(|formats| {
+            formats
+                .split(',')
+                .map(|format| format.trim().to_lowercase())
+                .filter(|format| !format.is_empty())
+                .collect()
+        });
+
+        let text_shaping = match std::env::var("SLINT_TEXT_SHAPING") {
+            Ok(var) => var.parse::<bool>().unwrap_or_else(|_| {
+                panic!(
+                    "SLINT_TEXT_SHAPING has incorrect value. Must be either unset, 'true' or 'false'"
+                )
+            }),
+            Err(_) => true,
+        };
+
         Self {
             embed_resources,
             include_paths: Default::default(),
@@ -122,6 +164,12 @@ pub fn new(output_format: crate::generator::OutputFormat) -> Self {
             open_import_fallback: Default::default(),
             inline_all_elements,
             scale_factor,
+            embed_fonts: Default::default(),
+            subset_fonts: false,
+            embed_glyphs_characters: Default::default(),
+            embed_glyphs_sizes: Default::default(),
+            supported_image_formats,
+            text_shaping,
         }
     }
 }
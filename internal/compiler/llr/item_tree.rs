@@ -66,6 +66,8 @@ pub struct GlobalComponent {
     pub aliases: Vec<String>,
     /// True when this is a built-in global that does not need to be generated
     pub is_builtin: bool,
+    /// The `///` comments of the global declaration
+    pub doc: Option<String>,
 
     /// Analysis for each properties
     pub prop_analysis: Vec<crate::object_tree::PropertyAnalysis>,
@@ -104,6 +106,8 @@ pub struct ListViewInfo {
     pub listview_height: PropertyReference,
     /// The ListView's inner visible width (not counting eventual scrollbar)
     pub listview_width: PropertyReference,
+    /// The first visible row of the ListView
+    pub first_visible_item: PropertyReference,
 
     // In the repeated component context
     pub prop_y: PropertyReference,
@@ -218,10 +222,17 @@ pub struct SubComponent {
     pub const_properties: Vec<PropertyReference>,
     // Code that is run in the sub component constructor, after property initializations
     pub init_code: Vec<MutExpression>,
+    /// Code that is run after the init code, and then every time the value of the property changes
+    pub change_callbacks: Vec<(PropertyReference, MutExpression)>,
 
     pub layout_info_h: MutExpression,
     pub layout_info_v: MutExpression,
 
+    /// Maps (item_index, property) to an expression.
+    /// The property is `Role`, the name of a variant of `AccessibleStringProperty`, or `Action`
+    /// followed by the name of a variant of `AccessibilityAction`
+    pub accessible_prop: BTreeMap<(u32, String), MutExpression>,
+
     pub prop_analysis: HashMap<PropertyReference, PropAnalysis>,
 }
 
@@ -241,6 +252,16 @@ pub fn repeater_count(&self) -> usize {
         }
         count
     }
+
+    /// total count of nodes in the item tree of this sub component, including the root
+    /// and the nodes of the sub components
+    pub fn item_count(&self) -> usize {
+        let mut count = self.items.len() + self.repeated.len();
+        for x in self.sub_components.iter() {
+            count += x.ty.item_count();
+        }
+        count
+    }
 }
 
 pub struct SubComponentInstance {
@@ -280,6 +301,8 @@ pub struct PublicComponent {
     pub item_tree: ItemTree,
     pub sub_components: Vec<Rc<SubComponent>>,
     pub globals: Vec<GlobalComponent>,
+    /// The `///` comments of the component declaration
+    pub doc: Option<String>,
 }
 
 impl PublicComponent {
@@ -321,11 +344,17 @@ pub fn for_each_expression<'a>(
             for e in &sc.init_code {
                 visitor(e, ctx);
             }
+            for (_, e) in &sc.change_callbacks {
+                visitor(e, ctx);
+            }
             for (_, e) in &sc.property_init {
                 visitor(&e.expression, ctx);
             }
             visitor(&sc.layout_info_h, ctx);
             visitor(&sc.layout_info_v, ctx);
+            for e in sc.accessible_prop.values() {
+                visitor(e, ctx);
+            }
         });
         for g in &self.globals {
             let ctx = EvaluationContext::new_global(self, g, ());
@@ -336,4 +365,14 @@ pub fn for_each_expression<'a>(
     }
 }
 
-pub type PublicProperties = BTreeMap<String, (Type, PropertyReference)>;
+#[derive(Debug, Clone)]
+pub struct PublicProperty {
+    pub ty: Type,
+    pub prop: PropertyReference,
+    /// An `out` property: only a getter is generated
+    pub read_only: bool,
+    /// The `///` comments of the declaration
+    pub doc: Option<String>,
+}
+
+pub type PublicProperties = BTreeMap<String, PublicProperty>;
@@ -183,6 +183,25 @@ pub fn lower_expression(
     }
 }
 
+/// Lower the default values of the fields of a struct declared as `struct Foo := { foo: int = 42 }`.
+///
+/// These are constant expressions that do not refer to any element or property.
+pub fn lower_struct_field_defaults(
+    defaults: &BTreeMap<String, tree_Expression>,
+) -> BTreeMap<String, llr_Expression> {
+    if defaults.is_empty() {
+        return BTreeMap::new();
+    }
+    let component = Rc::default();
+    let ctx = ExpressionContext {
+        component: &component,
+        mapping: &Default::default(),
+        state: &Default::default(),
+        parent: None,
+    };
+    defaults.iter().map(|(name, e)| (name.clone(), lower_expression(e, &ctx))).collect()
+}
+
 fn lower_assignment(
     lhs: &tree_Expression,
     rhs: &tree_Expression,
@@ -381,6 +400,7 @@ fn animation_ty() -> Type {
             fields: animation_fields().collect(),
             name: Some("PropertyAnimation".into()),
             node: None,
+            defaults: Default::default(),
         }
     }
 
@@ -418,6 +438,7 @@ fn animation_ty() -> Type {
                     .collect(),
                     name: None,
                     node: None,
+                    defaults: Default::default(),
                 },
                 values: IntoIterator::into_iter([
                     ("0".to_string(), get_anim),
@@ -679,6 +700,7 @@ fn box_layout_data(
         .collect(),
         name: Some("BoxLayoutCellData".into()),
         node: None,
+        defaults: Default::default(),
     };
 
     if repeater_count == 0 {
@@ -750,6 +772,35 @@ fn grid_layout_cell_data(
                     ],
                 )
             })
+            .chain(layout.policies.iter().filter(|p| p.orientation == orientation).map(|p| {
+                // The sizing policy of a column or row is a cell with a span of 0
+                let ty = crate::layout::layout_info_type();
+                let mut values = [
+                    ("min", 0.),
+                    ("max", f32::MAX as f64),
+                    ("preferred", 0.),
+                    ("min_percent", 0.),
+                    ("max_percent", 100.),
+                    ("stretch", f32::MAX as f64),
+                ]
+                .iter()
+                .map(|(s, v)| (s.to_string(), llr_Expression::NumberLiteral(*v)))
+                .collect::<HashMap<_, _>>();
+                for (nr, s) in p.constraints.for_each_restrictions(orientation) {
+                    values.insert(
+                        s.into(),
+                        llr_Expression::PropertyReference(ctx.map_property_reference(nr)),
+                    );
+                }
+                make_struct(
+                    "GridLayoutCellData".into(),
+                    [
+                        ("constraint", ty.clone(), llr_Expression::Struct { ty, values }),
+                        ("col_or_row", Type::Int32, llr_Expression::NumberLiteral(p.index as _)),
+                        ("span", Type::Int32, llr_Expression::NumberLiteral(0.)),
+                    ],
+                )
+            }))
             .collect(),
         as_model: false,
     }
@@ -765,6 +816,7 @@ pub(super) fn grid_layout_cell_data_ty() -> Type {
         .collect(),
         name: Some("GridLayoutCellData".into()),
         node: None,
+        defaults: Default::default(),
     }
 }
 
@@ -861,6 +913,7 @@ fn llr_path_elements(elements: Vec<llr_Expression>) -> llr_Expression {
                     fields: Default::default(),
                     name: Some("PathElement".to_owned()),
                     node: None,
+                    defaults: Default::default(),
                 },
                 values: elements,
                 as_model: false,
@@ -884,6 +937,7 @@ fn llr_path_elements(elements: Vec<llr_Expression>) -> llr_Expression {
                             .collect(),
                         name: element.element_type.native_class.cpp_type.clone(),
                         node: None,
+                        defaults: Default::default(),
                     };
 
                     llr_Expression::Struct {
@@ -935,6 +989,7 @@ fn llr_path_elements(elements: Vec<llr_Expression>) -> llr_Expression {
                         .collect(),
                         name: None,
                         node: None,
+                        defaults: Default::default(),
                     },
                     values: IntoIterator::into_iter([
                         (
@@ -978,5 +1033,8 @@ fn make_struct(
         values.insert(name.to_string(), expr);
     }
 
-    llr_Expression::Struct { ty: Type::Struct { fields, name: Some(name), node: None }, values }
+    llr_Expression::Struct {
+        ty: Type::Struct { fields, name: Some(name), node: None, defaults: Default::default() },
+        values,
+    }
 }
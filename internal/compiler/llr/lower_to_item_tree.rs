@@ -7,7 +7,7 @@
 use crate::langtype::Type;
 use crate::llr::item_tree::*;
 use crate::namedreference::NamedReference;
-use crate::object_tree::{Component, ElementRc};
+use crate::object_tree::{Component, ElementRc, PropertyVisibility};
 use std::collections::HashMap;
 use std::rc::Rc;
 
@@ -46,6 +46,7 @@ pub fn lower_to_item_tree(component: &Rc<Component>) -> PublicComponent {
             })
             .collect(),
         public_properties,
+        doc: component.doc_comment(),
     };
     super::optim_passes::run_passes(&root);
     root
@@ -188,9 +189,11 @@ fn lower_sub_component(
         two_way_bindings: Default::default(),
         const_properties: Default::default(),
         init_code: Default::default(),
+        change_callbacks: Default::default(),
         // just initialize to dummy expression right now and it will be set later
         layout_info_h: super::Expression::BoolLiteral(false).into(),
         layout_info_v: super::Expression::BoolLiteral(false).into(),
+        accessible_prop: Default::default(),
         prop_analysis: Default::default(),
     };
     let mut mapping = LoweredSubComponentMapping::default();
@@ -366,6 +369,15 @@ fn lower_sub_component(
         .map(|e| super::lower_expression::lower_expression(e, &ctx).into())
         .collect();
 
+    crate::object_tree::recurse_elem(&component.root_element, &(), &mut |element, _| {
+        for (nr, expr) in &element.borrow().change_callbacks {
+            sub_component.change_callbacks.push((
+                ctx.map_property_reference(nr),
+                super::lower_expression::lower_expression(expr, &ctx).into(),
+            ));
+        }
+    });
+
     sub_component.layout_info_h = super::lower_expression::get_layout_info(
         &component.root_element,
         &ctx,
@@ -381,6 +393,43 @@ fn lower_sub_component(
     )
     .into();
 
+    crate::object_tree::recurse_elem(&component.root_element, &(), &mut |element, _| {
+        let elem = element.borrow();
+        if elem.repeated.is_some() {
+            return;
+        };
+        let item_index = *elem.item_index.get().unwrap() as u32;
+        for (p, nr) in elem.accessibility_props.0.iter() {
+            // `accessible-value-maximum` -> `ValueMaximum`
+            let key = p
+                .strip_prefix("accessible-")
+                .unwrap()
+                .split('-')
+                .map(|word| word[..1].to_uppercase() + &word[1..])
+                .collect::<String>();
+            let prop = ctx.map_property_reference(nr);
+            let value = match nr.ty() {
+                Type::Bool => super::Expression::Condition {
+                    condition: super::Expression::PropertyReference(prop).into(),
+                    true_expr: super::Expression::StringLiteral("true".into()).into(),
+                    false_expr: super::Expression::StringLiteral("false".into()).into(),
+                },
+                Type::Float32 => super::Expression::Cast {
+                    from: super::Expression::PropertyReference(prop).into(),
+                    to: Type::String,
+                },
+                Type::Callback { args, .. } => super::Expression::CallBackCall {
+                    callback: prop,
+                    arguments: (0..args.len())
+                        .map(|index| super::Expression::FunctionParameterReference { index })
+                        .collect(),
+                },
+                _ => super::Expression::PropertyReference(prop),
+            };
+            sub_component.accessible_prop.insert((item_index, key), value.into());
+        }
+    });
+
     LoweredSubComponent { sub_component: Rc::new(sub_component), mapping }
 }
 
@@ -426,6 +475,7 @@ fn lower_repeated_component(elem: &ElementRc, ctx: &ExpressionContext) -> Repeat
         viewport_width: ctx.map_property_reference(&lv.viewport_width),
         listview_height: ctx.map_property_reference(&lv.listview_height),
         listview_width: ctx.map_property_reference(&lv.listview_width),
+        first_visible_item: ctx.map_property_reference(&lv.first_visible_item),
 
         prop_y: map_inner_prop("y"),
         prop_width: map_inner_prop("width"),
@@ -568,6 +618,7 @@ fn lower_global(
         exported: !global.exported_global_names.borrow().is_empty(),
         aliases: global.global_aliases(),
         is_builtin,
+        doc: global.doc_comment(),
         prop_analysis,
     }
 }
@@ -626,7 +677,15 @@ fn public_properties(
         .map(|(p, c)| {
             let property_reference = mapping
                 .map_property_reference(&NamedReference::new(&component.root_element, p), state);
-            (p.clone(), (c.property_type.clone(), property_reference))
+            (
+                p.clone(),
+                PublicProperty {
+                    ty: c.property_type.clone(),
+                    prop: property_reference,
+                    read_only: c.visibility == PropertyVisibility::Output,
+                    doc: c.doc_comment(),
+                },
+            )
         })
         .collect()
 }
@@ -67,6 +67,9 @@ fn callback_cost(_callback: &crate::llr::PropertyReference, _ctx: &EvaluationCon
 fn builtin_function_cost(function: BuiltinFunction) -> isize {
     match function {
         BuiltinFunction::GetWindowScaleFactor => PROPERTY_ACCESS_COST,
+        BuiltinFunction::GetWindowWidth
+        | BuiltinFunction::GetWindowHeight
+        | BuiltinFunction::GetWindowDefaultFontSize => PROPERTY_ACCESS_COST,
         BuiltinFunction::Debug => isize::MAX,
         BuiltinFunction::Mod => 10,
         BuiltinFunction::Round => 10,
@@ -74,6 +77,7 @@ fn builtin_function_cost(function: BuiltinFunction) -> isize {
         BuiltinFunction::Floor => 10,
         BuiltinFunction::Abs => 10,
         BuiltinFunction::Sqrt => 10,
+        BuiltinFunction::Snap => PROPERTY_ACCESS_COST,
         BuiltinFunction::Cos => 10,
         BuiltinFunction::Sin => 10,
         BuiltinFunction::Tan => 10,
@@ -84,10 +88,30 @@ fn builtin_function_cost(function: BuiltinFunction) -> isize {
         BuiltinFunction::Pow => 10,
         BuiltinFunction::SetFocusItem => isize::MAX,
         BuiltinFunction::ShowPopupWindow => isize::MAX,
+        BuiltinFunction::ScrollTo => isize::MAX,
+        BuiltinFunction::EnsureVisible => isize::MAX,
+        BuiltinFunction::TextInputUndo => isize::MAX,
+        BuiltinFunction::TextInputRedo => isize::MAX,
+        BuiltinFunction::TextInputFindNext => isize::MAX,
+        BuiltinFunction::TextInputFindPrevious => isize::MAX,
+        BuiltinFunction::TextInputSearchMatchCount => 50,
+        BuiltinFunction::TextInputCurrentMatch => 50,
         BuiltinFunction::StringToFloat => 50,
         BuiltinFunction::StringIsFloat => 50,
+        BuiltinFunction::StringFormatFloat => 50,
+        BuiltinFunction::StringPad => 50,
         BuiltinFunction::ColorBrighter => 50,
         BuiltinFunction::ColorDarker => 50,
+        BuiltinFunction::ColorLighter => 50,
+        BuiltinFunction::ColorSaturate => 50,
+        BuiltinFunction::ColorWithAlpha => 50,
+        BuiltinFunction::ColorMix => 50,
+        BuiltinFunction::FormatNumber => 50,
+        BuiltinFunction::FormatPercent => 50,
+        BuiltinFunction::FormatDate => 50,
+        BuiltinFunction::FormatTime => 50,
+        BuiltinFunction::GetColorScheme => PROPERTY_ACCESS_COST,
+        BuiltinFunction::AnimationTick => PROPERTY_ACCESS_COST,
         BuiltinFunction::ImageSize => 50,
         BuiltinFunction::ArrayLength => 50,
         BuiltinFunction::Rgb => 50,
@@ -82,6 +82,7 @@ pub enum BuiltinNamespace {
     Colors,
     Math,
     Keys,
+    Platform,
 }
 
 impl From<Expression> for LookupResult {
@@ -144,6 +145,9 @@ fn for_each_entry<R>(
             }
             LookupResult::Namespace(BuiltinNamespace::Math) => MathFunctions.for_each_entry(ctx, f),
             LookupResult::Namespace(BuiltinNamespace::Keys) => KeysLookup.for_each_entry(ctx, f),
+            LookupResult::Namespace(BuiltinNamespace::Platform) => {
+                PlatformLookup.for_each_entry(ctx, f)
+            }
         }
     }
 
@@ -156,6 +160,7 @@ fn lookup(&self, ctx: &LookupCtx, name: &str) -> Option<LookupResult> {
             }
             LookupResult::Namespace(BuiltinNamespace::Math) => MathFunctions.lookup(ctx, name),
             LookupResult::Namespace(BuiltinNamespace::Keys) => KeysLookup.lookup(ctx, name),
+            LookupResult::Namespace(BuiltinNamespace::Platform) => PlatformLookup.lookup(ctx, name),
         }
     }
 }
@@ -269,6 +274,14 @@ fn for_each_entry<R>(
             if let Some(r) = elem.for_each_entry(ctx, f) {
                 return Some(r);
             }
+
+            for name in ["is-first", "is-last"] {
+                if let Some(e) = repeater_position_expression(elem, name) {
+                    if let Some(r) = f(name, e.into()) {
+                        return Some(r);
+                    }
+                }
+            }
         }
         None
     }
@@ -294,11 +307,50 @@ fn lookup(&self, ctx: &LookupCtx, name: &str) -> Option<LookupResult> {
             if let Some(r) = elem.lookup(ctx, name) {
                 return Some(r);
             }
+
+            if let Some(e) = repeater_position_expression(elem, name) {
+                return Some(e.into());
+            }
         }
         None
     }
 }
 
+/// Returns the expression for `is-first` or `is-last` within the delegate of a `for`
+fn repeater_position_expression(elem: &ElementRc, name: &str) -> Option<Expression> {
+    let elem_ref = elem.borrow();
+    let repeated = elem_ref.repeated.as_ref().filter(|r| !r.is_conditional_element)?;
+    let position = match name {
+        "is-first" => Expression::NumberLiteral(0., Unit::None),
+        "is-last" => {
+            let row_count = match repeated.model.ty() {
+                Type::Int32 | Type::Float32 => {
+                    Expression::Cast { from: Box::new(repeated.model.clone()), to: Type::Int32 }
+                }
+                _ => Expression::FunctionCall {
+                    function: Box::new(Expression::BuiltinFunctionReference(
+                        BuiltinFunction::ArrayLength,
+                        None,
+                    )),
+                    arguments: vec![repeated.model.clone()],
+                    source_location: None,
+                },
+            };
+            Expression::BinaryExpression {
+                lhs: Box::new(row_count),
+                rhs: Box::new(Expression::NumberLiteral(1., Unit::None)),
+                op: '-',
+            }
+        }
+        _ => return None,
+    };
+    Some(Expression::BinaryExpression {
+        lhs: Box::new(Expression::RepeaterIndexReference { element: Rc::downgrade(elem) }),
+        rhs: Box::new(position),
+        op: '=',
+    })
+}
+
 impl LookupObject for ElementRc {
     fn for_each_entry<R>(
         &self,
@@ -348,6 +400,27 @@ fn expression_from_reference(n: NamedReference, ty: &Type) -> Expression {
     }
 }
 
+/// Lookup for the constants declared with `const`
+struct ConstantLookup;
+impl LookupObject for ConstantLookup {
+    fn for_each_entry<R>(
+        &self,
+        ctx: &LookupCtx,
+        f: &mut impl FnMut(&str, LookupResult) -> Option<R>,
+    ) -> Option<R> {
+        for (name, value) in ctx.type_register.all_constants() {
+            if let Some(r) = f(&name, value.into()) {
+                return Some(r);
+            }
+        }
+        None
+    }
+
+    fn lookup(&self, ctx: &LookupCtx, name: &str) -> Option<LookupResult> {
+        ctx.type_register.lookup_constant(name).map(LookupResult::from)
+    }
+}
+
 /// Lookup for Globals and Enum.
 /// Note: for enums, the expression's value is `usize::MAX`
 struct LookupType;
@@ -539,6 +612,12 @@ fn for_each_entry<R>(
             .or_else(|| f("atan", BuiltinFunctionReference(BuiltinFunction::ATan, sl())))
             .or_else(|| f("log", BuiltinFunctionReference(BuiltinFunction::Log, sl())))
             .or_else(|| f("pow", BuiltinFunctionReference(BuiltinFunction::Pow, sl())))
+            .or_else(|| f("clamp", BuiltinMacroReference(BuiltinMacroFunction::Clamp, t.clone())))
+            .or_else(|| f("lerp", BuiltinMacroReference(BuiltinMacroFunction::Lerp, t.clone())))
+            .or_else(|| {
+                f("map-range", BuiltinMacroReference(BuiltinMacroFunction::MapRange, t.clone()))
+            })
+            .or_else(|| f("snap", BuiltinFunctionReference(BuiltinFunction::Snap, sl())))
     }
 }
 
@@ -549,34 +628,94 @@ fn for_each_entry<R>(
         ctx: &LookupCtx,
         f: &mut impl FnMut(&str, LookupResult) -> Option<R>,
     ) -> Option<R> {
-        use Expression::BuiltinMacroReference;
+        use Expression::{BuiltinFunctionReference, BuiltinMacroReference};
         let t = &ctx.current_token;
+        let sl = || t.as_ref().map(|t| t.to_source_location());
         let mut f = |n, e: Expression| f(n, e.into());
         None.or_else(|| f("rgb", BuiltinMacroReference(BuiltinMacroFunction::Rgb, t.clone())))
             .or_else(|| f("rgba", BuiltinMacroReference(BuiltinMacroFunction::Rgb, t.clone())))
+            .or_else(|| f("mix", BuiltinFunctionReference(BuiltinFunction::ColorMix, sl())))
     }
 }
 
-struct BuiltinFunctionLookup;
-impl LookupObject for BuiltinFunctionLookup {
+struct FormatFunctions;
+impl LookupObject for FormatFunctions {
     fn for_each_entry<R>(
         &self,
         ctx: &LookupCtx,
         f: &mut impl FnMut(&str, LookupResult) -> Option<R>,
     ) -> Option<R> {
-        (MathFunctions, ColorFunctions).for_each_entry(ctx, f).or_else(|| {
+        use Expression::BuiltinFunctionReference;
+        let sl = || ctx.current_token.as_ref().map(|t| t.to_source_location());
+        let mut f = |n, e: Expression| f(n, e.into());
+        None.or_else(|| {
+            f("format-number", BuiltinFunctionReference(BuiltinFunction::FormatNumber, sl()))
+        })
+        .or_else(|| {
+            f("format-percent", BuiltinFunctionReference(BuiltinFunction::FormatPercent, sl()))
+        })
+        .or_else(|| f("format-date", BuiltinFunctionReference(BuiltinFunction::FormatDate, sl())))
+        .or_else(|| f("format-time", BuiltinFunctionReference(BuiltinFunction::FormatTime, sl())))
+    }
+}
+
+struct PlatformLookup;
+impl LookupObject for PlatformLookup {
+    fn for_each_entry<R>(
+        &self,
+        ctx: &LookupCtx,
+        f: &mut impl FnMut(&str, LookupResult) -> Option<R>,
+    ) -> Option<R> {
+        let sl = || ctx.current_token.as_ref().map(|t| t.to_source_location());
+        let mut f = |n, e: Expression| f(n, e.into());
+        None.or_else(|| {
             f(
-                "debug",
-                Expression::BuiltinMacroReference(
-                    BuiltinMacroFunction::Debug,
-                    ctx.current_token.clone(),
-                )
-                .into(),
+                "color-scheme",
+                Expression::FunctionCall {
+                    function: Box::new(Expression::BuiltinFunctionReference(
+                        BuiltinFunction::GetColorScheme,
+                        sl(),
+                    )),
+                    arguments: vec![],
+                    source_location: sl(),
+                },
             )
         })
     }
 }
 
+struct BuiltinFunctionLookup;
+impl LookupObject for BuiltinFunctionLookup {
+    fn for_each_entry<R>(
+        &self,
+        ctx: &LookupCtx,
+        f: &mut impl FnMut(&str, LookupResult) -> Option<R>,
+    ) -> Option<R> {
+        (MathFunctions, (ColorFunctions, FormatFunctions))
+            .for_each_entry(ctx, f)
+            .or_else(|| {
+                f(
+                    "debug",
+                    Expression::BuiltinMacroReference(
+                        BuiltinMacroFunction::Debug,
+                        ctx.current_token.clone(),
+                    )
+                    .into(),
+                )
+            })
+            .or_else(|| {
+                f(
+                    "animation-tick",
+                    Expression::BuiltinFunctionReference(
+                        BuiltinFunction::AnimationTick,
+                        ctx.current_token.as_ref().map(|t| t.to_source_location()),
+                    )
+                    .into(),
+                )
+            })
+    }
+}
+
 struct BuiltinNamespaceLookup;
 impl LookupObject for BuiltinNamespaceLookup {
     fn for_each_entry<R>(
@@ -587,6 +726,7 @@ fn for_each_entry<R>(
         None.or_else(|| f("Colors", LookupResult::Namespace(BuiltinNamespace::Colors)))
             .or_else(|| f("Math", LookupResult::Namespace(BuiltinNamespace::Math)))
             .or_else(|| f("Keys", LookupResult::Namespace(BuiltinNamespace::Keys)))
+            .or_else(|| f("Platform", LookupResult::Namespace(BuiltinNamespace::Platform)))
     }
 }
 
@@ -600,8 +740,14 @@ pub fn global_lookup() -> impl LookupObject {
                 (
                     InScopeLookup,
                     (
-                        LookupType,
-                        (BuiltinNamespaceLookup, (ReturnTypeSpecificLookup, BuiltinFunctionLookup)),
+                        ConstantLookup,
+                        (
+                            LookupType,
+                            (
+                                BuiltinNamespaceLookup,
+                                (ReturnTypeSpecificLookup, BuiltinFunctionLookup),
+                            ),
+                        ),
                     ),
                 ),
             ),
@@ -681,8 +827,16 @@ fn for_each_entry<R>(
                 )),
             })
         };
+        let member_macro = |m: BuiltinMacroFunction| {
+            LookupResult::from(Expression::MemberFunction {
+                base: Box::new(self.0.clone()),
+                base_node: ctx.current_token.clone(),
+                member: Box::new(Expression::BuiltinMacroReference(m, ctx.current_token.clone())),
+            })
+        };
         None.or_else(|| f("is-float", member_function(BuiltinFunction::StringIsFloat)))
             .or_else(|| f("to-float", member_function(BuiltinFunction::StringToFloat)))
+            .or_else(|| f("format", member_macro(BuiltinMacroFunction::Format)))
     }
 }
 struct ColorExpression<'a>(&'a Expression);
@@ -704,6 +858,10 @@ fn for_each_entry<R>(
         };
         None.or_else(|| f("brighter", member_function(BuiltinFunction::ColorBrighter)))
             .or_else(|| f("darker", member_function(BuiltinFunction::ColorDarker)))
+            .or_else(|| f("lighter", member_function(BuiltinFunction::ColorLighter)))
+            .or_else(|| f("saturate", member_function(BuiltinFunction::ColorSaturate)))
+            .or_else(|| f("with-alpha", member_function(BuiltinFunction::ColorWithAlpha)))
+            .or_else(|| f("mix", member_function(BuiltinFunction::ColorMix)))
     }
 }
 
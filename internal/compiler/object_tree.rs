@@ -10,7 +10,7 @@
 use crate::diagnostics::{BuildDiagnostics, SourceLocation, Spanned};
 use crate::expression_tree::{self, BindingExpression, Expression, Unit};
 use crate::langtype::PropertyLookupResult;
-use crate::langtype::{BuiltinElement, NativeClass, Type};
+use crate::langtype::{BuiltinElement, Enumeration, NativeClass, Type};
 use crate::layout::{LayoutConstraints, Orientation};
 use crate::namedreference::NamedReference;
 use crate::parser;
@@ -40,6 +40,7 @@ pub struct Document {
     pub node: Option<syntax_nodes::Document>,
     pub inner_components: Vec<Rc<Component>>,
     pub inner_structs: Vec<Type>,
+    pub inner_enums: Vec<Rc<Enumeration>>,
     pub root_component: Rc<Component>,
     pub local_registry: TypeRegister,
     /// A list of paths to .ttf/.ttc files that are supposed to be registered on
@@ -60,6 +61,7 @@ pub fn from_node(
         let mut local_registry = TypeRegister::new(parent_registry);
         let mut inner_components = vec![];
         let mut inner_structs = vec![];
+        let mut inner_enums = vec![];
 
         let mut process_component =
             |n: syntax_nodes::Component,
@@ -74,7 +76,13 @@ pub fn from_node(
              diag: &mut BuildDiagnostics,
              local_registry: &mut TypeRegister| {
                 let mut ty = type_struct_from_node(n.ObjectType(), diag, local_registry);
-                if let Type::Struct { name, .. } = &mut ty {
+                if let Type::Struct { name, fields, defaults, .. } = &mut ty {
+                    *defaults = resolve_struct_field_defaults(
+                        &n.ObjectType(),
+                        fields,
+                        local_registry,
+                        diag,
+                    );
                     *name = parser::identifier_text(&n.DeclaredIdentifier());
                 } else {
                     assert!(diag.has_error());
@@ -83,6 +91,78 @@ pub fn from_node(
                 local_registry.insert_type(ty.clone());
                 inner_structs.push(ty);
             };
+        let mut process_enum = |n: syntax_nodes::EnumDeclaration,
+                                diag: &mut BuildDiagnostics,
+                                local_registry: &mut TypeRegister| {
+            let name = match parser::identifier_text(&n.DeclaredIdentifier()) {
+                Some(name) => name,
+                None => {
+                    assert!(diag.has_error());
+                    return;
+                }
+            };
+            let mut values: Vec<String> = vec![];
+            for v in n.EnumValue() {
+                let value = match parser::identifier_text(&v) {
+                    Some(value) => value,
+                    None => continue,
+                };
+                if values.contains(&value) {
+                    diag.push_error(format!("Duplicated enum value '{}'", value), &v);
+                    continue;
+                }
+                values.push(value);
+            }
+            if values.is_empty() {
+                diag.push_error(
+                    format!("Enum '{}' must have at least one value", name),
+                    &n.DeclaredIdentifier(),
+                );
+                return;
+            }
+            let e = Rc::new(Enumeration {
+                name: name.clone(),
+                values,
+                default_value: 0,
+                node: Some(n),
+            });
+            local_registry.insert_type_with_name(Type::Enumeration(e.clone()), name);
+            inner_enums.push(e);
+        };
+        let process_type_alias =
+            |n: syntax_nodes::TypeAlias,
+             diag: &mut BuildDiagnostics,
+             local_registry: &mut TypeRegister| {
+                let name = match parser::identifier_text(&n.DeclaredIdentifier()) {
+                    Some(name) => name,
+                    None => {
+                        assert!(diag.has_error());
+                        return;
+                    }
+                };
+                let ty = type_from_node(n.Type(), diag, local_registry);
+                if ty != Type::Invalid {
+                    local_registry.insert_type_with_name(ty, name);
+                }
+            };
+        let process_constant = |n: syntax_nodes::ConstantDeclaration,
+                                diag: &mut BuildDiagnostics,
+                                local_registry: &mut TypeRegister| {
+            let name = match parser::identifier_text(&n.DeclaredIdentifier()) {
+                Some(name) => name,
+                None => {
+                    assert!(diag.has_error());
+                    return;
+                }
+            };
+            if local_registry.lookup_constant(&name).is_some() {
+                diag.push_error(format!("Duplicated constant '{}'", name), &n.DeclaredIdentifier());
+            }
+            let ty = type_from_node(n.Type(), diag, local_registry);
+            let value =
+                resolve_constant(&n, ty, local_registry, diag).unwrap_or(Expression::Invalid);
+            local_registry.insert_constant(name, value);
+        };
 
         for n in node.children() {
             match n.kind() {
@@ -90,6 +170,11 @@ pub fn from_node(
                 SyntaxKind::StructDeclaration => {
                     process_struct(n.into(), diag, &mut local_registry)
                 }
+                SyntaxKind::EnumDeclaration => process_enum(n.into(), diag, &mut local_registry),
+                SyntaxKind::TypeAlias => process_type_alias(n.into(), diag, &mut local_registry),
+                SyntaxKind::ConstantDeclaration => {
+                    process_constant(n.into(), diag, &mut local_registry)
+                }
                 SyntaxKind::ExportsList => {
                     for n in n.children() {
                         match n.kind() {
@@ -99,6 +184,15 @@ pub fn from_node(
                             SyntaxKind::StructDeclaration => {
                                 process_struct(n.into(), diag, &mut local_registry)
                             }
+                            SyntaxKind::EnumDeclaration => {
+                                process_enum(n.into(), diag, &mut local_registry)
+                            }
+                            SyntaxKind::TypeAlias => {
+                                process_type_alias(n.into(), diag, &mut local_registry)
+                            }
+                            SyntaxKind::ConstantDeclaration => {
+                                process_constant(n.into(), diag, &mut local_registry)
+                            }
                             _ => {}
                         }
                     }
@@ -162,6 +256,7 @@ pub fn from_node(
             root_component,
             inner_components,
             inner_structs,
+            inner_enums,
             local_registry,
             custom_fonts,
             exports,
@@ -169,7 +264,12 @@ pub fn from_node(
     }
 
     pub fn exports(&self) -> &Vec<(ExportedName, Type)> {
-        &self.exports.0
+        &self.exports.types
+    }
+
+    /// The constants exported from this document, with their value
+    pub fn exported_constants(&self) -> &Vec<(ExportedName, Expression)> {
+        &self.exports.constants
     }
 }
 
@@ -290,6 +390,15 @@ pub fn visible_in_public_api(&self) -> bool {
         }
     }
 
+    /// Returns the `///` comments of the component declaration, for the generated documentation
+    pub fn doc_comment(&self) -> Option<String> {
+        let node = self.root_element.borrow().node.as_ref()?.parent()?;
+        if node.kind() != SyntaxKind::Component {
+            return None;
+        }
+        crate::parser::doc_comment(&node)
+    }
+
     /// Returns the names of aliases to global singletons, exactly as
     /// specified in the .slint markup (not normalized).
     pub fn global_aliases(&self) -> Vec<String> {
@@ -322,14 +431,73 @@ pub fn repeater_count(&self) -> u32 {
     }
 }
 
+/// The visibility of a property declared with `private property`, `in property`, ...
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PropertyVisibility {
+    /// Only accessible from the component declaring it
+    Private,
+    /// Only accessible from the component declaring it, and from the components inheriting from it
+    Protected,
+    /// Can be set and read from outside, but cannot be assigned from within the component
+    Input,
+    /// Can be read from outside, but only set from within the component
+    Output,
+    /// Can be read and set from everywhere. This is the default
+    InOut,
+}
+
+impl Default for PropertyVisibility {
+    fn default() -> Self {
+        Self::InOut
+    }
+}
+
+impl PropertyVisibility {
+    /// Parses the keyword before `property` in a property declaration
+    pub fn from_keyword(keyword: &str) -> Option<Self> {
+        match keyword {
+            "private" => Some(Self::Private),
+            "protected" => Some(Self::Protected),
+            "in" => Some(Self::Input),
+            "out" => Some(Self::Output),
+            "in-out" | "in_out" => Some(Self::InOut),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the property is part of the interface of the component
+    pub fn is_public(self) -> bool {
+        !matches!(self, Self::Private | Self::Protected)
+    }
+}
+
+impl std::fmt::Display for PropertyVisibility {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Private => "private",
+            Self::Protected => "protected",
+            Self::Input => "input",
+            Self::Output => "output",
+            Self::InOut => "in-out",
+        })
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct PropertyDeclaration {
     pub property_type: Type,
-    pub node: Option<Either<syntax_nodes::PropertyDeclaration, syntax_nodes::CallbackDeclaration>>,
+    pub node: Option<
+        Either<
+            syntax_nodes::PropertyDeclaration,
+            Either<syntax_nodes::CallbackDeclaration, syntax_nodes::Function>,
+        >,
+    >,
     /// Tells if getter and setter will be added to expose in the native language API
     pub expose_in_public_api: bool,
     /// Public API property exposed as an alias: it shouldn't be generated but instead forward to the alias.
     pub is_alias: Option<NamedReference>,
+    /// Who can read and set this property
+    pub visibility: PropertyVisibility,
 }
 
 impl PropertyDeclaration {
@@ -338,10 +506,22 @@ pub fn type_node(&self) -> Option<SyntaxNode> {
         self.node.as_ref().map(|x| -> crate::parser::SyntaxNode {
             x.as_ref().either(
                 |x| x.Type().map_or_else(|| x.clone().into(), |x| x.into()),
-                |x| x.clone().into(),
+                |x| (**x).clone(),
             )
         })
     }
+
+    /// Returns the `///` comments of the declaration, for the generated documentation
+    pub fn doc_comment(&self) -> Option<String> {
+        let node: SyntaxNode =
+            self.node.as_ref()?.as_ref().either(|x| (**x).clone(), |x| (**x).clone());
+        crate::parser::doc_comment(&node)
+    }
+
+    /// Returns true if this declares a function (a callback with a fixed handler that cannot be set from outside)
+    pub fn is_function(&self) -> bool {
+        matches!(&self.node, Some(Either::Right(Either::Right(_))))
+    }
 }
 
 impl From<Type> for PropertyDeclaration {
@@ -420,6 +600,9 @@ fn deep_clone(e: &ElementRc) -> ElementRc {
 
 pub type BindingsMap = BTreeMap<String, RefCell<BindingExpression>>;
 
+#[derive(Debug, Clone, Default)]
+pub struct AccessibilityProps(pub BTreeMap<String, NamedReference>);
+
 /// An Element is an instantiation of a Component
 #[derive(Default)]
 pub struct Element {
@@ -450,11 +633,19 @@ pub struct Element {
     pub states: Vec<State>,
     pub transitions: Vec<Transition>,
 
+    /// Code that runs when the component is initialized, and then every time the value of the
+    /// property changes, before the code that changed it continues. Generated by the lower_states pass.
+    pub change_callbacks: Vec<(NamedReference, Expression)>,
+
     /// true when this item's geometry is handled by a layout
     pub child_of_layout: bool,
     /// The property pointing to the layout info. `(horizontal, vertical)`
     pub layout_info_prop: Option<(NamedReference, NamedReference)>,
 
+    /// The accessible properties (`accessible-role`, `accessible-label`, ...) set on this element.
+    /// The key is the property name. Filled by the lower_accessibility pass.
+    pub accessibility_props: AccessibilityProps,
+
     /// true if this Element is the fake Flickable viewport
     pub is_flickable_viewport: bool,
 
@@ -545,6 +736,12 @@ macro_rules! indent {
         indent!();
         writeln!(f, "transitions {:?} ", e.transitions)?;
     }
+    for (nr, expr) in &e.change_callbacks {
+        indent!();
+        write!(f, "changed {:?} => ", nr)?;
+        expression_tree::pretty_print(f, expr)?;
+        writeln!(f)?;
+    }
     for c in &e.children {
         indent!();
         pretty_print(f, &c.borrow(), indentation)?
@@ -607,6 +804,8 @@ pub struct ListViewInfo {
     pub listview_height: NamedReference,
     /// The ListView's inner visible width (not counting eventual scrollbar)
     pub listview_width: NamedReference,
+    /// The first visible row of the ListView, set by the runtime and by the application to scroll
+    pub first_visible_item: NamedReference,
 }
 
 #[derive(Debug, Clone)]
@@ -691,18 +890,28 @@ pub fn from_node(
                 resolved_name: prop_name,
                 property_type: maybe_existing_prop_type,
             } = r.lookup_property(&unresolved_prop_name);
-            if !matches!(maybe_existing_prop_type, Type::Invalid) {
+            // The declarations shadow the lifecycle callbacks of the elements
+            let shadows_lifecycle_callback =
+                !r.property_declarations.contains_key(prop_name.as_ref())
+                    && crate::typeregister::is_lifecycle_callback(&r.base_type, &prop_name);
+            if !matches!(maybe_existing_prop_type, Type::Invalid) && !shadows_lifecycle_callback {
                 diag.push_error(
                     format!("Cannot override property '{}'", prop_name),
                     &prop_decl.DeclaredIdentifier().child_token(SyntaxKind::Identifier).unwrap(),
                 )
             }
 
+            let visibility = prop_decl
+                .child_token(SyntaxKind::Identifier)
+                .and_then(|t| PropertyVisibility::from_keyword(t.text()))
+                .unwrap_or_default();
+
             r.property_declarations.insert(
                 prop_name.to_string(),
                 PropertyDeclaration {
                     property_type: prop_type,
                     node: Some(Either::Left(prop_decl.clone())),
+                    visibility,
                     ..Default::default()
                 },
             );
@@ -759,7 +968,7 @@ pub fn from_node(
                     name,
                     PropertyDeclaration {
                         property_type: Type::InferredCallback,
-                        node: Some(Either::Right(sig_decl)),
+                        node: Some(Either::Right(Either::Left(sig_decl))),
                         ..Default::default()
                     },
                 );
@@ -774,7 +983,41 @@ pub fn from_node(
                 name,
                 PropertyDeclaration {
                     property_type: Type::Callback { return_type, args },
-                    node: Some(Either::Right(sig_decl)),
+                    node: Some(Either::Right(Either::Left(sig_decl))),
+                    ..Default::default()
+                },
+            );
+        }
+
+        for func in node.Function() {
+            let name =
+                unwrap_or_continue!(parser::identifier_text(&func.DeclaredIdentifier()); diag);
+            let shadows_lifecycle_callback = !r.property_declarations.contains_key(&name)
+                && crate::typeregister::is_lifecycle_callback(&r.base_type, &name);
+            if r.lookup_property(&name).property_type != Type::Invalid
+                && !shadows_lifecycle_callback
+            {
+                diag.push_error(
+                    format!("Cannot declare function '{}' because it already exists", name),
+                    &func.DeclaredIdentifier(),
+                );
+                continue;
+            }
+            let args = func
+                .ArgumentDeclaration()
+                .map(|arg| type_from_node(arg.Type(), diag, tr))
+                .collect();
+            let return_type =
+                func.ReturnType().map(|ret_ty| Box::new(type_from_node(ret_ty.Type(), diag, tr)));
+            r.bindings.insert(
+                name.clone(),
+                BindingExpression::new_uncompiled(func.clone().into()).into(),
+            );
+            r.property_declarations.insert(
+                name,
+                PropertyDeclaration {
+                    property_type: Type::Callback { return_type, args },
+                    node: Some(Either::Right(Either::Right(func))),
                     ..Default::default()
                 },
             );
@@ -784,6 +1027,13 @@ pub fn from_node(
             let unresolved_name = unwrap_or_continue!(parser::identifier_text(&con_node); diag);
             let PropertyLookupResult { resolved_name, property_type } =
                 r.lookup_property(&unresolved_name);
+            if r.is_function(&resolved_name) {
+                diag.push_error(
+                    format!("Cannot override function '{}'", unresolved_name),
+                    &con_node.child_token(SyntaxKind::Identifier).unwrap(),
+                );
+                continue;
+            }
             if let Type::Callback { args, .. } = &property_type {
                 let num_arg = con_node.DeclaredIdentifier().count();
                 if num_arg > args.len() {
@@ -895,14 +1145,40 @@ pub fn from_node(
                 );
                 r.borrow_mut().children.push(rep);
             } else if se.kind() == SyntaxKind::ConditionalElement {
-                let rep = Element::from_conditional_node(
-                    se.into(),
-                    r.borrow().base_type.clone(),
-                    component_child_insertion_point,
-                    diag,
-                    tr,
-                );
-                r.borrow_mut().children.push(rep);
+                // Each branch of a `if ... else if ... else` becomes its own conditional element.
+                // The conditions of the previous branches are combined when resolving them.
+                let mut node: syntax_nodes::ConditionalElement = se.into();
+                loop {
+                    let rep = Element::from_conditional_node(
+                        Expression::Uncompiled(node.Expression().into()),
+                        node.SubElement(),
+                        r.borrow().base_type.clone(),
+                        component_child_insertion_point,
+                        diag,
+                        tr,
+                    );
+                    r.borrow_mut().children.push(rep);
+                    let else_node = match node.ConditionalElse() {
+                        Some(else_node) => else_node,
+                        None => break,
+                    };
+                    if let Some(else_if) = else_node.ConditionalElement() {
+                        node = else_if;
+                    } else if let Some(sub_element) = else_node.SubElement() {
+                        let rep = Element::from_conditional_node(
+                            Expression::BoolLiteral(true),
+                            sub_element,
+                            r.borrow().base_type.clone(),
+                            component_child_insertion_point,
+                            diag,
+                            tr,
+                        );
+                        r.borrow_mut().children.push(rep);
+                        break;
+                    } else {
+                        break;
+                    }
+                }
             } else if se.kind() == SyntaxKind::ChildrenPlaceholder {
                 if children_placeholder.is_some() {
                     diag.push_error(
@@ -926,20 +1202,8 @@ pub fn from_node(
             }
         }
 
-        for state in node.States().flat_map(|s| s.State()) {
-            let s = State {
-                id: parser::identifier_text(&state.DeclaredIdentifier()).unwrap_or_default(),
-                condition: state.Expression().map(|e| Expression::Uncompiled(e.into())),
-                property_changes: state
-                    .StatePropertyChange()
-                    .filter_map(|s| {
-                        lookup_property_from_qualified_name(s.QualifiedName(), &r, diag).map(
-                            |(ne, _)| (ne, Expression::Uncompiled(s.BindingExpression().into()), s),
-                        )
-                    })
-                    .collect(),
-            };
-            r.borrow_mut().states.push(s);
+        for states in node.States() {
+            collect_states(states, None, &r, diag);
         }
 
         for trs in node.Transitions().flat_map(|s| s.Transition()) {
@@ -1007,6 +1271,7 @@ fn from_repeated_node(
                 viewport_width: NamedReference::new(parent, "viewport-width"),
                 listview_height: NamedReference::new(parent, "visible-height"),
                 listview_width: NamedReference::new(parent, "visible-width"),
+                first_visible_item: NamedReference::new(parent, "first-visible-item"),
             })
         } else {
             None
@@ -1036,21 +1301,22 @@ fn from_repeated_node(
     }
 
     fn from_conditional_node(
-        node: syntax_nodes::ConditionalElement,
+        condition: Expression,
+        sub_element: syntax_nodes::SubElement,
         parent_type: Type,
         component_child_insertion_point: &mut Option<ChildrenInsertionPoint>,
         diag: &mut BuildDiagnostics,
         tr: &TypeRegister,
     ) -> ElementRc {
         let rei = RepeatedElementInfo {
-            model: Expression::Uncompiled(node.Expression().into()),
+            model: condition,
             model_data_id: String::new(),
             index_id: String::new(),
             is_conditional_element: true,
             is_listview: None,
         };
         let e = Element::from_sub_element_node(
-            node.SubElement(),
+            sub_element,
             parent_type,
             component_child_insertion_point,
             diag,
@@ -1070,6 +1336,44 @@ pub fn lookup_property<'a>(&self, name: &'a str) -> PropertyLookupResult<'a> {
         )
     }
 
+    /// Returns the names of the properties of this element and of its base, used to suggest
+    /// the right name when an unknown property is used
+    pub fn property_names(&self) -> Vec<String> {
+        self.base_type
+            .property_list()
+            .into_iter()
+            .chain(
+                self.property_declarations
+                    .iter()
+                    .map(|(name, decl)| (name.clone(), decl.property_type.clone())),
+            )
+            .filter(|(_, ty)| ty.is_property_type())
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    /// Returns true if `name` is a function declared in this element or in its base component
+    pub fn is_function(&self, name: &str) -> bool {
+        match self.property_declarations.get(name) {
+            Some(decl) => decl.is_function(),
+            None => match &self.base_type {
+                Type::Component(c) => c.root_element.borrow().is_function(name),
+                _ => false,
+            },
+        }
+    }
+
+    /// Returns the visibility of the property `name` declared in this element or in its base component
+    pub fn property_visibility(&self, name: &str) -> PropertyVisibility {
+        match self.property_declarations.get(name) {
+            Some(decl) => decl.visibility,
+            None => match &self.base_type {
+                Type::Component(c) => c.root_element.borrow().property_visibility(name),
+                _ => PropertyVisibility::InOut,
+            },
+        }
+    }
+
     /// Return the Span of this element in the AST for error reporting
     pub fn span(&self) -> crate::diagnostics::Span {
         self.node.as_ref().map(|n| n.span()).unwrap_or_default()
@@ -1084,19 +1388,18 @@ fn parse_bindings(
             let unresolved_name = crate::parser::normalize_identifier(name_token.text());
             let PropertyLookupResult { resolved_name, property_type } =
                 self.lookup_property(&unresolved_name);
-            if !property_type.is_property_type() {
+            if property_type == Type::Invalid {
+                if self.base_type == Type::Invalid {
+                    continue;
+                }
+                diag.push_error_with_suggestion(
+                    format!("Unknown property {} in {}", unresolved_name, self.base_type),
+                    &name_token,
+                    self.property_names(),
+                );
+            } else if !property_type.is_property_type() {
                 diag.push_error(
                     match property_type {
-                        Type::Invalid => {
-                            if self.base_type != Type::Invalid {
-                                format!(
-                                    "Unknown property {} in {}",
-                                    unresolved_name, self.base_type
-                                )
-                            } else {
-                                continue;
-                            }
-                        }
                         Type::Callback { .. } => {
                             format!("'{}' is a callback. Use `=>` to connect", unresolved_name)
                         }
@@ -1256,6 +1559,12 @@ pub fn type_from_node(
         }
         prop_type
     } else if let Some(object_node) = node.ObjectType() {
+        for default_value in object_node.ObjectTypeMember().filter_map(|m| m.Expression()) {
+            diag.push_error(
+                "Default values are only supported in struct declarations".into(),
+                &default_value,
+            );
+        }
         type_struct_from_node(object_node, diag, tr)
     } else if let Some(array_node) = node.ArrayType() {
         Type::Array(Box::new(type_from_node(array_node.Type(), diag, tr)))
@@ -1280,7 +1589,79 @@ pub fn type_struct_from_node(
             )
         })
         .collect();
-    Type::Struct { fields, name: None, node: Some(object_node) }
+    Type::Struct { fields, name: None, node: Some(object_node), defaults: Default::default() }
+}
+
+/// Resolve the default values of the fields of a struct declared with `foo: int = 42`.
+///
+/// The default values can only be constant expressions that do not refer to elements
+/// or globals, since they are evaluated wherever the struct is used.
+fn resolve_struct_field_defaults(
+    node: &syntax_nodes::ObjectType,
+    fields: &BTreeMap<String, Type>,
+    tr: &TypeRegister,
+    diag: &mut BuildDiagnostics,
+) -> BTreeMap<String, Expression> {
+    let mut result = BTreeMap::new();
+    for member in node.ObjectTypeMember() {
+        let expression_node = match member.Expression() {
+            Some(expression_node) => expression_node,
+            None => continue,
+        };
+        let name = parser::identifier_text(&member).unwrap_or_default();
+        let ty = match fields.get(&name) {
+            Some(ty) => ty.clone(),
+            None => continue,
+        };
+        let mut lookup_ctx = crate::lookup::LookupCtx::empty_context(tr, diag);
+        lookup_ctx.property_name = Some(name.as_str());
+        lookup_ctx.property_type = ty.clone();
+        let expression = Expression::from_expression_node(expression_node.clone(), &mut lookup_ctx)
+            .maybe_convert_to(ty, &expression_node, diag);
+        if !expression.is_constant() {
+            diag.push_error(
+                "The default value of a struct field must be a constant expression".into(),
+                &expression_node,
+            );
+            continue;
+        }
+        result.insert(name, expression);
+    }
+    result
+}
+
+/// Resolve the value of a constant declared with `const FOO: type = value;`
+///
+/// The value can only be a constant expression that may refer to other constants, and is
+/// inlined wherever the constant is used.
+fn resolve_constant(
+    node: &syntax_nodes::ConstantDeclaration,
+    ty: Type,
+    type_register: &TypeRegister,
+    diag: &mut BuildDiagnostics,
+) -> Option<Expression> {
+    let expression_node = node.Expression();
+    let mut lookup_ctx = crate::lookup::LookupCtx::empty_context(type_register, diag);
+    lookup_ctx.property_type = ty.clone();
+    let expression = Expression::from_expression_node(expression_node.clone(), &mut lookup_ctx)
+        .maybe_convert_to(ty, &expression_node, diag);
+    let mut refers_to_property = false;
+    expression.visit_recursive(&mut |e| {
+        refers_to_property |= matches!(
+            e,
+            Expression::PropertyReference(_)
+                | Expression::CallbackReference(_)
+                | Expression::ElementReference(_)
+        )
+    });
+    if refers_to_property || !expression.is_constant() {
+        diag.push_error(
+            "The value of a constant must be a constant expression".into(),
+            &expression_node,
+        );
+        return None;
+    }
+    Some(expression)
 }
 
 fn animation_element_from_node(
@@ -1340,6 +1721,120 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 }
 
 /// Return a NamedReference, if the reference is invalid, there will be a diagnostic
+/// Appends the states, and their nested states, to the states of the element `r`.
+/// A nested state comes after the state that contains it.
+fn collect_states(
+    states: syntax_nodes::States,
+    parent: Option<usize>,
+    r: &ElementRc,
+    diag: &mut BuildDiagnostics,
+) {
+    for state in states.State() {
+        let mut s = State {
+            id: parser::identifier_text(&state.DeclaredIdentifier()).unwrap_or_default(),
+            condition: state.Expression().map(|e| Expression::Uncompiled(e.into())),
+            property_changes: state
+                .StatePropertyChange()
+                .filter_map(|s| {
+                    lookup_property_from_qualified_name(s.QualifiedName(), r, diag).map(
+                        |(ne, _)| (ne, Expression::Uncompiled(s.BindingExpression().into()), s),
+                    )
+                })
+                .collect(),
+            parent,
+            entered: None,
+            exited: None,
+            triggers: state
+                .StateTrigger()
+                .filter_map(|trigger| {
+                    let event =
+                        lookup_callback_from_qualified_name(trigger.QualifiedName(), r, diag)?;
+                    Some(StateTrigger {
+                        event,
+                        guard: trigger.Expression().map(|e| Expression::Uncompiled(e.into())),
+                        target: parser::identifier_text(&trigger.DeclaredIdentifier())
+                            .unwrap_or_default(),
+                        node: trigger,
+                    })
+                })
+                .collect(),
+        };
+        for action in state.CallbackConnection() {
+            let name = parser::identifier_text(&action).unwrap_or_default();
+            let slot = match name.as_str() {
+                "entered" => &mut s.entered,
+                "exited" => &mut s.exited,
+                _ => {
+                    diag.push_error(
+                        format!(
+                            "'{}' is not an action of a state. Use 'entered' or 'exited'",
+                            name
+                        ),
+                        &action.child_token(SyntaxKind::Identifier).unwrap(),
+                    );
+                    continue;
+                }
+            };
+            if let Some(arg) = action.DeclaredIdentifier().next() {
+                diag.push_error(format!("The '{}' action has no arguments", name), &arg);
+            }
+            if slot.is_some() {
+                diag.push_error(format!("Duplicated '{}' action", name), &action);
+            }
+            *slot = Some((Expression::Uncompiled(action.clone().into()), action));
+        }
+        r.borrow_mut().states.push(s);
+        let index = r.borrow().states.len() - 1;
+        for nested in state.States() {
+            collect_states(nested, Some(index), r, diag);
+        }
+    }
+}
+
+/// Returns the callback that triggers a transition of a state, which must not return a value
+fn lookup_callback_from_qualified_name(
+    node: syntax_nodes::QualifiedName,
+    r: &ElementRc,
+    diag: &mut BuildDiagnostics,
+) -> Option<NamedReference> {
+    let qualname = QualifiedTypeName::from_node(node.clone());
+    let (element, name) = match qualname.members.as_slice() {
+        [name] => (r.clone(), name),
+        [elem_id, name] => match find_element_by_id(r, elem_id.as_ref()) {
+            Some(element) => (element, name),
+            None => {
+                diag.push_error(format!("'{}' is not a valid element id", elem_id), &node);
+                return None;
+            }
+        },
+        _ => {
+            diag.push_error(format!("'{}' is not a valid callback", qualname), &node);
+            return None;
+        }
+    };
+    let PropertyLookupResult { resolved_name, property_type } =
+        element.borrow().lookup_property(name.as_ref());
+    match property_type {
+        Type::Callback { return_type: None, .. } => {
+            Some(NamedReference::new(&element, &resolved_name))
+        }
+        Type::Callback { .. } => {
+            diag.push_error(
+                format!(
+                    "The callback '{}' returns a value and can't trigger a transition",
+                    qualname
+                ),
+                &node,
+            );
+            None
+        }
+        _ => {
+            diag.push_error(format!("'{}' is not a callback", qualname), &node);
+            None
+        }
+    }
+}
+
 fn lookup_property_from_qualified_name(
     node: syntax_nodes::QualifiedName,
     r: &Rc<RefCell<Element>>,
@@ -1553,6 +2048,14 @@ fn visit_element_expressions_simple(
                 ne.element().borrow().lookup_property(ne.name()).property_type
             });
         }
+        for (e, _) in s.entered.iter_mut().chain(s.exited.iter_mut()) {
+            vis(e, None, &|| Type::Callback { return_type: None, args: vec![] })
+        }
+        for trigger in &mut s.triggers {
+            if let Some(guard) = trigger.guard.as_mut() {
+                vis(guard, None, &|| Type::Bool)
+            }
+        }
     }
     elem.borrow_mut().states = states;
 
@@ -1563,6 +2066,12 @@ fn visit_element_expressions_simple(
         }
     }
     elem.borrow_mut().transitions = transitions;
+
+    let mut change_callbacks = std::mem::take(&mut elem.borrow_mut().change_callbacks);
+    for (_, e) in &mut change_callbacks {
+        vis(e, None, &|| Type::Callback { return_type: None, args: vec![] })
+    }
+    elem.borrow_mut().change_callbacks = change_callbacks;
 }
 
 /// Visit all the named reference in an element
@@ -1597,6 +2106,9 @@ fn recurse_expression(expr: &mut Expression, vis: &mut impl FnMut(&mut NamedRefe
         for (r, _, _) in &mut s.property_changes {
             vis(r);
         }
+        for trigger in &mut s.triggers {
+            vis(&mut trigger.event);
+        }
     }
     elem.borrow_mut().states = states;
     let mut transitions = std::mem::take(&mut elem.borrow_mut().transitions);
@@ -1606,6 +2118,11 @@ fn recurse_expression(expr: &mut Expression, vis: &mut impl FnMut(&mut NamedRefe
         }
     }
     elem.borrow_mut().transitions = transitions;
+    let mut change_callbacks = std::mem::take(&mut elem.borrow_mut().change_callbacks);
+    for (r, _) in &mut change_callbacks {
+        vis(r);
+    }
+    elem.borrow_mut().change_callbacks = change_callbacks;
     let mut repeated = std::mem::take(&mut elem.borrow_mut().repeated);
     if let Some(r) = &mut repeated {
         if let Some(lv) = &mut r.is_listview {
@@ -1614,6 +2131,7 @@ fn recurse_expression(expr: &mut Expression, vis: &mut impl FnMut(&mut NamedRefe
             vis(&mut lv.viewport_width);
             vis(&mut lv.listview_height);
             vis(&mut lv.listview_width);
+            vis(&mut lv.first_visible_item);
         }
     }
     elem.borrow_mut().repeated = repeated;
@@ -1621,6 +2139,10 @@ fn recurse_expression(expr: &mut Expression, vis: &mut impl FnMut(&mut NamedRefe
     layout_info_prop.as_mut().map(|(h, b)| (vis(h), vis(b)));
     elem.borrow_mut().layout_info_prop = layout_info_prop;
 
+    let mut accessibility_props = std::mem::take(&mut elem.borrow_mut().accessibility_props);
+    accessibility_props.0.iter_mut().for_each(|(_, x)| vis(x));
+    elem.borrow_mut().accessibility_props = accessibility_props;
+
     // visit two way bindings
     for expr in elem.borrow().bindings.values() {
         for nr in &mut expr.borrow_mut().two_way_bindings {
@@ -1676,6 +2198,23 @@ pub struct State {
     pub id: String,
     pub condition: Option<Expression>,
     pub property_changes: Vec<(NamedReference, Expression, syntax_nodes::StatePropertyChange)>,
+    /// The index, in the states of the element, of the state that contains this nested state
+    pub parent: Option<usize>,
+    /// The actions run when the state, or one of its nested states, is entered or exited
+    pub entered: Option<(Expression, syntax_nodes::CallbackConnection)>,
+    pub exited: Option<(Expression, syntax_nodes::CallbackConnection)>,
+    pub triggers: Vec<StateTrigger>,
+}
+
+/// A transition to another state, triggered by a callback while the state is active
+#[derive(Debug, Clone)]
+pub struct StateTrigger {
+    pub event: NamedReference,
+    /// The transition only happens if the guard, if any, is true when the callback is invoked
+    pub guard: Option<Expression>,
+    /// The id of the target state
+    pub target: String,
+    pub node: syntax_nodes::StateTrigger,
 }
 
 #[derive(Debug, Clone)]
@@ -1704,8 +2243,11 @@ pub fn original_name(&self) -> String {
     }
 }
 
-#[derive(Default, Debug, derive_more::Deref)]
-pub struct Exports(Vec<(ExportedName, Type)>);
+#[derive(Default, Debug)]
+pub struct Exports {
+    types: Vec<(ExportedName, Type)>,
+    constants: Vec<(ExportedName, Expression)>,
+}
 
 impl Exports {
     pub fn from_node(
@@ -1779,6 +2321,30 @@ struct NamedExport {
                 }
             }),
         );
+        let exports_it = exports_it.chain(
+            doc.ExportsList()
+                .flat_map(|exports| {
+                    exports
+                        .EnumDeclaration()
+                        .map(|en| en.DeclaredIdentifier())
+                        .chain(exports.TypeAlias().map(|alias| alias.DeclaredIdentifier()))
+                        .chain(exports.ConstantDeclaration().map(|c| c.DeclaredIdentifier()))
+                        .collect::<Vec<_>>()
+                })
+                .map(|ident| {
+                    let name_location: SyntaxNode = ident.clone().into();
+                    let name = parser::identifier_text(&ident).unwrap_or_else(|| {
+                        debug_assert!(diag.has_error());
+                        String::new()
+                    });
+                    NamedExport {
+                        internal_name_ident: name_location.clone(),
+                        internal_name: name.clone(),
+                        external_name_ident: name_location,
+                        exported_name: name,
+                    }
+                }),
+        );
 
         struct SeenExport {
             name_location: SyntaxNode,
@@ -1826,15 +2392,27 @@ struct SeenExport {
             }
         }
 
-        let mut resolve_export_to_inner_component_or_import =
-            |export: &NamedExport| match type_registry.lookup(export.internal_name.as_str()) {
-                ty @ Type::Component(_) | ty @ Type::Struct { .. } => Some(ty),
-                Type::Invalid => {
-                    diag.push_error(
+        let mut types = Vec::new();
+        let mut constants = Vec::new();
+        for export in &exports {
+            let exported_name = ExportedName {
+                name: export.exported_name.clone(),
+                name_ident: export.external_name_ident.clone(),
+            };
+            match type_registry.lookup(export.internal_name.as_str()) {
+                ty @ Type::Component(_) | ty @ Type::Struct { .. } | ty @ Type::Enumeration(_) => {
+                    types.push((exported_name, ty))
+                }
+                Type::Invalid => match type_registry.lookup_constant(&export.internal_name) {
+                    Some(value) => constants.push((exported_name, value)),
+                    None => diag.push_error(
                         format!("'{}' not found", export.internal_name),
                         &export.internal_name_ident,
-                    );
-                    None
+                    ),
+                },
+                // A type alias declared or imported in this document
+                ty if type_registry.is_local_type(&export.internal_name) => {
+                    types.push((exported_name, ty))
                 }
                 _ => {
                     diag.push_error(
@@ -1844,24 +2422,11 @@ struct SeenExport {
                         ),
                         &export.internal_name_ident,
                     );
-                    None
                 }
-            };
+            }
+        }
 
-        Self(
-            exports
-                .iter()
-                .filter_map(|export| {
-                    Some((
-                        ExportedName {
-                            name: export.exported_name.clone(),
-                            name_ident: export.external_name_ident.clone(),
-                        },
-                        resolve_export_to_inner_component_or_import(export)?,
-                    ))
-                })
-                .collect(),
-        )
+        Self { types, constants }
     }
 }
 
@@ -321,24 +321,32 @@ fn source_file(&self) -> Option<&SourceFile> {
         Question -> "?",
         Dollar -> "$",
         At -> "@",
+        Pipe -> "|",
     }
     // syntax kind
     {
-        Document -> [ *Component, *ExportsList, *ImportSpecifier, *StructDeclaration ],
+        Document -> [ *Component, *ExportsList, *ImportSpecifier, *StructDeclaration, *EnumDeclaration,
+                      *TypeAlias, *ConstantDeclaration ],
         /// `DeclaredIdentifier := Element { ... }`
         Component -> [ DeclaredIdentifier, Element ],
         /// `id := Element { ... }`
         SubElement -> [ Element ],
         Element -> [ ?QualifiedName, *PropertyDeclaration, *Binding, *CallbackConnection,
-                     *CallbackDeclaration, *SubElement, *RepeatedElement, *PropertyAnimation,
+                     *CallbackDeclaration, *Function, *SubElement, *RepeatedElement, *PropertyAnimation,
                      *TwoWayBinding, *States, *Transitions, ?ChildrenPlaceholder ],
         RepeatedElement -> [ ?DeclaredIdentifier, ?RepeatedIndex, Expression , SubElement],
         RepeatedIndex -> [],
-        ConditionalElement -> [ Expression , SubElement],
+        ConditionalElement -> [ Expression , SubElement, ?ConditionalElse ],
+        /// `else Element { }` or `else if condition : Element { }` after a ConditionalElement
+        ConditionalElse -> [ ?ConditionalElement, ?SubElement ],
         CallbackDeclaration -> [ DeclaredIdentifier, *Type, ?ReturnType, ?TwoWayBinding ],
         /// `-> type`  (but without the ->)
         ReturnType -> [Type],
         CallbackConnection -> [ *DeclaredIdentifier,  CodeBlock ],
+        /// `function foo(a: int) -> int { ... }`
+        Function -> [ DeclaredIdentifier, *ArgumentDeclaration, ?ReturnType, CodeBlock ],
+        /// `a: int` in the arguments of a Function
+        ArgumentDeclaration -> [ DeclaredIdentifier, Type ],
         /// Declaration of a property.
         PropertyDeclaration-> [ ?Type , DeclaredIdentifier, ?BindingExpression, ?TwoWayBinding ],
         /// QualifiedName are the properties name
@@ -360,11 +368,13 @@ fn source_file(&self) -> Option<&SourceFile> {
         Expression-> [ ?Expression, ?FunctionCallExpression, ?IndexExpression, ?SelfAssignment,
                        ?ConditionalExpression, ?QualifiedName, ?BinaryExpression, ?Array, ?ObjectLiteral,
                        ?UnaryOpExpression, ?CodeBlock, ?StringTemplate, ?AtImageUrl, ?AtLinearGradient,
-                       ?MemberAccess ],
+                       ?MemberAccess, ?MatchExpression ],
         /// Concatenate the Expressions to make a string (usually expended from a template string)
         StringTemplate -> [*Expression],
         /// `@image-url("foo.png")`
-        AtImageUrl -> [],
+        AtImageUrl -> [*AtImageUrlOption],
+        /// `max-size: 64` in `@image-url("foo.png", max-size: 64)`
+        AtImageUrlOption -> [],
         /// `@linear-gradient(...)`
         AtLinearGradient -> [*Expression],
         /// expression()
@@ -375,6 +385,12 @@ fn source_file(&self) -> Option<&SourceFile> {
         SelfAssignment -> [2 Expression],
         /// `condition ? first : second`
         ConditionalExpression -> [3 Expression],
+        /// `match value { pattern => expression, ... }`
+        MatchExpression -> [ Expression, *MatchArm ],
+        /// `pattern | pattern => expression`. The `_` arm has no MatchPattern
+        MatchArm -> [ *MatchPattern, Expression ],
+        /// A value, or a `low .. high` range if there are two expressions
+        MatchPattern -> [ *Expression ],
         /// `expr + expr`
         BinaryExpression -> [2 Expression],
         /// `- expr`
@@ -384,21 +400,28 @@ fn source_file(&self) -> Option<&SourceFile> {
         /// `[ ... ]`
         Array -> [ *Expression ],
         /// `{ foo: bar }`
-        ObjectLiteral -> [ *ObjectMember ],
+        ObjectLiteral -> [ *ObjectMember, ?ObjectDefault ],
         /// `foo: bar` inside an ObjectLiteral
         ObjectMember -> [ Expression ],
+        /// `..default` at the end of an ObjectLiteral
+        ObjectDefault -> [],
         /// `states: [...]`
         States -> [*State],
         /// The DeclaredIdentifier is the state name. The Expression, if any, is the condition.
-        State -> [DeclaredIdentifier, ?Expression, *StatePropertyChange],
+        /// The CallbackConnection are the `entered` and `exited` actions, and the States the nested states.
+        State -> [DeclaredIdentifier, ?Expression, *StatePropertyChange, *CallbackConnection, *StateTrigger, *States],
         /// binding within a state
         StatePropertyChange -> [ QualifiedName, BindingExpression ],
+        /// `on clicked if guard => other-state;` within a state. The QualifiedName is the callback that
+        /// triggers the transition, the Expression, if any, the guard, and the DeclaredIdentifier the target state.
+        StateTrigger -> [ QualifiedName, ?Expression, DeclaredIdentifier ],
         /// `transitions: [...]`
         Transitions -> [*Transition],
         /// There is an identifier "in" or "out", the DeclaredIdentifier is the state name
         Transition -> [DeclaredIdentifier, *PropertyAnimation],
         /// Export a set of declared components by name
-        ExportsList -> [ *ExportSpecifier, ?Component, *StructDeclaration ],
+        ExportsList -> [ *ExportSpecifier, ?Component, *StructDeclaration, *EnumDeclaration,
+                         *TypeAlias, *ConstantDeclaration ],
         /// Declare the first identifier to be exported, either under its name or instead
         /// under the name of the second identifier.
         ExportSpecifier -> [ ExportIdentifier, ?ExportName ],
@@ -415,12 +438,20 @@ fn source_file(&self) -> Option<&SourceFile> {
         Type -> [ ?QualifiedName, ?ObjectType, ?ArrayType ],
         /// `{foo: string, bar: string} `
         ObjectType ->[ *ObjectTypeMember ],
-        /// `foo: type` inside an ObjectType
-        ObjectTypeMember -> [ Type ],
+        /// `foo: type` or `foo: type = default` inside an ObjectType
+        ObjectTypeMember -> [ Type, ?Expression ],
         /// `[ type ]`
         ArrayType -> [ Type ],
         /// `struct Foo := { ... }
         StructDeclaration -> [DeclaredIdentifier, ObjectType],
+        /// `enum Foo { bar, baz }`
+        EnumDeclaration -> [DeclaredIdentifier, *EnumValue],
+        /// `bar` inside an EnumDeclaration
+        EnumValue -> [],
+        /// `type Foo = float;`
+        TypeAlias -> [DeclaredIdentifier, Type],
+        /// `const FOO: length = 8px;`
+        ConstantDeclaration -> [DeclaredIdentifier, Type, Expression],
 
     }
 }
@@ -889,6 +920,49 @@ pub fn identifier_text(node: &SyntaxNode) -> Option<String> {
     node.child_text(SyntaxKind::Identifier).map(|x| normalize_identifier(&x))
 }
 
+/// Returns the text of the `///` comments right before the node, without the slashes, or None if
+/// there are none. The comments before the `export` keyword document the exported declaration.
+pub fn doc_comment(node: &SyntaxNode) -> Option<String> {
+    let preceding =
+        std::iter::successors(node.node.prev_sibling_or_token(), |e| e.prev_sibling_or_token());
+    let mut lines = Vec::new();
+    for e in preceding.clone() {
+        match e.kind() {
+            SyntaxKind::Comment => {
+                let text = e.as_token().map(|t| t.text().to_string()).unwrap_or_default();
+                match text.strip_prefix("///") {
+                    Some(line) if !line.starts_with('/') => lines.push(line.trim_end().to_owned()),
+                    _ => break,
+                }
+            }
+            // A blank line separates the comments from the declaration
+            SyntaxKind::Whitespace
+                if e.as_token().map_or(false, |t| t.text().matches('\n').count() < 2) => {}
+            _ => break,
+        }
+    }
+
+    if lines.is_empty() {
+        let is_first_exported =
+            node.parent().map_or(false, |p| p.kind() == SyntaxKind::ExportsList)
+                && preceding
+                    .filter(|e| !matches!(e.kind(), SyntaxKind::Whitespace | SyntaxKind::Comment))
+                    .all(|e| e.as_token().map_or(false, |t| t.text() == "export"));
+        return if is_first_exported { doc_comment(&node.parent().unwrap()) } else { None };
+    }
+
+    lines.reverse();
+    // Remove the space after the slashes, if all the lines have it
+    if lines.iter().all(|line| line.is_empty() || line.starts_with(' ')) {
+        for line in &mut lines {
+            if !line.is_empty() {
+                line.remove(0);
+            }
+        }
+    }
+    Some(lines.join("\n"))
+}
+
 pub fn normalize_identifier(ident: &str) -> String {
     ident.replace('_', "-")
 }
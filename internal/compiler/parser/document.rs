@@ -3,7 +3,9 @@
 
 use super::element::{parse_element, parse_element_content};
 use super::prelude::*;
-use super::r#type::parse_struct_declaration;
+use super::r#type::{
+    parse_constant_declaration, parse_enum_declaration, parse_struct_declaration, parse_type_alias,
+};
 
 #[cfg_attr(test, parser_test)]
 /// ```test,Document
@@ -13,6 +15,9 @@
 /// Type := Base {} export { Type }
 /// import { Base } from "somewhere"; Type := Base {}
 /// struct Foo := { foo: foo }
+/// enum Foo { bar, baz }
+/// type Celsius = float;
+/// const SPACING: length = 8px;
 /// /* empty */
 /// ```
 pub fn parse_document(p: &mut impl Parser) -> bool {
@@ -39,6 +44,21 @@ pub fn parse_document(p: &mut impl Parser) -> bool {
                     return false;
                 }
             }
+            "enum" => {
+                if !parse_enum_declaration(&mut *p) {
+                    return false;
+                }
+            }
+            "type" if p.nth(1).kind() == SyntaxKind::Identifier => {
+                if !parse_type_alias(&mut *p) {
+                    return false;
+                }
+            }
+            "const" if p.nth(1).kind() == SyntaxKind::Identifier => {
+                if !parse_constant_declaration(&mut *p) {
+                    return false;
+                }
+            }
             _ => {
                 if !parse_component(&mut *p) {
                     return false;
@@ -110,6 +130,9 @@ pub fn parse_qualified_name(p: &mut impl Parser) -> bool {
 /// export { Type as Foo, AnotherType }
 /// export Foo := Item { }
 /// export struct Foo := { foo: bar }
+/// export enum Foo { bar, baz }
+/// export type Celsius = float;
+/// export const SPACING: length = 8px;
 /// ```
 fn parse_export(p: &mut impl Parser) -> bool {
     debug_assert_eq!(p.peek().as_str(), "export");
@@ -138,6 +161,12 @@ fn parse_export(p: &mut impl Parser) -> bool {
         }
     } else if p.peek().as_str() == "struct" {
         parse_struct_declaration(&mut *p)
+    } else if p.peek().as_str() == "enum" {
+        parse_enum_declaration(&mut *p)
+    } else if p.peek().as_str() == "type" && p.nth(1).kind() == SyntaxKind::Identifier {
+        parse_type_alias(&mut *p)
+    } else if p.peek().as_str() == "const" && p.nth(1).kind() == SyntaxKind::Identifier {
+        parse_constant_declaration(&mut *p)
     } else {
         parse_component(&mut *p)
     }
@@ -33,7 +33,9 @@ pub fn parse_element(p: &mut impl Parser) -> bool {
 /// if condition : Sub {}
 /// clicked => {}
 /// callback foobar;
+/// function foo() {}
 /// property<int> width;
+/// out property<int> height;
 /// animate someProp { }
 /// animate * { }
 /// @children
@@ -58,6 +60,9 @@ pub fn parse_element_content(p: &mut impl Parser) {
                 SyntaxKind::Identifier if p.peek().as_str() == "callback" => {
                     parse_callback_declaration(&mut *p);
                 }
+                SyntaxKind::Identifier if p.peek().as_str() == "function" => {
+                    parse_function(&mut *p);
+                }
                 SyntaxKind::Identifier | SyntaxKind::Star if p.peek().as_str() == "animate" => {
                     parse_property_animation(&mut *p);
                 }
@@ -67,6 +72,15 @@ pub fn parse_element_content(p: &mut impl Parser) {
                 SyntaxKind::Identifier if p.peek().as_str() == "property" => {
                     parse_property_declaration(&mut *p);
                 }
+                SyntaxKind::Identifier
+                    if p.nth(1).as_str() == "property"
+                        && matches!(
+                            p.peek().as_str(),
+                            "private" | "protected" | "in" | "out" | "in-out" | "in_out"
+                        ) =>
+                {
+                    parse_property_declaration(&mut *p);
+                }
                 _ if p.peek().as_str() == "if" => {
                     parse_if_element(&mut *p);
                 }
@@ -163,6 +177,8 @@ fn parse_repeated_element(p: &mut impl Parser) {
 /// if (foo ? bar : xx) : Elem { foo:bar; Elem {}}
 /// if (true) : foo := Elem {}
 /// if true && true : Elem {}
+/// if (condition) : Elem { } else Elem { }
+/// if (a) : Elem { } else if b : Elem { } else foo := Elem { }
 /// ```
 /// Must consume at least one token
 fn parse_if_element(p: &mut impl Parser) {
@@ -175,6 +191,15 @@ fn parse_if_element(p: &mut impl Parser) {
         return;
     }
     parse_sub_element(&mut *p);
+    if p.peek().as_str() == "else" && p.nth(1).kind() == SyntaxKind::Identifier {
+        let mut p = p.start_node(SyntaxKind::ConditionalElse);
+        p.consume(); // "else"
+        if p.peek().as_str() == "if" {
+            parse_if_element(&mut *p);
+        } else {
+            parse_sub_element(&mut *p);
+        }
+    }
 }
 
 #[cfg_attr(test, parser_test)]
@@ -195,10 +220,14 @@ fn parse_property_binding(p: &mut impl Parser) {
 /// expression ;
 /// {expression }
 /// {object: 42};
+/// { ..default };
 /// ```
 fn parse_binding_expression(p: &mut impl Parser) -> bool {
     let mut p = p.start_node(SyntaxKind::BindingExpression);
-    if p.nth(0).kind() == SyntaxKind::LBrace && p.nth(2).kind() != SyntaxKind::Colon {
+    if p.nth(0).kind() == SyntaxKind::LBrace
+        && p.nth(2).kind() != SyntaxKind::Colon
+        && p.nth(1).kind() != SyntaxKind::Dot
+    {
         parse_code_block(&mut *p);
         p.test(SyntaxKind::Semicolon);
         true
@@ -320,16 +349,61 @@ fn parse_callback_declaration(p: &mut impl Parser) {
     p.expect(SyntaxKind::Semicolon);
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,Function
+/// function foo() {}
+/// function foo(a: int) -> int { return a; }
+/// function foo(a: int, b: { c: string },) -> string { b.c }
+/// ```
+fn parse_function(p: &mut impl Parser) {
+    debug_assert_eq!(p.peek().as_str(), "function");
+    let mut p = p.start_node(SyntaxKind::Function);
+    p.consume(); // "function"
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    if p.expect(SyntaxKind::LParent) {
+        while p.peek().kind() != SyntaxKind::RParent {
+            {
+                let mut p = p.start_node(SyntaxKind::ArgumentDeclaration);
+                {
+                    let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+                    p.expect(SyntaxKind::Identifier);
+                }
+                p.expect(SyntaxKind::Colon);
+                parse_type(&mut *p);
+            }
+            if !p.test(SyntaxKind::Comma) {
+                break;
+            }
+        }
+        p.expect(SyntaxKind::RParent);
+    }
+    if p.test(SyntaxKind::Arrow) {
+        let mut p = p.start_node(SyntaxKind::ReturnType);
+        parse_type(&mut *p);
+    }
+    parse_code_block(&mut *p);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,PropertyDeclaration
 /// property<int> foobar;
 /// property<string> text: "Something";
 /// property<string> text <=> two.way;
 /// property alias <=> two.way;
+/// private property<int> foobar;
+/// in property<string> text: "Something";
+/// out property<int> count;
+/// in-out property alias <=> two.way;
 /// ```
 fn parse_property_declaration(p: &mut impl Parser) {
-    debug_assert_eq!(p.peek().as_str(), "property");
     let mut p = p.start_node(SyntaxKind::PropertyDeclaration);
+    if p.peek().as_str() != "property" {
+        p.consume(); // visibility
+    }
+    debug_assert_eq!(p.peek().as_str(), "property");
     p.consume(); // property
 
     if p.test(SyntaxKind::LAngle) {
@@ -424,6 +498,8 @@ fn parse_states(p: &mut impl Parser) {
 /// ```test,State
 /// foo : { x: 1px + 2px; aaa.y: {1px + 2px} }
 /// foo when bar == 1:  { color: blue; foo.color: red;   }
+/// foo when bar: { entered => { debug(); } exited => {} states [ baz when qux: { x: 1px; } ] }
+/// foo: { on clicked => bar; on touch.clicked if enabled && count > 2 => baz; x: 1px; }
 /// ```
 fn parse_state(p: &mut impl Parser) -> bool {
     if p.nth(0).kind() != SyntaxKind::Identifier {
@@ -450,6 +526,21 @@ fn parse_state(p: &mut impl Parser) -> bool {
                 return true;
             }
             SyntaxKind::Eof => return false,
+            SyntaxKind::Identifier
+                if p.peek().as_str() == "states" && p.nth(1).kind() == SyntaxKind::LBracket =>
+            {
+                parse_states(&mut *p);
+            }
+            SyntaxKind::Identifier
+                if matches!(p.nth(1).kind(), SyntaxKind::FatArrow | SyntaxKind::LParent) =>
+            {
+                parse_callback_connection(&mut *p);
+            }
+            SyntaxKind::Identifier
+                if p.peek().as_str() == "on" && p.nth(1).kind() == SyntaxKind::Identifier =>
+            {
+                parse_state_trigger(&mut *p);
+            }
             _ => {
                 let checkpoint = p.checkpoint();
                 if !parse_qualified_name(&mut *p)
@@ -465,6 +556,29 @@ fn parse_state(p: &mut impl Parser) -> bool {
     }
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,StateTrigger
+/// on clicked => foo;
+/// on touch.clicked if enabled => foo;
+/// on activated if a.checked || count == 3 => bar;
+/// ```
+fn parse_state_trigger(p: &mut impl Parser) {
+    debug_assert_eq!(p.peek().as_str(), "on");
+    let mut p = p.start_node(SyntaxKind::StateTrigger);
+    p.consume(); // "on"
+    parse_qualified_name(&mut *p);
+    if p.peek().as_str() == "if" {
+        p.consume();
+        parse_expression(&mut *p);
+    }
+    p.expect(SyntaxKind::FatArrow);
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    p.expect(SyntaxKind::Semicolon);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,Transitions
 /// transitions []
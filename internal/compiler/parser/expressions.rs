@@ -30,6 +30,7 @@
 /// array[index]
 /// {object:42}
 /// "foo".bar.something().something.xx({a: 1.foo}.a)
+/// match value { 1 => "one", _ => "other" }
 /// ```
 pub fn parse_expression(p: &mut impl Parser) -> bool {
     parse_expression_helper(p, OperatorPrecedence::Default)
@@ -55,6 +56,25 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
     let mut p = p.start_node(SyntaxKind::Expression);
     let checkpoint = p.checkpoint();
     match p.nth(0).kind() {
+        SyntaxKind::Identifier
+            if p.peek().as_str() == "match"
+                && matches!(
+                    p.nth(1).kind(),
+                    SyntaxKind::Identifier
+                        | SyntaxKind::StringLiteral
+                        | SyntaxKind::NumberLiteral
+                        | SyntaxKind::ColorLiteral
+                        | SyntaxKind::LParent
+                        | SyntaxKind::LBracket
+                        | SyntaxKind::LBrace
+                        | SyntaxKind::Plus
+                        | SyntaxKind::Minus
+                        | SyntaxKind::Bang
+                        | SyntaxKind::At
+                ) =>
+        {
+            parse_match_expression(&mut *p);
+        }
         SyntaxKind::Identifier => {
             parse_qualified_name(&mut *p);
         }
@@ -90,7 +110,8 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
 
     loop {
         match p.nth(0).kind() {
-            SyntaxKind::Dot => {
+            // `..` is not a member access, but a range in a match pattern
+            SyntaxKind::Dot if p.nth(1).kind() != SyntaxKind::Dot => {
                 {
                     let _ = p.start_node_at(checkpoint.clone(), SyntaxKind::Expression);
                 }
@@ -210,6 +231,9 @@ fn parse_expression_helper(p: &mut impl Parser, precedence: OperatorPrecedence)
 #[cfg_attr(test, parser_test)]
 /// ```test
 /// @image-url("/foo/bar.png")
+/// @image-url("/foo/bar.png", max-size: 64, recompress: true)
+/// @image-url("/foo/bar.png", scale-variants: true,)
+/// @image-url("/foo/bar.svg", colorize: #ff8000)
 /// @linear-gradient(0deg, blue, red)
 /// ```
 fn parse_at_keyword(p: &mut impl Parser) {
@@ -221,6 +245,14 @@ fn parse_at_keyword(p: &mut impl Parser) {
             p.consume(); // "image-url"
             p.expect(SyntaxKind::LParent);
             p.expect(SyntaxKind::StringLiteral);
+            while p.test(SyntaxKind::Comma) && p.nth(0).kind() != SyntaxKind::RParent {
+                let mut p = p.start_node(SyntaxKind::AtImageUrlOption);
+                p.expect(SyntaxKind::Identifier);
+                p.expect(SyntaxKind::Colon);
+                if !p.test(SyntaxKind::NumberLiteral) && !p.test(SyntaxKind::ColorLiteral) {
+                    p.expect(SyntaxKind::Identifier);
+                }
+            }
             p.expect(SyntaxKind::RParent);
         }
         "linear-gradient" | "linear_gradient" => {
@@ -259,12 +291,24 @@ fn parse_array(p: &mut impl Parser) {
 /// {a:b}
 /// { a: "foo" , }
 /// {a:b, c: 4 + 4, d: [a,] }
+/// { a: "foo", ..default }
+/// { ..default }
 /// ```
 fn parse_object_notation(p: &mut impl Parser) {
     let mut p = p.start_node(SyntaxKind::ObjectLiteral);
     p.expect(SyntaxKind::LBrace);
 
     while p.nth(0).kind() != SyntaxKind::RBrace {
+        if p.nth(0).kind() == SyntaxKind::Dot {
+            let mut p = p.start_node(SyntaxKind::ObjectDefault);
+            p.consume();
+            p.expect(SyntaxKind::Dot);
+            if p.peek().as_str() != "default" {
+                p.error("Expected 'default' after '..'");
+            }
+            p.expect(SyntaxKind::Identifier);
+            break;
+        }
         let mut p = p.start_node(SyntaxKind::ObjectMember);
         p.expect(SyntaxKind::Identifier);
         p.expect(SyntaxKind::Colon);
@@ -295,6 +339,54 @@ fn parse_function_arguments(p: &mut impl Parser) {
     p.expect(SyntaxKind::RParent);
 }
 
+#[cfg_attr(test, parser_test)]
+/// ```test,MatchExpression
+/// match value { 1 => "one", 2 | 3 => "two or three", _ => "other" }
+/// match status { Status.ok => green, Status.error => red, }
+/// match (x + 1) { 0 .. 10 => 1, 10..20 => 2, _ => 3 }
+/// match x {}
+/// match "x" { "x" => 1, _ => 0 }
+/// match 42 { 42 => true, _ => false }
+/// match -n { -1 => "minus one", _ => "other" }
+/// match +(a - b) { 0 .. 10 => 1, _ => 2 }
+/// ```
+fn parse_match_expression(p: &mut impl Parser) {
+    debug_assert_eq!(p.peek().as_str(), "match");
+    let mut p = p.start_node(SyntaxKind::MatchExpression);
+    p.consume(); // "match"
+    parse_expression(&mut *p);
+    if !p.expect(SyntaxKind::LBrace) {
+        return;
+    }
+    while !matches!(p.nth(0).kind(), SyntaxKind::RBrace | SyntaxKind::Eof) {
+        let mut p = p.start_node(SyntaxKind::MatchArm);
+        if p.peek().as_str() == "_" {
+            p.consume();
+        } else {
+            loop {
+                {
+                    let mut p = p.start_node(SyntaxKind::MatchPattern);
+                    parse_expression(&mut *p);
+                    if p.nth(0).kind() == SyntaxKind::Dot {
+                        p.consume();
+                        p.expect(SyntaxKind::Dot);
+                        parse_expression(&mut *p);
+                    }
+                }
+                if !p.test(SyntaxKind::Pipe) {
+                    break;
+                }
+            }
+        }
+        p.expect(SyntaxKind::FatArrow);
+        parse_expression(&mut *p);
+        if !p.test(SyntaxKind::Comma) {
+            break;
+        }
+    }
+    p.expect(SyntaxKind::RBrace);
+}
+
 #[cfg_attr(test, parser_test)]
 /// ```test,StringTemplate
 /// "foo\{bar}"
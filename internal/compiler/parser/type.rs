@@ -4,6 +4,7 @@
 //! Module containing the parsing functions for type names
 
 use super::document::parse_qualified_name;
+use super::expressions::parse_expression;
 use super::prelude::*;
 
 #[cfg_attr(test, parser_test)]
@@ -30,6 +31,7 @@ pub fn parse_type(p: &mut impl Parser) {
 /// {a: string}
 /// {a: string,}
 /// {a: { foo: string, bar: int, }, q: {} }
+/// {a: string = "foo", b: int = 42}
 /// ```
 pub fn parse_type_object(p: &mut impl Parser) {
     let mut p = p.start_node(SyntaxKind::ObjectType);
@@ -41,6 +43,9 @@ pub fn parse_type_object(p: &mut impl Parser) {
         p.expect(SyntaxKind::Identifier);
         p.expect(SyntaxKind::Colon);
         parse_type(&mut *p);
+        if p.test(SyntaxKind::Equal) {
+            parse_expression(&mut *p);
+        }
         if !p.test(SyntaxKind::Comma) {
             break;
         }
@@ -65,6 +70,7 @@ pub fn parse_type_array(p: &mut impl Parser) {
 /// ```test,StructDeclaration
 /// struct Foo := { foo: bar, xxx: { aaa: bbb, } }
 /// struct Bar := {}
+/// struct Baz := { foo: int = 42, bar: string }
 /// ```
 pub fn parse_struct_declaration(p: &mut impl Parser) -> bool {
     debug_assert_eq!(p.peek().as_str(), "struct");
@@ -78,3 +84,81 @@ pub fn parse_struct_declaration(p: &mut impl Parser) -> bool {
     parse_type_object(&mut *p);
     true
 }
+
+#[cfg_attr(test, parser_test)]
+/// ```test,EnumDeclaration
+/// enum Foo { bar, xxx-yyy }
+/// enum Bar := { aaa, bbb, }
+/// enum Empty {}
+/// ```
+pub fn parse_enum_declaration(p: &mut impl Parser) -> bool {
+    debug_assert_eq!(p.peek().as_str(), "enum");
+    let mut p = p.start_node(SyntaxKind::EnumDeclaration);
+    p.consume(); // "enum"
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    p.test(SyntaxKind::ColonEqual);
+    if !p.expect(SyntaxKind::LBrace) {
+        return false;
+    }
+    while p.nth(0).kind() != SyntaxKind::RBrace {
+        {
+            let mut p = p.start_node(SyntaxKind::EnumValue);
+            if !p.expect(SyntaxKind::Identifier) {
+                return false;
+            }
+        }
+        if !p.test(SyntaxKind::Comma) {
+            break;
+        }
+    }
+    p.expect(SyntaxKind::RBrace)
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,TypeAlias
+/// type Celsius = float;
+/// type Point = { x: length, y: length };
+/// type Names = [string];
+/// ```
+pub fn parse_type_alias(p: &mut impl Parser) -> bool {
+    debug_assert_eq!(p.peek().as_str(), "type");
+    let mut p = p.start_node(SyntaxKind::TypeAlias);
+    p.consume(); // "type"
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    if !p.expect(SyntaxKind::Equal) {
+        return false;
+    }
+    parse_type(&mut *p);
+    p.expect(SyntaxKind::Semicolon)
+}
+
+#[cfg_attr(test, parser_test)]
+/// ```test,ConstantDeclaration
+/// const SPACING: length = 8px;
+/// const DOUBLE-SPACING: length = SPACING * 2;
+/// const NAMES: [string] = ["a", "b"];
+/// ```
+pub fn parse_constant_declaration(p: &mut impl Parser) -> bool {
+    debug_assert_eq!(p.peek().as_str(), "const");
+    let mut p = p.start_node(SyntaxKind::ConstantDeclaration);
+    p.consume(); // "const"
+    {
+        let mut p = p.start_node(SyntaxKind::DeclaredIdentifier);
+        p.expect(SyntaxKind::Identifier);
+    }
+    if !p.expect(SyntaxKind::Colon) {
+        return false;
+    }
+    parse_type(&mut *p);
+    if !p.expect(SyntaxKind::Equal) {
+        return false;
+    }
+    parse_expression(&mut *p);
+    p.expect(SyntaxKind::Semicolon)
+}
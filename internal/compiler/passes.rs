@@ -3,6 +3,7 @@
 
 mod apply_default_properties_from_style;
 mod binding_analysis;
+mod check_backend_capabilities;
 mod check_expressions;
 mod check_public_api;
 mod clip;
@@ -22,10 +23,15 @@
 mod generate_item_indices;
 mod infer_aliases_types;
 mod inlining;
+mod lower_accessibility;
+mod lower_anchors;
+mod lower_hover;
 mod lower_layout;
+mod lower_lifecycle;
 mod lower_popups;
 mod lower_property_to_element;
 mod lower_shadows;
+mod lower_split_view;
 mod lower_states;
 mod lower_tabwidget;
 mod materialize_fake_properties;
@@ -36,6 +42,7 @@
 mod repeater_component;
 mod resolve_native_classes;
 mod resolving;
+mod subset_fonts;
 mod unique_id;
 mod visible;
 mod z_order;
@@ -82,6 +89,7 @@ pub async fn run_passes(
     {
         compile_paths::compile_paths(component, &doc.local_registry, diag);
         lower_tabwidget::lower_tabwidget(component, type_loader, diag).await;
+        lower_split_view::lower_split_view(component, type_loader, diag).await;
         apply_default_properties_from_style::apply_default_properties_from_style(
             component,
             &style_metrics,
@@ -93,6 +101,8 @@ pub async fn run_passes(
     inlining::inline(doc, inlining::InlineSelection::InlineOnlyRequiredComponents);
     collect_subcomponents::collect_subcomponents(root_component);
 
+    check_backend_capabilities::check_backend_capabilities(root_component, compiler_config, diag);
+
     embed_images::embed_images(
         root_component,
         compiler_config.embed_resources,
@@ -108,6 +118,7 @@ pub async fn run_passes(
             focus_item::determine_initial_focus_item(component, diag);
         }
         focus_item::erase_forward_focus_properties(component);
+        flickable::resolve_scroll_calls(component, diag);
     }
 
     ensure_window::ensure_window(root_component, &doc.local_registry, &style_metrics);
@@ -116,6 +127,7 @@ pub async fn run_passes(
         .chain(std::iter::once(root_component))
     {
         flickable::handle_flickable(component, &global_type_registry.borrow());
+        lower_anchors::lower_anchors(component, diag);
         repeater_component::process_repeater_components(component);
         lower_popups::lower_popups(component, &doc.local_registry, diag);
         lower_layout::lower_layouts(component, type_loader, diag).await;
@@ -135,9 +147,12 @@ pub async fn run_passes(
             &global_type_registry.borrow(),
             diag,
         );
+        lower_hover::lower_hover(component, &global_type_registry.borrow(), diag);
+        lower_lifecycle::lower_lifecycle(component, &global_type_registry.borrow());
         lower_shadows::lower_shadow_properties(component, &doc.local_registry, diag);
         clip::handle_clip(component, &global_type_registry.borrow(), diag);
         visible::handle_visible(component, &global_type_registry.borrow());
+        lower_accessibility::lower_accessibility_properties(component, diag);
         materialize_fake_properties::materialize_fake_properties(component);
     }
     collect_globals::collect_globals(doc, diag);
@@ -184,6 +199,12 @@ pub async fn run_passes(
     if compiler_config.embed_resources == crate::EmbedResourcesKind::EmbedTextures {
         // Include at least the default font sizes used in the MCU backend
         let mut font_pixel_sizes = vec![(12. * compiler_config.scale_factor) as i16];
+        for size in &compiler_config.embed_glyphs_sizes {
+            let pixel_size = (*size as f64 * compiler_config.scale_factor) as i16;
+            if let Err(pos) = font_pixel_sizes.binary_search(&pixel_size) {
+                font_pixel_sizes.insert(pos, pixel_size)
+            }
+        }
         for component in (root_component.used_types.borrow().sub_components.iter())
             .chain(std::iter::once(root_component))
         {
@@ -199,6 +220,8 @@ pub async fn run_passes(
             compiler_config.scale_factor,
             font_pixel_sizes,
             std::iter::once(&*doc).chain(type_loader.all_documents()),
+            &compiler_config.embed_fonts,
+            &compiler_config.embed_glyphs_characters,
             diag,
         );
     } else {
@@ -207,6 +230,9 @@ pub async fn run_passes(
             root_component,
             std::iter::once(&*doc).chain(type_loader.all_documents()),
             compiler_config.embed_resources == crate::EmbedResourcesKind::EmbedAllResources,
+            &compiler_config.embed_fonts,
+            compiler_config.subset_fonts,
+            diag,
         );
     }
 
@@ -411,6 +411,8 @@ fn visit_implicit_layout_info_dependencies(
                 vis(&NamedReference::new(item, "single-line"));
             } else {
                 vis(&NamedReference::new(item, "overflow"));
+                vis(&NamedReference::new(item, "ellipsis"));
+                vis(&NamedReference::new(item, "paragraph-spacing"));
             }
         }
 
@@ -0,0 +1,140 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Pass that warns about the parts of the design that need a capability that the backend was built
+//! without, as described by the `CompilerConfiguration`: the image formats it can decode, and
+//! whether it shapes the text.
+
+use crate::diagnostics::{BuildDiagnostics, SourceLocation};
+use crate::expression_tree::{Expression, ImageReference};
+use crate::object_tree::{recurse_elem_including_sub_components_no_borrow, Component};
+use crate::CompilerConfiguration;
+use std::rc::Rc;
+
+pub fn check_backend_capabilities(
+    component: &Rc<Component>,
+    compiler_config: &CompilerConfiguration,
+    diag: &mut BuildDiagnostics,
+) {
+    if compiler_config.supported_image_formats.is_none() && compiler_config.text_shaping {
+        return;
+    }
+
+    for component in
+        component.used_types.borrow().sub_components.iter().chain(std::iter::once(component))
+    {
+        recurse_elem_including_sub_components_no_borrow(component, &(), &mut |elem, _| {
+            for binding in elem.borrow().bindings.values() {
+                let binding = binding.borrow();
+                let span = match &binding.span {
+                    // The widgets of the styles are not the user's to fix
+                    Some(span) if !is_builtin(span) => span,
+                    _ => continue,
+                };
+                binding
+                    .expression
+                    .visit_recursive(&mut |e| check_expression(e, span, compiler_config, diag));
+            }
+        })
+    }
+}
+
+fn check_expression(
+    e: &Expression,
+    binding_span: &SourceLocation,
+    compiler_config: &CompilerConfiguration,
+    diag: &mut BuildDiagnostics,
+) {
+    match e {
+        Expression::ImageReference {
+            resource_ref: ImageReference::AbsolutePath(path),
+            source_location,
+            ..
+        } if !path.starts_with("builtin:/") => {
+            let formats = match &compiler_config.supported_image_formats {
+                Some(formats) => formats,
+                None => return,
+            };
+            let extension = std::path::Path::new(path)
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            if !formats.iter().any(|format| format.eq_ignore_ascii_case(&extension)) {
+                diag.push_warning_with_span(
+                    format!(
+                        "The image format of {} is not supported by the backend of this build (supported formats: {})",
+                        path,
+                        formats.join(", ")
+                    ),
+                    source_location.clone().unwrap_or_else(|| binding_span.clone()),
+                );
+            }
+        }
+        Expression::StringLiteral(text) if !compiler_config.text_shaping => {
+            if let Some(ch) = text.chars().find(|ch| needs_shaping(*ch)) {
+                diag.push_warning_with_span(
+                    format!(
+                        "The text \"{}\" needs text shaping to be displayed correctly (because of '{}'), but the backend of this build doesn't shape the text",
+                        text, ch
+                    ),
+                    binding_span.clone(),
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_builtin(span: &SourceLocation) -> bool {
+    span.source_file.as_ref().map_or(true, |source_file| source_file.path().starts_with("builtin:"))
+}
+
+/// Returns true for the combining marks and the characters of the scripts whose glyphs depend on
+/// their neighbors, which are not displayed correctly when each character is simply mapped to a glyph
+fn needs_shaping(ch: char) -> bool {
+    matches!(ch as u32,
+        0x0300..=0x036F // Combining Diacritical Marks
+        | 0x0591..=0x05C7 // Hebrew points
+        | 0x0600..=0x08FF // Arabic, Syriac, Thaana, NKo, Samaritan, Mandaic, Arabic Extended
+        | 0x0900..=0x0DFF // Devanagari to Sinhala
+        | 0x0E00..=0x0FFF // Thai, Lao, Tibetan
+        | 0x1000..=0x109F // Myanmar
+        | 0x1780..=0x17FF // Khmer
+        | 0x1AB0..=0x1AFF // Combining Diacritical Marks Extended
+        | 0x1DC0..=0x1DFF // Combining Diacritical Marks Supplement
+        | 0x200C..=0x200D // Zero width (non-)joiner
+        | 0x20D0..=0x20FF // Combining Diacritical Marks for Symbols
+        | 0xFB1D..=0xFDFF // Hebrew and Arabic presentation forms
+        | 0xFE20..=0xFE2F // Combining Half Marks
+        | 0xFE70..=0xFEFF // Arabic presentation forms B
+        | 0x1F3FB..=0x1F3FF // Emoji skin tone modifiers
+    )
+}
+
+#[test]
+fn test_check_backend_capabilities() {
+    let source = r#"
+Test := Rectangle {
+    Image { source: @image-url("icon.png"); }
+    Image { source: @image-url("photo.webp"); }
+    Text { text: "Hello"; }
+    Text { text: "مرحبا"; }
+}
+"#;
+    let mut compiler_config =
+        CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    compiler_config.style = Some("fluent".into());
+    compiler_config.supported_image_formats = Some(vec!["png".into(), "svg".into()]);
+    compiler_config.text_shaping = false;
+
+    let mut diag = BuildDiagnostics::default();
+    let syntax_node =
+        crate::parser::parse(source.into(), Some(std::path::Path::new("test.slint")), &mut diag);
+    let (_, diag) =
+        spin_on::spin_on(crate::compile_syntax_node(syntax_node, diag, compiler_config));
+
+    let warnings = diag.iter().map(|d| d.message().to_owned()).collect::<Vec<_>>();
+    assert_eq!(warnings.len(), 2, "{:?}", warnings);
+    assert!(warnings[0].contains("photo.webp"), "{:?}", warnings);
+    assert!(warnings[1].contains("مرحبا"), "{:?}", warnings);
+}
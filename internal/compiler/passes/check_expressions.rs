@@ -5,22 +5,41 @@
 
 use crate::diagnostics::BuildDiagnostics;
 use crate::expression_tree::{BuiltinFunction, Expression};
-use crate::object_tree::{visit_all_expressions, Component};
+use crate::object_tree::{recurse_elem_including_sub_components, visit_all_expressions, Component};
+use crate::parser::syntax_nodes;
+use itertools::Either;
 
 /// Check the validity of expressions
 ///
 /// - Make sure that there is no uncalled member function or macro
+/// - Make sure that functions do not have side effects
 pub fn check_expressions(doc: &crate::object_tree::Document, diag: &mut BuildDiagnostics) {
     for component in &doc.inner_components {
         visit_all_expressions(component, |e, _| check_expression(component, e, diag));
+        recurse_elem_including_sub_components(component, &(), &mut |elem, _| {
+            let elem = elem.borrow();
+            for (name, decl) in &elem.property_declarations {
+                let node = match &decl.node {
+                    Some(Either::Right(Either::Right(node))) => node,
+                    _ => continue,
+                };
+                if let Some(binding) = elem.bindings.get(name) {
+                    check_function_purity(&binding.borrow().expression, node, diag);
+                }
+            }
+        });
     }
 }
 
 fn check_expression(component: &Rc<Component>, e: &Expression, diag: &mut BuildDiagnostics) {
     match e {
-        Expression::MemberFunction { .. } => {
-            // Must already have been be reported.
-            debug_assert!(diag.has_error());
+        Expression::MemberFunction { member, .. } => {
+            if let Expression::BuiltinMacroReference(_, node) = &**member {
+                diag.push_error("Builtin function must be called".into(), node);
+            } else {
+                // Must already have been be reported.
+                debug_assert!(diag.has_error());
+            }
         }
         Expression::BuiltinMacroReference(_, node) => {
             diag.push_error("Builtin function must be called".into(), node);
@@ -30,6 +49,62 @@ fn check_expression(component: &Rc<Component>, e: &Expression, diag: &mut BuildD
                 diag.push_error("Cannot convert between logical and physical length in a global component, because the scale factor is not known".into(), loc);
             }
         }
+        Expression::BuiltinFunctionReference(BuiltinFunction::Snap, loc) => {
+            if component.is_global() {
+                diag.push_error("Cannot use the 'snap' function in a global component, because the scale factor is not known".into(), loc);
+            }
+        }
+        Expression::BuiltinFunctionReference(
+            BuiltinFunction::GetWindowWidth
+            | BuiltinFunction::GetWindowHeight
+            | BuiltinFunction::GetWindowDefaultFontSize,
+            loc,
+        ) => {
+            if component.is_global() {
+                diag.push_error("Cannot use the 'vw', 'vh' or 'rem' units in a global component, because the window is not known".into(), loc);
+            }
+        }
         _ => e.visit(|e| check_expression(component, e, diag)),
     }
 }
+
+/// Report an error if the body of a function assigns properties or calls something that can have side effects
+fn check_function_purity(
+    e: &Expression,
+    node: &syntax_nodes::Function,
+    diag: &mut BuildDiagnostics,
+) {
+    let what = match e {
+        Expression::SelfAssignment { .. } => Some("assign properties".to_owned()),
+        Expression::FunctionCall { function, .. } => match &**function {
+            Expression::CallbackReference(nr) if !nr.element().borrow().is_function(nr.name()) => {
+                Some(format!("call the callback '{}'", nr.name()))
+            }
+            Expression::BuiltinFunctionReference(
+                BuiltinFunction::SetFocusItem | BuiltinFunction::ShowPopupWindow,
+                _,
+            ) => Some("change the focus or show popups".to_owned()),
+            Expression::BuiltinFunctionReference(
+                BuiltinFunction::ScrollTo | BuiltinFunction::EnsureVisible,
+                _,
+            ) => Some("scroll".to_owned()),
+            Expression::BuiltinFunctionReference(
+                BuiltinFunction::TextInputUndo | BuiltinFunction::TextInputRedo,
+                _,
+            ) => Some("undo or redo changes of the text".to_owned()),
+            Expression::BuiltinFunctionReference(
+                BuiltinFunction::TextInputFindNext | BuiltinFunction::TextInputFindPrevious,
+                _,
+            ) => Some("change the selection".to_owned()),
+            _ => None,
+        },
+        _ => None,
+    };
+    if let Some(what) = what {
+        diag.push_error(
+            format!("Functions cannot have side effects: they cannot {}", what),
+            &node.DeclaredIdentifier(),
+        );
+    }
+    e.visit(|e| check_function_purity(e, node, diag));
+}
@@ -27,7 +27,11 @@ fn check_public_api_component(root_component: &Rc<Component>, diag: &mut BuildDi
     let root_elem = &mut *root_elem;
     let mut pa = root_elem.property_analysis.borrow_mut();
     root_elem.property_declarations.iter_mut().for_each(|(n, d)| {
-        if d.property_type.ok_for_public_api() {
+        if d.is_function() {
+            // Functions are private to the component and not exposed
+        } else if !d.visibility.is_public() {
+            // Private and protected properties are not part of the public API
+        } else if d.property_type.ok_for_public_api() {
             d.expose_in_public_api = true;
             pa.entry(n.to_string()).or_default().is_set = true;
         } else {
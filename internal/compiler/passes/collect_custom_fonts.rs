@@ -4,6 +4,7 @@
 //! Passes that fills the root component used_global
 
 use crate::{
+    diagnostics::BuildDiagnostics,
     expression_tree::{BuiltinFunction, Expression, Unit},
     object_tree::*,
 };
@@ -11,55 +12,81 @@
 use std::rc::Rc;
 
 /// Fill the root_component's used_globals
+///
+/// `extra_fonts` are the font files from the compiler configuration: they are always embedded.
+/// When `subset_fonts` is true, the embedded fonts only contain the glyphs used by the design.
 pub fn collect_custom_fonts<'a>(
     root_component: &Rc<Component>,
     all_docs: impl Iterator<Item = &'a crate::object_tree::Document> + 'a,
     embed_fonts: bool,
+    extra_fonts: &[std::path::PathBuf],
+    subset_fonts: bool,
+    diag: &mut BuildDiagnostics,
 ) {
     let mut all_fonts = BTreeSet::new();
 
     for doc in all_docs {
-        all_fonts.extend(doc.custom_fonts.iter().map(|(path, _)| path))
+        all_fonts.extend(doc.custom_fonts.iter().map(|(path, _)| (path.clone(), embed_fonts)))
     }
 
-    let registration_function = if embed_fonts {
-        Expression::BuiltinFunctionReference(BuiltinFunction::RegisterCustomFontByMemory, None)
-    } else {
-        Expression::BuiltinFunctionReference(BuiltinFunction::RegisterCustomFontByPath, None)
-    };
+    for path in extra_fonts {
+        let path = match dunce::canonicalize(path) {
+            Ok(path) => path.to_string_lossy().to_string(),
+            Err(_) => {
+                diag.push_error_with_span(
+                    format!("Cannot find font file {}", path.display()),
+                    Default::default(),
+                );
+                continue;
+            }
+        };
+        all_fonts.retain(|(p, _)| *p != path);
+        all_fonts.insert((path, true));
+    }
 
-    let prepare_font_registration_argument: Box<dyn Fn(&String) -> Expression> = if embed_fonts {
-        Box::new(|font_path| {
-            Expression::NumberLiteral(
-                {
-                    let mut resources = root_component.embedded_file_resources.borrow_mut();
-                    let resource_id = match resources.get(font_path) {
-                        Some(r) => r.id,
-                        None => {
-                            let id = resources.len();
-                            resources.insert(
-                                font_path.clone(),
-                                crate::embedded_resources::EmbeddedResources {
-                                    id,
-                                    kind: crate::embedded_resources::EmbeddedResourcesKind::RawData,
-                                },
-                            );
-                            id
-                        }
-                    };
-                    resource_id as _
-                },
-                Unit::None,
-            )
-        })
-    } else {
-        Box::new(|font_path| Expression::StringLiteral(font_path.clone()))
+    let used_characters = (subset_fonts && all_fonts.iter().any(|(_, embed)| *embed))
+        .then(|| super::subset_fonts::collect_used_characters(root_component));
+
+    let embed_font = |font_path: &String| {
+        let mut resources = root_component.embedded_file_resources.borrow_mut();
+        match resources.get(font_path) {
+            Some(r) => r.id,
+            None => {
+                let id = resources.len();
+                let subset = used_characters.as_ref().and_then(|characters| {
+                    let file = crate::fileaccess::load_file(std::path::Path::new(font_path))?;
+                    super::subset_fonts::subset_font(&file.read(), characters)
+                });
+                let kind = match subset {
+                    Some(data) => {
+                        crate::embedded_resources::EmbeddedResourcesKind::ProcessedData(data)
+                    }
+                    None => crate::embedded_resources::EmbeddedResourcesKind::RawData,
+                };
+                resources.insert(
+                    font_path.clone(),
+                    crate::embedded_resources::EmbeddedResources { id, kind },
+                );
+                id
+            }
+        }
     };
 
-    root_component.setup_code.borrow_mut().extend(all_fonts.into_iter().map(|font_path| {
+    root_component.setup_code.borrow_mut().extend(all_fonts.iter().map(|(font_path, embed)| {
+        let (function, argument) = if *embed {
+            (
+                BuiltinFunction::RegisterCustomFontByMemory,
+                Expression::NumberLiteral(embed_font(font_path) as _, Unit::None),
+            )
+        } else {
+            (
+                BuiltinFunction::RegisterCustomFontByPath,
+                Expression::StringLiteral(font_path.clone()),
+            )
+        };
         Expression::FunctionCall {
-            function: Box::new(registration_function.clone()),
-            arguments: vec![prepare_font_registration_argument(font_path)],
+            function: Box::new(Expression::BuiltinFunctionReference(function, None)),
+            arguments: vec![argument],
             source_location: None,
         }
     }));
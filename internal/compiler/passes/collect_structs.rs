@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
 
 //! Passes that fills the root component used_types.structs
+//! (this includes the enums declared in .slint files)
 
 use crate::expression_tree::Expression;
 use crate::langtype::Type;
@@ -43,10 +44,12 @@ fn collect_structs_in_component(root_component: &Rc<Component>, hash: &mut BTree
     });
 
     visit_all_expressions(root_component, |expr, _| {
-        expr.visit_recursive(&mut |expr| {
-            if let Expression::Struct { ty, .. } = expr {
-                maybe_collect_object(ty)
+        expr.visit_recursive(&mut |expr| match expr {
+            Expression::Struct { ty, .. } => maybe_collect_object(ty),
+            Expression::EnumerationValue(value) => {
+                maybe_collect_object(&Type::Enumeration(value.enumeration.clone()))
             }
+            _ => {}
         })
     });
 }
@@ -80,6 +83,7 @@ fn visit_named_object(ty: &Type, visitor: &mut impl FnMut(&String, &Type)) {
             }
         }
         Type::Array(x) => visit_named_object(x, visitor),
+        Type::Enumeration(e) if e.node.is_some() => visitor(&e.name, ty),
         Type::Callback { return_type, args } => {
             if let Some(rt) = return_type {
                 visit_named_object(rt, visitor);
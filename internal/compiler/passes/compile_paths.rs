@@ -129,6 +129,7 @@ fn compile_path_from_string_literal(commands: &str) -> Option<BindingExpression>
             .collect(),
             name: Some("Point".into()),
             node: None,
+            defaults: Default::default(),
         };
 
         let mut points = Vec::new();
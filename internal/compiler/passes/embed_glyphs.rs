@@ -16,6 +16,8 @@ pub fn embed_glyphs<'a>(
     _scale_factor: f64,
     _pixel_sizes: Vec<i16>,
     _all_docs: impl Iterator<Item = &'a crate::object_tree::Document> + 'a,
+    _extra_fonts: &[std::path::PathBuf],
+    _extra_characters: &str,
     _diag: &mut BuildDiagnostics,
 ) -> bool {
     false
@@ -27,6 +29,8 @@ pub fn embed_glyphs<'a>(
     scale_factor: f64,
     mut pixel_sizes: Vec<i16>,
     all_docs: impl Iterator<Item = &'a crate::object_tree::Document> + 'a,
+    extra_fonts: &[std::path::PathBuf],
+    extra_characters: &str,
     diag: &mut BuildDiagnostics,
 ) {
     if let Ok(sizes_str) = std::env::var("SLINT_FONT_SIZES") {
@@ -71,6 +75,11 @@ pub fn embed_glyphs<'a>(
             }
         }
     }
+    for font_path in extra_fonts {
+        if let Err(e) = fontdb.load_font_file(font_path) {
+            diag.push_error_with_span(format!("Error loading font: {}", e), Default::default());
+        }
+    }
 
     // TODO: improve heuristics in choice of which fonts to embed. use default-font-family, etc.
     let (family, source_location) = component
@@ -114,6 +123,10 @@ pub fn embed_glyphs<'a>(
         )
     };
 
+    let mut coverage = super::subset_fonts::collect_used_characters(component);
+    coverage.insert('…');
+    coverage.extend(extra_characters.chars().filter(|ch| !ch.is_control()));
+
     let font =
         fontdb
             .with_face_data(face_id, |font_data, face_index| {
@@ -122,7 +135,7 @@ pub fn embed_glyphs<'a>(
             fontdue::FontSettings { collection_index: face_index, scale: 40. },
         )
         .expect("internal error: fontdb returned a font that ttf-parser/fontdue could not parse");
-                embed_font(family_name, font, &pixel_sizes)
+                embed_font(family_name, font, &pixel_sizes, &coverage)
             })
             .unwrap();
 
@@ -146,15 +159,16 @@ pub fn embed_glyphs<'a>(
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn embed_font(family_name: String, font: fontdue::Font, pixel_sizes: &[i16]) -> BitmapFont {
-    // TODO: configure coverage
-    let coverage = ('a'..='z')
-        .chain('A'..='Z')
-        .chain('0'..='9')
-        .chain(" !\"#$%&'()*+,-./:;<=>?@\\]^_|~".chars())
-        .chain(std::iter::once('…'));
-
+fn embed_font(
+    family_name: String,
+    font: fontdue::Font,
+    pixel_sizes: &[i16],
+    coverage: &std::collections::BTreeSet<char>,
+) -> BitmapFont {
     let mut character_map: Vec<CharacterMapEntry> = coverage
+        .iter()
+        .copied()
+        .filter(|code_point| font.lookup_glyph_index(*code_point) != 0)
         .enumerate()
         .map(|(glyph_index, code_point)| CharacterMapEntry {
             code_point,
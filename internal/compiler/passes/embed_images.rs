@@ -5,7 +5,7 @@
 
 use crate::diagnostics::BuildDiagnostics;
 use crate::embedded_resources::*;
-use crate::expression_tree::{Expression, ImageReference};
+use crate::expression_tree::{Expression, ImageProcessingOptions, ImageReference};
 use crate::object_tree::*;
 use crate::EmbedResourcesKind;
 #[cfg(not(target_arch = "wasm32"))]
@@ -22,6 +22,8 @@ pub fn embed_images(
     diag: &mut BuildDiagnostics,
 ) {
     let global_embedded_resources = &component.embedded_file_resources;
+    // The processing options each embedded file was embedded with
+    let mut embedded_options = HashMap::new();
 
     for component in
         component.used_types.borrow().sub_components.iter().chain(std::iter::once(component))
@@ -30,6 +32,7 @@ pub fn embed_images(
             embed_images_from_expression(
                 e,
                 global_embedded_resources,
+                &mut embedded_options,
                 embed_files,
                 scale_factor,
                 diag,
@@ -41,21 +44,47 @@ pub fn embed_images(
 fn embed_images_from_expression(
     e: &mut Expression,
     global_embedded_resources: &RefCell<HashMap<String, EmbeddedResources>>,
+    embedded_options: &mut HashMap<String, ImageProcessingOptions>,
     embed_files: EmbedResourcesKind,
     scale_factor: f64,
     diag: &mut BuildDiagnostics,
 ) {
-    if let Expression::ImageReference { ref mut resource_ref, source_location } = e {
+    if let Expression::ImageReference { ref mut resource_ref, source_location, options } = e {
+        if cfg!(target_arch = "wasm32") && options.colorize.is_some() {
+            diag.push_warning(
+                "The 'colorize' option of @image-url is not supported in this build".into(),
+                source_location,
+            );
+        }
         match resource_ref {
+            // Colorized images are always embedded, as the result of the processing is not a file
             ImageReference::AbsolutePath(path)
                 if embed_files != EmbedResourcesKind::OnlyBuiltinResources
-                    || path.starts_with("builtin:/") =>
+                    || path.starts_with("builtin:/")
+                    || (options.colorize.is_some() && cfg!(not(target_arch = "wasm32"))) =>
             {
+                match embedded_options.entry(resource_key(path, options)) {
+                    std::collections::hash_map::Entry::Occupied(entry)
+                        if entry.get() != options =>
+                    {
+                        diag.push_error(
+                            format!(
+                                "The image file {} is used with different @image-url options",
+                                path
+                            ),
+                            source_location,
+                        );
+                    }
+                    entry => {
+                        entry.or_insert_with(|| options.clone());
+                    }
+                }
                 *resource_ref = embed_image(
                     global_embedded_resources,
                     embed_files,
                     path,
                     scale_factor,
+                    options,
                     diag,
                     source_location,
                 );
@@ -65,29 +94,78 @@ fn embed_images_from_expression(
     };
 
     e.visit_mut(|e| {
-        embed_images_from_expression(e, global_embedded_resources, embed_files, scale_factor, diag)
+        embed_images_from_expression(
+            e,
+            global_embedded_resources,
+            embedded_options,
+            embed_files,
+            scale_factor,
+            diag,
+        )
     });
 }
 
+/// The key of the image in the embedded resources. An image file colorized with different colors
+/// gives different resources.
+fn resource_key(path: &str, options: &ImageProcessingOptions) -> String {
+    match options.colorize {
+        #[cfg(not(target_arch = "wasm32"))]
+        Some(color) => format!("{}#{:08x}", path, color),
+        _ => path.into(),
+    }
+}
+
 fn embed_image(
     global_embedded_resources: &RefCell<HashMap<String, EmbeddedResources>>,
     embed_files: EmbedResourcesKind,
     path: &str,
     _scale_factor: f64,
+    _options: &ImageProcessingOptions,
     diag: &mut BuildDiagnostics,
     source_location: &Option<crate::diagnostics::SourceLocation>,
 ) -> ImageReference {
     let mut resources = global_embedded_resources.borrow_mut();
     let maybe_id = resources.len();
-    let e = match resources.entry(path.into()) {
+    let e = match resources.entry(resource_key(path, _options)) {
         std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
         std::collections::hash_map::Entry::Vacant(e) => {
             // Check that the file exists, so that later we can unwrap safely in the generators, etc.
             if let Some(file) = crate::fileaccess::load_file(std::path::Path::new(path)) {
                 let mut kind = EmbeddedResourcesKind::RawData;
                 #[cfg(not(target_arch = "wasm32"))]
-                if embed_files == EmbedResourcesKind::EmbedTextures {
-                    match load_image(file, _scale_factor) {
+                if embed_files != EmbedResourcesKind::EmbedTextures
+                    && _options.colorize.is_some()
+                    && is_svg(path)
+                {
+                    match colorize_svg(file, _options) {
+                        Ok(data) => kind = EmbeddedResourcesKind::ProcessedData(data),
+                        Err(err) => {
+                            diag.push_error(
+                                format!("Cannot process image file {}: {}", path, err),
+                                source_location,
+                            );
+                            return ImageReference::None;
+                        }
+                    }
+                } else if embed_files != EmbedResourcesKind::EmbedTextures
+                    && (_options.colorize.is_some()
+                        || (embed_files == EmbedResourcesKind::EmbedAllResources
+                            && (_options.max_size.is_some() || _options.recompress)))
+                    && !is_svg(path)
+                {
+                    match process_image(file, _options) {
+                        Ok(Some(data)) => kind = EmbeddedResourcesKind::ProcessedData(data),
+                        Ok(None) => {}
+                        Err(err) => {
+                            diag.push_error(
+                                format!("Cannot process image file {}: {}", path, err),
+                                source_location,
+                            );
+                            return ImageReference::None;
+                        }
+                    }
+                } else if embed_files == EmbedResourcesKind::EmbedTextures {
+                    match load_image(file, _scale_factor, _options) {
                         Ok((img, original_size)) => {
                             kind = EmbeddedResourcesKind::TextureData(generate_texture(
                                 img,
@@ -110,18 +188,22 @@ fn embed_image(
             }
         }
     };
-    let is_texture = matches!(e.kind, EmbeddedResourcesKind::TextureData { .. });
-    if is_texture {
-        ImageReference::EmbeddedTexture { resource_id: e.id }
-    } else {
-        ImageReference::EmbeddedData {
+    match e.kind {
+        EmbeddedResourcesKind::TextureData { .. } => {
+            ImageReference::EmbeddedTexture { resource_id: e.id }
+        }
+        EmbeddedResourcesKind::ProcessedData(_) => ImageReference::EmbeddedData {
+            resource_id: e.id,
+            extension: if is_svg(path) { "svg" } else { "png" }.into(),
+        },
+        _ => ImageReference::EmbeddedData {
             resource_id: e.id,
             extension: std::path::Path::new(path)
                 .extension()
                 .and_then(|e| e.to_str())
                 .map(|x| x.to_string())
                 .unwrap_or_default(),
-        }
+        },
     }
 }
 
@@ -251,18 +333,113 @@ fn convert_image(image: image::RgbaImage, format: PixelFormat, rect: Rect) -> Ve
     }
 }
 
+fn is_svg(path: &str) -> bool {
+    path.ends_with(".svg") || path.ends_with(".svgz")
+}
+
+/// Returns the factor by which an image of the given size needs to be downscaled so that it fits
+/// in the `max_size` of the options.
+fn max_size_factor(width: f64, height: f64, options: &ImageProcessingOptions) -> f64 {
+    match options.max_size {
+        Some(max_size) if width.max(height) > max_size as f64 => {
+            max_size as f64 / width.max(height)
+        }
+        _ => 1.,
+    }
+}
+
+/// Downscales and re-encodes the image according to the options.
+/// Returns None if the original file should be embedded as is.
+#[cfg(not(target_arch = "wasm32"))]
+fn process_image(
+    file: crate::fileaccess::VirtualFile,
+    options: &ImageProcessingOptions,
+) -> image::ImageResult<Option<Vec<u8>>> {
+    use image::ImageEncoder;
+    let original_data = file.read();
+    let mut image = image::load_from_memory(&original_data)?;
+    let (width, height) = image.dimensions();
+    let downscale = max_size_factor(width as f64, height as f64, options) < 1.;
+    if let (true, Some(max_size)) = (downscale, options.max_size) {
+        // resize() keeps the aspect ratio
+        image = image.resize(max_size, max_size, image::imageops::FilterType::Lanczos3);
+    } else if !options.recompress && options.colorize.is_none() {
+        return Ok(None);
+    }
+    if let Some(color) = options.colorize {
+        let mut rgba = image.to_rgba8();
+        colorize(&mut rgba, color);
+        image = image::DynamicImage::ImageRgba8(rgba);
+    }
+
+    let mut data = Vec::new();
+    image::codecs::png::PngEncoder::new_with_quality(
+        &mut data,
+        image::codecs::png::CompressionType::Best,
+        image::codecs::png::FilterType::Adaptive,
+    )
+    .write_image(image.as_bytes(), image.width(), image.height(), image.color())?;
+
+    // Recompressing an image that was not resized is only worth it if the result is smaller
+    if !downscale && options.colorize.is_none() && data.len() >= original_data.len() {
+        return Ok(None);
+    }
+    Ok(Some(data))
+}
+
+/// Uses the image as an alpha mask: all the pixels get the color (encoded as ARGB), and the
+/// alpha of the color multiplied by their own alpha.
+#[cfg(not(target_arch = "wasm32"))]
+fn colorize(image: &mut image::RgbaImage, color: u32) {
+    let [a, r, g, b] = color.to_be_bytes();
+    for p in image.pixels_mut() {
+        let alpha = (p[3] as u32 * a as u32 + 127) / 255;
+        *p = image::Rgba([r, g, b, alpha as u8]);
+    }
+}
+
+/// Replaces the paint of all the fills and strokes of the SVG document by the color of the
+/// `colorize` option, and returns the document serialized again.
+#[cfg(not(target_arch = "wasm32"))]
+fn colorize_svg(
+    file: crate::fileaccess::VirtualFile,
+    options: &ImageProcessingOptions,
+) -> Result<Vec<u8>, usvg::Error> {
+    let [a, r, g, b] = options.colorize.unwrap_or_default().to_be_bytes();
+    let alpha = a as f64 / 255.;
+    let usvg_options = usvg::Options::default();
+    let tree = usvg::Tree::from_data(&file.read(), &usvg_options.to_ref())?;
+    for mut node in tree.root().descendants() {
+        if let usvg::NodeKind::Path(ref mut path) = *node.borrow_mut() {
+            if let Some(fill) = &mut path.fill {
+                fill.paint = usvg::Paint::Color(usvg::Color::new_rgb(r, g, b));
+                fill.opacity = usvg::Opacity::new(fill.opacity.value() * alpha);
+            }
+            if let Some(stroke) = &mut path.stroke {
+                stroke.paint = usvg::Paint::Color(usvg::Color::new_rgb(r, g, b));
+                stroke.opacity = usvg::Opacity::new(stroke.opacity.value() * alpha);
+            }
+        }
+    }
+    Ok(tree.to_string(&usvg::XmlOptions::default()).into_bytes())
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn load_image(
     file: crate::fileaccess::VirtualFile,
     scale_factor: f64,
+    options: &ImageProcessingOptions,
 ) -> image::ImageResult<(image::RgbaImage, Size)> {
-    if file.path.ends_with(".svg") || file.path.ends_with(".svgz") {
-        let options = usvg::Options::default();
+    // The size of the image as seen by the program: a variant for a higher scale factor
+    // has the same size as the base image.
+    let variant_factor = options.scale_factor.unwrap_or(1) as f64;
+    if is_svg(&file.path) {
+        let usvg_options = usvg::Options::default();
         let tree = match file.builtin_contents {
-            Some(data) => usvg::Tree::from_data(data, &options.to_ref()),
+            Some(data) => usvg::Tree::from_data(data, &usvg_options.to_ref()),
             None => usvg::Tree::from_data(
                 std::fs::read(file.path.as_ref()).map_err(image::ImageError::IoError)?.as_slice(),
-                &options.to_ref(),
+                &usvg_options.to_ref(),
             ),
         }
         .map_err(|e| {
@@ -273,6 +450,12 @@ fn load_image(
         })?;
         // TODO: ideally we should find the size used for that `Image`
         let original_size = tree.svg_node().size;
+        let scale_factor = scale_factor
+            * max_size_factor(
+                original_size.width() * scale_factor,
+                original_size.height() * scale_factor,
+                options,
+            );
         let width = original_size.width() * scale_factor;
         let height = original_size.height() * scale_factor;
 
@@ -294,10 +477,16 @@ fn load_image(
         .ok_or_else(size_error)?;
         return image::RgbaImage::from_raw(width as u32, height as u32, buffer)
             .ok_or_else(size_error)
-            .map(|img| {
+            .map(|mut img| {
+                if let Some(color) = options.colorize {
+                    colorize(&mut img, color);
+                }
                 (
                     img,
-                    Size { width: original_size.width() as _, height: original_size.height() as _ },
+                    Size {
+                        width: (original_size.width() / variant_factor) as _,
+                        height: (original_size.height() / variant_factor) as _,
+                    },
                 )
             });
     }
@@ -308,6 +497,13 @@ fn load_image(
     }
     .map(|mut image| {
         let (original_width, original_height) = image.dimensions();
+        let scale_factor = scale_factor.min(1.);
+        let scale_factor = scale_factor
+            * max_size_factor(
+                original_width as f64 * scale_factor,
+                original_height as f64 * scale_factor,
+                options,
+            );
 
         if scale_factor < 1. {
             image = image.resize_exact(
@@ -317,6 +513,17 @@ fn load_image(
             );
         }
 
-        (image.to_rgba8(), Size { width: original_width, height: original_height })
+        let mut image = image.to_rgba8();
+        if let Some(color) = options.colorize {
+            colorize(&mut image, color);
+        }
+
+        (
+            image,
+            Size {
+                width: (original_width as f64 / variant_factor) as u32,
+                height: (original_height as f64 / variant_factor) as u32,
+            },
+        )
     })
 }
@@ -40,9 +40,11 @@ pub fn ensure_window(
         repeated: Default::default(),
         states: Default::default(),
         transitions: Default::default(),
+        change_callbacks: Default::default(),
         child_of_layout: false,
         has_popup_child: false,
         layout_info_prop: Default::default(),
+        accessibility_props: Default::default(),
         is_flickable_viewport: false,
         item_index: Default::default(),
         item_index_of_first_children: Default::default(),
@@ -9,13 +9,18 @@
 //! It will also initialize proper geometry
 //! This pass must be called before the materialize_fake_properties as it going to be generate
 //! binding reference to fake properties
+//!
+//! The `scroll-to` and `ensure-visible` calls are also resolved here so that they refer to the
+//! Flickable of the ScrollView or the ListView they are called on.
 
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
-use crate::expression_tree::{BindingExpression, Expression, NamedReference};
+use crate::diagnostics::BuildDiagnostics;
+use crate::expression_tree::{BindingExpression, BuiltinFunction, Expression, NamedReference};
 use crate::langtype::{NativeClass, Type};
-use crate::object_tree::{Component, Element, ElementRc};
+use crate::object_tree::{visit_all_expressions, Component, Element, ElementRc};
 use crate::typeregister::TypeRegister;
 
 pub fn is_flickable_element(element: &ElementRc) -> bool {
@@ -168,3 +173,67 @@ fn set_binding_if_not_explicit(
         }
     }
 }
+
+/// Make the element reference passed to `scroll-to` and `ensure-visible` point to the Flickable itself
+/// when these functions are called on a ScrollView or a ListView.
+///
+/// Must be called after inlining, and before handle_flickable moves the children to the viewport.
+pub fn resolve_scroll_calls(component: &Rc<Component>, diag: &mut BuildDiagnostics) {
+    visit_all_expressions(component, |e, _| resolve_scroll_call(e, diag));
+}
+
+fn resolve_scroll_call(expr: &mut Expression, diag: &mut BuildDiagnostics) {
+    if let Expression::FunctionCall { function, arguments, source_location } = expr {
+        let name = match function.as_ref() {
+            Expression::BuiltinFunctionReference(BuiltinFunction::ScrollTo, _) => "scroll-to",
+            Expression::BuiltinFunctionReference(BuiltinFunction::EnsureVisible, _) => {
+                "ensure-visible"
+            }
+            _ => "",
+        };
+        if !name.is_empty() {
+            if let Some(Expression::ElementReference(weak_element)) = arguments.first_mut() {
+                let element = weak_element.upgrade().expect(
+                    "internal compiler error: weak element reference of a scroll call cannot be dangling",
+                );
+                match find_flickable(&element) {
+                    Some(flickable) => *weak_element = Rc::downgrade(&flickable),
+                    None => diag.push_error(
+                        format!(
+                            "{}() can only be called on a Flickable, a ScrollView or a ListView",
+                            name
+                        ),
+                        source_location,
+                    ),
+                }
+            }
+            if let Some(Expression::ElementReference(target)) = arguments.get(1) {
+                // The geometry of the target is queried at run-time, so it must stay in the item tree
+                if let Some(target) = target.upgrade() {
+                    target
+                        .borrow()
+                        .property_analysis
+                        .borrow_mut()
+                        .entry("x".into())
+                        .or_default()
+                        .is_set = true;
+                }
+            }
+        }
+    }
+    expr.visit_mut(|e| resolve_scroll_call(e, diag))
+}
+
+/// The element itself if it is a Flickable, or the closest Flickable in its children which is not repeated
+fn find_flickable(element: &ElementRc) -> Option<ElementRc> {
+    let mut queue = VecDeque::from([element.clone()]);
+    while let Some(elem) = queue.pop_front() {
+        if is_flickable_element(&elem) {
+            return Some(elem);
+        }
+        queue.extend(
+            elem.borrow().children.iter().filter(|c| c.borrow().repeated.is_none()).cloned(),
+        );
+    }
+    None
+}
@@ -166,6 +166,15 @@ fn inline_element(
         }
     }
 
+    // The accessible properties set on the element take precedence over the ones of the inlined root
+    for (k, nr) in inlined_component.root_element.borrow().accessibility_props.0.iter() {
+        elem_mut.accessibility_props.0.entry(k.clone()).or_insert_with(|| nr.clone());
+    }
+
+    elem_mut
+        .change_callbacks
+        .extend(inlined_component.root_element.borrow().change_callbacks.iter().cloned());
+
     core::mem::drop(elem_mut);
 
     // Now fixup all binding and reference
@@ -177,6 +186,14 @@ fn inline_element(
         fixup_reference(&mut p.x, &mapping);
         fixup_reference(&mut p.y, &mapping);
     }
+    // The setup code of the inlined component now runs in the setup code of the root component
+    root_component.setup_code.borrow_mut().extend(
+        inlined_component.setup_code.borrow().iter().map(|code| {
+            let mut code = code.clone();
+            fixup_setup_code(&mut code, &mapping);
+            code
+        }),
+    );
 }
 
 // Duplicate the element elem and all its children. And fill the mapping to point from the old to the new
@@ -212,8 +229,11 @@ fn duplicate_element_with_mapping(
             .iter()
             .map(|t| duplicate_transition(t, mapping, root_component, priority_delta))
             .collect(),
+        // We will do the fixup of the references in the change callbacks later
+        change_callbacks: elem.change_callbacks.clone(),
         child_of_layout: elem.child_of_layout,
         layout_info_prop: elem.layout_info_prop.clone(),
+        accessibility_props: elem.accessibility_props.clone(),
         named_references: Default::default(),
         item_index: Default::default(), // Not determined yet
         item_index_of_first_children: Default::default(),
@@ -289,6 +309,9 @@ fn duplicate_sub_component(
         fixup_reference(&mut p.x, mapping);
         fixup_reference(&mut p.y, mapping);
     }
+    for code in new_component.setup_code.borrow_mut().iter_mut() {
+        fixup_setup_code(code, mapping);
+    }
     new_component
         .root_constraints
         .borrow_mut()
@@ -377,6 +400,21 @@ fn fixup_reference(nr: &mut NamedReference, mapping: &HashMap<ByAddress<ElementR
     }
 }
 
+/// Fixup the references to the elements and their properties in a setup code expression
+fn fixup_setup_code(expr: &mut Expression, mapping: &HashMap<ByAddress<ElementRc>, ElementRc>) {
+    fn fixup_named_references(
+        expr: &mut Expression,
+        mapping: &HashMap<ByAddress<ElementRc>, ElementRc>,
+    ) {
+        expr.visit_mut(|sub| fixup_named_references(sub, mapping));
+        if let Expression::PropertyReference(nr) | Expression::CallbackReference(nr) = expr {
+            fixup_reference(nr, mapping);
+        }
+    }
+    fixup_named_references(expr, mapping);
+    fixup_element_references(expr, mapping);
+}
+
 fn fixup_element_references(
     expr: &mut Expression,
     mapping: &HashMap<ByAddress<ElementRc>, ElementRc>,
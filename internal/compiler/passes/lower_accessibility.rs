@@ -0,0 +1,65 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Pass that collects the accessible properties (`accessible-role`, `accessible-label`, ...)
+//! of each element into `Element::accessibility_props`, so that the generators can expose
+//! them to the platform accessibility APIs.
+//!
+//! Must be run before materialize_fake_properties
+
+use crate::diagnostics::BuildDiagnostics;
+use crate::expression_tree::{Expression, NamedReference};
+use crate::langtype::Type;
+use crate::object_tree::{Component, ElementRc};
+use crate::typeregister::{RESERVED_ACCESSIBILITY_ACTIONS, RESERVED_ACCESSIBILITY_PROPERTIES};
+use std::rc::Rc;
+
+pub fn lower_accessibility_properties(component: &Rc<Component>, diag: &mut BuildDiagnostics) {
+    crate::object_tree::recurse_elem_including_sub_components_no_borrow(
+        component,
+        &(),
+        &mut |elem, _| {
+            if elem.borrow().repeated.is_some() {
+                return;
+            };
+            apply_builtin(elem);
+
+            if let Some(role_binding) = elem.borrow().bindings.get("accessible-role") {
+                let role_binding = role_binding.borrow();
+                if let Expression::EnumerationValue(val) = &role_binding.expression {
+                    debug_assert_eq!(val.enumeration.name, "AccessibleRole");
+                } else {
+                    diag.push_error(
+                        "The `accessible-role` property must be a constant expression".into(),
+                        &*role_binding,
+                    );
+                }
+            }
+
+            let props = std::iter::once("accessible-role")
+                .chain(RESERVED_ACCESSIBILITY_PROPERTIES.iter().map(|(name, _)| *name))
+                .chain(RESERVED_ACCESSIBILITY_ACTIONS.iter().copied())
+                .filter(|name| elem.borrow().bindings.contains_key(*name))
+                .map(|name| (name.to_string(), NamedReference::new(elem, name)))
+                .collect::<Vec<_>>();
+            elem.borrow_mut().accessibility_props.0.extend(props);
+        },
+    )
+}
+
+/// Give a default role and label to the builtin elements that have one
+fn apply_builtin(e: &ElementRc) {
+    let is_text = matches!(&e.borrow().base_type, Type::Builtin(b) if b.name == "Text");
+    if !is_text || e.borrow().is_binding_set("accessible-role", false) {
+        return;
+    }
+    let text_role = crate::typeregister::BUILTIN_ENUMS.with(|e| {
+        e.AccessibleRole.clone().try_value_from_string("text").expect("AccessibleRole::text")
+    });
+    let text = NamedReference::new(e, "text");
+    let mut elem = e.borrow_mut();
+    elem.set_binding_if_not_set("accessible-role".into(), || {
+        Expression::EnumerationValue(text_role)
+    });
+    elem.set_binding_if_not_set("accessible-label".into(), || Expression::PropertyReference(text));
+}
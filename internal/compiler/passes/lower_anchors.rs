@@ -0,0 +1,175 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Pass that lowers the `anchor` property.
+//!
+//! An element with an `anchor` gets its `x` and `y` bound to the position of the anchor element,
+//! mapped into the coordinates of the element's parent, plus `anchor-x` and `anchor-y` percent
+//! of the anchor's size.
+
+use crate::diagnostics::BuildDiagnostics;
+use crate::expression_tree::{Expression, NamedReference, Unit};
+use crate::langtype::Type;
+use crate::object_tree::*;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+pub fn lower_anchors(component: &Rc<Component>, diag: &mut BuildDiagnostics) {
+    let mut parents = HashMap::new();
+    let mut anchored = Vec::new();
+    recurse_elem(&component.root_element, &None, &mut |elem, parent: &Option<ElementRc>| {
+        if let Some(parent) = parent {
+            parents.insert(Rc::as_ptr(elem), parent.clone());
+        }
+        if elem.borrow().bindings.contains_key("anchor") {
+            anchored.push(elem.clone());
+        }
+        Some(elem.clone())
+    });
+    if anchored.is_empty() {
+        return;
+    }
+
+    // Returns the element and all its ancestors, up to the root
+    let ancestors = |elem: &ElementRc| {
+        let mut result = vec![elem.clone()];
+        while let Some(parent) = parents.get(&Rc::as_ptr(result.last().unwrap())) {
+            result.push(parent.clone());
+        }
+        result
+    };
+
+    // For each anchored element, the elements whose position it depends on
+    let mut dependencies = HashMap::new();
+
+    for elem in &anchored {
+        let binding = elem.borrow_mut().bindings.remove("anchor").unwrap().into_inner();
+        let anchor = match &binding.expression {
+            Expression::ElementReference(anchor) => anchor.upgrade().unwrap(),
+            _ => {
+                debug_assert!(diag.has_error());
+                continue;
+            }
+        };
+        let parent = match parents.get(&Rc::as_ptr(elem)) {
+            Some(parent) => parent.clone(),
+            None => {
+                diag.push_error(
+                    "The root element of a component cannot have an anchor".into(),
+                    &binding,
+                );
+                continue;
+            }
+        };
+        if crate::passes::lower_layout::is_layout_element(&parent) {
+            diag.push_error("An element in a layout cannot have an anchor".into(), &binding);
+            continue;
+        }
+        for prop in ["x", "y"] {
+            if elem.borrow().bindings.contains_key(prop) {
+                diag.push_error(format!("Cannot specify both 'anchor' and '{}'", prop), &binding);
+            }
+        }
+
+        let anchor_chain = ancestors(&anchor);
+        let parent_chain = ancestors(&parent);
+        let (anchor_len, parent_len) =
+            match anchor_chain.iter().enumerate().find_map(|(i, a)| {
+                parent_chain.iter().position(|p| Rc::ptr_eq(a, p)).map(|j| (i, j))
+            }) {
+                Some(lengths) => lengths,
+                None => {
+                    diag.push_error(
+                        "The anchor must be an element of the same component".into(),
+                        &binding,
+                    );
+                    continue;
+                }
+            };
+        let anchor_chain = &anchor_chain[..anchor_len];
+        let parent_chain = &parent_chain[..parent_len];
+        if anchor_chain.iter().any(|a| Rc::ptr_eq(a, elem)) {
+            diag.push_error(
+                "An element cannot be anchored to itself or to one of its children".into(),
+                &binding,
+            );
+            continue;
+        }
+        if anchor_chain.iter().any(|a| a.borrow().repeated.is_some()) {
+            diag.push_error(
+                "The anchor cannot be within a repeated or conditional element".into(),
+                &binding,
+            );
+            continue;
+        }
+
+        for (pos, size, relative) in [("x", "width", "anchor-x"), ("y", "height", "anchor-y")] {
+            // The default value of a percent property is 100%, but the anchor point defaults to
+            // the top left corner.
+            if !elem.borrow().bindings.contains_key(relative) {
+                let mut default_binding = binding.clone();
+                default_binding.expression = Expression::NumberLiteral(0., Unit::Percent);
+                elem.borrow_mut().bindings.insert(relative.into(), default_binding.into());
+            }
+            // The position of the anchor, relative to the common ancestor, minus the position
+            // of the parent, relative to the common ancestor.
+            let mut expression = Expression::BinaryExpression {
+                lhs: Box::new(Expression::PropertyReference(NamedReference::new(&anchor, size))),
+                rhs: Box::new(Expression::BinaryExpression {
+                    lhs: Box::new(Expression::PropertyReference(NamedReference::new(
+                        elem, relative,
+                    ))),
+                    rhs: Box::new(Expression::NumberLiteral(100., Unit::Percent)),
+                    op: '/',
+                }),
+                op: '*',
+            };
+            for (a, op) in
+                anchor_chain.iter().map(|a| (a, '+')).chain(parent_chain.iter().map(|p| (p, '-')))
+            {
+                expression = Expression::BinaryExpression {
+                    lhs: Box::new(expression),
+                    rhs: Box::new(Expression::PropertyReference(NamedReference::new(a, pos))),
+                    op,
+                };
+            }
+            debug_assert_eq!(expression.ty(), Type::LogicalLength);
+            let mut new_binding = binding.clone();
+            new_binding.expression = expression;
+            elem.borrow_mut().bindings.insert(pos.into(), new_binding.into());
+        }
+
+        dependencies.insert(
+            Rc::as_ptr(elem),
+            (anchor_chain.iter().chain(parent_chain.iter()).cloned().collect::<Vec<_>>(), binding),
+        );
+    }
+
+    // Report cycles, such as two elements anchored to each other.
+    for elem in &anchored {
+        let mut visited = vec![Rc::as_ptr(elem)];
+        let mut stack = match dependencies.get(&Rc::as_ptr(elem)) {
+            Some((deps, _)) => deps.clone(),
+            None => continue,
+        };
+        while let Some(dep) = stack.pop() {
+            if Rc::ptr_eq(&dep, elem) {
+                diag.push_error(
+                    "The position of this element depends on itself through its anchor".into(),
+                    &dependencies[&Rc::as_ptr(elem)].1,
+                );
+                // Don't report the binding loop of the position as well
+                elem.borrow_mut().bindings.remove("x");
+                elem.borrow_mut().bindings.remove("y");
+                break;
+            }
+            if visited.contains(&Rc::as_ptr(&dep)) {
+                continue;
+            }
+            visited.push(Rc::as_ptr(&dep));
+            if let Some((deps, _)) = dependencies.get(&Rc::as_ptr(&dep)) {
+                stack.extend(deps.iter().cloned());
+            }
+        }
+    }
+}
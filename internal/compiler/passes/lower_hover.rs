@@ -0,0 +1,114 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Pass that lowers the `has-hover` property and the `pointer-entered` and `pointer-exited`
+//! callbacks of any element into a `HoverArea` element.
+//! For example `f := Foo { pointer-entered => {...} background: f.has-hover ? red : blue; }` is mapped to
+//! `HoverArea { has-hover <=> f.has-hover; pointer-entered => {...} f := Foo { ... } }`
+//!
+//! Must be run before materialize_fake_properties
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use by_address::ByAddress;
+
+use crate::diagnostics::BuildDiagnostics;
+use crate::expression_tree::{BindingExpression, NamedReference};
+use crate::langtype::Type;
+use crate::object_tree::{self, Component, Element, ElementRc};
+use crate::typeregister::{TypeRegister, RESERVED_HOVER_CALLBACKS};
+
+pub fn lower_hover(
+    component: &Rc<Component>,
+    type_register: &TypeRegister,
+    diag: &mut BuildDiagnostics,
+) {
+    // The elements whose reserved `has-hover` property is read somewhere
+    let mut hover_read = HashSet::new();
+    object_tree::visit_all_named_references(component, &mut |nr| {
+        let element = nr.element();
+        if nr.name() == "has-hover" && !has_native_hover(&element) {
+            hover_read.insert(ByAddress(element));
+        }
+    });
+    let needs_hover_area = |e: &ElementRc| {
+        hover_read.contains(&ByAddress(e.clone()))
+            || RESERVED_HOVER_CALLBACKS.iter().any(|name| e.borrow().bindings.contains_key(*name))
+    };
+
+    if needs_hover_area(&component.root_element) {
+        diag.push_warning(
+            "The has-hover property and the pointer-entered and pointer-exited callbacks cannot be used on the root element, they will not be applied".into(),
+            &*component.root_element.borrow(),
+        );
+    }
+
+    object_tree::recurse_elem_including_sub_components_no_borrow(component, &(), &mut |elem, _| {
+        if matches!(&elem.borrow().base_type, Type::Builtin(b) if b.name == "HoverArea") {
+            return;
+        }
+
+        let old_children = {
+            let mut elem = elem.borrow_mut();
+            let new_children = Vec::with_capacity(elem.children.len());
+            std::mem::replace(&mut elem.children, new_children)
+        };
+
+        for mut child in old_children {
+            if child.borrow().repeated.is_some() {
+                let root_elem = child.borrow().base_type.as_component().root_element.clone();
+                if needs_hover_area(&root_elem) {
+                    object_tree::inject_element_as_repeated_element(
+                        &child,
+                        create_hover_area(&root_elem, type_register),
+                    )
+                }
+            } else if needs_hover_area(&child) {
+                let new_child = create_hover_area(&child, type_register);
+                object_tree::adjust_geometry_for_injected_parent(&new_child, &child);
+                new_child.borrow_mut().children.push(child);
+                child = new_child;
+            }
+
+            elem.borrow_mut().children.push(child);
+        }
+    });
+}
+
+/// Returns true if `has-hover` is a property of the element itself, like the one of the
+/// TouchArea, and not the reserved property.
+fn has_native_hover(elem: &ElementRc) -> bool {
+    let elem = elem.borrow();
+    if elem.property_declarations.contains_key("has-hover") {
+        return true;
+    }
+    match &elem.base_type {
+        Type::Builtin(b) => b.properties.contains_key("has-hover"),
+        Type::Component(c) => has_native_hover(&c.root_element),
+        _ => false,
+    }
+}
+
+fn create_hover_area(child: &ElementRc, type_register: &TypeRegister) -> ElementRc {
+    // The callbacks are moved to the HoverArea, while the property is shared
+    let mut bindings = RESERVED_HOVER_CALLBACKS
+        .iter()
+        .filter_map(|name| child.borrow_mut().bindings.remove_entry(*name))
+        .collect::<object_tree::BindingsMap>();
+    if !has_native_hover(child) {
+        bindings.insert(
+            "has-hover".into(),
+            BindingExpression::new_two_way(NamedReference::new(child, "has-hover")).into(),
+        );
+    }
+    let element = Element {
+        id: format!("{}-hover", child.borrow().id),
+        base_type: type_register.lookup_element("HoverArea").unwrap(),
+        enclosing_component: child.borrow().enclosing_component.clone(),
+        bindings,
+        ..Default::default()
+    };
+    Rc::new(RefCell::new(element))
+}
@@ -59,7 +59,7 @@ fn lower_element_layout(
         return;
     };
     match base_type.name.as_str() {
-        "Row" => panic!("Error caught at element lookup time"),
+        "Row" | "Column" => panic!("Error caught at element lookup time"),
         "GridLayout" => lower_grid_layout(component, elem, diag),
         "HorizontalLayout" => lower_box_layout(elem, diag, Orientation::Horizontal),
         "VerticalLayout" => lower_box_layout(elem, diag, Orientation::Vertical),
@@ -100,6 +100,7 @@ fn lower_grid_layout(
 ) {
     let mut grid = GridLayout {
         elems: Default::default(),
+        policies: Default::default(),
         geometry: LayoutGeometry::new(grid_layout_element),
         dialog_button_roles: None,
     };
@@ -115,20 +116,46 @@ fn lower_grid_layout(
 
     let mut row = 0;
     let mut col = 0;
+    let mut policy_col = 0;
 
     let layout_children = std::mem::take(&mut grid_layout_element.borrow_mut().children);
     let mut collected_children = Vec::new();
     for layout_child in layout_children {
-        let is_row = if let Type::Builtin(be) = &layout_child.borrow().base_type {
-            be.name == "Row"
+        let builtin_name = if let Type::Builtin(be) = &layout_child.borrow().base_type {
+            Some(be.name.clone())
         } else {
-            false
+            None
         };
-        if is_row {
+        let is_row = builtin_name.as_deref() == Some("Row");
+        if builtin_name.as_deref() == Some("Column") {
+            if let Some(c) =
+                layout_child.borrow_mut().bindings.remove("col").and_then(|e| {
+                    eval_const_expr(&e.borrow().expression, "col", &*e.borrow(), diag)
+                })
+            {
+                policy_col = c;
+            }
+            if !layout_child.borrow().children.is_empty() {
+                diag.push_error(
+                    "A Column in a GridLayout cannot have children".into(),
+                    &*layout_child.borrow(),
+                );
+            }
+            grid.add_policy(
+                &layout_child,
+                grid_layout_element,
+                Orientation::Horizontal,
+                policy_col,
+                diag,
+            );
+            policy_col += 1;
+            component.optimized_elements.borrow_mut().push(layout_child);
+        } else if is_row {
             if col > 0 {
                 row += 1;
                 col = 0;
             }
+            grid.add_policy(&layout_child, grid_layout_element, Orientation::Vertical, row, diag);
             let row_children = std::mem::take(&mut layout_child.borrow_mut().children);
             for x in row_children {
                 grid.add_element(
@@ -235,6 +262,79 @@ fn add_element(
         )
     }
 
+    /// Move the sizing properties set on a `Column` or `Row` element to the layout element,
+    /// and record them as the policy of the column or row with the given index.
+    fn add_policy(
+        &mut self,
+        policy_element: &ElementRc,
+        grid_layout_element: &ElementRc,
+        orientation: Orientation,
+        index: u16,
+        diag: &mut BuildDiagnostics,
+    ) {
+        let (prefix, size, min, max, preferred, stretch) = match orientation {
+            Orientation::Horizontal => (
+                "column",
+                "width",
+                "min-width",
+                "max-width",
+                "preferred-width",
+                "horizontal-stretch",
+            ),
+            Orientation::Vertical => (
+                "row",
+                "height",
+                "min-height",
+                "max-height",
+                "preferred-height",
+                "vertical-stretch",
+            ),
+        };
+        let move_binding = |name: &str| {
+            let binding = policy_element.borrow_mut().bindings.remove(name)?;
+            let ty = policy_element.borrow().lookup_property(name).property_type;
+            let nr =
+                create_new_prop(grid_layout_element, &format!("{}{}-{}", prefix, index, name), ty);
+            grid_layout_element.borrow_mut().bindings.insert(nr.name().into(), binding);
+            Some(nr)
+        };
+
+        if policy_element.borrow().bindings.contains_key(size) {
+            for name in [min, max] {
+                if let Some(binding) = policy_element.borrow().bindings.get(name) {
+                    diag.push_error(
+                        format!("Cannot specify both '{}' and '{}'", size, name),
+                        &*binding.borrow(),
+                    );
+                }
+            }
+        }
+        let fixed = move_binding(size);
+        let min_ref = move_binding(min).or_else(|| fixed.clone());
+        let max_ref = move_binding(max).or(fixed);
+        let preferred = move_binding(preferred);
+        let stretch = move_binding(stretch);
+
+        let mut constraints = LayoutConstraints::default();
+        match orientation {
+            Orientation::Horizontal => {
+                constraints.min_width = min_ref;
+                constraints.max_width = max_ref;
+                constraints.preferred_width = preferred;
+                constraints.horizontal_stretch = stretch;
+            }
+            Orientation::Vertical => {
+                constraints.min_height = min_ref;
+                constraints.max_height = max_ref;
+                constraints.preferred_height = preferred;
+                constraints.vertical_stretch = stretch;
+            }
+        }
+        if constraints.has_explicit_restrictions() {
+            self.policies.push(GridLayoutPolicy { orientation, index, constraints });
+        }
+    }
+
     fn add_element_with_coord(
         &mut self,
         item_element: &ElementRc,
@@ -393,6 +493,7 @@ fn lower_dialog_layout(
 ) {
     let mut grid = GridLayout {
         elems: Default::default(),
+        policies: Default::default(),
         geometry: LayoutGeometry::new(dialog_element),
         dialog_button_roles: None,
     };
@@ -494,6 +595,7 @@ fn lower_dialog_layout(
                                             layout_child,
                                             "clicked",
                                         )),
+                                        ..Default::default()
                                     });
                             }
                         }
@@ -0,0 +1,64 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Pass that lowers the `init`, `shown`, `hidden` and `will-destroy` callbacks of any element into a
+//! `LifecycleHandler` child element, which the runtime finds when the window is shown or hidden, or before
+//! a repeater destroys the component. `init` is called by the setup code of the component containing the
+//! element, which also runs when the component is instantiated by a `for` or an `if`.
+//! For example `Foo { init => {...} will-destroy => {...} }` is mapped to
+//! `Foo { Foo-lifecycle := LifecycleHandler { init => {...} will-destroy => {...} } }`, with
+//! `Foo-lifecycle.init()` in the setup code.
+//!
+//! The properties, callbacks and functions declared by the elements with the same names shadow these
+//! callbacks.
+//!
+//! Must be run after lower_layout and repeater_component, and before materialize_fake_properties
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::expression_tree::{Expression, NamedReference};
+use crate::object_tree::{self, Component, Element, ElementRc};
+use crate::typeregister::{self, TypeRegister, RESERVED_LIFECYCLE_CALLBACKS};
+
+pub fn lower_lifecycle(component: &Rc<Component>, type_register: &TypeRegister) {
+    object_tree::recurse_elem_including_sub_components_no_borrow(component, &(), &mut |elem, _| {
+        let bindings = RESERVED_LIFECYCLE_CALLBACKS
+            .iter()
+            .filter(|name| is_lifecycle_callback(elem, name))
+            .filter_map(|name| elem.borrow_mut().bindings.remove_entry(*name))
+            .collect::<object_tree::BindingsMap>();
+        if bindings.is_empty() {
+            return;
+        }
+        let has_init = bindings.contains_key("init");
+        let enclosing_component = elem.borrow().enclosing_component.clone();
+        let handler = Rc::new(RefCell::new(Element {
+            id: format!("{}-lifecycle", elem.borrow().id),
+            base_type: type_register.lookup_element("LifecycleHandler").unwrap(),
+            enclosing_component: enclosing_component.clone(),
+            bindings,
+            ..Default::default()
+        }));
+        if has_init {
+            enclosing_component.upgrade().unwrap().setup_code.borrow_mut().push(
+                Expression::FunctionCall {
+                    function: Box::new(Expression::CallbackReference(NamedReference::new(
+                        &handler, "init",
+                    ))),
+                    arguments: vec![],
+                    source_location: None,
+                },
+            );
+        }
+        elem.borrow_mut().children.insert(0, handler);
+    });
+}
+
+/// Returns true if `name` is the reserved callback, and not a property, a callback or a function
+/// declared by the element or by its base types.
+fn is_lifecycle_callback(elem: &ElementRc, name: &str) -> bool {
+    let elem = elem.borrow();
+    !elem.property_declarations.contains_key(name)
+        && typeregister::is_lifecycle_callback(&elem.base_type, name)
+}
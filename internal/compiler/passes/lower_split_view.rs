@@ -0,0 +1,241 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Pass that lowers the SplitView: the panes are placed next to each other according to their ratio,
+//! and a splitter handle is added between each pair of panes.
+//!
+//! Must be done before inlining, like the lowering of the TabWidget, because the elements it adds
+//! come from the style and must be inlined.
+
+use crate::diagnostics::BuildDiagnostics;
+use crate::expression_tree::{BindingExpression, Expression, NamedReference, Unit};
+use crate::langtype::Type;
+use crate::object_tree::*;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+pub async fn lower_split_view(
+    component: &Rc<Component>,
+    type_loader: &mut crate::typeloader::TypeLoader<'_>,
+    diag: &mut BuildDiagnostics,
+) {
+    // Ignore import errors
+    let mut build_diags_to_ignore = BuildDiagnostics::default();
+    let split_view_impl = type_loader
+        .import_type("std-widgets.slint", "SplitViewImpl", &mut build_diags_to_ignore)
+        .await
+        .expect("can't load SplitViewImpl from std-widgets.slint");
+    let splitter_impl = type_loader
+        .import_type("std-widgets.slint", "SplitterImpl", &mut build_diags_to_ignore)
+        .await
+        .expect("can't load SplitterImpl from std-widgets.slint");
+    let rectangle_type =
+        type_loader.global_type_registry.borrow().lookup_element("Rectangle").unwrap();
+
+    recurse_elem_including_sub_components_no_borrow(component, &(), &mut |elem, _| {
+        if elem.borrow().base_type.to_string() == "SplitView" {
+            process_split_view(elem, &split_view_impl, &splitter_impl, &rectangle_type, diag);
+        }
+    })
+}
+
+fn process_split_view(
+    elem: &ElementRc,
+    split_view_impl: &Type,
+    splitter_impl: &Type,
+    rectangle_type: &Type,
+    diag: &mut BuildDiagnostics,
+) {
+    elem.borrow_mut().base_type = split_view_impl.clone();
+    let children = std::mem::take(&mut elem.borrow_mut().children);
+    let mut panes = Vec::new();
+    for child in children {
+        if child.borrow().repeated.is_some() {
+            diag.push_error(
+                "dynamic panes ('if' or 'for') are currently not supported".into(),
+                &*child.borrow(),
+            );
+            continue;
+        }
+        if child.borrow().base_type.to_string() != "Pane" {
+            assert!(diag.has_error());
+            continue;
+        }
+        process_pane(elem, &child, panes.last(), rectangle_type, diag);
+        panes.push(child);
+    }
+
+    let splitters = panes
+        .windows(2)
+        .enumerate()
+        .map(|(index, pair)| create_splitter(elem, &pair[0], &pair[1], index, splitter_impl))
+        .collect::<Vec<_>>();
+
+    let mut elem_mut = elem.borrow_mut();
+    elem_mut.bindings.insert(
+        "pane-count".into(),
+        RefCell::new(Expression::NumberLiteral(panes.len() as _, Unit::None).into()),
+    );
+    let sum = |name: &str, zero: Expression| {
+        panes.iter().map(|p| unless_collapsed(p, name, zero.clone())).reduce(|lhs, rhs| {
+            Expression::BinaryExpression { lhs: lhs.into(), rhs: rhs.into(), op: '+' }
+        })
+    };
+    let max = |name: &str| {
+        panes
+            .iter()
+            .map(|p| unless_collapsed(p, name, Expression::NumberLiteral(0., Unit::Px)))
+            .reduce(|lhs, rhs| crate::builtin_macros::min_max_expression(lhs, rhs, '>'))
+    };
+    let computed = [
+        ("total-ratio", sum("ratio", Expression::NumberLiteral(0., Unit::None))),
+        ("sum-min-width", sum("min-width", Expression::NumberLiteral(0., Unit::Px))),
+        ("sum-min-height", sum("min-height", Expression::NumberLiteral(0., Unit::Px))),
+        ("max-min-width", max("min-width")),
+        ("max-min-height", max("min-height")),
+    ];
+    for (name, expr) in computed {
+        if let Some(expr) = expr {
+            elem_mut.bindings.insert(name.into(), RefCell::new(expr.into()));
+        }
+    }
+    elem_mut.children = panes.into_iter().chain(splitters).collect();
+}
+
+/// Turn the pane into a Rectangle whose geometry is computed from its ratio and from the pane before it
+fn process_pane(
+    split_view: &ElementRc,
+    pane: &ElementRc,
+    previous_pane: Option<&ElementRc>,
+    rectangle_type: &Type,
+    diag: &mut BuildDiagnostics,
+) {
+    {
+        let mut pane_mut = pane.borrow_mut();
+        pane_mut.base_type = rectangle_type.clone();
+        for (name, ty) in [
+            ("ratio", Type::Float32),
+            ("collapsible", Type::Bool),
+            ("collapsed", Type::Bool),
+            ("size", Type::LogicalLength),
+            ("offset", Type::LogicalLength),
+        ] {
+            pane_mut.property_declarations.insert(name.to_owned(), ty.into());
+        }
+        pane_mut
+            .bindings
+            .entry("ratio".into())
+            .or_insert_with(|| RefCell::new(Expression::NumberLiteral(1., Unit::None).into()));
+    }
+
+    let size = Expression::Condition {
+        condition: property(pane, "collapsed").into(),
+        true_expr: Expression::NumberLiteral(0., Unit::Px).into(),
+        false_expr: Expression::BinaryExpression {
+            lhs: property(pane, "ratio").into(),
+            rhs: property(split_view, "ratio-scale").into(),
+            op: '*',
+        }
+        .into(),
+    };
+    let offset = match previous_pane {
+        Some(previous_pane) => Expression::BinaryExpression {
+            lhs: Expression::BinaryExpression {
+                lhs: property(previous_pane, "offset").into(),
+                rhs: property(previous_pane, "size").into(),
+                op: '+',
+            }
+            .into(),
+            rhs: property(split_view, "handle-size").into(),
+            op: '+',
+        },
+        None => Expression::NumberLiteral(0., Unit::Px),
+    };
+    pane.borrow_mut().bindings.insert("size".into(), RefCell::new(size.into()));
+    pane.borrow_mut().bindings.insert("offset".into(), RefCell::new(offset.into()));
+
+    let along = |horizontal: Expression, vertical: Expression| Expression::Condition {
+        condition: property(split_view, "vertical").into(),
+        true_expr: vertical.into(),
+        false_expr: horizontal.into(),
+    };
+    let geometry = [
+        ("x", along(property(pane, "offset"), Expression::NumberLiteral(0., Unit::Px))),
+        ("y", along(Expression::NumberLiteral(0., Unit::Px), property(pane, "offset"))),
+        ("width", along(property(pane, "size"), property(split_view, "cross-size"))),
+        ("height", along(property(split_view, "cross-size"), property(pane, "size"))),
+        ("visible", Expression::UnaryOp { sub: property(pane, "collapsed").into(), op: '!' }),
+    ];
+    for (name, expr) in geometry {
+        let old = pane.borrow_mut().bindings.insert(name.into(), RefCell::new(expr.into()));
+        if let Some(old) = old {
+            diag.push_error(
+                format!("The property '{}' cannot be set for Panes inside a SplitView", name),
+                &old.into_inner(),
+            );
+        }
+    }
+}
+
+fn create_splitter(
+    split_view: &ElementRc,
+    before: &ElementRc,
+    after: &ElementRc,
+    index: usize,
+    splitter_impl: &Type,
+) -> ElementRc {
+    let mut splitter = Element {
+        id: format!("{}-splitter{}", split_view.borrow().id, index),
+        base_type: splitter_impl.clone(),
+        enclosing_component: split_view.borrow().enclosing_component.clone(),
+        ..Default::default()
+    };
+    let offset = Expression::BinaryExpression {
+        lhs: property(before, "offset").into(),
+        rhs: property(before, "size").into(),
+        op: '+',
+    };
+    splitter.bindings.insert("offset".into(), RefCell::new(offset.into()));
+    for (name, element, property_name) in [
+        ("vertical", split_view, "vertical"),
+        ("thickness", split_view, "handle-size"),
+        ("cross-size", split_view, "cross-size"),
+        ("size-before", before, "size"),
+        ("size-after", after, "size"),
+        ("min-width-before", before, "min-width"),
+        ("min-height-before", before, "min-height"),
+        ("min-width-after", after, "min-width"),
+        ("min-height-after", after, "min-height"),
+        ("collapsible-before", before, "collapsible"),
+        ("collapsible-after", after, "collapsible"),
+    ] {
+        splitter
+            .bindings
+            .insert(name.into(), RefCell::new(property(element, property_name).into()));
+    }
+    for (name, element, property_name) in [
+        ("ratio-before", before, "ratio"),
+        ("ratio-after", after, "ratio"),
+        ("collapsed-before", before, "collapsed"),
+        ("collapsed-after", after, "collapsed"),
+    ] {
+        splitter.bindings.insert(
+            name.into(),
+            BindingExpression::new_two_way(NamedReference::new(element, property_name)).into(),
+        );
+    }
+    Rc::new(RefCell::new(splitter))
+}
+
+/// The value of the property of the pane, or `zero` when the pane is collapsed
+fn unless_collapsed(pane: &ElementRc, name: &str, zero: Expression) -> Expression {
+    Expression::Condition {
+        condition: property(pane, "collapsed").into(),
+        true_expr: zero.into(),
+        false_expr: property(pane, name).into(),
+    }
+}
+
+fn property(element: &ElementRc, name: &str) -> Expression {
+    Expression::PropertyReference(NamedReference::new(element, name))
+}
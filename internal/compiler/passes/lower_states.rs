@@ -2,6 +2,14 @@
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
 
 //! Pass that create a state property, and change all the binding to depend on that property
+//!
+//! The nested states are flattened: a nested state is active when its own condition and the
+//! conditions of the states that contain it are true, and it applies their property changes as well
+//! as its own. The `entered` and `exited` actions are lowered into a change callback of the state
+//! property, which compares the new state with the previous one, kept in another property, and runs
+//! the actions of the states that are exited and entered. The triggers are lowered into code that
+//! runs before the handler of their callback, and sets a property to the state they request, which
+//! the state property selects while no `when` condition is true.
 
 use crate::diagnostics::BuildDiagnostics;
 use crate::diagnostics::SourceLocation;
@@ -33,7 +41,7 @@ fn lower_state_in_element(
         return;
     }
     let has_transitions = !root_element.borrow().transitions.is_empty();
-    let state_property_name = compute_state_property_name(root_element);
+    let state_property_name = compute_state_property_name(root_element, "state");
     let state_property =
         Expression::PropertyReference(NamedReference::new(root_element, &state_property_name));
     let state_property_ref = if has_transitions {
@@ -44,39 +52,80 @@ fn lower_state_in_element(
     } else {
         state_property.clone()
     };
+    let is_state_value = |value: &Expression, idx: usize| Expression::BinaryExpression {
+        lhs: Box::new(value.clone()),
+        rhs: Box::new(Expression::NumberLiteral((idx + 1) as _, Unit::None)),
+        op: '=',
+    };
+    let is_state = |idx: usize| is_state_value(&state_property_ref, idx);
     let mut affected_properties = HashSet::new();
     // Maps State name string -> integer id
     let mut states_id = HashMap::new();
     let mut state_value = Expression::NumberLiteral(0., Unit::None);
     let states = std::mem::take(&mut root_element.borrow_mut().states);
-    for (idx, state) in states.into_iter().enumerate().rev() {
-        if let Some(condition) = &state.condition {
+    // The state requested by the last trigger, which is selected while no `when` condition is true
+    let requested_state = states.iter().any(|s| !s.triggers.is_empty()).then(|| {
+        let name = compute_state_property_name(root_element, "requested-state");
+        root_element.borrow_mut().property_declarations.insert(name.clone(), Type::Int32.into());
+        // The first state without condition is the initial state
+        if let Some(initial) =
+            states.iter().position(|s| s.parent.is_none() && s.condition.is_none())
+        {
+            let initial = Expression::NumberLiteral((initial + 1) as _, Unit::None);
+            root_element.borrow_mut().bindings.insert(name.clone(), RefCell::new(initial.into()));
+        }
+        let requested_state = NamedReference::new(root_element, &name);
+        requested_state.mark_as_set();
+        Expression::PropertyReference(requested_state)
+    });
+    for idx in evaluation_order(&states, None).into_iter().rev() {
+        if let Some(condition) = effective_condition(&states, idx, requested_state.as_ref()) {
             state_value = Expression::Condition {
-                condition: Box::new(condition.clone()),
+                condition: Box::new(condition),
                 true_expr: Box::new(Expression::NumberLiteral((idx + 1) as _, Unit::None)),
                 false_expr: Box::new(std::mem::take(&mut state_value)),
             };
         }
-        for (ne, expr, node) in state.property_changes {
+    }
+
+    let mut two_way_bindings = HashSet::new();
+    for state in &states {
+        for (ne, _, node) in &state.property_changes {
+            if matches!(
+                expression_for_property(&ne.element(), ne.name()),
+                ExpressionForProperty::TwoWayBinding
+            ) && two_way_bindings.insert(ne.clone())
+            {
+                diag.push_error(
+                    format!("Cannot change the property '{}' in a state because it is initialized with a two-way binding", ne.name()),
+                    node
+                );
+            }
+        }
+    }
+
+    for (idx, state) in states.iter().enumerate().rev() {
+        // The property changes of the states that contain this state apply too, unless this state overrides them
+        let mut property_changes = Vec::<(&NamedReference, &Expression)>::new();
+        for ancestor in ancestors(&states, idx).into_iter().rev() {
+            for (ne, expr, _) in &states[ancestor].property_changes {
+                property_changes.retain(|(n, _)| *n != ne);
+                property_changes.push((ne, expr));
+            }
+        }
+        for (ne, expr) in property_changes {
+            if two_way_bindings.contains(ne) {
+                continue;
+            }
             affected_properties.insert(ne.clone());
             let e = ne.element();
             let property_expr = match expression_for_property(&e, ne.name()) {
-                ExpressionForProperty::TwoWayBinding => {
-                    diag.push_error(
-                    format!("Cannot change the property '{}' in a state because it is initialized with a two-way binding", ne.name()),
-                    &node
-                );
-                    continue;
-                }
+                ExpressionForProperty::TwoWayBinding => continue,
                 ExpressionForProperty::Expression(e) => e,
             };
             let new_expr = Expression::Condition {
-                condition: Box::new(Expression::BinaryExpression {
-                    lhs: Box::new(state_property_ref.clone()),
-                    rhs: Box::new(Expression::NumberLiteral((idx + 1) as _, Unit::None)),
-                    op: '=',
-                }),
-                true_expr: Box::new(expr),
+                condition: Box::new(is_state(idx)),
+                true_expr: Box::new(expr.clone()),
                 false_expr: Box::new(property_expr),
             };
             match e.borrow_mut().bindings.entry(ne.name().to_owned()) {
@@ -88,7 +137,7 @@ fn lower_state_in_element(
                 }
             };
         }
-        states_id.insert(state.id, idx as i32 + 1);
+        states_id.insert(state.id.clone(), idx as i32 + 1);
     }
 
     root_element.borrow_mut().property_declarations.insert(
@@ -101,7 +150,151 @@ fn lower_state_in_element(
     root_element
         .borrow_mut()
         .bindings
-        .insert(state_property_name, RefCell::new(state_value.into()));
+        .insert(state_property_name.clone(), RefCell::new(state_value.into()));
+
+    // The state is active when it, or one of its nested states, is the current state
+    let is_active = |idx: usize, value: &Expression| {
+        (0..states.len())
+            .filter(|i| ancestors(&states, *i).contains(&idx))
+            .map(|i| is_state_value(value, i))
+            .reduce(or)
+            .unwrap()
+    };
+    // The nested states are exited before the states that contain them, and entered after
+    let mut by_depth = (0..states.len()).collect::<Vec<_>>();
+    by_depth.sort_by_key(|idx| ancestors(&states, *idx).len());
+
+    if states.iter().any(|s| s.entered.is_some() || s.exited.is_some()) {
+        let previous_property_name = compute_state_property_name(root_element, "previous-state");
+        root_element
+            .borrow_mut()
+            .property_declarations
+            .insert(previous_property_name.clone(), Type::Int32.into());
+        let previous_property = NamedReference::new(root_element, &previous_property_name);
+        previous_property.mark_as_set();
+
+        let read_local =
+            |name: &str| Expression::ReadLocalVariable { name: name.into(), ty: Type::Int32 };
+        let run_if = |condition: Expression, action: &Expression| Expression::Condition {
+            condition: Box::new(condition),
+            true_expr: Box::new(action.clone()),
+            false_expr: Box::new(match action.ty() {
+                Type::Void => Expression::CodeBlock(vec![]),
+                ty => Expression::default_value_for_type(&ty),
+            }),
+        };
+        let (previous, current) = (read_local("previous_state"), read_local("current_state"));
+        let mut code = vec![
+            Expression::StoreLocalVariable {
+                name: "previous_state".into(),
+                value: Box::new(Expression::PropertyReference(previous_property.clone())),
+            },
+            Expression::StoreLocalVariable {
+                name: "current_state".into(),
+                value: Box::new(state_property_ref.clone()),
+            },
+            Expression::SelfAssignment {
+                lhs: Box::new(Expression::PropertyReference(previous_property)),
+                rhs: Box::new(current.clone()),
+                op: '=',
+            },
+        ];
+        for idx in by_depth.iter().rev() {
+            if let Some((exited, _)) = &states[*idx].exited {
+                code.push(run_if(
+                    Expression::BinaryExpression {
+                        lhs: Box::new(is_active(*idx, &previous)),
+                        rhs: Box::new(Expression::UnaryOp {
+                            sub: Box::new(is_active(*idx, &current)),
+                            op: '!',
+                        }),
+                        op: '&',
+                    },
+                    exited,
+                ));
+            }
+        }
+        for idx in by_depth.iter() {
+            if let Some((entered, _)) = &states[*idx].entered {
+                code.push(run_if(
+                    Expression::BinaryExpression {
+                        lhs: Box::new(Expression::UnaryOp {
+                            sub: Box::new(is_active(*idx, &previous)),
+                            op: '!',
+                        }),
+                        rhs: Box::new(is_active(*idx, &current)),
+                        op: '&',
+                    },
+                    entered,
+                ));
+            }
+        }
+        let state_property_nr = NamedReference::new(root_element, &state_property_name);
+        root_element
+            .borrow_mut()
+            .change_callbacks
+            .push((state_property_nr, Expression::CodeBlock(code)));
+    }
+
+    if let Some(requested_state) = &requested_state {
+        // The triggers of the nested states take precedence over the ones of the states that
+        // contain them, and only the first trigger whose state is active and guard is true applies
+        let mut transitions_by_event = Vec::<(NamedReference, Vec<(Expression, usize)>)>::new();
+        for idx in by_depth.iter().rev() {
+            for trigger in &states[*idx].triggers {
+                let target = match states.iter().position(|s| s.id == trigger.target) {
+                    Some(target) => target,
+                    None => {
+                        diag.push_error(
+                            format!("State '{}' does not exist", trigger.target),
+                            &trigger.node.DeclaredIdentifier(),
+                        );
+                        continue;
+                    }
+                };
+                let mut condition = is_active(*idx, &state_property_ref);
+                if let Some(guard) = &trigger.guard {
+                    condition = and(condition, guard.clone());
+                }
+                match transitions_by_event.iter_mut().find(|(event, _)| *event == trigger.event) {
+                    Some((_, transitions)) => transitions.push((condition, target)),
+                    None => transitions_by_event
+                        .push((trigger.event.clone(), vec![(condition, target)])),
+                }
+            }
+        }
+        for (event, transitions) in transitions_by_event {
+            let mut code = Expression::CodeBlock(vec![]);
+            for (condition, target) in transitions.into_iter().rev() {
+                code = Expression::Condition {
+                    condition: Box::new(condition),
+                    true_expr: Box::new(Expression::SelfAssignment {
+                        lhs: Box::new(requested_state.clone()),
+                        rhs: Box::new(Expression::NumberLiteral((target + 1) as _, Unit::None)),
+                        op: '=',
+                    }),
+                    false_expr: Box::new(code),
+                };
+            }
+            // The transition happens before the handler of the callback, if any, runs
+            let element = event.element();
+            let mut element = element.borrow_mut();
+            match element.bindings.entry(event.name().to_owned()) {
+                std::collections::btree_map::Entry::Occupied(mut e) => {
+                    let binding = e.get_mut().get_mut();
+                    let handler = std::mem::take(&mut binding.expression);
+                    binding.expression = if matches!(handler, Expression::Invalid) {
+                        code
+                    } else {
+                        Expression::CodeBlock(vec![code, handler])
+                    };
+                }
+                std::collections::btree_map::Entry::Vacant(e) => {
+                    e.insert(RefCell::new(code.into()));
+                }
+            }
+        }
+    }
 
     lower_transitions_in_element(
         root_element,
@@ -166,9 +359,90 @@ fn lower_transitions_in_element(
     }
 }
 
-/// Returns a suitable unique name for the "state" property
-fn compute_state_property_name(root_element: &ElementRc) -> String {
-    let mut property_name = "state".to_owned();
+/// Returns the index of the state, followed by the indices of the states that contain it
+fn ancestors(states: &[State], idx: usize) -> Vec<usize> {
+    std::iter::successors(Some(idx), |i| states[*i].parent).collect()
+}
+
+/// Returns the order in which the conditions of the states are evaluated: the nested states
+/// come before the state that contains them, so that they take precedence, and the nested states
+/// without condition come after the other nested states of the same state
+fn evaluation_order(states: &[State], parent: Option<usize>) -> Vec<usize> {
+    let (with_condition, without_condition): (Vec<_>, Vec<_>) = (0..states.len())
+        .filter(|i| states[*i].parent == parent)
+        .partition(|i| states[*i].condition.is_some());
+    let mut order = vec![];
+    for idx in with_condition.into_iter().chain(without_condition) {
+        order.extend(evaluation_order(states, Some(idx)));
+        order.push(idx);
+    }
+    order
+}
+
+/// Returns the condition for the state to be active, which includes the conditions of the states
+/// that contain it, or None if it can't be active. A nested state without condition is active
+/// when the state that contains it is active and none of the other nested states is.
+///
+/// With triggers, a state without condition is also active when it, or one of its nested states,
+/// is the `requested` state, and a requested nested state without condition takes precedence over
+/// the other nested states without condition.
+fn effective_condition(
+    states: &[State],
+    idx: usize,
+    requested: Option<&Expression>,
+) -> Option<Expression> {
+    let state = &states[idx];
+    let own = match (&state.condition, state.parent, requested) {
+        (Some(condition), _, _) => condition.clone(),
+        (None, None, Some(requested)) => is_requested(states, idx, requested),
+        (None, None, None) => return None,
+        (None, Some(_), None) => Expression::BoolLiteral(true),
+        (None, Some(parent), Some(requested)) => {
+            let other_requested = (0..states.len())
+                .filter(|i| {
+                    *i != idx && states[*i].parent == Some(parent) && states[*i].condition.is_none()
+                })
+                .map(|i| is_requested(states, i, requested))
+                .reduce(or);
+            match other_requested {
+                Some(other_requested) => or(
+                    is_requested(states, idx, requested),
+                    Expression::UnaryOp { sub: Box::new(other_requested), op: '!' },
+                ),
+                None => Expression::BoolLiteral(true),
+            }
+        }
+    };
+    match state.parent {
+        None => Some(own),
+        Some(parent) => Some(and(effective_condition(states, parent, requested)?, own)),
+    }
+}
+
+/// Returns whether the `requested` state is the state, or one of its nested states
+fn is_requested(states: &[State], idx: usize, requested: &Expression) -> Expression {
+    (0..states.len())
+        .filter(|i| ancestors(states, *i).contains(&idx))
+        .map(|i| Expression::BinaryExpression {
+            lhs: Box::new(requested.clone()),
+            rhs: Box::new(Expression::NumberLiteral((i + 1) as _, Unit::None)),
+            op: '=',
+        })
+        .reduce(or)
+        .unwrap()
+}
+
+fn and(lhs: Expression, rhs: Expression) -> Expression {
+    Expression::BinaryExpression { lhs: Box::new(lhs), rhs: Box::new(rhs), op: '&' }
+}
+
+fn or(lhs: Expression, rhs: Expression) -> Expression {
+    Expression::BinaryExpression { lhs: Box::new(lhs), rhs: Box::new(rhs), op: '|' }
+}
+
+/// Returns a suitable unique name for the "state" property, or another property with the given name
+fn compute_state_property_name(root_element: &ElementRc, name: &str) -> String {
+    let mut property_name = name.to_owned();
     while root_element.borrow().lookup_property(property_name.as_ref()).property_type
         != Type::Invalid
     {
@@ -7,12 +7,21 @@
 //!
 //! Must be done before inlining and many other passes because the lowered code must
 //! be further inlined as it may expends to native widget that needs inlining
+//!
+//! The tab at a given position of the tabbar is the one at that position in the `tab-order`
+//! of the TabWidget, so the tabs of the tabbar read their title from the `tab-titles` array.
+//! The content of the lazy tabs is moved in a conditional element which is only instantiated
+//! when the tab is the current one.
+//! By default, the content of the tabs that is not accessed from outside of the tab is deferred:
+//! it is moved in a conditional element which is instantiated the first time the tab is the current
+//! one, and then kept. Its `init` callback removes the binding of the condition, by setting it.
 
 use crate::diagnostics::BuildDiagnostics;
 use crate::expression_tree::{BindingExpression, Expression, NamedReference, Unit};
 use crate::langtype::Type;
 use crate::object_tree::*;
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::rc::Rc;
 
 pub async fn lower_tabwidget(
@@ -63,6 +72,9 @@ fn process_tabwidget(
     let mut children = std::mem::take(&mut elem.borrow_mut().children);
     let num_tabs = children.len();
     let mut tabs = Vec::new();
+    let mut tab_contents = Vec::new();
+    let mut lazy_contents = Vec::new();
+    let mut deferred_candidates = Vec::new();
     for child in &mut children {
         if child.borrow().repeated.is_some() {
             diag.push_error(
@@ -77,7 +89,17 @@ fn process_tabwidget(
         }
         let index = tabs.len();
         child.borrow_mut().base_type = rectangle_type.clone();
-        child.borrow_mut().property_declarations.insert("title".to_owned(), Type::String.into());
+        for (name, ty) in [("title", Type::String), ("closable", Type::Bool), ("lazy", Type::Bool)]
+        {
+            child.borrow_mut().property_declarations.insert(name.to_owned(), ty.into());
+        }
+        match lazy_mode(child, diag) {
+            Some(true) => {
+                lazy_contents.push(make_content_lazy(elem, child, index, rectangle_type));
+            }
+            Some(false) => {}
+            None => deferred_candidates.push((child.clone(), index)),
+        }
         set_geometry_prop(elem, child, "x", diag);
         set_geometry_prop(elem, child, "y", diag);
         set_geometry_prop(elem, child, "width", diag);
@@ -98,33 +120,76 @@ fn process_tabwidget(
             );
         }
 
-        let mut tab = Element {
+        let tab = Element {
             id: format!("{}-tab{}", elem.borrow().id, index),
             base_type: tab_impl.clone(),
             enclosing_component: elem.borrow().enclosing_component.clone(),
             ..Default::default()
         };
-        tab.bindings.insert(
-            "title".to_owned(),
-            BindingExpression::new_two_way(NamedReference::new(child, "title")).into(),
+        tab_contents.push(child.clone());
+        tabs.push(Rc::new(RefCell::new(tab)));
+    }
+
+    for (position, tab) in tabs.iter().enumerate() {
+        let tab_index_ref = NamedReference::new(tab, "tab-index");
+        let mut tab_mut = tab.borrow_mut();
+        let tab_index = Expression::ArrayIndex {
+            array: Expression::PropertyReference(NamedReference::new(elem, "tab-order")).into(),
+            index: Expression::NumberLiteral(position as _, Unit::None).into(),
+        };
+        tab_mut.bindings.insert("tab-index".to_owned(), RefCell::new(tab_index.into()));
+        for (name, array) in [("title", "tab-titles"), ("closable", "tab-closable")] {
+            let value = Expression::ArrayIndex {
+                array: Expression::PropertyReference(NamedReference::new(elem, array)).into(),
+                index: Expression::PropertyReference(tab_index_ref.clone()).into(),
+            };
+            tab_mut.bindings.insert(name.to_owned(), RefCell::new(value.into()));
+        }
+        tab_mut.bindings.insert(
+            "tab-position".to_owned(),
+            RefCell::new(Expression::NumberLiteral(position as _, Unit::None).into()),
         );
-        tab.bindings.insert(
+        for name in ["tab-order", "close-requested"] {
+            tab_mut.bindings.insert(
+                name.to_owned(),
+                BindingExpression::new_two_way(NamedReference::new(elem, name)).into(),
+            );
+        }
+        tab_mut.bindings.insert(
             "current".to_owned(),
             BindingExpression::new_two_way(NamedReference::new(elem, "current-index")).into(),
         );
-        tab.bindings.insert(
+        tab_mut.bindings.insert(
             "current-focused".to_owned(),
             BindingExpression::new_two_way(NamedReference::new(elem, "current-focused")).into(),
         );
-        tab.bindings.insert(
-            "tab-index".to_owned(),
-            RefCell::new(Expression::NumberLiteral(index as _, Unit::None).into()),
-        );
-        tab.bindings.insert(
+        tab_mut.bindings.insert(
             "num-tabs".to_owned(),
             RefCell::new(Expression::NumberLiteral(num_tabs as _, Unit::None).into()),
         );
-        tabs.push(Rc::new(RefCell::new(tab)));
+    }
+
+    let tab_array = |name: &str, element_ty: Type| Expression::Array {
+        element_ty,
+        values: tab_contents
+            .iter()
+            .map(|x| Expression::PropertyReference(NamedReference::new(x, name)))
+            .collect(),
+    };
+    let tab_titles = tab_array("title", Type::String);
+    let tab_closable = tab_array("closable", Type::Bool);
+    let tab_order = Expression::Array {
+        element_ty: Type::Int32,
+        values: (0..tabs.len()).map(|i| Expression::NumberLiteral(i as _, Unit::None)).collect(),
+    };
+    {
+        let mut elem_mut = elem.borrow_mut();
+        elem_mut.bindings.insert("tab-titles".to_owned(), RefCell::new(tab_titles.into()));
+        elem_mut.bindings.insert("tab-closable".to_owned(), RefCell::new(tab_closable.into()));
+        elem_mut
+            .bindings
+            .entry("tab-order".to_owned())
+            .or_insert_with(|| RefCell::new(tab_order.into()));
     }
 
     let tabbar = Element {
@@ -147,6 +212,14 @@ fn process_tabwidget(
         "current".to_owned(),
         BindingExpression::new_two_way(NamedReference::new(elem, "current-index")).into(),
     );
+    tabbar.borrow_mut().bindings.insert(
+        "tab-order".to_owned(),
+        BindingExpression::new_two_way(NamedReference::new(elem, "tab-order")).into(),
+    );
+    tabbar.borrow_mut().bindings.insert(
+        "titles".to_owned(),
+        RefCell::new(Expression::PropertyReference(NamedReference::new(elem, "tab-titles")).into()),
+    );
     tabbar.borrow_mut().bindings.insert(
         "current-focused".to_owned(),
         BindingExpression::new_two_way(NamedReference::new(elem, "current-focused")).into(),
@@ -176,6 +249,186 @@ fn process_tabwidget(
     };
 
     elem.borrow_mut().children = std::iter::once(tabbar).chain(children.into_iter()).collect();
+
+    if !lazy_contents.is_empty() {
+        check_lazy_contents_references(elem, &lazy_contents, diag);
+    }
+
+    for (tab, index) in deferred_candidates {
+        let content = tab.borrow().children.clone();
+        if !content.is_empty() && !is_referenced_from_outside(elem, &content) {
+            make_content_deferred(elem, &tab, index, rectangle_type);
+        }
+    }
+}
+
+/// Returns the value of the `lazy` property of the tab, which must be a constant, or None if it's not set
+fn lazy_mode(tab: &ElementRc, diag: &mut BuildDiagnostics) -> Option<bool> {
+    let tab = tab.borrow();
+    let binding = tab.bindings.get("lazy")?.borrow();
+    match &binding.expression {
+        Expression::BoolLiteral(lazy) if binding.two_way_bindings.is_empty() => Some(*lazy),
+        _ => {
+            diag.push_error(
+                "The property 'lazy' of a Tab must be set to a constant value (true or false)"
+                    .to_owned(),
+                &*binding,
+            );
+            Some(false)
+        }
+    }
+}
+
+/// Move the children of the tab into a conditional element that is instantiated the first time
+/// the tab is the current one, and is then kept
+fn make_content_deferred(
+    tab_widget: &ElementRc,
+    tab: &ElementRc,
+    index: usize,
+    rectangle_type: &Type,
+) {
+    let instantiated = "content-instantiated";
+    tab.borrow_mut().property_declarations.insert(instantiated.to_owned(), Type::Bool.into());
+    let condition = Expression::BinaryExpression {
+        lhs: Expression::PropertyReference(NamedReference::new(tab_widget, "current-index")).into(),
+        rhs: Expression::NumberLiteral(index as _, Unit::None).into(),
+        op: '=',
+    };
+    tab.borrow_mut().bindings.insert(instantiated.to_owned(), RefCell::new(condition.into()));
+
+    let content = make_content_lazy(tab_widget, tab, index, rectangle_type);
+    let mut content = content.borrow_mut();
+    content.repeated.as_mut().unwrap().model =
+        Expression::PropertyReference(NamedReference::new(tab, instantiated));
+    // Setting the property to the value of its binding removes the binding without changing the
+    // condition, so that the content isn't destroyed when another tab becomes the current one
+    let latch = Expression::SelfAssignment {
+        lhs: Expression::PropertyReference(NamedReference::new(tab, instantiated)).into(),
+        rhs: Expression::BoolLiteral(true).into(),
+        op: '=',
+    };
+    content.bindings.insert("init".to_owned(), RefCell::new(latch.into()));
+}
+
+/// Move the children of the tab into a conditional element that only exists while the tab is the
+/// current one, and return that element
+fn make_content_lazy(
+    tab_widget: &ElementRc,
+    tab: &ElementRc,
+    index: usize,
+    rectangle_type: &Type,
+) -> ElementRc {
+    let condition = Expression::BinaryExpression {
+        lhs: Expression::PropertyReference(NamedReference::new(tab_widget, "current-index")).into(),
+        rhs: Expression::NumberLiteral(index as _, Unit::None).into(),
+        op: '=',
+    };
+    let children = std::mem::take(&mut tab.borrow_mut().children);
+    let content = Element {
+        id: format!("{}-content", tab.borrow().id),
+        base_type: rectangle_type.clone(),
+        enclosing_component: tab.borrow().enclosing_component.clone(),
+        children,
+        repeated: Some(RepeatedElementInfo {
+            model: condition,
+            model_data_id: String::new(),
+            index_id: String::new(),
+            is_conditional_element: true,
+            is_listview: None,
+        }),
+        node: tab.borrow().node.clone(),
+        ..Default::default()
+    };
+    let content = Rc::new(RefCell::new(content));
+    tab.borrow_mut().children.push(content.clone());
+    content
+}
+
+/// The elements of a lazy tab do not exist when the tab is not the current one, so they cannot be
+/// accessed by the elements outside of that tab.
+fn check_lazy_contents_references(
+    tab_widget: &ElementRc,
+    lazy_contents: &[ElementRc],
+    diag: &mut BuildDiagnostics,
+) {
+    visit_references_from_outside(tab_widget, lazy_contents, &mut |nr, span| {
+        report_lazy_reference(nr, span, diag)
+    });
+}
+
+/// Returns true if an element of `contents` is accessed by an element outside of them
+fn is_referenced_from_outside(tab_widget: &ElementRc, contents: &[ElementRc]) -> bool {
+    let mut referenced = false;
+    visit_references_from_outside(tab_widget, contents, &mut |_, _| referenced = true);
+    referenced
+}
+
+/// Calls `f` with the first reference to the elements of `contents`, or to their children, that is
+/// found in each element of the component outside of them, and the binding or the element that
+/// contains it.
+fn visit_references_from_outside(
+    tab_widget: &ElementRc,
+    contents: &[ElementRc],
+    f: &mut dyn FnMut(&NamedReference, &dyn crate::diagnostics::Spanned),
+) {
+    let mut lazy_elements = HashSet::new();
+    for content in contents {
+        recurse_elem(content, &(), &mut |elem, _| {
+            lazy_elements.insert(Rc::as_ptr(elem));
+        });
+    }
+
+    let in_lazy_tab = |nr: &NamedReference| lazy_elements.contains(&Rc::as_ptr(&nr.element()));
+    let root_element =
+        tab_widget.borrow().enclosing_component.upgrade().unwrap().root_element.clone();
+    recurse_elem_no_borrow(&root_element, &(), &mut |elem, _| {
+        if lazy_elements.contains(&Rc::as_ptr(elem)) {
+            return;
+        }
+        let mut reported = false;
+        for binding in elem.borrow().bindings.values() {
+            let binding = binding.borrow();
+            let mut found = binding.two_way_bindings.iter().find(|nr| in_lazy_tab(nr)).cloned();
+            binding.expression.visit_recursive(&mut |expr| match expr {
+                Expression::PropertyReference(nr) | Expression::CallbackReference(nr)
+                    if found.is_none() && in_lazy_tab(nr) =>
+                {
+                    found = Some(nr.clone())
+                }
+                _ => {}
+            });
+            if let Some(nr) = found {
+                f(&nr, &*binding);
+                reported = true;
+            }
+        }
+        if !reported {
+            // The references which are not in a binding, for example in the states
+            let mut found = None;
+            visit_all_named_references_in_element(elem, |nr| {
+                if found.is_none() && in_lazy_tab(nr) {
+                    found = Some(nr.clone());
+                }
+            });
+            if let Some(nr) = found {
+                f(&nr, &*elem.borrow());
+            }
+        }
+    });
+}
+
+fn report_lazy_reference(
+    nr: &NamedReference,
+    span: &dyn crate::diagnostics::Spanned,
+    diag: &mut BuildDiagnostics,
+) {
+    diag.push_error(
+        format!(
+            "Cannot access '{}' from outside of the lazy Tab that contains it",
+            nr.element().borrow().id
+        ),
+        span,
+    );
 }
 
 fn set_geometry_prop(
@@ -36,7 +36,11 @@ pub fn optimize_useless_rectangles(root_component: &Rc<Component>) {
 /// Check that this is a element we can optimize
 fn can_optimize(elem: &ElementRc) -> bool {
     let e = elem.borrow();
-    if e.is_flickable_viewport || e.has_popup_child {
+    if e.is_flickable_viewport
+        || e.has_popup_child
+        || !e.accessibility_props.0.is_empty()
+        || !e.change_callbacks.is_empty()
+    {
         return false;
     };
 
@@ -39,8 +39,10 @@ fn create_repeater_components(component: &Rc<Component>) {
                 enclosing_component: Default::default(),
                 states: std::mem::take(&mut elem.states),
                 transitions: std::mem::take(&mut elem.transitions),
+                change_callbacks: std::mem::take(&mut elem.change_callbacks),
                 child_of_layout: elem.child_of_layout || is_listview.is_some(),
                 layout_info_prop: elem.layout_info_prop.take(),
+                accessibility_props: std::mem::take(&mut elem.accessibility_props),
                 is_flickable_viewport: elem.is_flickable_viewport,
                 has_popup_child: elem.has_popup_child,
                 item_index: Default::default(), // Not determined yet
@@ -70,14 +72,26 @@ fn create_repeater_components(component: &Rc<Component>) {
             }
 
             NamedReference::new(&comp.root_element, "y").mark_as_set();
+            // Set by the ListView to report the first visible row. When the ListView is itself
+            // repeated, its properties were moved to the root of the component created for it.
+            let lv = listview.first_visible_item.element();
+            let lv_component = match &lv.borrow().base_type {
+                Type::Component(c) if lv.borrow().repeated.is_some() => Some(c.root_element.clone()),
+                _ => None,
+            };
+            match lv_component {
+                Some(root) => NamedReference::new(&root, "first-visible-item").mark_as_set(),
+                None => listview.first_visible_item.mark_as_set(),
+            }
         }
 
         let weak = Rc::downgrade(&comp);
         recurse_elem(&comp.root_element, &(), &mut |e, _| {
             e.borrow_mut().enclosing_component = weak.clone()
         });
+        elem.base_type = Type::Component(comp.clone());
+        drop(elem);
         create_repeater_components(&comp);
-        elem.base_type = Type::Component(comp);
     });
 
     for p in component.popup_windows.borrow().iter() {
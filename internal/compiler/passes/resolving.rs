@@ -18,6 +18,8 @@
 use std::collections::HashMap;
 use std::rc::Rc;
 
+static COUNTER: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(1);
+
 /// This represents a scope for the Component, where Component is the repeated component, but
 /// does not represent a component in the .slint file
 #[derive(Clone)]
@@ -50,6 +52,7 @@ fn resolve_expression(
                 //FIXME: proper callback support (node is a codeblock)
                 Expression::from_callback_connection(node.clone().into(), &mut lookup_ctx)
             }
+            SyntaxKind::Function => Expression::from_function(node.clone().into(), &mut lookup_ctx),
             SyntaxKind::Expression => {
                 //FIXME again: this happen for non-binding expression (i.e: model)
                 Expression::from_expression_node(node.clone().into(), &mut lookup_ctx)
@@ -93,6 +96,7 @@ pub fn resolve_expressions(
                 new_scope.0.push(elem.clone())
             }
             new_scope.0.push(elem.clone());
+            check_bindings_access(elem, diag);
             let mut two_ways = vec![];
             visit_element_expressions(elem, |expr, property_name, property_type| {
                 if is_repeated {
@@ -131,10 +135,60 @@ pub fn resolve_expressions(
             }
             new_scope.0.pop();
             new_scope
-        })
+        });
+        resolve_else_branches(component);
     }
 }
 
+/// The `else` and `else if` branches of a conditional element are only instantiated when the
+/// conditions of all the previous branches are false. Combine their condition accordingly.
+fn resolve_else_branches(component: &Component) {
+    recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        // The conditions of the previous branches of the current `if ... else` chain
+        let mut previous_conditions: Vec<Expression> = vec![];
+        for child in &elem.borrow().children {
+            let mut child = child.borrow_mut();
+            let is_else_branch =
+                child.node.as_ref().and_then(|n| n.parent()?.parent()).map_or(false, |n| {
+                    match n.kind() {
+                        SyntaxKind::ConditionalElse => true,
+                        SyntaxKind::ConditionalElement => {
+                            n.parent().map_or(false, |p| p.kind() == SyntaxKind::ConditionalElse)
+                        }
+                        _ => false,
+                    }
+                });
+            let repeated = match &mut child.repeated {
+                Some(repeated) if repeated.is_conditional_element => repeated,
+                _ => {
+                    previous_conditions.clear();
+                    continue;
+                }
+            };
+            if !is_else_branch {
+                previous_conditions.clear();
+            }
+            let condition = repeated.model.clone();
+            if let Some(none_before) = previous_conditions
+                .iter()
+                .map(|c| Expression::UnaryOp { sub: Box::new(c.clone()), op: '!' })
+                .reduce(|lhs, rhs| Expression::BinaryExpression {
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(rhs),
+                    op: '&',
+                })
+            {
+                repeated.model = Expression::BinaryExpression {
+                    lhs: Box::new(none_before),
+                    rhs: Box::new(condition.clone()),
+                    op: '&',
+                };
+            }
+            previous_conditions.push(condition);
+        }
+    });
+}
+
 impl Expression {
     pub fn from_binding_expression_node(node: SyntaxNode, ctx: &mut LookupCtx) -> Self {
         debug_assert_eq!(node.kind(), SyntaxKind::BindingExpression);
@@ -232,7 +286,22 @@ fn from_callback_connection(
         )
     }
 
-    fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) -> Self {
+    fn from_function(node: syntax_nodes::Function, ctx: &mut LookupCtx) -> Expression {
+        ctx.arguments = node
+            .ArgumentDeclaration()
+            .map(|x| identifier_text(&x.DeclaredIdentifier()).unwrap_or_default())
+            .collect();
+        Self::from_codeblock_node(node.CodeBlock(), ctx).maybe_convert_to(
+            ctx.return_type().clone(),
+            &node,
+            ctx.diag,
+        )
+    }
+
+    pub(crate) fn from_expression_node(
+        node: syntax_nodes::Expression,
+        ctx: &mut LookupCtx,
+    ) -> Self {
         node.Expression()
             .map(|n| Self::from_expression_node(n, ctx))
             .or_else(|| node.AtImageUrl().map(|n| Self::from_at_image_url_node(n, ctx)))
@@ -270,6 +339,7 @@ fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) ->
                         ctx.diag.push_error(e, &node);
                         Some(Self::Invalid)
                     })
+                    .map(|e| lower_relative_length_unit(e, &node))
             })
             .or_else(|| {
                 node.child_text(SyntaxKind::ColorLiteral).map(|s| {
@@ -297,6 +367,7 @@ fn from_expression_node(node: syntax_nodes::Expression, ctx: &mut LookupCtx) ->
             .or_else(|| {
                 node.ConditionalExpression().map(|n| Self::from_conditional_expression_node(n, ctx))
             })
+            .or_else(|| node.MatchExpression().map(|n| Self::from_match_expression_node(n, ctx)))
             .or_else(|| node.ObjectLiteral().map(|n| Self::from_object_literal_node(n, ctx)))
             .or_else(|| node.Array().map(|n| Self::from_array_node(n, ctx)))
             .or_else(|| node.CodeBlock().map(|n| Self::from_codeblock_node(n, ctx)))
@@ -316,10 +387,69 @@ fn from_at_image_url_node(node: syntax_nodes::AtImageUrl, ctx: &mut LookupCtx) -
             }
         };
 
+        let mut options = ImageProcessingOptions::default();
+        let mut scale_variants = false;
+        for option in node.AtImageUrlOption() {
+            let name = option.child_text(SyntaxKind::Identifier).unwrap_or_default();
+            let value = option
+                .children_with_tokens()
+                .filter_map(|t| t.into_token())
+                .filter(|t| {
+                    matches!(
+                        t.kind(),
+                        SyntaxKind::NumberLiteral
+                            | SyntaxKind::ColorLiteral
+                            | SyntaxKind::Identifier
+                    )
+                })
+                .nth(1)
+                .map(|t| t.text().to_string())
+                .unwrap_or_default();
+            let as_bool = |ctx: &mut LookupCtx| match value.as_str() {
+                "true" => true,
+                "false" => false,
+                _ => {
+                    ctx.diag.push_error(
+                        format!("The '{}' option of @image-url must be true or false", name),
+                        &option,
+                    );
+                    false
+                }
+            };
+            match name.as_str() {
+                "max-size" => match value.parse::<u32>() {
+                    Ok(size) if size > 0 => options.max_size = Some(size),
+                    _ => ctx.diag.push_error(
+                        "The 'max-size' option of @image-url must be a positive integer number of pixels"
+                            .into(),
+                        &option,
+                    ),
+                },
+                "recompress" => options.recompress = as_bool(ctx),
+                "scale-variants" => scale_variants = as_bool(ctx),
+                "colorize" => match crate::literals::parse_color_literal(&value) {
+                    Some(color) => options.colorize = Some(color),
+                    None => ctx.diag.push_error(
+                        "The 'colorize' option of @image-url must be a color literal such as #ff0000"
+                            .into(),
+                        &option,
+                    ),
+                },
+                _ => ctx.diag.push_error(
+                    format!(
+                        "Unknown @image-url option '{}'. Expected 'colorize', 'max-size', 'recompress' or 'scale-variants'",
+                        name
+                    ),
+                    &option,
+                ),
+            }
+        }
+
         if s.is_empty() {
             return Expression::ImageReference {
                 resource_ref: ImageReference::None,
                 source_location: Some(node.to_source_location()),
+                options,
             };
         }
 
@@ -340,10 +470,94 @@ fn from_at_image_url_node(node: syntax_nodes::AtImageUrl, ctx: &mut LookupCtx) -
             }
         };
 
+        if scale_variants {
+            let is_global = ctx
+                .component_scope
+                .first()
+                .and_then(|e| e.borrow().enclosing_component.upgrade())
+                .map_or(false, |c| c.is_global());
+            if is_global {
+                ctx.diag.push_error(
+                    "Cannot use the 'scale-variants' option of @image-url in a global component, because the scale factor is not known".into(),
+                    &node,
+                );
+            } else {
+                return Self::image_scale_variants(absolute_source_path, options, &node);
+            }
+        }
+
         Expression::ImageReference {
             resource_ref: ImageReference::AbsolutePath(absolute_source_path),
             source_location: Some(node.to_source_location()),
+            options,
+        }
+    }
+
+    /// Returns an expression that selects, depending on the scale factor of the window, between
+    /// the image at `path` and its variants for higher scale factors, such as `foo@2x.png`.
+    fn image_scale_variants(
+        path: String,
+        options: ImageProcessingOptions,
+        node: &syntax_nodes::AtImageUrl,
+    ) -> Self {
+        let source_location = Some(node.to_source_location());
+        let image = |path: String, scale_factor: u32| {
+            let mut options = options.clone();
+            if scale_factor > 1 {
+                options.max_size = options.max_size.map(|s| s * scale_factor);
+                options.scale_factor = Some(scale_factor);
+            }
+            Expression::ImageReference {
+                resource_ref: ImageReference::AbsolutePath(path),
+                source_location: source_location.clone(),
+                options,
+            }
+        };
+
+        let mut variants = vec![];
+        {
+            let p = std::path::Path::new(&path);
+            if let (Some(stem), Some(extension)) =
+                (p.file_stem().and_then(|s| s.to_str()), p.extension().and_then(|e| e.to_str()))
+            {
+                for scale_factor in 2..=4 {
+                    let variant =
+                        p.with_file_name(format!("{}@{}x.{}", stem, scale_factor, extension));
+                    if crate::fileaccess::load_file(&variant).is_some() {
+                        variants.push((variant.to_string_lossy().to_string(), scale_factor));
+                    }
+                }
+            }
+        }
+
+        // Pick the smallest variant made for a scale factor that is at least the one of the window
+        let mut result = match variants.pop() {
+            Some((variant, scale_factor)) => image(variant, scale_factor),
+            None => return image(path, 1),
+        };
+        for (variant, scale_factor) in std::iter::once((path, 1)).chain(variants).rev() {
+            result = Expression::Condition {
+                condition: Box::new(Expression::BinaryExpression {
+                    lhs: Box::new(Expression::BinaryExpression {
+                        lhs: Box::new(Expression::FunctionCall {
+                            function: Box::new(Expression::BuiltinFunctionReference(
+                                BuiltinFunction::GetWindowScaleFactor,
+                                source_location.clone(),
+                            )),
+                            arguments: vec![],
+                            source_location: source_location.clone(),
+                        }),
+                        rhs: Box::new(Expression::NumberLiteral(1., Unit::Px)),
+                        op: '*',
+                    }),
+                    rhs: Box::new(Expression::NumberLiteral(scale_factor as _, Unit::Phx)),
+                    op: '≤',
+                }),
+                true_expr: Box::new(image(variant, scale_factor)),
+                false_expr: Box::new(result),
+            };
         }
+        result
     }
 
     fn from_at_linear_gradient(node: syntax_nodes::AtLinearGradient, ctx: &mut LookupCtx) -> Self {
@@ -519,6 +733,14 @@ fn from_qualified_name_node(node: syntax_nodes::QualifiedName, ctx: &mut LookupC
             ctx.diag.push_property_deprecation_warning(&first_str, depr, &first);
         }
 
+        if let LookupResult::Expression {
+            expression: Expression::PropertyReference(nr) | Expression::CallbackReference(nr),
+            ..
+        } = &result
+        {
+            check_property_read_access(&nr.element(), nr.name(), &first, ctx);
+        }
+
         match result {
             LookupResult::Expression { expression: Expression::ElementReference(e), .. } => {
                 continue_lookup_within_element(&e.upgrade().unwrap(), &mut it, node, ctx)
@@ -600,8 +822,27 @@ fn from_function_call_node(
                 .unwrap_or_else(|| Self::from_expression_node(n, ctx))
         });
 
-        let sub_expr = sub_expr.map(|n| {
-            (Self::from_expression_node(n.clone(), ctx), Some(NodeOrToken::from((*n).clone())))
+        // Arguments of type element reference (such as the item passed to `ensure-visible`)
+        // are looked up as elements rather than as properties
+        let element_reference_args = match &function {
+            Expression::MemberFunction { member, .. } => match member.ty() {
+                Type::Function { args, .. } => args.iter().skip(1).cloned().collect(),
+                _ => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        let sub_expr = sub_expr.enumerate().map(|(index, n)| {
+            let expr = if matches!(element_reference_args.get(index), Some(Type::ElementReference))
+            {
+                let property_type =
+                    std::mem::replace(&mut ctx.property_type, Type::ElementReference);
+                let expr = Self::from_expression_node(n.clone(), ctx);
+                ctx.property_type = property_type;
+                expr
+            } else {
+                Self::from_expression_node(n.clone(), ctx)
+            };
+            (expr, Some(NodeOrToken::from((*n).clone())))
         });
 
         let function = match function {
@@ -611,6 +852,15 @@ fn from_function_call_node(
             }
             Expression::MemberFunction { base, base_node, member } => {
                 arguments.push((*base, base_node));
+                if let Expression::BuiltinMacroReference(mac, n) = *member {
+                    arguments.extend(sub_expr);
+                    return crate::builtin_macros::lower_macro(
+                        mac,
+                        n,
+                        arguments.into_iter(),
+                        ctx.diag,
+                    );
+                }
                 member
             }
             _ => Box::new(function),
@@ -671,6 +921,13 @@ fn from_self_assignment_node(
             .or_else(|| node.child_token(SyntaxKind::DivEqual).and(Some('/')))
             .or_else(|| node.child_token(SyntaxKind::Equal).and(Some('=')))
             .unwrap_or('_');
+        let mut property = &lhs;
+        while let Expression::StructFieldAccess { base, .. } = property {
+            property = base;
+        }
+        if let Expression::PropertyReference(nr) = property {
+            check_property_write_access(nr, &lhs_n, ctx);
+        }
         if !lhs.try_set_rw() && lhs.ty() != Type::Invalid {
             ctx.diag.push_error(
                 format!(
@@ -838,6 +1095,156 @@ fn from_conditional_expression_node(
         }
     }
 
+    /// Lower a `match` expression to a chain of conditions on a local variable holding the value
+    fn from_match_expression_node(
+        node: syntax_nodes::MatchExpression,
+        ctx: &mut LookupCtx,
+    ) -> Expression {
+        let subject_n = node.Expression();
+        let subject = Self::from_expression_node(subject_n.clone(), ctx);
+        let subject_ty = subject.ty();
+        let is_numeric = subject_ty.as_unit_product().is_some();
+        if !is_numeric && !matches!(subject_ty, Type::Enumeration(_) | Type::String | Type::Invalid)
+        {
+            ctx.diag
+                .push_error(format!("Cannot match on a value of type {}", subject_ty), &subject_n);
+            return Expression::Invalid;
+        }
+
+        let local =
+            format!("match_subject{}", COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed));
+        let read_subject = || {
+            Box::new(Expression::ReadLocalVariable { name: local.clone(), ty: subject_ty.clone() })
+        };
+
+        let mut covered_enum_values = match &subject_ty {
+            Type::Enumeration(e) => vec![false; e.values.len()],
+            _ => vec![],
+        };
+        let mut has_catch_all = false;
+        let mut arms = vec![];
+        for arm in node.MatchArm() {
+            if has_catch_all {
+                ctx.diag.push_error("Unreachable match arm after the '_' arm".into(), &arm);
+            }
+            let old_property_type = std::mem::replace(&mut ctx.property_type, subject_ty.clone());
+            let mut condition: Option<Expression> = None;
+            for pattern in arm.MatchPattern() {
+                let mut values = pattern
+                    .Expression()
+                    .map(|n| {
+                        Self::from_expression_node(n.clone(), ctx).maybe_convert_to(
+                            subject_ty.clone(),
+                            &n,
+                            ctx.diag,
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter();
+                let c = match (values.next(), values.next()) {
+                    (Some(low), Some(high)) => {
+                        if !is_numeric {
+                            ctx.diag.push_error(
+                                "Ranges can only be used when matching numbers".into(),
+                                &pattern,
+                            );
+                        }
+                        Expression::BinaryExpression {
+                            lhs: Box::new(Expression::BinaryExpression {
+                                lhs: read_subject(),
+                                rhs: Box::new(low),
+                                op: '≥',
+                            }),
+                            rhs: Box::new(Expression::BinaryExpression {
+                                lhs: read_subject(),
+                                rhs: Box::new(high),
+                                op: '<',
+                            }),
+                            op: '&',
+                        }
+                    }
+                    (Some(value), None) => {
+                        if let Expression::EnumerationValue(v) = &value {
+                            if let Some(covered) = covered_enum_values.get_mut(v.value) {
+                                *covered = true;
+                            }
+                        }
+                        Expression::BinaryExpression {
+                            lhs: read_subject(),
+                            rhs: Box::new(value),
+                            op: '=',
+                        }
+                    }
+                    _ => Expression::Invalid,
+                };
+                condition = Some(match condition {
+                    None => c,
+                    Some(prev) => Expression::BinaryExpression {
+                        lhs: Box::new(prev),
+                        rhs: Box::new(c),
+                        op: '|',
+                    },
+                });
+            }
+            ctx.property_type = old_property_type;
+            has_catch_all |= condition.is_none();
+            let value_n = arm.Expression();
+            let value = Self::from_expression_node(value_n.clone(), ctx);
+            arms.push((condition, value, value_n));
+        }
+
+        if !has_catch_all {
+            match &subject_ty {
+                Type::Enumeration(e) => {
+                    let missing = e
+                        .values
+                        .iter()
+                        .zip(covered_enum_values.iter())
+                        .filter(|(_, covered)| !**covered)
+                        .map(|(v, _)| format!("{}.{}", e.name, v))
+                        .collect::<Vec<_>>();
+                    if !missing.is_empty() {
+                        ctx.diag.push_error(
+                            format!("Non-exhaustive match: missing {}", missing.join(", ")),
+                            &node,
+                        );
+                        return Expression::Invalid;
+                    }
+                }
+                Type::Invalid => return Expression::Invalid,
+                _ => {
+                    ctx.diag.push_error(
+                        format!(
+                            "Non-exhaustive match: a '_' arm is required when matching on a {}",
+                            subject_ty
+                        ),
+                        &node,
+                    );
+                    return Expression::Invalid;
+                }
+            }
+        }
+
+        let result_ty =
+            Self::common_target_type_for_type_list(arms.iter().map(|(_, value, _)| value.ty()));
+        // The last arm (either `_`, or the last value of an exhaustive enum) is the fallback
+        let result = arms.into_iter().rev().fold(None, |result, (condition, value, value_n)| {
+            let value = value.maybe_convert_to(result_ty.clone(), &value_n, ctx.diag);
+            Some(match (condition, result) {
+                (Some(condition), Some(false_expr)) => Expression::Condition {
+                    condition: Box::new(condition),
+                    true_expr: Box::new(value),
+                    false_expr: Box::new(false_expr),
+                },
+                _ => value,
+            })
+        });
+        Expression::CodeBlock(vec![
+            Expression::StoreLocalVariable { name: local, value: Box::new(subject) },
+            result.unwrap_or(Expression::Invalid),
+        ])
+    }
+
     fn from_index_expression_node(
         node: syntax_nodes::IndexExpression,
         ctx: &mut LookupCtx,
@@ -874,6 +1281,7 @@ fn from_object_literal_node(
             fields: values.iter().map(|(k, v)| (k.clone(), v.ty())).collect(),
             name: None,
             node: None,
+            defaults: Default::default(),
         };
         Expression::Struct { ty, values }
     }
@@ -935,8 +1343,14 @@ fn common_target_type_for_type_list(types: impl Iterator<Item = Type>) -> Type {
                             fields: mut result_fields,
                             name: result_name,
                             node: result_node,
+                            defaults: result_defaults,
+                        },
+                        Type::Struct {
+                            fields: elem_fields,
+                            name: elem_name,
+                            node: elem_node,
+                            defaults: elem_defaults,
                         },
-                        Type::Struct { fields: elem_fields, name: elem_name, node: elem_node },
                     ) => {
                         for (elem_name, elem_ty) in elem_fields.into_iter() {
                             match result_fields.entry(elem_name) {
@@ -953,10 +1367,13 @@ fn common_target_type_for_type_list(types: impl Iterator<Item = Type>) -> Type {
                                 }
                             }
                         }
+                        let defaults =
+                            if result_node.is_some() { result_defaults } else { elem_defaults };
                         Type::Struct {
                             name: result_name.or(elem_name),
                             fields: result_fields,
                             node: result_node.or(elem_node),
+                            defaults,
                         }
                     }
                     (Type::Color, Type::Brush) | (Type::Brush, Type::Color) => Type::Brush,
@@ -980,6 +1397,29 @@ fn common_target_type_for_type_list(types: impl Iterator<Item = Type>) -> Type {
     }
 }
 
+/// The `vw`, `vh` and `rem` units depend on the window, so they are replaced by a multiplication
+/// with the size of the window or its default font size.
+fn lower_relative_length_unit(e: Expression, node: &syntax_nodes::Expression) -> Expression {
+    let (value, function) = match e {
+        Expression::NumberLiteral(v, Unit::Vw) => (v / 100., BuiltinFunction::GetWindowWidth),
+        Expression::NumberLiteral(v, Unit::Vh) => (v / 100., BuiltinFunction::GetWindowHeight),
+        Expression::NumberLiteral(v, Unit::Rem) => (v, BuiltinFunction::GetWindowDefaultFontSize),
+        e => return e,
+    };
+    Expression::BinaryExpression {
+        lhs: Box::new(Expression::NumberLiteral(value, Unit::None)),
+        rhs: Box::new(Expression::FunctionCall {
+            function: Box::new(Expression::BuiltinFunctionReference(
+                function,
+                Some(node.to_source_location()),
+            )),
+            arguments: vec![],
+            source_location: Some(node.to_source_location()),
+        }),
+        op: '*',
+    }
+}
+
 fn continue_lookup_within_element(
     elem: &ElementRc,
     it: &mut impl Iterator<Item = crate::parser::SyntaxToken>,
@@ -1002,9 +1442,11 @@ fn continue_lookup_within_element(
         if resolved_name != prop_name {
             ctx.diag.push_property_deprecation_warning(&prop_name, &resolved_name, &second);
         }
+        check_property_read_access(elem, &resolved_name, &second, ctx);
         let prop = Expression::PropertyReference(NamedReference::new(elem, &resolved_name));
         maybe_lookup_object(prop, it, ctx)
     } else if matches!(property_type, Type::Callback { .. }) {
+        check_property_read_access(elem, &resolved_name, &second, ctx);
         if let Some(x) = it.next() {
             ctx.diag.push_error("Cannot access fields of callback".into(), &x)
         }
@@ -1031,9 +1473,13 @@ fn continue_lookup_within_element(
                     return;
                 }
             };
-            ctx.diag.push_error(
+            // Only suggest another name if there is no other hint
+            let candidates =
+                if extra.is_empty() { elem.borrow().property_names() } else { Vec::new() };
+            ctx.diag.push_error_with_suggestion(
                 format!("{} does not have a property '{}'{}", what, second.text(), extra),
                 &second,
+                candidates,
             );
         };
         if let Some(minus_pos) = second.text().find('-') {
@@ -1053,6 +1499,101 @@ fn continue_lookup_within_element(
     }
 }
 
+/// How a property is accessed, relative to the component that declares it
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum PropertyAccess {
+    /// From within the component that declares the property
+    Internal,
+    /// From a component that inherits from the component that declares the property
+    Inherited,
+    /// From a component that uses the component that declares the property
+    External,
+}
+
+/// Returns how the property `name` of `elem` is accessed from an expression in the given scope
+fn property_access(elem: &ElementRc, name: &str, component_scope: &[ElementRc]) -> PropertyAccess {
+    let current = match component_scope.first() {
+        Some(e) => e.borrow().enclosing_component.upgrade(),
+        None => return PropertyAccess::Internal,
+    };
+    let e = elem.borrow();
+    match (e.enclosing_component.upgrade(), current) {
+        (Some(enclosing), Some(current)) if !Rc::ptr_eq(&enclosing, &current) => {
+            PropertyAccess::External
+        }
+        _ if e.property_declarations.contains_key(name) => PropertyAccess::Internal,
+        (Some(enclosing), _) if Rc::ptr_eq(&enclosing.root_element, elem) => {
+            PropertyAccess::Inherited
+        }
+        _ => PropertyAccess::External,
+    }
+}
+
+/// Report an error if the property `name` of `elem` is private or protected and cannot be
+/// accessed from the expression being resolved
+fn check_property_read_access(
+    elem: &ElementRc,
+    name: &str,
+    node: &dyn Spanned,
+    ctx: &mut LookupCtx,
+) {
+    let visibility = elem.borrow().property_visibility(name);
+    let allowed = match property_access(elem, name, ctx.component_scope) {
+        PropertyAccess::Internal => true,
+        PropertyAccess::Inherited => visibility != PropertyVisibility::Private,
+        PropertyAccess::External => visibility.is_public(),
+    };
+    if !allowed {
+        ctx.diag.push_error(format!("The property '{}' is {}", name, visibility), node);
+    }
+}
+
+/// Report an error if the property referenced by the left hand side of an assignment is an
+/// input property assigned from within its component, or an output property assigned from outside
+fn check_property_write_access(nr: &NamedReference, node: &dyn Spanned, ctx: &mut LookupCtx) {
+    let elem = nr.element();
+    let visibility = elem.borrow().property_visibility(nr.name());
+    match (visibility, property_access(&elem, nr.name(), ctx.component_scope)) {
+        (PropertyVisibility::Input, PropertyAccess::Internal) => ctx.diag.push_error(
+            format!("Cannot assign to input property '{}' from within its component", nr.name()),
+            node,
+        ),
+        (PropertyVisibility::Output, PropertyAccess::External) => {
+            ctx.diag.push_error(format!("Cannot assign to output property '{}'", nr.name()), node)
+        }
+        _ => {}
+    }
+}
+
+/// Report an error for the bindings of `elem` that set a property of its base component which
+/// is not accessible, or which is an output property
+fn check_bindings_access(elem: &ElementRc, diag: &mut BuildDiagnostics) {
+    let e = elem.borrow();
+    let base = match &e.base_type {
+        Type::Component(base) => base.clone(),
+        _ => return,
+    };
+    let is_root =
+        e.enclosing_component.upgrade().map_or(false, |c| Rc::ptr_eq(&c.root_element, elem));
+    for (name, binding) in &e.bindings {
+        if e.property_declarations.contains_key(name) {
+            continue;
+        }
+        let visibility = base.root_element.borrow().property_visibility(name);
+        let error = match visibility {
+            PropertyVisibility::Private => format!("The property '{}' is private", name),
+            PropertyVisibility::Protected if !is_root => {
+                format!("The property '{}' is protected", name)
+            }
+            PropertyVisibility::Output if !is_root => {
+                format!("Cannot set a binding on output property '{}'", name)
+            }
+            _ => continue,
+        };
+        diag.push_error(error, &*binding.borrow());
+    }
+}
+
 fn maybe_lookup_object(
     mut base: Expression,
     it: impl Iterator<Item = crate::parser::SyntaxToken>,
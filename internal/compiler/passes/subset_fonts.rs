@@ -0,0 +1,207 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Helpers to reduce the size of the fonts embedded in the program to the glyphs used by the design.
+//!
+//! Only the outlines of TrueType fonts are subset: the glyphs that are not needed are replaced by
+//! empty glyphs, so that the glyph indices, metrics and other tables of the font remain valid.
+
+use crate::expression_tree::Expression;
+use crate::object_tree::*;
+use std::collections::BTreeSet;
+use std::convert::TryInto;
+use std::rc::Rc;
+
+/// Returns the characters that appear in the string literals of the component and its
+/// sub-components and globals, as well as all the printable ASCII characters, which may be
+/// used by formatted numbers or by strings coming from the program.
+pub fn collect_used_characters(root_component: &Rc<Component>) -> BTreeSet<char> {
+    let mut characters = (' '..='~').collect::<BTreeSet<_>>();
+    let used_types = root_component.used_types.borrow();
+    for component in used_types
+        .sub_components
+        .iter()
+        .chain(used_types.globals.iter())
+        .chain(std::iter::once(root_component))
+    {
+        visit_all_expressions(component, |e, _| {
+            collect_characters_in_expression(e, &mut characters)
+        });
+    }
+    characters
+}
+
+fn collect_characters_in_expression(e: &Expression, characters: &mut BTreeSet<char>) {
+    if let Expression::StringLiteral(s) = e {
+        characters.extend(s.chars());
+    }
+    e.visit(|e| collect_characters_in_expression(e, characters));
+}
+
+/// Returns a copy of the TrueType font in `data` where all the glyphs that are not needed to
+/// render `characters` are empty.
+/// Returns None if the font cannot be subset, for example because it is a font collection or
+/// has CFF outlines.
+pub fn subset_font(data: &[u8], characters: &BTreeSet<char>) -> Option<Vec<u8>> {
+    let font = fontdue::Font::from_bytes(data, fontdue::FontSettings::default()).ok()?;
+    let read_u16 = |data: &[u8], offset: usize| -> Option<u16> {
+        Some(u16::from_be_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+    };
+    let read_u32 = |data: &[u8], offset: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+    };
+
+    if read_u32(data, 0)? != 0x00010000 && data.get(0..4)? != b"true" {
+        return None;
+    }
+    let num_tables = read_u16(data, 4)? as usize;
+    let mut tables = Vec::with_capacity(num_tables);
+    for i in 0..num_tables {
+        let record = 12 + i * 16;
+        let tag: [u8; 4] = data.get(record..record + 4)?.try_into().ok()?;
+        let offset = read_u32(data, record + 8)? as usize;
+        let length = read_u32(data, record + 12)? as usize;
+        tables.push((tag, data.get(offset..offset + length)?));
+    }
+    let table = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).map(|(_, data)| *data);
+
+    let head = table(b"head")?;
+    let long_loca = read_u16(head, 50)? != 0;
+    let num_glyphs = read_u16(table(b"maxp")?, 4)? as usize;
+    let loca = table(b"loca")?;
+    let glyf = table(b"glyf")?;
+    let glyph_range = |glyph: usize| -> Option<std::ops::Range<usize>> {
+        let (start, end) = if long_loca {
+            (read_u32(loca, glyph * 4)? as usize, read_u32(loca, glyph * 4 + 4)? as usize)
+        } else {
+            (read_u16(loca, glyph * 2)? as usize * 2, read_u16(loca, glyph * 2 + 2)? as usize * 2)
+        };
+        (start <= end && end <= glyf.len()).then(|| start..end)
+    };
+
+    // The .notdef glyph, the glyphs of the characters, and the components of composite glyphs
+    let mut used_glyphs = BTreeSet::new();
+    let mut pending = std::iter::once(0)
+        .chain(characters.iter().map(|c| font.lookup_glyph_index(*c) as usize))
+        .collect::<Vec<_>>();
+    while let Some(glyph) = pending.pop() {
+        if glyph >= num_glyphs || !used_glyphs.insert(glyph) {
+            continue;
+        }
+        let glyph_data = &glyf[glyph_range(glyph)?];
+        if glyph_data.len() < 10 || (read_u16(glyph_data, 0)? as i16) >= 0 {
+            continue;
+        }
+        const ARG_1_AND_2_ARE_WORDS: u16 = 0x1;
+        const WE_HAVE_A_SCALE: u16 = 0x8;
+        const MORE_COMPONENTS: u16 = 0x20;
+        const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x40;
+        const WE_HAVE_A_TWO_BY_TWO: u16 = 0x80;
+        let mut offset = 10;
+        loop {
+            let flags = read_u16(glyph_data, offset)?;
+            pending.push(read_u16(glyph_data, offset + 2)? as usize);
+            offset += if flags & ARG_1_AND_2_ARE_WORDS != 0 { 8 } else { 6 };
+            if flags & WE_HAVE_A_SCALE != 0 {
+                offset += 2;
+            } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+                offset += 4;
+            } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+                offset += 8;
+            }
+            if flags & MORE_COMPONENTS == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((num_glyphs + 1) * 4);
+    for glyph in 0..num_glyphs {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if used_glyphs.contains(&glyph) {
+            new_glyf.extend_from_slice(&glyf[glyph_range(glyph)?]);
+            new_glyf.resize((new_glyf.len() + 3) & !3, 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    let mut new_head = head.to_vec();
+    // Clear checkSumAdjustment, it is computed at the end
+    new_head.get_mut(8..12)?.copy_from_slice(&[0; 4]);
+    // indexToLocFormat: the new loca table uses 32 bit offsets
+    new_head.get_mut(50..52)?.copy_from_slice(&1u16.to_be_bytes());
+
+    // The signature is no longer valid
+    let tables = tables
+        .iter()
+        .filter(|(tag, _)| tag != b"DSIG")
+        .map(|(tag, data)| {
+            let data = match tag {
+                b"head" => new_head.as_slice(),
+                b"loca" => new_loca.as_slice(),
+                b"glyf" => new_glyf.as_slice(),
+                _ => *data,
+            };
+            (*tag, data)
+        })
+        .collect::<Vec<_>>();
+
+    let checksum = |data: &[u8]| {
+        data.chunks(4).fold(0u32, |sum, chunk| {
+            let mut word = [0; 4];
+            word[..chunk.len()].copy_from_slice(chunk);
+            sum.wrapping_add(u32::from_be_bytes(word))
+        })
+    };
+
+    let num_tables = tables.len() as u16;
+    let entry_selector = 15 - num_tables.leading_zeros() as u16;
+    let search_range: u16 = (1 << entry_selector) * 16;
+    let mut result = Vec::with_capacity(data.len());
+    result.extend_from_slice(&data[0..4]);
+    result.extend_from_slice(&num_tables.to_be_bytes());
+    result.extend_from_slice(&search_range.to_be_bytes());
+    result.extend_from_slice(&entry_selector.to_be_bytes());
+    result.extend_from_slice(&(num_tables * 16 - search_range).to_be_bytes());
+    let mut offset = 12 + tables.len() * 16;
+    for (tag, data) in &tables {
+        result.extend_from_slice(tag);
+        result.extend_from_slice(&checksum(data).to_be_bytes());
+        result.extend_from_slice(&(offset as u32).to_be_bytes());
+        result.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += (data.len() + 3) & !3;
+    }
+    let mut head_offset = 0;
+    for (tag, data) in &tables {
+        if tag == b"head" {
+            head_offset = result.len();
+        }
+        result.extend_from_slice(data);
+        result.resize((result.len() + 3) & !3, 0);
+    }
+    let adjustment = 0xB1B0AFBAu32.wrapping_sub(checksum(&result));
+    result[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+    Some(result)
+}
+
+#[test]
+fn test_subset_font() {
+    let data = std::fs::read(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../examples/printerdemo/ui/fonts/NotoSans-Regular.ttf"),
+    )
+    .unwrap();
+    let subset = subset_font(&data, &"Hello".chars().collect()).unwrap();
+    assert!(subset.len() < data.len() / 2);
+
+    let original = fontdue::Font::from_bytes(data.as_slice(), Default::default()).unwrap();
+    let font = fontdue::Font::from_bytes(subset.as_slice(), Default::default()).unwrap();
+    assert_eq!(font.glyph_count(), original.glyph_count());
+    for c in ['H', 'e', 'l', 'o'] {
+        assert_eq!(font.rasterize(c, 20.).1, original.rasterize(c, 20.).1);
+    }
+    let (metrics, bitmap) = font.rasterize('W', 20.);
+    assert_eq!(metrics.advance_width, original.metrics('W', 20.).advance_width);
+    assert!(bitmap.iter().all(|p| *p == 0));
+}
@@ -61,7 +61,14 @@ pub fn from_node(importident: syntax_nodes::ImportIdentifier) -> Self {
 pub struct TypeLoader<'a> {
     pub global_type_registry: Rc<RefCell<TypeRegister>>,
     pub compiler_config: &'a CompilerConfiguration,
-    style: Cow<'a, str>,
+    /// The directory of the style, in which the std-widgets.slint file is looked up: `builtin:/<style>`
+    /// for the builtin styles, or a directory of the file system for the custom styles
+    style_directory: PathBuf,
+    /// The name of a custom style and the directories in which it may be, when it was not found in
+    /// the file system and must be looked up with the `open_import_fallback` before loading a file
+    pending_style: Option<(String, Vec<PathBuf>)>,
+    /// Whether the std-widgets.slint file of the style is provided by the `open_import_fallback`
+    style_from_fallback: bool,
     all_documents: LoadedDocuments,
 }
 
@@ -99,31 +106,65 @@ pub fn new(
             Cow::from("fluent")
         });
 
-        let myself = Self {
+        let known_styles = fileaccess::styles();
+        let mut pending_style = None;
+        let style_directory = if known_styles.contains(&style.as_ref()) {
+            format!("builtin:/{}", style).into()
+        } else {
+            // A custom style is a directory with a std-widgets.slint file, either found in the include
+            // directories or given by its path
+            let candidates = compiler_config
+                .include_paths
+                .iter()
+                .map(|include_path| include_path.join(style.as_ref()))
+                .chain(std::iter::once(PathBuf::from(style.as_ref())))
+                .collect::<Vec<_>>();
+            match candidates.iter().find(|directory| {
+                fileaccess::load_file(&directory.join("std-widgets.slint")).is_some()
+            }) {
+                Some(directory) => {
+                    dunce::canonicalize(directory).unwrap_or_else(|_| directory.clone())
+                }
+                None if compiler_config.open_import_fallback.is_some() => {
+                    let style_path = candidates.last().unwrap().clone();
+                    pending_style = Some((style.to_string(), candidates));
+                    style_path
+                }
+                None => {
+                    push_unknown_style_error(&style, diag);
+                    format!("builtin:/{}", style).into()
+                }
+            }
+        };
+
+        Self {
             global_type_registry,
             compiler_config,
-            style: style.clone(),
+            style_directory,
+            pending_style,
+            style_from_fallback: false,
             all_documents: Default::default(),
-        };
-
-        let known_styles = fileaccess::styles();
-        if !known_styles.contains(&style.as_ref())
-            && myself
-                .find_file_in_include_path(None, &format!("{}/std-widgets.slint", style))
-                .is_none()
-        {
-            diag.push_diagnostic_with_span(
-                format!(
-                    "Style {} in not known. Use one of the builtin styles [{}] or make sure your custom style is found in the include directories",
-                    &style,
-                    known_styles.join(", ")
-                ),
-                Default::default(),
-                crate::diagnostics::DiagnosticLevel::Error,
-            );
         }
+    }
 
-        myself
+    /// Looks up the directory of a custom style that was not found in the file system with the
+    /// `open_import_fallback`, which provides the files of the LSP and of the browser
+    async fn resolve_pending_style(&mut self, diagnostics: &mut BuildDiagnostics) {
+        let (style, candidates) = match self.pending_style.take() {
+            Some(pending_style) => pending_style,
+            None => return,
+        };
+        if let Some(fallback) = self.compiler_config.open_import_fallback.clone() {
+            for directory in candidates {
+                let file = directory.join("std-widgets.slint");
+                if matches!(fallback(file.to_string_lossy().into()).await, Some(Ok(_))) {
+                    self.style_directory = directory;
+                    self.style_from_fallback = true;
+                    return;
+                }
+            }
+        }
+        push_unknown_style_error(&style, diagnostics);
     }
 
     /// Imports of files that don't have the .slint extension are returned.
@@ -136,7 +177,10 @@ pub async fn load_dependencies_recursively(
         let dependencies = self.collect_dependencies(doc, diagnostics).await;
         let mut foreign_imports = vec![];
         for mut import in dependencies {
-            if import.file.ends_with(".60") || import.file.ends_with(".slint") {
+            if import.file.ends_with(".60")
+                || import.file.ends_with(".slint")
+                || import.file.ends_with(".json")
+            {
                 if let Some(imported_types) =
                     ImportedName::extract_imported_names(&import.imported_types)
                 {
@@ -225,6 +269,7 @@ async fn ensure_document_loaded<'b>(
         import_token: Option<NodeOrToken>,
         diagnostics: &'b mut BuildDiagnostics,
     ) -> Option<PathBuf> {
+        self.resolve_pending_style(diagnostics).await;
         let (path, is_builtin) = self.resolve_import_path(import_token.as_ref(), file_to_import);
 
         let path_canon = dunce::canonicalize(&path).unwrap_or_else(|_| path.to_owned());
@@ -274,6 +319,27 @@ async fn ensure_document_loaded<'b>(
             }
         };
 
+        let source_code = if path_canon.extension().map_or(false, |ext| ext == "json") {
+            match crate::design_tokens::to_slint_source(&source_code) {
+                Ok((source_code, warnings)) => {
+                    for warning in warnings {
+                        diagnostics.push_warning(warning, &import_token);
+                    }
+                    source_code
+                }
+                Err(err) => {
+                    diagnostics.push_error(
+                        format!("Error parsing design tokens \"{}\": {}", path.display(), err),
+                        &import_token,
+                    );
+                    self.all_documents.currently_loading.remove(path_canon.as_path());
+                    return None;
+                }
+            }
+        } else {
+            source_code
+        };
+
         self.load_file(&path_canon, &path, source_code, is_builtin, diagnostics).await;
         let _ok = self.all_documents.currently_loading.remove(path_canon.as_path());
         assert!(_ok);
@@ -371,9 +437,20 @@ fn load_dependency<'b>(
                     }
                 });
 
-                let imported_type = match imported_type {
-                    Some(ty) => ty,
-                    None => {
+                let imported_constant =
+                    doc.exported_constants().iter().find_map(|(export_name, value)| {
+                        (import_name.external_name == export_name.as_str()).then(|| value.clone())
+                    });
+
+                let imported_type = match (imported_type, imported_constant) {
+                    (Some(ty), _) => ty,
+                    (None, Some(value)) => {
+                        registry_to_populate
+                            .borrow_mut()
+                            .insert_constant(import_name.internal_name, value);
+                        continue;
+                    }
+                    (None, None) => {
                         build_diagnostics.push_error(
                             format!(
                                 "No exported type called '{}' found in \"{}\"",
@@ -414,12 +491,16 @@ pub fn find_file_in_include_path(
                     }
                 }
             }))
-            .chain(std::iter::once_with(|| format!("builtin:/{}", self.style).into()))
+            .chain(std::iter::once_with(|| self.style_directory.clone()))
             .find_map(|include_dir| {
                 let candidate = include_dir.join(file_to_import);
                 crate::fileaccess::load_file(&candidate)
                     .map(|virtual_file| (candidate, virtual_file.builtin_contents))
             })
+            .or_else(|| {
+                (self.style_from_fallback && file_to_import == "std-widgets.slint")
+                    .then(|| (self.style_directory.join(file_to_import), None))
+            })
     }
 
     async fn collect_dependencies(
@@ -474,6 +555,18 @@ pub fn all_documents(&self) -> impl Iterator<Item = &object_tree::Document> + '_
     }
 }
 
+fn push_unknown_style_error(style: &str, diag: &mut BuildDiagnostics) {
+    diag.push_diagnostic_with_span(
+        format!(
+            "Style {} in not known. Use one of the builtin styles [{}] or make sure your custom style is found in the include directories or is the path of a directory that contains a std-widgets.slint file",
+            style,
+            fileaccess::styles().join(", ")
+        ),
+        Default::default(),
+        crate::diagnostics::DiagnosticLevel::Error,
+    );
+}
+
 #[test]
 fn test_dependency_loading() {
     let test_source_path: std::path::PathBuf =
@@ -607,9 +700,62 @@ fn test_user_style() {
 
     let global_registry = TypeRegister::builtin();
     let mut build_diagnostics = BuildDiagnostics::default();
-    let _loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+    let mut loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+
+    let maybe_button_type =
+        spin_on::spin_on(loader.import_type("std-widgets.slint", "Button", &mut build_diagnostics));
 
     assert!(!build_diagnostics.has_error());
+    assert!(maybe_button_type.is_some());
+}
+
+#[test]
+fn test_style_directory() {
+    let style_dir: std::path::PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "tests", "typeloader", "custom_style", "TestStyle"]
+            .iter()
+            .collect();
+
+    let mut compiler_config =
+        CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    compiler_config.style = Some(style_dir.to_string_lossy().into());
+
+    let global_registry = TypeRegister::builtin();
+    let mut build_diagnostics = BuildDiagnostics::default();
+    let mut loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+
+    let (path, _) = loader.resolve_import_path(None, "std-widgets.slint");
+    assert_eq!(path, dunce::canonicalize(&style_dir).unwrap().join("std-widgets.slint"));
+
+    let maybe_button_type =
+        spin_on::spin_on(loader.import_type("std-widgets.slint", "Button", &mut build_diagnostics));
+
+    assert!(!build_diagnostics.has_error());
+    assert!(maybe_button_type.is_some());
+}
+
+#[test]
+fn test_style_directory_from_callback() {
+    let mut compiler_config =
+        CompilerConfiguration::new(crate::generator::OutputFormat::Interpreter);
+    compiler_config.style = Some("/virtual/VirtualStyle".into());
+    compiler_config.open_import_fallback = Some(Rc::new(move |path| {
+        Box::pin(async move {
+            (path == "/virtual/VirtualStyle/std-widgets.slint")
+                .then(|| Ok("export Button := Rectangle {}".to_owned()))
+        })
+    }));
+
+    let global_registry = TypeRegister::builtin();
+    let mut build_diagnostics = BuildDiagnostics::default();
+    let mut loader = TypeLoader::new(global_registry, &compiler_config, &mut build_diagnostics);
+    assert!(!build_diagnostics.has_error());
+
+    let maybe_button_type =
+        spin_on::spin_on(loader.import_type("std-widgets.slint", "Button", &mut build_diagnostics));
+
+    assert!(!build_diagnostics.has_error());
+    assert!(maybe_button_type.is_some());
 }
 
 #[test]
@@ -49,6 +49,7 @@ fn new() -> Self {
                         name: stringify!($Name).replace('_', "-"),
                         values: vec![$(stringify!($Value).trim_start_matches("r#").replace('_', "-")),*],
                         default_value: 0,
+                        node: None,
                     })),*
                 }
             }
@@ -75,8 +76,33 @@ fn fill_register(&self, register: &mut TypeRegister) {
     ("opacity", Type::Float32),
     ("cache-rendering-hint", Type::Bool),
     ("visible", Type::Bool), // ("enabled", Type::Bool),
+    ("has-hover", Type::Bool),
 ];
 
+/// The callbacks called when the mouse enters or leaves the element, see the lower_hover pass
+pub(crate) const RESERVED_HOVER_CALLBACKS: &[&str] = &["pointer-entered", "pointer-exited"];
+
+/// The callbacks called when the element is created, when its window is shown or hidden, and before it's
+/// destroyed by a `for` or an `if`, see the lower_lifecycle pass
+pub(crate) const RESERVED_LIFECYCLE_CALLBACKS: &[&str] =
+    &["init", "shown", "hidden", "will-destroy"];
+
+/// Returns true if `name` is a lifecycle callback that isn't shadowed by a property, a callback or a
+/// function of the same name, declared by `ty` or by the types it derives from.
+pub(crate) fn is_lifecycle_callback(ty: &Type, name: &str) -> bool {
+    fn is_declared(ty: &Type, name: &str) -> bool {
+        match ty {
+            Type::Builtin(b) => b.properties.contains_key(name),
+            Type::Component(c) => {
+                let root = c.root_element.borrow();
+                root.property_declarations.contains_key(name) || is_declared(&root.base_type, name)
+            }
+            _ => false,
+        }
+    }
+    RESERVED_LIFECYCLE_CALLBACKS.contains(&name) && !is_declared(ty, name)
+}
+
 pub(crate) const RESERVED_DROP_SHADOW_PROPERTIES: &[(&str, Type)] = &[
     ("drop-shadow-offset-x", Type::LogicalLength),
     ("drop-shadow-offset-y", Type::LogicalLength),
@@ -84,6 +110,25 @@ fn fill_register(&self, register: &mut TypeRegister) {
     ("drop-shadow-color", Type::Color),
 ];
 
+pub(crate) const RESERVED_ACCESSIBILITY_PROPERTIES: &[(&str, Type)] = &[
+    ("accessible-checkable", Type::Bool),
+    ("accessible-checked", Type::Bool),
+    ("accessible-description", Type::String),
+    ("accessible-label", Type::String),
+    ("accessible-value", Type::String),
+    ("accessible-value-maximum", Type::Float32),
+    ("accessible-value-minimum", Type::Float32),
+    ("accessible-value-step", Type::Float32),
+];
+
+/// The callbacks called when an assistive technology requests an action on the element
+pub(crate) const RESERVED_ACCESSIBILITY_ACTIONS: &[&str] = &[
+    "accessible-action-default",
+    "accessible-action-increment",
+    "accessible-action-decrement",
+    "accessible-action-set-value",
+];
+
 /// list of reserved property injected in every item
 pub fn reserved_properties() -> impl Iterator<Item = (&'static str, Type)> {
     RESERVED_GEOMETRY_PROPERTIES
@@ -91,14 +136,35 @@ pub fn reserved_properties() -> impl Iterator<Item = (&'static str, Type)> {
         .chain(RESERVED_LAYOUT_PROPERTIES.iter())
         .chain(RESERVED_OTHER_PROPERTIES.iter())
         .chain(RESERVED_DROP_SHADOW_PROPERTIES.iter())
+        .chain(RESERVED_ACCESSIBILITY_PROPERTIES.iter())
         .map(|(k, v)| (*k, v.clone()))
+        .chain(RESERVED_ACCESSIBILITY_ACTIONS.iter().map(|name| {
+            let args =
+                if *name == "accessible-action-set-value" { vec![Type::String] } else { vec![] };
+            (*name, Type::Callback { return_type: None, args })
+        }))
+        .chain(
+            RESERVED_HOVER_CALLBACKS
+                .iter()
+                .chain(RESERVED_LIFECYCLE_CALLBACKS.iter())
+                .map(|name| (*name, Type::Callback { return_type: None, args: vec![] })),
+        )
         .chain(IntoIterator::into_iter([
             ("forward-focus", Type::ElementReference),
+            ("anchor", Type::ElementReference),
+            ("anchor-x", Type::Percent),
+            ("anchor-y", Type::Percent),
             ("focus", BuiltinFunction::SetFocusItem.ty()),
+            ("scroll-to", BuiltinFunction::ScrollTo.ty()),
+            ("ensure-visible", BuiltinFunction::EnsureVisible.ty()),
             (
                 "dialog-button-role",
                 Type::Enumeration(BUILTIN_ENUMS.with(|e| e.DialogButtonRole.clone())),
             ),
+            (
+                "accessible-role",
+                Type::Enumeration(BUILTIN_ENUMS.with(|e| e.AccessibleRole.clone())),
+            ),
         ]))
 }
 
@@ -132,6 +198,11 @@ pub fn reserved_property(name: &str) -> PropertyLookupResult {
 pub fn reserved_member_function(name: &str) -> Expression {
     for (m, e) in [
         ("focus", Expression::BuiltinFunctionReference(BuiltinFunction::SetFocusItem, None)), // match for callable "focus" property
+        ("scroll-to", Expression::BuiltinFunctionReference(BuiltinFunction::ScrollTo, None)),
+        (
+            "ensure-visible",
+            Expression::BuiltinFunctionReference(BuiltinFunction::EnsureVisible, None),
+        ),
     ]
     .iter()
     {
@@ -146,6 +217,8 @@ pub fn reserved_member_function(name: &str) -> Expression {
 pub struct TypeRegister {
     /// The set of types.
     types: HashMap<String, Type>,
+    /// The constants declared with `const`, or imported
+    constants: HashMap<String, Expression>,
     supported_property_animation_types: HashSet<String>,
     pub(crate) property_animation_type: Type,
     /// Map from a context restricted type to the list of contexts (parent type) it is allowed in. This is
@@ -165,6 +238,24 @@ pub fn insert_type_with_name(&mut self, t: Type, name: String) {
         self.types.insert(name, t);
     }
 
+    /// Returns true if the type with this name was declared or imported in this register,
+    /// and not in one of its parent
+    pub fn is_local_type(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+
+    pub fn insert_constant(&mut self, name: String, value: Expression) {
+        self.constants.insert(name, value);
+    }
+
+    /// Returns the value of the constant with the given name
+    pub fn lookup_constant(&self, name: &str) -> Option<Expression> {
+        self.constants
+            .get(name)
+            .cloned()
+            .or_else(|| self.parent_registry.as_ref()?.borrow().lookup_constant(name))
+    }
+
     pub fn builtin() -> Rc<RefCell<Self>> {
         let mut register = TypeRegister::default();
 
@@ -216,6 +307,29 @@ pub fn builtin() -> Rc<RefCell<Self>> {
             _ => unreachable!(),
         };
 
+        match &mut register.types.get_mut("TextInput").unwrap() {
+            Type::Builtin(ref mut b) => {
+                for (name, function) in [
+                    ("undo", BuiltinFunction::TextInputUndo),
+                    ("redo", BuiltinFunction::TextInputRedo),
+                    ("find-next", BuiltinFunction::TextInputFindNext),
+                    ("find-previous", BuiltinFunction::TextInputFindPrevious),
+                    ("search-match-count", BuiltinFunction::TextInputSearchMatchCount),
+                    ("current-match", BuiltinFunction::TextInputCurrentMatch),
+                ] {
+                    Rc::get_mut(b)
+                        .unwrap()
+                        .properties
+                        .insert(name.into(), BuiltinPropertyInfo::new(function.ty()));
+                    Rc::get_mut(b)
+                        .unwrap()
+                        .member_functions
+                        .insert(name.into(), Expression::BuiltinFunctionReference(function, None));
+                }
+            }
+            _ => unreachable!(),
+        };
+
         Rc::new(RefCell::new(register))
     }
 
@@ -309,4 +423,13 @@ pub fn all_types(&self) -> HashMap<String, Type> {
         }
         all
     }
+
+    pub fn all_constants(&self) -> HashMap<String, Expression> {
+        let mut all =
+            self.parent_registry.as_ref().map(|r| r.borrow().all_constants()).unwrap_or_default();
+        for (k, v) in &self.constants {
+            all.insert(k.clone(), v.clone());
+        }
+        all
+    }
 }
@@ -0,0 +1,56 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+#![warn(missing_docs)]
+
+//! This module contains the types used to expose the items to assistive technologies,
+//! such as screen readers.
+//!
+//! The elements which have an `accessible-role` report it through
+//! [`ItemRc::accessible_role()`](crate::item_tree::ItemRc::accessible_role), and their other
+//! `accessible-*` properties through
+//! [`ItemRc::accessible_string_property()`](crate::item_tree::ItemRc::accessible_string_property).
+
+use crate::SharedString;
+
+pub use crate::items::AccessibleRole;
+
+/// The accessible properties of an item, other than its role.
+///
+/// They are all reported as strings: booleans are `"true"` or `"false"`, and numbers are
+/// formatted as decimals. An empty string means that the property is not set.
+#[repr(C)]
+#[derive(PartialEq, Eq, Copy, Clone, Debug, strum::Display)]
+pub enum AccessibleStringProperty {
+    /// The `accessible-checkable` property
+    Checkable,
+    /// The `accessible-checked` property
+    Checked,
+    /// The `accessible-description` property
+    Description,
+    /// The `accessible-label` property
+    Label,
+    /// The `accessible-value` property
+    Value,
+    /// The `accessible-value-maximum` property
+    ValueMaximum,
+    /// The `accessible-value-minimum` property
+    ValueMinimum,
+    /// The `accessible-value-step` property
+    ValueStep,
+}
+
+/// An action that an assistive technology requests on an item. It invokes the matching
+/// `accessible-action-*` callback of the element.
+#[repr(C)]
+#[derive(PartialEq, Clone, Debug)]
+pub enum AccessibilityAction {
+    /// The default action of the element, such as clicking a button
+    Default,
+    /// Decrements the value of the element, such as for a slider
+    Decrement,
+    /// Increments the value of the element, such as for a slider
+    Increment,
+    /// Sets the value of the element
+    SetValue(SharedString),
+}
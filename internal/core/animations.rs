@@ -206,8 +206,16 @@ pub fn now() -> Self {
         Self(Self::duration_since_start().as_millis() as u64)
     }
 
-    fn duration_since_start() -> core::time::Duration {
-        crate::backend::instance().map(|backend| backend.duration_since_start()).unwrap_or_default()
+    pub(crate) fn duration_since_start() -> core::time::Duration {
+        if let Some(mocked) = MOCKED_CLOCK.with(|clock| clock.get()) {
+            return core::time::Duration::from_millis(mocked.0);
+        }
+        let time = crate::backend::instance()
+            .map(|backend| backend.duration_since_start())
+            .unwrap_or_default();
+        #[cfg(feature = "std")]
+        let time = crate::recording::adjust_time(time);
+        time
     }
 }
 
@@ -266,12 +274,63 @@ pub fn current_tick(&self) -> Instant {
 pub static CURRENT_ANIMATION_DRIVER : AnimationDriver = AnimationDriver::default()
 );
 
+thread_local!(
+/// The time of the mocked clock, or None if the time comes from the backend
+static MOCKED_CLOCK : Cell<Option<Instant>> = Cell::new(None)
+);
+
+/// Replaces the clock of the backend with a mocked clock that only advances when
+/// [`advance_mocked_clock()`] is called, for the animations and the timers of this thread.
+/// The mocked clock starts at the current time. This is used by the testing backend, so that the
+/// code depending on animations or timers can be tested deterministically, without sleeping.
+pub fn enable_mocked_clock() {
+    if !is_clock_mocked() {
+        let now = Instant::now();
+        MOCKED_CLOCK.with(|clock| clock.set(Some(now)));
+    }
+}
+
+/// Returns true if the time comes from the mocked clock, see [`enable_mocked_clock()`]
+pub fn is_clock_mocked() -> bool {
+    MOCKED_CLOCK.with(|clock| clock.get()).is_some()
+}
+
+/// Advances the mocked clock by `duration`, enabling it first if needed. The timers expiring in the
+/// meantime are activated in the order of their timeout, with the clock set to their timeout, so that a
+/// repeated timer fires as many times as it would in real time. Then the animations are updated to
+/// the new time.
+pub fn advance_mocked_clock(duration: core::time::Duration) {
+    use crate::timers::TimerList;
+    enable_mocked_clock();
+    let set_clock = |instant| MOCKED_CLOCK.with(|clock| clock.set(Some(instant)));
+    let target = Instant::now() + duration;
+    while let Some(timeout) =
+        TimerList::next_timeout().filter(|timeout| *timeout > Instant::now() && *timeout <= target)
+    {
+        set_clock(timeout);
+        update_animations();
+        TimerList::maybe_activate_timers();
+    }
+    set_clock(target);
+    update_animations();
+    TimerList::maybe_activate_timers();
+}
+
 /// The current instant that is to be used for animation
 /// using this function register the current binding as a dependency
 pub fn current_tick() -> Instant {
     CURRENT_ANIMATION_DRIVER.with(|driver| driver.current_tick())
 }
 
+/// Returns the time in milliseconds elapsed since the start of the animation driver, and tells the driver
+/// that there are active animations so that the bindings calling this function are re-evaluated for every frame.
+pub fn animation_tick() -> u64 {
+    CURRENT_ANIMATION_DRIVER.with(|driver| {
+        driver.set_has_active_animations();
+        driver.current_tick().0
+    })
+}
+
 /// map a value between 0 and 1 to another value between 0 and 1 according to the curve
 pub fn easing_curve(curve: &EasingCurve, value: f32) -> f32 {
     match curve {
@@ -337,3 +396,16 @@ pub fn update_animations() {
         driver.update_animations(Instant(duration))
     });
 }
+
+#[cfg(feature = "ffi")]
+pub(crate) mod ffi {
+    #![allow(unsafe_code)]
+
+    use super::*;
+
+    /// Returns the time elapsed since the start of the animation driver, in milliseconds.
+    #[no_mangle]
+    pub extern "C" fn slint_animation_tick() -> u64 {
+        animation_tick()
+    }
+}
@@ -9,6 +9,9 @@
 use alloc::rc::Rc;
 
 use crate::component::ComponentVTable;
+pub use crate::event_loop::{EventLoop, IdleCallback};
+pub use crate::items::ColorScheme;
+pub use crate::items::SortOrder;
 use crate::window::WindowRc;
 
 /// This enum describes a low-level access to specific graphics APIs used
@@ -116,6 +119,24 @@ fn default() -> Self {
     }
 }
 
+/// The power state of the display that shows a window, set with [Window::set_display_power].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DisplayPower {
+    /// The display is on, with its full brightness.
+    On,
+    /// The display is on, with the brightness reduced to the given fraction of the full
+    /// brightness, between 0 and 1.
+    Dimmed(f32),
+    /// The display is off, or blank if it can't be turned off.
+    Off,
+}
+
+impl Default for DisplayPower {
+    fn default() -> Self {
+        Self::On
+    }
+}
+
 impl Window {
     /// Registers the window with the windowing system in order to make it visible on the screen.
     pub fn show(&self) {
@@ -142,6 +163,66 @@ pub fn on_close_requested(&self, callback: impl FnMut() -> CloseRequestResponse
         self.0.on_close_requested(callback);
     }
 
+    /// Returns the color scheme preferred by the user, as reported by the windowing system.
+    pub fn color_scheme(&self) -> ColorScheme {
+        crate::color_scheme::color_scheme()
+    }
+
+    /// This function allows registering a callback that's invoked when the color scheme
+    /// preferred by the user changes, for example when the user switches the system to dark mode.
+    pub fn on_color_scheme_changed(&self, callback: impl FnMut(ColorScheme) + 'static) {
+        self.0.on_color_scheme_changed(callback);
+    }
+
+    /// This function allows registering a callback that's invoked once the first frame of the
+    /// window was presented on the screen, after the window is shown. The fonts and the images
+    /// of the first frame are loaded by then, so this is the point where an application can
+    /// dismiss its splash screen.
+    ///
+    /// The callback is only invoked by the GL backend.
+    pub fn on_first_frame_rendered(&self, callback: impl FnMut() + 'static) {
+        self.0.on_first_frame_rendered(callback);
+    }
+
+    /// Sets whether the window is kept invisible when it is shown, until its first frame is
+    /// rendered. This avoids showing an empty window while the fonts and the images are loaded,
+    /// and lets a splash screen shown by the platform be replaced by the complete window.
+    ///
+    /// This must be called before [`Self::show()`]. It is only supported by the GL backend; the
+    /// other backends show the window right away.
+    pub fn set_show_after_first_frame(&self, value: bool) {
+        self.0.set_show_after_first_frame(value);
+    }
+
+    /// Returns the factor by which the size of all fonts in the window is multiplied.
+    /// The default is 1.0.
+    pub fn text_scale_factor(&self) -> f32 {
+        self.0.text_scale_factor()
+    }
+
+    /// Sets the factor by which the size of all fonts in the window is multiplied, on top of the
+    /// scale factor provided by the windowing system. This can be used to let the user of an
+    /// application choose a bigger or smaller text size.
+    ///
+    /// Lengths that use the `rem` unit are also multiplied by this factor.
+    pub fn set_text_scale_factor(&self, factor: f32) {
+        self.0.set_text_scale_factor(factor);
+        self.0.request_redraw();
+    }
+
+    /// Moves the keyboard focus to the next item that accepts it, as when pressing the Tab key.
+    ///
+    /// The items with a positive `tab-index` come first, by increasing `tab-index`, then the other
+    /// items in the order in which they are declared.
+    pub fn focus_next_item(&self) {
+        self.0.clone().focus_next_item();
+    }
+
+    /// Moves the keyboard focus to the previous item that accepts it, as when pressing Shift+Tab.
+    pub fn focus_previous_item(&self) {
+        self.0.clone().focus_previous_item();
+    }
+
     /// This function issues a request to the windowing system to redraw the contents of the window.
     pub fn request_redraw(&self) {
         self.0.request_redraw();
@@ -154,6 +235,81 @@ pub fn request_redraw(&self) {
         crate::animations::CURRENT_ANIMATION_DRIVER
             .with(|driver| driver.set_has_active_animations());
     }
+
+    /// Rotates the rendering of the window clockwise on the display, for the embedded displays that
+    /// are mounted rotated. The size of the window and the positions of the pointer events are
+    /// rotated as well, so the items are laid out for the rotated display.
+    ///
+    /// This is supported by the GL and LinuxKMS backends. With a custom platform, the rotation is set
+    /// with `SoftwareRenderer::set_rendering_rotation()` instead.
+    pub fn set_rendering_rotation(&self, rotation: crate::graphics::RenderingRotation) {
+        self.0.set_rendering_rotation(rotation);
+        self.0.request_redraw();
+    }
+
+    /// Turns the display that shows the window on or off, or dims it, for example to blank the
+    /// screen of a kiosk after a period of inactivity, see [Self::set_inactivity_timeout].
+    ///
+    /// While the display is off, the input events are not delivered to the items of the window:
+    /// they only count as user activity, so that touching the blank screen doesn't click an
+    /// invisible button.
+    ///
+    /// This is supported by the LinuxKMS backend, which controls the backlight and the power of
+    /// the display. The other backends ignore it.
+    pub fn set_display_power(&self, power: DisplayPower) {
+        self.0.set_display_power(power);
+    }
+
+    /// Returns the power state of the display last set with [Self::set_display_power].
+    pub fn display_power(&self) -> DisplayPower {
+        self.0.display_power()
+    }
+
+    /// Registers a callback that's invoked with `true` when the user didn't interact with the
+    /// window, with the mouse, the touch screen or the keyboard, for the given `timeout`, and with
+    /// `false` on the next input event after that. A kiosk can use it to turn the display off and
+    /// back on with [Self::set_display_power]:
+    ///
+    /// ```rust
+    /// # i_slint_backend_testing::init();
+    /// use slint::{ComponentHandle, DisplayPower};
+    /// slint::slint! { MyApp := Window { /* ... */ } }
+    /// let handle = MyApp::new();
+    /// let handle_weak = handle.as_weak();
+    /// handle.window().set_inactivity_timeout(Some(std::time::Duration::from_secs(60)), move |inactive| {
+    ///     let power = if inactive { DisplayPower::Off } else { DisplayPower::On };
+    ///     handle_weak.unwrap().window().set_display_power(power);
+    /// });
+    /// ```
+    ///
+    /// A `timeout` of None removes the callback.
+    pub fn set_inactivity_timeout(
+        &self,
+        timeout: Option<core::time::Duration>,
+        callback: impl FnMut(bool) + 'static,
+    ) {
+        self.0.set_inactivity_timeout(timeout, callback);
+    }
+
+    /// Returns an SVG document of the items of the window, as they are currently shown, for example
+    /// to save a report previewed in the window. The shapes and the text are vector elements, so the
+    /// document can be printed, or converted to PDF with an external tool, without loss of quality.
+    ///
+    /// The text is laid out in lines measured with the fonts of the backend, and written as the
+    /// outlines of its glyphs, so the document looks the same where the fonts are not installed.
+    /// With the backends that can't provide the outlines of their fonts, such as the Qt backend, the
+    /// text is written as text elements instead, and drawn by the viewer of the document with the
+    /// fonts it finds with the same family. The popups are not included.
+    #[cfg(feature = "std")]
+    pub fn export_svg(&self) -> String {
+        crate::graphics::svg_export::render_to_svg(&self.0)
+    }
+
+    /// Sends an input or windowing event to the window. This is used by applications that
+    /// integrate Slint with their own platform, see [`crate::platform`].
+    pub fn dispatch_event(&self, event: crate::platform::WindowEvent) {
+        crate::platform::dispatch_event(&self.0, event)
+    }
 }
 
 impl crate::window::WindowHandleAccess for Window {
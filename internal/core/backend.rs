@@ -9,7 +9,9 @@
 use alloc::rc::Rc;
 use alloc::string::String;
 
+use crate::dialogs::{MessageBoxCallback, MessageLevel};
 use crate::graphics::{Image, IntSize};
+use crate::items::StandardButtonKind;
 use crate::window::Window;
 
 #[cfg(feature = "std")]
@@ -61,6 +63,11 @@ fn register_bitmap_font(&'static self, _font_data: &'static crate::graphics::Bit
         unimplemented!()
     }
 
+    /// Shapes and rasterizes the characters of `charset` with the font of `font_request` ahead of
+    /// time, so that the first frame showing them doesn't have to. Backends that don't cache the
+    /// glyphs ignore this.
+    fn preload_glyphs(&'static self, _font_request: crate::graphics::FontRequest, _charset: &str) {}
+
     fn set_clipboard_text(&'static self, text: String);
     fn clipboard_text(&'static self) -> Option<String>;
 
@@ -69,6 +76,30 @@ fn register_bitmap_font(&'static self, _font_data: &'static crate::graphics::Bit
 
     fn image_size(&'static self, image: &Image) -> IntSize;
 
+    /// Show a message box with the native dialogs of the platform, without blocking, and call `on_close`
+    /// with the button that closed it. The `buttons` are already in the order of the platform.
+    ///
+    /// Backends without native dialogs return `on_close` as the error, and a `MessageBox` widget is shown
+    /// in a window instead.
+    fn show_message_box(
+        &'static self,
+        _level: MessageLevel,
+        _title: &str,
+        _text: &str,
+        _buttons: &[StandardButtonKind],
+        on_close: MessageBoxCallback,
+    ) -> Result<(), MessageBoxCallback> {
+        Err(on_close)
+    }
+
+    /// Returns the time between two toggles of the visibility of the text cursor, or None if the
+    /// cursor shouldn't blink, following the settings of the platform, which can disable the blinking
+    /// for accessibility. A `TextInput` can override it with its `cursor-blink-interval` and
+    /// `cursor-blinking` properties.
+    fn cursor_blink_interval(&'static self) -> Option<core::time::Duration> {
+        Some(crate::input::DEFAULT_CURSOR_BLINK_INTERVAL)
+    }
+
     fn duration_since_start(&'static self) -> core::time::Duration {
         #[cfg(feature = "std")]
         {
@@ -81,7 +112,7 @@ fn duration_since_start(&'static self) -> core::time::Duration {
 }
 
 #[cfg(feature = "std")]
-static INITIAL_INSTANT: once_cell::sync::OnceCell<instant::Instant> =
+pub(crate) static INITIAL_INSTANT: once_cell::sync::OnceCell<instant::Instant> =
     once_cell::sync::OnceCell::new();
 
 #[cfg(feature = "std")]
@@ -99,6 +130,11 @@ pub fn instance() -> Option<&'static dyn Backend> {
     PRIVATE_BACKEND_INSTANCE.get().map(|backend_box| backend_box.deref())
 }
 
+/// Sets the backend instance, or returns it back if one was already set.
+pub fn set_instance(backend: Box<dyn Backend + 'static>) -> Result<(), Box<dyn Backend + 'static>> {
+    PRIVATE_BACKEND_INSTANCE.set(backend)
+}
+
 pub fn instance_or_init(
     factory_fn: impl FnOnce() -> Box<dyn Backend + 'static>,
 ) -> &'static dyn Backend {
@@ -0,0 +1,65 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Measures how long it takes to evaluate the bindings of properties that depend on each other.
+
+use i_slint_core::benchmarks::Benchmarks;
+use i_slint_core::Property;
+use std::pin::Pin;
+use std::rc::Rc;
+
+const PROPERTY_COUNT: usize = 100;
+
+fn properties() -> Vec<Pin<Rc<Property<i32>>>> {
+    (0..PROPERTY_COUNT).map(|_| Rc::pin(Property::new(0))).collect()
+}
+
+fn main() {
+    let mut benchmarks = Benchmarks::new("properties");
+
+    let property = Rc::pin(Property::new(42));
+    benchmarks.bench("get value", || property.as_ref().get());
+
+    // Each property is bound to the previous one, so setting the first one dirties all of them
+    let chain = properties();
+    for (previous, property) in chain.iter().zip(chain.iter().skip(1)) {
+        let previous = previous.clone();
+        property.set_binding(move || previous.as_ref().get() + 1);
+    }
+    let mut value = 0;
+    benchmarks.bench("chain of 100 bindings", || {
+        value += 1;
+        chain[0].set(value);
+        chain[PROPERTY_COUNT - 1].as_ref().get()
+    });
+
+    // Many properties are bound to a single one
+    let root = Rc::pin(Property::new(0));
+    let dependents = properties();
+    for property in &dependents {
+        let root = root.clone();
+        property.set_binding(move || root.as_ref().get() * 2);
+    }
+    let mut value = 0;
+    benchmarks.bench("100 bindings on one property", || {
+        value += 1;
+        root.set(value);
+        dependents.iter().map(|property| property.as_ref().get()).sum::<i32>()
+    });
+
+    // A single binding depends on many properties
+    let dependencies = properties();
+    let sum = Rc::pin(Property::new(0));
+    let bound_dependencies = dependencies.clone();
+    sum.set_binding(move || {
+        bound_dependencies.iter().map(|property| property.as_ref().get()).sum()
+    });
+    let mut value = 0;
+    benchmarks.bench("binding on 100 properties", || {
+        value += 1;
+        dependencies[value as usize % PROPERTY_COUNT].set(value);
+        sum.as_ref().get()
+    });
+
+    benchmarks.finish();
+}
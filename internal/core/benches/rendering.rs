@@ -0,0 +1,107 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Measures the rendering of frames with the software renderer, in a window of a custom platform.
+
+use i_slint_core::benchmarks::Benchmarks;
+use i_slint_core::graphics::Size;
+use i_slint_core::platform::{Platform, Renderer, WindowAdapter, WindowEvent, WindowWeak};
+use i_slint_core::swrenderer::{RepaintBufferType, Rgb565Pixel, SoftwareRenderer};
+use i_slint_core::Color;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+slint::slint! {
+    App := Window {
+        property <bool> toggle;
+        property <length> cursor-x;
+        background: toggle ? #334 : #343;
+        VerticalLayout {
+            padding: 4px;
+            spacing: 4px;
+            for row in 6: HorizontalLayout {
+                spacing: 4px;
+                for column in 8: Rectangle {
+                    border-radius: 6px;
+                    border-width: 1px;
+                    border-color: #fff;
+                    background: column > row ? #48c : #c84;
+                }
+            }
+        }
+        Rectangle {
+            x: cursor-x;
+            y: 100px;
+            width: 20px;
+            height: 20px;
+            background: #e22;
+        }
+    }
+}
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+
+thread_local! {
+    /// The renderer of the window created by the platform
+    static RENDERER: RefCell<Option<Rc<SoftwareRenderer>>> = RefCell::new(None);
+}
+
+struct BenchWindowAdapter(Rc<SoftwareRenderer>);
+
+impl WindowAdapter for BenchWindowAdapter {
+    fn renderer(&self) -> &dyn Renderer {
+        &*self.0
+    }
+}
+
+struct BenchPlatform;
+
+impl Platform for BenchPlatform {
+    fn create_window_adapter(&self, _window: WindowWeak) -> Rc<dyn WindowAdapter> {
+        let renderer = Rc::new(SoftwareRenderer::new(RepaintBufferType::ReusedBuffer));
+        RENDERER.with(|r| *r.borrow_mut() = Some(renderer.clone()));
+        Rc::new(BenchWindowAdapter(renderer))
+    }
+}
+
+fn main() {
+    i_slint_core::platform::set_platform(Box::new(BenchPlatform)).unwrap();
+
+    let mut benchmarks = Benchmarks::new("rendering");
+
+    let app = App::new();
+    let window = app.window();
+    let size = Size::new(WIDTH as _, HEIGHT as _);
+    window.dispatch_event(WindowEvent::Resized { size });
+    let renderer = RENDERER.with(|r| r.borrow().clone()).unwrap();
+    let mut buffer = vec![Rgb565Pixel::default(); WIDTH * HEIGHT];
+    let background = Color::from_rgb_u8(0, 0, 0);
+
+    // The background changes, so all the items are drawn again
+    let mut toggle = false;
+    benchmarks.bench("full frame", || {
+        toggle = !toggle;
+        app.set_toggle(toggle);
+        renderer.render(window, &mut buffer, WIDTH, background)
+    });
+
+    // Only the small rectangle moves, so only the region around it is drawn again
+    let mut cursor_x = 0;
+    benchmarks.bench("partial frame", || {
+        cursor_x = (cursor_x + 1) % (WIDTH - 20);
+        app.set_cursor_x(cursor_x as _);
+        renderer.render(window, &mut buffer, WIDTH, background)
+    });
+
+    // The layout is computed again for the new size before drawing
+    let mut narrow = false;
+    benchmarks.bench("resize and layout", || {
+        narrow = !narrow;
+        let width = if narrow { WIDTH - 40 } else { WIDTH };
+        window.dispatch_event(WindowEvent::Resized { size: Size::new(width as _, HEIGHT as _) });
+        renderer.render(window, &mut buffer, WIDTH, background)
+    });
+
+    benchmarks.finish();
+}
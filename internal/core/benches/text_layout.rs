@@ -0,0 +1,111 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! Measures the shaping of text with the DejaVu Sans font, and the breaking of paragraphs into lines.
+
+use i_slint_core::benchmarks::Benchmarks;
+use i_slint_core::items::{TextHorizontalAlignment, TextOverflow, TextVerticalAlignment, TextWrap};
+use i_slint_core::textlayout::{TextParagraphLayout, TextShaper};
+
+const SHORT_TEXT: &str = "Hello World";
+
+const PARAGRAPH: &str = "Slint is a toolkit to efficiently develop fluid graphical user \
+    interfaces for any display: embedded devices and desktop applications. We support multiple \
+    programming languages, such as Rust, C++ or JavaScript. Ça marche aussi en français, \
+    на русском и ελληνικά, and with combining marks like ā́.";
+
+#[derive(Clone, Copy)]
+struct Glyph {
+    advance_x: f32,
+}
+
+/// Shapes the text with rustybuzz, like the renderers do with the fonts of the system
+struct Font<'a>(rustybuzz::Face<'a>);
+
+impl TextShaper for Font<'_> {
+    type LengthPrimitive = f32;
+    type Length = f32;
+    type Glyph = Glyph;
+
+    fn shape_text<GlyphStorage: std::iter::Extend<(Glyph, usize)>>(
+        &self,
+        text: &str,
+        glyphs: &mut GlyphStorage,
+    ) {
+        let mut buffer = rustybuzz::UnicodeBuffer::new();
+        buffer.push_str(text);
+        let glyph_buffer = rustybuzz::shape(&self.0, &[], buffer);
+        glyphs.extend(glyph_buffer.glyph_infos().iter().zip(glyph_buffer.glyph_positions()).map(
+            |(info, position)| {
+                (Glyph { advance_x: position.x_advance as _ }, info.cluster as usize)
+            },
+        ));
+    }
+
+    fn glyph_for_char(&self, ch: char) -> Option<Glyph> {
+        let glyph_id = self.0.glyph_index(ch)?;
+        Some(Glyph { advance_x: self.0.glyph_hor_advance(glyph_id)? as _ })
+    }
+
+    fn glyph_advance_x(&self, glyph: &Glyph) -> f32 {
+        glyph.advance_x
+    }
+}
+
+fn main() {
+    let mut benchmarks = Benchmarks::new("text_layout");
+
+    let dejavu_path: std::path::PathBuf =
+        [env!("CARGO_MANIFEST_DIR"), "..", "backends", "gl", "fonts", "DejaVuSans.ttf"]
+            .iter()
+            .collect();
+    let font_data = std::fs::read(dejavu_path).expect("unable to load test dejavu font");
+    let font =
+        Font(rustybuzz::Face::from_slice(&font_data, 0).expect("unable to parse dejavu font"));
+    // The font units of DejaVu Sans are 2048 per em, so this is a 12px font in a 300px wide box
+    let font_height = 2048. * 1.2;
+    let max_width = 2048. * 25.;
+
+    let mut glyphs = Vec::new();
+    benchmarks.bench("shape short text", || {
+        glyphs.clear();
+        font.shape_text(SHORT_TEXT, &mut glyphs);
+        glyphs.len()
+    });
+    benchmarks.bench("shape paragraph", || {
+        glyphs.clear();
+        font.shape_text(PARAGRAPH, &mut glyphs);
+        glyphs.len()
+    });
+
+    benchmarks.bench("size of paragraph", || {
+        i_slint_core::textlayout::text_size(&font, PARAGRAPH, Some(max_width))
+    });
+
+    for (name, wrap, overflow) in [
+        ("layout wrapped paragraph", TextWrap::word_wrap, TextOverflow::clip),
+        ("layout elided paragraph", TextWrap::no_wrap, TextOverflow::elide),
+    ] {
+        let layout = TextParagraphLayout {
+            string: PARAGRAPH,
+            font: &font,
+            font_height,
+            max_width,
+            max_height: font_height * 10.,
+            horizontal_alignment: TextHorizontalAlignment::center,
+            vertical_alignment: TextVerticalAlignment::center,
+            wrap,
+            overflow,
+            ellipsis: "…",
+            single_line: false,
+            paragraph_spacing: 0.,
+        };
+        benchmarks.bench(name, || {
+            let mut glyph_count = 0;
+            layout.layout_lines(|glyphs, _, _| glyph_count += glyphs.count());
+            glyph_count
+        });
+    }
+
+    benchmarks.finish();
+}
@@ -0,0 +1,211 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+This module contains a small benchmark harness, used by the benchmarks in `internal/core/benches`
+to measure the text layout, the property evaluation and the rendering of frames. It does not need
+more than the standard library, so that the benchmarks can be cross-compiled and run on the
+target hardware.
+
+Each benchmark runs a routine for a while to estimate how long it takes, and then measures
+[`SAMPLE_COUNT`] samples of many iterations. The results are printed to stdout. When the
+`SLINT_BENCHMARK_OUTPUT` environment variable names a file, the results are also appended to it
+as JSON, one object per line, to compare them between two builds or two devices.
+
+The command line arguments that don't start with `--` only run the benchmarks whose name
+contains one of them, and `SLINT_BENCHMARK_TIME` sets the time in seconds spent measuring each
+benchmark (one second by default).
+*/
+
+#![warn(missing_docs)]
+
+use instant::{Duration, Instant};
+use std::io::Write;
+
+/// The number of samples that are measured for each benchmark
+pub const SAMPLE_COUNT: usize = 30;
+
+const WARM_UP_TIME: Duration = Duration::from_millis(100);
+
+/// The time measured for one benchmark.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchmarkResult {
+    /// The group of the benchmark, given to [`Benchmarks::new()`]
+    pub group: String,
+    /// The name of the benchmark, given to [`Benchmarks::bench()`]
+    pub name: String,
+    /// The number of times the routine was run for all the samples
+    pub iterations: u64,
+    /// The shortest time of one iteration among all the samples
+    pub min: Duration,
+    /// The mean time of one iteration over all the samples
+    pub mean: Duration,
+    /// The median time of one iteration over all the samples
+    pub median: Duration,
+    /// The longest time of one iteration among all the samples
+    pub max: Duration,
+}
+
+impl BenchmarkResult {
+    /// Returns the result as a JSON object on a single line, with the times in nanoseconds.
+    pub fn to_json(&self) -> String {
+        format!(
+            r#"{{"group":"{}","name":"{}","iterations":{},"min_ns":{},"mean_ns":{},"median_ns":{},"max_ns":{}}}"#,
+            escape_json(&self.group),
+            escape_json(&self.name),
+            self.iterations,
+            self.min.as_nanos(),
+            self.mean.as_nanos(),
+            self.median.as_nanos(),
+            self.max.as_nanos(),
+        )
+    }
+}
+
+impl core::fmt::Display for BenchmarkResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{}/{}: {:?} (min {:?}, median {:?}, max {:?}, {} iterations)",
+            self.group, self.name, self.mean, self.min, self.median, self.max, self.iterations
+        )
+    }
+}
+
+fn escape_json(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+    for ch in string.chars() {
+        match ch {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            ch if (ch as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", ch as u32)),
+            ch => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Prevents the optimizer from removing the computation of `value`, like
+/// `std::hint::black_box`, which is not available in the minimum supported Rust version.
+#[allow(unsafe_code)]
+pub fn black_box<T>(value: T) -> T {
+    // Safety: the value is read from a valid reference, and the original is forgotten so that
+    // it is only dropped once.
+    unsafe {
+        let result = core::ptr::read_volatile(&value);
+        core::mem::forget(value);
+        result
+    }
+}
+
+/// Runs and measures the benchmarks of a group, and reports their results in [`Self::finish()`].
+pub struct Benchmarks {
+    group: String,
+    filters: Vec<String>,
+    measurement_time: Duration,
+    results: Vec<BenchmarkResult>,
+}
+
+impl Benchmarks {
+    /// Creates the harness for the benchmarks of `group`, configured by the command line
+    /// arguments and the environment variables described in the [module documentation](self).
+    pub fn new(group: &str) -> Self {
+        let filters =
+            std::env::args().skip(1).filter(|arg| !arg.starts_with("--")).collect::<Vec<_>>();
+        let measurement_time = std::env::var("SLINT_BENCHMARK_TIME")
+            .ok()
+            .and_then(|time| time.parse::<f64>().ok())
+            .filter(|time| *time > 0.)
+            .map_or(Duration::from_secs(1), Duration::from_secs_f64);
+        Self { group: group.into(), filters, measurement_time, results: Vec::new() }
+    }
+
+    /// Measures how long `routine` takes, unless the benchmark is filtered out. The value
+    /// returned by the routine is passed to [`black_box()`] so that it isn't optimized away.
+    pub fn bench<T>(&mut self, name: &str, mut routine: impl FnMut() -> T) {
+        if !self.filters.is_empty() && !self.filters.iter().any(|filter| name.contains(filter)) {
+            return;
+        }
+
+        // Run the routine for a while to fill the caches, and estimate how long it takes
+        let mut warm_up_iterations = 0u32;
+        let warm_up_start = Instant::now();
+        while warm_up_iterations == 0 || warm_up_start.elapsed() < WARM_UP_TIME {
+            black_box(routine());
+            warm_up_iterations += 1;
+        }
+        let estimated_time = warm_up_start.elapsed() / warm_up_iterations;
+
+        let sample_time = self.measurement_time / SAMPLE_COUNT as u32;
+        let iterations_per_sample = (sample_time.as_nanos() / estimated_time.as_nanos().max(1))
+            .clamp(1, u32::MAX as _) as u32;
+
+        let mut samples = (0..SAMPLE_COUNT)
+            .map(|_| {
+                let start = Instant::now();
+                for _ in 0..iterations_per_sample {
+                    black_box(routine());
+                }
+                start.elapsed() / iterations_per_sample
+            })
+            .collect::<Vec<_>>();
+        samples.sort_unstable();
+
+        let result = BenchmarkResult {
+            group: self.group.clone(),
+            name: name.into(),
+            iterations: iterations_per_sample as u64 * SAMPLE_COUNT as u64,
+            min: samples[0],
+            mean: samples.iter().sum::<Duration>() / SAMPLE_COUNT as u32,
+            median: samples[SAMPLE_COUNT / 2],
+            max: samples[SAMPLE_COUNT - 1],
+        };
+        println!("{}", result);
+        self.results.push(result);
+    }
+
+    /// Returns the results of the benchmarks measured so far.
+    pub fn results(&self) -> &[BenchmarkResult] {
+        &self.results
+    }
+
+    /// Appends the results to the file named by the `SLINT_BENCHMARK_OUTPUT` environment
+    /// variable, if it is set.
+    pub fn finish(self) {
+        let path = match std::env::var_os("SLINT_BENCHMARK_OUTPUT") {
+            Some(path) => path,
+            None => return,
+        };
+        let write_results = || -> std::io::Result<()> {
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+            for result in &self.results {
+                writeln!(file, "{}", result.to_json())?;
+            }
+            Ok(())
+        };
+        if let Err(err) = write_results() {
+            eprintln!(
+                "Could not write the benchmark results to {}: {}",
+                std::path::Path::new(&path).display(),
+                err
+            );
+        }
+    }
+}
+
+#[test]
+fn test_json_output() {
+    let result = BenchmarkResult {
+        group: "text".into(),
+        name: "shape \"long\"\n".into(),
+        iterations: 300,
+        min: Duration::from_nanos(10),
+        mean: Duration::from_nanos(12),
+        median: Duration::from_nanos(11),
+        max: Duration::from_micros(2),
+    };
+    assert_eq!(
+        result.to_json(),
+        r#"{"group":"text","name":"shape \"long\"\u000a","iterations":300,"min_ns":10,"mean_ns":12,"median_ns":11,"max_ns":2000}"#
+    );
+}
@@ -0,0 +1,99 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+The color scheme preferred by the user.
+
+The backends report the color scheme of the platform with [`set_color_scheme`] when they start
+and whenever the user changes it. It is available in the `.slint` language as
+`Platform.color-scheme`, and bindings using it are re-evaluated when it changes.
+*/
+
+use crate::window::Window;
+use crate::Property;
+use alloc::boxed::Box;
+use alloc::rc::Weak;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::pin::Pin;
+
+pub use crate::items::ColorScheme;
+
+#[cfg(all(not(feature = "std"), feature = "unsafe_single_core"))]
+use crate::unsafe_single_core::thread_local;
+
+thread_local!(static CURRENT_COLOR_SCHEME : Pin<Box<Property<ColorScheme>>> =
+    Box::pin(Property::new_named(ColorScheme::unknown, "i_slint_core::color_scheme::CURRENT_COLOR_SCHEME")));
+
+thread_local!(static LISTENERS : RefCell<Vec<Weak<Window>>> = RefCell::default());
+
+/// Returns the color scheme preferred by the user.
+/// Calling this function from a binding registers the color scheme as a dependency of the binding.
+pub fn color_scheme() -> ColorScheme {
+    CURRENT_COLOR_SCHEME.with(|scheme| scheme.as_ref().get())
+}
+
+/// Change the color scheme reported to the application. This is called by the backends
+/// when the preference of the platform changes, and can also be called by the application
+/// to override it.
+///
+/// The `color_scheme_changed` callback of the windows is run if the color scheme changed.
+pub fn set_color_scheme(scheme: ColorScheme) {
+    let changed = CURRENT_COLOR_SCHEME.with(|current| {
+        let current = current.as_ref();
+        if current.get_untracked() == scheme {
+            return false;
+        }
+        current.set(scheme);
+        true
+    });
+    if !changed {
+        return;
+    }
+    let windows = LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        listeners.retain(|window| window.strong_count() > 0);
+        listeners.iter().filter_map(Weak::upgrade).collect::<Vec<_>>()
+    });
+    for window in windows {
+        window.color_scheme_changed(scheme);
+    }
+}
+
+/// Registers a window whose `color_scheme_changed` callback must be run when the color scheme changes.
+pub(crate) fn register_listener(window: Weak<Window>) {
+    LISTENERS.with(|listeners| {
+        let mut listeners = listeners.borrow_mut();
+        if !listeners.iter().any(|w| w.ptr_eq(&window)) {
+            listeners.push(window);
+        }
+    })
+}
+
+#[test]
+fn test_color_scheme() {
+    assert_eq!(color_scheme(), ColorScheme::unknown);
+    set_color_scheme(ColorScheme::dark);
+    assert_eq!(color_scheme(), ColorScheme::dark);
+    set_color_scheme(ColorScheme::light);
+    assert_eq!(color_scheme(), ColorScheme::light);
+}
+
+#[cfg(feature = "ffi")]
+pub(crate) mod ffi {
+    #![allow(unsafe_code)]
+
+    use super::*;
+
+    /// Returns the color scheme preferred by the user.
+    #[no_mangle]
+    pub extern "C" fn slint_color_scheme() -> ColorScheme {
+        color_scheme()
+    }
+
+    /// Change the color scheme reported to the application.
+    #[no_mangle]
+    pub extern "C" fn slint_set_color_scheme(scheme: ColorScheme) {
+        set_color_scheme(scheme)
+    }
+}
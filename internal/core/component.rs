@@ -5,6 +5,7 @@
 
 //! This module contains the basic datastructures that are exposed to the C API
 
+use crate::accessibility::{AccessibilityAction, AccessibleRole, AccessibleStringProperty};
 use crate::item_tree::{
     ItemTreeNode, ItemVisitorVTable, ItemWeak, TraversalOrder, VisitChildrenResult,
 };
@@ -75,6 +76,27 @@ pub struct ComponentVTable {
     pub layout_info:
         extern "C" fn(core::pin::Pin<VRef<ComponentVTable>>, Orientation) -> LayoutInfo,
 
+    /// Returns the accessible role of the item at `item_index`
+    pub accessible_role:
+        extern "C" fn(core::pin::Pin<VRef<ComponentVTable>>, item_index: usize) -> AccessibleRole,
+
+    /// Returns the value of an accessible property of the item at `item_index`, or an empty
+    /// string if it is not set.
+    /// The result is passed by &mut because SharedString has a destructor
+    pub accessible_string_property: extern "C" fn(
+        core::pin::Pin<VRef<ComponentVTable>>,
+        item_index: usize,
+        what: AccessibleStringProperty,
+        result: &mut crate::SharedString,
+    ),
+
+    /// Performs an accessibility action on the item at `item_index`
+    pub accessibility_action: extern "C" fn(
+        core::pin::Pin<VRef<ComponentVTable>>,
+        item_index: usize,
+        action: &AccessibilityAction,
+    ),
+
     /// in-place destructor (for VRc)
     pub drop_in_place: unsafe fn(VRefMut<ComponentVTable>) -> vtable::Layout,
     /// dealloc function (for VRc)
@@ -114,6 +136,16 @@ pub fn free_component_item_graphics_resources<Base>(
     window.free_graphics_resources(&mut item_array.iter().map(|item| item.apply_pin(base)));
 }
 
+/// Calls the `will-destroy` callbacks of the elements of the component, including the ones of the
+/// components instantiated by its repeaters. This is called before a repeater destroys the component,
+/// and when the last handle of a component is dropped.
+pub fn will_destroy_component(component: &ComponentRc) {
+    // The component may be destroyed while evaluating a binding, which must not depend on the callbacks
+    crate::properties::evaluate_no_tracking(|| {
+        crate::items::dispatch_lifecycle_event(component, crate::items::LifecycleEvent::WillDestroy)
+    })
+}
+
 #[cfg(feature = "ffi")]
 pub(crate) mod ffi {
     #![allow(unsafe_code)]
@@ -149,4 +181,10 @@ pub(crate) mod ffi {
             window,
         )
     }
+
+    /// Calls the `will-destroy` callbacks of the elements of a component that is about to be destroyed.
+    #[no_mangle]
+    pub extern "C" fn slint_component_will_destroy(component: &ComponentRc) {
+        super::will_destroy_component(component)
+    }
 }
@@ -0,0 +1,134 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Standard message boxes.
+
+A message box is first offered to the backend with [`Backend::show_message_box`](crate::backend::Backend::show_message_box),
+which shows it with the native dialogs of the platform if it has some. Otherwise the caller shows the `MessageBox`
+widget in a window. Either way, the button that closed the message box is reported through a
+[`MessageBoxFuture`].
+*/
+
+use crate::items::{DialogButtonRole, StandardButtonKind};
+use crate::layout::{dialog_button_roles_order, standard_button_role};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::Pin;
+use core::task::{Context, Poll, Waker};
+
+pub use crate::items::MessageLevel;
+
+/// The function called with the button that closed a message box
+pub type MessageBoxCallback = Box<dyn FnOnce(StandardButtonKind)>;
+
+#[derive(Default)]
+struct MessageBoxState {
+    result: Option<StandardButtonKind>,
+    waker: Option<Waker>,
+}
+
+/// A future that resolves to the button that closed a message box.
+///
+/// It is returned by `slint::dialogs::message_box()`.
+pub struct MessageBoxFuture(Rc<RefCell<MessageBoxState>>);
+
+impl MessageBoxFuture {
+    /// Returns a future, and the callback that resolves it when the message box is closed
+    pub fn new() -> (Self, MessageBoxCallback) {
+        let state = Rc::new(RefCell::new(MessageBoxState::default()));
+        let callback_state = state.clone();
+        let callback = Box::new(move |button| {
+            let waker = {
+                let mut state = callback_state.borrow_mut();
+                state.result = Some(button);
+                state.waker.take()
+            };
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        });
+        (Self(state), callback)
+    }
+}
+
+impl Future for MessageBoxFuture {
+    type Output = StandardButtonKind;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.borrow_mut();
+        match state.result {
+            Some(button) => Poll::Ready(button),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// Returns the buttons in the order in which they are shown in a dialog on the current platform,
+/// according to their role
+pub fn sort_dialog_buttons(buttons: &[StandardButtonKind]) -> Vec<StandardButtonKind> {
+    dialog_button_roles_order()
+        .iter()
+        .flatten()
+        .flat_map(|role| buttons.iter().copied().filter(move |b| standard_button_role(*b) == *role))
+        .collect()
+}
+
+/// Returns the button reported when a message box is closed without clicking one of its buttons:
+/// the first one that rejects the message box, or the last one
+pub fn escape_button(buttons: &[StandardButtonKind]) -> StandardButtonKind {
+    buttons
+        .iter()
+        .copied()
+        .find(|b| standard_button_role(*b) == DialogButtonRole::reject)
+        .or_else(|| buttons.last().copied())
+        .unwrap_or_default()
+}
+
+#[test]
+fn message_box_future() {
+    use core::task::{RawWaker, RawWakerVTable};
+
+    fn raw_waker() -> RawWaker {
+        fn clone(_: *const ()) -> RawWaker {
+            raw_waker()
+        }
+        fn noop(_: *const ()) {}
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+        RawWaker::new(core::ptr::null(), &VTABLE)
+    }
+    #[allow(unsafe_code)]
+    let waker = unsafe { Waker::from_raw(raw_waker()) };
+    let mut cx = Context::from_waker(&waker);
+
+    let (mut future, callback) = MessageBoxFuture::new();
+    assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Pending);
+    callback(StandardButtonKind::yes);
+    assert_eq!(Pin::new(&mut future).poll(&mut cx), Poll::Ready(StandardButtonKind::yes));
+}
+
+#[test]
+fn sorted_buttons() {
+    let sorted = sort_dialog_buttons(&[
+        StandardButtonKind::help,
+        StandardButtonKind::cancel,
+        StandardButtonKind::ok,
+    ]);
+    assert_eq!(sorted.len(), 3);
+    assert_eq!(escape_button(&sorted), StandardButtonKind::cancel);
+    assert_eq!(escape_button(&[StandardButtonKind::yes]), StandardButtonKind::yes);
+    if cfg!(windows) {
+        assert_eq!(
+            sorted,
+            [StandardButtonKind::ok, StandardButtonKind::cancel, StandardButtonKind::help]
+        );
+    } else {
+        assert_eq!(sorted[0], StandardButtonKind::help);
+    }
+}
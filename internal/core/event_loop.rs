@@ -0,0 +1,227 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+    Utilities of the event loop that don't depend on a window: the callbacks invoked when the
+    event loop is about to sleep, the events with a payload posted from other threads, and
+    whether the event loop is running.
+
+    The backends call [`enter_event_loop()`] when their event loop starts, and
+    [`invoke_idle_callbacks()`] before they wait for the next event.
+*/
+
+#![warn(missing_docs)]
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::any::{Any, TypeId};
+use core::cell::{Cell, RefCell};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+type IdleCallbackFn = Box<dyn FnMut()>;
+type EventHandler = Box<dyn FnMut(Box<dyn Any>)>;
+
+#[derive(Default)]
+struct EventLoopState {
+    next_idle_callback_id: usize,
+    idle_callbacks: Vec<(usize, Option<IdleCallbackFn>)>,
+    event_handlers: BTreeMap<TypeId, Option<EventHandler>>,
+}
+
+#[cfg(all(not(feature = "std"), feature = "unsafe_single_core"))]
+use crate::unsafe_single_core::thread_local;
+
+thread_local!(static EVENT_LOOP_STATE : RefCell<EventLoopState> = RefCell::default());
+
+static EVENT_LOOP_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// The utilities of the event loop that don't depend on a window.
+///
+/// ## Example
+/// ```rust,no_run
+/// # i_slint_backend_testing::init();
+/// use slint::EventLoop;
+/// struct SensorReading(f32);
+/// EventLoop::on_event(|reading: SensorReading| println!("The sensor reads {}", reading.0));
+/// let _idle = EventLoop::on_idle(|| println!("Nothing to do until the next event"));
+/// std::thread::spawn(|| EventLoop::post_event(SensorReading(42.)));
+/// slint::run_event_loop();
+/// ```
+pub struct EventLoop {
+    _private: (),
+}
+
+impl EventLoop {
+    /// Returns true while the event loop is running, in any thread.
+    pub fn is_running() -> bool {
+        EVENT_LOOP_RUNNING.load(Ordering::Relaxed)
+    }
+
+    /// Registers a callback that is invoked each time the event loop is about to wait for the next
+    /// event, after the windows were rendered, and when no animation is running. This is where
+    /// the application can poll its hardware or kick a watchdog without a timer that fires all
+    /// the time. The callback must return quickly, as the events are not processed meanwhile.
+    ///
+    /// The callback is unregistered when the returned [`IdleCallback`] is dropped.
+    ///
+    /// Note: this must be called in the thread that runs the Slint event loop.
+    #[must_use = "the idle callback is unregistered when the IdleCallback is dropped"]
+    pub fn on_idle(callback: impl FnMut() + 'static) -> IdleCallback {
+        EVENT_LOOP_STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let id = state.next_idle_callback_id;
+            state.next_idle_callback_id += 1;
+            state.idle_callbacks.push((id, Some(Box::new(callback))));
+            IdleCallback { id: Cell::new(Some(id)) }
+        })
+    }
+
+    /// Sends `payload` from any thread to the thread that runs the event loop, where it is passed
+    /// to the handler that was registered for its type with [`Self::on_event()`]. The payload is
+    /// dropped if there is no such handler.
+    ///
+    /// Note: this must be called after the Slint backend is initialized, like
+    /// [`invoke_from_event_loop()`](crate::api::invoke_from_event_loop).
+    pub fn post_event<T: Send + 'static>(payload: T) {
+        crate::api::invoke_from_event_loop(move || dispatch_event(Box::new(payload)))
+    }
+
+    /// Registers the handler of the events posted with [`Self::post_event()`] whose payload has the
+    /// type `T`, replacing the previous handler of that type.
+    ///
+    /// Note: this must be called in the thread that runs the Slint event loop.
+    pub fn on_event<T: 'static>(mut handler: impl FnMut(T) + 'static) {
+        let handler: EventHandler = Box::new(move |payload: Box<dyn Any>| {
+            if let Ok(payload) = payload.downcast::<T>() {
+                handler(*payload)
+            }
+        });
+        EVENT_LOOP_STATE.with(|state| {
+            state.borrow_mut().event_handlers.insert(TypeId::of::<T>(), Some(handler));
+        })
+    }
+}
+
+/// The handle of a callback registered with [`EventLoop::on_idle()`], which unregisters it when
+/// dropped.
+pub struct IdleCallback {
+    id: Cell<Option<usize>>,
+}
+
+impl IdleCallback {
+    /// Unregisters the callback. Does nothing if it was already unregistered.
+    pub fn unregister(&self) {
+        if let Some(id) = self.id.take() {
+            // The state might already be destroyed when the thread exits
+            let _ = EVENT_LOOP_STATE.try_with(|state| {
+                state.borrow_mut().idle_callbacks.retain(|(callback_id, _)| *callback_id != id)
+            });
+        }
+    }
+}
+
+impl Drop for IdleCallback {
+    fn drop(&mut self) {
+        self.unregister();
+    }
+}
+
+fn dispatch_event(payload: Box<dyn Any>) {
+    let type_id = (*payload).type_id();
+    // The handler is taken out of the state while it runs, so that it can register handlers
+    let handler = EVENT_LOOP_STATE.with(|state| {
+        state.borrow_mut().event_handlers.get_mut(&type_id).and_then(|handler| handler.take())
+    });
+    if let Some(mut handler) = handler {
+        handler(payload);
+        EVENT_LOOP_STATE.with(|state| {
+            if let Some(slot @ None) = state.borrow_mut().event_handlers.get_mut(&type_id) {
+                *slot = Some(handler);
+            }
+        });
+    }
+}
+
+/// Invokes the callbacks registered with [`EventLoop::on_idle()`]. The backends call this function
+/// when their event loop is about to wait for the next event.
+pub fn invoke_idle_callbacks() {
+    let ids = EVENT_LOOP_STATE
+        .with(|state| state.borrow().idle_callbacks.iter().map(|(id, _)| *id).collect::<Vec<_>>());
+    for id in ids {
+        // The callback is taken out of the state while it runs, so that it can register or
+        // unregister idle callbacks
+        let callback = EVENT_LOOP_STATE.with(|state| {
+            let mut state = state.borrow_mut();
+            let entry = state.idle_callbacks.iter_mut().find(|(callback_id, _)| *callback_id == id);
+            entry.and_then(|(_, callback)| callback.take())
+        });
+        if let Some(mut callback) = callback {
+            callback();
+            EVENT_LOOP_STATE.with(|state| {
+                let mut state = state.borrow_mut();
+                // The callback is dropped if it was unregistered meanwhile
+                if let Some((_, slot)) =
+                    state.idle_callbacks.iter_mut().find(|(callback_id, _)| *callback_id == id)
+                {
+                    *slot = Some(callback);
+                }
+            });
+        }
+    }
+}
+
+/// Records that the event loop is running, for [`EventLoop::is_running()`], until the returned
+/// guard is dropped. The backends call this function when their event loop starts.
+pub fn enter_event_loop() -> RunningEventLoopGuard {
+    EVENT_LOOP_RUNNING.store(true, Ordering::Relaxed);
+    RunningEventLoopGuard { _private: () }
+}
+
+/// Returned by [`enter_event_loop()`], records that the event loop stopped when dropped.
+pub struct RunningEventLoopGuard {
+    _private: (),
+}
+
+impl Drop for RunningEventLoopGuard {
+    fn drop(&mut self) {
+        EVENT_LOOP_RUNNING.store(false, Ordering::Relaxed);
+    }
+}
+
+#[test]
+fn test_idle_callbacks() {
+    use alloc::rc::Rc;
+    let count = Rc::new(Cell::new(0));
+    let first = EventLoop::on_idle({
+        let count = count.clone();
+        move || count.set(count.get() + 1)
+    });
+    let second = EventLoop::on_idle({
+        let count = count.clone();
+        move || count.set(count.get() + 10)
+    });
+    invoke_idle_callbacks();
+    assert_eq!(count.get(), 11);
+    second.unregister();
+    invoke_idle_callbacks();
+    assert_eq!(count.get(), 12);
+    drop(first);
+    invoke_idle_callbacks();
+    assert_eq!(count.get(), 12);
+}
+
+#[test]
+fn test_dispatch_event() {
+    use alloc::rc::Rc;
+    struct Reading(u32);
+    let received = Rc::new(Cell::new(0));
+    dispatch_event(Box::new(Reading(1)));
+    EventLoop::on_event({
+        let received = received.clone();
+        move |reading: Reading| received.set(received.get() + reading.0)
+    });
+    dispatch_event(Box::new(Reading(2)));
+    // Events of other types are not passed to the handler
+    dispatch_event(Box::new(3u32));
+    assert_eq!(received.get(), 2);
+}
@@ -46,9 +46,20 @@
 pub(crate) mod bitmapfont;
 pub use self::bitmapfont::*;
 
+mod rotation;
+pub use rotation::*;
+
+#[cfg(feature = "std")]
+mod video;
+#[cfg(feature = "std")]
+pub use video::*;
+
 #[cfg(feature = "std")]
 pub mod rendering_metrics_collector;
 
+#[cfg(feature = "std")]
+pub mod svg_export;
+
 /// CachedGraphicsData allows the graphics backend to store an arbitrary piece of data associated with
 /// an item, which is typically computed by accessing properties. The dependency_tracker is used to allow
 /// for a lazy computation. Typically back ends store either compute intensive data or handles that refer to
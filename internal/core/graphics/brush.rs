@@ -93,6 +93,45 @@ pub fn darker(&self, factor: f32) -> Self {
             )),
         }
     }
+
+    /// Returns a new version of this brush with the perceived lightness changed by the
+    /// specified factor. This is done by calling [`Color::lighter`] on all the colors of this brush.
+    #[must_use]
+    pub fn lighter(&self, factor: f32) -> Self {
+        self.map_colors(|c| c.lighter(factor))
+    }
+
+    /// Returns a new version of this brush with the saturation changed by the specified factor.
+    /// This is done by calling [`Color::saturate`] on all the colors of this brush.
+    #[must_use]
+    pub fn saturate(&self, factor: f32) -> Self {
+        self.map_colors(|c| c.saturate(factor))
+    }
+
+    /// Returns a new version of this brush with the alpha channel of all its colors replaced.
+    /// See [`Color::with_alpha`].
+    #[must_use]
+    pub fn with_alpha(&self, alpha: f32) -> Self {
+        self.map_colors(|c| c.with_alpha(alpha))
+    }
+
+    /// Returns a brush where all the colors of this brush are mixed with the color of `other`.
+    /// See [`Color::mix`].
+    #[must_use]
+    pub fn mix(&self, other: &Self, factor: f32) -> Self {
+        let other = other.color();
+        self.map_colors(|c| c.mix(&other, factor))
+    }
+
+    fn map_colors(&self, f: impl Fn(Color) -> Color) -> Self {
+        match self {
+            Brush::SolidColor(c) => Brush::SolidColor(f(*c)),
+            Brush::LinearGradient(g) => Brush::LinearGradient(LinearGradientBrush::new(
+                g.angle(),
+                g.stops().map(|s| GradientStop { color: f(s.color), position: s.position }),
+            )),
+        }
+    }
 }
 
 /// The LinearGradientBrush describes a way of filling a shape with different colors, which
@@ -192,6 +192,61 @@ pub fn darker(&self, factor: f32) -> Self {
         let rgba: RgbaColor<f32> = hsva.into();
         rgba.into()
     }
+
+    /// Returns a new version of this color with the perceived lightness changed by the
+    /// specified factor. This is done in the OKLab color space, so that the hue is preserved
+    /// and the same factor yields a similar visual change for all colors. A positive factor
+    /// moves the color towards white by that fraction, a negative factor towards black.
+    /// So for example `lighter(0.2)` gives a hover shade and `lighter(-0.2)` a pressed shade.
+    /// The alpha channel is unchanged.
+    #[must_use]
+    pub fn lighter(&self, factor: f32) -> Self {
+        let target = if factor >= 0. { 255 } else { 0 };
+        let target = Self::from_argb_u8(self.alpha, target, target, target);
+        self.mix(&target, factor.abs())
+    }
+
+    /// Returns a new version of this color that has its saturation multiplied by (1 + factor),
+    /// using the HSL color space. Negative factors desaturate the color, and a factor of -1
+    /// returns a gray of the same lightness.
+    #[must_use]
+    pub fn saturate(&self, factor: f32) -> Self {
+        let mut hsla: HslaColor = (*self).to_argb_f32().into();
+        hsla.s = (hsla.s * (1. + factor)).max(0.).min(1.);
+        Self::from_argb_f32_rounded(hsla.into())
+    }
+
+    /// Returns a new version of this color with the alpha channel replaced by `alpha`,
+    /// which is a value between 0 (transparent) and 1 (opaque).
+    #[must_use]
+    pub fn with_alpha(&self, alpha: f32) -> Self {
+        Self { alpha: (alpha.max(0.).min(1.) * 255.).round() as u8, ..*self }
+    }
+
+    /// Returns a color that is a mix between this color and `other`. A `factor` of 0 returns
+    /// this color, and a factor of 1 returns `other`. The interpolation is done in the OKLab
+    /// color space, which avoids the muddy midpoints of a mix in RGB.
+    #[must_use]
+    pub fn mix(&self, other: &Self, factor: f32) -> Self {
+        let factor = factor.max(0.).min(1.);
+        let a: OklabColor = (*self).to_argb_f32().into();
+        let b: OklabColor = (*other).to_argb_f32().into();
+        let lerp = |a: f32, b: f32| a + (b - a) * factor;
+        let lab = OklabColor {
+            l: lerp(a.l, b.l),
+            a: lerp(a.a, b.a),
+            b: lerp(a.b, b.b),
+            alpha: lerp(a.alpha, b.alpha),
+        };
+        Self::from_argb_f32_rounded(lab.into())
+    }
+
+    /// Like the `From<RgbaColor<f32>>` conversion, but rounding instead of truncating, so
+    /// that round trips through other color spaces are stable.
+    fn from_argb_f32_rounded(col: RgbaColor<f32>) -> Self {
+        let c = |x: f32| (x.max(0.).min(1.) * 255.).round() as u8;
+        Self { red: c(col.red), green: c(col.green), blue: c(col.blue), alpha: c(col.alpha) }
+    }
 }
 
 impl InterpolatedPropertyValue for Color {
@@ -273,6 +328,88 @@ fn from(col: HsvaColor) -> Self {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct HslaColor {
+    h: f32,
+    s: f32,
+    l: f32,
+    alpha: f32,
+}
+
+impl From<RgbaColor<f32>> for HslaColor {
+    fn from(col: RgbaColor<f32>) -> Self {
+        let hsva = HsvaColor::from(col);
+        let l = hsva.v * (1. - hsva.s / 2.);
+        let s = if l <= 0. || l >= 1. { 0. } else { (hsva.v - l) / l.min(1. - l) };
+        Self { h: hsva.h, s, l, alpha: col.alpha }
+    }
+}
+
+impl From<HslaColor> for RgbaColor<f32> {
+    fn from(col: HslaColor) -> Self {
+        let v = col.l + col.s * col.l.min(1. - col.l);
+        let s = if v <= 0. { 0. } else { 2. * (1. - col.l / v) };
+        // The hue may be negative for colors between magenta and red
+        let h = if col.h < 0. { col.h + 360. } else { col.h };
+        HsvaColor { h, s, v, alpha: col.alpha }.into()
+    }
+}
+
+/// A color in the OKLab perceptual color space, see https://bottosson.github.io/posts/oklab/
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OklabColor {
+    l: f32,
+    a: f32,
+    b: f32,
+    alpha: f32,
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    let c = if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1. / 2.4) - 0.055 };
+    c.max(0.).min(1.)
+}
+
+#[allow(clippy::excessive_precision)]
+impl From<RgbaColor<f32>> for OklabColor {
+    fn from(col: RgbaColor<f32>) -> Self {
+        let (r, g, b) =
+            (srgb_to_linear(col.red), srgb_to_linear(col.green), srgb_to_linear(col.blue));
+        let l = (0.4122214708 * r + 0.5363325363 * g + 0.0514459929 * b).cbrt();
+        let m = (0.2119034982 * r + 0.6806995451 * g + 0.1073969566 * b).cbrt();
+        let s = (0.0883024619 * r + 0.2817188376 * g + 0.6299787005 * b).cbrt();
+        Self {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+            alpha: col.alpha,
+        }
+    }
+}
+
+#[allow(clippy::excessive_precision)]
+impl From<OklabColor> for RgbaColor<f32> {
+    fn from(col: OklabColor) -> Self {
+        let l = col.l + 0.3963377774 * col.a + 0.2158037573 * col.b;
+        let m = col.l - 0.1055613458 * col.a - 0.0638541728 * col.b;
+        let s = col.l - 0.0894841775 * col.a - 1.2914855480 * col.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        Self {
+            red: linear_to_srgb(4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s),
+            green: linear_to_srgb(-1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s),
+            blue: linear_to_srgb(-0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s),
+            alpha: col.alpha,
+        }
+    }
+}
+
 #[test]
 fn test_rgb_to_hsv() {
     // White
@@ -303,6 +440,24 @@ fn test_brighter_darker() {
     assert_eq!(blue.darker(0.5), Color::from_rgb_u8(0, 0, 85));
 }
 
+#[test]
+fn test_perceptual_functions() {
+    let red = Color::from_rgb_u8(255, 0, 0);
+    let blue = Color::from_rgb_u8(0, 0, 255);
+    assert_eq!(red.mix(&blue, 0.), red);
+    assert_eq!(red.mix(&blue, 1.), blue);
+    assert_eq!(red.lighter(0.), red);
+    assert_eq!(red.lighter(1.), Color::from_rgb_u8(255, 255, 255));
+    assert_eq!(red.lighter(-1.), Color::from_rgb_u8(0, 0, 0));
+    assert!(red.lighter(0.3).green() > 0);
+    assert_eq!(red.with_alpha(0.5).alpha(), 128);
+    assert_eq!(red.with_alpha(0.5).red(), 255);
+    let gray = red.saturate(-1.);
+    assert_eq!(gray.red(), gray.green());
+    assert_eq!(gray.green(), gray.blue());
+    assert_eq!(Color::from_rgb_u8(200, 100, 100).saturate(0.), Color::from_rgb_u8(200, 100, 100));
+}
+
 #[cfg(feature = "ffi")]
 pub(crate) mod ffi {
     #![allow(unsafe_code)]
@@ -317,4 +472,29 @@ pub(crate) mod ffi {
     pub unsafe extern "C" fn slint_color_darker(col: &Color, factor: f32, out: *mut Color) {
         core::ptr::write(out, col.darker(factor))
     }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_color_lighter(col: &Color, factor: f32, out: *mut Color) {
+        core::ptr::write(out, col.lighter(factor))
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_color_saturate(col: &Color, factor: f32, out: *mut Color) {
+        core::ptr::write(out, col.saturate(factor))
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_color_with_alpha(col: &Color, alpha: f32, out: *mut Color) {
+        core::ptr::write(out, col.with_alpha(alpha))
+    }
+
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_color_mix(
+        col: &Color,
+        other: &Color,
+        factor: f32,
+        out: *mut Color,
+    ) {
+        core::ptr::write(out, col.mix(other, factor))
+    }
 }
@@ -233,6 +233,12 @@ pub struct StaticTextures {
     pub textures: Slice<'static, StaticTexture>,
 }
 
+/// Identifies the frames pushed to a [`VideoSink`](crate::graphics::VideoSink), in an
+/// [`ImageInner::VideoStream`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(C)]
+pub struct VideoStreamId(pub u64);
+
 /// A resource is a reference to binary data, for example images. They can be accessible on the file
 /// system or embedded in the resulting binary. Or they might be URLs to a web server and a downloaded
 /// is necessary before they can be used.
@@ -252,6 +258,9 @@ pub enum ImageInner {
     },
     EmbeddedImage(SharedImageBuffer),
     StaticTextures(&'static StaticTextures),
+    /// The frames of a video sink. The renderers draw the frame that was shown last, and the
+    /// same image shows the next frames.
+    VideoStream(VideoStreamId),
 }
 
 impl Default for ImageInner {
@@ -403,7 +412,10 @@ pub fn size(&self) -> IntSize {
             },
             ImageInner::EmbeddedImage(buffer) => buffer.size(),
             ImageInner::StaticTextures(StaticTextures { original_size, .. }) => *original_size,
-
+            #[cfg(feature = "std")]
+            ImageInner::VideoStream(stream) => super::video::shown_video_frame_size(*stream),
+            #[cfg(not(feature = "std"))]
+            ImageInner::VideoStream(_) => Default::default(),
         }
     }
 
@@ -35,15 +35,24 @@ fn try_from(options: &Vec<&'a str>) -> Result<Self, Self::Error> {
 pub struct RenderingMetrics {
     /// The number of layers that were created. None if the renderer does not create layers.
     pub layers_created: Option<usize>,
+    /// The number of fills that were merged into batches. None if the renderer does not batch fills.
+    pub batched_fills: Option<usize>,
+    /// The number of draw calls issued to draw the batches of fills.
+    pub fill_batches: Option<usize>,
 }
 
 impl core::fmt::Display for RenderingMetrics {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(layer_count) = self.layers_created {
-            write!(f, "[{} layers created]", layer_count)
-        } else {
-            Ok(())
+            write!(f, "[{} layers created]", layer_count)?;
+        }
+        if let (Some(fills), Some(batches)) = (self.batched_fills, self.fill_batches) {
+            if self.layers_created.is_some() {
+                write!(f, " ")?;
+            }
+            write!(f, "[{} fills batched into {} draw calls]", fills, batches)?;
         }
+        Ok(())
     }
 }
 
@@ -170,8 +179,11 @@ pub fn measure_frame_rendered(
         self.trim_frame_data_to_second_boundary();
 
         if self.output_overlay {
+            let frame_data = self.collected_frame_data_since_second_ago.borrow();
+            let details =
+                frame_data.last().map(|frame| frame.metrics.to_string()).unwrap_or_default();
             renderer.draw_string(
-                &format!("FPS: {}", self.collected_frame_data_since_second_ago.borrow().len()),
+                &format!("FPS: {} {}", frame_data.len(), details),
                 crate::Color::from_rgb_u8(0, 128, 128),
             );
         }
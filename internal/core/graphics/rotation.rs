@@ -0,0 +1,153 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+This module contains the rotation of the rendering, for the displays that are mounted rotated.
+*/
+
+use euclid::{Point2D, Rect, Size2D};
+
+/// The clockwise rotation of the scene on the display. When the display is rotated, the size of
+/// the window is the size of the display with the width and height swapped for the quarter turns,
+/// and the positions of the input events must be mapped from the display to the window with
+/// [`Self::map_point_to_window()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum RenderingRotation {
+    /// The scene is drawn as is
+    NoRotation,
+    /// The scene is rotated by 90 degrees clockwise
+    Rotate90,
+    /// The scene is drawn upside down
+    Rotate180,
+    /// The scene is rotated by 270 degrees clockwise, which is 90 degrees counter-clockwise
+    Rotate270,
+}
+
+impl Default for RenderingRotation {
+    fn default() -> Self {
+        Self::NoRotation
+    }
+}
+
+impl RenderingRotation {
+    /// Returns the rotation from its angle in degrees, which must be a multiple of 90.
+    pub fn from_degrees(degrees: i32) -> Option<Self> {
+        match degrees.rem_euclid(360) {
+            0 => Some(Self::NoRotation),
+            90 => Some(Self::Rotate90),
+            180 => Some(Self::Rotate180),
+            270 => Some(Self::Rotate270),
+            _ => None,
+        }
+    }
+
+    /// Returns the clockwise angle of the rotation in degrees.
+    pub fn degrees(self) -> i32 {
+        match self {
+            Self::NoRotation => 0,
+            Self::Rotate90 => 90,
+            Self::Rotate180 => 180,
+            Self::Rotate270 => 270,
+        }
+    }
+
+    /// Returns the rotation that undoes this one.
+    pub fn inverse(self) -> Self {
+        match self {
+            Self::Rotate90 => Self::Rotate270,
+            Self::Rotate270 => Self::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Returns true if the width and height are swapped by the rotation.
+    pub fn is_transpose(self) -> bool {
+        matches!(self, Self::Rotate90 | Self::Rotate270)
+    }
+
+    /// Returns the size of the window shown on a display of this `size`, which is also the size of
+    /// the display showing a window of this `size`.
+    pub fn map_size<T, U>(self, size: Size2D<T, U>) -> Size2D<T, U> {
+        if self.is_transpose() {
+            Size2D::new(size.height, size.width)
+        } else {
+            size
+        }
+    }
+
+    /// Maps `rect` from the window, whose size is `window_size`, to the display.
+    pub fn map_rect_to_display<T, U>(
+        self,
+        rect: Rect<T, U>,
+        window_size: Size2D<T, U>,
+    ) -> Rect<T, U>
+    where
+        T: Copy + core::ops::Add<Output = T> + core::ops::Sub<Output = T>,
+    {
+        let (x, y, width, height) =
+            (rect.origin.x, rect.origin.y, rect.size.width, rect.size.height);
+        match self {
+            Self::NoRotation => rect,
+            Self::Rotate90 => Rect::new(
+                Point2D::new(window_size.height - y - height, x),
+                Size2D::new(height, width),
+            ),
+            Self::Rotate180 => Rect::new(
+                Point2D::new(window_size.width - x - width, window_size.height - y - height),
+                rect.size,
+            ),
+            Self::Rotate270 => Rect::new(
+                Point2D::new(y, window_size.width - x - width),
+                Size2D::new(height, width),
+            ),
+        }
+    }
+
+    /// Maps `point`, for example the position of a touch event, from the display whose size is
+    /// `display_size` to the window.
+    pub fn map_point_to_window<T, U>(
+        self,
+        point: Point2D<T, U>,
+        display_size: Size2D<T, U>,
+    ) -> Point2D<T, U>
+    where
+        T: Copy + core::ops::Sub<Output = T>,
+    {
+        match self {
+            Self::NoRotation => point,
+            Self::Rotate90 => Point2D::new(point.y, display_size.width - point.x),
+            Self::Rotate180 => {
+                Point2D::new(display_size.width - point.x, display_size.height - point.y)
+            }
+            Self::Rotate270 => Point2D::new(display_size.height - point.y, point.x),
+        }
+    }
+}
+
+#[test]
+fn test_rotation_mapping() {
+    let window_size = euclid::default::Size2D::new(100, 50);
+    let rect = euclid::default::Rect::new(Point2D::new(10, 20), Size2D::new(30, 5));
+    for (rotation, expected) in [
+        (RenderingRotation::NoRotation, Rect::new(Point2D::new(10, 20), Size2D::new(30, 5))),
+        (RenderingRotation::Rotate90, Rect::new(Point2D::new(25, 10), Size2D::new(5, 30))),
+        (RenderingRotation::Rotate180, Rect::new(Point2D::new(60, 25), Size2D::new(30, 5))),
+        (RenderingRotation::Rotate270, Rect::new(Point2D::new(20, 60), Size2D::new(5, 30))),
+    ] {
+        let display_size = rotation.map_size(window_size);
+        let display_rect = rotation.map_rect_to_display(rect, window_size);
+        assert_eq!(display_rect, expected);
+        // The rectangle is mapped back to the window with the inverse rotation
+        assert_eq!(rotation.inverse().map_rect_to_display(display_rect, display_size), rect);
+        // The corners of the rectangle on the display map back to the corners in the window
+        let corner = rotation.map_point_to_window(display_rect.origin, display_size);
+        let far_corner = rotation.map_point_to_window(display_rect.max(), display_size);
+        assert_eq!(
+            euclid::default::Box2D::new(corner.min(far_corner), corner.max(far_corner)),
+            rect.to_box2d()
+        );
+    }
+    assert_eq!(RenderingRotation::from_degrees(-90), Some(RenderingRotation::Rotate270));
+    assert_eq!(RenderingRotation::from_degrees(45), None);
+}
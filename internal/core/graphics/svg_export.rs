@@ -0,0 +1,759 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+The export of the items of a window into an SVG document, see [`crate::api::Window::export_svg()`].
+
+The shapes are written as vector elements, so that the document can be scaled or printed without loss.
+The text is laid out in lines measured with the fonts of the backend, and written as the outlines of its
+glyphs, so that the document looks the same on systems that don't have the fonts. With the backends that
+can't provide the outlines, the text is written as SVG text elements with the same font family, size and
+weight instead. The images are embedded, and the
+images generated by the application and the cached layers are encoded as uncompressed PNG.
+*/
+
+use super::{
+    Brush, Color, FontRequest, Image, ImageInner, Point, Rect, SharedImageBuffer, Size, Transform,
+};
+use crate::item_rendering::{CachedRenderingData, ItemRenderer};
+use crate::items::{
+    BorderRectangle, BoxShadow, ClippedImage, FillRule, ImageFit, ImageItem, Item, Path, Rectangle,
+    Text, TextHorizontalAlignment, TextInput, TextVerticalAlignment, TextWrap,
+};
+use crate::window::WindowRc;
+use crate::Coord;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::pin::Pin;
+
+/// The character that replaces the characters of a password in the exported document
+const PASSWORD_CHARACTER: &str = "●";
+
+#[derive(Clone, Copy)]
+struct SvgRenderState {
+    /// The clip rectangle in the current coordinates
+    clip: Rect,
+    /// The number of `<g>` elements opened since the state was saved
+    open_groups: usize,
+}
+
+/// An [`ItemRenderer`] that writes the items as SVG elements into a string.
+pub struct SvgItemRenderer {
+    window: WindowRc,
+    output: String,
+    /// The number of definitions, such as gradients and clip paths, used to make their ids unique
+    definitions: usize,
+    state: SvgRenderState,
+    state_stack: Vec<SvgRenderState>,
+}
+
+impl SvgItemRenderer {
+    /// Creates a renderer for the items of `window`, which are clipped to `size`.
+    pub fn new(window: WindowRc, size: Size) -> Self {
+        Self {
+            window,
+            output: String::new(),
+            definitions: 0,
+            state: SvgRenderState { clip: Rect::new(Point::default(), size), open_groups: 0 },
+            state_stack: Vec::new(),
+        }
+    }
+
+    /// Returns the SVG elements written so far, without the enclosing `<svg>` element.
+    pub fn into_elements(self) -> String {
+        self.output
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.definitions += 1;
+        alloc::format!("{}{}", prefix, self.definitions)
+    }
+
+    fn open_group(&mut self, attributes: core::fmt::Arguments) {
+        let _ = writeln!(self.output, "<g {}>", attributes);
+        self.state.open_groups += 1;
+    }
+
+    /// Returns the attributes that paint `attribute` (`fill` or `stroke`) with `brush`, and writes
+    /// the gradient definition it refers to, if any.
+    fn paint(&mut self, attribute: &str, brush: &Brush) -> String {
+        match brush {
+            _ if brush.is_transparent() => alloc::format!(r#"{}="none""#, attribute),
+            Brush::SolidColor(color) => color_attributes(attribute, *color),
+            Brush::LinearGradient(gradient) => {
+                let id = self.next_id("gradient");
+                let (start, end) = super::line_for_angle(gradient.angle());
+                let _ = write!(
+                    self.output,
+                    r#"<defs><linearGradient id="{}" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+                    id, start.x, start.y, end.x, end.y
+                );
+                for stop in gradient.stops() {
+                    let color = stop.color;
+                    let _ = write!(
+                        self.output,
+                        r#"<stop offset="{}" stop-color="rgb({},{},{})" stop-opacity="{}"/>"#,
+                        stop.position,
+                        color.red(),
+                        color.green(),
+                        color.blue(),
+                        color.alpha() as f32 / 255.
+                    );
+                }
+                let _ = writeln!(self.output, "</linearGradient></defs>");
+                alloc::format!(r#"{}="url(#{})""#, attribute, id)
+            }
+        }
+    }
+
+    /// Writes `text` in lines, wrapped to the width of `size` if `wrap` is set, and aligned in `size`.
+    #[allow(clippy::too_many_arguments)]
+    fn write_text(
+        &mut self,
+        text: &str,
+        font_request: FontRequest,
+        color: &Brush,
+        size: Size,
+        horizontal_alignment: TextHorizontalAlignment,
+        vertical_alignment: TextVerticalAlignment,
+        wrap: TextWrap,
+        letter_spacing: Coord,
+    ) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let max_width = matches!(wrap, TextWrap::word_wrap).then(|| size.width);
+        let lines = wrap_lines(&self.window, &font_request, text, max_width);
+        let line_height = self.window.text_size(font_request.clone(), "X", None).height;
+
+        let total_height = line_height * lines.len() as Coord;
+        let top = match vertical_alignment {
+            TextVerticalAlignment::top => 0 as Coord,
+            TextVerticalAlignment::center => (size.height - total_height) / 2 as Coord,
+            TextVerticalAlignment::bottom => size.height - total_height,
+        };
+        let fill = self.paint("fill", color);
+
+        let outlines = lines
+            .iter()
+            .map(|line| self.window.text_outline(font_request.clone(), line))
+            .collect::<Option<Vec<_>>>();
+        if let Some(outlines) = outlines {
+            for (i, (line, outline)) in lines.iter().zip(outlines).enumerate() {
+                let width = self.window.text_size(font_request.clone(), line, None).width;
+                let x = match horizontal_alignment {
+                    TextHorizontalAlignment::left => 0 as Coord,
+                    TextHorizontalAlignment::center => (size.width - width) / 2 as Coord,
+                    TextHorizontalAlignment::right => size.width - width,
+                };
+                let _ = writeln!(
+                    self.output,
+                    r#"<path transform="translate({} {})" d="{}" {}/>"#,
+                    x,
+                    top + line_height * i as Coord,
+                    path_data(outline.iter()),
+                    fill
+                );
+            }
+            return;
+        }
+
+        // Without the outlines of the glyphs, the text is written as text elements
+        let (anchor, x) = match horizontal_alignment {
+            TextHorizontalAlignment::left => ("start", 0 as Coord),
+            TextHorizontalAlignment::center => ("middle", size.width / 2 as Coord),
+            TextHorizontalAlignment::right => ("end", size.width),
+        };
+        let font = font_request.merge(&self.window.default_font_properties());
+        let font_size = font.pixel_size.map_or_else(
+            || self.window.default_font_size(),
+            |size| size * self.window.text_scale_factor() as Coord,
+        );
+        let _ = write!(
+            self.output,
+            r#"<text font-size="{}" text-anchor="{}" dominant-baseline="text-before-edge" xml:space="preserve" {}"#,
+            font_size, anchor, fill
+        );
+        if let Some(family) = &font.family {
+            let _ = write!(self.output, r#" font-family="{}""#, escape(family));
+        }
+        if let Some(weight) = font.weight {
+            let _ = write!(self.output, r#" font-weight="{}""#, weight);
+        }
+        if letter_spacing != 0 as Coord {
+            let _ = write!(self.output, r#" letter-spacing="{}""#, letter_spacing);
+        }
+        self.output.push('>');
+        for (i, line) in lines.iter().enumerate() {
+            let _ = write!(
+                self.output,
+                r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                x,
+                top + line_height * i as Coord,
+                escape(line)
+            );
+        }
+        let _ = writeln!(self.output, "</text>");
+    }
+
+    /// Writes `image`, or the `source_clip` part of it, fitted into `size`.
+    fn write_image(
+        &mut self,
+        image: &Image,
+        size: Size,
+        image_fit: ImageFit,
+        source_clip: Option<euclid::default::Rect<i32>>,
+    ) {
+        let href = match image_href(image) {
+            Some(href) => href,
+            None => return,
+        };
+        let aspect_ratio = match image_fit {
+            ImageFit::fill => "none",
+            ImageFit::contain => "xMidYMid meet",
+            ImageFit::cover => "xMidYMid slice",
+        };
+        let image_size = image.size();
+        let viewbox = source_clip.filter(|clip| !clip.is_empty()).unwrap_or_else(|| {
+            euclid::rect(0, 0, image_size.width as i32, image_size.height as i32)
+        });
+        let _ = writeln!(
+            self.output,
+            r#"<svg width="{}" height="{}" viewBox="{} {} {} {}" preserveAspectRatio="{}"><image width="{}" height="{}" href="{}"/></svg>"#,
+            size.width,
+            size.height,
+            viewbox.origin.x,
+            viewbox.origin.y,
+            viewbox.size.width,
+            viewbox.size.height,
+            aspect_ratio,
+            image_size.width,
+            image_size.height,
+            href
+        );
+    }
+}
+
+impl ItemRenderer for SvgItemRenderer {
+    fn draw_rectangle(&mut self, rect: Pin<&Rectangle>) {
+        let geometry = rect.geometry();
+        let fill = self.paint("fill", &rect.background());
+        let _ = writeln!(
+            self.output,
+            r#"<rect width="{}" height="{}" {}/>"#,
+            geometry.width(),
+            geometry.height(),
+            fill
+        );
+    }
+
+    fn draw_border_rectangle(&mut self, rect: Pin<&BorderRectangle>) {
+        let geometry = rect.geometry();
+        let radius = rect.border_radius();
+        let fill = self.paint("fill", &rect.background());
+        let _ = writeln!(
+            self.output,
+            r#"<rect width="{}" height="{}" rx="{}" {}/>"#,
+            geometry.width(),
+            geometry.height(),
+            radius,
+            fill
+        );
+        // The border is drawn inside of the rectangle
+        let border_width = rect.border_width().min(geometry.width() / 2 as Coord);
+        if border_width > 0 as Coord && !rect.border_color().is_transparent() {
+            let half = border_width / 2 as Coord;
+            let stroke = self.paint("stroke", &rect.border_color());
+            let _ = writeln!(
+                self.output,
+                r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="none" stroke-width="{}" {}/>"#,
+                half,
+                half,
+                geometry.width() - border_width,
+                geometry.height() - border_width,
+                (radius - half).max(0 as Coord),
+                border_width,
+                stroke
+            );
+        }
+    }
+
+    fn draw_image(&mut self, image: Pin<&ImageItem>) {
+        let size = image.geometry().size;
+        self.write_image(&image.source(), size, image.image_fit(), None);
+    }
+
+    fn draw_clipped_image(&mut self, image: Pin<&ClippedImage>) {
+        let size = image.geometry().size;
+        self.write_image(&image.source(), size, image.image_fit(), Some(image.source_clip()));
+    }
+
+    fn draw_text(&mut self, text: Pin<&Text>) {
+        self.write_text(
+            &text.text(),
+            text.unresolved_font_request(),
+            &text.color(),
+            text.geometry().size,
+            text.horizontal_alignment(),
+            text.vertical_alignment(),
+            text.wrap(),
+            text.letter_spacing(),
+        );
+    }
+
+    fn draw_text_input(&mut self, text_input: Pin<&TextInput>) {
+        let mut visual_representation = text_input.visual_representation();
+        visual_representation.apply_password_character(PASSWORD_CHARACTER);
+        self.write_text(
+            &visual_representation.text,
+            text_input.unresolved_font_request(),
+            &text_input.color(),
+            text_input.geometry().size,
+            text_input.horizontal_alignment(),
+            text_input.vertical_alignment(),
+            text_input.wrap(),
+            text_input.letter_spacing(),
+        );
+    }
+
+    fn draw_path(&mut self, path: Pin<&Path>) {
+        let (offset, path_events) = path.fitted_path_events();
+        let fill = self.paint("fill", &path.fill());
+        let stroke = self.paint("stroke", &path.stroke());
+        let fill_rule = match path.fill_rule() {
+            FillRule::nonzero => "nonzero",
+            FillRule::evenodd => "evenodd",
+        };
+        let _ = writeln!(
+            self.output,
+            r#"<path transform="translate({} {})" d="{}" fill-rule="{}" stroke-width="{}" {} {}/>"#,
+            offset.x,
+            offset.y,
+            path_data(path_events.iter()),
+            fill_rule,
+            path.stroke_width(),
+            fill,
+            stroke
+        );
+    }
+
+    fn draw_box_shadow(&mut self, box_shadow: Pin<&BoxShadow>) {
+        let color = box_shadow.color();
+        if color.alpha() == 0 {
+            return;
+        }
+        let blur = box_shadow.blur();
+        let filter = if blur > 0 as Coord {
+            let id = self.next_id("shadow");
+            let _ = writeln!(
+                self.output,
+                r#"<defs><filter id="{}" x="-50%" y="-50%" width="200%" height="200%"><feGaussianBlur stdDeviation="{}"/></filter></defs>"#,
+                id,
+                blur / 2 as Coord
+            );
+            alloc::format!(r#" filter="url(#{})""#, id)
+        } else {
+            String::new()
+        };
+        let _ = writeln!(
+            self.output,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" {}{}/>"#,
+            box_shadow.offset_x(),
+            box_shadow.offset_y(),
+            box_shadow.width(),
+            box_shadow.height(),
+            box_shadow.border_radius(),
+            color_attributes("fill", color),
+            filter
+        );
+    }
+
+    fn combine_clip(&mut self, rect: Rect, radius: Coord, border_width: Coord) {
+        // Like for the other renderers, the clip excludes the border
+        let rect = rect.inflate(-border_width, -border_width);
+        let radius = (radius - border_width).max(0 as Coord);
+        self.state.clip = self.state.clip.intersection(&rect).unwrap_or_default();
+        let id = self.next_id("clip");
+        let _ = writeln!(
+            self.output,
+            r#"<clipPath id="{}"><rect x="{}" y="{}" width="{}" height="{}" rx="{}"/></clipPath>"#,
+            id,
+            rect.origin.x,
+            rect.origin.y,
+            rect.width().max(0 as Coord),
+            rect.height().max(0 as Coord),
+            radius
+        );
+        self.open_group(format_args!(r#"clip-path="url(#{})""#, id));
+    }
+
+    fn get_current_clip(&self) -> Rect {
+        self.state.clip
+    }
+
+    fn translate(&mut self, x: Coord, y: Coord) {
+        if x == 0 as Coord && y == 0 as Coord {
+            return;
+        }
+        self.state.clip = self.state.clip.translate((-x, -y).into());
+        self.open_group(format_args!(r#"transform="translate({} {})""#, x, y));
+    }
+
+    fn rotate(&mut self, angle_in_degrees: f32) {
+        let rotation = Transform::rotation(euclid::Angle::degrees(-angle_in_degrees as Coord));
+        self.state.clip = rotation.outer_transformed_rect(&self.state.clip);
+        self.open_group(format_args!(r#"transform="rotate({})""#, angle_in_degrees));
+    }
+
+    fn apply_opacity(&mut self, opacity: f32) {
+        self.open_group(format_args!(r#"opacity="{}""#, opacity));
+    }
+
+    fn save_state(&mut self) {
+        self.state_stack.push(self.state);
+        self.state.open_groups = 0;
+    }
+
+    fn restore_state(&mut self) {
+        for _ in 0..self.state.open_groups {
+            self.output.push_str("</g>\n");
+        }
+        if let Some(state) = self.state_stack.pop() {
+            self.state = state;
+        }
+    }
+
+    fn scale_factor(&self) -> f32 {
+        1.
+    }
+
+    fn draw_cached_pixmap(
+        &mut self,
+        _item_cache: &CachedRenderingData,
+        update_fn: &dyn Fn(&mut dyn FnMut(u32, u32, &[u8])),
+    ) {
+        update_fn(&mut |width, height, data| {
+            let mut pixels = data.to_vec();
+            unpremultiply(&mut pixels);
+            let png = encode_png(width, height, 4, &pixels);
+            let _ = writeln!(
+                self.output,
+                r#"<image width="{}" height="{}" href="data:image/png;base64,{}"/>"#,
+                width,
+                height,
+                base64(&png)
+            );
+        });
+    }
+
+    fn draw_string(&mut self, string: &str, color: Color) {
+        if let Some(outline) = self.window.text_outline(FontRequest::default(), string) {
+            let _ = writeln!(
+                self.output,
+                r#"<path d="{}" {}/>"#,
+                path_data(outline.iter()),
+                color_attributes("fill", color)
+            );
+            return;
+        }
+        let _ = writeln!(
+            self.output,
+            r#"<text dominant-baseline="text-before-edge" {}>{}</text>"#,
+            color_attributes("fill", color),
+            escape(string)
+        );
+    }
+
+    fn window(&self) -> WindowRc {
+        self.window.clone()
+    }
+
+    fn as_any(&mut self) -> &mut dyn core::any::Any {
+        self
+    }
+}
+
+/// Renders the items of `window` into an SVG document of the size of the window.
+pub fn render_to_svg(window: &WindowRc) -> String {
+    let size = window.window_item_size();
+    let mut renderer = SvgItemRenderer::new(window.clone(), size);
+    if let Some(component) = window.try_component() {
+        crate::item_rendering::render_component_items(&component, &mut renderer, Point::default());
+    }
+    alloc::format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{}</svg>\n",
+        renderer.into_elements(),
+        w = size.width,
+        h = size.height,
+    )
+}
+
+/// Returns the attributes that set `attribute` to `color`, with its opacity if it's translucent
+fn color_attributes(attribute: &str, color: Color) -> String {
+    let mut result = alloc::format!(
+        r#"{}="rgb({},{},{})""#,
+        attribute,
+        color.red(),
+        color.green(),
+        color.blue()
+    );
+    if color.alpha() != 255 {
+        let _ = write!(result, r#" {}-opacity="{}""#, attribute, color.alpha() as f32 / 255.);
+    }
+    result
+}
+
+/// Returns the SVG path data of the given path events
+fn path_data(
+    events: impl Iterator<Item = lyon_path::Event<lyon_path::math::Point, lyon_path::math::Point>>,
+) -> String {
+    let mut data = String::new();
+    for event in events {
+        let _ = match event {
+            lyon_path::Event::Begin { at } => write!(data, "M{} {} ", at.x, at.y),
+            lyon_path::Event::Line { to, .. } => write!(data, "L{} {} ", to.x, to.y),
+            lyon_path::Event::Quadratic { ctrl, to, .. } => {
+                write!(data, "Q{} {} {} {} ", ctrl.x, ctrl.y, to.x, to.y)
+            }
+            lyon_path::Event::Cubic { ctrl1, ctrl2, to, .. } => {
+                write!(data, "C{} {} {} {} {} {} ", ctrl1.x, ctrl1.y, ctrl2.x, ctrl2.y, to.x, to.y)
+            }
+            lyon_path::Event::End { close: true, .. } => write!(data, "Z "),
+            lyon_path::Event::End { close: false, .. } => Ok(()),
+        };
+    }
+    data.truncate(data.trim_end().len());
+    data
+}
+
+/// Splits `text` into its lines, and wraps them at the word boundaries so that they are not wider
+/// than `max_width`, if set.
+fn wrap_lines(
+    window: &WindowRc,
+    font_request: &FontRequest,
+    text: &str,
+    max_width: Option<Coord>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.split('\n') {
+        let max_width = match max_width {
+            Some(max_width) => max_width,
+            None => {
+                lines.push(paragraph.into());
+                continue;
+            }
+        };
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate =
+                if line.is_empty() { word.into() } else { alloc::format!("{} {}", line, word) };
+            if !line.is_empty()
+                && window.text_size(font_request.clone(), &candidate, None).width > max_width
+            {
+                lines.push(core::mem::replace(&mut line, word.into()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Returns the URL of the image, embedding its data, or None if it can't be represented.
+fn image_href(image: &Image) -> Option<String> {
+    let inner: &ImageInner = image.into();
+    match inner {
+        ImageInner::None | ImageInner::StaticTextures(_) => None,
+        ImageInner::AbsoluteFilePath(path) => {
+            Some(alloc::format!("file://{}", escape(&path.replace('\\', "/"))))
+        }
+        ImageInner::EmbeddedData { data, format } => {
+            let format = core::str::from_utf8(format.as_slice()).ok()?;
+            let mime = match format {
+                "svg" => "svg+xml",
+                "jpg" => "jpeg",
+                format => format,
+            };
+            Some(alloc::format!("data:image/{};base64,{}", mime, base64(data.as_slice())))
+        }
+        ImageInner::EmbeddedImage(buffer) => {
+            let (channels, bytes) = match buffer {
+                SharedImageBuffer::RGB8(buffer) => (
+                    3,
+                    packed_rows(
+                        buffer.as_bytes(),
+                        buffer.stride(),
+                        buffer.width(),
+                        buffer.height(),
+                        3,
+                    ),
+                ),
+                SharedImageBuffer::RGBA8(buffer) => (
+                    4,
+                    packed_rows(
+                        buffer.as_bytes(),
+                        buffer.stride(),
+                        buffer.width(),
+                        buffer.height(),
+                        4,
+                    ),
+                ),
+                SharedImageBuffer::RGBA8Premultiplied(buffer) => {
+                    let mut bytes = packed_rows(
+                        buffer.as_bytes(),
+                        buffer.stride(),
+                        buffer.width(),
+                        buffer.height(),
+                        4,
+                    );
+                    unpremultiply(&mut bytes);
+                    (4, bytes)
+                }
+            };
+            let png = encode_png(buffer.width(), buffer.height(), channels, &bytes);
+            Some(alloc::format!("data:image/png;base64,{}", base64(&png)))
+        }
+        // The frame that is shown
+        ImageInner::VideoStream(stream) => image_href(&Image::from(ImageInner::EmbeddedImage(
+            crate::graphics::shown_video_frame_to_rgb(*stream)?,
+        ))),
+    }
+}
+
+/// Returns the pixels of the rows of an image without the padding at the end of each row
+fn packed_rows(bytes: &[u8], stride: u32, width: u32, height: u32, channels: usize) -> Vec<u8> {
+    let (stride, width) = (stride as usize * channels, width as usize * channels);
+    bytes
+        .chunks(stride.max(1))
+        .take(height as usize)
+        .flat_map(|row| &row[..width.min(row.len())])
+        .copied()
+        .collect()
+}
+
+/// Converts RGBA pixels with the color premultiplied by the alpha to plain RGBA
+fn unpremultiply(pixels: &mut [u8]) {
+    for pixel in pixels.chunks_exact_mut(4) {
+        let alpha = pixel[3] as u32;
+        for c in &mut pixel[..3] {
+            *c = (*c as u32 * 255).checked_div(alpha).map_or(0, |c| c.min(255) as u8);
+        }
+    }
+}
+
+/// Escapes the characters that are special in the text and the attributes of an XML document
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut result = String::with_capacity((data.len() + 2) / 3 * 4);
+    for chunk in data.chunks(3) {
+        let bits =
+            chunk.iter().enumerate().fold(0u32, |bits, (i, b)| bits | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                result.push(ALPHABET[(bits >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                result.push('=');
+            }
+        }
+    }
+    result
+}
+
+/// Encodes 8 bit RGB (3 `channels`) or RGBA (4 `channels`) pixels as a PNG image. The image data is
+/// stored without compression, which is enough for the images of a document.
+fn encode_png(width: u32, height: u32, channels: u8, pixels: &[u8]) -> Vec<u8> {
+    fn chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        let start = png.len();
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let crc = crc32(&png[start..]);
+        png.extend_from_slice(&crc.to_be_bytes());
+    }
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&width.to_be_bytes());
+    header.extend_from_slice(&height.to_be_bytes());
+    // 8 bits per channel, RGB or RGBA, no interlacing
+    header.extend_from_slice(&[8, if channels == 4 { 6 } else { 2 }, 0, 0, 0]);
+
+    // Each row starts with the filter type 0, meaning no filter
+    let row_len = width as usize * channels as usize;
+    let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in pixels.chunks(row_len.max(1)).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    // A zlib stream made of "stored" deflate blocks
+    let mut zlib = alloc::vec![0x78, 0x01];
+    let mut blocks = raw.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        zlib.push(blocks.peek().is_none() as u8);
+        zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+        zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+    zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+
+    let mut png = alloc::vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+    chunk(&mut png, b"IHDR", &header);
+    chunk(&mut png, b"IDAT", &zlib);
+    chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    !data.iter().fold(!0u32, |crc, byte| {
+        (0..8).fold(crc ^ *byte as u32, |crc, _| {
+            if crc & 1 != 0 {
+                0xedb88320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (a, b) = data.iter().fold((1u32, 0u32), |(a, b), byte| {
+        let a = (a + *byte as u32) % 65521;
+        (a, (b + a) % 65521)
+    });
+    b << 16 | a
+}
+
+#[test]
+fn test_encodings() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(crc32(b"IEND"), 0xae426082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+    assert_eq!(escape(r#"<a & "b">"#), "&lt;a &amp; &quot;b&quot;&gt;");
+    assert_eq!(packed_rows(&[1, 2, 3, 4, 5, 6], 3, 2, 2, 1), [1, 2, 4, 5]);
+
+    let png = encode_png(1, 1, 4, &[255, 0, 0, 255]);
+    assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+    assert_eq!(&png[png.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+}
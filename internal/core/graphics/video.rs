@@ -0,0 +1,621 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+This module contains [`VideoSink`], to show the frames of a camera or of a video decoder that
+are produced in another thread.
+*/
+
+use super::{
+    Image, ImageInner, IntSize, Rgb8Pixel, Rgba8Pixel, SharedImageBuffer, SharedPixelBuffer,
+    VideoStreamId,
+};
+use crate::Property;
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use const_field_offset::FieldOffsets;
+use core::cell::RefCell;
+use core::pin::Pin;
+use once_cell::sync::Lazy;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// The number of frames that were shown or dropped before, whose memory is kept to be reused for
+/// the next frames
+const RECYCLED_FRAME_COUNT: usize = 2;
+
+/// A frame pushed to a [`VideoSink`], borrowing the pixels of the capture device or of the
+/// decoder. The rows of each plane are `stride` bytes apart, which may be more than the width.
+///
+/// The YUV formats use the BT.601 coefficients with the limited range, which is what most
+/// cameras produce.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub enum VideoFrame<'a> {
+    /// Three bytes per pixel: red, green and blue.
+    Rgb8 {
+        /// The width of the frame in pixels
+        width: u32,
+        /// The height of the frame in pixels
+        height: u32,
+        /// The number of bytes between the start of two rows
+        stride: u32,
+        /// The pixels
+        data: &'a [u8],
+    },
+    /// Four bytes per pixel: red, green, blue and alpha, not premultiplied.
+    Rgba8 {
+        /// The width of the frame in pixels
+        width: u32,
+        /// The height of the frame in pixels
+        height: u32,
+        /// The number of bytes between the start of two rows
+        stride: u32,
+        /// The pixels
+        data: &'a [u8],
+    },
+    /// Planar YUV 4:2:0, also known as I420: a plane of luma, followed by a plane of each
+    /// chroma component with half the width and half the height.
+    I420 {
+        /// The width of the frame in pixels
+        width: u32,
+        /// The height of the frame in pixels
+        height: u32,
+        /// The luma plane, and the number of bytes between the start of two of its rows
+        y: (&'a [u8], u32),
+        /// The blue-difference chroma plane, and the number of bytes between the start of two of its rows
+        u: (&'a [u8], u32),
+        /// The red-difference chroma plane, and the number of bytes between the start of two of its rows
+        v: (&'a [u8], u32),
+    },
+    /// Semi-planar YUV 4:2:0, also known as NV12: a plane of luma, followed by a plane of
+    /// interleaved blue-difference and red-difference chroma with half the height.
+    Nv12 {
+        /// The width of the frame in pixels
+        width: u32,
+        /// The height of the frame in pixels
+        height: u32,
+        /// The luma plane, and the number of bytes between the start of two of its rows
+        y: (&'a [u8], u32),
+        /// The chroma plane, and the number of bytes between the start of two of its rows
+        uv: (&'a [u8], u32),
+    },
+    /// Packed YUV 4:2:2, also known as YUY2: four bytes for every two pixels, in the order
+    /// luma of the first pixel, blue-difference chroma, luma of the second pixel and
+    /// red-difference chroma. This is the format of most USB cameras.
+    Yuyv {
+        /// The width of the frame in pixels
+        width: u32,
+        /// The height of the frame in pixels
+        height: u32,
+        /// The number of bytes between the start of two rows
+        stride: u32,
+        /// The pixels
+        data: &'a [u8],
+    },
+}
+
+impl VideoFrame<'_> {
+    /// Returns the width of the frame in pixels.
+    pub fn width(&self) -> u32 {
+        match self {
+            Self::Rgb8 { width, .. }
+            | Self::Rgba8 { width, .. }
+            | Self::I420 { width, .. }
+            | Self::Nv12 { width, .. }
+            | Self::Yuyv { width, .. } => *width,
+        }
+    }
+
+    /// Returns the height of the frame in pixels.
+    pub fn height(&self) -> u32 {
+        match self {
+            Self::Rgb8 { height, .. }
+            | Self::Rgba8 { height, .. }
+            | Self::I420 { height, .. }
+            | Self::Nv12 { height, .. }
+            | Self::Yuyv { height, .. } => *height,
+        }
+    }
+
+    /// Returns true if the planes are large enough for the size of the frame.
+    fn is_valid(&self) -> bool {
+        fn plane_fits(plane: &[u8], stride: u32, row_size: u32, rows: u32) -> bool {
+            rows == 0
+                || (stride >= row_size
+                    && plane.len() >= stride as usize * (rows as usize - 1) + row_size as usize)
+        }
+        let (width, height) = (self.width(), self.height());
+        let (chroma_width, chroma_height) = ((width + 1) / 2, (height + 1) / 2);
+        match *self {
+            Self::Rgb8 { stride, data, .. } => plane_fits(data, stride, width * 3, height),
+            Self::Rgba8 { stride, data, .. } => plane_fits(data, stride, width * 4, height),
+            Self::I420 { y, u, v, .. } => {
+                plane_fits(y.0, y.1, width, height)
+                    && plane_fits(u.0, u.1, chroma_width, chroma_height)
+                    && plane_fits(v.0, v.1, chroma_width, chroma_height)
+            }
+            Self::Nv12 { y, uv, .. } => {
+                plane_fits(y.0, y.1, width, height)
+                    && plane_fits(uv.0, uv.1, chroma_width * 2, chroma_height)
+            }
+            Self::Yuyv { stride, data, .. } => plane_fits(data, stride, chroma_width * 4, height),
+        }
+    }
+
+    /// Converts the frame into `buffer`, which is reused if it has the right size and format.
+    fn convert_into(&self, buffer: Option<SharedImageBuffer>) -> SharedImageBuffer {
+        let (width, height) = (self.width(), self.height());
+        if let Self::Rgba8 { stride, data, .. } = *self {
+            let mut pixels = match buffer {
+                Some(SharedImageBuffer::RGBA8(pixels))
+                    if pixels.width() == width && pixels.height() == height =>
+                {
+                    pixels
+                }
+                _ => SharedPixelBuffer::<Rgba8Pixel>::new(width, height),
+            };
+            copy_rows(pixels.make_mut_bytes(), data, stride, width * 4);
+            return SharedImageBuffer::RGBA8(pixels);
+        }
+
+        let mut pixels = match buffer {
+            Some(SharedImageBuffer::RGB8(pixels))
+                if pixels.width() == width && pixels.height() == height =>
+            {
+                pixels
+            }
+            _ => SharedPixelBuffer::<Rgb8Pixel>::new(width, height),
+        };
+        if let Self::Rgb8 { stride, data, .. } = *self {
+            copy_rows(pixels.make_mut_bytes(), data, stride, width * 3);
+            return SharedImageBuffer::RGB8(pixels);
+        }
+
+        let rows = pixels.make_mut_slice().chunks_exact_mut(width.max(1) as usize);
+        match *self {
+            Self::Rgb8 { .. } | Self::Rgba8 { .. } => unreachable!(),
+            Self::I420 { y, u, v, .. } => {
+                for (row, pixels) in rows.enumerate() {
+                    let luma = &y.0[row * y.1 as usize..];
+                    let blue = &u.0[row / 2 * u.1 as usize..];
+                    let red = &v.0[row / 2 * v.1 as usize..];
+                    for (x, pixel) in pixels.iter_mut().enumerate() {
+                        *pixel = yuv_to_rgb(luma[x], blue[x / 2], red[x / 2]);
+                    }
+                }
+            }
+            Self::Nv12 { y, uv, .. } => {
+                for (row, pixels) in rows.enumerate() {
+                    let luma = &y.0[row * y.1 as usize..];
+                    let chroma = &uv.0[row / 2 * uv.1 as usize..];
+                    for (x, pixel) in pixels.iter_mut().enumerate() {
+                        *pixel = yuv_to_rgb(luma[x], chroma[x / 2 * 2], chroma[x / 2 * 2 + 1]);
+                    }
+                }
+            }
+            Self::Yuyv { stride, data, .. } => {
+                for (row, pixels) in rows.enumerate() {
+                    let packed = &data[row * stride as usize..];
+                    for (x, pixel) in pixels.iter_mut().enumerate() {
+                        let pair = &packed[x / 2 * 4..];
+                        *pixel = yuv_to_rgb(pair[x % 2 * 2], pair[1], pair[3]);
+                    }
+                }
+            }
+        }
+        SharedImageBuffer::RGB8(pixels)
+    }
+}
+
+fn copy_rows(destination: &mut [u8], source: &[u8], stride: u32, row_size: u32) {
+    if row_size == 0 {
+        return;
+    }
+    for (row, destination_row) in destination.chunks_exact_mut(row_size as usize).enumerate() {
+        let start = row * stride as usize;
+        destination_row.copy_from_slice(&source[start..start + row_size as usize]);
+    }
+}
+
+/// Converts a pixel from YUV with the BT.601 coefficients and the limited range.
+fn yuv_to_rgb(y: u8, u: u8, v: u8) -> Rgb8Pixel {
+    let c = 298 * (y as i32 - 16);
+    let d = u as i32 - 128;
+    let e = v as i32 - 128;
+    let clamp = |value: i32| ((value + 128) >> 8).clamp(0, 255) as u8;
+    Rgb8Pixel { r: clamp(c + 409 * e), g: clamp(c - 100 * d - 208 * e), b: clamp(c + 516 * d) }
+}
+
+/// Error returned by [`VideoSink::push_frame()`] when the planes of the frame are too small
+/// for its size and strides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidVideoFrame(());
+
+impl core::fmt::Display for InvalidVideoFrame {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("the planes of the video frame are too small for its size")
+    }
+}
+
+impl std::error::Error for InvalidVideoFrame {}
+
+/// The pixel format of a [`StoredFrame`], which is the variant of the [`VideoFrame`] it was copied from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StoredFormat {
+    Rgb8,
+    Rgba8,
+    I420,
+    Nv12,
+    Yuyv,
+}
+
+/// A copy of the planes of a [`VideoFrame`], whose rows are not padded
+struct StoredFrame {
+    format: StoredFormat,
+    width: u32,
+    height: u32,
+    planes: [Vec<u8>; 3],
+}
+
+impl StoredFrame {
+    /// Copies the frame, into the memory of the planes of `recycled` if there is one.
+    fn new(frame: &VideoFrame<'_>, recycled: Option<StoredFrame>) -> Self {
+        let mut planes = recycled.map(|recycled| recycled.planes).unwrap_or_default();
+        let (width, height) = (frame.width(), frame.height());
+        let (chroma_width, chroma_height) = ((width + 1) / 2, (height + 1) / 2);
+        let mut copy = |index: usize, plane: &[u8], stride: u32, row_size: u32, rows: u32| {
+            let destination = &mut planes[index];
+            destination.clear();
+            for row in 0..rows as usize {
+                let start = row * stride as usize;
+                destination.extend_from_slice(&plane[start..start + row_size as usize]);
+            }
+        };
+        let format = match *frame {
+            VideoFrame::Rgb8 { stride, data, .. } => {
+                copy(0, data, stride, width * 3, height);
+                StoredFormat::Rgb8
+            }
+            VideoFrame::Rgba8 { stride, data, .. } => {
+                copy(0, data, stride, width * 4, height);
+                StoredFormat::Rgba8
+            }
+            VideoFrame::I420 { y, u, v, .. } => {
+                copy(0, y.0, y.1, width, height);
+                copy(1, u.0, u.1, chroma_width, chroma_height);
+                copy(2, v.0, v.1, chroma_width, chroma_height);
+                StoredFormat::I420
+            }
+            VideoFrame::Nv12 { y, uv, .. } => {
+                copy(0, y.0, y.1, width, height);
+                copy(1, uv.0, uv.1, chroma_width * 2, chroma_height);
+                StoredFormat::Nv12
+            }
+            VideoFrame::Yuyv { stride, data, .. } => {
+                copy(0, data, stride, chroma_width * 4, height);
+                StoredFormat::Yuyv
+            }
+        };
+        Self { format, width, height, planes }
+    }
+
+    /// Returns the frame that borrows the planes of the copy
+    fn as_frame(&self) -> VideoFrame<'_> {
+        let (width, height) = (self.width, self.height);
+        let chroma_width = (width + 1) / 2;
+        let [first, second, third] = &self.planes;
+        match self.format {
+            StoredFormat::Rgb8 => {
+                VideoFrame::Rgb8 { width, height, stride: width * 3, data: first }
+            }
+            StoredFormat::Rgba8 => {
+                VideoFrame::Rgba8 { width, height, stride: width * 4, data: first }
+            }
+            StoredFormat::I420 => VideoFrame::I420 {
+                width,
+                height,
+                y: (first, width),
+                u: (second, chroma_width),
+                v: (third, chroma_width),
+            },
+            StoredFormat::Nv12 => VideoFrame::Nv12 {
+                width,
+                height,
+                y: (first, width),
+                uv: (second, chroma_width * 2),
+            },
+            StoredFormat::Yuyv => {
+                VideoFrame::Yuyv { width, height, stride: chroma_width * 4, data: first }
+            }
+        }
+    }
+}
+
+struct VideoSinkInner {
+    id: VideoStreamId,
+    set_image: Mutex<Option<Box<dyn FnOnce(Image) + Send>>>,
+    /// The last frame that was pushed and not shown yet
+    pending_frame: Mutex<Option<StoredFrame>>,
+    /// Whether an event was posted to the event loop to show the pending frame
+    delivery_scheduled: AtomicBool,
+    /// The frame that is shown
+    shown_frame: Mutex<Option<StoredFrame>>,
+    /// The frames that were shown or dropped before, to copy the next frames into
+    recycled_frames: Mutex<Vec<StoredFrame>>,
+}
+
+impl Drop for VideoSinkInner {
+    fn drop(&mut self) {
+        VIDEO_STREAMS.lock().unwrap().remove(&self.id.0);
+    }
+}
+
+/// The sinks whose frames are shown by the images of [`ImageInner::VideoStream`]
+static VIDEO_STREAMS: Lazy<Mutex<BTreeMap<u64, Weak<VideoSinkInner>>>> =
+    Lazy::new(Default::default);
+
+static NEXT_VIDEO_STREAM_ID: AtomicU64 = AtomicU64::new(1);
+
+/// The state of the shown frame of a stream in the thread of the event loop, which the renderers
+/// and the layouts depend on
+#[derive(Default, FieldOffsets)]
+#[repr(C)]
+#[pin]
+struct ShownFrameState {
+    /// Incremented each time a frame is shown
+    generation: Property<u64>,
+    size: Property<IntSize>,
+    /// The shown frame converted to RGB on the CPU, and its generation
+    converted: RefCell<Option<(u64, SharedImageBuffer)>>,
+}
+
+thread_local!(static SHOWN_FRAMES: RefCell<BTreeMap<u64, Pin<Rc<ShownFrameState>>>> = Default::default());
+
+fn shown_frame_state(stream: VideoStreamId) -> Option<Pin<Rc<ShownFrameState>>> {
+    SHOWN_FRAMES.with(|shown_frames| shown_frames.borrow().get(&stream.0).cloned())
+}
+
+fn video_sink(stream: VideoStreamId) -> Option<Arc<VideoSinkInner>> {
+    VIDEO_STREAMS.lock().unwrap().get(&stream.0).and_then(Weak::upgrade)
+}
+
+/// Returns the size of the frame of the stream that is shown, and registers a dependency to it.
+pub(crate) fn shown_video_frame_size(stream: VideoStreamId) -> IntSize {
+    shown_frame_state(stream).map_or_else(Default::default, |state| {
+        ShownFrameState::FIELD_OFFSETS.size.apply_pin(state.as_ref()).get()
+    })
+}
+
+/// Returns a number that changes each time a frame of the stream is shown, or None if no frame was
+/// shown yet or if the [`VideoSink`] was dropped. The renderers that draw the frames call this
+/// to be redrawn when the next frame is shown.
+pub fn shown_video_frame_generation(stream: VideoStreamId) -> Option<u64> {
+    let state = shown_frame_state(stream)?;
+    let generation = ShownFrameState::FIELD_OFFSETS.generation.apply_pin(state.as_ref()).get();
+    video_sink(stream).is_some().then(|| generation)
+}
+
+/// Calls `f` with the frame of the stream that is shown, for the renderers that upload its planes
+/// to the GPU. Returns None if no frame was shown yet or if the [`VideoSink`] was dropped.
+pub fn with_shown_video_frame<R>(
+    stream: VideoStreamId,
+    f: impl FnOnce(&VideoFrame<'_>) -> R,
+) -> Option<R> {
+    let sink = video_sink(stream)?;
+    let shown_frame = sink.shown_frame.lock().unwrap();
+    shown_frame.as_ref().map(|frame| f(&frame.as_frame()))
+}
+
+/// Returns the frame of the stream that is shown, converted to RGB on the CPU, for the renderers
+/// that don't convert the YUV formats. The conversion is done once per frame.
+pub fn shown_video_frame_to_rgb(stream: VideoStreamId) -> Option<SharedImageBuffer> {
+    let state = shown_frame_state(stream)?;
+    let generation = shown_video_frame_generation(stream)?;
+    let mut converted = state.converted.borrow_mut();
+    match converted.take() {
+        Some((converted_generation, buffer)) if converted_generation == generation => {
+            *converted = Some((generation, buffer));
+        }
+        previous => {
+            let buffer = with_shown_video_frame(stream, |frame| {
+                frame.convert_into(previous.map(|(_, buffer)| buffer))
+            })?;
+            *converted = Some((generation, buffer));
+        }
+    }
+    converted.as_ref().map(|(_, buffer)| buffer.clone())
+}
+
+/// A handle to show the frames of a camera or of a video decoder in an `Image` element, at the
+/// rate at which they are produced.
+///
+/// The frames are pushed with [`Self::push_frame()`], from any thread. Their planes are copied in
+/// that thread, into the memory of the frames shown before, so that a new frame usually doesn't
+/// allocate memory, and the event loop is woken up to show it. When frames are pushed faster than
+/// they are shown, the older ones are dropped.
+///
+/// All the frames are shown by the same [`Image`], returned by [`Self::image()`] and passed to the
+/// function given to [`Self::new()`] when the first frame is shown. The image is updated in place:
+/// the GL backend uploads the planes of each new frame to textures and converts the YUV formats
+/// to RGB with a shader, into one texture that is reused for all the frames. The other renderers
+/// convert the frames on the CPU.
+///
+/// ```no_run
+/// # i_slint_backend_testing::init();
+/// slint::slint! { Preview := Window { property <image> frame; Image { source: frame; } } }
+/// let preview = Preview::new();
+/// let preview_weak = preview.as_weak();
+/// let sink = slint::VideoSink::new(move |image| preview_weak.unwrap().set_frame(image));
+/// std::thread::spawn(move || loop {
+///     # let (width, height, y, u, v): (u32, u32, &[u8], &[u8], &[u8]) = (0, 0, &[], &[], &[]);
+///     // ... capture a frame from the camera
+///     sink.push_frame(slint::VideoFrame::I420 {
+///         width,
+///         height,
+///         y: (y, width),
+///         u: (u, width / 2),
+///         v: (v, width / 2),
+///     })
+///     .unwrap();
+/// });
+/// preview.run();
+/// ```
+#[derive(Clone)]
+pub struct VideoSink(Arc<VideoSinkInner>);
+
+impl VideoSink {
+    /// Creates a sink that calls `set_image` in the thread of the event loop with the image that
+    /// shows the frames, when the first frame is shown.
+    pub fn new(set_image: impl FnOnce(Image) + Send + 'static) -> Self {
+        let id = VideoStreamId(NEXT_VIDEO_STREAM_ID.fetch_add(1, Ordering::Relaxed));
+        let inner = Arc::new(VideoSinkInner {
+            id,
+            set_image: Mutex::new(Some(Box::new(set_image))),
+            pending_frame: Default::default(),
+            delivery_scheduled: AtomicBool::new(false),
+            shown_frame: Default::default(),
+            recycled_frames: Default::default(),
+        });
+        VIDEO_STREAMS.lock().unwrap().insert(id.0, Arc::downgrade(&inner));
+        Self(inner)
+    }
+
+    /// Returns the image that shows the frames of this sink. It is empty until the first frame
+    /// is shown.
+    pub fn image(&self) -> Image {
+        Image::from(ImageInner::VideoStream(self.0.id))
+    }
+
+    /// Copies the frame and schedules it to be shown. This function can be called from any
+    /// thread.
+    pub fn push_frame(&self, frame: VideoFrame<'_>) -> Result<(), InvalidVideoFrame> {
+        if !frame.is_valid() {
+            return Err(InvalidVideoFrame(()));
+        }
+
+        let recycled_frame = self.0.recycled_frames.lock().unwrap().pop();
+        let stored_frame = StoredFrame::new(&frame, recycled_frame);
+        let dropped_frame = self.0.pending_frame.lock().unwrap().replace(stored_frame);
+        if let Some(dropped_frame) = dropped_frame {
+            self.recycle(dropped_frame);
+        }
+
+        if !self.0.delivery_scheduled.swap(true, Ordering::AcqRel) {
+            // The backend might not be initialized yet, in which case the frame is shown after
+            // the next one is pushed
+            match crate::backend::instance() {
+                Some(backend) => {
+                    let inner = self.0.clone();
+                    backend.post_event(Box::new(move || VideoSink(inner).show_pending_frame()))
+                }
+                None => self.0.delivery_scheduled.store(false, Ordering::Release),
+            }
+        }
+        Ok(())
+    }
+
+    fn show_pending_frame(&self) {
+        self.0.delivery_scheduled.store(false, Ordering::Release);
+        let frame = match self.0.pending_frame.lock().unwrap().take() {
+            Some(frame) => frame,
+            None => return,
+        };
+        let size = IntSize::new(frame.width, frame.height);
+        let previous_frame = self.0.shown_frame.lock().unwrap().replace(frame);
+        // The renderers copy the shown frame to the GPU when they draw it, and don't keep it
+        if let Some(previous_frame) = previous_frame {
+            self.recycle(previous_frame);
+        }
+
+        let state = SHOWN_FRAMES.with(|shown_frames| {
+            shown_frames
+                .borrow_mut()
+                .entry(self.0.id.0)
+                .or_insert_with(|| Rc::pin(Default::default()))
+                .clone()
+        });
+        let generation = ShownFrameState::FIELD_OFFSETS.generation.apply_pin(state.as_ref());
+        generation.set(generation.get_untracked() + 1);
+        ShownFrameState::FIELD_OFFSETS.size.apply_pin(state.as_ref()).set(size);
+
+        let set_image = self.0.set_image.lock().unwrap().take();
+        if let Some(set_image) = set_image {
+            set_image(self.image());
+        }
+    }
+
+    /// Keeps the memory of a frame that is not shown anymore, to copy one of the next frames
+    /// into it.
+    fn recycle(&self, frame: StoredFrame) {
+        let mut recycled_frames = self.0.recycled_frames.lock().unwrap();
+        if recycled_frames.len() < RECYCLED_FRAME_COUNT {
+            recycled_frames.push(frame);
+        }
+    }
+}
+
+#[test]
+fn test_video_frame_conversion() {
+    let y = [16, 235, 81, 41];
+    let (u, v) = ([128], [128]);
+    let frame = VideoFrame::I420 { width: 2, height: 2, y: (&y, 2), u: (&u, 1), v: (&v, 1) };
+    assert!(frame.is_valid());
+    let rgb = match frame.convert_into(None) {
+        SharedImageBuffer::RGB8(pixels) => pixels,
+        _ => panic!("YUV frames are converted to RGB"),
+    };
+    assert_eq!(rgb.as_slice()[0], Rgb8Pixel { r: 0, g: 0, b: 0 });
+    assert_eq!(rgb.as_slice()[1], Rgb8Pixel { r: 255, g: 255, b: 255 });
+
+    // Pure red in BT.601
+    let yuyv = [81, 90, 81, 240];
+    let frame = VideoFrame::Yuyv { width: 2, height: 1, stride: 4, data: &yuyv };
+    let rgb = match frame.convert_into(None) {
+        SharedImageBuffer::RGB8(pixels) => pixels,
+        _ => panic!("YUV frames are converted to RGB"),
+    };
+    assert_eq!(rgb.as_slice()[0], Rgb8Pixel { r: 255, g: 0, b: 0 });
+    assert_eq!(rgb.as_slice()[0], rgb.as_slice()[1]);
+
+    let rgba = [1, 2, 3, 4, 0xff, 5, 6, 7, 8, 0xff];
+    let frame = VideoFrame::Rgba8 { width: 1, height: 2, stride: 5, data: &rgba };
+    match frame.convert_into(None) {
+        SharedImageBuffer::RGBA8(pixels) => {
+            assert_eq!(pixels.as_bytes(), &[1, 2, 3, 4, 5, 6, 7, 8])
+        }
+        _ => panic!("RGBA frames stay RGBA"),
+    }
+
+    let frame = VideoFrame::Rgb8 { width: 2, height: 2, stride: 6, data: &[0; 11] };
+    assert!(!frame.is_valid());
+}
+
+#[test]
+fn test_stored_video_frame() {
+    // The rows of the planes are padded to 4 bytes
+    let y = [1, 2, 3, 0, 4, 5, 6, 0];
+    let (u, v) = ([7, 8, 0, 0], [9, 10, 0, 0]);
+    let frame = VideoFrame::I420 { width: 3, height: 2, y: (&y, 4), u: (&u, 4), v: (&v, 4) };
+    let stored = StoredFrame::new(&frame, None);
+    assert_eq!(stored.planes, [vec![1, 2, 3, 4, 5, 6], vec![7, 8], vec![9, 10]]);
+    match stored.as_frame() {
+        VideoFrame::I420 { width: 3, height: 2, y, u, v } => {
+            assert_eq!(y, (&[1, 2, 3, 4, 5, 6][..], 3));
+            assert_eq!(u, (&[7, 8][..], 2));
+            assert_eq!(v, (&[9, 10][..], 2));
+        }
+        _ => panic!("the format is kept"),
+    }
+
+    // The memory of a recycled frame is reused
+    let rgb = [1, 2, 3, 4, 5, 6];
+    let frame = VideoFrame::Rgb8 { width: 1, height: 2, stride: 3, data: &rgb };
+    let capacity = stored.planes[0].capacity();
+    let stored = StoredFrame::new(&frame, Some(stored));
+    assert_eq!(stored.planes[0], rgb);
+    assert_eq!(stored.planes[0].capacity(), capacity);
+    assert!(stored.as_frame().is_valid());
+}
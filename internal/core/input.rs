@@ -8,7 +8,8 @@
 use crate::graphics::Point;
 use crate::item_tree::{ItemRc, ItemVisitorResult, ItemWeak, VisitChildrenResult};
 use crate::items::{ItemRef, TextCursorDirection};
-pub use crate::items::{KeyEventType, PointerEventButton};
+pub use crate::items::{KeyEventType, PointerEventButton, ScrollEventPhase};
+use crate::properties::PropertyTracker;
 use crate::window::WindowRc;
 use crate::{component::ComponentRc, SharedString};
 use crate::{Coord, Property};
@@ -20,7 +21,7 @@
 
 /// A Mouse event
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 #[allow(missing_docs)]
 pub enum MouseEvent {
     /// The mouse was pressed
@@ -29,10 +30,11 @@ pub enum MouseEvent {
     MouseReleased { pos: Point, button: PointerEventButton },
     /// The mouse position has changed
     MouseMoved { pos: Point },
-    /// Wheel was operated.
+    /// Wheel was operated, or the fingers moved on a touchpad.
     /// `pos` is the position of the mouse when the event happens.
     /// `delta` is the amount of pixel to scroll.
-    MouseWheel { pos: Point, delta: Point },
+    /// `phase` is the phase of the touchpad gesture, or `none` for a wheel.
+    MouseWheel { pos: Point, delta: Point, phase: ScrollEventPhase },
     /// The mouse exited the item or component
     MouseExit,
 }
@@ -51,23 +53,48 @@ pub fn pos(&self) -> Option<Point> {
 
     /// Translate the position by the given value
     pub fn translate(&mut self, vec: Vector2D<Coord>) {
-        let pos = match self {
+        if let Some(pos) = self.pos_mut() {
+            *pos += vec;
+        }
+    }
+
+    fn pos_mut(&mut self) -> Option<&mut Point> {
+        match self {
             MouseEvent::MousePressed { pos, .. } => Some(pos),
             MouseEvent::MouseReleased { pos, .. } => Some(pos),
             MouseEvent::MouseMoved { pos } => Some(pos),
             MouseEvent::MouseWheel { pos, .. } => Some(pos),
             MouseEvent::MouseExit => None,
-        };
-        if let Some(pos) = pos {
-            *pos += vec;
         }
     }
 }
 
+/// Maps a position in the coordinates of the item to the coordinates of its children. This only
+/// changes the position for the items that transform their children, like `Rotate`, so that the
+/// children receive the events in their own, untransformed, coordinates.
+fn map_to_children(item: Pin<ItemRef>, pos: Point) -> Point {
+    match ItemRef::downcast_pin::<crate::items::Rotate>(item) {
+        Some(rotate) => rotate.map_to_children(pos),
+        None => pos,
+    }
+}
+
+fn map_event_to_children(item: Pin<ItemRef>, event: &mut MouseEvent) {
+    if let Some(pos) = event.pos_mut() {
+        *pos = map_to_children(item, *pos);
+    }
+}
+
 /// This value is returned by the `input_event` function of an Item
 /// to notify the run-time about how the event was handled and
 /// what the next steps are.
 /// See [`crate::items::ItemVTable::input_event`].
+///
+/// A mouse event goes through two phases: in the capture phase, the
+/// `input_event_filter_before_children` function of the items under the mouse is called from the
+/// root to the leaves, and each of them can intercept the event (see [`InputEventFilterResult`]).
+/// Then, in the bubble phase, the `input_event` function is called from the leaves to the root,
+/// until an item accepts the event or grabs the mouse.
 #[repr(C)]
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum InputEventResult {
@@ -78,6 +105,9 @@ pub enum InputEventResult {
     EventIgnored,
     /// All further mouse event need to be sent to this item or component
     GrabMouse,
+    /// Like `GrabMouse`, but the parents of the item don't see the further mouse events in their
+    /// capture phase, so they can't intercept them until the grab is released.
+    GrabMouseExclusive,
 }
 
 impl Default for InputEventResult {
@@ -155,6 +185,13 @@ pub struct KeyEvent {
     // note: this field is not exported in the .slint in the KeyEvent builtin struct
     /// Indicates whether the key was pressed or released
     pub event_type: KeyEventType,
+
+    /// The platform specific code of the physical key, which doesn't depend on the keyboard layout,
+    /// or 0 when the platform doesn't provide it. On Linux, this is the evdev key code.
+    pub scan_code: i32,
+    /// True when the key press was generated because the key is held down. The key release event
+    /// is only sent once, when the key is actually released.
+    pub is_auto_repeat: bool,
 }
 
 impl KeyEvent {
@@ -212,6 +249,8 @@ pub fn text_shortcut(&self) -> Option<TextShortcut> {
                 key_codes::DownArrow => {
                     return Some(TextShortcut::Move(TextCursorDirection::EndOfParagraph))
                 }
+                key_codes::Backspace => return Some(TextShortcut::DeleteWordBackward),
+                key_codes::Delete => return Some(TextShortcut::DeleteWordForward),
                 _ => (),
             };
         }
@@ -297,6 +336,10 @@ pub enum TextShortcut {
     DeleteForward,
     /// Delete the Character to the left of the cursor (aka Backspace).
     DeleteBackward,
+    /// Delete the word to the right of the cursor
+    DeleteWordForward,
+    /// Delete the word to the left of the cursor
+    DeleteWordBackward,
 }
 
 /// Represents how an item's key_event handler dealt with a key event.
@@ -344,6 +387,8 @@ pub struct MouseInputState {
     item_stack: Vec<(ItemWeak, InputEventFilterResult)>,
     /// true if the top item of the stack has the mouse grab
     grabbed: bool,
+    /// true if the grab is exclusive, and the parents of the grabber can't intercept the events
+    exclusive: bool,
 }
 
 /// Try to handle the mouse grabber. Return true if the event has handled, or false otherwise
@@ -356,9 +401,12 @@ fn handle_mouse_grab(
         return false;
     };
 
+    // The event in the coordinates of the parent of the current item, and of the grabber
     let mut event = *mouse_event;
+    let mut grabber_event = event;
     let mut intercept = false;
     let mut invalid = false;
+    let exclusive = mouse_input_state.exclusive;
 
     mouse_input_state.item_stack.retain(|it| {
         if invalid {
@@ -376,13 +424,16 @@ fn handle_mouse_grab(
         }
         let g = item.borrow().as_ref().geometry();
         event.translate(-g.origin.to_vector());
+        grabber_event = event;
 
         if it.1 == InputEventFilterResult::ForwardAndInterceptGrab
+            && !exclusive
             && item.borrow().as_ref().input_event_filter_before_children(event, window, &item)
                 == InputEventFilterResult::Intercept
         {
             intercept = true;
         }
+        map_event_to_children(item.borrow(), &mut event);
         true
     });
     if invalid {
@@ -390,8 +441,9 @@ fn handle_mouse_grab(
     }
 
     let grabber = mouse_input_state.item_stack.last().unwrap().0.upgrade().unwrap();
-    let input_result = grabber.borrow().as_ref().input_event(event, window, &grabber);
-    if input_result != InputEventResult::GrabMouse {
+    let input_result = grabber.borrow().as_ref().input_event(grabber_event, window, &grabber);
+    mouse_input_state.exclusive = input_result == InputEventResult::GrabMouseExclusive;
+    if !matches!(input_result, InputEventResult::GrabMouse | InputEventResult::GrabMouseExclusive) {
         mouse_input_state.grabbed = false;
         send_exit_events(mouse_input_state, mouse_event.pos(), window);
     }
@@ -409,7 +461,7 @@ fn send_exit_events(
         let g = item.borrow().as_ref().geometry();
         let contains = pos.map_or(false, |p| g.contains(p));
         if let Some(p) = pos.as_mut() {
-            *p -= g.origin.to_vector();
+            *p = map_to_children(item.borrow(), *p - g.origin.to_vector());
         }
         if !contains {
             item.borrow().as_ref().input_event(MouseEvent::MouseExit, window, &item);
@@ -433,26 +485,27 @@ pub fn process_mouse_input(
     send_exit_events(&mouse_input_state, mouse_event.pos(), window);
 
     let mut result = MouseInputState::default();
-    type State = (Vector2D<Coord>, Vec<(ItemWeak, InputEventFilterResult)>);
+    // The state is the event in the coordinates of the parent item, and the stack of its parents
+    type State = (MouseEvent, Vec<(ItemWeak, InputEventFilterResult)>);
     crate::item_tree::visit_items_with_post_visit(
         &component,
         crate::item_tree::TraversalOrder::FrontToBack,
         |comp_rc: &ComponentRc,
          item: core::pin::Pin<ItemRef>,
          item_index: usize,
-         (offset, mouse_grabber_stack): &State| {
+         (event, mouse_grabber_stack): &State| {
             let item_rc = ItemRc::new(comp_rc.clone(), item_index);
 
             let geom = item.as_ref().geometry();
-            let geom = geom.translate(*offset);
 
             let mut mouse_grabber_stack = mouse_grabber_stack.clone();
 
-            let post_visit_state = if mouse_event.pos().map_or(false, |p| geom.contains(p))
+            let mut event2 = *event;
+            event2.translate(-geom.origin.to_vector());
+
+            let post_visit_state = if event.pos().map_or(false, |p| geom.contains(p))
                 || crate::item_rendering::is_clipping_item(item)
             {
-                let mut event2 = mouse_event;
-                event2.translate(-geom.origin.to_vector());
                 let filter_result =
                     item.as_ref().input_event_filter_before_children(event2, window, &item_rc);
                 mouse_grabber_stack.push((item_rc.downgrade(), filter_result));
@@ -477,10 +530,8 @@ pub fn process_mouse_input(
                 None
             };
 
-            (
-                ItemVisitorResult::Continue((geom.origin.to_vector(), mouse_grabber_stack)),
-                post_visit_state,
-            )
+            map_event_to_children(item, &mut event2);
+            (ItemVisitorResult::Continue((event2, mouse_grabber_stack)), post_visit_state)
         },
         |_, item, post_state, r| {
             if let Some((event2, mouse_grabber_stack, item_rc, intercept)) = post_state {
@@ -496,22 +547,89 @@ pub fn process_mouse_input(
                     InputEventResult::EventIgnored => {
                         return VisitChildrenResult::CONTINUE;
                     }
-                    InputEventResult::GrabMouse => {
+                    grab @ (InputEventResult::GrabMouse | InputEventResult::GrabMouseExclusive) => {
                         result.item_stack = mouse_grabber_stack;
                         result.item_stack.last_mut().unwrap().1 =
                             InputEventFilterResult::ForwardAndInterceptGrab;
                         result.grabbed = true;
+                        result.exclusive = grab == InputEventResult::GrabMouseExclusive;
                         return VisitChildrenResult::abort(item_rc.index(), 0);
                     }
                 }
             }
             r
         },
-        (Vector2D::new(0 as Coord, 0 as Coord), Vec::new()),
+        (mouse_event, Vec::new()),
     );
     result
 }
 
+/// Updates the `has-hover` property of the [`HoverArea`](crate::items::HoverArea) items of the
+/// components, and calls their `pointer-entered` and `pointer-exited` callbacks. Contrary to the
+/// TouchArea, all the HoverAreas under the mouse are hovered, even if they are covered by other
+/// elements, unless they are outside of a clipping parent. `pos` is None when the mouse left the
+/// window. `hovered` contains the items that were hovered before, and is updated.
+pub(crate) fn update_hover(
+    components: &[(ComponentRc, Point)],
+    pos: Option<Point>,
+    hovered: &mut Vec<ItemWeak>,
+) {
+    let mut new_hovered = Vec::new();
+    for (component, origin) in components {
+        let pos = match pos {
+            Some(pos) => pos - origin.to_vector(),
+            None => break,
+        };
+        crate::item_tree::visit_items(
+            component,
+            crate::item_tree::TraversalOrder::FrontToBack,
+            |comp_rc, item, item_index, pos: &Option<Point>| {
+                // None for the children of a clipping item that doesn't contain the mouse
+                let pos = match pos {
+                    Some(pos) => *pos,
+                    None => return ItemVisitorResult::Continue(None),
+                };
+                let geom = item.as_ref().geometry();
+                let contains = geom.contains(pos);
+                if contains && ItemRef::downcast_pin::<crate::items::HoverArea>(item).is_some() {
+                    new_hovered.push(ItemRc::new(comp_rc.clone(), item_index).downgrade());
+                }
+                if !contains && crate::item_rendering::is_enabled_clipping_item(item) {
+                    ItemVisitorResult::Continue(None)
+                } else {
+                    ItemVisitorResult::Continue(Some(map_to_children(
+                        item,
+                        pos - geom.origin.to_vector(),
+                    )))
+                }
+            },
+            Some(pos),
+        );
+    }
+
+    let old_hovered = core::mem::replace(hovered, new_hovered.clone());
+    let set_hovered = |item: &ItemWeak, value: bool| {
+        if let Some(item) = item.upgrade() {
+            if let Some(hover_area) =
+                ItemRef::downcast_pin::<crate::items::HoverArea>(item.borrow())
+            {
+                hover_area.set_hovered(value);
+            }
+        }
+    };
+    for item in old_hovered.iter().filter(|item| !new_hovered.contains(item)) {
+        set_hovered(item, false);
+    }
+    for item in new_hovered.iter().filter(|item| !old_hovered.contains(item)) {
+        set_hovered(item, true);
+    }
+}
+
+/// The time between two toggles of the text cursor, when the backend doesn't know the
+/// setting of the platform
+pub const DEFAULT_CURSOR_BLINK_INTERVAL: core::time::Duration =
+    core::time::Duration::from_millis(500);
+
 /// The TextCursorBlinker takes care of providing a toggled boolean property
 /// that can be used to animate a blinking cursor. It's typically stored in the
 /// Window using a Weak and set_binding() can be used to set up a binding on a given
@@ -524,22 +642,42 @@ pub fn process_mouse_input(
 pub(crate) struct TextCursorBlinker {
     cursor_visible: Property<bool>,
     cursor_blink_timer: crate::timers::Timer,
+    /// The time between two toggles, or None if the cursor doesn't blink
+    interval: core::cell::Cell<Option<core::time::Duration>>,
+    /// Tracks the properties the interval was computed from, while the cursor is shown
+    interval_tracker: Pin<alloc::boxed::Box<PropertyTracker<alloc::boxed::Box<dyn Fn()>>>>,
 }
 
 impl TextCursorBlinker {
     /// Creates a new instance, wrapped in a Pin<Rc<_>> because the boolean property
     /// the blinker properties uses the property system that requires pinning.
-    pub fn new() -> Pin<Rc<Self>> {
+    /// `on_interval_changed` is called when one of the properties the interval of the
+    /// shown cursor depends on changes.
+    pub fn new(on_interval_changed: alloc::boxed::Box<dyn Fn()>) -> Pin<Rc<Self>> {
         Rc::pin(Self {
             cursor_visible: Property::new(true),
             cursor_blink_timer: Default::default(),
+            interval: Default::default(),
+            interval_tracker: alloc::boxed::Box::pin(PropertyTracker::new_with_change_handler(
+                on_interval_changed,
+            )),
         })
     }
 
     /// Sets a binding on the provided property that will ensure that the property value
-    /// is true when the cursor should be shown and false if not.
-    pub fn set_binding(instance: Pin<Rc<TextCursorBlinker>>, prop: &Property<bool>) {
+    /// is true when the cursor should be shown and false if not. The cursor is toggled every
+    /// duration returned by `interval`, or always shown if it returns None. The properties
+    /// read by `interval` are tracked until the blinker is stopped.
+    pub fn set_binding(
+        instance: Pin<Rc<TextCursorBlinker>>,
+        prop: &Property<bool>,
+        interval: impl FnOnce() -> Option<core::time::Duration>,
+    ) {
         instance.as_ref().cursor_visible.set(true);
+        let interval = instance.interval_tracker.as_ref().evaluate_as_dependency_root(interval);
+        if instance.interval.replace(interval) != interval {
+            instance.cursor_blink_timer.stop();
+        }
         // Re-start timer, in case.
         Self::start(&instance);
         prop.set_binding(move || {
@@ -550,6 +688,11 @@ pub fn set_binding(instance: Pin<Rc<TextCursorBlinker>>, prop: &Property<bool>)
     /// Starts the blinking cursor timer that will toggle the cursor and update all bindings that
     /// were installed on properties with set_binding call.
     pub fn start(self: &Pin<Rc<Self>>) {
+        let interval = match self.interval.get() {
+            Some(interval) => interval,
+            // A steady cursor doesn't need the timer, nor to redraw the window
+            None => return self.cursor_blink_timer.stop(),
+        };
         if self.cursor_blink_timer.running() {
             self.cursor_blink_timer.restart();
         } else {
@@ -567,7 +710,7 @@ pub fn start(self: &Pin<Rc<Self>>) {
             };
             self.cursor_blink_timer.start(
                 crate::timers::TimerMode::Repeated,
-                core::time::Duration::from_millis(500),
+                interval,
                 toggle_cursor,
             );
         }
@@ -576,6 +719,8 @@ pub fn start(self: &Pin<Rc<Self>>) {
     /// Stops the blinking cursor timer. This is usually used for example when the window that contains
     /// text editable elements looses the focus or is hidden.
     pub fn stop(&self) {
-        self.cursor_blink_timer.stop()
+        self.cursor_blink_timer.stop();
+        // Changes to the interval don't matter anymore until the cursor is shown again
+        self.interval_tracker.as_ref().evaluate_as_dependency_root(|| ());
     }
 }
@@ -7,7 +7,10 @@
 This module contains the code moving the keyboard focus between items
 */
 
-use crate::item_tree::ComponentItemTree;
+use crate::item_tree::{ComponentItemTree, ItemRc};
+use crate::items::{FocusGroupPolicy, FocusScope, ItemRef, TextInput};
+use alloc::vec::Vec;
+use core::pin::Pin;
 
 pub fn step_out_of_node(
     index: usize,
@@ -59,6 +62,125 @@ pub fn default_previous_in_local_focus_chain(
     }
 }
 
+/// Returns the `tab-index` of the item, or 0 if the item doesn't have that property.
+fn tab_index(item: &ItemRc) -> i32 {
+    if let Some(focus_scope) = ItemRef::downcast_pin::<FocusScope>(item.borrow()) {
+        focus_scope.tab_index()
+    } else if let Some(text_input) = ItemRef::downcast_pin::<TextInput>(item.borrow()) {
+        text_input.tab_index()
+    } else {
+        0
+    }
+}
+
+/// Returns the closest ancestor of `item` that is a `FocusScope` for which `predicate` returns true.
+pub fn enclosing_focus_scope(
+    item: &ItemRc,
+    predicate: impl Fn(Pin<&FocusScope>) -> bool,
+) -> Option<ItemRc> {
+    let mut ancestor = item.parent_item();
+    while let Some(current) = ancestor {
+        if ItemRef::downcast_pin::<FocusScope>(current.borrow()).map_or(false, &predicate) {
+            return Some(current);
+        }
+        ancestor = current.parent_item();
+    }
+    None
+}
+
+/// Returns true if `item` is within a `FocusScope` with the `skip` focus group policy, that
+/// is itself within `scope`.
+fn is_in_skipped_group(item: &ItemRc, scope: Option<&ItemRc>) -> bool {
+    let mut ancestor = item.parent_item();
+    while let Some(current) = ancestor {
+        if Some(&current) == scope {
+            return false;
+        }
+        if ItemRef::downcast_pin::<FocusScope>(current.borrow())
+            .map_or(false, |focus_scope| focus_scope.focus_group() == FocusGroupPolicy::skip)
+        {
+            return true;
+        }
+        ancestor = current.parent_item();
+    }
+    false
+}
+
+fn is_descendant(item: &ItemRc, ancestor: &ItemRc) -> bool {
+    let mut current = item.parent_item();
+    while let Some(parent) = current {
+        if &parent == ancestor {
+            return true;
+        }
+        current = parent.parent_item();
+    }
+    false
+}
+
+/// Returns the items to try to give the focus to, in that order, when moving the focus forward
+/// or backward from `current` with the keyboard.
+///
+/// The candidates are the descendants of `scope`, or all the items of the tree of `root` if `scope`
+/// is None. They are ordered by `tab-index`: first the items with a positive `tab-index`, in increasing
+/// order, then the items with a `tab-index` of 0, in the order of the item tree. The items with a negative
+/// `tab-index`, and the ones within a `FocusScope` with the `skip` focus group policy, are not candidates.
+pub fn focus_chain_candidates(
+    root: ItemRc,
+    scope: Option<&ItemRc>,
+    current: Option<&ItemRc>,
+    forward: bool,
+) -> Vec<ItemRc> {
+    // All the items in the order of the item tree
+    let mut items = Vec::new();
+    let start = scope.cloned().unwrap_or(root);
+    if scope.is_none() {
+        items.push(start.clone());
+    }
+    let mut item = start.next_focus_item();
+    while item != start && scope.map_or(true, |scope| is_descendant(&item, scope)) {
+        items.push(item.clone());
+        item = item.next_focus_item();
+    }
+
+    let order_key = |tab_index: i32, position: usize| {
+        if tab_index > 0 {
+            (0, tab_index, position)
+        } else {
+            (1, 0, position)
+        }
+    };
+
+    let mut chain = items
+        .iter()
+        .enumerate()
+        .filter_map(|(position, item)| {
+            let tab_index = tab_index(item);
+            (tab_index >= 0 && !is_in_skipped_group(item, scope))
+                .then(|| (order_key(tab_index, position), item.clone()))
+        })
+        .collect::<Vec<_>>();
+    chain.sort_by_key(|(key, _)| *key);
+
+    let current_key = current.and_then(|current| {
+        let position = items.iter().position(|item| item == current)?;
+        Some(order_key(tab_index(current), position))
+    });
+
+    // The index in chain of the first item after current
+    let split = match current_key {
+        Some(current_key) if forward => chain.partition_point(|(key, _)| *key <= current_key),
+        Some(current_key) => chain.partition_point(|(key, _)| *key < current_key),
+        None if forward => 0,
+        None => chain.len(),
+    };
+    let (before, after) = chain.split_at(split);
+    if forward {
+        after.iter().chain(before.iter()).map(|(_, item)| item.clone()).collect()
+    } else {
+        before.iter().rev().chain(after.iter().rev()).map(|(_, item)| item.clone()).collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
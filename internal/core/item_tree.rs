@@ -138,6 +138,29 @@ pub fn component(&self) -> vtable::VRc<ComponentVTable> {
         self.component.clone()
     }
 
+    /// Returns the accessible role of the item, `AccessibleRole::none` if it isn't accessible
+    pub fn accessible_role(&self) -> crate::accessibility::AccessibleRole {
+        let comp_ref_pin = vtable::VRc::borrow_pin(&self.component);
+        comp_ref_pin.as_ref().accessible_role(self.index)
+    }
+
+    /// Returns the value of an accessible property of the item, or an empty string if it isn't set
+    pub fn accessible_string_property(
+        &self,
+        what: crate::accessibility::AccessibleStringProperty,
+    ) -> crate::SharedString {
+        let comp_ref_pin = vtable::VRc::borrow_pin(&self.component);
+        let mut result = Default::default();
+        comp_ref_pin.as_ref().accessible_string_property(self.index, what, &mut result);
+        result
+    }
+
+    /// Performs an action requested by an assistive technology on the item
+    pub fn accessibility_action(&self, action: &crate::accessibility::AccessibilityAction) {
+        let comp_ref_pin = vtable::VRc::borrow_pin(&self.component);
+        comp_ref_pin.as_ref().accessibility_action(self.index, action);
+    }
+
     fn find_child(
         &self,
         child_access: &dyn Fn(&crate::item_tree::ComponentItemTree, usize) -> Option<usize>,
@@ -822,6 +845,7 @@ mod tests {
 
     use super::*;
 
+    use crate::accessibility::{AccessibilityAction, AccessibleRole, AccessibleStringProperty};
     use crate::component::{Component, ComponentRc, ComponentVTable, ComponentWeak, IndexRange};
     use crate::layout::{LayoutInfo, Orientation};
     use crate::slice::Slice;
@@ -867,6 +891,23 @@ fn layout_info(self: core::pin::Pin<&Self>, _1: Orientation) -> LayoutInfo {
             unimplemented!("Not needed for this test")
         }
 
+        fn accessible_role(self: core::pin::Pin<&Self>, _: usize) -> AccessibleRole {
+            unimplemented!("Not needed for this test")
+        }
+
+        fn accessible_string_property(
+            self: core::pin::Pin<&Self>,
+            _: usize,
+            _: AccessibleStringProperty,
+            _: &mut crate::SharedString,
+        ) {
+            unimplemented!("Not needed for this test")
+        }
+
+        fn accessibility_action(self: core::pin::Pin<&Self>, _: usize, _: &AccessibilityAction) {
+            unimplemented!("Not needed for this test")
+        }
+
         fn subtree_index(self: core::pin::Pin<&Self>) -> usize {
             self.subtree_index
         }
@@ -36,12 +36,22 @@
 use core::cell::Cell;
 use core::pin::Pin;
 use i_slint_core_macros::*;
+#[cfg(not(feature = "std"))]
+#[allow(unused)]
+use num_traits::Float;
 use vtable::*;
 
+mod custom;
+pub use custom::*;
 mod flickable;
 pub use flickable::*;
 mod text;
+mod text_history;
+mod text_password;
+mod text_search;
+mod text_validation;
 pub use text::*;
+pub use text_password::PasswordMask;
 mod image;
 pub use self::image::*;
 #[cfg(feature = "std")]
@@ -57,7 +67,10 @@
 pub type VoidArg = ();
 pub type KeyEventArg = (KeyEvent,);
 type PointerEventArg = (PointerEvent,);
+type ScrollEventArg = (ScrollEvent,);
 type PointArg = (Point,);
+type IntArg = (i32,);
+type StringArg = (SharedString,);
 
 #[cfg(all(feature = "ffi", windows))]
 #[macro_export]
@@ -334,9 +347,13 @@ pub struct TouchArea {
     pub mouse_x: Property<Coord>,
     pub mouse_y: Property<Coord>,
     pub mouse_cursor: Property<MouseCursor>,
+    pub exclusive_grab: Property<bool>,
     pub clicked: Callback<VoidArg>,
     pub moved: Callback<VoidArg>,
     pub pointer_event: Callback<PointerEventArg>,
+    pub capture_pointer_event: Callback<PointerEventArg, EventResult>,
+    pub bubble_pointer_event: Callback<PointerEventArg, EventResult>,
+    pub scroll_event: Callback<ScrollEventArg, EventResult>,
     /// FIXME: remove this
     pub cached_rendering_data: CachedRenderingData,
     /// true when we are currently grabbing the mouse
@@ -372,6 +389,23 @@ fn input_event_filter_before_children(
         if hovering {
             window.set_mouse_cursor(self.mouse_cursor());
         }
+        let pointer_event = match event {
+            MouseEvent::MousePressed { button, .. } => {
+                Some(PointerEvent { button, kind: PointerEventKind::down })
+            }
+            MouseEvent::MouseReleased { button, .. } => {
+                Some(PointerEvent { button, kind: PointerEventKind::up })
+            }
+            _ => None,
+        };
+        if let Some(pointer_event) = pointer_event {
+            // Capture phase: the TouchArea takes the event before its children
+            if Self::FIELD_OFFSETS.capture_pointer_event.apply_pin(self).call(&(pointer_event,))
+                == EventResult::accept
+            {
+                return InputEventFilterResult::Intercept;
+            }
+        }
         InputEventFilterResult::ForwardAndInterceptGrab
     }
 
@@ -388,6 +422,22 @@ fn input_event(
         if !self.enabled() {
             return InputEventResult::EventIgnored;
         }
+        if let MouseEvent::MousePressed { button, .. } = event {
+            // Bubble phase: without a handler, the TouchArea accepts all the presses
+            let bubble_pointer_event = Self::FIELD_OFFSETS.bubble_pointer_event.apply_pin(self);
+            if bubble_pointer_event.has_handler()
+                && bubble_pointer_event
+                    .call(&(PointerEvent { button, kind: PointerEventKind::down },))
+                    == EventResult::reject
+            {
+                return InputEventResult::EventIgnored;
+            }
+        }
+        let grab = if self.exclusive_grab() {
+            InputEventResult::GrabMouseExclusive
+        } else {
+            InputEventResult::GrabMouse
+        };
         let result = if let MouseEvent::MouseReleased { pos, button } = event {
             if button == PointerEventButton::left
                 && euclid::rect(0 as Coord, 0 as Coord, self.width(), self.height()).contains(pos)
@@ -396,7 +446,7 @@ fn input_event(
             }
             InputEventResult::EventAccepted
         } else {
-            InputEventResult::GrabMouse
+            grab
         };
 
         match event {
@@ -434,17 +484,25 @@ fn input_event(
             MouseEvent::MouseMoved { .. } => {
                 return if self.grabbed.get() {
                     Self::FIELD_OFFSETS.moved.apply_pin(self).call(&());
-                    InputEventResult::GrabMouse
+                    grab
                 } else {
                     InputEventResult::EventAccepted
                 }
             }
-            MouseEvent::MouseWheel { .. } => {
-                return if self.grabbed.get() {
-                    InputEventResult::GrabMouse
-                } else {
-                    InputEventResult::EventAccepted
+            MouseEvent::MouseWheel { delta, phase, .. } => {
+                if self.grabbed.get() {
+                    return grab;
                 }
+                // Without a handler, the event goes to the parents, for example a Flickable
+                let scroll_event = Self::FIELD_OFFSETS.scroll_event.apply_pin(self);
+                let event = ScrollEvent { delta_x: delta.x, delta_y: delta.y, phase };
+                return if scroll_event.has_handler()
+                    && scroll_event.call(&(event,)) == EventResult::accept
+                {
+                    InputEventResult::EventAccepted
+                } else {
+                    InputEventResult::EventIgnored
+                };
             }
         };
         result
@@ -478,6 +536,217 @@ impl ItemConsts for TouchArea {
     fn slint_get_TouchAreaVTable() -> TouchAreaVTable for TouchArea
 }
 
+#[repr(C)]
+#[derive(FieldOffsets, Default, SlintElement)]
+#[pin]
+/// The HoverArea Item is not meant to be used directly by the .slint code, instead, the compiler
+/// puts it around the elements that use `has-hover`, `pointer-entered` or `pointer-exited`.
+/// Unlike the TouchArea, it doesn't take part in the handling of the mouse events: the window
+/// updates all the HoverAreas under the mouse after each event, see [`crate::input::update_hover`].
+pub struct HoverArea {
+    pub x: Property<Coord>,
+    pub y: Property<Coord>,
+    pub width: Property<Coord>,
+    pub height: Property<Coord>,
+    pub has_hover: Property<bool>,
+    pub pointer_entered: Callback<VoidArg>,
+    pub pointer_exited: Callback<VoidArg>,
+    pub cached_rendering_data: CachedRenderingData,
+}
+
+impl Item for HoverArea {
+    fn init(self: Pin<&Self>, window: &WindowRc) {
+        window.enable_hover_tracking();
+    }
+
+    fn geometry(self: Pin<&Self>) -> Rect {
+        euclid::rect(self.x(), self.y(), self.width(), self.height())
+    }
+
+    fn layout_info(self: Pin<&Self>, _orientation: Orientation, _window: &WindowRc) -> LayoutInfo {
+        LayoutInfo { stretch: 1., ..LayoutInfo::default() }
+    }
+
+    fn input_event_filter_before_children(
+        self: Pin<&Self>,
+        _: MouseEvent,
+        _window: &WindowRc,
+        _self_rc: &ItemRc,
+    ) -> InputEventFilterResult {
+        InputEventFilterResult::ForwardAndIgnore
+    }
+
+    fn input_event(
+        self: Pin<&Self>,
+        _: MouseEvent,
+        _window: &WindowRc,
+        _self_rc: &ItemRc,
+    ) -> InputEventResult {
+        InputEventResult::EventIgnored
+    }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent, _window: &WindowRc) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
+
+    fn render(
+        self: Pin<&Self>,
+        _backend: &mut ItemRendererRef,
+        _self_rc: &ItemRc,
+    ) -> RenderingResult {
+        RenderingResult::ContinueRenderingChildren
+    }
+}
+
+impl HoverArea {
+    /// Sets `has-hover` and calls `pointer-entered` or `pointer-exited` if it changed.
+    pub(crate) fn set_hovered(self: Pin<&Self>, hovered: bool) {
+        if Self::FIELD_OFFSETS.has_hover.apply_pin(self).get_untracked() == hovered {
+            return;
+        }
+        Self::FIELD_OFFSETS.has_hover.apply_pin(self).set(hovered);
+        let callback = if hovered {
+            Self::FIELD_OFFSETS.pointer_entered.apply_pin(self)
+        } else {
+            Self::FIELD_OFFSETS.pointer_exited.apply_pin(self)
+        };
+        callback.call(&());
+    }
+}
+
+impl ItemConsts for HoverArea {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<
+        HoverArea,
+        CachedRenderingData,
+    > = HoverArea::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+declare_item_vtable! {
+    fn slint_get_HoverAreaVTable() -> HoverAreaVTable for HoverArea
+}
+
+/// The events of the life of a component, dispatched to the [`LifecycleHandler`] items
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub(crate) enum LifecycleEvent {
+    /// The window was shown
+    Shown,
+    /// The window was hidden
+    Hidden,
+    /// The component is about to be destroyed by a `for` or an `if`, or with its last handle
+    WillDestroy,
+}
+
+#[repr(C)]
+#[derive(FieldOffsets, Default, SlintElement)]
+#[pin]
+/// The LifecycleHandler Item is not meant to be used directly by the .slint code, instead, the compiler
+/// adds it as a child of the elements that use the `init`, `shown`, `hidden` or `will-destroy` callbacks.
+/// `init` is called by the setup code of the component, and the other callbacks by the runtime, see
+/// [`dispatch_lifecycle_event`].
+pub struct LifecycleHandler {
+    pub x: Property<Coord>,
+    pub y: Property<Coord>,
+    pub width: Property<Coord>,
+    pub height: Property<Coord>,
+    pub init: Callback<VoidArg>,
+    pub shown: Callback<VoidArg>,
+    pub hidden: Callback<VoidArg>,
+    pub will_destroy: Callback<VoidArg>,
+    pub cached_rendering_data: CachedRenderingData,
+}
+
+impl Item for LifecycleHandler {
+    fn init(self: Pin<&Self>, _window: &WindowRc) {}
+
+    fn geometry(self: Pin<&Self>) -> Rect {
+        euclid::rect(self.x(), self.y(), self.width(), self.height())
+    }
+
+    fn layout_info(self: Pin<&Self>, _orientation: Orientation, _window: &WindowRc) -> LayoutInfo {
+        LayoutInfo::default()
+    }
+
+    fn input_event_filter_before_children(
+        self: Pin<&Self>,
+        _: MouseEvent,
+        _window: &WindowRc,
+        _self_rc: &ItemRc,
+    ) -> InputEventFilterResult {
+        InputEventFilterResult::ForwardAndIgnore
+    }
+
+    fn input_event(
+        self: Pin<&Self>,
+        _: MouseEvent,
+        _window: &WindowRc,
+        _self_rc: &ItemRc,
+    ) -> InputEventResult {
+        InputEventResult::EventIgnored
+    }
+
+    fn key_event(self: Pin<&Self>, _: &KeyEvent, _window: &WindowRc) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    fn focus_event(self: Pin<&Self>, _: &FocusEvent, _window: &WindowRc) -> FocusEventResult {
+        FocusEventResult::FocusIgnored
+    }
+
+    fn render(
+        self: Pin<&Self>,
+        _backend: &mut ItemRendererRef,
+        _self_rc: &ItemRc,
+    ) -> RenderingResult {
+        RenderingResult::ContinueRenderingChildren
+    }
+}
+
+impl ItemConsts for LifecycleHandler {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<
+        LifecycleHandler,
+        CachedRenderingData,
+    > = LifecycleHandler::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+declare_item_vtable! {
+    fn slint_get_LifecycleHandlerVTable() -> LifecycleHandlerVTable for LifecycleHandler
+}
+
+/// Calls the callback of `event` on the [`LifecycleHandler`] items of the component, including the ones
+/// of the components instantiated by its repeaters.
+pub(crate) fn dispatch_lifecycle_event(
+    component: &crate::component::ComponentRc,
+    event: LifecycleEvent,
+) {
+    // The callbacks are called once the traversal is over, since they may change the item tree
+    let mut handlers = alloc::vec::Vec::new();
+    crate::item_tree::visit_items(
+        component,
+        crate::item_tree::TraversalOrder::BackToFront,
+        |component, item, index, _| {
+            if ItemRef::downcast_pin::<LifecycleHandler>(item).is_some() {
+                handlers.push(ItemRc::new(component.clone(), index));
+            }
+            crate::item_tree::ItemVisitorResult::Continue(())
+        },
+        (),
+    );
+    for handler in handlers {
+        if let Some(handler) = ItemRef::downcast_pin::<LifecycleHandler>(handler.borrow()) {
+            let callback = match event {
+                LifecycleEvent::Shown => LifecycleHandler::FIELD_OFFSETS.shown,
+                LifecycleEvent::Hidden => LifecycleHandler::FIELD_OFFSETS.hidden,
+                LifecycleEvent::WillDestroy => LifecycleHandler::FIELD_OFFSETS.will_destroy,
+            };
+            callback.apply_pin(handler).call(&());
+        }
+    }
+}
+
 /// A runtime item that exposes key
 #[repr(C)]
 #[derive(FieldOffsets, Default, SlintElement)]
@@ -489,6 +758,9 @@ pub struct FocusScope {
     pub height: Property<Coord>,
     pub enabled: Property<bool>,
     pub has_focus: Property<bool>,
+    pub tab_index: Property<i32>,
+    pub focus_group: Property<FocusGroupPolicy>,
+    pub arrow_navigation: Property<bool>,
     pub key_pressed: Callback<KeyEventArg, EventResult>,
     pub key_released: Callback<KeyEventArg, EventResult>,
     /// FIXME: remove this
@@ -535,6 +807,9 @@ fn key_event(self: Pin<&Self>, event: &KeyEvent, _window: &WindowRc) -> KeyEvent
             KeyEventType::KeyReleased => {
                 Self::FIELD_OFFSETS.key_released.apply_pin(self).call(&(event.clone(),))
             }
+            KeyEventType::UpdateComposition | KeyEventType::CommitComposition => {
+                EventResult::reject
+            }
         };
         match r {
             EventResult::accept => KeyEventResult::EventAccepted,
@@ -896,6 +1171,17 @@ impl ItemConsts for Rotate {
     > = Rotate::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
 }
 
+impl Rotate {
+    /// Maps a position in the coordinates of the Rotate to the coordinates of its children, which
+    /// are rendered rotated by `angle` around the origin.
+    pub(crate) fn map_to_children(self: Pin<&Self>, pos: Point) -> Point {
+        let origin = euclid::vec2(self.origin_x(), self.origin_y());
+        let (sin, cos) = (-self.angle()).to_radians().sin_cos();
+        let p = pos - origin;
+        Point::new(p.x * cos - p.y * sin, p.x * sin + p.y * cos) + origin
+    }
+}
+
 declare_item_vtable! {
     fn slint_get_RotateVTable() -> RotateVTable for Rotate
 }
@@ -1107,6 +1393,10 @@ impl ItemConsts for BoxShadow {
     fn slint_get_BoxShadowVTable() -> BoxShadowVTable for BoxShadow
 }
 
+declare_item_vtable! {
+    fn slint_get_CustomItemVTable() -> CustomItemVTable for CustomItem
+}
+
 declare_item_vtable! {
     fn slint_get_TextVTable() -> TextVTable for Text
 }
@@ -1158,3 +1448,15 @@ pub struct PointerEvent {
     pub button: PointerEventButton,
     pub kind: PointerEventKind,
 }
+
+/// Represents a scroll event, from a mouse wheel or a touchpad.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[repr(C)]
+pub struct ScrollEvent {
+    /// The amount of pixels to scroll horizontally. Positive when the content should move to the right.
+    pub delta_x: Coord,
+    /// The amount of pixels to scroll vertically. Positive when the content should move down.
+    pub delta_y: Coord,
+    /// The phase of the gesture on a touchpad, or `none` for a mouse wheel.
+    pub phase: ScrollEventPhase,
+}
@@ -0,0 +1,262 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+The `CustomItem` item, which delegates its layout, input handling and rendering to a
+[`CustomItemHandler`] implemented in Rust and registered with [`register_custom_item`].
+*/
+
+use super::{Item, ItemConsts, ItemRc, ItemRendererRef, RenderingResult};
+use crate::graphics::{Rect, Rgba8Pixel, SharedPixelBuffer};
+use crate::input::{
+    FocusEvent, FocusEventResult, InputEventFilterResult, InputEventResult, KeyEvent,
+    KeyEventResult, MouseEvent,
+};
+use crate::item_rendering::CachedRenderingData;
+use crate::layout::{LayoutInfo, Orientation};
+#[cfg(feature = "rtti")]
+use crate::rtti::*;
+use crate::window::WindowRc;
+use crate::{Coord, Property, SharedString};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use const_field_offset::FieldOffsets;
+use core::cell::RefCell;
+use core::pin::Pin;
+use i_slint_core_macros::*;
+
+/// The implementation of the content of a `CustomItem` element.
+///
+/// All the positions and sizes are in logical pixels and relative to the item, except in
+/// [`Self::render`].
+pub trait CustomItemHandler {
+    /// Returns the constraints of the item when it is in a layout. The default implementation
+    /// allows any size.
+    fn layout_info(&self, _orientation: Orientation) -> LayoutInfo {
+        LayoutInfo { stretch: 1., ..LayoutInfo::default() }
+    }
+
+    /// Handles a mouse or touch event within the item.
+    fn input_event(&self, _event: MouseEvent) -> InputEventResult {
+        InputEventResult::EventIgnored
+    }
+
+    /// Handles a key event. The item gets the focus when it is clicked or with its `focus()`
+    /// function.
+    fn key_event(&self, _event: &KeyEvent) -> KeyEventResult {
+        KeyEventResult::EventIgnored
+    }
+
+    /// Renders the content of the item into `buffer`, which has the size of the item in physical
+    /// pixels and is initially transparent. The pixels use premultiplied alpha.
+    ///
+    /// The result is cached until the size of the item changes or until
+    /// [`CustomItemHandle::request_redraw`] is called.
+    fn render(&self, buffer: &mut SharedPixelBuffer<Rgba8Pixel>, scale_factor: f32);
+}
+
+/// A handle given to the factory of a custom item, to notify the item that its content
+/// has changed.
+#[derive(Clone)]
+pub struct CustomItemHandle(Pin<Rc<Property<u32>>>);
+
+impl CustomItemHandle {
+    /// Schedules the item to be rendered again and its layout constraints to be queried again.
+    pub fn request_redraw(&self) {
+        self.0.set(self.0.as_ref().get().wrapping_add(1));
+    }
+}
+
+type CustomItemFactory = Rc<dyn Fn(CustomItemHandle) -> Rc<dyn CustomItemHandler>>;
+
+#[cfg(all(not(feature = "std"), feature = "unsafe_single_core"))]
+use crate::unsafe_single_core::thread_local;
+
+thread_local!(static CUSTOM_ITEM_FACTORIES : RefCell<BTreeMap<SharedString, CustomItemFactory>> = RefCell::default());
+
+/// Registers the factory for the `CustomItem` elements that have the given `type-name`.
+///
+/// The factory is called for each element when it is first used, and gets a handle that
+/// the handler can use to request the item to be rendered again.
+/// The factories are registered for the current thread.
+pub fn register_custom_item(
+    type_name: &str,
+    factory: impl Fn(CustomItemHandle) -> Rc<dyn CustomItemHandler> + 'static,
+) {
+    CUSTOM_ITEM_FACTORIES
+        .with(|factories| factories.borrow_mut().insert(type_name.into(), Rc::new(factory)));
+}
+
+/// The implementation of the `CustomItem` element
+#[repr(C)]
+#[derive(FieldOffsets, Default, SlintElement)]
+#[pin]
+pub struct CustomItem {
+    pub x: Property<Coord>,
+    pub y: Property<Coord>,
+    pub width: Property<Coord>,
+    pub height: Property<Coord>,
+    pub type_name: Property<SharedString>,
+    pub has_focus: Property<bool>,
+    data: CustomItemDataBox,
+    pub cached_rendering_data: CachedRenderingData,
+}
+
+impl CustomItem {
+    /// Returns the handler of the item, creating it if the `type-name` changed.
+    fn handler(self: Pin<&Self>) -> Option<Rc<dyn CustomItemHandler>> {
+        let type_name = self.type_name();
+        let mut handler = self.data.handler.borrow_mut();
+        if !matches!(&*handler, Some((name, _)) if *name == type_name) {
+            let factory = CUSTOM_ITEM_FACTORIES
+                .with(|factories| factories.borrow().get(&type_name).cloned())?;
+            let handle = CustomItemHandle(self.data.redraw_counter.clone());
+            *handler = Some((type_name, factory(handle)));
+        }
+        // Read the counter so that the bindings depending on the handler are re-evaluated
+        // when it requests it.
+        self.data.redraw_counter.as_ref().get();
+        handler.as_ref().map(|(_, handler)| handler.clone())
+    }
+}
+
+impl Item for CustomItem {
+    fn init(self: Pin<&Self>, _window: &WindowRc) {}
+
+    fn geometry(self: Pin<&Self>) -> Rect {
+        euclid::rect(self.x(), self.y(), self.width(), self.height())
+    }
+
+    fn layout_info(self: Pin<&Self>, orientation: Orientation, _window: &WindowRc) -> LayoutInfo {
+        self.handler().map_or_else(
+            || LayoutInfo { stretch: 1., ..LayoutInfo::default() },
+            |handler| handler.layout_info(orientation),
+        )
+    }
+
+    fn input_event_filter_before_children(
+        self: Pin<&Self>,
+        _: MouseEvent,
+        _window: &WindowRc,
+        _self_rc: &ItemRc,
+    ) -> InputEventFilterResult {
+        InputEventFilterResult::ForwardEvent
+    }
+
+    fn input_event(
+        self: Pin<&Self>,
+        event: MouseEvent,
+        window: &WindowRc,
+        self_rc: &ItemRc,
+    ) -> InputEventResult {
+        let handler = match self.handler() {
+            Some(handler) => handler,
+            None => return InputEventResult::EventIgnored,
+        };
+        if matches!(event, MouseEvent::MousePressed { .. }) && !self.has_focus() {
+            window.clone().set_focus_item(self_rc);
+        }
+        handler.input_event(event)
+    }
+
+    fn key_event(self: Pin<&Self>, event: &KeyEvent, _window: &WindowRc) -> KeyEventResult {
+        self.handler().map_or(KeyEventResult::EventIgnored, |handler| handler.key_event(event))
+    }
+
+    fn focus_event(self: Pin<&Self>, event: &FocusEvent, _window: &WindowRc) -> FocusEventResult {
+        match event {
+            FocusEvent::FocusIn | FocusEvent::WindowReceivedFocus => {
+                self.has_focus.set(true);
+            }
+            FocusEvent::FocusOut | FocusEvent::WindowLostFocus => {
+                self.has_focus.set(false);
+            }
+        }
+        FocusEventResult::FocusAccepted
+    }
+
+    fn render(
+        self: Pin<&Self>,
+        backend: &mut ItemRendererRef,
+        _self_rc: &ItemRc,
+    ) -> RenderingResult {
+        let scale_factor = (*backend).scale_factor();
+        (*backend).draw_cached_pixmap(&self.cached_rendering_data, &|callback| {
+            let handler = match self.handler() {
+                Some(handler) => handler,
+                None => return,
+            };
+            let width = (self.width() as f32 * scale_factor) as u32;
+            let height = (self.height() as f32 * scale_factor) as u32;
+            if width == 0 || height == 0 {
+                return;
+            }
+            let mut buffer = SharedPixelBuffer::<Rgba8Pixel>::new(width, height);
+            handler.render(&mut buffer, scale_factor);
+            callback(buffer.width(), buffer.height(), buffer.as_bytes());
+        });
+        RenderingResult::ContinueRenderingChildren
+    }
+}
+
+impl ItemConsts for CustomItem {
+    const cached_rendering_data_offset: const_field_offset::FieldOffset<Self, CachedRenderingData> =
+        Self::FIELD_OFFSETS.cached_rendering_data.as_unpinned_projection();
+}
+
+#[repr(C)]
+/// Wraps the internal data structure for the CustomItem
+pub struct CustomItemDataBox(core::ptr::NonNull<CustomItemData>);
+
+impl Default for CustomItemDataBox {
+    fn default() -> Self {
+        CustomItemDataBox(Box::leak(Box::new(CustomItemData::default())).into())
+    }
+}
+impl Drop for CustomItemDataBox {
+    fn drop(&mut self) {
+        // Safety: the self.0 was constructed from a Box::leak in CustomItemDataBox::default
+        unsafe {
+            Box::from_raw(self.0.as_ptr());
+        }
+    }
+}
+
+impl core::ops::Deref for CustomItemDataBox {
+    type Target = CustomItemData;
+    fn deref(&self) -> &Self::Target {
+        // Safety: initialized in CustomItemDataBox::default
+        unsafe { self.0.as_ref() }
+    }
+}
+
+pub struct CustomItemData {
+    /// The handler, and the type name it was created for
+    handler: RefCell<Option<(SharedString, Rc<dyn CustomItemHandler>)>>,
+    /// Incremented by the handler to request the item to be rendered again
+    redraw_counter: Pin<Rc<Property<u32>>>,
+}
+
+impl Default for CustomItemData {
+    fn default() -> Self {
+        Self { handler: Default::default(), redraw_counter: Rc::pin(Default::default()) }
+    }
+}
+
+/// # Safety
+/// This must be called using a non-null pointer pointing to a chunk of memory big enough to
+/// hold a CustomItemDataBox
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn slint_custom_item_data_init(data: *mut CustomItemDataBox) {
+    core::ptr::write(data, CustomItemDataBox::default());
+}
+
+/// # Safety
+/// This must be called using a non-null pointer pointing to an initialized CustomItemDataBox
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn slint_custom_item_data_free(data: *mut CustomItemDataBox) {
+    core::ptr::drop_in_place(data);
+}
@@ -9,7 +9,8 @@
 //! The `Flickable` item
 
 use super::{
-    Item, ItemConsts, ItemRc, ItemRendererRef, KeyEventResult, PointerEventButton, RenderingResult,
+    Item, ItemConsts, ItemRc, ItemRef, ItemRendererRef, KeyEventResult, OvershootBehavior,
+    PointerEventButton, RenderingResult, VoidArg,
 };
 use crate::animations::{EasingCurve, Instant};
 use crate::graphics::{Point, Rect};
@@ -23,10 +24,11 @@
 use crate::rtti::*;
 use crate::window::WindowRc;
 use crate::Coord;
-use crate::Property;
+use crate::{Callback, Property};
 use alloc::boxed::Box;
+use alloc::rc::Rc;
 use const_field_offset::FieldOffsets;
-use core::cell::RefCell;
+use core::cell::{Cell, RefCell};
 use core::pin::Pin;
 use core::time::Duration;
 #[allow(unused)]
@@ -47,6 +49,18 @@ pub struct Flickable {
     pub height: Property<Coord>,
     pub viewport: Rectangle,
     pub interactive: Property<bool>,
+    /// How fast the viewport slows down after it was flicked, in logical pixels per second squared
+    pub deceleration: Property<f32>,
+    pub overshoot: Property<OvershootBehavior>,
+    /// The distance by which the viewport is pulled beyond its edges, when `overshoot` is `glow`
+    pub overshoot_x: Property<Coord>,
+    pub overshoot_y: Property<Coord>,
+    /// Set to true while the viewport is moved by the user: dragged, flicked, or scrolled with the wheel
+    pub moving: Property<bool>,
+    /// Called when the viewport was moved by the user, once it stops for a flick
+    pub flicked: Callback<VoidArg>,
+    /// Called when the user releases the viewport after dragging it, before it is flicked
+    pub released: Callback<VoidArg>,
     data: FlickableDataBox,
 
     /// FIXME: remove this
@@ -54,7 +68,10 @@ pub struct Flickable {
 }
 
 impl Item for Flickable {
-    fn init(self: Pin<&Self>, _window: &WindowRc) {}
+    fn init(self: Pin<&Self>, _window: &WindowRc) {
+        let moving = self.data.moving.clone();
+        Self::FIELD_OFFSETS.moving.apply_pin(self).set_binding(move || moving.as_ref().get());
+    }
 
     fn geometry(self: Pin<&Self>) -> Rect {
         euclid::rect(self.x(), self.y(), self.width(), self.height())
@@ -68,7 +85,7 @@ fn input_event_filter_before_children(
         self: Pin<&Self>,
         event: MouseEvent,
         _window: &WindowRc,
-        _self_rc: &ItemRc,
+        self_rc: &ItemRc,
     ) -> InputEventFilterResult {
         if let Some(pos) = event.pos() {
             if pos.x < 0 as _ || pos.y < 0 as _ || pos.x > self.width() || pos.y > self.height() {
@@ -78,14 +95,14 @@ fn input_event_filter_before_children(
         if !self.interactive() && !matches!(event, MouseEvent::MouseWheel { .. }) {
             return InputEventFilterResult::ForwardAndIgnore;
         }
-        self.data.handle_mouse_filter(self, event)
+        self.data.handle_mouse_filter(self, event, self_rc)
     }
 
     fn input_event(
         self: Pin<&Self>,
         event: MouseEvent,
         _window: &WindowRc,
-        _self_rc: &ItemRc,
+        self_rc: &ItemRc,
     ) -> InputEventResult {
         if !self.interactive() && !matches!(event, MouseEvent::MouseWheel { .. }) {
             return InputEventResult::EventIgnored;
@@ -101,7 +118,7 @@ fn input_event(
             }
         }
 
-        self.data.handle_mouse(self, event)
+        self.data.handle_mouse(self, event, self_rc)
     }
 
     fn key_event(self: Pin<&Self>, _: &KeyEvent, _window: &WindowRc) -> KeyEventResult {
@@ -162,6 +179,21 @@ fn deref(&self) -> &Self::Target {
 const DISTANCE_THRESHOLD: Coord = 8 as _;
 /// Time required before we stop caring about child event if the mouse hasn't been moved
 const DURATION_THRESHOLD: Duration = Duration::from_millis(500);
+/// The viewport doesn't keep moving when the mouse is released if it hasn't been moved for that long
+const VELOCITY_TIMEOUT: Duration = Duration::from_millis(100);
+/// The maximum distance by which the viewport can be pulled beyond its edges, relative to the size of the Flickable
+const MAX_OVERSHOOT_RATIO: f32 = 0.25;
+/// The duration of the animation that brings the viewport back within its edges, in milliseconds
+const BOUNCE_BACK_DURATION: i32 = 300;
+/// The duration of the animation of `scroll-to`, in milliseconds
+const SCROLL_TO_DURATION: i32 = 250;
+/// How long the Flickable is `moving` after a wheel event, in milliseconds
+const WHEEL_MOVING_DURATION: i32 = 200;
+/// The curve of a motion with a constant deceleration until it stops (a quadratic ease-out)
+const DECELERATION_CURVE: EasingCurve = EasingCurve::CubicBezier([1. / 3., 2. / 3., 2. / 3., 1.]);
+/// Like an ease-out, but going a bit beyond the target before coming back to it
+const BOUNCE_CURVE: EasingCurve = EasingCurve::CubicBezier([0.175, 0.885, 0.32, 1.275]);
+const EASE_OUT_CURVE: EasingCurve = EasingCurve::CubicBezier([0.0, 0.0, 0.58, 1.0]);
 
 #[derive(Default, Debug)]
 struct FlickableDataInner {
@@ -171,32 +203,83 @@ struct FlickableDataInner {
     pressed_viewport_pos: Point,
     /// Set to true if the flickable is flicking and capturing all mouse event, not forwarding back to the children
     capture_events: bool,
+    /// The last position of the mouse while it is pressed, and when it was there
+    last_pos: Point,
+    last_time: Option<Instant>,
+    /// The velocity of the mouse, in logical pixels per millisecond
+    velocity: euclid::default::Vector2D<f32>,
 }
 
-#[derive(Default, Debug)]
+#[derive(Debug)]
 pub struct FlickableData {
     inner: RefCell<FlickableDataInner>,
+    /// The value of the `moving` property. It is shared with the timer that resets it.
+    moving: Pin<Rc<Property<bool>>>,
+    /// Incremented every time the viewport moves, so that a timer only resets `moving` if the
+    /// viewport didn't move since it was started
+    moving_generation: Rc<Cell<u32>>,
+}
+
+impl Default for FlickableData {
+    fn default() -> Self {
+        Self {
+            inner: Default::default(),
+            moving: Rc::pin(Default::default()),
+            moving_generation: Default::default(),
+        }
+    }
 }
 
 impl FlickableData {
+    /// Set `moving` to true, until [`Self::stop_moving()`] is called
+    fn start_moving(&self) {
+        self.moving_generation.set(self.moving_generation.get().wrapping_add(1));
+        self.moving.as_ref().set(true);
+    }
+
+    /// Set `moving` to false once `duration` milliseconds have elapsed, unless the viewport moves again.
+    /// If `flicked` is set, the `flicked` callback of that Flickable is called then, as the viewport
+    /// was animated until it stopped.
+    fn stop_moving(&self, duration: i32, flicked: Option<&ItemRc>) {
+        if !self.moving.as_ref().get_untracked() {
+            return;
+        }
+        if duration <= 0 {
+            self.moving.as_ref().set(false);
+            return;
+        }
+        let generation = self.moving_generation.get();
+        let moving_generation = self.moving_generation.clone();
+        let moving = self.moving.clone();
+        let flicked = flicked.map(|item| item.downgrade());
+        crate::timers::Timer::single_shot(Duration::from_millis(duration as u64), move || {
+            if moving_generation.get() != generation {
+                return;
+            }
+            moving.as_ref().set(false);
+            if let Some(self_rc) = flicked.and_then(|item| item.upgrade()) {
+                if let Some(flick) = ItemRef::downcast_pin::<Flickable>(self_rc.borrow()) {
+                    Flickable::FIELD_OFFSETS.flicked.apply_pin(flick).call(&());
+                }
+            }
+        });
+    }
+
     pub fn handle_mouse_filter(
         &self,
         flick: Pin<&Flickable>,
         event: MouseEvent,
+        self_rc: &ItemRc,
     ) -> InputEventFilterResult {
         let mut inner = self.inner.borrow_mut();
         match event {
             MouseEvent::MousePressed { pos, button: PointerEventButton::left } => {
                 inner.pressed_pos = pos;
                 inner.pressed_time = Some(crate::animations::current_tick());
-                inner.pressed_viewport_pos = Point::new(
-                    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
-                        .apply_pin(flick)
-                        .get(),
-                    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
-                        .apply_pin(flick)
-                        .get(),
-                );
+                inner.pressed_viewport_pos = viewport_pos(flick);
+                inner.last_pos = pos;
+                inner.last_time = inner.pressed_time;
+                inner.velocity = Default::default();
                 if inner.capture_events {
                     InputEventFilterResult::Intercept
                 } else {
@@ -206,7 +289,8 @@ pub fn handle_mouse_filter(
             MouseEvent::MouseExit
             | MouseEvent::MouseReleased { button: PointerEventButton::left, .. } => {
                 let was_capturing = inner.capture_events;
-                Self::mouse_released(&mut inner, flick, event);
+                let duration = Self::mouse_released(&mut inner, flick, event);
+                self.stop_moving(duration, Some(self_rc));
                 if was_capturing {
                     InputEventFilterResult::Intercept
                 } else {
@@ -219,16 +303,7 @@ pub fn handle_mouse_filter(
                         if crate::animations::current_tick() - pressed_time > DURATION_THRESHOLD {
                             return false;
                         }
-                        let can_move_horiz = (Flickable::FIELD_OFFSETS.viewport
-                            + Rectangle::FIELD_OFFSETS.width)
-                            .apply_pin(flick)
-                            .get()
-                            > flick.width();
-                        let can_move_vert = (Flickable::FIELD_OFFSETS.viewport
-                            + Rectangle::FIELD_OFFSETS.height)
-                            .apply_pin(flick)
-                            .get()
-                            > flick.height();
+                        let (can_move_horiz, can_move_vert) = can_move(flick);
                         let diff = pos - inner.pressed_pos;
                         (can_move_horiz && diff.x.abs() > DISTANCE_THRESHOLD)
                             || (can_move_vert && diff.y.abs() > DISTANCE_THRESHOLD)
@@ -241,7 +316,8 @@ pub fn handle_mouse_filter(
                     InputEventFilterResult::ForwardEvent
                 }
             }
-            MouseEvent::MouseWheel { .. } => InputEventFilterResult::Intercept,
+            // The children get the wheel events first, for example a nested Flickable
+            MouseEvent::MouseWheel { .. } => InputEventFilterResult::ForwardEvent,
             // Not the left button
             MouseEvent::MousePressed { .. } | MouseEvent::MouseReleased { .. } => {
                 InputEventFilterResult::ForwardAndIgnore
@@ -249,7 +325,12 @@ pub fn handle_mouse_filter(
         }
     }
 
-    pub fn handle_mouse(&self, flick: Pin<&Flickable>, event: MouseEvent) -> InputEventResult {
+    pub fn handle_mouse(
+        &self,
+        flick: Pin<&Flickable>,
+        event: MouseEvent,
+        self_rc: &ItemRc,
+    ) -> InputEventResult {
         let mut inner = self.inner.borrow_mut();
         match event {
             MouseEvent::MousePressed { .. } => {
@@ -257,22 +338,42 @@ pub fn handle_mouse(&self, flick: Pin<&Flickable>, event: MouseEvent) -> InputEv
                 InputEventResult::GrabMouse
             }
             MouseEvent::MouseExit | MouseEvent::MouseReleased { .. } => {
-                Self::mouse_released(&mut inner, flick, event);
+                let duration = Self::mouse_released(&mut inner, flick, event);
+                self.stop_moving(duration, Some(self_rc));
                 InputEventResult::EventAccepted
             }
             MouseEvent::MouseMoved { pos } => {
                 if inner.pressed_time.is_some() {
                     inner.capture_events = true;
-                    let new_pos = ensure_in_bound(
-                        flick,
-                        inner.pressed_viewport_pos + (pos - inner.pressed_pos),
-                    );
-                    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
-                        .apply_pin(flick)
-                        .set(new_pos.x);
-                    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
-                        .apply_pin(flick)
-                        .set(new_pos.y);
+                    Self::track_velocity(&mut inner, pos);
+                    let requested_pos = inner.pressed_viewport_pos + (pos - inner.pressed_pos);
+                    let new_pos = ensure_in_bound(flick, requested_pos);
+                    let excess = requested_pos - new_pos;
+                    let moved = requested_pos != inner.pressed_viewport_pos;
+                    if moved {
+                        self.start_moving();
+                    }
+                    let (can_move_horiz, can_move_vert) = can_move(flick);
+                    let overshoot_x =
+                        if can_move_horiz { rubber_band(excess.x, flick.width()) } else { 0 as _ };
+                    let overshoot_y =
+                        if can_move_vert { rubber_band(excess.y, flick.height()) } else { 0 as _ };
+                    match flick.overshoot() {
+                        OvershootBehavior::none => set_viewport_pos(flick, new_pos),
+                        OvershootBehavior::bounce => set_viewport_pos(
+                            flick,
+                            new_pos + euclid::vec2(overshoot_x, overshoot_y),
+                        ),
+                        OvershootBehavior::glow => {
+                            set_viewport_pos(flick, new_pos);
+                            Flickable::FIELD_OFFSETS.overshoot_x.apply_pin(flick).set(overshoot_x);
+                            Flickable::FIELD_OFFSETS.overshoot_y.apply_pin(flick).set(overshoot_y);
+                        }
+                    }
+                    drop(inner);
+                    if moved {
+                        Flickable::FIELD_OFFSETS.flicked.apply_pin(flick).call(&());
+                    }
                     InputEventResult::GrabMouse
                 } else {
                     inner.capture_events = false;
@@ -280,52 +381,173 @@ pub fn handle_mouse(&self, flick: Pin<&Flickable>, event: MouseEvent) -> InputEv
                 }
             }
             MouseEvent::MouseWheel { delta, .. } => {
-                let old_pos = Point::new(
-                    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
-                        .apply_pin(flick)
-                        .get(),
-                    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
-                        .apply_pin(flick)
-                        .get(),
-                );
+                let old_pos = viewport_pos(flick);
                 let new_pos = ensure_in_bound(flick, old_pos + delta.to_vector());
-                (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
-                    .apply_pin(flick)
-                    .set(new_pos.x);
-                (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
-                    .apply_pin(flick)
-                    .set(new_pos.y);
+                if new_pos == old_pos {
+                    // Already at the end: let a parent Flickable scroll instead
+                    return InputEventResult::EventIgnored;
+                }
+                set_viewport_pos(flick, new_pos);
+                self.start_moving();
+                drop(inner);
+                Flickable::FIELD_OFFSETS.flicked.apply_pin(flick).call(&());
+                self.stop_moving(WHEEL_MOVING_DURATION, None);
                 InputEventResult::EventAccepted
             }
         }
     }
 
-    fn mouse_released(inner: &mut FlickableDataInner, flick: Pin<&Flickable>, event: MouseEvent) {
-        if let (Some(pressed_time), Some(pos)) = (inner.pressed_time, event.pos()) {
-            let dist = (pos - inner.pressed_pos).cast::<f32>();
-            let speed =
-                dist / ((crate::animations::current_tick() - pressed_time).as_millis() as f32);
-
-            let duration = 100;
-            let final_pos = ensure_in_bound(
-                flick,
-                (inner.pressed_viewport_pos.cast() + dist + speed * (duration as f32)).cast(),
-            );
-            let anim = PropertyAnimation {
-                duration,
-                easing: EasingCurve::CubicBezier([0.0, 0.0, 0.58, 1.0]),
-                ..PropertyAnimation::default()
+    /// Record the velocity of the mouse since the previous move
+    fn track_velocity(inner: &mut FlickableDataInner, pos: Point) {
+        let now = crate::animations::current_tick();
+        if let Some(last_time) = inner.last_time {
+            let elapsed = (now - last_time).as_millis() as f32;
+            if elapsed > 0. {
+                inner.velocity = (pos - inner.last_pos).cast::<f32>() / elapsed;
+                inner.last_pos = pos;
+                inner.last_time = Some(now);
+            }
+        }
+    }
+
+    /// Returns how long the viewport keeps moving after the release, in milliseconds
+    fn mouse_released(
+        inner: &mut FlickableDataInner,
+        flick: Pin<&Flickable>,
+        event: MouseEvent,
+    ) -> i32 {
+        let mut duration = 0;
+        if inner.pressed_time.is_some() {
+            if inner.capture_events {
+                Flickable::FIELD_OFFSETS.released.apply_pin(flick).call(&());
+            }
+            let velocity = match inner.last_time {
+                Some(last_time)
+                    if event.pos().is_some()
+                        && crate::animations::current_tick() - last_time < VELOCITY_TIMEOUT =>
+                {
+                    inner.velocity
+                }
+                _ => Default::default(),
             };
-            (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
-                .apply_pin(flick)
-                .set_animated_value(final_pos.x, anim.clone());
-            (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
-                .apply_pin(flick)
-                .set_animated_value(final_pos.y, anim);
+            duration = fling(flick, velocity);
         }
         inner.capture_events = false; // FIXME: should only be set to false once the flick animation is over
         inner.pressed_time = None;
+        inner.last_time = None;
+        duration
+    }
+}
+
+/// Keep the viewport moving with the velocity it had when the mouse was released, until it stops or
+/// reaches the edges, and bring it back within its edges if it was pulled beyond them.
+/// Returns the duration of the longest animation, in milliseconds.
+fn fling(flick: Pin<&Flickable>, velocity: euclid::default::Vector2D<f32>) -> i32 {
+    let pos = viewport_pos(flick);
+    let min = ensure_in_bound(flick, Point::new(Coord::MIN, Coord::MIN));
+    let overshoot = flick.overshoot();
+    // in logical pixels per millisecond squared
+    let deceleration = flick.deceleration() / 1_000_000.;
+    let x = fling_axis(pos.x as f32, min.x as f32, velocity.x, deceleration, overshoot);
+    let y = fling_axis(pos.y as f32, min.y as f32, velocity.y, deceleration, overshoot);
+    let mut duration = 0;
+    if let Some((target, animation)) = x {
+        duration = duration.max(animation.duration);
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
+            .apply_pin(flick)
+            .set_animated_value(target as _, animation);
+    }
+    if let Some((target, animation)) = y {
+        duration = duration.max(animation.duration);
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
+            .apply_pin(flick)
+            .set_animated_value(target as _, animation);
     }
+
+    let back = PropertyAnimation {
+        duration: BOUNCE_BACK_DURATION,
+        easing: EASE_OUT_CURVE,
+        ..PropertyAnimation::default()
+    };
+    for overshoot in [Flickable::FIELD_OFFSETS.overshoot_x, Flickable::FIELD_OFFSETS.overshoot_y] {
+        let overshoot = overshoot.apply_pin(flick);
+        if overshoot.get() != 0 as Coord {
+            overshoot.set_animated_value(0 as _, back.clone());
+            duration = duration.max(back.duration);
+        }
+    }
+    duration
+}
+
+/// Compute where the viewport goes along one axis, where its position is between `min` and 0,
+/// and the animation to get there. Returns None if it stays where it is.
+fn fling_axis(
+    pos: f32,
+    min: f32,
+    velocity: f32,
+    deceleration: f32,
+    overshoot: OvershootBehavior,
+) -> Option<(f32, PropertyAnimation)> {
+    let animation = |duration: f32, easing: EasingCurve| PropertyAnimation {
+        duration: duration as i32,
+        easing,
+        ..PropertyAnimation::default()
+    };
+    let edge = pos.max(min).min(0.);
+    if edge != pos {
+        // The viewport was pulled beyond its edge
+        return Some((edge, animation(BOUNCE_BACK_DURATION as f32, EASE_OUT_CURVE)));
+    }
+    if velocity == 0. || deceleration <= 0. {
+        return None;
+    }
+    let duration = velocity.abs() / deceleration;
+    let target = pos + velocity * duration / 2.;
+    let edge = target.max(min).min(0.);
+    if edge == target {
+        return Some((target, animation(duration, DECELERATION_CURVE)));
+    }
+    // The time at which the viewport reaches the edge
+    let distance = (edge - pos).abs();
+    let remaining_speed = (velocity * velocity - 2. * deceleration * distance).max(0.).sqrt();
+    let duration = (velocity.abs() - remaining_speed) / deceleration;
+    match overshoot {
+        OvershootBehavior::bounce => {
+            Some((edge, animation(duration + BOUNCE_BACK_DURATION as f32, BOUNCE_CURVE)))
+        }
+        OvershootBehavior::none | OvershootBehavior::glow => {
+            Some((edge, animation(duration, EASE_OUT_CURVE)))
+        }
+    }
+}
+
+/// The distance by which the viewport moves beyond its edge when it is pulled by `excess`:
+/// the further it is pulled, the less it moves.
+fn rubber_band(excess: Coord, size: Coord) -> Coord {
+    let max = size as f32 * MAX_OVERSHOOT_RATIO;
+    (excess as f32 / 2.).max(-max).min(max) as _
+}
+
+/// Whether the viewport is larger than the Flickable, horizontally and vertically
+fn can_move(flick: Pin<&Flickable>) -> (bool, bool) {
+    let vw =
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.width).apply_pin(flick).get();
+    let vh = (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.height)
+        .apply_pin(flick)
+        .get();
+    (vw > flick.width(), vh > flick.height())
+}
+
+fn viewport_pos(flick: Pin<&Flickable>) -> Point {
+    Point::new(
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x).apply_pin(flick).get(),
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y).apply_pin(flick).get(),
+    )
+}
+
+fn set_viewport_pos(flick: Pin<&Flickable>, pos: Point) {
+    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x).apply_pin(flick).set(pos.x);
+    (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y).apply_pin(flick).set(pos.y);
 }
 
 /// Make sure that the point is within the bounds
@@ -343,6 +565,75 @@ fn ensure_in_bound(flick: Pin<&Flickable>, p: Point) -> Point {
     p.max(min).min(max)
 }
 
+/// Scroll the viewport of the Flickable so that the position `(x, y)` of the viewport is
+/// at the top left of the Flickable. This implements the `scroll-to` function.
+pub fn flickable_scroll_to(flickable: &ItemRc, x: Coord, y: Coord, animated: bool) {
+    let flickable = flickable.borrow();
+    let flick = match ItemRef::downcast_pin::<Flickable>(flickable) {
+        Some(flick) => flick,
+        None => return,
+    };
+    let new_pos = ensure_in_bound(flick, Point::new(-x, -y));
+    if animated {
+        let animation = PropertyAnimation {
+            duration: SCROLL_TO_DURATION,
+            easing: EASE_OUT_CURVE,
+            ..PropertyAnimation::default()
+        };
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.x)
+            .apply_pin(flick)
+            .set_animated_value(new_pos.x, animation.clone());
+        (Flickable::FIELD_OFFSETS.viewport + Rectangle::FIELD_OFFSETS.y)
+            .apply_pin(flick)
+            .set_animated_value(new_pos.y, animation);
+    } else {
+        set_viewport_pos(flick, new_pos);
+    }
+}
+
+/// Scroll the viewport of the Flickable by the smallest distance so that the target item, which
+/// must be within the Flickable, is visible. This implements the `ensure-visible` function.
+pub fn flickable_ensure_visible(flickable: &ItemRc, target: &ItemRc) {
+    let flickable_ref = flickable.borrow();
+    let flick = match ItemRef::downcast_pin::<Flickable>(flickable_ref) {
+        Some(flick) => flick,
+        None => return,
+    };
+    // Compute the geometry of the target relative to the viewport, which is the child of the
+    // Flickable in the item tree.
+    let mut rect = target.borrow().as_ref().geometry();
+    let mut item = match target.parent_item() {
+        Some(item) if item != *flickable => item,
+        _ => return,
+    };
+    loop {
+        match item.parent_item() {
+            Some(parent) if parent == *flickable => break,
+            Some(parent) => {
+                rect.origin += item.borrow().as_ref().geometry().origin.to_vector();
+                item = parent;
+            }
+            // The target is not in this Flickable
+            None => return,
+        }
+    }
+
+    let mut new_pos = viewport_pos(flick);
+    if rect.max_x() + new_pos.x > flick.width() {
+        new_pos.x = flick.width() - rect.max_x();
+    }
+    if rect.min_x() + new_pos.x < 0 as Coord {
+        new_pos.x = -rect.min_x();
+    }
+    if rect.max_y() + new_pos.y > flick.height() {
+        new_pos.y = flick.height() - rect.max_y();
+    }
+    if rect.min_y() + new_pos.y < 0 as Coord {
+        new_pos.y = -rect.min_y();
+    }
+    set_viewport_pos(flick, ensure_in_bound(flick, new_pos));
+}
+
 /// # Safety
 /// This must be called using a non-null pointer pointing to a chunk of memory big enough to
 /// hold a FlickableDataBox
@@ -359,3 +650,20 @@ fn ensure_in_bound(flick: Pin<&Flickable>, p: Point) -> Point {
 pub unsafe extern "C" fn slint_flickable_data_free(data: *mut FlickableDataBox) {
     core::ptr::drop_in_place(data);
 }
+
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn slint_flickable_scroll_to(
+    flickable: &ItemRc,
+    x: Coord,
+    y: Coord,
+    animated: bool,
+) {
+    flickable_scroll_to(flickable, x, y, animated)
+}
+
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn slint_flickable_ensure_visible(flickable: &ItemRc, target: &ItemRc) {
+    flickable_ensure_visible(flickable, target)
+}
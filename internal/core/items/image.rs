@@ -8,7 +8,7 @@
 Lookup the [`crate::items`] module documentation.
 */
 use super::{ImageFit, ImageRendering, Item, ItemConsts, ItemRc, RenderingResult};
-use crate::graphics::Rect;
+use crate::graphics::{IntRect, Rect};
 use crate::input::{
     FocusEvent, FocusEventResult, InputEventFilterResult, InputEventResult, KeyEvent,
     KeyEventResult, MouseEvent,
@@ -120,9 +120,54 @@ pub struct ClippedImage {
     pub source_clip_y: Property<i32>,
     pub source_clip_width: Property<i32>,
     pub source_clip_height: Property<i32>,
+    pub frame_width: Property<i32>,
+    pub frame_height: Property<i32>,
+    pub frame_count: Property<i32>,
+    pub frames_per_second: Property<f32>,
     pub cached_rendering_data: CachedRenderingData,
 }
 
+impl ClippedImage {
+    /// Returns the rectangle of the source image to draw, in source image coordinates. When the
+    /// image is a sprite sheet, this is the rectangle of the current frame within the source clip,
+    /// and the frames are laid out from left to right, then from top to bottom.
+    /// An empty size means that the whole image is drawn.
+    pub fn source_clip(self: Pin<&Self>) -> IntRect {
+        let source_clip = IntRect::new(
+            [self.source_clip_x(), self.source_clip_y()].into(),
+            [self.source_clip_width(), self.source_clip_height()].into(),
+        );
+        let frame_size = euclid::size2(self.frame_width(), self.frame_height());
+        let frame_count = self.frame_count();
+        if frame_size.is_empty() || frame_count <= 0 {
+            return source_clip;
+        }
+
+        let sheet_width = if source_clip.width() > 0 {
+            source_clip.width()
+        } else {
+            self.source().size().width as i32 - source_clip.min_x()
+        };
+        let columns = (sheet_width / frame_size.width).max(1);
+        let frames_per_second = self.frames_per_second();
+        let frame = if frames_per_second > 0. && frame_count > 1 {
+            let elapsed_frames =
+                crate::animations::animation_tick() as f64 * frames_per_second as f64 / 1000.;
+            (elapsed_frames as u64 % frame_count as u64) as i32
+        } else {
+            0
+        };
+        IntRect::new(
+            source_clip.origin
+                + euclid::vec2(
+                    frame % columns * frame_size.width,
+                    frame / columns * frame_size.height,
+                ),
+            frame_size,
+        )
+    }
+}
+
 impl Item for ClippedImage {
     fn init(self: Pin<&Self>, _window: &WindowRc) {}
 
@@ -131,7 +176,12 @@ fn geometry(self: Pin<&Self>) -> Rect {
     }
 
     fn layout_info(self: Pin<&Self>, orientation: Orientation, _window: &WindowRc) -> LayoutInfo {
-        let natural_size = self.source().size();
+        // The natural size of a sprite sheet is the size of a frame
+        let natural_size = if self.frame_width() > 0 && self.frame_height() > 0 {
+            crate::graphics::IntSize::new(self.frame_width() as _, self.frame_height() as _)
+        } else {
+            self.source().size()
+        };
         LayoutInfo {
             preferred: match orientation {
                 _ if natural_size.width == 0 || natural_size.height == 0 => 0 as Coord,
@@ -8,10 +8,14 @@
 Lookup the [`crate::items`] module documentation.
 */
 
+use super::text_history::{TextEditHistory, TextEditKind};
+use super::text_password::PasswordMask;
+use super::text_search::{find_matches, SearchOptions};
+use super::text_validation::{insert_mask_literals, is_partial_number, matches_mask};
 use super::{
-    InputType, Item, ItemConsts, ItemRc, KeyEventResult, KeyEventType, PointArg,
-    PointerEventButton, RenderingResult, TextHorizontalAlignment, TextOverflow,
-    TextVerticalAlignment, TextWrap, VoidArg,
+    InputType, IntArg, Item, ItemConsts, ItemRc, ItemRef, KeyEventResult, KeyEventType,
+    PasswordEcho, PointArg, PointerEventButton, RenderingResult, StringArg,
+    TextHorizontalAlignment, TextOverflow, TextVerticalAlignment, TextWrap, VoidArg,
 };
 use crate::graphics::{Brush, Color, FontRequest, Rect};
 use crate::input::{
@@ -24,14 +28,21 @@
 use crate::rtti::*;
 use crate::window::WindowRc;
 use crate::{Callback, Coord, Property, SharedString};
+use alloc::boxed::Box;
+use alloc::rc::Rc;
 use alloc::string::String;
+use alloc::vec::Vec;
 use const_field_offset::FieldOffsets;
+use core::cell::RefCell;
 use core::pin::Pin;
 #[allow(unused)]
 use euclid::num::Ceil;
 use i_slint_core_macros::*;
 use unicode_segmentation::UnicodeSegmentation;
 
+/// How long the last typed character of a password is shown, see `PasswordEcho::last_character`
+const PASSWORD_ECHO_DURATION: core::time::Duration = core::time::Duration::from_millis(1500);
+
 /// The implementation of the `Text` element
 #[repr(C)]
 #[derive(FieldOffsets, Default, SlintElement)]
@@ -46,7 +57,9 @@ pub struct Text {
     pub vertical_alignment: Property<TextVerticalAlignment>,
     pub wrap: Property<TextWrap>,
     pub overflow: Property<TextOverflow>,
+    pub ellipsis: Property<SharedString>,
     pub letter_spacing: Property<Coord>,
+    pub paragraph_spacing: Property<Coord>,
     pub x: Property<Coord>,
     pub y: Property<Coord>,
     pub width: Property<Coord>,
@@ -73,12 +86,18 @@ fn layout_info(self: Pin<&Self>, orientation: Orientation, window: &WindowRc) ->
             Orientation::Horizontal => {
                 let implicit_size = implicit_size(None);
                 let min = match self.overflow() {
-                    TextOverflow::elide => implicit_size
-                        .width
-                        .min(window.text_size(self.unresolved_font_request(), "…", None).width),
+                    TextOverflow::elide => implicit_size.width.min(
+                        window
+                            .text_size(self.unresolved_font_request(), &self.ellipsis(), None)
+                            .width,
+                    ),
+                    // The text can be wrapped until its widest word doesn't fit anymore
                     TextOverflow::clip => match self.wrap() {
                         TextWrap::no_wrap => implicit_size.width,
-                        TextWrap::word_wrap => 0 as _,
+                        TextWrap::word_wrap => window.text_min_content_width(
+                            self.unresolved_font_request(),
+                            self.text().as_str(),
+                        ),
                     },
                 };
                 LayoutInfo {
@@ -91,8 +110,10 @@ fn layout_info(self: Pin<&Self>, orientation: Orientation, window: &WindowRc) ->
                 let h = match self.wrap() {
                     TextWrap::no_wrap => implicit_size(None).height,
                     TextWrap::word_wrap => implicit_size(Some(self.width())).height,
-                }
-                .ceil();
+                };
+                let paragraph_spacing = self.paragraph_spacing()
+                    * crate::textlayout::paragraph_break_count(self.text().as_str()) as Coord;
+                let h = (h + paragraph_spacing).ceil();
                 LayoutInfo { min: h, preferred: h, ..LayoutInfo::default() }
             }
         }
@@ -171,6 +192,55 @@ pub fn unresolved_font_request(self: Pin<&Self>) -> FontRequest {
     }
 }
 
+/// The attributes of a range of the text of a `TextInput`, used to highlight parts of the text
+#[repr(C)]
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TextSpan {
+    /// The byte offset of the start of the range
+    pub start: i32,
+    /// The byte offset of the end of the range (excluded)
+    pub end: i32,
+    /// The color of the text in the range. When transparent, the color of the `TextInput` is used.
+    pub color: Color,
+    /// The color drawn behind the text in the range
+    pub background: Color,
+    /// The weight of the font, or 0 to keep the weight of the `TextInput`
+    pub font_weight: i32,
+    /// Whether the text in the range is underlined
+    pub underline: bool,
+}
+
+impl TextSpan {
+    /// Returns the attributes of the text at the byte offset `pos`, combining all the spans that contain it.
+    /// The attributes set by a span override the ones of the previous spans.
+    pub fn attributes_at(spans: &[TextSpan], pos: usize) -> Option<TextSpan> {
+        spans.iter().filter(|span| (span.start as usize..span.end as usize).contains(&pos)).fold(
+            None,
+            |attributes, span| {
+                Some(match attributes {
+                    None => span.clone(),
+                    Some(attributes) => TextSpan {
+                        start: span.start,
+                        end: span.end,
+                        color: if span.color.alpha() > 0 { span.color } else { attributes.color },
+                        background: if span.background.alpha() > 0 {
+                            span.background
+                        } else {
+                            attributes.background
+                        },
+                        font_weight: if span.font_weight > 0 {
+                            span.font_weight
+                        } else {
+                            attributes.font_weight
+                        },
+                        underline: span.underline || attributes.underline,
+                    },
+                })
+            },
+        )
+    }
+}
+
 /// The implementation of the `TextInput` element
 #[repr(C)]
 #[derive(FieldOffsets, Default, SlintElement)]
@@ -187,6 +257,9 @@ pub struct TextInput {
     pub vertical_alignment: Property<TextVerticalAlignment>,
     pub wrap: Property<TextWrap>,
     pub input_type: Property<InputType>,
+    pub input_mask: Property<SharedString>,
+    pub password_echo: Property<PasswordEcho>,
+    pub password_revealed: Property<bool>,
     pub letter_spacing: Property<Coord>,
     pub x: Property<Coord>,
     pub y: Property<Coord>,
@@ -196,17 +269,30 @@ pub struct TextInput {
     pub anchor_position: Property<i32>, // byte offset
     pub text_cursor_width: Property<Coord>,
     pub cursor_visible: Property<bool>,
+    pub cursor_blinking: Property<bool>,
+    pub cursor_blink_interval: Property<i64>,
     pub has_focus: Property<bool>,
+    pub tab_index: Property<i32>,
     pub enabled: Property<bool>,
     pub accepted: Callback<VoidArg>,
     pub cursor_position_changed: Callback<PointArg>,
     pub edited: Callback<VoidArg>,
+    pub validate: Callback<StringArg, bool>,
     pub pressed: core::cell::Cell<bool>,
     pub single_line: Property<bool>,
+    pub can_undo: Property<bool>,
+    pub can_redo: Property<bool>,
+    pub text_span_count: Property<i32>,
+    pub text_span: Callback<IntArg, TextSpan>,
+    pub search_text: Property<SharedString>,
+    pub search_case_sensitive: Property<bool>,
+    pub search_whole_words: Property<bool>,
+    pub search_highlight_color: Property<Color>,
     pub cached_rendering_data: CachedRenderingData,
     // The x position where the cursor wants to be.
     // It is not updated when moving up and down even when the line is shorter.
     preferred_x_pos: core::cell::Cell<Coord>,
+    data: TextInputDataBox,
 }
 
 impl Item for TextInput {
@@ -281,6 +367,7 @@ fn input_event(
         match event {
             MouseEvent::MousePressed { pos, button: PointerEventButton::left } => {
                 let clicked_offset = window.text_input_byte_offset_for_position(self, pos) as i32;
+                self.data.history.borrow_mut().close_group();
                 self.as_ref().pressed.set(true);
                 self.as_ref().anchor_position.set(clicked_offset);
                 self.set_cursor_position(clicked_offset, true, window);
@@ -312,6 +399,7 @@ fn key_event(self: Pin<&Self>, event: &KeyEvent, window: &WindowRc) -> KeyEventR
                 match event.text_shortcut() {
                     Some(text_shortcut) => match text_shortcut {
                         TextShortcut::Move(direction) => {
+                            self.data.history.borrow_mut().close_group();
                             TextInput::move_cursor(self, direction, event.modifiers.into(), window);
                             return KeyEventResult::EventAccepted;
                         }
@@ -323,6 +411,22 @@ fn key_event(self: Pin<&Self>, event: &KeyEvent, window: &WindowRc) -> KeyEventR
                             TextInput::delete_previous(self, window);
                             return KeyEventResult::EventAccepted;
                         }
+                        TextShortcut::DeleteWordForward => {
+                            TextInput::delete_word(
+                                self,
+                                TextCursorDirection::ForwardByWord,
+                                window,
+                            );
+                            return KeyEventResult::EventAccepted;
+                        }
+                        TextShortcut::DeleteWordBackward => {
+                            TextInput::delete_word(
+                                self,
+                                TextCursorDirection::BackwardByWord,
+                                window,
+                            );
+                            return KeyEventResult::EventAccepted;
+                        }
                     },
                     None => (),
                 };
@@ -358,8 +462,19 @@ fn key_event(self: Pin<&Self>, event: &KeyEvent, window: &WindowRc) -> KeyEventR
                             return KeyEventResult::EventAccepted;
                         }
                         StandardShortcut::Cut => {
-                            self.copy();
-                            self.delete_selection(window);
+                            // Cutting a hidden password would only delete it
+                            if !self.hides_text() {
+                                self.copy();
+                                self.delete_selection(window);
+                            }
+                            return KeyEventResult::EventAccepted;
+                        }
+                        StandardShortcut::Undo => {
+                            self.undo(window);
+                            return KeyEventResult::EventAccepted;
+                        }
+                        StandardShortcut::Redo => {
+                            self.redo(window);
                             return KeyEventResult::EventAccepted;
                         }
                         _ => (),
@@ -369,25 +484,25 @@ fn key_event(self: Pin<&Self>, event: &KeyEvent, window: &WindowRc) -> KeyEventR
                 if event.modifiers.control {
                     return KeyEventResult::EventIgnored;
                 }
-                self.delete_selection(window);
-
-                let mut text: String = self.text().into();
 
                 // FIXME: respect grapheme boundaries
-                let insert_pos = self.selection_anchor_and_cursor().1;
-                text.insert_str(insert_pos, &event.text);
-
-                self.as_ref().text.set(text.into());
-                let new_cursor_pos = (insert_pos + event.text.len()) as i32;
-                self.as_ref().anchor_position.set(new_cursor_pos);
-                self.set_cursor_position(new_cursor_pos, true, window);
+                self.replace_selection(&event.text, TextEditKind::Typing, window);
 
                 // Keep the cursor visible when inserting text. Blinking should only occur when
                 // nothing is entered or the cursor isn't moved.
                 self.as_ref().show_cursor(window);
 
-                Self::FIELD_OFFSETS.edited.apply_pin(self).call(&());
-
+                KeyEventResult::EventAccepted
+            }
+            KeyEventType::UpdateComposition => {
+                self.data.preedit_text.set(event.text.clone());
+                self.as_ref().show_cursor(window);
+                KeyEventResult::EventAccepted
+            }
+            KeyEventType::CommitComposition => {
+                self.data.preedit_text.set(Default::default());
+                self.insert(&event.text, window);
+                self.as_ref().show_cursor(window);
                 KeyEventResult::EventAccepted
             }
             _ => KeyEventResult::EventIgnored,
@@ -402,8 +517,12 @@ fn focus_event(self: Pin<&Self>, event: &FocusEvent, window: &WindowRc) -> Focus
                 window.show_virtual_keyboard(self.input_type());
             }
             FocusEvent::FocusOut | FocusEvent::WindowLostFocus => {
+                self.data.history.borrow_mut().close_group();
+                // The input method drops the composition when the focus leaves
+                self.data.preedit_text.set(Default::default());
+                self.data.password_echo.as_ref().set(None);
                 self.has_focus.set(false);
-                self.hide_cursor();
+                self.hide_cursor(window);
                 window.hide_virtual_keyboard();
             }
         }
@@ -478,12 +597,30 @@ fn from(modifiers: KeyboardModifiers) -> Self {
 }
 
 impl TextInput {
-    fn show_cursor(&self, window: &WindowRc) {
-        window.set_cursor_blink_binding(&self.cursor_visible);
+    pub(crate) fn show_cursor(self: Pin<&Self>, window: &WindowRc) {
+        window.set_cursor_blink_binding(&self.cursor_visible, || {
+            self.effective_cursor_blink_interval()
+        });
     }
 
-    fn hide_cursor(&self) {
+    fn hide_cursor(&self, window: &WindowRc) {
         self.cursor_visible.set(false);
+        window.stop_cursor_blinking();
+    }
+
+    /// Returns the time between two toggles of the cursor, or None if it doesn't blink: the
+    /// `cursor-blink-interval` if it's set, or else the setting of the platform.
+    pub fn effective_cursor_blink_interval(self: Pin<&Self>) -> Option<core::time::Duration> {
+        if !self.cursor_blinking() {
+            return None;
+        }
+        match self.cursor_blink_interval() {
+            interval if interval > 0 => Some(core::time::Duration::from_millis(interval as u64)),
+            _ => crate::backend::instance()
+                .map_or(Some(crate::input::DEFAULT_CURSOR_BLINK_INTERVAL), |backend| {
+                    backend.cursor_blink_interval()
+                }),
+        }
     }
 
     fn move_cursor(
@@ -633,10 +770,13 @@ fn set_cursor_position(
     }
 
     fn delete_char(self: Pin<&Self>, window: &WindowRc) {
-        if !self.has_selection() {
-            self.move_cursor(TextCursorDirection::Forward, AnchorMode::KeepAnchor, window);
+        if self.has_selection() {
+            self.delete_selection(window);
+            return;
         }
-        self.delete_selection(window);
+        let selection_before = self.selection();
+        self.move_cursor(TextCursorDirection::Forward, AnchorMode::KeepAnchor, window);
+        self.replace_text("", TextEditKind::DeleteForward, selection_before, window);
     }
 
     fn delete_previous(self: Pin<&Self>, window: &WindowRc) {
@@ -644,30 +784,205 @@ fn delete_previous(self: Pin<&Self>, window: &WindowRc) {
             self.delete_selection(window);
             return;
         }
-        if self.move_cursor(TextCursorDirection::PreviousCharacter, AnchorMode::MoveAnchor, window)
+        let selection_before = self.selection();
+        if self.move_cursor(TextCursorDirection::PreviousCharacter, AnchorMode::KeepAnchor, window)
         {
-            self.delete_char(window);
+            self.replace_text("", TextEditKind::DeleteBackward, selection_before, window);
         }
     }
 
-    fn delete_selection(self: Pin<&Self>, window: &WindowRc) {
-        let text: String = self.text().into();
-        if text.is_empty() {
+    /// Delete from the cursor to the end of the next word, or to the start of the previous word
+    fn delete_word(self: Pin<&Self>, direction: TextCursorDirection, window: &WindowRc) {
+        if self.has_selection() {
+            self.delete_selection(window);
             return;
         }
+        let selection_before = self.selection();
+        self.move_cursor(direction, AnchorMode::KeepAnchor, window);
+        self.replace_text("", TextEditKind::Other, selection_before, window);
+    }
 
+    fn delete_selection(self: Pin<&Self>, window: &WindowRc) {
+        self.replace_selection("", TextEditKind::Other, window);
+    }
+
+    fn selection(self: Pin<&Self>) -> (i32, i32) {
+        (self.anchor_position(), self.cursor_position())
+    }
+
+    fn replace_selection(
+        self: Pin<&Self>,
+        replacement: &str,
+        kind: TextEditKind,
+        window: &WindowRc,
+    ) {
+        self.replace_text(replacement, kind, self.selection(), window)
+    }
+
+    /// Replace the selected text, record the change in the undo history, and place the cursor after
+    /// the inserted text. All the changes of the text made by the user go through this function.
+    /// `selection_before` is the selection restored when the change is undone.
+    fn replace_text(
+        self: Pin<&Self>,
+        replacement: &str,
+        kind: TextEditKind,
+        selection_before: (i32, i32),
+        window: &WindowRc,
+    ) {
         let (anchor, cursor) = self.selection_anchor_and_cursor();
-        if anchor == cursor {
+        if anchor == cursor && replacement.is_empty() {
             return;
         }
-
-        let text = [text.split_at(anchor).0, text.split_at(cursor).1].concat();
-        self.text.set(text.into());
-        self.anchor_position.set(anchor as i32);
-        self.set_cursor_position(anchor as i32, true, window);
+        let mut text: String = self.text().into();
+        let replacement = match self.validate_edit(&text, anchor..cursor, replacement) {
+            Some(replacement) => replacement,
+            None => return,
+        };
+        let removed = text[anchor..cursor].into();
+        text.replace_range(anchor..cursor, &replacement);
+        let text = SharedString::from(text);
+
+        let mut history = self.data.history.borrow_mut();
+        if matches!(self.input_type(), InputType::password) {
+            // Don't keep the plain text of a password in the undo stack
+            *history = Default::default();
+        } else {
+            history.sync_with(&self.text());
+            history.record(
+                anchor,
+                removed,
+                replacement.as_str().into(),
+                selection_before,
+                kind,
+                text.clone(),
+            );
+        }
+        drop(history);
+        self.update_password_echo(anchor, &replacement, kind);
+
+        self.text.set(text);
+        let new_cursor_pos = (anchor + replacement.len()) as i32;
+        self.anchor_position.set(new_cursor_pos);
+        self.set_cursor_position(new_cursor_pos, true, window);
+        self.update_undo_state();
         Self::FIELD_OFFSETS.edited.apply_pin(self).call(&());
     }
 
+    /// Returns the text replacing the `range` of `text`, adapted to the `input-type` and the
+    /// `input-mask`, or None if the edit is rejected because the new text would not be valid for
+    /// them, or because the `validate` callback returned false.
+    fn validate_edit(
+        self: Pin<&Self>,
+        text: &str,
+        range: core::ops::Range<usize>,
+        replacement: &str,
+    ) -> Option<String> {
+        let input_type = self.input_type();
+        let decimal_separator = matches!(input_type, InputType::decimal)
+            .then(|| crate::locale::current_locale().decimal_separator);
+        let mut replacement: String = match decimal_separator {
+            // Accept both the point and the comma, as the keypads only have one of them
+            Some(separator) => replacement
+                .chars()
+                .map(|c| if c == '.' || c == ',' { separator } else { c })
+                .collect(),
+            None => replacement.into(),
+        };
+        let mask = self.input_mask();
+        if !mask.is_empty() {
+            replacement =
+                insert_mask_literals(&mask, text[..range.start].chars().count(), &replacement);
+        }
+
+        let mut new_text: String = text.into();
+        new_text.replace_range(range, &replacement);
+        let valid = match input_type {
+            InputType::number | InputType::decimal => {
+                is_partial_number(&new_text, decimal_separator)
+            }
+            _ => true,
+        } && (mask.is_empty() || matches_mask(&new_text, &mask));
+        let validate = Self::FIELD_OFFSETS.validate.apply_pin(self);
+        let valid = valid && (!validate.has_handler() || validate.call(&(new_text.into(),)));
+        valid.then(|| replacement)
+    }
+
+    /// Shows the character that was just typed in a hidden password for a short time, if
+    /// `password-echo` is `last-character`, and hides the previously shown one.
+    fn update_password_echo(self: Pin<&Self>, position: usize, inserted: &str, kind: TextEditKind) {
+        let echo = &self.data.password_echo;
+        let echoed_character = (self.hides_text()
+            && matches!(self.password_echo(), PasswordEcho::last_character)
+            && kind == TextEditKind::Typing
+            && inserted.chars().count() == 1)
+            .then(|| position);
+        echo.as_ref().set(echoed_character);
+        if echoed_character.is_some() {
+            let echo = echo.clone();
+            crate::timers::Timer::single_shot(PASSWORD_ECHO_DURATION, move || {
+                // Unless another character was typed since
+                if echo.as_ref().get_untracked() == echoed_character {
+                    echo.as_ref().set(None);
+                }
+            });
+        }
+    }
+
+    /// Returns true if the text is a password that isn't revealed with `password-revealed`.
+    /// The hidden text can't be copied to the clipboard.
+    pub fn hides_text(self: Pin<&Self>) -> bool {
+        matches!(self.input_type(), InputType::password) && !self.password_revealed()
+    }
+
+    /// Returns how the characters of the text are hidden when it's drawn, or None if the text
+    /// is shown.
+    pub fn password_mask(self: Pin<&Self>) -> Option<PasswordMask> {
+        self.hides_text()
+            .then(|| PasswordMask { echoed_character: self.data.password_echo.as_ref().get() })
+    }
+
+    /// Revert the last change made by the user. This implements the `undo()` function.
+    pub fn undo(self: Pin<&Self>, window: &WindowRc) {
+        let mut text: String = self.text().into();
+        let edit = {
+            let mut history = self.data.history.borrow_mut();
+            history.sync_with(&self.text());
+            history.undo(&mut text)
+        };
+        if let Some(edit) = edit {
+            self.text.set(text.into());
+            let (anchor, cursor) = edit.selection_before;
+            self.anchor_position.set(anchor);
+            self.set_cursor_position(cursor, true, window);
+            Self::FIELD_OFFSETS.edited.apply_pin(self).call(&());
+        }
+        self.update_undo_state();
+    }
+
+    /// Apply again the last change that was undone. This implements the `redo()` function.
+    pub fn redo(self: Pin<&Self>, window: &WindowRc) {
+        let mut text: String = self.text().into();
+        let edit = {
+            let mut history = self.data.history.borrow_mut();
+            history.sync_with(&self.text());
+            history.redo(&mut text)
+        };
+        if let Some(edit) = edit {
+            self.text.set(text.into());
+            let new_cursor_pos = (edit.position + edit.inserted.len()) as i32;
+            self.anchor_position.set(new_cursor_pos);
+            self.set_cursor_position(new_cursor_pos, true, window);
+            Self::FIELD_OFFSETS.edited.apply_pin(self).call(&());
+        }
+        self.update_undo_state();
+    }
+
+    fn update_undo_state(self: Pin<&Self>) {
+        let history = self.data.history.borrow();
+        self.can_undo.set(history.can_undo());
+        self.can_redo.set(history.can_redo());
+    }
+
     // Avoid accessing self.cursor_position()/self.anchor_position() directly, always
     // use this bounds-checking function.
     pub fn selection_anchor_and_cursor(self: Pin<&Self>) -> (usize, usize) {
@@ -682,6 +997,126 @@ pub fn selection_anchor_and_cursor(self: Pin<&Self>) -> (usize, usize) {
         }
     }
 
+    /// Returns the ranges of the text with their attributes, as provided by the `text-span` callback,
+    /// followed by the matches of the search that are highlighted. The ranges are clamped to the text
+    /// and to character boundaries. See [`TextSpan::attributes_at`] for overlapping ranges.
+    pub fn text_spans(self: Pin<&Self>) -> Vec<TextSpan> {
+        let text = self.text();
+        let clamp = |pos: i32| {
+            let mut pos = (pos.max(0) as usize).min(text.len());
+            while !text.is_char_boundary(pos) {
+                pos -= 1;
+            }
+            pos as i32
+        };
+        (0..self.text_span_count())
+            .map(|i| Self::FIELD_OFFSETS.text_span.apply_pin(self).call(&(i,)))
+            .filter_map(|span| {
+                let (start, end) = (clamp(span.start), clamp(span.end));
+                (start < end).then(|| TextSpan { start, end, ..span })
+            })
+            .chain(self.search_matches().into_iter().map(|m| TextSpan {
+                start: m.start as i32,
+                end: m.end as i32,
+                background: self.search_highlight_color(),
+                ..Default::default()
+            }))
+            .collect()
+    }
+
+    /// Returns the text being composed by an input method, which is not part of the text yet.
+    pub fn preedit_text(self: Pin<&Self>) -> SharedString {
+        // Safety: the data is allocated in TextInputDataBox::default and is never moved
+        unsafe { Pin::new_unchecked(&self.data.preedit_text) }.get()
+    }
+
+    /// Returns the text as it is drawn, with the text being composed by an input method inserted
+    /// at the cursor and underlined. Renderers draw this instead of the `text` property.
+    pub fn visual_representation(self: Pin<&Self>) -> TextInputVisualRepresentation {
+        let text = self.text();
+        let max_pos = text.len() as i32;
+        let cursor_position = self.cursor_position().max(0).min(max_pos) as usize;
+        let mut representation = TextInputVisualRepresentation {
+            cursor_position,
+            anchor_position: self.anchor_position().max(0).min(max_pos) as usize,
+            spans: self.text_spans(),
+            password_mask: self.password_mask(),
+            text,
+        };
+
+        let preedit_text = self.preedit_text();
+        if !preedit_text.is_empty() && representation.text.is_char_boundary(cursor_position) {
+            let mut text: String = representation.text.as_str().into();
+            text.insert_str(cursor_position, &preedit_text);
+            representation.text = text.into();
+            let shift = |pos: &mut i32| {
+                if *pos as usize > cursor_position {
+                    *pos += preedit_text.len() as i32
+                }
+            };
+            for span in &mut representation.spans {
+                shift(&mut span.start);
+                shift(&mut span.end);
+            }
+            // The echoed character would be at a different offset once the preedit text is inserted
+            if let Some(mask) = &mut representation.password_mask {
+                mask.echoed_character = None;
+            }
+            representation.spans.push(TextSpan {
+                start: cursor_position as i32,
+                end: (cursor_position + preedit_text.len()) as i32,
+                underline: true,
+                ..Default::default()
+            });
+            // The selection is replaced when the composition is committed, so it isn't shown
+            representation.cursor_position = cursor_position + preedit_text.len();
+            representation.anchor_position = representation.cursor_position;
+        }
+        representation
+    }
+
+    /// Returns the byte ranges of the text that match `search-text`
+    pub fn search_matches(self: Pin<&Self>) -> Vec<core::ops::Range<usize>> {
+        let options = SearchOptions {
+            case_sensitive: self.search_case_sensitive(),
+            whole_words: self.search_whole_words(),
+        };
+        find_matches(&self.text(), &self.search_text(), options)
+    }
+
+    /// Returns the index of the match of the search that is selected, or -1.
+    /// This implements the `current-match()` function.
+    pub fn current_match(self: Pin<&Self>) -> i32 {
+        let (anchor, cursor) = self.selection_anchor_and_cursor();
+        self.search_matches()
+            .iter()
+            .position(|m| m.start == anchor && m.end == cursor)
+            .map_or(-1, |index| index as i32)
+    }
+
+    /// Select the next (or previous) match of the search after (or before) the selection, wrapping
+    /// around at the end (or the start) of the text. Returns false if there is no match.
+    /// This implements the `find-next()` and `find-previous()` functions.
+    pub fn find(self: Pin<&Self>, forward: bool, window: &WindowRc) -> bool {
+        let matches = self.search_matches();
+        let (anchor, cursor) = self.selection_anchor_and_cursor();
+        let found = if forward {
+            matches.iter().find(|m| m.start >= cursor).or_else(|| matches.first())
+        } else {
+            matches.iter().rev().find(|m| m.end <= anchor).or_else(|| matches.last())
+        };
+        match found {
+            Some(m) => {
+                self.data.history.borrow_mut().close_group();
+                self.anchor_position.set(m.start as i32);
+                // Moving the cursor to the end of the match scrolls it into view
+                self.set_cursor_position(m.end as i32, true, window);
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn has_selection(self: Pin<&Self>) -> bool {
         let (anchor_pos, cursor_pos) = self.selection_anchor_and_cursor();
         anchor_pos != cursor_pos
@@ -694,19 +1129,11 @@ fn selected_text(self: Pin<&Self>) -> String {
     }
 
     fn insert(self: Pin<&Self>, text_to_insert: &str, window: &WindowRc) {
-        self.delete_selection(window);
-        let mut text: String = self.text().into();
-        let cursor_pos = self.selection_anchor_and_cursor().1;
         if text_to_insert.contains('\n') && self.single_line() {
-            text.insert_str(cursor_pos, &text_to_insert.replace('\n', " "));
+            self.replace_selection(&text_to_insert.replace('\n', " "), TextEditKind::Other, window);
         } else {
-            text.insert_str(cursor_pos, text_to_insert);
+            self.replace_selection(text_to_insert, TextEditKind::Other, window);
         }
-        let cursor_pos = cursor_pos + text_to_insert.len();
-        self.text.set(text.into());
-        self.anchor_position.set(cursor_pos as i32);
-        self.set_cursor_position(cursor_pos as i32, true, window);
-        Self::FIELD_OFFSETS.edited.apply_pin(self).call(&());
     }
 
     fn select_all(self: Pin<&Self>, window: &WindowRc) {
@@ -715,6 +1142,9 @@ fn select_all(self: Pin<&Self>, window: &WindowRc) {
     }
 
     fn copy(self: Pin<&Self>) {
+        if self.hides_text() {
+            return;
+        }
         if let Some(backend) = crate::backend::instance() {
             backend.set_clipboard_text(self.selected_text());
         }
@@ -757,3 +1187,184 @@ pub fn unresolved_font_request(self: Pin<&Self>) -> FontRequest {
         }
     }
 }
+
+/// The text of a `TextInput` as it is drawn, returned by [`TextInput::visual_representation()`].
+/// The positions are byte offsets in `text`, clamped to its length.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextInputVisualRepresentation {
+    /// The text, with the composition of the input method at the cursor
+    pub text: SharedString,
+    /// The position of the cursor
+    pub cursor_position: usize,
+    /// The position of the anchor of the selection
+    pub anchor_position: usize,
+    /// The ranges of the text with their attributes, see [`TextInput::text_spans()`]
+    pub spans: Vec<TextSpan>,
+    /// How the characters are hidden if the text is a password, see [`Self::apply_password_character()`]
+    pub password_mask: Option<PasswordMask>,
+}
+
+impl TextInputVisualRepresentation {
+    /// Returns the start and the end of the selection
+    pub fn selection(&self) -> (usize, usize) {
+        if self.anchor_position > self.cursor_position {
+            (self.cursor_position, self.anchor_position)
+        } else {
+            (self.anchor_position, self.cursor_position)
+        }
+    }
+
+    /// Replaces the characters of a password by `password_character`, as described by
+    /// `password_mask`, and maps the cursor and the anchor accordingly. The spans are dropped, as
+    /// they would reveal the structure of the password.
+    pub fn apply_password_character(&mut self, password_character: &str) {
+        if let Some(mask) = self.password_mask.take() {
+            let text = self.text.as_str();
+            self.cursor_position =
+                mask.to_masked_offset(text, password_character, self.cursor_position);
+            self.anchor_position =
+                mask.to_masked_offset(text, password_character, self.anchor_position);
+            self.text = mask.mask(text, password_character).into();
+            self.spans.clear();
+        }
+    }
+}
+
+#[repr(C)]
+/// Wraps the internal data structure for the TextInput
+pub struct TextInputDataBox(core::ptr::NonNull<TextInputData>);
+
+impl Default for TextInputDataBox {
+    fn default() -> Self {
+        TextInputDataBox(Box::leak(Box::new(TextInputData::default())).into())
+    }
+}
+impl Drop for TextInputDataBox {
+    fn drop(&mut self) {
+        // Safety: the self.0 was constructed from a Box::leak in TextInputDataBox::default
+        unsafe {
+            Box::from_raw(self.0.as_ptr());
+        }
+    }
+}
+
+impl core::ops::Deref for TextInputDataBox {
+    type Target = TextInputData;
+    fn deref(&self) -> &Self::Target {
+        // Safety: initialized in TextInputDataBox::default
+        unsafe { self.0.as_ref() }
+    }
+}
+
+#[derive(Debug)]
+pub struct TextInputData {
+    history: RefCell<TextEditHistory>,
+    /// The text being composed by an input method, shown at the cursor
+    preedit_text: Property<SharedString>,
+    /// The byte offset of the character of a password that is shown, see `password-echo`. It is
+    /// shared with the timer that hides the character again.
+    password_echo: Pin<Rc<Property<Option<usize>>>>,
+}
+
+impl Default for TextInputData {
+    fn default() -> Self {
+        Self {
+            history: Default::default(),
+            preedit_text: Default::default(),
+            password_echo: Rc::pin(Default::default()),
+        }
+    }
+}
+
+/// Revert the last change made by the user in the TextInput. This implements the `undo()` function.
+pub fn text_input_undo(text_input: &ItemRc, window: &WindowRc) {
+    if let Some(text_input) = ItemRef::downcast_pin::<TextInput>(text_input.borrow()) {
+        text_input.undo(window);
+    }
+}
+
+/// Apply again the last change undone in the TextInput. This implements the `redo()` function.
+pub fn text_input_redo(text_input: &ItemRc, window: &WindowRc) {
+    if let Some(text_input) = ItemRef::downcast_pin::<TextInput>(text_input.borrow()) {
+        text_input.redo(window);
+    }
+}
+
+/// Select the next or the previous match of the search. This implements the `find-next()` and
+/// `find-previous()` functions.
+pub fn text_input_find(text_input: &ItemRc, window: &WindowRc, forward: bool) -> bool {
+    ItemRef::downcast_pin::<TextInput>(text_input.borrow())
+        .map_or(false, |text_input| text_input.find(forward, window))
+}
+
+/// Returns the number of matches of the search. This implements the `search-match-count()` function.
+pub fn text_input_search_match_count(text_input: &ItemRc) -> i32 {
+    ItemRef::downcast_pin::<TextInput>(text_input.borrow())
+        .map_or(0, |text_input| text_input.search_matches().len() as i32)
+}
+
+/// Returns the index of the selected match of the search. This implements the `current-match()` function.
+pub fn text_input_current_match(text_input: &ItemRc) -> i32 {
+    ItemRef::downcast_pin::<TextInput>(text_input.borrow())
+        .map_or(-1, |text_input| text_input.current_match())
+}
+
+/// # Safety
+/// This must be called using a non-null pointer pointing to a chunk of memory big enough to
+/// hold a TextInputDataBox
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn slint_text_input_data_init(data: *mut TextInputDataBox) {
+    core::ptr::write(data, TextInputDataBox::default());
+}
+
+/// # Safety
+/// This must be called using a non-null pointer pointing to an initialized TextInputDataBox
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn slint_text_input_data_free(data: *mut TextInputDataBox) {
+    core::ptr::drop_in_place(data);
+}
+
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn slint_text_input_undo(
+    text_input: &ItemRc,
+    window: *const crate::window::ffi::WindowRcOpaque,
+) {
+    let window = &*(window as *const WindowRc);
+    text_input_undo(text_input, window)
+}
+
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn slint_text_input_redo(
+    text_input: &ItemRc,
+    window: *const crate::window::ffi::WindowRcOpaque,
+) {
+    let window = &*(window as *const WindowRc);
+    text_input_redo(text_input, window)
+}
+
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub unsafe extern "C" fn slint_text_input_find(
+    text_input: &ItemRc,
+    window: *const crate::window::ffi::WindowRcOpaque,
+    forward: bool,
+) -> bool {
+    let window = &*(window as *const WindowRc);
+    text_input_find(text_input, window, forward)
+}
+
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn slint_text_input_search_match_count(text_input: &ItemRc) -> i32 {
+    text_input_search_match_count(text_input)
+}
+
+#[cfg(feature = "ffi")]
+#[no_mangle]
+pub extern "C" fn slint_text_input_current_match(text_input: &ItemRc) -> i32 {
+    text_input_current_match(text_input)
+}
@@ -0,0 +1,267 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+The undo and redo history of the `TextInput`.
+
+Each change of the text is recorded as the replacement of a range of the text by another string.
+Consecutive changes of the same kind, such as typing the characters of a word or pressing backspace
+several times, are merged into one entry so that they are undone together.
+*/
+
+use crate::SharedString;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// The maximum number of entries kept in the undo history
+const MAX_UNDO_ENTRIES: usize = 1000;
+
+/// What caused a change of the text, to decide whether it can be merged with the previous one
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextEditKind {
+    /// A character typed by the user
+    Typing,
+    /// The character before the cursor was deleted
+    DeleteBackward,
+    /// The character after the cursor was deleted
+    DeleteForward,
+    /// Any other change (paste, cut, deletion of a word or of the selection), which is never merged
+    Other,
+}
+
+/// A change of the text: `removed` was replaced by `inserted` at the byte offset `position`
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextEdit {
+    pub position: usize,
+    pub removed: String,
+    pub inserted: String,
+    /// The anchor and the cursor position before the change, restored when it is undone
+    pub selection_before: (i32, i32),
+    kind: TextEditKind,
+}
+
+impl TextEdit {
+    /// Revert this change in the text
+    pub fn undo(&self, text: &mut String) {
+        text.replace_range(self.position..self.position + self.inserted.len(), &self.removed);
+    }
+
+    /// Apply this change to the text
+    pub fn redo(&self, text: &mut String) {
+        text.replace_range(self.position..self.position + self.removed.len(), &self.inserted);
+    }
+
+    /// Merge the `next` change into this one if they are of the same kind and adjacent
+    fn merge(&mut self, next: &TextEdit) -> bool {
+        if self.kind != next.kind {
+            return false;
+        }
+        match self.kind {
+            TextEditKind::Typing => {
+                let starts_word = next.inserted.starts_with(char::is_whitespace)
+                    && !self.inserted.ends_with(char::is_whitespace);
+                if !self.removed.is_empty()
+                    || !next.removed.is_empty()
+                    || next.position != self.position + self.inserted.len()
+                    || starts_word
+                {
+                    return false;
+                }
+                self.inserted.push_str(&next.inserted);
+            }
+            TextEditKind::DeleteBackward => {
+                if next.position + next.removed.len() != self.position {
+                    return false;
+                }
+                self.position = next.position;
+                self.removed.insert_str(0, &next.removed);
+            }
+            TextEditKind::DeleteForward => {
+                if next.position != self.position {
+                    return false;
+                }
+                self.removed.push_str(&next.removed);
+            }
+            TextEditKind::Other => return false,
+        }
+        true
+    }
+}
+
+/// The undo and redo stacks of a `TextInput`
+#[derive(Default, Debug)]
+pub struct TextEditHistory {
+    undo_stack: Vec<TextEdit>,
+    redo_stack: Vec<TextEdit>,
+    /// When set, the next change is not merged with the previous one (for example because the cursor moved)
+    group_closed: bool,
+    /// The text after the last recorded change. If the text differs, it was changed by something
+    /// else than the user editing it, and the history doesn't apply to it anymore.
+    text: SharedString,
+}
+
+impl TextEditHistory {
+    /// Record that `removed` was replaced by `inserted` at `position`, and that the text is now `new_text`
+    pub fn record(
+        &mut self,
+        position: usize,
+        removed: String,
+        inserted: String,
+        selection_before: (i32, i32),
+        kind: TextEditKind,
+        new_text: SharedString,
+    ) {
+        let edit = TextEdit { position, removed, inserted, selection_before, kind };
+        let merged = !self.group_closed
+            && self.redo_stack.is_empty()
+            && self.undo_stack.last_mut().map_or(false, |last| last.merge(&edit));
+        if !merged {
+            self.undo_stack.push(edit);
+            if self.undo_stack.len() > MAX_UNDO_ENTRIES {
+                self.undo_stack.remove(0);
+            }
+        }
+        self.redo_stack.clear();
+        self.group_closed = false;
+        self.text = new_text;
+    }
+
+    /// Make sure that the next change is not merged with the previous one
+    pub fn close_group(&mut self) {
+        self.group_closed = true;
+    }
+
+    /// Clear the history if the text was changed by something else than the recorded changes
+    pub fn sync_with(&mut self, text: &SharedString) {
+        if *text != self.text {
+            *self = Self { text: text.clone(), ..Default::default() };
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Revert the last change in the text, and return it
+    pub fn undo(&mut self, text: &mut String) -> Option<TextEdit> {
+        let edit = self.undo_stack.pop()?;
+        edit.undo(text);
+        self.redo_stack.push(edit.clone());
+        self.group_closed = true;
+        self.text = text.as_str().into();
+        Some(edit)
+    }
+
+    /// Apply again the last undone change, and return it
+    pub fn redo(&mut self, text: &mut String) -> Option<TextEdit> {
+        let edit = self.redo_stack.pop()?;
+        edit.redo(text);
+        self.undo_stack.push(edit.clone());
+        self.group_closed = true;
+        self.text = text.as_str().into();
+        Some(edit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Editor {
+        text: String,
+        history: TextEditHistory,
+    }
+
+    impl Editor {
+        fn new(text: &str) -> Self {
+            let mut history = TextEditHistory::default();
+            history.sync_with(&text.into());
+            Self { text: text.into(), history }
+        }
+
+        fn replace(&mut self, position: usize, len: usize, inserted: &str, kind: TextEditKind) {
+            let removed = self.text[position..position + len].to_owned();
+            self.text.replace_range(position..position + len, inserted);
+            let cursor = position as i32;
+            self.history.record(
+                position,
+                removed,
+                inserted.into(),
+                (cursor, cursor),
+                kind,
+                self.text.as_str().into(),
+            );
+        }
+
+        fn undo(&mut self) -> bool {
+            self.history.undo(&mut self.text).is_some()
+        }
+
+        fn redo(&mut self) -> bool {
+            self.history.redo(&mut self.text).is_some()
+        }
+    }
+
+    #[test]
+    fn typing_is_undone_word_by_word() {
+        let mut editor = Editor::new("");
+        for (i, c) in "hello world".char_indices() {
+            editor.replace(i, 0, &c.to_string(), TextEditKind::Typing);
+        }
+        assert!(editor.undo());
+        assert_eq!(editor.text, "hello");
+        assert!(editor.undo());
+        assert_eq!(editor.text, "");
+        assert!(!editor.undo());
+        assert!(editor.redo());
+        assert_eq!(editor.text, "hello");
+        assert!(editor.redo());
+        assert_eq!(editor.text, "hello world");
+        assert!(!editor.redo());
+    }
+
+    #[test]
+    fn deletions_are_merged() {
+        let mut editor = Editor::new("abcdef");
+        editor.replace(5, 1, "", TextEditKind::DeleteBackward);
+        editor.replace(4, 1, "", TextEditKind::DeleteBackward);
+        editor.replace(0, 1, "", TextEditKind::DeleteForward);
+        editor.replace(0, 1, "", TextEditKind::DeleteForward);
+        assert_eq!(editor.text, "cd");
+        assert!(editor.undo());
+        assert_eq!(editor.text, "abcd");
+        assert!(editor.undo());
+        assert_eq!(editor.text, "abcdef");
+    }
+
+    #[test]
+    fn closed_group_and_new_change() {
+        let mut editor = Editor::new("");
+        editor.replace(0, 0, "a", TextEditKind::Typing);
+        editor.history.close_group();
+        editor.replace(1, 0, "b", TextEditKind::Typing);
+        editor.replace(0, 2, "xyz", TextEditKind::Other);
+        assert!(editor.undo());
+        assert_eq!(editor.text, "ab");
+        // A new change discards the changes that could be redone
+        editor.replace(2, 0, "c", TextEditKind::Typing);
+        assert!(!editor.redo());
+        assert!(editor.undo());
+        assert_eq!(editor.text, "ab");
+        assert!(editor.undo());
+        assert_eq!(editor.text, "a");
+    }
+
+    #[test]
+    fn external_change_clears_the_history() {
+        let mut editor = Editor::new("");
+        editor.replace(0, 0, "a", TextEditKind::Typing);
+        editor.history.sync_with(&"something else".into());
+        assert!(!editor.history.can_undo());
+        assert!(!editor.history.can_redo());
+    }
+}
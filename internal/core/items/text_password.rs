@@ -0,0 +1,88 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+The hiding of the characters of a password in a `TextInput`, when it's drawn.
+*/
+
+use alloc::string::String;
+
+/// How the characters of a password are hidden when it's drawn: each character is replaced by the
+/// password character of the backend, except the character that is echoed, if any.
+/// It is returned by [`super::TextInput::password_mask()`].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct PasswordMask {
+    /// The byte offset in the text of the character that is shown, see `PasswordEcho`
+    pub echoed_character: Option<usize>,
+}
+
+impl PasswordMask {
+    /// Returns the length in bytes of the character `c` at `offset` once it's hidden
+    fn masked_len(&self, offset: usize, c: char, password_character: &str) -> usize {
+        if Some(offset) == self.echoed_character {
+            c.len_utf8()
+        } else {
+            password_character.len()
+        }
+    }
+
+    /// Returns `text` with its characters replaced by `password_character`, except the echoed one
+    pub fn mask(&self, text: &str, password_character: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        for (offset, c) in text.char_indices() {
+            if Some(offset) == self.echoed_character {
+                result.push(c);
+            } else {
+                result.push_str(password_character);
+            }
+        }
+        result
+    }
+
+    /// Maps a byte offset in `text` to the same position in the text returned by [`Self::mask()`]
+    pub fn to_masked_offset(&self, text: &str, password_character: &str, offset: usize) -> usize {
+        text.char_indices()
+            .take_while(|(i, _)| *i < offset)
+            .map(|(i, c)| self.masked_len(i, c, password_character))
+            .sum()
+    }
+
+    /// Maps a byte offset in the text returned by [`Self::mask()`] back to `text`
+    pub fn from_masked_offset(
+        &self,
+        text: &str,
+        password_character: &str,
+        masked_offset: usize,
+    ) -> usize {
+        let mut current = 0;
+        for (offset, c) in text.char_indices() {
+            if current >= masked_offset {
+                return offset;
+            }
+            current += self.masked_len(offset, c, password_character);
+        }
+        text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mask_and_offsets() {
+        let text = "aé€b";
+        let hidden = PasswordMask::default();
+        assert_eq!(hidden.mask(text, "●"), "●●●●");
+        assert_eq!(hidden.to_masked_offset(text, "●", 3), 6);
+        assert_eq!(hidden.from_masked_offset(text, "●", 6), 3);
+        assert_eq!(hidden.from_masked_offset(text, "●", 12), text.len());
+
+        let echoed = PasswordMask { echoed_character: Some(3) };
+        assert_eq!(echoed.mask(text, "*"), "**€*");
+        for (offset, masked_offset) in [(0, 0), (1, 1), (3, 2), (6, 5), (7, 6)] {
+            assert_eq!(echoed.to_masked_offset(text, "*", offset), masked_offset);
+            assert_eq!(echoed.from_masked_offset(text, "*", masked_offset), offset);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+The search of a string in the text of a `TextInput`.
+*/
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+/// The options of the search, set with the `search-*` properties of the `TextInput`
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SearchOptions {
+    pub case_sensitive: bool,
+    /// Only match the needle when it is not preceded or followed by a letter, a digit or an underscore
+    pub whole_words: bool,
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Returns the length in bytes of the start of `haystack` that matches `needle`
+fn match_len(haystack: &str, needle: &str, case_sensitive: bool) -> Option<usize> {
+    let mut haystack_chars = haystack.char_indices();
+    for n in needle.chars() {
+        let (_, h) = haystack_chars.next()?;
+        let equal = if case_sensitive { h == n } else { h.to_lowercase().eq(n.to_lowercase()) };
+        if !equal {
+            return None;
+        }
+    }
+    Some(haystack_chars.next().map_or(haystack.len(), |(i, _)| i))
+}
+
+/// Returns the byte ranges of the non-overlapping occurrences of `needle` in `text`
+pub fn find_matches(text: &str, needle: &str, options: SearchOptions) -> Vec<Range<usize>> {
+    let mut matches = Vec::new();
+    if needle.is_empty() {
+        return matches;
+    }
+    let mut search_start = 0;
+    for (start, _) in text.char_indices() {
+        if start < search_start {
+            continue;
+        }
+        let len = match match_len(&text[start..], needle, options.case_sensitive) {
+            Some(len) => len,
+            None => continue,
+        };
+        let end = start + len;
+        if options.whole_words
+            && (text[..start].chars().next_back().map_or(false, is_word_char)
+                || text[end..].chars().next().map_or(false, is_word_char))
+        {
+            continue;
+        }
+        matches.push(start..end);
+        search_start = end;
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn case_sensitivity() {
+        let text = "Hello hello HELLO";
+        assert_eq!(find_matches(text, "hello", SearchOptions::default()), [0..5, 6..11, 12..17]);
+        let case_sensitive = SearchOptions { case_sensitive: true, ..Default::default() };
+        assert_eq!(find_matches(text, "hello", case_sensitive), [6..11]);
+        assert_eq!(find_matches("Straße STRASSE", "straße", SearchOptions::default()), [0..7]);
+        assert!(find_matches(text, "", SearchOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn whole_words() {
+        let text = "cat concat cat_x cat.";
+        let whole_words = SearchOptions { whole_words: true, ..Default::default() };
+        assert_eq!(
+            find_matches(text, "cat", SearchOptions::default()),
+            [0..3, 7..10, 11..14, 17..20]
+        );
+        assert_eq!(find_matches(text, "cat", whole_words), [0..3, 17..20]);
+    }
+
+    #[test]
+    fn non_overlapping() {
+        assert_eq!(find_matches("aaaa", "aa", SearchOptions::default()), [0..2, 2..4]);
+        assert_eq!(find_matches("é é", "é", SearchOptions::default()), [0..2, 3..5]);
+    }
+}
@@ -0,0 +1,98 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+The validation of the text entered in a `TextInput`, according to its `input-type` and `input-mask`.
+The text is checked after every edit, so it only needs to be a valid beginning of a complete input.
+*/
+
+use alloc::string::String;
+
+/// Returns whether `c` is accepted by the character `placeholder` of a mask, or None if the
+/// character of the mask isn't a placeholder but a literal, which only accepts itself.
+fn placeholder_accepts(placeholder: char, c: char) -> Option<bool> {
+    match placeholder {
+        '#' => Some(c.is_ascii_digit()),
+        'A' => Some(c.is_alphabetic()),
+        'N' => Some(c.is_alphanumeric()),
+        '*' => Some(true),
+        _ => None,
+    }
+}
+
+/// Returns true if every character of `text` is accepted by the character of `mask` at the same
+/// position, and `text` isn't longer than `mask`.
+pub fn matches_mask(text: &str, mask: &str) -> bool {
+    let mut mask_chars = mask.chars();
+    text.chars().all(|c| match mask_chars.next() {
+        Some(m) => placeholder_accepts(m, c).unwrap_or(c == m),
+        None => false,
+    })
+}
+
+/// Returns `replacement` with the literals of `mask` added in front of the characters that
+/// don't match them, when `replacement` is inserted after the first `position` characters of the
+/// text. This way, the user only types the digits of a `##:##` mask.
+pub fn insert_mask_literals(mask: &str, position: usize, replacement: &str) -> String {
+    let mut mask_chars = mask.chars().skip(position);
+    let mut result = String::with_capacity(replacement.len());
+    for c in replacement.chars() {
+        while let Some(m) = mask_chars.next() {
+            if placeholder_accepts(m, c).is_some() || m == c {
+                break;
+            }
+            result.push(m);
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Returns true if `text` is a valid beginning of a number: an optional minus sign followed by
+/// digits, and, if `decimal_separator` is set, at most one decimal separator.
+pub fn is_partial_number(text: &str, decimal_separator: Option<char>) -> bool {
+    let mut separator_found = false;
+    text.strip_prefix('-').unwrap_or(text).chars().all(|c| {
+        if Some(c) == decimal_separator && !separator_found {
+            separator_found = true;
+            true
+        } else {
+            c.is_ascii_digit()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks() {
+        assert!(matches_mask("12:3", "##:##"));
+        assert!(matches_mask("", "##:##"));
+        assert!(!matches_mask("12:345", "##:##"));
+        assert!(!matches_mask("1a", "##:##"));
+        assert!(!matches_mask("123", "##:##"));
+        assert!(matches_mask("AB-1é", "AA-N*"));
+
+        assert_eq!(insert_mask_literals("##:##", 0, "1234"), "12:34");
+        assert_eq!(insert_mask_literals("##:##", 2, "3"), ":3");
+        assert_eq!(insert_mask_literals("##:##", 2, ":3"), ":3");
+        assert_eq!(insert_mask_literals("(###) ###", 0, "555123"), "(555) 123");
+        // The characters that don't fit are kept, so that matches_mask rejects them
+        assert_eq!(insert_mask_literals("##", 2, "3"), "3");
+    }
+
+    #[test]
+    fn numbers() {
+        assert!(is_partial_number("", None));
+        assert!(is_partial_number("-", None));
+        assert!(is_partial_number("-42", None));
+        assert!(!is_partial_number("4-2", None));
+        assert!(!is_partial_number("4.2", None));
+        assert!(is_partial_number("4.", Some('.')));
+        assert!(is_partial_number("-0,25", Some(',')));
+        assert!(!is_partial_number("0.2.5", Some('.')));
+        assert!(!is_partial_number("0.25", Some(',')));
+    }
+}
@@ -5,7 +5,7 @@
 
 // cspell:ignore coord
 
-use crate::items::{DialogButtonRole, LayoutAlignment};
+use crate::items::{DialogButtonRole, LayoutAlignment, StandardButtonKind};
 use crate::{slice::Slice, Coord, SharedVector};
 use alloc::vec::Vec;
 
@@ -256,6 +256,37 @@ fn test_layout_items() {
         assert_eq!(my_items[2].size, 100.);
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)] // We want bit-wise equality here
+    fn test_layout_policies() {
+        let cell = |col_or_row, span, min, stretch| GridLayoutCellData {
+            col_or_row,
+            span,
+            constraint: LayoutInfo { min, max: Coord::MAX, stretch, ..Default::default() },
+        };
+        let cells = [
+            cell(0, 1, 10., 1.),
+            cell(1, 1, 10., 1.),
+            // Policies: the first column needs 50 and doesn't stretch, the third is empty
+            cell(0, 0, 50., 0.),
+            cell(1, 0, 0., f32::MAX),
+            cell(2, 0, 20., 2.),
+        ];
+        let mut data = to_layout_data(&cells, 0., None);
+        assert_eq!(data.len(), 3);
+        assert_eq!(data[0].min, 50.);
+        assert_eq!(data[0].stretch, 0.);
+        assert_eq!(data[1].min, 10.);
+        assert_eq!(data[1].stretch, 1.);
+        assert_eq!(data[2].min, 20.);
+        assert_eq!(data[2].stretch, 2.);
+
+        layout_items(&mut data, 0., 350., 0.);
+        assert_eq!(data[0].size, 50.);
+        assert_eq!(data[1].size, 100.);
+        assert_eq!(data[2].size, 200.);
+    }
+
     /// Create a vector of LayoutData for an array of GridLayoutCellData
     pub fn to_layout_data(
         data: &[GridLayoutCellData],
@@ -264,14 +295,14 @@ pub fn to_layout_data(
     ) -> Vec<LayoutData> {
         let mut num = 0;
         for cell in data {
-            num = num.max(cell.col_or_row + cell.span);
+            num = num.max(cell.col_or_row + cell.span.max(1));
         }
         if num < 1 {
             return Default::default();
         }
         let mut layout_data = alloc::vec![grid_internal::LayoutData { stretch: 1., ..Default::default() }; num as usize];
         let mut has_spans = false;
-        for cell in data {
+        for cell in data.iter().filter(|cell| cell.span > 0) {
             let constraint = &cell.constraint;
             let mut max = constraint.max;
             if let Some(size) = size {
@@ -295,6 +326,24 @@ pub fn to_layout_data(
                 has_spans = true;
             }
         }
+        // Apply the sizing policies of the columns or rows, before distributing the cells spanning
+        // several of them
+        for policy in data.iter().filter(|cell| cell.span == 0) {
+            let constraint = &policy.constraint;
+            let mut min = constraint.min;
+            let mut max = constraint.max;
+            if let Some(size) = size {
+                min = min.max(size * constraint.min_percent / 100 as Coord);
+                max = max.min(size * constraint.max_percent / 100 as Coord);
+            }
+            let cdata = &mut layout_data[policy.col_or_row as usize];
+            cdata.min = cdata.min.max(min);
+            cdata.max = cdata.max.min(max).max(cdata.min);
+            cdata.pref = cdata.pref.max(constraint.preferred).min(cdata.max).max(cdata.min);
+            if constraint.stretch < f32::MAX {
+                cdata.stretch = constraint.stretch;
+            }
+        }
         if has_spans {
             // Adjust minimum sizes
             for cell in data.iter().filter(|cell| cell.span > 1) {
@@ -384,7 +433,10 @@ pub struct GridLayoutData<'a> {
 pub struct GridLayoutCellData {
     /// col, or row.
     pub col_or_row: u16,
-    /// colspan or rowspan
+    /// colspan or rowspan.
+    /// A span of 0 means that this is not a cell, but the sizing policy of the column or row:
+    /// its constraint is applied to the whole column or row, and its stretch (if not `f32::MAX`)
+    /// replaces the stretch of the cells.
     pub span: u16,
     pub constraint: LayoutInfo,
 }
@@ -411,7 +463,8 @@ pub fn solve_grid_layout(data: &GridLayoutData) -> SharedVector<Coord> {
         result.push(cdata.pos);
         result.push({
             let first_cell = &layout_data[cell.col_or_row as usize];
-            let last_cell = &layout_data[cell.col_or_row as usize + cell.span as usize - 1];
+            let last_cell =
+                &layout_data[cell.col_or_row as usize + (cell.span as usize).max(1) - 1];
             last_cell.pos + last_cell.size - first_cell.pos
         });
     }
@@ -723,24 +776,10 @@ pub fn solve_path_layout(
     result
 }
 
-/// Given the cells of a layout of a Dialog, re-order the button according to the platform
-///
-/// This function assume that the `roles` contains the roles of the button which are the first `cells`
-/// It will simply change the column field of the cell
-pub fn reorder_dialog_button_layout(cells: &mut [GridLayoutCellData], roles: &[DialogButtonRole]) {
-    fn add_buttons(
-        cells: &mut [GridLayoutCellData],
-        roles: &[DialogButtonRole],
-        idx: &mut u16,
-        role: DialogButtonRole,
-    ) {
-        for (cell, r) in cells.iter_mut().zip(roles.iter()) {
-            if *r == role {
-                cell.col_or_row = *idx;
-                *idx += 1;
-            }
-        }
-    }
+/// Returns the order of the buttons of a dialog on the current platform, by role. `None` is the position
+/// of the space that separates the buttons on the left from the buttons on the right.
+pub fn dialog_button_roles_order() -> &'static [Option<DialogButtonRole>] {
+    use DialogButtonRole::*;
 
     #[cfg(feature = "std")]
     fn is_kde() -> bool {
@@ -753,42 +792,55 @@ fn is_kde() -> bool {
     #[cfg(not(feature = "std"))]
     let is_kde = || true;
 
-    let mut idx = 0;
-
     if cfg!(windows) {
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reset);
-        idx += 1;
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::accept);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::action);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reject);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::apply);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::help);
+        &[Some(reset), None, Some(accept), Some(action), Some(reject), Some(apply), Some(help)]
     } else if cfg!(target_os = "macos") {
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::help);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reset);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::apply);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::action);
-        idx += 1;
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reject);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::accept);
+        &[Some(help), Some(reset), Some(apply), Some(action), None, Some(reject), Some(accept)]
     } else if is_kde() {
         // KDE variant
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::help);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reset);
-        idx += 1;
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::action);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::accept);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::apply);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reject);
+        &[Some(help), Some(reset), None, Some(action), Some(accept), Some(apply), Some(reject)]
     } else {
         // GNOME variant and fallback for WASM build
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::help);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reset);
-        idx += 1;
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::action);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::apply);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::reject);
-        add_buttons(cells, roles, &mut idx, DialogButtonRole::accept);
+        &[Some(help), Some(reset), None, Some(action), Some(apply), Some(reject), Some(accept)]
+    }
+}
+
+/// Returns the role of a `StandardButton` of the given kind in a Dialog
+pub fn standard_button_role(kind: StandardButtonKind) -> DialogButtonRole {
+    match kind {
+        StandardButtonKind::ok => DialogButtonRole::accept,
+        StandardButtonKind::cancel => DialogButtonRole::reject,
+        StandardButtonKind::apply => DialogButtonRole::apply,
+        StandardButtonKind::close => DialogButtonRole::reject,
+        StandardButtonKind::reset => DialogButtonRole::reset,
+        StandardButtonKind::help => DialogButtonRole::help,
+        StandardButtonKind::yes => DialogButtonRole::accept,
+        StandardButtonKind::no => DialogButtonRole::reject,
+        StandardButtonKind::abort => DialogButtonRole::reject,
+        StandardButtonKind::retry => DialogButtonRole::accept,
+        StandardButtonKind::ignore => DialogButtonRole::accept,
+    }
+}
+
+/// Given the cells of a layout of a Dialog, re-order the button according to the platform
+///
+/// This function assume that the `roles` contains the roles of the button which are the first `cells`
+/// It will simply change the column field of the cell
+pub fn reorder_dialog_button_layout(cells: &mut [GridLayoutCellData], roles: &[DialogButtonRole]) {
+    let mut idx = 0;
+    for role in dialog_button_roles_order() {
+        match role {
+            Some(role) => {
+                for (cell, r) in cells.iter_mut().zip(roles.iter()) {
+                    if r == role {
+                        cell.col_or_row = idx;
+                        idx += 1;
+                    }
+                }
+            }
+            // Leave an empty column for the stretch
+            None => idx += 1,
+        }
     }
 }
 
@@ -55,6 +55,9 @@ pub fn get(&self) -> Option<&T> {
         pub fn get_or_init(&self, f: impl FnOnce() -> T) -> &T {
             self.0.get_or_init(f)
         }
+        pub fn set(&self, value: T) -> Result<(), T> {
+            self.0.set(value)
+        }
     }
 
     // Safety: the unsafe_single_core feature means we will only be called from a single thread
@@ -62,11 +65,17 @@ unsafe impl<T> Send for OnceCell<T> {}
     unsafe impl<T> Sync for OnceCell<T> {}
 }
 
+pub mod accessibility;
 pub mod animations;
 pub mod api;
 pub mod backend;
+#[cfg(feature = "std")]
+pub mod benchmarks;
 pub mod callbacks;
+pub mod color_scheme;
 pub mod component;
+pub mod dialogs;
+pub mod event_loop;
 pub mod graphics;
 pub mod input;
 pub mod item_focus;
@@ -74,13 +83,23 @@ unsafe impl<T> Sync for OnceCell<T> {}
 pub mod item_tree;
 pub mod items;
 pub mod layout;
+pub mod lengths;
+pub mod locale;
 pub mod model;
+#[cfg(feature = "std")]
+pub mod parallel_binding;
+pub mod platform;
 pub mod properties;
+#[cfg(feature = "std")]
+pub mod recording;
 pub mod sharedvector;
 pub mod slice;
 pub mod string;
+#[cfg(feature = "swrenderer")]
+pub mod swrenderer;
 pub mod tests;
 pub mod timers;
+pub mod two_way_binding;
 pub mod window;
 
 #[cfg(feature = "rtti")]
@@ -144,6 +163,9 @@ pub fn use_modules() -> usize {
             + timers::ffi::slint_timer_start as usize
             + graphics::color::ffi::slint_color_brighter as usize
             + graphics::image::ffi::slint_image_size as usize
+            + locale::ffi::slint_locale_format_number as usize
+            + color_scheme::ffi::slint_color_scheme as usize
+            + animations::ffi::slint_animation_tick as usize
     }
     #[cfg(not(feature = "ffi"))]
     {
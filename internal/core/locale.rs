@@ -0,0 +1,314 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Locale aware formatting of numbers, dates and times.
+
+The formatting functions of the `.slint` language (`format-number`, `format-percent`,
+`format-date` and `format-time`) are implemented here. They use the conventions of the
+current [`Locale`], which is derived from the environment on startup and can be changed
+with [`set_locale`]. Bindings using these functions are re-evaluated when the locale changes.
+*/
+
+use crate::{Property, SharedString};
+use alloc::string::String;
+use core::fmt::Write;
+use core::pin::Pin;
+
+#[cfg(not(feature = "std"))]
+use num_traits::float::Float;
+
+/// The order in which the day, month and year are shown in a formatted date.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DateOrder {
+    /// `31.12.2022`
+    DayMonthYear,
+    /// `12/31/2022`
+    MonthDayYear,
+    /// `2022-12-31`
+    YearMonthDay,
+}
+
+/// The conventions used to format numbers, dates and times for a given language and region.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Locale {
+    /// The character between the integral and the fractional part of a number.
+    pub decimal_separator: char,
+    /// The character used to group the thousands, if any.
+    pub group_separator: Option<char>,
+    /// The order of the components of a date.
+    pub date_order: DateOrder,
+    /// The character between the components of a date.
+    pub date_separator: char,
+    /// Whether times are shown with a 24 hour clock, or with a 12 hour clock and AM/PM suffix.
+    pub use_24_hour_clock: bool,
+    /// Whether a space is put between a number and the percent sign.
+    pub space_before_percent: bool,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Self::from_name("en_US")
+    }
+}
+
+impl Locale {
+    /// Returns the conventions for a locale name as found in the `LANG` environment variable
+    /// or a BCP 47 language tag, for example `de_DE.UTF-8`, `fr-CH` or `ja`.
+    /// Unknown languages fall back to the conventions of `en_US`.
+    pub fn from_name(name: &str) -> Self {
+        let name = name.split(|c| c == '.' || c == '@').next().unwrap_or_default();
+        let mut parts = name.split(|c| c == '_' || c == '-');
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts.next().unwrap_or_default().to_ascii_uppercase();
+
+        use DateOrder::*;
+        let (decimal_separator, group_separator, date_order, date_separator, use_24_hour_clock) =
+            match (language.as_str(), region.as_str()) {
+                ("c" | "posix", _) => ('.', None, YearMonthDay, '-', true),
+                ("en", "US" | "") => ('.', Some(','), MonthDayYear, '/', false),
+                ("en", "CA" | "AU" | "NZ") => ('.', Some(','), DayMonthYear, '/', false),
+                ("en", _) => ('.', Some(','), DayMonthYear, '/', true),
+                ("de", "CH") => ('.', Some('\''), DayMonthYear, '.', true),
+                ("de" | "da" | "nb" | "nn" | "no" | "fi" | "tr", _) => {
+                    (',', Some('.'), DayMonthYear, '.', true)
+                }
+                ("fr", _) => (',', Some('\u{202f}'), DayMonthYear, '/', true),
+                ("es" | "it" | "pt" | "el", _) => (',', Some('.'), DayMonthYear, '/', true),
+                ("nl", _) => (',', Some('.'), DayMonthYear, '-', true),
+                ("ru" | "uk" | "pl" | "cs" | "sk", _) => {
+                    (',', Some('\u{a0}'), DayMonthYear, '.', true)
+                }
+                ("sv", _) => (',', Some('\u{a0}'), YearMonthDay, '-', true),
+                ("ja" | "zh", _) => ('.', Some(','), YearMonthDay, '/', true),
+                ("ko", _) => ('.', Some(','), YearMonthDay, '.', false),
+                _ => ('.', Some(','), MonthDayYear, '/', false),
+            };
+        let space_before_percent =
+            matches!(language.as_str(), "de" | "fr" | "sv" | "nb" | "nn" | "no" | "fi" | "cs");
+        Self {
+            decimal_separator,
+            group_separator,
+            date_order,
+            date_separator,
+            use_24_hour_clock,
+            space_before_percent,
+        }
+    }
+
+    /// Format a number with `decimals` digits after the decimal separator, grouping the
+    /// thousands of the integral part.
+    pub fn format_number(&self, value: f64, decimals: i32) -> SharedString {
+        let mut result = String::new();
+        self.write_number(&mut result, value, decimals);
+        result.as_str().into()
+    }
+
+    /// Format a ratio as a percentage, so that `0.25` becomes `25%`.
+    pub fn format_percent(&self, value: f64, decimals: i32) -> SharedString {
+        let mut result = String::new();
+        self.write_number(&mut result, value * 100., decimals);
+        if self.space_before_percent {
+            result.push('\u{a0}');
+        }
+        result.push('%');
+        result.as_str().into()
+    }
+
+    /// Format the date of a timestamp expressed in seconds since the Unix epoch (UTC).
+    pub fn format_date(&self, timestamp: i64) -> SharedString {
+        let (year, month, day) = civil_from_days(timestamp.div_euclid(86400));
+        let s = self.date_separator;
+        let mut result = String::new();
+        let _ = match self.date_order {
+            DateOrder::DayMonthYear => write!(result, "{:02}{}{:02}{}{}", day, s, month, s, year),
+            DateOrder::MonthDayYear => write!(result, "{:02}{}{:02}{}{}", month, s, day, s, year),
+            DateOrder::YearMonthDay => write!(result, "{}{}{:02}{}{:02}", year, s, month, s, day),
+        };
+        result.as_str().into()
+    }
+
+    /// Format the time of day of a timestamp expressed in seconds since the Unix epoch (UTC).
+    pub fn format_time(&self, timestamp: i64) -> SharedString {
+        let seconds_in_day = timestamp.rem_euclid(86400) as u32;
+        let (hour, minute) = (seconds_in_day / 3600, (seconds_in_day / 60) % 60);
+        let mut result = String::new();
+        let _ = if self.use_24_hour_clock {
+            write!(result, "{:02}:{:02}", hour, minute)
+        } else {
+            let suffix = if hour < 12 { "AM" } else { "PM" };
+            let hour = if hour % 12 == 0 { 12 } else { hour % 12 };
+            write!(result, "{}:{:02} {}", hour, minute, suffix)
+        };
+        result.as_str().into()
+    }
+
+    fn write_number(&self, result: &mut String, value: f64, decimals: i32) {
+        if !value.is_finite() {
+            let _ = write!(result, "{}", value);
+            return;
+        }
+        let decimals = decimals.clamp(0, 15) as usize;
+        let formatted = alloc::format!("{:.*}", decimals, value.abs());
+        let (integral, fractional) = formatted.split_once('.').unwrap_or((&formatted, ""));
+        if value < 0. && formatted.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            result.push('-');
+        }
+        let len = integral.len();
+        for (i, digit) in integral.chars().enumerate() {
+            if i > 0 && (len - i) % 3 == 0 {
+                if let Some(group_separator) = self.group_separator {
+                    result.push(group_separator);
+                }
+            }
+            result.push(digit);
+        }
+        if !fractional.is_empty() {
+            result.push(self.decimal_separator);
+            result.push_str(fractional);
+        }
+    }
+}
+
+/// Convert a number of days since the Unix epoch into a (year, month, day) triple,
+/// using the proleptic Gregorian calendar.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Algorithm from http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(feature = "std")]
+fn locale_from_environment() -> Locale {
+    ["LC_ALL", "LC_NUMERIC", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .map_or_else(Locale::default, |name| Locale::from_name(&name))
+}
+
+#[cfg(not(feature = "std"))]
+fn locale_from_environment() -> Locale {
+    Locale::default()
+}
+
+#[cfg(all(not(feature = "std"), feature = "unsafe_single_core"))]
+use crate::unsafe_single_core::thread_local;
+
+thread_local!(static CURRENT_LOCALE : Pin<alloc::boxed::Box<Property<Locale>>> =
+    alloc::boxed::Box::pin(Property::new_named(locale_from_environment(), "i_slint_core::locale::CURRENT_LOCALE")));
+
+/// Returns the locale currently used for formatting.
+/// Calling this function from a binding registers the locale as a dependency of the binding.
+pub fn current_locale() -> Locale {
+    CURRENT_LOCALE.with(|locale| locale.as_ref().get())
+}
+
+/// Change the locale used by the formatting functions of the `.slint` language.
+/// The locale name is a language tag such as `de_DE` or `fr-CH`.
+pub fn set_locale(name: &str) {
+    CURRENT_LOCALE.with(|locale| locale.as_ref().set(Locale::from_name(name)))
+}
+
+/// Format a number according to the current locale. See [`Locale::format_number`].
+pub fn format_number(value: f64, decimals: i32) -> SharedString {
+    current_locale().format_number(value, decimals)
+}
+
+/// Format a percentage according to the current locale. See [`Locale::format_percent`].
+pub fn format_percent(value: f64, decimals: i32) -> SharedString {
+    current_locale().format_percent(value, decimals)
+}
+
+/// Format a date according to the current locale. See [`Locale::format_date`].
+pub fn format_date(timestamp: i64) -> SharedString {
+    current_locale().format_date(timestamp)
+}
+
+/// Format a time according to the current locale. See [`Locale::format_time`].
+pub fn format_time(timestamp: i64) -> SharedString {
+    current_locale().format_time(timestamp)
+}
+
+#[test]
+fn test_format_number() {
+    let en = Locale::from_name("en_US.UTF-8");
+    assert_eq!(en.format_number(1234.5, 2), "1,234.50");
+    assert_eq!(en.format_number(-1234567., 0), "-1,234,567");
+    assert_eq!(en.format_number(-0.001, 1), "0.0");
+    assert_eq!(en.format_number(12., 0), "12");
+    let de = Locale::from_name("de_DE");
+    assert_eq!(de.format_number(1234.5, 1), "1.234,5");
+    assert_eq!(de.format_percent(0.255, 1), "25,5\u{a0}%");
+    assert_eq!(en.format_percent(0.5, 0), "50%");
+    assert_eq!(Locale::from_name("C").format_number(1234.5, 1), "1234.5");
+}
+
+#[test]
+fn test_format_date_time() {
+    // 2022-05-17 14:05:09 UTC
+    let timestamp = 1652796309;
+    assert_eq!(Locale::from_name("en_US").format_date(timestamp), "05/17/2022");
+    assert_eq!(Locale::from_name("de-DE").format_date(timestamp), "17.05.2022");
+    assert_eq!(Locale::from_name("ja_JP").format_date(timestamp), "2022/05/17");
+    assert_eq!(Locale::from_name("en_US").format_time(timestamp), "2:05 PM");
+    assert_eq!(Locale::from_name("fr_FR").format_time(timestamp), "14:05");
+    // 2022-05-17 15:59:59 UTC, the closest f32 is already the next minute
+    assert_eq!(Locale::from_name("fr_FR").format_time(1652803199), "15:59");
+    assert_eq!(Locale::from_name("en_US").format_time(0), "12:00 AM");
+    assert_eq!(Locale::from_name("C").format_date(-86400), "1969-12-31");
+}
+
+#[cfg(feature = "ffi")]
+pub(crate) mod ffi {
+    #![allow(unsafe_code)]
+
+    use super::*;
+
+    /// Format a number according to the current locale into `out`.
+    #[no_mangle]
+    pub extern "C" fn slint_locale_format_number(
+        value: f64,
+        decimals: i32,
+        out: &mut SharedString,
+    ) {
+        *out = format_number(value, decimals);
+    }
+
+    /// Format a percentage according to the current locale into `out`.
+    #[no_mangle]
+    pub extern "C" fn slint_locale_format_percent(
+        value: f64,
+        decimals: i32,
+        out: &mut SharedString,
+    ) {
+        *out = format_percent(value, decimals);
+    }
+
+    /// Format the date of a timestamp according to the current locale into `out`.
+    #[no_mangle]
+    pub extern "C" fn slint_locale_format_date(timestamp: i64, out: &mut SharedString) {
+        *out = format_date(timestamp);
+    }
+
+    /// Format the time of a timestamp according to the current locale into `out`.
+    #[no_mangle]
+    pub extern "C" fn slint_locale_format_time(timestamp: i64, out: &mut SharedString) {
+        *out = format_time(timestamp);
+    }
+
+    /// Change the current locale.
+    #[no_mangle]
+    pub extern "C" fn slint_locale_set(name: &SharedString) {
+        set_locale(name.as_str());
+    }
+}
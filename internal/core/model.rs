@@ -7,13 +7,12 @@
 
 use crate::component::ComponentVTable;
 use crate::item_tree::TraversalOrder;
-use crate::items::ItemRef;
 use crate::layout::Orientation;
 use crate::{Coord, Property, SharedString, SharedVector};
-pub use adapters::{FilterModel, MapModel};
+pub use adapters::{FilterModel, MapModel, SortModel};
 use alloc::boxed::Box;
 use alloc::vec::Vec;
-use core::cell::{Cell, RefCell};
+use core::cell::RefCell;
 use core::pin::Pin;
 #[allow(unused)]
 use euclid::num::{Ceil, Floor};
@@ -21,9 +20,15 @@
 use once_cell::unsync::OnceCell;
 use pin_project::pin_project;
 use pin_weak::rc::{PinWeak, Rc};
+pub use selection::{SelectionMode, SelectionModel};
+#[cfg(feature = "std")]
+pub use stream::{ChannelModel, StreamModel, StreamModelOptions};
 
 mod adapters;
 mod model_peer;
+mod selection;
+#[cfg(feature = "std")]
+mod stream;
 
 type ComponentRc<C> = vtable::VRc<crate::component::ComponentVTable, C>;
 
@@ -207,6 +212,16 @@ fn filter<F>(self, filter_function: F) -> FilterModel<Self, F>
     {
         FilterModel::new(self, filter_function)
     }
+
+    /// Returns a new Model where the elements are sorted by the function `sort_function`.
+    /// This is a shortcut for [`SortModel::new()`].
+    fn sort_by<F>(self, sort_function: F) -> SortModel<Self, F>
+    where
+        Self: Sized + 'static,
+        F: Fn(&Self::Data, &Self::Data) -> core::cmp::Ordering + 'static,
+    {
+        SortModel::new(self, sort_function)
+    }
 }
 
 impl<T: Model> ModelExt for T {}
@@ -579,18 +594,204 @@ enum RepeatedComponentState {
     /// The model data is stale and needs to be refreshed
     Dirty,
 }
+/// The height of each row of a ListView, as measured the last time it was laid out, or None if it was never
+/// instantiated.
+///
+/// The measured heights, and how many there are, are also kept in a Fenwick tree, so that the position of a
+/// row in the viewport, and the row at a position, are found in logarithmic time.
+#[derive(Default)]
+struct ItemHeights {
+    heights: Vec<Option<Coord>>,
+    /// `tree[i - 1]` is the sum and the number of the measured heights of the rows `i - lowbit(i)..i`
+    tree: Vec<(Coord, usize)>,
+    /// The sum of all the measured heights
+    total: Coord,
+    /// The number of measured heights
+    measured: usize,
+}
+
+impl ItemHeights {
+    fn len(&self) -> usize {
+        self.heights.len()
+    }
+
+    fn get(&self, row: usize) -> Option<Coord> {
+        self.heights.get(row).copied().flatten()
+    }
+
+    fn set(&mut self, row: usize, height: Option<Coord>) {
+        let old = if let Some(old) = self.heights.get(row).copied() { old } else { return };
+        if old == height {
+            return;
+        }
+        self.heights[row] = height;
+        if let Some(old) = old {
+            self.total -= old;
+            self.measured -= 1;
+            self.update(row, |node| {
+                node.0 -= old;
+                node.1 -= 1;
+            });
+        }
+        if let Some(height) = height {
+            self.total += height;
+            self.measured += 1;
+            self.update(row, |node| {
+                node.0 += height;
+                node.1 += 1;
+            });
+        }
+    }
+
+    fn update(&mut self, row: usize, f: impl Fn(&mut (Coord, usize))) {
+        let mut i = row + 1;
+        while i <= self.tree.len() {
+            f(&mut self.tree[i - 1]);
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Inserts `count` rows that were never measured before `index`
+    fn insert(&mut self, index: usize, count: usize) {
+        if index <= self.heights.len() {
+            self.heights.splice(index..index, core::iter::repeat(None).take(count));
+            self.rebuild();
+        }
+    }
+
+    fn remove(&mut self, index: usize, count: usize) {
+        let len = self.heights.len();
+        self.heights.drain(index.min(len)..(index + count).min(len));
+        self.rebuild();
+    }
+
+    fn resize(&mut self, len: usize) {
+        if len != self.heights.len() {
+            self.heights.resize(len, None);
+            self.rebuild();
+        }
+    }
+
+    fn clear(&mut self) {
+        self.heights.clear();
+        self.rebuild();
+    }
+
+    fn rebuild(&mut self) {
+        self.tree.clear();
+        self.tree.extend(self.heights.iter().map(|h| (h.unwrap_or(0 as _), h.is_some() as usize)));
+        for i in 1..=self.tree.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent <= self.tree.len() {
+                let node = self.tree[i - 1];
+                self.tree[parent - 1].0 += node.0;
+                self.tree[parent - 1].1 += node.1;
+            }
+        }
+        self.total = self.heights.iter().flatten().fold(0 as Coord, |total, h| total + *h);
+        self.measured = self.heights.iter().flatten().count();
+    }
+
+    /// The height assumed for the rows that were never measured: the average of the measured heights
+    fn estimated(&self) -> Coord {
+        if self.measured > 0 {
+            self.total / self.measured as Coord
+        } else {
+            0 as _
+        }
+    }
+
+    /// The `y` position of the row in the viewport, from the measured or estimated height of the rows before it
+    fn row_position(&self, row: usize, estimated_height: Coord) -> Coord {
+        let (mut total, mut measured) = (0 as Coord, 0);
+        let mut i = row.min(self.tree.len());
+        while i > 0 {
+            total += self.tree[i - 1].0;
+            measured += self.tree[i - 1].1;
+            i -= i & i.wrapping_neg();
+        }
+        total + (row - measured) as Coord * estimated_height
+    }
+
+    /// Returns the first row that is visible when the top of the ListView is at `offset_y` in the
+    /// viewport, and how far below the top of that row it is
+    fn row_at(&self, offset_y: Coord, row_count: usize, estimated_height: Coord) -> (usize, Coord) {
+        // Find the number of rows that end above `offset_y`, by going down the tree
+        let mut row = 0;
+        let mut y = 0 as Coord;
+        let mut step = (self.tree.len() + 1).next_power_of_two() / 2;
+        while step > 0 {
+            if row + step <= self.tree.len() {
+                let (total, measured) = self.tree[row + step - 1];
+                let height = total + (step - measured) as Coord * estimated_height;
+                if y + height <= offset_y {
+                    row += step;
+                    y += height;
+                }
+            }
+            step >>= 1;
+        }
+        // The rows after the measured ones all have the estimated height
+        while row < row_count && row >= self.tree.len() && y + estimated_height <= offset_y {
+            y += estimated_height;
+            row += 1;
+        }
+        if row + 1 > row_count {
+            let row = row_count.saturating_sub(1);
+            return (row, offset_y - self.row_position(row, estimated_height));
+        }
+        (row, offset_y - y)
+    }
+}
+
 struct RepeaterInner<C: RepeatedComponent> {
     components: Vec<(RepeatedComponentState, Option<ComponentRc<C>>)>,
     /// The model row (index) of the first component in the `components` vector.
     /// Only used for ListView
     offset: usize,
-    /// The average visible item_height. Only used for ListView
-    cached_item_height: Coord,
+    /// The height of each row of the model, as measured the last time it was laid out. Only used for
+    /// ListView
+    item_heights: ItemHeights,
+    /// The first visible row, and how far the top of the ListView is below the top of that row, at the
+    /// last layout. It follows the rows added or removed before it, so that the visible rows stay in
+    /// place. Only used for ListView
+    anchor: Option<(usize, Coord)>,
+    /// Whether rows were changed since the last layout, so that the viewport must be moved to keep the
+    /// anchor in place. Only used for ListView
+    anchor_moved: bool,
+    /// The value last written to the `first-visible-item` property of the ListView, to detect when the
+    /// application sets it to scroll to another row. Only used for ListView
+    reported_first_visible_item: i32,
 }
 
 impl<C: RepeatedComponent> Default for RepeaterInner<C> {
     fn default() -> Self {
-        RepeaterInner { components: Default::default(), offset: 0, cached_item_height: 0 as _ }
+        RepeaterInner {
+            components: Default::default(),
+            offset: 0,
+            item_heights: Default::default(),
+            anchor: None,
+            anchor_moved: false,
+            reported_first_visible_item: 0,
+        }
+    }
+}
+
+impl<C: RepeatedComponent> RepeaterInner<C> {
+    fn estimated_item_height(&self) -> Coord {
+        self.item_heights.estimated()
+    }
+
+    fn item_height(&self, row: usize, estimated_height: Coord) -> Coord {
+        self.item_heights.get(row).unwrap_or(estimated_height)
+    }
+
+    fn row_position(&self, row: usize, estimated_height: Coord) -> Coord {
+        self.item_heights.row_position(row, estimated_height)
+    }
+
+    fn row_at(&self, offset_y: Coord, row_count: usize, estimated_height: Coord) -> (usize, Coord) {
+        self.item_heights.row_at(offset_y, row_count, estimated_height)
     }
 }
 
@@ -614,22 +815,34 @@ fn row_changed(&self, row: usize) {
         self.is_dirty.set(true);
         let mut inner = self.inner.borrow_mut();
         let inner = &mut *inner;
+        inner.item_heights.set(row, None);
+        // The height of the row may change
+        inner.anchor_moved = true;
         if let Some(c) = inner.components.get_mut(row.wrapping_sub(inner.offset)) {
             c.0 = RepeatedComponentState::Dirty;
         }
     }
     /// Notify the peers that rows were added
-    fn row_added(&self, mut index: usize, mut count: usize) {
+    fn row_added(&self, mut index: usize, count: usize) {
         let mut inner = self.inner.borrow_mut();
+        inner.item_heights.insert(index, count);
+        if let Some((anchor, _)) = &mut inner.anchor {
+            if index <= *anchor {
+                *anchor += count;
+            }
+            inner.anchor_moved = true;
+        }
         if index < inner.offset {
-            if index + count < inner.offset {
-                return;
+            // The rows are added before the instantiated ones, which keep their rows
+            inner.offset += count;
+            self.is_dirty.set(true);
+            for c in inner.components.iter_mut() {
+                // Because all the indexes are dirty
+                c.0 = RepeatedComponentState::Dirty;
             }
-            count -= inner.offset - index;
-            index = 0;
-        } else {
-            index -= inner.offset;
+            return;
         }
+        index -= inner.offset;
         if count == 0 || index > inner.components.len() {
             return;
         }
@@ -646,12 +859,28 @@ fn row_added(&self, mut index: usize, mut count: usize) {
     /// Notify the peers that rows were removed
     fn row_removed(&self, mut index: usize, mut count: usize) {
         let mut inner = self.inner.borrow_mut();
-        if index < inner.offset {
-            if index + count < inner.offset {
-                return;
+        inner.item_heights.remove(index, count);
+        if let Some((anchor, delta)) = &mut inner.anchor {
+            if index + count <= *anchor {
+                *anchor -= count;
+            } else if index <= *anchor {
+                // The first visible row was removed, the row after it takes its place
+                *anchor = index;
+                *delta = 0 as _;
             }
-            count -= inner.offset - index;
+            inner.anchor_moved = true;
+        }
+        if index < inner.offset {
+            // The rows removed before the instantiated ones move them up
+            let removed_before = count.min(inner.offset - index);
+            inner.offset -= removed_before;
+            count -= removed_before;
             index = 0;
+            self.is_dirty.set(true);
+            for c in inner.components.iter_mut() {
+                // Because all the indexes are dirty
+                c.0 = RepeatedComponentState::Dirty;
+            }
         } else {
             index -= inner.offset;
         }
@@ -662,16 +891,24 @@ fn row_removed(&self, mut index: usize, mut count: usize) {
             count = inner.components.len() - index;
         }
         self.is_dirty.set(true);
-        inner.components.drain(index..(index + count));
+        let removed = inner.components.drain(index..(index + count)).collect();
         for c in inner.components[index..].iter_mut() {
             // Because all the indexes are dirty
             c.0 = RepeatedComponentState::Dirty;
         }
+        drop(inner);
+        destroy_components(removed);
     }
 
     fn reset(&self) {
         self.is_dirty.set(true);
-        self.inner.borrow_mut().components.clear();
+        let mut inner = self.inner.borrow_mut();
+        let removed = core::mem::take(&mut inner.components);
+        inner.item_heights.clear();
+        // The rows can't be followed
+        inner.anchor = None;
+        drop(inner);
+        destroy_components(removed);
     }
 }
 
@@ -686,6 +923,15 @@ fn default() -> Self {
     }
 }
 
+/// Calls the `will-destroy` callbacks of the components removed from a repeater, before dropping them
+fn destroy_components<C: RepeatedComponent>(
+    removed: Vec<(RepeatedComponentState, Option<ComponentRc<C>>)>,
+) {
+    for component in removed.into_iter().filter_map(|(_, component)| component) {
+        crate::component::will_destroy_component(&vtable::VRc::into_dyn(component));
+    }
+}
+
 #[pin_project]
 pub struct Repeater<C: RepeatedComponent>(#[pin] ModelChangeListenerContainer<RepeaterTracker<C>>);
 
@@ -705,7 +951,8 @@ fn model(self: Pin<&Self>) -> ModelRc<C::Data> {
         let model = self.data().project_ref().model;
 
         if model.is_dirty() {
-            *self.data().inner.borrow_mut() = RepeaterInner::default();
+            let old_inner = core::mem::take(&mut *self.data().inner.borrow_mut());
+            destroy_components(old_inner.components);
             self.data().is_dirty.set(true);
             let m = model.get();
             let peer = self.project_ref().0.model_peer();
@@ -732,6 +979,12 @@ fn ensure_updated_impl(
         model: &ModelRc<C::Data>,
         count: usize,
     ) -> bool {
+        let removed = {
+            let mut inner = self.0.inner.borrow_mut();
+            let count = count.min(inner.components.len());
+            inner.components.split_off(count)
+        };
+        destroy_components(removed);
         let mut inner = self.0.inner.borrow_mut();
         inner.components.resize_with(count, || (RepeatedComponentState::Dirty, None));
         let offset = inner.offset;
@@ -751,6 +1004,11 @@ fn ensure_updated_impl(
     }
 
     /// Same as `Self::ensuer_updated` but for a ListView
+    ///
+    /// The first visible row is written to `first_visible_item`. When the rows before it are added or
+    /// removed, the viewport is moved so that it stays in place. When the application sets
+    /// `first_visible_item`, the ListView is scrolled to show that row at the top.
+    #[allow(clippy::too_many_arguments)]
     pub fn ensure_updated_listview(
         self: Pin<&Self>,
         init: impl Fn() -> ComponentRc<C>,
@@ -759,11 +1017,13 @@ pub fn ensure_updated_listview(
         viewport_y: Pin<&Property<Coord>>,
         listview_width: Coord,
         listview_height: Pin<&Property<Coord>>,
+        first_visible_item: Pin<&Property<i32>>,
     ) {
         let model = self.model();
         let row_count = model.row_count();
         if row_count == 0 {
-            self.0.inner.borrow_mut().components.clear();
+            let removed = core::mem::take(&mut self.0.inner.borrow_mut().components);
+            destroy_components(removed);
             viewport_height.set(0 as _);
             viewport_y.set(0 as _);
 
@@ -781,82 +1041,97 @@ pub fn ensure_updated_listview(
                 let _ = self.data().project_ref().is_dirty.get();
 
                 let listview_height = listview_height.get();
-                // Compute the element height
-                let total_height = Cell::new(0 as Coord);
-                let min_height = Cell::new(listview_height);
-                let count = Cell::new(0);
-
-                let get_height_visitor = |item: Pin<ItemRef>| {
-                    count.set(count.get() + 1);
-                    let height = item.as_ref().geometry().height();
-                    total_height.set(total_height.get() + height);
-                    min_height.set(min_height.get().min(height))
-                };
-                for c in self.data().inner.borrow().components.iter() {
-                    if let Some(x) = c.1.as_ref() {
-                        get_height_visitor(x.as_pin_ref().get_item_ref(0));
-                    }
-                }
-
-                let mut element_height = if count.get() > 0 {
-                    total_height.get() / (count.get() as Coord)
-                } else {
-                    // There seems to be currently no items. Just instantiate one item.
 
+                // Lay out the components that are already instantiated, to measure them. If there are none,
+                // instantiate one so that there is an estimation of the height of the rows.
+                let count = {
+                    let mut inner = self.0.inner.borrow_mut();
+                    inner.item_heights.resize(row_count);
+                    inner.offset = inner.offset.min(row_count - 1);
+                    inner.components.len().clamp(1, row_count - inner.offset)
+                };
+                self.ensure_updated_impl(init, &model, count);
+                self.compute_layout_listview(viewport_width, listview_width);
+
+                let estimated_height = || self.0.inner.borrow().estimated_item_height().max(1 as _);
+                let total_height =
+                    self.0.inner.borrow().row_position(row_count, estimated_height());
+                let requested_first_visible_item = first_visible_item.get();
+                let offset_y = {
+                    let estimated_height = estimated_height();
+                    let mut inner = self.0.inner.borrow_mut();
+                    let anchor_moved = core::mem::take(&mut inner.anchor_moved);
+                    if requested_first_visible_item != inner.reported_first_visible_item
+                        && requested_first_visible_item >= 0
                     {
-                        let mut inner = self.0.inner.borrow_mut();
-                        inner.offset = inner.offset.min(row_count - 1);
-                    }
-
-                    self.ensure_updated_impl(&init, &model, 1);
-                    if let Some(c) = self.data().inner.borrow().components.get(0) {
-                        if let Some(x) = c.1.as_ref() {
-                            get_height_visitor(x.as_pin_ref().get_item_ref(0));
-                        }
+                        // The application scrolls to a row
+                        let row = (requested_first_visible_item as usize).min(row_count - 1);
+                        inner.row_position(row, estimated_height)
                     } else {
-                        panic!("Could not determine size of items");
+                        match inner.anchor {
+                            Some((row, delta)) if anchor_moved && row < row_count => {
+                                // Keep the first visible row in place
+                                inner.row_position(row, estimated_height) + delta
+                            }
+                            _ => -viewport_y.get(),
+                        }
                     }
-                    total_height.get()
+                };
+                let mut offset_y =
+                    offset_y.max(0 as _).min((total_height - listview_height).max(0 as _));
+
+                // Find the first visible row, and how many rows are needed to fill the ListView, from the
+                // heights that are known and the estimation of the others
+                let (mut offset, mut count) = {
+                    let estimated_height = estimated_height();
+                    let inner = self.0.inner.borrow();
+                    let (offset, delta) = inner.row_at(offset_y, row_count, estimated_height);
+                    let mut y = offset_y - delta;
+                    let mut count = 1;
+                    y += inner.item_height(offset, estimated_height);
+                    while offset + count < row_count && y < offset_y + listview_height {
+                        y += inner.item_height(offset + count, estimated_height);
+                        count += 1;
+                    }
+                    (offset, count)
                 };
 
-                let min_height = min_height.get().min(element_height).max(1 as _);
-
-                let mut offset_y = -viewport_y.get().min(0 as _);
-                if offset_y > element_height * row_count as Coord - listview_height
-                    && offset_y > viewport_height.get() - listview_height
-                {
-                    offset_y =
-                        (element_height * row_count as Coord - listview_height).max(0 as Coord);
-                }
-                let mut count = ((listview_height / min_height).ceil() as usize)
-                    // count never decreases to avoid too much flickering if items have different size
-                    .max(self.data().inner.borrow().components.len())
-                    .min(row_count);
-                let mut offset =
-                    ((offset_y / element_height).floor() as usize).min(row_count - count);
-                self.data().inner.borrow_mut().cached_item_height = element_height;
                 loop {
-                    self.data().inner.borrow_mut().cached_item_height = element_height;
                     self.set_offset(offset, count);
                     self.ensure_updated_impl(init, &model, count);
                     let end = self.compute_layout_listview(viewport_width, listview_width);
-                    let adjusted_element_height =
-                        (end - element_height * offset as Coord) / count as Coord;
-                    element_height = adjusted_element_height;
                     let diff = listview_height + offset_y - end;
-                    if diff > 0.5 as _ && count < row_count {
-                        // we did not create enough item, try increasing count until it matches
-                        count = (count + (diff / element_height).ceil() as usize).min(row_count);
-                        if offset + count > row_count {
-                            // apparently, we scrolled past the end, so decrease the offset and make offset_y
-                            // so we just are at the end
-                            offset = row_count - count;
-                            offset_y = (offset_y - diff).max(0 as _);
-                        }
+                    if diff > 0.5 as _ && offset + count < row_count {
+                        // The rows are smaller than estimated, instantiate more of them
+                        count = (count + (diff / estimated_height()).ceil() as usize)
+                            .min(row_count - offset);
+                        continue;
+                    }
+                    if diff > 0.5 as _ {
+                        // The last row ends before the bottom of the ListView: scroll back to align it
+                        // with the bottom
+                        offset_y = (end - listview_height).max(0 as _);
+                    }
+                    if offset > 0
+                        && self.0.inner.borrow().row_position(offset, estimated_height()) > offset_y
+                    {
+                        // The first row starts below the top of the ListView, instantiate the one before
+                        offset -= 1;
+                        count += 1;
                         continue;
                     }
-                    viewport_height.set((element_height * row_count as Coord).max(end));
+                    let total_height =
+                        self.0.inner.borrow().row_position(row_count, estimated_height());
+                    viewport_height.set(total_height.max(end));
                     viewport_y.set(-offset_y);
+
+                    let estimated_height = estimated_height();
+                    let mut inner = self.0.inner.borrow_mut();
+                    let anchor = inner.row_at(offset_y, row_count, estimated_height);
+                    inner.anchor = Some(anchor);
+                    inner.reported_first_visible_item = anchor.0 as i32;
+                    drop(inner);
+                    first_visible_item.set(anchor.0 as i32);
                     break;
                 }
             })
@@ -880,18 +1155,26 @@ fn set_offset(&self, offset: usize, count: usize) {
         let old_offset = inner.offset;
         // Remove the items before the offset, or add items until the old offset
         let to_remove = offset.saturating_sub(old_offset);
-        if to_remove < inner.components.len() {
-            inner.components.splice(
-                0..to_remove,
-                core::iter::repeat((RepeatedComponentState::Dirty, None))
-                    .take(old_offset.saturating_sub(offset)),
-            );
+        let mut removed: Vec<_> = if to_remove < inner.components.len() {
+            inner
+                .components
+                .splice(
+                    0..to_remove,
+                    core::iter::repeat((RepeatedComponentState::Dirty, None))
+                        .take(old_offset.saturating_sub(offset)),
+                )
+                .collect()
         } else {
-            inner.components.truncate(0);
+            core::mem::take(&mut inner.components)
+        };
+        if count < inner.components.len() {
+            removed.extend(inner.components.split_off(count));
         }
         inner.components.resize_with(count, || (RepeatedComponentState::Dirty, None));
         inner.offset = offset;
         self.0.is_dirty.set(true);
+        drop(inner);
+        destroy_components(removed);
     }
 
     /// Sets the data directly in the model
@@ -967,7 +1250,7 @@ pub fn components_vec(&self) -> Vec<ComponentRc<C>> {
         self.0.inner.borrow().components.iter().flat_map(|x| x.1.clone()).collect()
     }
 
-    /// Set the position of all the element in the listview
+    /// Set the position of all the element in the listview, and record their height
     ///
     /// Returns the offset of the end of the last element
     pub fn compute_layout_listview(
@@ -975,12 +1258,30 @@ pub fn compute_layout_listview(
         viewport_width: Pin<&Property<Coord>>,
         listview_width: Coord,
     ) -> Coord {
-        let inner = self.0.inner.borrow();
-        let mut y_offset = inner.offset as Coord * inner.cached_item_height;
+        let (offset, mut y_offset, components) = {
+            let inner = self.0.inner.borrow();
+            let y_offset = inner.row_position(inner.offset, inner.estimated_item_height());
+            (
+                inner.offset,
+                y_offset,
+                inner.components.iter().map(|c| c.1.clone()).collect::<Vec<_>>(),
+            )
+        };
         viewport_width.set(listview_width);
-        for c in self.0.inner.borrow().components.iter() {
-            if let Some(x) = c.1.as_ref() {
+        let mut heights = Vec::with_capacity(components.len());
+        for c in components.iter() {
+            if let Some(x) = c.as_ref() {
+                let y = y_offset;
                 x.as_pin_ref().listview_layout(&mut y_offset, viewport_width);
+                heights.push(Some(y_offset - y));
+            } else {
+                heights.push(None);
+            }
+        }
+        let mut inner = self.0.inner.borrow_mut();
+        for (row, height) in (offset..).zip(heights) {
+            if height.is_some() {
+                inner.item_heights.set(row, height);
             }
         }
         y_offset
@@ -1007,6 +1308,22 @@ fn from(other: SharedString) -> Self {
     }
 }
 
+/// Represent a column of a StandardTableView
+#[repr(C)]
+#[derive(Clone, Default, Debug, PartialEq)]
+pub struct TableColumn {
+    /// The title of the column, shown in its header
+    pub title: crate::SharedString,
+    /// The minimum width of the column, in logical pixels
+    pub min_width: crate::Coord,
+    /// How much of the remaining space the column takes, relative to the other columns
+    pub horizontal_stretch: f32,
+    /// Whether the rows are sorted by this column, as shown by an indicator in its header
+    pub sort_order: crate::items::SortOrder,
+    /// The width of the column, in logical pixels. It is changed when the user resizes the column.
+    pub width: crate::Coord,
+}
+
 #[test]
 fn test_tracking_model_handle() {
     let model: Rc<VecModel<u8>> = Rc::new(Default::default());
@@ -1155,3 +1472,40 @@ fn reset(&self) {
     assert_eq!(*view.reset.borrow(), 1);
     view.clear();
 }
+
+#[test]
+fn test_item_heights() {
+    fn naive_position(heights: &[Option<Coord>], row: usize, estimated_height: Coord) -> Coord {
+        (0..row).map(|r| heights.get(r).copied().flatten().unwrap_or(estimated_height)).sum()
+    }
+
+    let mut heights = ItemHeights::default();
+    heights.resize(10);
+    assert_eq!(heights.estimated(), 0 as Coord);
+    for (row, height) in [(0, 10), (3, 30), (4, 20), (9, 40)] {
+        heights.set(row, Some(height as Coord));
+    }
+    assert_eq!(heights.estimated(), 25 as Coord);
+    heights.insert(2, 3);
+    heights.remove(0, 1);
+    heights.set(5, None);
+    heights.set(1, Some(15 as Coord));
+    assert_eq!(heights.len(), 12);
+
+    let expected: Vec<Option<Coord>> =
+        [None, Some(15), None, None, None, None, Some(20), None, None, None, None, Some(40)]
+            .iter()
+            .map(|h| h.map(|h| h as Coord))
+            .collect();
+    assert_eq!(heights.heights, expected);
+    assert_eq!(heights.estimated(), 25 as Coord);
+
+    for row in 0..=12 {
+        assert_eq!(heights.row_position(row, 10 as _), naive_position(&expected, row, 10 as _));
+    }
+    assert_eq!(heights.row_at(0 as _, 12, 10 as _), (0, 0 as _));
+    assert_eq!(heights.row_at(24 as _, 12, 10 as _), (1, 14 as _));
+    assert_eq!(heights.row_at(25 as _, 12, 10 as _), (2, 0 as _));
+    assert_eq!(heights.row_at(1000 as _, 12, 10 as _), (11, 1000 as Coord - 125 as Coord));
+    assert_eq!(heights.row_at(1000 as _, 14, 10 as _), (13, 1000 as Coord - 175 as Coord));
+}
@@ -413,3 +413,254 @@ fn test_filter_model() {
     assert_eq!(filter.row_data(4).unwrap(), 8);
     assert_eq!(filter.row_count(), 5);
 }
+
+struct SortModelInner<M, F>
+where
+    M: Model + 'static,
+    F: Fn(&M::Data, &M::Data) -> core::cmp::Ordering + 'static,
+{
+    wrapped_model: M,
+    sort_function: F,
+    // This vector saves the indices of the elements of the wrapped model, in sorted order
+    mapping: RefCell<Vec<usize>>,
+    notify: ModelNotify,
+}
+
+impl<M, F> SortModelInner<M, F>
+where
+    M: Model + 'static,
+    F: Fn(&M::Data, &M::Data) -> core::cmp::Ordering + 'static,
+{
+    fn build_mapping_vec(&self) {
+        let data: Vec<M::Data> = self.wrapped_model.iter().collect();
+        let mut mapping = self.mapping.borrow_mut();
+        *mapping = (0..data.len()).collect();
+        // The sort is stable, so that equal rows keep the order of the wrapped model
+        mapping.sort_by(|a, b| (self.sort_function)(&data[*a], &data[*b]));
+    }
+
+    /// Returns the position in the sorted mapping at which the given row of the wrapped
+    /// model needs to be inserted. Equal rows are placed after the existing ones.
+    fn insertion_point(&self, mapping: &[usize], row: usize) -> usize {
+        let data = self.wrapped_model.row_data(row).unwrap();
+        mapping.partition_point(|&r| {
+            (self.sort_function)(&self.wrapped_model.row_data(r).unwrap(), &data)
+                != core::cmp::Ordering::Greater
+        })
+    }
+}
+
+impl<M, F> ModelChangeListener for SortModelInner<M, F>
+where
+    M: Model + 'static,
+    F: Fn(&M::Data, &M::Data) -> core::cmp::Ordering + 'static,
+{
+    fn row_changed(&self, row: usize) {
+        let mut mapping = self.mapping.borrow_mut();
+        let old_index = match mapping.iter().position(|&r| r == row) {
+            Some(index) => index,
+            None => return,
+        };
+        mapping.remove(old_index);
+        let new_index = self.insertion_point(&mapping, row);
+        mapping.insert(new_index, row);
+        drop(mapping);
+
+        if old_index == new_index {
+            self.notify.row_changed(new_index);
+        } else {
+            self.notify.row_removed(old_index, 1);
+            self.notify.row_added(new_index, 1);
+        }
+    }
+
+    fn row_added(&self, index: usize, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        self.mapping.borrow_mut().iter_mut().filter(|i| **i >= index).for_each(|i| *i += count);
+
+        for row in index..index + count {
+            let mut mapping = self.mapping.borrow_mut();
+            let insertion_point = self.insertion_point(&mapping, row);
+            mapping.insert(insertion_point, row);
+            drop(mapping);
+            self.notify.row_added(insertion_point, 1);
+        }
+    }
+
+    fn row_removed(&self, index: usize, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        let mut mapping = self.mapping.borrow_mut();
+        let mut removed = Vec::new();
+        let mut position = 0;
+        mapping.retain(|&r| {
+            let keep = r < index || r >= index + count;
+            if !keep {
+                removed.push(position);
+            }
+            position += 1;
+            keep
+        });
+        mapping.iter_mut().filter(|i| **i >= index + count).for_each(|i| *i -= count);
+        drop(mapping);
+
+        // Notify from the back so that the positions of the rows before are still valid
+        for position in removed.into_iter().rev() {
+            self.notify.row_removed(position, 1);
+        }
+    }
+
+    fn reset(&self) {
+        self.build_mapping_vec();
+        self.notify.reset();
+    }
+}
+
+/// Provides the rows of another [`Model`], sorted by a comparison function.
+///
+/// When the other Model is updated, the `SortModel` is updated accordingly.
+/// Rows that compare equal keep the order they have in the other Model.
+///
+/// ## Example
+///
+/// Here we have a [`VecModel`] holding [`SharedString`]s.
+/// It is then sorted into a `SortModel`.
+///
+/// ```
+/// # use slint::{Model, VecModel, SharedString, SortModel};
+/// let model = VecModel::from(vec![
+///     SharedString::from("Lorem"),
+///     SharedString::from("ipsum"),
+///     SharedString::from("dolor"),
+/// ]);
+///
+/// let sorted_model = SortModel::new(model, |lhs, rhs| lhs.to_lowercase().cmp(&rhs.to_lowercase()));
+///
+/// assert_eq!(sorted_model.row_data(0).unwrap(), SharedString::from("dolor"));
+/// assert_eq!(sorted_model.row_data(1).unwrap(), SharedString::from("ipsum"));
+/// assert_eq!(sorted_model.row_data(2).unwrap(), SharedString::from("Lorem"));
+/// ```
+///
+/// Alternatively you can use the shortcut [`ModelExt::sort_by`].
+/// ```
+/// # use slint::{Model, ModelExt, VecModel, SharedString, SortModel};
+/// let sorted_model = VecModel::from(vec![
+///     SharedString::from("Lorem"),
+///     SharedString::from("ipsum"),
+///     SharedString::from("dolor"),
+/// ]).sort_by(|lhs, rhs| lhs.to_lowercase().cmp(&rhs.to_lowercase()));
+/// # assert_eq!(sorted_model.row_data(0).unwrap(), SharedString::from("dolor"));
+/// # assert_eq!(sorted_model.row_data(1).unwrap(), SharedString::from("ipsum"));
+/// # assert_eq!(sorted_model.row_data(2).unwrap(), SharedString::from("Lorem"));
+/// ```
+///
+/// This is typically used together with a `StandardTableView`, re-creating the sorted model
+/// in the `sort-ascending` and `sort-descending` callbacks of the table.
+pub struct SortModel<M, F>(Pin<Box<ModelChangeListenerContainer<SortModelInner<M, F>>>>)
+where
+    M: Model + 'static,
+    F: Fn(&M::Data, &M::Data) -> core::cmp::Ordering + 'static;
+
+impl<M, F> SortModel<M, F>
+where
+    M: Model + 'static,
+    F: Fn(&M::Data, &M::Data) -> core::cmp::Ordering + 'static,
+{
+    /// Creates a new SortModel based on the given `wrapped_model` and sorted by `sort_function`.
+    /// Alternativly you can use [`ModelExt::sort_by`] on your Model.
+    pub fn new(wrapped_model: M, sort_function: F) -> Self {
+        let sort_model_inner = SortModelInner {
+            wrapped_model,
+            sort_function,
+            mapping: RefCell::new(Vec::new()),
+            notify: Default::default(),
+        };
+
+        sort_model_inner.build_mapping_vec();
+
+        let container = Box::pin(ModelChangeListenerContainer::new(sort_model_inner));
+
+        container.wrapped_model.model_tracker().attach_peer(container.as_ref().model_peer());
+
+        Self(container)
+    }
+
+    /// Manually reapply the sorting. You need to run this e.g. if the sorting function depends
+    /// on mutable state and it has changed.
+    pub fn apply_sorting(&self) {
+        self.0.reset();
+    }
+
+    /// Gets the row index of the underlying unsorted model for a given sorted row index.
+    pub fn unsorted_row(&self, sorted_row: usize) -> usize {
+        self.0.mapping.borrow()[sorted_row]
+    }
+}
+
+impl<M, F> Model for SortModel<M, F>
+where
+    M: Model + 'static,
+    F: Fn(&M::Data, &M::Data) -> core::cmp::Ordering + 'static,
+{
+    type Data = M::Data;
+
+    fn row_count(&self) -> usize {
+        self.0.mapping.borrow().len()
+    }
+
+    fn row_data(&self, row: usize) -> Option<Self::Data> {
+        self.0
+            .mapping
+            .borrow()
+            .get(row)
+            .map(|&wrapped_row| self.0.wrapped_model.row_data(wrapped_row).unwrap())
+    }
+
+    fn set_row_data(&self, row: usize, data: Self::Data) {
+        let wrapped_row = self.unsorted_row(row);
+        self.0.wrapped_model.set_row_data(wrapped_row, data);
+    }
+
+    fn model_tracker(&self) -> &dyn ModelTracker {
+        &self.0.notify
+    }
+}
+
+#[test]
+fn test_sort_model() {
+    let wrapped_rc = Rc::new(VecModel::from(vec![3, 1, 4, 1, 5]));
+    let sorted = SortModel::new(wrapped_rc.clone(), |a, b| a.cmp(b));
+
+    assert_eq!(sorted.iter().collect::<Vec<_>>(), vec![1, 1, 3, 4, 5]);
+    assert_eq!(sorted.unsorted_row(0), 1);
+    assert_eq!(sorted.unsorted_row(1), 3);
+    assert_eq!(sorted.unsorted_row(2), 0);
+
+    wrapped_rc.push(2);
+    assert_eq!(sorted.iter().collect::<Vec<_>>(), vec![1, 1, 2, 3, 4, 5]);
+    assert_eq!(sorted.unsorted_row(2), 5);
+
+    wrapped_rc.insert(0, 9);
+    assert_eq!(sorted.iter().collect::<Vec<_>>(), vec![1, 1, 2, 3, 4, 5, 9]);
+    assert_eq!(sorted.unsorted_row(0), 2);
+    assert_eq!(sorted.unsorted_row(6), 0);
+
+    wrapped_rc.remove(1);
+    assert_eq!(sorted.iter().collect::<Vec<_>>(), vec![1, 1, 2, 4, 5, 9]);
+    assert_eq!(sorted.unsorted_row(0), 1);
+    assert_eq!(sorted.unsorted_row(1), 3);
+
+    wrapped_rc.set_row_data(0, 0);
+    assert_eq!(sorted.iter().collect::<Vec<_>>(), vec![0, 1, 1, 2, 4, 5]);
+    assert_eq!(sorted.unsorted_row(0), 0);
+
+    sorted.set_row_data(5, 3);
+    assert_eq!(sorted.iter().collect::<Vec<_>>(), vec![0, 1, 1, 2, 3, 4]);
+    assert_eq!(wrapped_rc.row_data(4).unwrap(), 3);
+    assert_eq!(sorted.row_count(), 6);
+}
@@ -0,0 +1,290 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! This module contains the [`SelectionModel`], which holds the selected rows of another model.
+
+use super::*;
+use core::cell::Cell;
+
+/// How many rows of a [`SelectionModel`] can be selected at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// At most one row is selected: selecting a row deselects the others
+    Single,
+    /// Any number of rows can be selected
+    Multi,
+}
+
+impl Default for SelectionMode {
+    fn default() -> Self {
+        Self::Single
+    }
+}
+
+struct SelectionModelInner<M: Model + 'static> {
+    wrapped_model: M,
+    mode: Cell<SelectionMode>,
+    /// Whether each row of the wrapped model is selected
+    selected: RefCell<Vec<bool>>,
+    /// The row from which a range selection starts, usually the last row selected by the user
+    anchor: Cell<Option<usize>>,
+    notify: ModelNotify,
+}
+
+impl<M: Model + 'static> ModelChangeListener for SelectionModelInner<M> {
+    fn row_changed(&self, _row: usize) {}
+
+    fn row_added(&self, index: usize, count: usize) {
+        let mut selected = self.selected.borrow_mut();
+        let index = index.min(selected.len());
+        selected.splice(index..index, core::iter::repeat(false).take(count));
+        drop(selected);
+        if let Some(anchor) = self.anchor.get().filter(|anchor| *anchor >= index) {
+            self.anchor.set(Some(anchor + count));
+        }
+        self.notify.row_added(index, count);
+    }
+
+    fn row_removed(&self, index: usize, count: usize) {
+        let mut selected = self.selected.borrow_mut();
+        let end = (index + count).min(selected.len());
+        let index = index.min(end);
+        selected.drain(index..end);
+        drop(selected);
+        self.anchor.set(match self.anchor.get() {
+            Some(anchor) if anchor >= end => Some(anchor - (end - index)),
+            Some(anchor) if anchor >= index => None,
+            anchor => anchor,
+        });
+        self.notify.row_removed(index, end - index);
+    }
+
+    fn reset(&self) {
+        *self.selected.borrow_mut() = alloc::vec![false; self.wrapped_model.row_count()];
+        self.anchor.set(None);
+        self.notify.reset();
+    }
+}
+
+/// Holds which rows of another [`Model`] are selected, so that the selection is shared by all
+/// the views of that model and can be queried and changed from the application code, instead
+/// of storing a `selected` field in the data of each row.
+///
+/// The `SelectionModel` is itself a model of `bool`, with one row for each row of the wrapped
+/// model, that is `true` if the row is selected. It can be set to the `selection` property of
+/// the `StandardListView` and of the `StandardTableView` widgets. Setting the data of a row
+/// selects or deselects it, like [`Self::select()`] and [`Self::deselect()`].
+///
+/// When rows are added to or removed from the wrapped model, the selection of the other rows
+/// is kept.
+///
+/// ## Example
+///
+/// ```
+/// # use std::rc::Rc;
+/// # use slint::{Model, VecModel, SharedString, SelectionModel, SelectionMode};
+/// let model = Rc::new(VecModel::from(vec![
+///     SharedString::from("Lorem"),
+///     SharedString::from("ipsum"),
+///     SharedString::from("dolor"),
+///     SharedString::from("sit"),
+/// ]));
+///
+/// let selection = SelectionModel::new(model.clone(), SelectionMode::Multi);
+/// selection.select(1);
+/// // Select from the anchor, the last selected row, to the row 3, like with a shift+click
+/// selection.select_range(3);
+/// assert_eq!(selection.selected_rows(), vec![1, 2, 3]);
+///
+/// model.remove(0);
+/// assert_eq!(selection.selected_rows(), vec![0, 1, 2]);
+/// assert_eq!(selection.row_data(0), Some(true));
+/// ```
+pub struct SelectionModel<M: Model + 'static>(
+    Pin<Box<ModelChangeListenerContainer<SelectionModelInner<M>>>>,
+);
+
+impl<M: Model + 'static> SelectionModel<M> {
+    /// Creates a new SelectionModel for the rows of `wrapped_model`, where no row is selected.
+    pub fn new(wrapped_model: M, mode: SelectionMode) -> Self {
+        let inner = SelectionModelInner {
+            selected: RefCell::new(alloc::vec![false; wrapped_model.row_count()]),
+            wrapped_model,
+            mode: Cell::new(mode),
+            anchor: Cell::new(None),
+            notify: Default::default(),
+        };
+        let container = Box::pin(ModelChangeListenerContainer::new(inner));
+        container.wrapped_model.model_tracker().attach_peer(container.as_ref().model_peer());
+        Self(container)
+    }
+
+    /// Returns the model whose rows are selected
+    pub fn source_model(&self) -> &M {
+        &self.0.wrapped_model
+    }
+
+    /// Returns how many rows can be selected at once
+    pub fn mode(&self) -> SelectionMode {
+        self.0.mode.get()
+    }
+
+    /// Changes how many rows can be selected at once. When switching to [`SelectionMode::Single`],
+    /// only the anchor stays selected, if it was.
+    pub fn set_mode(&self, mode: SelectionMode) {
+        self.0.mode.set(mode);
+        if mode == SelectionMode::Single {
+            let anchor = self.0.anchor.get();
+            self.update(|row, selected| selected && Some(row) == anchor);
+        }
+    }
+
+    /// Returns true if the row is selected
+    pub fn is_selected(&self, row: usize) -> bool {
+        self.0.selected.borrow().get(row).copied().unwrap_or(false)
+    }
+
+    /// Returns the indices of the selected rows, in increasing order
+    pub fn selected_rows(&self) -> Vec<usize> {
+        self.0.selected.borrow().iter().enumerate().filter_map(|(i, s)| s.then(|| i)).collect()
+    }
+
+    /// Returns the row from which [`Self::select_range()`] starts: the last row selected with
+    /// [`Self::select()`] or [`Self::toggle()`], if it's still in the model.
+    pub fn anchor(&self) -> Option<usize> {
+        self.0.anchor.get()
+    }
+
+    /// Selects the row and makes it the anchor. In the single selection mode, the other rows are
+    /// deselected.
+    pub fn select(&self, row: usize) {
+        if row >= self.row_count() {
+            return;
+        }
+        self.0.anchor.set(Some(row));
+        let single = self.mode() == SelectionMode::Single;
+        self.update(|r, selected| r == row || (selected && !single));
+    }
+
+    /// Deselects the row
+    pub fn deselect(&self, row: usize) {
+        self.update(|r, selected| selected && r != row);
+    }
+
+    /// Selects the row if it isn't selected, like with a control+click, or deselects it. In both
+    /// cases, the row becomes the anchor.
+    pub fn toggle(&self, row: usize) {
+        if self.is_selected(row) {
+            self.0.anchor.set(Some(row));
+            self.deselect(row);
+        } else {
+            self.select(row);
+        }
+    }
+
+    /// Selects the rows from the anchor to `row`, included, and deselects the other ones, like
+    /// with a shift+click. The anchor doesn't change, so that the range can be changed again.
+    /// Without an anchor, or in the single selection mode, this is the same as [`Self::select()`].
+    pub fn select_range(&self, row: usize) {
+        let anchor = match self.0.anchor.get() {
+            Some(anchor) if self.mode() == SelectionMode::Multi => anchor,
+            _ => return self.select(row),
+        };
+        let range = anchor.min(row)..=anchor.max(row);
+        self.update(|r, _| range.contains(&r));
+    }
+
+    /// Selects all the rows, in the multiple selection mode
+    pub fn select_all(&self) {
+        if self.mode() == SelectionMode::Multi {
+            self.update(|_, _| true);
+        }
+    }
+
+    /// Deselects all the rows
+    pub fn clear(&self) {
+        self.update(|_, _| false);
+    }
+
+    /// Sets the selection of each row to the result of `f(row, selected)`, and notifies the
+    /// views of the rows that changed
+    fn update(&self, f: impl Fn(usize, bool) -> bool) {
+        let mut changed = Vec::new();
+        for (row, selected) in self.0.selected.borrow_mut().iter_mut().enumerate() {
+            let new = f(row, *selected);
+            if new != *selected {
+                *selected = new;
+                changed.push(row);
+            }
+        }
+        for row in changed {
+            self.0.notify.row_changed(row);
+        }
+    }
+}
+
+impl<M: Model + 'static> Model for SelectionModel<M> {
+    type Data = bool;
+
+    fn row_count(&self) -> usize {
+        self.0.selected.borrow().len()
+    }
+
+    fn row_data(&self, row: usize) -> Option<Self::Data> {
+        self.0.selected.borrow().get(row).copied()
+    }
+
+    fn set_row_data(&self, row: usize, data: Self::Data) {
+        if data {
+            self.select(row)
+        } else {
+            self.deselect(row)
+        }
+    }
+
+    fn model_tracker(&self) -> &dyn ModelTracker {
+        &self.0.notify
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+}
+
+#[test]
+fn test_selection_model() {
+    let wrapped_rc = Rc::new(VecModel::from(vec![1, 2, 3, 4, 5]));
+    let selection = SelectionModel::new(wrapped_rc.clone(), SelectionMode::Single);
+
+    selection.select(1);
+    selection.set_row_data(3, true);
+    assert_eq!(selection.selected_rows(), vec![3]);
+    selection.select_range(0);
+    assert_eq!(selection.selected_rows(), vec![0]);
+
+    selection.set_mode(SelectionMode::Multi);
+    selection.toggle(2);
+    selection.select_range(4);
+    assert_eq!(selection.selected_rows(), vec![2, 3, 4]);
+    selection.select_range(1);
+    assert_eq!(selection.selected_rows(), vec![1, 2]);
+    selection.toggle(1);
+    assert_eq!(selection.selected_rows(), vec![2]);
+    assert_eq!(selection.anchor(), Some(1));
+
+    wrapped_rc.insert(0, 0);
+    assert_eq!(selection.row_count(), 6);
+    assert_eq!(selection.selected_rows(), vec![3]);
+    assert_eq!(selection.anchor(), Some(2));
+    wrapped_rc.remove(2);
+    assert_eq!(selection.selected_rows(), vec![2]);
+    assert_eq!(selection.anchor(), None);
+
+    selection.select_all();
+    assert_eq!(selection.selected_rows(), vec![0, 1, 2, 3, 4]);
+    selection.set_mode(SelectionMode::Single);
+    assert_eq!(selection.selected_rows(), Vec::<usize>::new());
+
+    wrapped_rc.set_vec(vec![1, 2]);
+    assert_eq!(selection.row_count(), 2);
+}
@@ -0,0 +1,576 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+//! This module contains the models that are filled from asynchronous sources:
+//! [`StreamModel`] and [`ChannelModel`].
+
+use super::*;
+use crate::timers::Timer;
+use alloc::rc::Weak;
+use core::cell::Cell;
+use core::task::{Context, Poll};
+use core::time::Duration;
+use futures_core::Stream;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Options of the [`StreamModel`] and of the [`ChannelModel`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamModelOptions {
+    /// The maximum number of items that are handled at once. The remaining items are handled
+    /// after `batch_interval`, which leaves time to the event loop to render and to process
+    /// the input events. In the meantime, the senders of a bounded channel block when it's full.
+    pub batch_size: usize,
+    /// The time between two batches.
+    pub batch_interval: Duration,
+    /// The maximum number of rows of the model. When there are more, the oldest rows, at the
+    /// beginning of the model, are removed. `None` if there is no limit.
+    pub max_rows: Option<usize>,
+}
+
+impl Default for StreamModelOptions {
+    fn default() -> Self {
+        Self { batch_size: 1000, batch_interval: Duration::from_millis(16), max_rows: None }
+    }
+}
+
+/// Polls the source for the next item. `None` means that the source is finished.
+type PollNext<I> = Box<dyn FnMut(&mut Context) -> Poll<Option<I>>>;
+
+/// The part of the inner state that depends on the type of the items of the source
+trait Pump<T> {
+    /// Handles at most `batch_size` items of the source
+    fn pump(&mut self, rows: &VecModel<T>, batch_size: usize, cx: &mut Context) -> BatchResult;
+}
+
+enum BatchResult {
+    /// The source is finished
+    Finished,
+    /// The batch is full, and the source might have more items
+    Full,
+    /// The source has no more items for now
+    Pending,
+}
+
+struct SourcePump<T, I> {
+    poll_next: PollNext<I>,
+    handler: Box<dyn FnMut(&VecModel<T>, I)>,
+}
+
+impl<T, I> Pump<T> for SourcePump<T, I> {
+    fn pump(&mut self, rows: &VecModel<T>, batch_size: usize, cx: &mut Context) -> BatchResult {
+        for _ in 0..batch_size {
+            match (self.poll_next)(cx) {
+                Poll::Ready(Some(item)) => (self.handler)(rows, item),
+                Poll::Ready(None) => return BatchResult::Finished,
+                Poll::Pending => return BatchResult::Pending,
+            }
+        }
+        BatchResult::Full
+    }
+}
+
+/// The state shared by the [`StreamModel`] and the [`ChannelModel`]
+struct SourceModelInner<T> {
+    rows: VecModel<T>,
+    options: StreamModelOptions,
+    /// None once the source is finished
+    pump: RefCell<Option<Box<dyn Pump<T>>>>,
+    /// The key in `WAKEABLE_MODELS`
+    id: usize,
+    /// Whether a timer will handle the next batch
+    batch_scheduled: Cell<bool>,
+}
+
+thread_local! {
+    /// The models that can be woken up by the waker of their stream, by id
+    static WAKEABLE_MODELS: RefCell<HashMap<usize, Weak<dyn Wakeable>>> = RefCell::default()
+}
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+trait Wakeable {
+    fn process_batch(self: Rc<Self>);
+}
+
+/// The waker passed to the stream of a [`StreamModel`], and to the thread that waits on the
+/// receiver of a [`ChannelModel`]. It can be used from any thread.
+struct ModelWaker {
+    id: usize,
+}
+
+impl alloc::task::Wake for ModelWaker {
+    fn wake(self: Arc<Self>) {
+        let id = self.id;
+        // Without a backend, there is no event loop that could process the model
+        if let Some(backend) = crate::backend::instance() {
+            backend.post_event(Box::new(move || {
+                let model = WAKEABLE_MODELS
+                    .with(|models| models.borrow().get(&id).and_then(|model| model.upgrade()));
+                if let Some(model) = model {
+                    model.process_batch();
+                }
+            }))
+        }
+    }
+}
+
+impl<T: Clone + 'static> SourceModelInner<T> {
+    fn new(pump: Box<dyn Pump<T>>, options: StreamModelOptions) -> Rc<Self> {
+        let inner = Rc::new(Self {
+            rows: VecModel::from(Vec::new()),
+            options,
+            pump: RefCell::new(Some(pump)),
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            batch_scheduled: Cell::new(false),
+        });
+        let weak = Rc::downgrade(&inner) as Weak<dyn Wakeable>;
+        WAKEABLE_MODELS.with(|models| models.borrow_mut().insert(inner.id, weak));
+        inner.clone().process_batch();
+        inner
+    }
+
+    fn schedule_next_batch(self: &Rc<Self>) {
+        self.batch_scheduled.set(true);
+        let weak = Rc::downgrade(self);
+        Timer::single_shot(self.options.batch_interval, move || {
+            if let Some(inner) = weak.upgrade() {
+                inner.batch_scheduled.set(false);
+                inner.process_batch();
+            }
+        });
+    }
+
+    fn remove_oldest_rows(&self) {
+        if let Some(max_rows) = self.options.max_rows {
+            let excess = {
+                let mut array = self.rows.array.borrow_mut();
+                let excess = array.len().saturating_sub(max_rows);
+                array.drain(..excess);
+                excess
+            };
+            if excess > 0 {
+                self.rows.notify.row_removed(0, excess);
+            }
+        }
+    }
+}
+
+impl<T: Clone + 'static> Wakeable for SourceModelInner<T> {
+    fn process_batch(self: Rc<Self>) {
+        if self.batch_scheduled.get() {
+            // A batch was already scheduled, don't handle more items before that
+            return;
+        }
+        let waker: core::task::Waker = Arc::new(ModelWaker { id: self.id }).into();
+        let mut cx = Context::from_waker(&waker);
+        let result = match self.pump.borrow_mut().as_mut() {
+            Some(pump) => pump.pump(&self.rows, self.options.batch_size, &mut cx),
+            None => return,
+        };
+        self.remove_oldest_rows();
+        match result {
+            BatchResult::Finished => *self.pump.borrow_mut() = None,
+            BatchResult::Full => self.schedule_next_batch(),
+            // The waker calls process_batch again when the source has new items
+            BatchResult::Pending => {}
+        }
+    }
+}
+
+impl<T> Drop for SourceModelInner<T> {
+    fn drop(&mut self) {
+        let _ = WAKEABLE_MODELS.try_with(|models| models.borrow_mut().remove(&self.id));
+    }
+}
+
+macro_rules! delegate_model_to_rows {
+    ($model:ident) => {
+        impl<T: Clone + 'static> Model for $model<T> {
+            type Data = T;
+
+            fn row_count(&self) -> usize {
+                self.0.rows.row_count()
+            }
+
+            fn row_data(&self, row: usize) -> Option<Self::Data> {
+                self.0.rows.row_data(row)
+            }
+
+            fn set_row_data(&self, row: usize, data: Self::Data) {
+                self.0.rows.set_row_data(row, data)
+            }
+
+            fn model_tracker(&self) -> &dyn ModelTracker {
+                self.0.rows.model_tracker()
+            }
+
+            fn as_any(&self) -> &dyn core::any::Any {
+                self
+            }
+        }
+
+        impl<T: Clone + 'static> $model<T> {
+            /// Returns true when the source is finished and no more rows will be added
+            pub fn is_finished(&self) -> bool {
+                self.0.pump.borrow().is_none()
+            }
+        }
+    };
+}
+
+/// A model whose rows come from a [`Stream`].
+///
+/// The stream is polled from the event loop, so it doesn't need to be [`Send`], and the
+/// model can be used in the UI right away: the rows appear as the stream produces items.
+/// The stream is dropped when it's finished, or when the model is dropped.
+///
+/// By default each item is appended to the model. Use [`StreamModel::with_handler`] to
+/// update the existing rows instead, and to control the batching with [`StreamModelOptions`].
+///
+/// ## Example
+///
+/// ```
+/// # i_slint_backend_testing::init();
+/// use slint::{Model, StreamModel};
+/// # struct Iter<I>(I);
+/// # impl<I: Iterator + Unpin> futures_core::Stream for Iter<I> {
+/// #     type Item = I::Item;
+/// #     fn poll_next(
+/// #         mut self: core::pin::Pin<&mut Self>,
+/// #         _: &mut core::task::Context,
+/// #     ) -> core::task::Poll<Option<I::Item>> {
+/// #         core::task::Poll::Ready(self.0.next())
+/// #     }
+/// # }
+/// # let log_lines = Iter(["Started", "Connected"].into_iter().map(slint::SharedString::from));
+/// // `log_lines` is a stream of `SharedString`
+/// let model = StreamModel::new(log_lines);
+/// assert_eq!(model.row_data(1), Some("Connected".into()));
+/// ```
+pub struct StreamModel<T>(Rc<SourceModelInner<T>>);
+
+impl<T: Clone + 'static> StreamModel<T> {
+    /// Creates a model that appends the items of the stream, with the default options.
+    pub fn new<S: Stream<Item = T> + 'static>(stream: S) -> Self {
+        Self::with_handler(stream, Default::default(), |rows, item| rows.push(item))
+    }
+
+    /// Creates a model that calls `handler` with its rows for each item of the stream.
+    /// The handler can append, insert, remove or change rows.
+    pub fn with_handler<S>(
+        stream: S,
+        options: StreamModelOptions,
+        handler: impl FnMut(&VecModel<T>, S::Item) + 'static,
+    ) -> Self
+    where
+        S: Stream + 'static,
+        S::Item: 'static,
+    {
+        let mut stream = Box::pin(stream);
+        let pump = SourcePump {
+            poll_next: Box::new(move |cx| stream.as_mut().poll_next(cx)),
+            handler: Box::new(handler),
+        };
+        Self(SourceModelInner::new(Box::new(pump), options))
+    }
+}
+
+delegate_model_to_rows!(StreamModel);
+
+/// A model whose rows come from the [`Receiver`] of a [`std::sync::mpsc`] channel.
+///
+/// The items can be sent from any thread. A thread waits for them on the receiver and wakes
+/// the event loop up when there are new items, so an idle channel doesn't cost anything. The
+/// receiver is dropped when all the senders are dropped, or when an item is received after the
+/// model was dropped. With a bounded channel created by
+/// [`sync_channel`](std::sync::mpsc::sync_channel), the senders wait when the model doesn't keep up.
+///
+/// ## Example
+///
+/// ```
+/// # i_slint_backend_testing::init();
+/// use slint::{ChannelModel, Model, StreamModelOptions};
+/// let (sender, receiver) = std::sync::mpsc::sync_channel(100);
+/// // Keep only the 500 latest samples
+/// let options = StreamModelOptions { max_rows: Some(500), ..Default::default() };
+/// let model = ChannelModel::with_handler(receiver, options, |rows, sample: f32| rows.push(sample));
+/// std::thread::spawn(move || {
+///     for i in 0..1000 {
+///         // This blocks when the model has 100 samples left to handle
+///         if sender.send(i as f32).is_err() {
+///             // The model was dropped
+///             break;
+///         }
+///     }
+/// });
+/// # assert!(model.row_count() <= 500);
+/// ```
+pub struct ChannelModel<T>(Rc<SourceModelInner<T>>);
+
+impl<T: Clone + 'static> ChannelModel<T> {
+    /// Creates a model that appends the items received from the channel, with the default options.
+    pub fn new(receiver: Receiver<T>) -> Self
+    where
+        T: Send,
+    {
+        Self::with_handler(receiver, Default::default(), |rows, item| rows.push(item))
+    }
+
+    /// Creates a model that calls `handler` with its rows for each item received from the channel.
+    /// The handler can append, insert, remove or change rows.
+    pub fn with_handler<I: 'static>(
+        receiver: Receiver<I>,
+        options: StreamModelOptions,
+        handler: impl FnMut(&VecModel<T>, I) + 'static,
+    ) -> Self
+    where
+        I: Send,
+    {
+        Self::with_queue(ChannelQueue::spawn(receiver, options.batch_size.max(1)), options, handler)
+    }
+
+    fn with_queue<I: Send + 'static>(
+        queue: ChannelQueue<I>,
+        options: StreamModelOptions,
+        handler: impl FnMut(&VecModel<T>, I) + 'static,
+    ) -> Self {
+        let pump = SourcePump {
+            poll_next: Box::new(move |cx| queue.poll_next(cx)),
+            handler: Box::new(handler),
+        };
+        Self(SourceModelInner::new(Box::new(pump), options))
+    }
+}
+
+/// The items received by the thread that waits on the receiver of a [`ChannelModel`]
+struct ChannelQueueState<I> {
+    items: VecDeque<I>,
+    /// Set when all the senders are dropped
+    disconnected: bool,
+    /// Set when the model is dropped, so that the thread stops
+    closed: bool,
+    /// The waker of the model, set when the model is waiting for items
+    waker: Option<core::task::Waker>,
+}
+
+/// The state of the queue, and the condition variable notified when it changes
+type ChannelQueueShared<I> = Arc<(Mutex<ChannelQueueState<I>>, Condvar)>;
+
+/// The model's end of the queue filled by the thread that waits on the receiver. It holds at
+/// most `capacity` items, so that the senders of a bounded channel still wait for the model.
+struct ChannelQueue<I> {
+    shared: ChannelQueueShared<I>,
+}
+
+impl<I: Send + 'static> ChannelQueue<I> {
+    fn spawn(receiver: Receiver<I>, capacity: usize) -> Self {
+        let shared = Arc::new((
+            Mutex::new(ChannelQueueState {
+                items: VecDeque::new(),
+                disconnected: false,
+                closed: false,
+                waker: None,
+            }),
+            Condvar::new(),
+        ));
+        let thread_shared = shared.clone();
+        std::thread::spawn(move || {
+            let (state, condvar) = &*thread_shared;
+            loop {
+                {
+                    let mut state = state.lock().unwrap();
+                    while state.items.len() >= capacity && !state.closed {
+                        state = condvar.wait(state).unwrap();
+                    }
+                    if state.closed {
+                        return;
+                    }
+                }
+                let item = receiver.recv();
+                let mut state = state.lock().unwrap();
+                if state.closed {
+                    return;
+                }
+                let disconnected = match item {
+                    Ok(item) => {
+                        state.items.push_back(item);
+                        false
+                    }
+                    Err(_) => {
+                        state.disconnected = true;
+                        true
+                    }
+                };
+                let waker = state.waker.take();
+                drop(state);
+                condvar.notify_all();
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+                if disconnected {
+                    return;
+                }
+            }
+        });
+        Self { shared }
+    }
+
+    fn poll_next(&self, cx: &mut Context) -> Poll<Option<I>> {
+        let (state, condvar) = &*self.shared;
+        let mut state = state.lock().unwrap();
+        if let Some(item) = state.items.pop_front() {
+            condvar.notify_all();
+            Poll::Ready(Some(item))
+        } else if state.disconnected {
+            Poll::Ready(None)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<I> Drop for ChannelQueue<I> {
+    fn drop(&mut self) {
+        let (state, condvar) = &*self.shared;
+        if let Ok(mut state) = state.lock() {
+            state.closed = true;
+        }
+        condvar.notify_all();
+    }
+}
+
+delegate_model_to_rows!(ChannelModel);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::pin::Pin;
+
+    /// A stream that returns the items of the queue, then `Pending` until it is closed
+    #[derive(Default)]
+    struct TestStream {
+        queue: Rc<RefCell<(Vec<i32>, bool)>>,
+    }
+
+    impl Stream for TestStream {
+        type Item = i32;
+        fn poll_next(self: Pin<&mut Self>, _: &mut Context) -> Poll<Option<i32>> {
+            let mut queue = self.queue.borrow_mut();
+            if !queue.0.is_empty() {
+                Poll::Ready(Some(queue.0.remove(0)))
+            } else if queue.1 {
+                Poll::Ready(None)
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Creates a channel model, and returns the queue filled by its thread with it
+    fn channel_model(
+        receiver: Receiver<i32>,
+        options: StreamModelOptions,
+    ) -> (ChannelModel<i32>, ChannelQueueShared<i32>) {
+        let queue = ChannelQueue::spawn(receiver, options.batch_size.max(1));
+        let shared = queue.shared.clone();
+        (ChannelModel::with_queue(queue, options, |rows, item| rows.push(item)), shared)
+    }
+
+    /// What the event loop does until the model is finished: wait until the thread received
+    /// items, or all the senders are dropped, and run the batch requested by the waker or
+    /// scheduled by the timer. `after_batch` is called after each batch.
+    fn run_batches(
+        model: &ChannelModel<i32>,
+        shared: &ChannelQueueShared<i32>,
+        mut after_batch: impl FnMut(),
+    ) {
+        while !model.is_finished() {
+            {
+                let (state, condvar) = &**shared;
+                let state = state.lock().unwrap();
+                let _state = condvar
+                    .wait_while(state, |state| state.items.is_empty() && !state.disconnected)
+                    .unwrap();
+            }
+            model.0.batch_scheduled.set(false);
+            model.0.clone().process_batch();
+            after_batch();
+        }
+    }
+
+    #[test]
+    fn stream_model() {
+        let stream = TestStream::default();
+        let queue = stream.queue.clone();
+        queue.borrow_mut().0.extend([1, 2, 3]);
+        let model = StreamModel::new(stream);
+        assert_eq!(model.iter().collect::<Vec<_>>(), [1, 2, 3]);
+        assert!(!model.is_finished());
+
+        queue.borrow_mut().0.push(4);
+        queue.borrow_mut().1 = true;
+        // What the event loop does when the waker is woken up
+        model.0.clone().process_batch();
+        assert_eq!(model.iter().collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert!(model.is_finished());
+    }
+
+    #[test]
+    fn stream_model_with_handler() {
+        let stream = TestStream::default();
+        stream.queue.borrow_mut().0.extend([1, 2, 3, 4, 5]);
+        stream.queue.borrow_mut().1 = true;
+        // The even items replace the last row
+        let options = StreamModelOptions { max_rows: Some(2), ..Default::default() };
+        let model = StreamModel::with_handler(stream, options, |rows, item| {
+            if item % 2 == 0 {
+                rows.set_row_data(rows.row_count() - 1, item);
+            } else {
+                rows.push(item);
+            }
+        });
+        assert!(model.is_finished());
+        // [1] [2] [2, 3] [2, 4] [2, 4, 5], and the oldest rows are removed
+        assert_eq!(model.iter().collect::<Vec<_>>(), [4, 5]);
+    }
+
+    #[test]
+    fn channel_model_batches() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let options = StreamModelOptions { batch_size: 2, ..Default::default() };
+        let (model, shared) = channel_model(receiver, options);
+        // Nothing is scheduled while the channel is empty
+        assert_eq!(model.row_count(), 0);
+        assert!(!model.0.batch_scheduled.get());
+
+        for i in 1..=5 {
+            sender.send(i).unwrap();
+        }
+        drop(sender);
+        let mut previous_count = 0;
+        run_batches(&model, &shared, || {
+            let count = model.row_count();
+            assert!(count - previous_count <= 2, "more rows than the batch size");
+            previous_count = count;
+        });
+        assert_eq!(model.iter().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+        assert!(!model.0.batch_scheduled.get());
+    }
+
+    #[test]
+    fn remove_oldest_rows_at_once() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let options = StreamModelOptions { max_rows: Some(3), ..Default::default() };
+        let (model, shared) = channel_model(receiver, options);
+        for i in 1..=10 {
+            sender.send(i).unwrap();
+        }
+        drop(sender);
+        run_batches(&model, &shared, || {});
+        assert_eq!(model.iter().collect::<Vec<_>>(), [8, 9, 10]);
+    }
+}
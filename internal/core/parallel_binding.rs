@@ -0,0 +1,434 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+    Bindings whose value is computed on a pool of worker threads.
+
+    The properties and their bindings can only be used from the thread that owns them, so a parallel
+    binding is split in three steps: its inputs are read on the UI thread, where their dependencies
+    are recorded, then the computation runs on a worker thread with a copy of these inputs only, and
+    finally the result is delivered to the property on the UI thread.
+
+    The computation is dispatched as soon as the dependencies of the inputs are marked dirty, right
+    after the property that changed has notified all its dependencies, so the bindings whose inputs
+    changed together run in parallel. Nothing waits for the results: the property keeps its previous
+    value until its result is delivered by the event loop, or by [`deliver_parallel_results()`] before
+    a frame is rendered. The delivery marks the bindings that depend on the property dirty, which
+    dispatches the parallel bindings that read it.
+
+    The thread pool is opt-in: until [`set_parallel_evaluation_threads()`] is called, the
+    computations run on the UI thread when they are dispatched.
+*/
+
+#![warn(missing_docs)]
+
+use crate::api::{ComponentHandle, Weak};
+use crate::properties::{PropertyChangeHandler, PropertyTracker};
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::{Cell, RefCell};
+use core::pin::Pin;
+use std::sync::{mpsc, Arc, Condvar, Mutex};
+
+type Job = Box<dyn FnOnce() + Send>;
+type Computation = Box<dyn FnOnce() -> Box<dyn Any + Send> + Send>;
+type ComputationResult = std::thread::Result<Box<dyn Any + Send>>;
+
+/// The results computed by the worker threads, with the id and generation of their binding
+#[derive(Default)]
+struct Results {
+    queue: Mutex<Vec<(u64, u64, ComputationResult)>>,
+    /// Notified when a result is pushed to the queue
+    condvar: Condvar,
+}
+
+struct ThreadPool {
+    sender: Option<mpsc::Sender<Job>>,
+    threads: Vec<std::thread::JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    fn new(count: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let threads = (0..count)
+            .map(|idx| {
+                let receiver = receiver.clone();
+                std::thread::Builder::new()
+                    .name(format!("slint-binding-{}", idx))
+                    .spawn(move || loop {
+                        let job = receiver.lock().unwrap().recv();
+                        match job {
+                            Ok(job) => job(),
+                            Err(_) => break,
+                        }
+                    })
+                    .expect("could not spawn a thread for the parallel bindings")
+            })
+            .collect();
+        Self { sender: Some(sender), threads }
+    }
+
+    /// Runs the computation on a worker thread, and pushes its result to `results`. Doesn't wait
+    /// for the computation.
+    fn spawn(&self, id: u64, generation: u64, computation: Computation, results: Arc<Results>) {
+        let job = Box::new(move || {
+            let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(computation));
+            results.queue.lock().unwrap().push((id, generation, result));
+            results.condvar.notify_all();
+            if let Some(backend) = crate::backend::instance() {
+                backend.post_event(Box::new(deliver_parallel_results));
+            }
+        });
+        self.sender.as_ref().unwrap().send(job).unwrap();
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        // The threads stop once the channel is closed
+        self.sender.take();
+        for thread in self.threads.drain(..) {
+            thread.join().ok();
+        }
+    }
+}
+
+trait ParallelBindingEntry {
+    /// Reads the inputs, and records their dependencies. Returns the generation of the
+    /// computation, and the computation to run on a worker thread.
+    fn prepare(&self) -> (u64, Computation);
+    /// Delivers the result of the computation of the given generation
+    fn apply(&self, generation: u64, result: Box<dyn Any + Send>);
+}
+
+#[derive(Default)]
+struct ParallelEvaluation {
+    entries: RefCell<BTreeMap<u64, alloc::rc::Weak<dyn ParallelBindingEntry>>>,
+    next_id: Cell<u64>,
+    /// The bindings whose inputs were marked dirty, in order
+    pending: RefCell<Vec<u64>>,
+    dispatching: Cell<bool>,
+    /// The number of computations that were spawned and whose result wasn't delivered yet
+    in_flight: Cell<usize>,
+    results: Arc<Results>,
+    pool: RefCell<Option<ThreadPool>>,
+}
+
+thread_local!(static PARALLEL_EVALUATION: ParallelEvaluation = Default::default());
+
+/// Queues the dispatch of a parallel binding when the dependencies of its inputs are marked dirty
+struct DispatchNotifier(u64);
+
+impl PropertyChangeHandler for DispatchNotifier {
+    fn notify(&self) {
+        // The inputs can't be read while the dependencies are being marked dirty, so the binding
+        // is dispatched once the property that changed has notified all its dependencies
+        PARALLEL_EVALUATION.with(|evaluation| evaluation.pending.borrow_mut().push(self.0))
+    }
+}
+
+/// Dispatches the parallel bindings whose inputs were marked dirty.
+///
+/// This is called by the property system after the dependencies of a property that changed were
+/// marked dirty.
+pub(crate) fn dispatch_pending_parallel_bindings() {
+    PARALLEL_EVALUATION.with(|evaluation| {
+        if evaluation.dispatching.get() || evaluation.pending.borrow().is_empty() {
+            // The bindings dispatched while delivering a result are dispatched by the outer loop
+            return;
+        }
+        struct ResetDispatching<'a>(&'a Cell<bool>);
+        impl Drop for ResetDispatching<'_> {
+            fn drop(&mut self) {
+                self.0.set(false);
+            }
+        }
+        evaluation.dispatching.set(true);
+        let _reset = ResetDispatching(&evaluation.dispatching);
+        loop {
+            let pending = core::mem::take(&mut *evaluation.pending.borrow_mut());
+            if pending.is_empty() {
+                break;
+            }
+            for id in pending {
+                let entry = evaluation.entries.borrow().get(&id).and_then(|entry| entry.upgrade());
+                if let Some(entry) = entry {
+                    let (generation, computation) = entry.prepare();
+                    if let Some(pool) = &*evaluation.pool.borrow() {
+                        evaluation.in_flight.set(evaluation.in_flight.get() + 1);
+                        pool.spawn(id, generation, computation, evaluation.results.clone());
+                        continue;
+                    }
+                    entry.apply(generation, computation());
+                }
+            }
+        }
+    })
+}
+
+/// Sets the number of worker threads that compute the value of the parallel bindings.
+///
+/// With 0, which is the default, the bindings are computed on the UI thread. This must be called
+/// from the thread that runs the event loop.
+pub fn set_parallel_evaluation_threads(count: usize) {
+    PARALLEL_EVALUATION.with(|evaluation| {
+        let pool = (count > 0).then(|| ThreadPool::new(count));
+        // Waits for the previous threads outside of the borrow
+        let previous = core::mem::replace(&mut *evaluation.pool.borrow_mut(), pool);
+        drop(previous);
+    })
+}
+
+/// Delivers the results of the parallel bindings that were computed since the last call, without
+/// waiting for the computations that are still running.
+///
+/// The worker threads post this to the event loop when a result is ready, and it's called before
+/// rendering a window. A panic of a computation is raised here, like for the other bindings.
+pub fn deliver_parallel_results() {
+    PARALLEL_EVALUATION.with(|evaluation| {
+        let results = core::mem::take(&mut *evaluation.results.queue.lock().unwrap());
+        evaluation.in_flight.set(evaluation.in_flight.get() - results.len());
+        for (id, generation, result) in results {
+            let result = result.unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            let entry = evaluation.entries.borrow().get(&id).and_then(|entry| entry.upgrade());
+            if let Some(entry) = entry {
+                entry.apply(generation, result);
+            }
+        }
+    })
+}
+
+/// Blocks until the results of all the parallel bindings that were dispatched are delivered,
+/// including the results of the bindings that are dispatched by these deliveries.
+///
+/// The event loop doesn't need this, since it delivers the results as they come: this is for the
+/// tests and the programs that don't run an event loop.
+pub fn wait_for_parallel_bindings() {
+    loop {
+        deliver_parallel_results();
+        let (in_flight, results) = PARALLEL_EVALUATION
+            .with(|evaluation| (evaluation.in_flight.get(), evaluation.results.clone()));
+        if in_flight == 0 {
+            return;
+        }
+        let queue = results.queue.lock().unwrap();
+        drop(results.condvar.wait_while(queue, |queue| queue.is_empty()).unwrap());
+    }
+}
+
+/// The state of a parallel binding, shared between the binding of the property and the queue
+pub(crate) struct ParallelBindingState<I, T> {
+    id: u64,
+    inputs: Box<dyn Fn() -> I>,
+    compute: Arc<dyn Fn(I) -> T + Send + Sync>,
+    deliver: Box<dyn Fn(T)>,
+    /// Records the dependencies of the inputs
+    tracker: Pin<Box<PropertyTracker<DispatchNotifier>>>,
+    /// Incremented at each dispatch, so that the results of older computations are dropped
+    generation: Cell<u64>,
+}
+
+impl<I: Send + 'static, T: Send + 'static> ParallelBindingState<I, T> {
+    /// Registers the binding, and queues its first dispatch. `deliver` is called with the results
+    /// on the UI thread.
+    pub(crate) fn new(
+        inputs: impl Fn() -> I + 'static,
+        compute: impl Fn(I) -> T + Send + Sync + 'static,
+        deliver: impl Fn(T) + 'static,
+    ) -> Rc<Self> {
+        let id = PARALLEL_EVALUATION.with(|evaluation| {
+            let id = evaluation.next_id.get();
+            evaluation.next_id.set(id + 1);
+            id
+        });
+        let state = Rc::new(Self {
+            id,
+            inputs: Box::new(inputs),
+            compute: Arc::new(compute),
+            deliver: Box::new(deliver),
+            tracker: Box::pin(PropertyTracker::new_with_change_handler(DispatchNotifier(id))),
+            generation: Cell::new(0),
+        });
+        let entry = Rc::downgrade(&(state.clone() as Rc<dyn ParallelBindingEntry>));
+        PARALLEL_EVALUATION.with(|evaluation| {
+            evaluation.entries.borrow_mut().insert(id, entry);
+            evaluation.pending.borrow_mut().push(id);
+        });
+        state
+    }
+}
+
+impl<I: Send + 'static, T: Send + 'static> ParallelBindingEntry for ParallelBindingState<I, T> {
+    fn prepare(&self) -> (u64, Computation) {
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        let inputs = self.tracker.as_ref().evaluate_as_dependency_root(|| (self.inputs)());
+        let compute = self.compute.clone();
+        (generation, Box::new(move || Box::new(compute(inputs)) as Box<dyn Any + Send>))
+    }
+
+    fn apply(&self, generation: u64, result: Box<dyn Any + Send>) {
+        // A newer computation with other inputs is running
+        if generation != self.generation.get() {
+            return;
+        }
+        if let Ok(result) = result.downcast::<T>() {
+            (self.deliver)(*result);
+        }
+    }
+}
+
+impl<I, T> Drop for ParallelBindingState<I, T> {
+    fn drop(&mut self) {
+        PARALLEL_EVALUATION
+            .try_with(|evaluation| evaluation.entries.borrow_mut().remove(&self.id))
+            .ok();
+    }
+}
+
+/// Sets a property of a component to the result of a computation that runs on a worker thread.
+///
+/// The `inputs` function reads the properties the computation depends on, on the UI thread, and
+/// returns them as a value that can be sent to another thread. When any of these properties
+/// changes, `compute` is dispatched with the new inputs to a worker thread, and its result is
+/// passed to `set` on the UI thread when the event loop delivers it. The bindings whose inputs
+/// changed at the same time are computed in parallel.
+///
+/// `compute` can't access the component nor its properties: it only sees the inputs. The worker
+/// threads are enabled with [`set_parallel_evaluation_threads()`]. The computations stop when the
+/// `ParallelBinding` is dropped, or when the component is destroyed. The properties of the Rust code
+/// can also have a parallel binding, with [`crate::Property::set_parallel_binding()`].
+///
+/// # Example
+/// ```rust
+/// # i_slint_backend_testing::init();
+/// slint::slint! { MyApp := Window { property <int> count; property <int> sum; } }
+/// slint::set_parallel_evaluation_threads(2);
+/// let app = MyApp::new();
+/// let _binding = slint::ParallelBinding::new(
+///     app.as_weak(),
+///     |app| app.get_count(),
+///     |count| (1..=count).sum(),
+///     MyApp::set_sum,
+/// );
+///
+/// app.set_count(100);
+/// // The event loop delivers the result once it's computed
+/// slint::wait_for_parallel_bindings();
+/// assert_eq!(app.get_sum(), 5050);
+/// ```
+#[must_use = "the computations stop when the ParallelBinding is dropped"]
+pub struct ParallelBinding {
+    _state: Rc<dyn Any>,
+}
+
+impl ParallelBinding {
+    /// Creates a binding that sets the property of `component` with `set` to the result of
+    /// `compute`, called with the value returned by `inputs`.
+    pub fn new<C: ComponentHandle + 'static, I: Send + 'static, T: Send + 'static>(
+        component: Weak<C>,
+        inputs: impl Fn(&C) -> I + 'static,
+        compute: impl Fn(I) -> T + Send + Sync + 'static,
+        set: impl Fn(&C, T) + 'static,
+    ) -> Self {
+        let state = ParallelBindingState::new(
+            {
+                let component = component.clone();
+                move || component.upgrade().map(|component| inputs(&component))
+            },
+            move |inputs: Option<I>| inputs.map(&compute),
+            move |result: Option<T>| {
+                if let (Some(component), Some(result)) = (component.upgrade(), result) {
+                    set(&component, result)
+                }
+            },
+        );
+        dispatch_pending_parallel_bindings();
+        Self { _state: state }
+    }
+}
+
+#[test]
+fn test_parallel_bindings_dispatched_from_dirty_walk() {
+    use crate::properties::Property;
+    set_parallel_evaluation_threads(2);
+    let a = Rc::pin(Property::new(1));
+    let b = Rc::pin(Property::new(10));
+    let double_a = Rc::pin(Property::new(0));
+    let double_b = Rc::pin(Property::new(0));
+    let (sender, receiver) = mpsc::channel::<()>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    {
+        let a = a.clone();
+        let receiver = receiver.clone();
+        double_a.as_ref().set_parallel_binding(
+            move || a.as_ref().get(),
+            move |a| {
+                // Blocks the worker until the test lets it go
+                receiver.lock().unwrap().recv().ok();
+                a * 2
+            },
+        );
+    }
+    {
+        let b = b.clone();
+        double_b.as_ref().set_parallel_binding(move || b.as_ref().get(), |b| b * 2);
+    }
+    sender.send(()).unwrap();
+    wait_for_parallel_bindings();
+    assert_eq!(double_a.as_ref().get(), 2);
+    assert_eq!(double_b.as_ref().get(), 20);
+
+    // The computation is dispatched by `set`, and nothing waits for the result
+    a.set(2);
+    b.set(20);
+    assert_eq!(double_a.as_ref().get(), 2);
+    sender.send(()).unwrap();
+    wait_for_parallel_bindings();
+    assert_eq!(double_a.as_ref().get(), 4);
+    assert_eq!(double_b.as_ref().get(), 40);
+
+    // Only the result of the last dispatch is delivered
+    a.set(3);
+    a.set(4);
+    sender.send(()).unwrap();
+    sender.send(()).unwrap();
+    wait_for_parallel_bindings();
+    assert_eq!(double_a.as_ref().get(), 8);
+    set_parallel_evaluation_threads(0);
+}
+
+#[test]
+fn test_parallel_binding_depending_on_parallel_binding() {
+    use crate::properties::Property;
+    set_parallel_evaluation_threads(2);
+    let count = Rc::pin(Property::new(3));
+    let sum = Rc::pin(Property::new(0));
+    let label = Rc::pin(Property::new(alloc::string::String::new()));
+    {
+        let count = count.clone();
+        sum.as_ref().set_parallel_binding(move || count.as_ref().get(), |count| (1..=count).sum());
+    }
+    {
+        let sum = sum.clone();
+        label.as_ref().set_parallel_binding(move || sum.as_ref().get(), |sum| format!("{}", sum));
+    }
+    wait_for_parallel_bindings();
+    assert_eq!(label.as_ref().get(), "6");
+
+    // The delivery of the sum dispatches the binding that reads it
+    count.set(4);
+    wait_for_parallel_bindings();
+    assert_eq!(sum.as_ref().get(), 10);
+    assert_eq!(label.as_ref().get(), "10");
+
+    // Without threads, the bindings are computed when they are dispatched
+    set_parallel_evaluation_threads(0);
+    count.set(5);
+    assert_eq!(sum.as_ref().get(), 15);
+    assert_eq!(label.as_ref().get(), "15");
+}
@@ -0,0 +1,611 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+This module contains the API to integrate Slint with a custom platform: an application that
+provides its own window surfaces, feeds the input events and drives the timers and the drawing
+from its own event loop, for example when running Slint inside of a game engine.
+
+The application implements the [`Platform`] trait and installs it with [`set_platform()`]
+before creating any component. Each window of a component is then backed by a
+[`WindowAdapter`] returned by [`Platform::create_window_adapter()`], and the adapter selects
+the [`Renderer`] that measures the text for that window.
+*/
+
+#![warn(missing_docs)]
+
+use alloc::boxed::Box;
+use alloc::rc::{Rc, Weak};
+use alloc::string::String;
+use core::pin::Pin;
+
+use crate::component::ComponentRc;
+use crate::graphics::{FontRequest, Point, Rect, Size};
+use crate::input::{KeyEvent, KeyEventType, KeyboardModifiers, MouseEvent};
+use crate::item_rendering::ItemRenderer;
+use crate::items::{ItemRef, PointerEventButton, ScrollEventPhase, TextInput};
+use crate::window::{PlatformWindow, PopupWindow, PopupWindowLocation};
+use crate::{Coord, SharedString};
+
+/// The interface implemented by applications that integrate Slint with their own platform.
+///
+/// Only [`Self::create_window_adapter()`] is mandatory. The other functions have default
+/// implementations, suitable for applications that drive the event loop themselves.
+pub trait Platform: Send + Sync {
+    /// Creates the adapter for a new window. `window` is a weak reference to the Slint window
+    /// it is associated with, used to dispatch the events and to render its contents.
+    fn create_window_adapter(&self, window: WindowWeak) -> Rc<dyn WindowAdapter>;
+
+    /// Spins an event loop until [`Self::quit_event_loop()`] is called. This is called by
+    /// functions like `ComponentHandle::run()`. On each iteration, the implementation calls
+    /// [`update_timers_and_animations()`], which also calls the events sent with the default
+    /// implementation of [`Self::post_event()`], and it calls
+    /// [`invoke_idle_callbacks()`](crate::event_loop::invoke_idle_callbacks) before it waits for
+    /// the next event.
+    ///
+    /// The default implementation does only that, and waits until the next timer or the next
+    /// posted event. It doesn't forward any input nor draw the windows: a platform with windows on
+    /// screen implements its own event loop, or the [`WindowAdapter`] draws its window when
+    /// [`WindowAdapter::request_redraw()`] is called.
+    fn run_event_loop(&self) {
+        default_event_loop::run()
+    }
+
+    /// Exits the event loop started with [`Self::run_event_loop()`]. The default implementation
+    /// exits the default event loop.
+    fn quit_event_loop(&self) {
+        default_event_loop::quit()
+    }
+
+    /// Sends the `event` from any thread to the thread running the event loop, where it must be
+    /// called. This is used by `slint::invoke_from_event_loop()`, and by the models that are
+    /// filled from other threads.
+    ///
+    /// The default implementation queues the event, and the next call to
+    /// [`update_timers_and_animations()`] calls it.
+    fn post_event(&self, event: Box<dyn FnOnce() + Send>) {
+        default_event_loop::post_event(event)
+    }
+
+    /// Returns the time elapsed since an arbitrary point in the past, used by the timers and the
+    /// animations. The default implementation uses the system clock when the standard library
+    /// is available.
+    fn duration_since_start(&self) -> core::time::Duration {
+        #[cfg(feature = "std")]
+        {
+            let the_beginning = *crate::backend::INITIAL_INSTANT.get_or_init(instant::Instant::now);
+            instant::Instant::now() - the_beginning
+        }
+        #[cfg(not(feature = "std"))]
+        core::time::Duration::ZERO
+    }
+
+    /// Puts the text on the clipboard. The default implementation does nothing.
+    fn set_clipboard_text(&self, _text: &str) {}
+
+    /// Returns the text on the clipboard, if any. The default implementation returns `None`.
+    fn clipboard_text(&self) -> Option<String> {
+        None
+    }
+}
+
+/// The interface between a Slint window and the surface it is shown on.
+pub trait WindowAdapter {
+    /// Called when the window is shown, to map the surface.
+    fn show(&self) {}
+    /// Called when the window is hidden, to unmap the surface.
+    fn hide(&self) {}
+    /// Called when the contents of the window changed and the application needs to call
+    /// [`render()`] before presenting the next frame. This is typically an asynchronous request.
+    fn request_redraw(&self) {}
+    /// Returns the renderer used for this window.
+    fn renderer(&self) -> &dyn Renderer;
+}
+
+/// The renderer of a window, which knows the metrics of the fonts. The drawing itself is done
+/// by an [`ItemRenderer`] passed to [`render()`].
+pub trait Renderer {
+    /// Returns the size of the given text in logical pixels. When set, `max_width` means that
+    /// the text needs to be wrapped so that it does not go further than that.
+    /// The `font_request` already contains the default font properties of the window.
+    fn text_size(
+        &self,
+        font_request: FontRequest,
+        text: &str,
+        max_width: Option<Coord>,
+        scale_factor: f32,
+    ) -> Size;
+
+    /// Returns the width of the widest word of the given text in logical pixels, which is the
+    /// narrowest width the text can be wrapped to without breaking a word.
+    fn text_min_content_width(
+        &self,
+        font_request: FontRequest,
+        text: &str,
+        scale_factor: f32,
+    ) -> Coord {
+        text.split_whitespace()
+            .map(|word| self.text_size(font_request.clone(), word, None, scale_factor).width)
+            .fold(0 as Coord, |widest, width| if width > widest { width } else { widest })
+    }
+
+    /// Returns the (UTF-8) byte offset in the text of the `text_input` that is visually nearest
+    /// to `pos`, in logical pixels. This is used to place the cursor when clicking in a text field.
+    fn text_input_byte_offset_for_position(
+        &self,
+        text_input: Pin<&TextInput>,
+        pos: Point,
+        scale_factor: f32,
+    ) -> usize;
+
+    /// Returns the rectangle of the cursor placed before `byte_offset`, the opposite of
+    /// [`Self::text_input_byte_offset_for_position()`]. It is one logical pixel wide and ends at
+    /// the baseline.
+    fn text_input_cursor_rect_for_byte_offset(
+        &self,
+        text_input: Pin<&TextInput>,
+        byte_offset: usize,
+        scale_factor: f32,
+    ) -> Rect;
+
+    /// Called when items are destroyed, so that the graphics resources cached for them can be freed.
+    fn free_graphics_resources(&self, _items: &mut dyn Iterator<Item = Pin<ItemRef<'_>>>) {}
+}
+
+/// A weak reference to a Slint window, given to [`Platform::create_window_adapter()`].
+#[derive(Clone)]
+pub struct WindowWeak(Weak<crate::window::Window>);
+
+impl WindowWeak {
+    /// Returns the window, or `None` if it was already destroyed.
+    pub fn upgrade(&self) -> Option<crate::api::Window> {
+        self.0.upgrade().map(Into::into)
+    }
+}
+
+/// The events that an application feeds to a window with [`crate::api::Window::dispatch_event()`].
+/// All positions and sizes are in logical pixels.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum WindowEvent {
+    /// A pointer button was pressed.
+    PointerPressed {
+        /// The position of the pointer
+        position: Point,
+        /// The button that was pressed
+        button: PointerEventButton,
+    },
+    /// A pointer button was released.
+    PointerReleased {
+        /// The position of the pointer
+        position: Point,
+        /// The button that was released
+        button: PointerEventButton,
+    },
+    /// The pointer moved.
+    PointerMoved {
+        /// The new position of the pointer
+        position: Point,
+    },
+    /// The wheel was turned, or the touchpad was swiped.
+    PointerScrolled {
+        /// The position of the pointer
+        position: Point,
+        /// The amount of pixels to scroll
+        delta: Point,
+        /// Where the event is in a touchpad scroll gesture, or `ScrollEventPhase::none` for a wheel
+        phase: ScrollEventPhase,
+    },
+    /// The pointer left the window.
+    PointerExited,
+    /// A key was pressed.
+    KeyPressed {
+        /// The unicode representation of the key, or one of the constants of `slint::platform::key_codes`
+        text: SharedString,
+        /// The modifiers active at the time of the event
+        modifiers: KeyboardModifiers,
+        /// The platform specific code of the physical key, or 0 if unknown
+        scan_code: i32,
+        /// True when the event is a repetition because the key is held down
+        is_auto_repeat: bool,
+    },
+    /// A key was released. It is sent once, even if the key press was repeated.
+    KeyReleased {
+        /// The unicode representation of the key, or one of the constants of `slint::platform::key_codes`
+        text: SharedString,
+        /// The modifiers active at the time of the event
+        modifiers: KeyboardModifiers,
+        /// The platform specific code of the physical key, or 0 if unknown
+        scan_code: i32,
+    },
+    /// The text being composed by an input method changed. It is shown at the cursor of the
+    /// focused `TextInput`, but is not part of its text yet.
+    CompositionUpdated {
+        /// The text being composed, or an empty string when the composition was cancelled
+        text: SharedString,
+    },
+    /// The input method finished the composition, and the text is inserted in the focused `TextInput`.
+    CompositionCommitted {
+        /// The composed text
+        text: SharedString,
+    },
+    /// The surface of the window was resized.
+    Resized {
+        /// The new size of the window
+        size: Size,
+    },
+    /// The ratio between the physical and the logical pixels changed.
+    ScaleFactorChanged {
+        /// The new scale factor
+        scale_factor: f32,
+    },
+    /// The window gained or lost the keyboard focus.
+    FocusChanged {
+        /// Whether the window has the focus
+        has_focus: bool,
+    },
+    /// The user asked to close the window. The window is hidden unless the `close_requested`
+    /// callback keeps it shown.
+    CloseRequested,
+}
+
+pub(crate) fn dispatch_event(window: &Rc<crate::window::Window>, event: WindowEvent) {
+    let key_event = |event_type, text, modifiers| KeyEvent {
+        event_type,
+        text,
+        modifiers,
+        ..Default::default()
+    };
+    match event {
+        WindowEvent::PointerPressed { position, button } => {
+            window.clone().process_mouse_input(MouseEvent::MousePressed { pos: position, button })
+        }
+        WindowEvent::PointerReleased { position, button } => {
+            window.clone().process_mouse_input(MouseEvent::MouseReleased { pos: position, button })
+        }
+        WindowEvent::PointerMoved { position } => {
+            window.clone().process_mouse_input(MouseEvent::MouseMoved { pos: position })
+        }
+        WindowEvent::PointerScrolled { position, delta, phase } => window
+            .clone()
+            .process_mouse_input(MouseEvent::MouseWheel { pos: position, delta, phase }),
+        WindowEvent::PointerExited => window.clone().process_mouse_input(MouseEvent::MouseExit),
+        WindowEvent::KeyPressed { text, modifiers, scan_code, is_auto_repeat } => {
+            let event = KeyEvent {
+                scan_code,
+                is_auto_repeat,
+                ..key_event(KeyEventType::KeyPressed, text, modifiers)
+            };
+            window.clone().process_key_input(&event)
+        }
+        WindowEvent::KeyReleased { text, modifiers, scan_code } => {
+            let event =
+                KeyEvent { scan_code, ..key_event(KeyEventType::KeyReleased, text, modifiers) };
+            window.clone().process_key_input(&event)
+        }
+        WindowEvent::CompositionUpdated { text } => {
+            let event = key_event(KeyEventType::UpdateComposition, text, Default::default());
+            window.clone().process_key_input(&event)
+        }
+        WindowEvent::CompositionCommitted { text } => {
+            let event = key_event(KeyEventType::CommitComposition, text, Default::default());
+            window.clone().process_key_input(&event)
+        }
+        WindowEvent::Resized { size } => window.set_window_item_geometry(size.width, size.height),
+        WindowEvent::ScaleFactorChanged { scale_factor } => window.set_scale_factor(scale_factor),
+        WindowEvent::FocusChanged { has_focus } => window.clone().set_focus(has_focus),
+        WindowEvent::CloseRequested => {
+            if window.request_close() {
+                window.hide();
+            }
+        }
+    }
+}
+
+/// The error returned by [`set_platform()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SetPlatformError {
+    /// A platform, or one of the built-in backends, was already set.
+    AlreadySet,
+}
+
+/// Installs the platform used by Slint for all the windows. This must be called before
+/// creating any component, and can only be called once.
+pub fn set_platform(platform: Box<dyn Platform + 'static>) -> Result<(), SetPlatformError> {
+    crate::backend::set_instance(Box::new(PlatformBackend(platform)))
+        .map_err(|_| SetPlatformError::AlreadySet)
+}
+
+/// Calls the events sent with the default implementation of [`Platform::post_event()`] and the
+/// timers that expired, and advances the animations. Applications driving their own event loop
+/// call this function on every iteration, before rendering the windows.
+pub fn update_timers_and_animations() {
+    default_event_loop::take_posted_events().into_iter().for_each(|event| event());
+    crate::timers::TimerList::maybe_activate_timers();
+    crate::animations::update_animations();
+}
+
+/// Returns how long the application can wait before it needs to call
+/// [`update_timers_and_animations()`] again, or `None` if there are no active timers.
+/// The duration is zero while animations are running.
+pub fn duration_until_next_timer_update() -> Option<core::time::Duration> {
+    if crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| driver.has_active_animations()) {
+        return Some(core::time::Duration::ZERO);
+    }
+    crate::timers::TimerList::next_timeout().map(|timeout| {
+        let now = crate::animations::Instant::now();
+        core::time::Duration::from_millis(timeout.0.saturating_sub(now.0))
+    })
+}
+
+/// Renders the contents of the `window` with the `item_renderer`, from the top left corner of
+/// the surface. The items that did not change since the last call are still drawn.
+pub fn render(window: &crate::api::Window, item_renderer: &mut dyn ItemRenderer) {
+    use crate::window::WindowHandleAccess;
+    let window = window.window_handle().clone();
+    window.update_window_properties();
+    window.draw_contents(|components| {
+        for (component, origin) in components {
+            crate::item_rendering::render_component_items(component, item_renderer, *origin);
+        }
+    });
+}
+
+/// The state of the default implementation of the event loop functions of [`Platform`]
+mod default_event_loop {
+    use alloc::boxed::Box;
+    use alloc::vec::Vec;
+
+    type PostedEvent = Box<dyn FnOnce() + Send>;
+
+    /// The events posted from any thread, and whether the event loop must exit
+    #[cfg(feature = "std")]
+    #[derive(Default)]
+    struct EventLoopState {
+        state: std::sync::Mutex<(Vec<PostedEvent>, bool)>,
+        wakeup: std::sync::Condvar,
+    }
+
+    #[cfg(feature = "std")]
+    static STATE: once_cell::sync::Lazy<EventLoopState> =
+        once_cell::sync::Lazy::new(Default::default);
+
+    // Without the standard library, there is only one thread
+    #[cfg(all(not(feature = "std"), feature = "unsafe_single_core"))]
+    use crate::unsafe_single_core::thread_local;
+    #[cfg(not(feature = "std"))]
+    thread_local!(static STATE: core::cell::RefCell<(Vec<PostedEvent>, bool)> = Default::default());
+
+    fn with_state<R>(f: impl FnOnce(&mut (Vec<PostedEvent>, bool)) -> R) -> R {
+        #[cfg(feature = "std")]
+        {
+            let result = f(&mut STATE.state.lock().unwrap());
+            STATE.wakeup.notify_all();
+            result
+        }
+        #[cfg(not(feature = "std"))]
+        STATE.with(|state| f(&mut state.borrow_mut()))
+    }
+
+    pub(super) fn post_event(event: PostedEvent) {
+        with_state(|(events, _)| events.push(event))
+    }
+
+    pub(super) fn quit() {
+        with_state(|(_, quit)| *quit = true)
+    }
+
+    pub(super) fn take_posted_events() -> Vec<PostedEvent> {
+        with_state(|(events, _)| core::mem::take(events))
+    }
+
+    pub(super) fn run() {
+        loop {
+            super::update_timers_and_animations();
+            crate::event_loop::invoke_idle_callbacks();
+            if with_state(|(_, quit)| core::mem::take(quit)) {
+                break;
+            }
+            #[cfg(feature = "std")]
+            {
+                let timeout = super::duration_until_next_timer_update();
+                let state = STATE.state.lock().unwrap();
+                let wait =
+                    |(events, quit): &mut (Vec<PostedEvent>, bool)| events.is_empty() && !*quit;
+                let _state = match timeout {
+                    Some(timeout) => {
+                        STATE.wakeup.wait_timeout_while(state, timeout, wait).unwrap().0
+                    }
+                    None => STATE.wakeup.wait_while(state, wait).unwrap(),
+                };
+            }
+        }
+    }
+}
+
+struct PlatformBackend(Box<dyn Platform>);
+
+impl crate::backend::Backend for PlatformBackend {
+    fn create_window(&'static self) -> Rc<crate::window::Window> {
+        crate::window::Window::new(|window| {
+            Rc::new(WindowAdapterBridge {
+                adapter: self.0.create_window_adapter(WindowWeak(window.clone())),
+                window: window.clone(),
+            })
+        })
+    }
+
+    fn run_event_loop(&'static self, _behavior: crate::backend::EventLoopQuitBehavior) {
+        let _running_guard = crate::event_loop::enter_event_loop();
+        self.0.run_event_loop()
+    }
+
+    fn quit_event_loop(&'static self) {
+        self.0.quit_event_loop()
+    }
+
+    #[cfg(feature = "std")]
+    fn register_font_from_memory(
+        &'static self,
+        _data: &'static [u8],
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("Registering fonts is not supported by custom platforms".into())
+    }
+
+    #[cfg(feature = "std")]
+    fn register_font_from_path(
+        &'static self,
+        _path: &std::path::Path,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        Err("Registering fonts is not supported by custom platforms".into())
+    }
+
+    fn set_clipboard_text(&'static self, text: String) {
+        self.0.set_clipboard_text(&text)
+    }
+
+    fn clipboard_text(&'static self) -> Option<String> {
+        self.0.clipboard_text()
+    }
+
+    fn post_event(&'static self, event: Box<dyn FnOnce() + Send>) {
+        self.0.post_event(event)
+    }
+
+    #[cfg(feature = "swrenderer")]
+    fn register_bitmap_font(&'static self, font_data: &'static crate::graphics::BitmapFont) {
+        crate::swrenderer::register_bitmap_font(font_data)
+    }
+
+    fn image_size(&'static self, image: &crate::graphics::Image) -> crate::graphics::IntSize {
+        // Only the images embedded in the binary can be used, there is no image loader
+        let inner: &crate::ImageInner = image.into();
+        match inner {
+            crate::ImageInner::EmbeddedImage(buffer) => buffer.size(),
+            crate::ImageInner::StaticTextures(crate::StaticTextures { original_size, .. }) => {
+                *original_size
+            }
+            _ => Default::default(),
+        }
+    }
+
+    fn duration_since_start(&'static self) -> core::time::Duration {
+        self.0.duration_since_start()
+    }
+}
+
+struct WindowAdapterBridge {
+    adapter: Rc<dyn WindowAdapter>,
+    window: Weak<crate::window::Window>,
+}
+
+impl WindowAdapterBridge {
+    fn scale_factor(&self) -> f32 {
+        self.window.upgrade().map_or(1., |window| window.scale_factor())
+    }
+}
+
+impl PlatformWindow for WindowAdapterBridge {
+    fn show(self: Rc<Self>) {
+        self.adapter.show()
+    }
+
+    fn hide(self: Rc<Self>) {
+        self.adapter.hide()
+    }
+
+    fn request_redraw(&self) {
+        self.adapter.request_redraw()
+    }
+
+    fn free_graphics_resources<'a>(&self, items: &mut dyn Iterator<Item = Pin<ItemRef<'a>>>) {
+        self.adapter.renderer().free_graphics_resources(items)
+    }
+
+    fn show_popup(&self, popup: &ComponentRc, position: Point) {
+        let window = match self.window.upgrade() {
+            Some(window) => window,
+            None => return,
+        };
+        let size = window.set_active_popup(PopupWindow {
+            location: PopupWindowLocation::ChildWindow(position),
+            component: popup.clone(),
+        });
+
+        let popup = ComponentRc::borrow_pin(popup);
+        let popup_root = popup.as_ref().get_item_ref(0);
+        if let Some(window_item) = ItemRef::downcast_pin(popup_root) {
+            let width_property =
+                crate::items::WindowItem::FIELD_OFFSETS.width.apply_pin(window_item);
+            let height_property =
+                crate::items::WindowItem::FIELD_OFFSETS.height.apply_pin(window_item);
+            width_property.set(size.width);
+            height_property.set(size.height);
+        }
+    }
+
+    fn close_popup(&self, _popup: &PopupWindow) {
+        self.adapter.request_redraw()
+    }
+
+    fn request_window_properties_update(&self) {
+        // The properties are updated by render()
+        self.adapter.request_redraw()
+    }
+
+    fn apply_window_properties(&self, _window_item: Pin<&crate::items::WindowItem>) {}
+
+    fn apply_geometry_constraint(
+        &self,
+        _constraints_horizontal: crate::layout::LayoutInfo,
+        _constraints_vertical: crate::layout::LayoutInfo,
+    ) {
+    }
+
+    fn set_mouse_cursor(&self, _cursor: crate::items::MouseCursor) {}
+
+    fn text_size(&self, font_request: FontRequest, text: &str, max_width: Option<Coord>) -> Size {
+        let font_request = match self.window.upgrade() {
+            Some(window) => font_request.merge(&window.default_font_properties()),
+            None => font_request,
+        };
+        self.adapter.renderer().text_size(font_request, text, max_width, self.scale_factor())
+    }
+
+    fn text_min_content_width(&self, font_request: FontRequest, text: &str) -> Coord {
+        let font_request = match self.window.upgrade() {
+            Some(window) => font_request.merge(&window.default_font_properties()),
+            None => font_request,
+        };
+        self.adapter.renderer().text_min_content_width(font_request, text, self.scale_factor())
+    }
+
+    fn text_input_byte_offset_for_position(
+        &self,
+        text_input: Pin<&TextInput>,
+        pos: Point,
+    ) -> usize {
+        self.adapter.renderer().text_input_byte_offset_for_position(
+            text_input,
+            pos,
+            self.scale_factor(),
+        )
+    }
+
+    fn text_input_cursor_rect_for_byte_offset(
+        &self,
+        text_input: Pin<&TextInput>,
+        byte_offset: usize,
+    ) -> Rect {
+        self.adapter.renderer().text_input_cursor_rect_for_byte_offset(
+            text_input,
+            byte_offset,
+            self.scale_factor(),
+        )
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+}
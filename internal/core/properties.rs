@@ -6,8 +6,15 @@
 
     The current implementation uses lots of heap allocation but that can be optimized later using
     thin dst container, and intrusive linked list
+
+    The bindings are evaluated on the thread that owns the properties: the properties, the bindings
+    and their dependency lists are neither `Send` nor `Sync`.
 */
 
+#![cfg_attr(
+    feature = "std",
+    doc = "The computations that can run on other threads are done with the parallel bindings of the [`crate::parallel_binding`] module, which are dispatched while the dependencies are marked dirty."
+)]
 #![allow(unsafe_code)]
 #![warn(missing_docs)]
 
@@ -621,6 +628,9 @@ fn mark_dirty(&self, #[cfg(slint_debug_property)] debug_name: &str) {
             );
             mark_dependencies_dirty(dependencies)
         };
+        run_pending_change_trackers();
+        #[cfg(feature = "std")]
+        crate::parallel_binding::dispatch_pending_parallel_bindings();
     }
 
     fn set_constant(&self) {
@@ -894,6 +904,71 @@ pub fn set_constant(&self) {
     }
 }
 
+#[cfg(feature = "std")]
+impl<T: Clone + Send + 'static> Property<T> {
+    /// Set a binding whose value is computed on a worker thread.
+    ///
+    /// `inputs` is evaluated on this thread, and the properties it reads are the dependencies of
+    /// the binding. As soon as one of them changes, `compute` is dispatched to the thread pool of
+    /// [`crate::parallel_binding::set_parallel_evaluation_threads()`] with the new inputs. The
+    /// property keeps its previous value until the result is delivered on this thread, which marks
+    /// the bindings that depend on this property dirty.
+    ///
+    /// ## Example
+    /// ```
+    /// use std::rc::Rc;
+    /// use i_slint_core::Property;
+    /// use i_slint_core::parallel_binding::wait_for_parallel_bindings;
+    /// let count = Rc::pin(Property::new(3));
+    /// let sum = Rc::pin(Property::<i32>::default());
+    /// sum.as_ref().set_parallel_binding(
+    ///     { let count = count.clone(); move || count.as_ref().get() },
+    ///     |count| (1..=count).sum(),
+    /// );
+    /// wait_for_parallel_bindings();
+    /// assert_eq!(sum.as_ref().get(), 6);
+    /// count.set(100);
+    /// wait_for_parallel_bindings();
+    /// assert_eq!(sum.as_ref().get(), 5050);
+    /// ```
+    pub fn set_parallel_binding<I: Send + 'static>(
+        &self,
+        inputs: impl Fn() -> I + 'static,
+        compute: impl Fn(I) -> T + Send + Sync + 'static,
+    ) {
+        // `delivered` is changed by each result, so that the binding is marked dirty
+        let delivered = Rc::pin(Property::new(0u64));
+        let result = Rc::new(RefCell::new(None));
+        let state = crate::parallel_binding::ParallelBindingState::new(inputs, compute, {
+            let delivered = delivered.clone();
+            let result = result.clone();
+            move |value: T| {
+                *result.borrow_mut() = Some(value);
+                delivered.as_ref().set(delivered.as_ref().get_untracked() + 1);
+            }
+        });
+        // Safety: This will make a binding callable for the type T
+        unsafe {
+            self.handle.set_binding(
+                move |val: *mut ()| {
+                    let _keep_alive = &state;
+                    delivered.as_ref().get();
+                    if let Some(value) = result.borrow_mut().take() {
+                        *(val as *mut T) = value;
+                    }
+                    BindingResult::KeepBinding
+                },
+                #[cfg(slint_debug_property)]
+                self.debug_name.borrow().as_str(),
+            )
+        }
+        self.handle.mark_dirty(
+            #[cfg(slint_debug_property)]
+            self.debug_name.borrow().as_str(),
+        );
+    }
+}
+
 impl<T: Clone + InterpolatedPropertyValue + 'static> Property<T> {
     /// Change the value of this property, by animating (interpolating) from the current property's value
     /// to the specified parameter value. The animation is done according to the parameters described by
@@ -2172,6 +2247,9 @@ pub fn evaluate_if_dirty<R>(self: Pin<&Self>, f: impl FnOnce() -> R) -> Option<R
     pub fn set_dirty(&self) {
         self.holder.dirty.set(true);
         unsafe { mark_dependencies_dirty(self.holder.dependencies.as_ptr() as *mut _) };
+        run_pending_change_trackers();
+        #[cfg(feature = "std")]
+        crate::parallel_binding::dispatch_pending_parallel_bindings();
     }
 
     /// Sets the specified callback handler function, which will be called if any
@@ -2349,6 +2427,201 @@ fn test_nested_property_tracker_evaluate_if_dirty() {
     assert_eq!(r, 12);
 }
 
+trait ChangeTrackerEntry {
+    /// Evaluates the tracked value and calls the handler if it changed
+    fn run(&self);
+}
+
+struct ChangeTrackerNotifier(once_cell::unsync::OnceCell<alloc::rc::Weak<dyn ChangeTrackerEntry>>);
+
+impl PropertyChangeHandler for ChangeTrackerNotifier {
+    fn notify(&self) {
+        // The properties can't be evaluated while the dependencies are being marked dirty,
+        // so the entry is run once the property that changed has notified all its dependencies
+        if let Some(entry) = self.0.get() {
+            PENDING_CHANGE_TRACKERS.with(|pending| pending.entries.borrow_mut().push(entry.clone()))
+        }
+    }
+}
+
+#[derive(Default)]
+struct PendingChangeTrackers {
+    entries: RefCell<alloc::vec::Vec<alloc::rc::Weak<dyn ChangeTrackerEntry>>>,
+    running: Cell<bool>,
+}
+
+#[cfg(all(not(feature = "std"), feature = "unsafe_single_core"))]
+use crate::unsafe_single_core::thread_local;
+
+thread_local!(static PENDING_CHANGE_TRACKERS : PendingChangeTrackers = PendingChangeTrackers::default());
+
+/// Runs the change trackers whose dependencies were marked dirty.
+fn run_pending_change_trackers() {
+    PENDING_CHANGE_TRACKERS.with(|pending| {
+        if pending.running.get() || pending.entries.borrow().is_empty() {
+            // The changes made by the handlers that are running are run by the outer loop
+            return;
+        }
+        struct ResetRunning<'a>(&'a Cell<bool>);
+        impl Drop for ResetRunning<'_> {
+            fn drop(&mut self) {
+                self.0.set(false);
+            }
+        }
+        pending.running.set(true);
+        let _reset = ResetRunning(&pending.running);
+        loop {
+            let entries = core::mem::take(&mut *pending.entries.borrow_mut());
+            if entries.is_empty() {
+                break;
+            }
+            for entry in entries {
+                if let Some(entry) = entry.upgrade() {
+                    entry.run();
+                }
+            }
+        }
+    })
+}
+
+struct ChangeTrackerInner<Data, EvalFn, NotifyFn> {
+    tracker: Pin<Box<PropertyTracker<ChangeTrackerNotifier>>>,
+    data: Data,
+    /// Returns true if the value changed since the last evaluation
+    eval_fn: EvalFn,
+    notify_fn: NotifyFn,
+}
+
+impl<Data, EvalFn: Fn(&Data) -> bool, NotifyFn: Fn(&Data)> ChangeTrackerEntry
+    for ChangeTrackerInner<Data, EvalFn, NotifyFn>
+{
+    fn run(&self) {
+        if !self.tracker.is_dirty() {
+            return;
+        }
+        let changed =
+            self.tracker.as_ref().evaluate_as_dependency_root(|| (self.eval_fn)(&self.data));
+        if changed {
+            evaluate_no_tracking(|| (self.notify_fn)(&self.data));
+        }
+    }
+}
+
+/// Calls a handler when the value returned by a function changes.
+///
+/// Unlike the bindings, which are evaluated lazily, the value is evaluated again as soon as one of
+/// the properties it depends on is changed, and the handler is called before the function that
+/// changed the property returns. The changes made by the handler itself are handled after it returns.
+#[derive(Default)]
+pub struct ChangeTracker {
+    inner: Cell<Option<Rc<dyn ChangeTrackerEntry>>>,
+}
+
+impl ChangeTracker {
+    /// Starts tracking the value returned by `eval_fn`, and calls `notify_fn` when it changes.
+    /// The handler isn't called for the initial value. Any previous tracking is replaced.
+    pub fn init<Data: 'static, T: PartialEq + 'static>(
+        &self,
+        data: Data,
+        eval_fn: impl Fn(&Data) -> T + 'static,
+        notify_fn: impl Fn(&Data) + 'static,
+    ) {
+        let value = RefCell::new(None);
+        self.init_impl(
+            data,
+            move |data: &Data| {
+                let new_value = Some(eval_fn(data));
+                let changed = *value.borrow() != new_value;
+                *value.borrow_mut() = new_value;
+                changed
+            },
+            notify_fn,
+        )
+    }
+
+    fn init_impl<Data: 'static>(
+        &self,
+        data: Data,
+        eval_fn: impl Fn(&Data) -> bool + 'static,
+        notify_fn: impl Fn(&Data) + 'static,
+    ) {
+        let inner = Rc::new(ChangeTrackerInner {
+            tracker: Box::pin(PropertyTracker::new_with_change_handler(ChangeTrackerNotifier(
+                Default::default(),
+            ))),
+            data,
+            eval_fn,
+            notify_fn,
+        });
+        inner.tracker.as_ref().evaluate_as_dependency_root(|| (inner.eval_fn)(&inner.data));
+        let entry: Rc<dyn ChangeTrackerEntry> = inner.clone();
+        // We need to use a OnceCell only so we can have a cycle with the Weak.
+        inner.tracker.holder.binding.0.set(Rc::downgrade(&entry)).ok().unwrap();
+        self.inner.set(Some(entry));
+    }
+}
+
+#[test]
+fn test_change_tracker() {
+    let prop = Rc::pin(Property::new(1));
+    let double = Rc::pin(Property::new(0));
+    {
+        let prop = prop.clone();
+        double.as_ref().set_binding(move || prop.as_ref().get() * 2);
+    }
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let tracker = ChangeTracker::default();
+    tracker.init(
+        (double.clone(), log.clone()),
+        |(double, _)| double.as_ref().get(),
+        |(double, log)| log.borrow_mut().push(double.as_ref().get()),
+    );
+    assert!(log.borrow().is_empty());
+
+    // The handler is called before set returns
+    prop.as_ref().set(2);
+    assert_eq!(*log.borrow(), vec![4]);
+
+    // Not called if the value is the same
+    prop.as_ref().set(2);
+    double.as_ref().mark_dirty();
+    assert_eq!(*log.borrow(), vec![4]);
+
+    prop.as_ref().set(3);
+    assert_eq!(*log.borrow(), vec![4, 6]);
+
+    drop(tracker);
+    prop.as_ref().set(4);
+    assert_eq!(*log.borrow(), vec![4, 6]);
+}
+
+#[test]
+fn test_change_tracker_nested_changes() {
+    let prop = Rc::pin(Property::new(0));
+    let other = Rc::pin(Property::new(0));
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let tracker1 = ChangeTracker::default();
+    tracker1.init(
+        (prop.clone(), other.clone(), log.clone()),
+        |(prop, _, _)| prop.as_ref().get(),
+        |(prop, other, log)| {
+            log.borrow_mut().push(format!("prop {}", prop.as_ref().get()));
+            other.as_ref().set(prop.as_ref().get() * 10);
+            log.borrow_mut().push("prop done".into());
+        },
+    );
+    let tracker2 = ChangeTracker::default();
+    tracker2.init(
+        (other.clone(), log.clone()),
+        |(other, _)| other.as_ref().get(),
+        |(other, log)| log.borrow_mut().push(format!("other {}", other.as_ref().get())),
+    );
+
+    // The handler of the change made by another handler runs after it
+    prop.as_ref().set(1);
+    assert_eq!(*log.borrow(), vec!["prop 1", "prop done", "other 10"]);
+}
+
 #[cfg(feature = "ffi")]
 pub(crate) mod ffi {
     use super::*;
@@ -2804,4 +3077,58 @@ pub struct PropertyTrackerOpaque {
     pub unsafe extern "C" fn slint_property_tracker_drop(handle: *mut PropertyTrackerOpaque) {
         core::ptr::drop_in_place(handle as *mut PropertyTracker);
     }
+
+    #[repr(C)]
+    /// Opaque type representing the ChangeTracker
+    pub struct ChangeTrackerOpaque {
+        inner: usize,
+        vtable: usize,
+    }
+
+    static_assertions::assert_eq_align!(ChangeTrackerOpaque, ChangeTracker);
+    static_assertions::assert_eq_size!(ChangeTrackerOpaque, ChangeTracker);
+
+    /// Initialize the ChangeTracker.
+    /// `out` is assumed to be uninitialized
+    /// slint_change_tracker_drop need to be called after that
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_change_tracker_construct(out: *mut ChangeTrackerOpaque) {
+        core::ptr::write(out as *mut ChangeTracker, ChangeTracker::default());
+    }
+
+    /// Starts tracking the value: `eval_fn` evaluates it and returns true if it changed since the
+    /// last call, and `notify_fn` is called when it changed.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_change_tracker_init(
+        handle: *const ChangeTrackerOpaque,
+        user_data: *mut c_void,
+        drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        eval_fn: extern "C" fn(*mut c_void) -> bool,
+        notify_fn: extern "C" fn(*mut c_void),
+    ) {
+        struct CUserData {
+            user_data: *mut c_void,
+            drop_user_data: Option<extern "C" fn(*mut c_void)>,
+        }
+
+        impl Drop for CUserData {
+            fn drop(&mut self) {
+                if let Some(x) = self.drop_user_data {
+                    x(self.user_data)
+                }
+            }
+        }
+
+        (*(handle as *const ChangeTracker)).init_impl(
+            CUserData { user_data, drop_user_data },
+            move |data| eval_fn(data.user_data),
+            move |data| notify_fn(data.user_data),
+        )
+    }
+
+    /// Destroy handle
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_change_tracker_drop(handle: *mut ChangeTrackerOpaque) {
+        core::ptr::drop_in_place(handle as *mut ChangeTracker);
+    }
 }
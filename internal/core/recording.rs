@@ -0,0 +1,425 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+This module records the input events received by the windows, and the activation of the timers,
+to replay them later. This is used to reproduce a bug that only happens after a precise sequence
+of events, or to run the same session over and over on a device.
+
+The recording is started with [`start_recording()`], or by setting the `SLINT_RECORD_EVENTS`
+environment variable to the name of the file to write. Each event is written on one line,
+prefixed with the time in milliseconds since the start of the recording:
+
+```text
+# slint event recording
+0 move 120 48
+35 press 120 48 left
+80 timers
+112 release 120 48 left
+300 key-press - Hello\u{a}
+```
+
+[`replay()`] feeds the events of an [`EventRecording`] to a window. It doesn't wait between the
+events: the time seen by the timers and the animations is the time of the recording, so that the
+replay gives the same result every time, regardless of the speed of the device.
+*/
+
+#![warn(missing_docs)]
+
+use crate::input::{KeyEvent, KeyboardModifiers, MouseEvent};
+use crate::items::{KeyEventType, PointerEventButton, ScrollEventPhase};
+use crate::window::WindowRc;
+use crate::{Coord, SharedString};
+use core::cell::{Cell, RefCell};
+use core::time::Duration;
+use std::io::Write;
+use std::path::Path;
+
+/// An event of an [`EventRecording`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum RecordedEvent {
+    /// A mouse or touch event, in the coordinates of the window.
+    Mouse(MouseEvent),
+    /// A key event, or a change of the input method composition.
+    Key(KeyEvent),
+    /// The window gained or lost the keyboard focus.
+    Focus(bool),
+    /// Some timers expired and were activated.
+    Timers,
+}
+
+/// An event and the time at which it was received, since the start of the recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedEntry {
+    /// The time since the start of the recording.
+    pub time: Duration,
+    /// The recorded event.
+    pub event: RecordedEvent,
+}
+
+/// The error returned when an [`EventRecording`] can't be read.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum RecordingError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The line, starting at 1, is not a valid event.
+    InvalidLine(usize),
+}
+
+impl core::fmt::Display for RecordingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RecordingError::Io(err) => err.fmt(f),
+            RecordingError::InvalidLine(line) => write!(f, "Invalid event at line {}", line),
+        }
+    }
+}
+
+impl std::error::Error for RecordingError {}
+
+impl From<std::io::Error> for RecordingError {
+    fn from(err: std::io::Error) -> Self {
+        RecordingError::Io(err)
+    }
+}
+
+/// A sequence of events, read from a file written by the recorder.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EventRecording {
+    entries: Vec<RecordedEntry>,
+}
+
+impl EventRecording {
+    /// Reads the recording from a file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RecordingError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Reads the recording from the content of a file.
+    pub fn parse(source: &str) -> Result<Self, RecordingError> {
+        let entries = source
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.starts_with('#'))
+            .map(|(index, line)| parse_entry(line).ok_or(RecordingError::InvalidLine(index + 1)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { entries })
+    }
+
+    /// Returns the recorded events, in the order in which they were received.
+    pub fn entries(&self) -> &[RecordedEntry] {
+        &self.entries
+    }
+}
+
+impl core::fmt::Display for RecordedEntry {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} ", self.time.as_millis())?;
+        match &self.event {
+            RecordedEvent::Mouse(MouseEvent::MousePressed { pos, button }) => {
+                write!(f, "press {} {} {}", pos.x, pos.y, button)
+            }
+            RecordedEvent::Mouse(MouseEvent::MouseReleased { pos, button }) => {
+                write!(f, "release {} {} {}", pos.x, pos.y, button)
+            }
+            RecordedEvent::Mouse(MouseEvent::MouseMoved { pos }) => {
+                write!(f, "move {} {}", pos.x, pos.y)
+            }
+            RecordedEvent::Mouse(MouseEvent::MouseWheel { pos, delta, phase }) => {
+                write!(f, "wheel {} {} {} {} {}", pos.x, pos.y, delta.x, delta.y, phase)
+            }
+            RecordedEvent::Mouse(MouseEvent::MouseExit) => write!(f, "exit"),
+            RecordedEvent::Key(event) => {
+                let kind = match event.event_type {
+                    KeyEventType::KeyPressed => "key-press",
+                    KeyEventType::KeyReleased => "key-release",
+                    KeyEventType::UpdateComposition => "composition-update",
+                    KeyEventType::CommitComposition => "composition-commit",
+                };
+                let modifiers = &event.modifiers;
+                let mut flags = String::new();
+                for (set, flag) in [
+                    (modifiers.alt, 'a'),
+                    (modifiers.control, 'c'),
+                    (modifiers.meta, 'm'),
+                    (modifiers.shift, 's'),
+                    (event.is_auto_repeat, 'r'),
+                ] {
+                    if set {
+                        flags.push(flag);
+                    }
+                }
+                if flags.is_empty() {
+                    flags.push('-');
+                }
+                write!(f, "{} {} {}", kind, flags, escape_text(&event.text))?;
+                if event.scan_code != 0 {
+                    write!(f, " {}", event.scan_code)?;
+                }
+                Ok(())
+            }
+            RecordedEvent::Focus(has_focus) => write!(f, "focus {}", *has_focus as u8),
+            RecordedEvent::Timers => write!(f, "timers"),
+        }
+    }
+}
+
+/// Escapes the text of a key event so that it fits on one line: the printable ASCII characters
+/// are kept, and all the others, including the spaces and the special keys, are written as `\u{...}`.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if ch.is_ascii_graphic() && ch != '\\' {
+            escaped.push(ch);
+        } else {
+            escaped.push_str(&format!("\\u{{{:x}}}", ch as u32));
+        }
+    }
+    escaped
+}
+
+fn unescape_text(text: &str) -> Option<SharedString> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+        let code = rest[index..].strip_prefix("\\u{")?;
+        let end = code.find('}')?;
+        unescaped.push(char::from_u32(u32::from_str_radix(&code[..end], 16).ok()?)?);
+        rest = &code[end + 1..];
+    }
+    unescaped.push_str(rest);
+    Some(unescaped.into())
+}
+
+fn parse_entry(line: &str) -> Option<RecordedEntry> {
+    let mut words = line.split(' ').filter(|word| !word.is_empty());
+    let time = Duration::from_millis(words.next()?.parse().ok()?);
+    let kind = words.next()?;
+    let args = words.collect::<Vec<_>>();
+    let coord = |arg: &str| arg.parse::<Coord>().ok();
+    let point = |x: &str, y: &str| Some(euclid::point2(coord(x)?, coord(y)?));
+
+    let event = match (kind, args.as_slice()) {
+        ("press", [x, y, button]) => RecordedEvent::Mouse(MouseEvent::MousePressed {
+            pos: point(x, y)?,
+            button: button.parse::<PointerEventButton>().ok()?,
+        }),
+        ("release", [x, y, button]) => RecordedEvent::Mouse(MouseEvent::MouseReleased {
+            pos: point(x, y)?,
+            button: button.parse::<PointerEventButton>().ok()?,
+        }),
+        ("move", [x, y]) => RecordedEvent::Mouse(MouseEvent::MouseMoved { pos: point(x, y)? }),
+        ("wheel", [x, y, dx, dy, phase]) => RecordedEvent::Mouse(MouseEvent::MouseWheel {
+            pos: point(x, y)?,
+            delta: point(dx, dy)?,
+            phase: phase.parse::<ScrollEventPhase>().ok()?,
+        }),
+        ("exit", []) => RecordedEvent::Mouse(MouseEvent::MouseExit),
+        ("focus", [has_focus]) => RecordedEvent::Focus(match *has_focus {
+            "0" => false,
+            "1" => true,
+            _ => return None,
+        }),
+        ("timers", []) => RecordedEvent::Timers,
+        (kind, [flags, text, scan_code @ ..]) if scan_code.len() <= 1 => {
+            let event_type = match kind {
+                "key-press" => KeyEventType::KeyPressed,
+                "key-release" => KeyEventType::KeyReleased,
+                "composition-update" => KeyEventType::UpdateComposition,
+                "composition-commit" => KeyEventType::CommitComposition,
+                _ => return None,
+            };
+            let mut modifiers = KeyboardModifiers::default();
+            let mut is_auto_repeat = false;
+            for flag in flags.chars().filter(|flag| *flag != '-') {
+                match flag {
+                    'a' => modifiers.alt = true,
+                    'c' => modifiers.control = true,
+                    'm' => modifiers.meta = true,
+                    's' => modifiers.shift = true,
+                    'r' => is_auto_repeat = true,
+                    _ => return None,
+                }
+            }
+            let scan_code = match scan_code.first() {
+                Some(scan_code) => scan_code.parse::<i32>().ok()?,
+                None => 0,
+            };
+            RecordedEvent::Key(KeyEvent {
+                event_type,
+                text: unescape_text(text)?,
+                modifiers,
+                scan_code,
+                is_auto_repeat,
+            })
+        }
+        _ => return None,
+    };
+    Some(RecordedEntry { time, event })
+}
+
+struct Recorder {
+    file: std::io::BufWriter<std::fs::File>,
+    start: Duration,
+}
+
+thread_local! {
+    /// The recorder of this thread. It is `None` until the first event is recorded, when the
+    /// `SLINT_RECORD_EVENTS` environment variable is looked up.
+    static RECORDER: RefCell<Option<Option<Recorder>>> = RefCell::new(None);
+    /// The time of the event being replayed, that replaces the time of the backend.
+    static REPLAY_TIME: Cell<Option<Duration>> = Cell::new(None);
+    /// How much the clock was advanced by the replays, so that it doesn't go back in time
+    /// after a replay.
+    static CLOCK_OFFSET: Cell<Duration> = Cell::new(Duration::ZERO);
+}
+
+fn open_recorder(path: &Path) -> std::io::Result<Recorder> {
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writeln!(file, "# slint event recording")?;
+    file.flush()?;
+    Ok(Recorder { file, start: crate::animations::Instant::duration_since_start() })
+}
+
+/// Starts recording the input events of all the windows, and the activation of the timers, into
+/// the file at `path`. A recording that was already in progress is stopped.
+pub fn start_recording(path: impl AsRef<Path>) -> std::io::Result<()> {
+    let recorder = open_recorder(path.as_ref())?;
+    RECORDER.with(|current| *current.borrow_mut() = Some(Some(recorder)));
+    Ok(())
+}
+
+/// Stops the recording started with [`start_recording()`] or by the `SLINT_RECORD_EVENTS`
+/// environment variable, and closes the file.
+pub fn stop_recording() {
+    RECORDER.with(|current| *current.borrow_mut() = Some(None));
+}
+
+pub(crate) fn record(event: impl FnOnce() -> RecordedEvent) {
+    if is_replaying() {
+        return;
+    }
+    RECORDER.with(|current| {
+        let mut current = current.borrow_mut();
+        let recorder = current.get_or_insert_with(|| {
+            let path = std::env::var_os("SLINT_RECORD_EVENTS")?;
+            open_recorder(Path::new(&path))
+                .map_err(|err| {
+                    eprintln!(
+                        "Could not record the events into {}: {}",
+                        Path::new(&path).display(),
+                        err
+                    )
+                })
+                .ok()
+        });
+        if let Some(recorder) = recorder {
+            let entry = RecordedEntry {
+                time: crate::animations::Instant::duration_since_start()
+                    .saturating_sub(recorder.start),
+                event: event(),
+            };
+            // Flush every line, so that nothing is lost if the application crashes
+            if let Err(err) =
+                writeln!(recorder.file, "{}", entry).and_then(|_| recorder.file.flush())
+            {
+                eprintln!("Could not record the events: {}", err);
+                *current = Some(None);
+            }
+        }
+    })
+}
+
+fn is_replaying() -> bool {
+    REPLAY_TIME.with(|time| time.get().is_some())
+}
+
+/// Returns the time to use instead of `time`, the time since the start given by the backend:
+/// the time of the recording during a replay.
+pub(crate) fn adjust_time(time: Duration) -> Duration {
+    REPLAY_TIME
+        .with(|replay_time| replay_time.get())
+        .unwrap_or_else(|| time + CLOCK_OFFSET.with(|offset| offset.get()))
+}
+
+/// Feeds the events of the recording to the window, through the same functions as the events of
+/// the windowing system. The timers and the animations see the time of the recording, which
+/// starts at the current time: the events are processed as fast as possible, without waiting.
+pub fn replay(window: &WindowRc, recording: &EventRecording) {
+    let start = crate::animations::Instant::duration_since_start();
+    let mut time = start;
+    for entry in &recording.entries {
+        // Never go back in time, even if the file was edited
+        time = time.max(start + entry.time);
+        REPLAY_TIME.with(|replay_time| replay_time.set(Some(time)));
+        crate::timers::TimerList::maybe_activate_timers();
+        crate::animations::update_animations();
+        match &entry.event {
+            RecordedEvent::Mouse(event) => window.clone().process_mouse_input(*event),
+            RecordedEvent::Key(event) => window.clone().process_key_input(event),
+            RecordedEvent::Focus(has_focus) => window.clone().set_focus(*has_focus),
+            RecordedEvent::Timers => {}
+        }
+    }
+    REPLAY_TIME.with(|replay_time| replay_time.set(None));
+    let now = crate::animations::Instant::duration_since_start();
+    if now < time {
+        CLOCK_OFFSET.with(|offset| offset.set(offset.get() + (time - now)));
+    }
+}
+
+#[test]
+fn test_parse_and_write() {
+    let source = "# slint event recording
+0 move 120 48
+35 press 120 48 left
+80 timers
+112 release 120 48.5 right
+120 wheel 1 2 0 -30 none
+125 wheel 1 2 0 -3.5 update
+200 key-press cs H\\u{20}\\u{5c}\\u{a}
+205 key-press sr H\\u{20}\\u{5c}\\u{a} 35
+210 composition-update - \\u{e9}
+300 focus 0
+310 exit
+";
+    let recording = EventRecording::parse(source).unwrap();
+    assert_eq!(recording.entries().len(), 11);
+    assert_eq!(
+        recording.entries()[6],
+        RecordedEntry {
+            time: Duration::from_millis(200),
+            event: RecordedEvent::Key(KeyEvent {
+                event_type: KeyEventType::KeyPressed,
+                text: "H \\\n".into(),
+                modifiers: KeyboardModifiers { control: true, shift: true, ..Default::default() },
+                ..Default::default()
+            }),
+        }
+    );
+    assert_eq!(
+        recording.entries()[7].event,
+        RecordedEvent::Key(KeyEvent {
+            event_type: KeyEventType::KeyPressed,
+            text: "H \\\n".into(),
+            modifiers: KeyboardModifiers { shift: true, ..Default::default() },
+            scan_code: 35,
+            is_auto_repeat: true,
+        })
+    );
+    let written =
+        recording.entries().iter().map(|entry| format!("{}\n", entry)).collect::<String>();
+    assert_eq!(written, source.strip_prefix("# slint event recording\n").unwrap());
+
+    assert!(matches!(
+        EventRecording::parse("0 move 1 2\n10 jump 3 4"),
+        Err(RecordingError::InvalidLine(2))
+    ));
+    assert!(matches!(
+        EventRecording::parse("5 key-press x \\u{zz}"),
+        Err(RecordingError::InvalidLine(1))
+    ));
+}
@@ -35,10 +35,13 @@ macro_rules! declare_ValueType_2 {
             crate::PathData,
             crate::animations::EasingCurve,
             crate::model::StandardListViewItem,
+            crate::model::TableColumn,
+            crate::items::TextSpan,
             crate::input::KeyEvent,
             crate::Brush,
             crate::graphics::Point,
             crate::items::PointerEvent,
+            crate::items::ScrollEvent,
             $(crate::items::$Name,)*
         ];
     };
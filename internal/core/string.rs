@@ -201,6 +201,70 @@ fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
     }
 }
 
+/// Format a number with `precision` digits after the decimal point, regardless of the locale.
+///
+/// This is used by the `format` function of strings in the `.slint` language, for
+/// placeholders such as `{:.2}`.
+pub fn format_float(value: f64, precision: i32) -> SharedString {
+    let mut result = alloc::format!("{:.*}", precision.max(0) as usize, value);
+    // Don't show a sign when the value is rounded to zero
+    if result.starts_with('-') && result[1..].chars().all(|c| c == '0' || c == '.') {
+        result.remove(0);
+    }
+    result.as_str().into()
+}
+
+/// Pad `string` with the first character of `fill` until it is `width` characters long.
+///
+/// The `alignment` is the position of the string within the padding: `0` aligns it to the left,
+/// `1` to the right and `2` centers it. `3` aligns it to the right but puts the padding after
+/// the sign of the number that the string represents, to pad numbers with zeros.
+///
+/// This is used by the `format` function of strings in the `.slint` language, for
+/// placeholders such as `{:>8}`.
+pub fn pad_string(string: &str, width: i32, fill: &str, alignment: i32) -> SharedString {
+    let padding = (width.max(0) as usize).saturating_sub(string.chars().count());
+    if padding == 0 {
+        return string.into();
+    }
+    let fill = fill.chars().next().unwrap_or(' ');
+    let (before, after) = match alignment {
+        0 => (0, padding),
+        2 => (padding / 2, padding - padding / 2),
+        _ => (padding, 0),
+    };
+    let mut result = String::with_capacity(string.len() + padding * fill.len_utf8());
+    let mut string = string;
+    if alignment == 3 && (string.starts_with('-') || string.starts_with('+')) {
+        result.push_str(&string[..1]);
+        string = &string[1..];
+    }
+    result.extend(core::iter::repeat(fill).take(before));
+    result.push_str(string);
+    result.extend(core::iter::repeat(fill).take(after));
+    result.as_str().into()
+}
+
+#[test]
+fn test_format_float() {
+    assert_eq!(format_float(21.456, 1), "21.5");
+    assert_eq!(format_float(3., 2), "3.00");
+    assert_eq!(format_float(-1234.4, 0), "-1234");
+    assert_eq!(format_float(-0.001, 2), "0.00");
+    assert_eq!(format_float(12.2, -1), "12");
+}
+
+#[test]
+fn test_pad_string() {
+    assert_eq!(pad_string("abc", 6, " ", 0), "abc   ");
+    assert_eq!(pad_string("abc", 6, " ", 1), "   abc");
+    assert_eq!(pad_string("abc", 6, "*", 2), "*abc**");
+    assert_eq!(pad_string("-4.5", 6, "0", 3), "-004.5");
+    assert_eq!(pad_string("4.5", 5, "0", 3), "004.5");
+    assert_eq!(pad_string("°C", 3, "", 1), " °C");
+    assert_eq!(pad_string("abcdef", 3, " ", 1), "abcdef");
+}
+
 #[test]
 fn simple_test() {
     let x = SharedString::from("hello world!");
@@ -327,6 +391,28 @@ fn test_slint_shared_string_from_number() {
         }
     }
 
+    /// Format a number with a fixed number of digits after the decimal point into `out`.
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_format_float(
+        value: f64,
+        precision: i32,
+        out: &mut SharedString,
+    ) {
+        *out = format_float(value, precision);
+    }
+
+    /// Pad a string to the given width into `out`. See [`pad_string`].
+    #[no_mangle]
+    pub extern "C" fn slint_shared_string_pad(
+        string: &SharedString,
+        width: i32,
+        fill: &SharedString,
+        alignment: i32,
+        out: &mut SharedString,
+    ) {
+        *out = pad_string(string, width, fill, alignment);
+    }
+
     /// Append some bytes to an existing shared string
     ///
     /// bytes must be a valid utf8 array of size `len`, without null bytes inside
@@ -1,103 +1,356 @@
 // Copyright © SixtyFPS GmbH <info@slint-ui.com>
 // SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
 
-mod draw_functions;
+/*!
+This module contains a software renderer that works without the standard library and draws the
+scene line by line, so that it only needs a buffer of one line of pixels instead of a whole frame
+buffer. This makes it suitable for micro-controllers with a few hundred kilobytes of RAM.
+
+The text is drawn with the pre-rendered glyphs of the bitmap fonts registered with
+[`register_bitmap_font()`], which the compiler embeds when the `.slint` files are compiled
+for this renderer.
+
+[`SoftwareRenderer::render()`] draws in a whole frame buffer instead, for example the texture
+in which a game engine composites the window in its scene.
 
-use crate::lengths::PhysicalPx;
-use crate::{
-    profiler, Devices, LogicalItemGeometry, LogicalLength, LogicalPoint, LogicalRect,
-    PhysicalLength, PhysicalPoint, PhysicalRect, PhysicalSize, PointLengths, RectLengths,
-    ScaleFactor, SizeLengths,
+The lines are drawn with integer and fixed point arithmetic. When the crate is compiled with
+`RUSTFLAGS="--cfg slint_int_coord"`, the logical coordinates are integers as well.
+*/
+
+#![warn(missing_docs)]
+
+mod draw_functions;
+mod fonts;
+
+use crate::graphics::{FontRequest, IntRect, PixelFormat, Rect as RectF, RenderingRotation};
+use crate::item_rendering::{ItemRenderer, PartialRenderingCache};
+use crate::items::{ImageFit, ItemRef};
+use crate::lengths::{
+    LogicalItemGeometry, LogicalLength, LogicalPoint, LogicalRect, PhysicalLength, PhysicalPoint,
+    PhysicalPx, PhysicalRect, PhysicalSize, PointLengths, RectLengths, ScaleFactor, SizeLengths,
 };
+use crate::textlayout::TextParagraphLayout;
+use crate::{Color, Coord, ImageInner, StaticTextures};
 use alloc::rc::Rc;
 use alloc::{vec, vec::Vec};
+use core::cell::{Cell, RefCell};
 use core::pin::Pin;
-pub use draw_functions::TargetPixel;
-use embedded_graphics::pixelcolor::Rgb888;
-use i_slint_core::graphics::{FontRequest, IntRect, PixelFormat, Rect as RectF};
-use i_slint_core::item_rendering::{ItemRenderer, PartialRenderingCache};
-use i_slint_core::items::ImageFit;
-use i_slint_core::textlayout::TextParagraphLayout;
-use i_slint_core::{Color, Coord, ImageInner, StaticTextures};
+pub use draw_functions::{Rgb565Pixel, TargetPixel};
+pub use fonts::register_bitmap_font;
 
 type DirtyRegion = PhysicalRect;
 
-pub fn render_window_frame(
-    runtime_window: Rc<i_slint_core::window::Window>,
-    background: super::TargetPixel,
-    devices: &mut dyn Devices,
-    initial_dirty_region: i_slint_core::item_rendering::DirtyRegion,
-    cache: &mut PartialRenderingCache,
-) {
-    let size = devices.screen_size();
-    let mut scene = prepare_scene(runtime_window, size, devices, initial_dirty_region, cache);
-
-    let mut line_processing_profiler = profiler::Timer::new_stopped();
-    let mut span_drawing_profiler = profiler::Timer::new_stopped();
-    let mut screen_fill_profiler = profiler::Timer::new_stopped();
-
-    let mut line_buffer = vec![background; size.width as usize];
-    let dirty_region = scene.dirty_region;
-
-    debug_assert!(scene.current_line >= dirty_region.origin.y_length());
-    while scene.current_line < dirty_region.origin.y_length() + dirty_region.size.height_length() {
-        line_buffer[dirty_region.min_x() as usize..dirty_region.max_x() as usize].fill(background);
-        span_drawing_profiler.start(devices);
-        for span in scene.items[0..scene.current_items_index].iter().rev() {
-            debug_assert!(scene.current_line >= span.pos.y_length());
-            debug_assert!(scene.current_line < span.pos.y_length() + span.size.height_length(),);
-            match span.command {
-                SceneCommand::Rectangle { color } => {
-                    TargetPixel::blend_buffer(
-                        &mut line_buffer[span.pos.x as usize
-                            ..(span.pos.x_length() + span.size.width_length()).get() as usize],
-                        color,
-                    );
-                }
-                SceneCommand::Texture { texture_index } => {
-                    let texture = &scene.textures[texture_index as usize];
-                    draw_functions::draw_texture_line(
-                        span,
-                        scene.current_line,
-                        texture,
-                        &mut line_buffer,
-                    );
-                }
-                SceneCommand::RoundedRectangle { rectangle_index } => {
-                    let rr = &scene.rounded_rectangles[rectangle_index as usize];
-                    draw_functions::draw_rounded_rectangle_line(
-                        span,
-                        scene.current_line,
-                        rr,
-                        &mut line_buffer,
-                    );
-                }
+/// Tells the renderer which part of the buffer it needs to draw again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepaintBufferType {
+    /// The buffer is the same from one frame to the next, so only the parts of the window that
+    /// changed since the last frame are drawn.
+    ReusedBuffer,
+    /// Two buffers are swapped after each frame, so the parts that changed in the previous
+    /// frame are drawn as well.
+    SwappedBuffers,
+}
+
+impl Default for RepaintBufferType {
+    fn default() -> Self {
+        Self::ReusedBuffer
+    }
+}
+
+/// The display driver implements this trait to receive the pixels, one line at a time.
+///
+/// The provider owns the buffer in which the line is drawn. A driver can for example send one
+/// line to the display with DMA while the next one is drawn in a second buffer, or collect a band
+/// of several lines before sending it.
+pub trait LineBufferProvider {
+    /// The pixel type of the buffer
+    type TargetPixel: TargetPixel;
+
+    /// Called once for each line of the region to draw. `range` is the range of pixels of the
+    /// `line` that changed, and `render_fn` draws them, background included, in the slice of
+    /// the buffer it is given, which must have the same length as the range.
+    fn process_line(
+        &mut self,
+        line: usize,
+        range: core::ops::Range<usize>,
+        render_fn: impl FnOnce(&mut [Self::TargetPixel]),
+    );
+}
+
+/// The [`LineBufferProvider`] used by [`SoftwareRenderer::render()`], which draws in a whole frame
+struct FrameBuffer<'a, T> {
+    buffer: &'a mut [T],
+    pixel_stride: usize,
+}
+
+impl<T: TargetPixel> LineBufferProvider for FrameBuffer<'_, T> {
+    type TargetPixel = T;
+
+    fn process_line(
+        &mut self,
+        line: usize,
+        range: core::ops::Range<usize>,
+        render_fn: impl FnOnce(&mut [T]),
+    ) {
+        let start = line * self.pixel_stride;
+        render_fn(&mut self.buffer[start + range.start..start + range.end])
+    }
+}
+
+/// A renderer that draws the window with the CPU, one line at a time, without the standard
+/// library. It also measures the text, so it can be used as the [`crate::platform::Renderer`]
+/// of a window adapter.
+#[derive(Default)]
+pub struct SoftwareRenderer {
+    partial_cache: RefCell<PartialRenderingCache>,
+    repaint_buffer_type: RepaintBufferType,
+    /// This is the area which we are going to redraw in the next frame, no matter if the items
+    /// are dirty or not
+    force_dirty: Cell<crate::item_rendering::DirtyRegion>,
+    /// The region that was dirty in the previous frame, used with `RepaintBufferType::SwappedBuffers`,
+    /// in the coordinates of the window
+    prev_frame_dirty: Cell<DirtyRegion>,
+    rendering_rotation: Cell<RenderingRotation>,
+    /// Set when the whole display needs to be drawn again in the next frame, after the rotation changed
+    force_screen_refresh: Cell<bool>,
+}
+
+impl SoftwareRenderer {
+    /// Creates a renderer, with the given kind of buffer.
+    pub fn new(repaint_buffer_type: RepaintBufferType) -> Self {
+        Self { repaint_buffer_type, ..Default::default() }
+    }
+
+    /// Marks the region, in logical pixels, to be drawn in the next frame even if the items in it
+    /// did not change. This is needed for example when a popup closes.
+    pub fn mark_dirty_region(&self, region: crate::item_rendering::DirtyRegion) {
+        self.force_dirty.set(self.force_dirty.get().union(&region))
+    }
+
+    /// Sets the clockwise rotation of the window on the display, for the displays that are mounted
+    /// rotated. The size of the window must then be the size of the display, with the width and
+    /// height swapped for the quarter turns, and the positions of the input events are mapped with
+    /// [`RenderingRotation::map_point_to_window()`].
+    pub fn set_rendering_rotation(&self, rotation: RenderingRotation) {
+        if self.rendering_rotation.replace(rotation) != rotation {
+            self.force_screen_refresh.set(true);
+        }
+    }
+
+    /// Returns the rotation set with [`Self::set_rendering_rotation()`].
+    pub fn rendering_rotation(&self) -> RenderingRotation {
+        self.rendering_rotation.get()
+    }
+
+    /// Draws the parts of the `window` that changed since the last call, and returns the dirty
+    /// region that was drawn, in physical pixels. `size` is the size of the display in physical
+    /// pixels, and `background` is the color drawn below the items. The lines are the lines of the
+    /// display, so with the rendering rotation applied.
+    pub fn render_by_line(
+        &self,
+        window: &crate::api::Window,
+        size: PhysicalSize,
+        background: Color,
+        mut line_buffer: impl LineBufferProvider,
+    ) -> PhysicalRect {
+        use crate::window::WindowHandleAccess;
+        let runtime_window = window.window_handle().clone();
+        let mut scene = self.prepare_scene(runtime_window, size);
+        let background = TargetPixel::from_color(background);
+
+        let dirty_region = scene.dirty_region;
+
+        debug_assert!(scene.current_line >= dirty_region.origin.y_length());
+        while scene.current_line
+            < dirty_region.origin.y_length() + dirty_region.size.height_length()
+        {
+            line_buffer.process_line(
+                scene.current_line.get() as usize,
+                dirty_region.min_x() as usize..dirty_region.max_x() as usize,
+                |line_buffer| {
+                    let offset = dirty_region.min_x() as usize;
+                    line_buffer.fill(background);
+                    for span in scene.items[0..scene.current_items_index].iter().rev() {
+                        debug_assert!(scene.current_line >= span.pos.y_length());
+                        debug_assert!(
+                            scene.current_line < span.pos.y_length() + span.size.height_length(),
+                        );
+                        match span.command {
+                            SceneCommand::Rectangle { color } => {
+                                TargetPixel::blend_buffer(
+                                    &mut line_buffer[span.pos.x as usize - offset
+                                        ..(span.pos.x_length() + span.size.width_length()).get()
+                                            as usize
+                                            - offset],
+                                    color,
+                                );
+                            }
+                            SceneCommand::Texture { texture_index } => {
+                                let texture = &scene.textures[texture_index as usize];
+                                draw_functions::draw_texture_line(
+                                    span,
+                                    scene.current_line,
+                                    texture,
+                                    scene.rotation,
+                                    line_buffer,
+                                    offset,
+                                );
+                            }
+                            SceneCommand::RoundedRectangle { rectangle_index } => {
+                                let rr = &scene.rounded_rectangles[rectangle_index as usize];
+                                draw_functions::draw_rounded_rectangle_line(
+                                    span,
+                                    scene.current_line,
+                                    rr,
+                                    line_buffer,
+                                    offset,
+                                );
+                            }
+                        }
+                    }
+                },
+            );
+            if scene.current_line
+                < dirty_region.origin.y_length() + dirty_region.size.height_length()
+            {
+                scene.next_line();
             }
         }
-        span_drawing_profiler.stop(devices);
-        screen_fill_profiler.start(devices);
-        devices.fill_region(
-            euclid::rect(
-                dirty_region.origin.x,
-                scene.current_line.get() as i16,
-                dirty_region.size.width,
-                1,
-            ),
-            &line_buffer[dirty_region.min_x() as usize..dirty_region.max_x() as usize],
+        dirty_region
+    }
+
+    /// Draws the parts of the `window` that changed since the last call in `buffer`, a frame
+    /// buffer with `pixel_stride` pixels per line, and returns the dirty region that was drawn,
+    /// in physical pixels. `background` is the color drawn below the items, and can be
+    /// transparent when the pixels have an alpha channel.
+    ///
+    /// This draws the window in the texture of a game engine, for example, which then only needs
+    /// to upload the pixels of the returned region.
+    pub fn render(
+        &self,
+        window: &crate::api::Window,
+        buffer: &mut [impl TargetPixel],
+        pixel_stride: usize,
+        background: Color,
+    ) -> PhysicalRect {
+        let height = if pixel_stride == 0 { 0 } else { buffer.len() / pixel_stride };
+        let size = PhysicalSize::new(pixel_stride as _, height as _);
+        self.render_by_line(window, size, background, FrameBuffer { buffer, pixel_stride })
+    }
+
+    fn prepare_scene(
+        &self,
+        runtime_window: Rc<crate::window::Window>,
+        size: PhysicalSize,
+    ) -> Scene {
+        let factor = ScaleFactor::new(runtime_window.scale_factor());
+        let rotation = self.rendering_rotation.get();
+        // The scene is prepared in the coordinates of the window, and then rotated to the display
+        let window_size = rotation.map_size(size);
+        let prepare_scene =
+            PrepareScene::new(window_size, factor, runtime_window.default_font_properties());
+        let mut cache = self.partial_cache.borrow_mut();
+        let mut renderer = crate::item_rendering::PartialRenderer::new(
+            &mut cache,
+            self.force_dirty.take(),
+            prepare_scene,
         );
-        screen_fill_profiler.stop(devices);
-        line_processing_profiler.start(devices);
-        if scene.current_line < dirty_region.origin.y_length() + dirty_region.size.height_length() {
-            scene.next_line();
+
+        let mut dirty_region = PhysicalRect::default();
+        runtime_window.draw_contents(|components| {
+            for (component, origin) in components {
+                renderer.compute_dirty_regions(component, *origin);
+            }
+
+            let window_rect = PhysicalRect { origin: euclid::point2(0, 0), size: window_size };
+            dirty_region = (LogicalRect::from_untyped(&renderer.dirty_region.to_rect()).cast()
+                * factor)
+                .round_out()
+                .cast()
+                .intersection(&window_rect)
+                .unwrap_or_default();
+            if self.force_screen_refresh.take() {
+                dirty_region = window_rect;
+                self.prev_frame_dirty.set(window_rect);
+            }
+            if self.repaint_buffer_type == RepaintBufferType::SwappedBuffers {
+                dirty_region = dirty_region.union(&self.prev_frame_dirty.replace(dirty_region));
+            }
+
+            renderer.combine_clip(
+                (dirty_region.cast() / factor).to_untyped().cast(),
+                0 as _,
+                0 as _,
+            );
+            for (component, origin) in components {
+                crate::item_rendering::render_component_items(component, &mut renderer, *origin);
+            }
+        });
+
+        let mut prepare_scene = renderer.into_inner();
+        if rotation != RenderingRotation::NoRotation {
+            prepare_scene.rotate(rotation, window_size);
         }
-        line_processing_profiler.stop(devices);
+        Scene::new(
+            prepare_scene.items,
+            prepare_scene.textures,
+            prepare_scene.rounded_rectangles,
+            rotation.map_rect_to_display(dirty_region, window_size),
+            rotation,
+        )
     }
 
-    line_processing_profiler.stop_profiling(devices, "line processing");
-    span_drawing_profiler.stop_profiling(devices, "span drawing");
-    screen_fill_profiler.stop_profiling(devices, "screen fill");
+    /// Frees the cached data of the items when they are destroyed.
+    pub fn free_graphics_resources(&self, items: &mut dyn Iterator<Item = Pin<ItemRef<'_>>>) {
+        let mut cache = self.partial_cache.borrow_mut();
+        for item in items {
+            item.cached_rendering_data_offset().release(&mut cache);
+        }
+    }
+}
 
-    devices.flush_frame();
+impl crate::platform::Renderer for SoftwareRenderer {
+    fn text_size(
+        &self,
+        font_request: FontRequest,
+        text: &str,
+        max_width: Option<Coord>,
+        scale_factor: f32,
+    ) -> crate::graphics::Size {
+        fonts::text_size(font_request, text, max_width, ScaleFactor::new(scale_factor)).to_untyped()
+    }
+
+    fn text_min_content_width(
+        &self,
+        font_request: FontRequest,
+        text: &str,
+        scale_factor: f32,
+    ) -> Coord {
+        fonts::text_min_content_width(font_request, text, ScaleFactor::new(scale_factor)).get()
+    }
+
+    fn text_input_byte_offset_for_position(
+        &self,
+        _text_input: Pin<&crate::items::TextInput>,
+        _pos: crate::graphics::Point,
+        _scale_factor: f32,
+    ) -> usize {
+        // TODO: the text input is not rendered yet
+        0
+    }
+
+    fn text_input_cursor_rect_for_byte_offset(
+        &self,
+        _text_input: Pin<&crate::items::TextInput>,
+        _byte_offset: usize,
+        _scale_factor: f32,
+    ) -> RectF {
+        Default::default()
+    }
+
+    fn free_graphics_resources(&self, items: &mut dyn Iterator<Item = Pin<ItemRef<'_>>>) {
+        SoftwareRenderer::free_graphics_resources(self, items)
+    }
 }
 
 struct Scene {
@@ -117,6 +370,8 @@ struct Scene {
     textures: Vec<SceneTexture>,
     rounded_rectangles: Vec<RoundedRectangle>,
     dirty_region: DirtyRegion,
+    /// The rotation of the items, which the textures are sampled with
+    rotation: RenderingRotation,
 }
 
 impl Scene {
@@ -125,6 +380,7 @@ pub fn new(
         textures: Vec<SceneTexture>,
         rounded_rectangles: Vec<RoundedRectangle>,
         dirty_region: DirtyRegion,
+        rotation: RenderingRotation,
     ) -> Self {
         let current_line = dirty_region.origin.y_length();
         items.retain(|i| i.pos.y_length() + i.size.height_length() > current_line);
@@ -139,6 +395,7 @@ pub fn new(
             textures,
             rounded_rectangles,
             dirty_region,
+            rotation,
         }
     }
 
@@ -315,56 +572,6 @@ struct RoundedRectangle {
     bottom_clip: PhysicalLength,
 }
 
-fn prepare_scene(
-    runtime_window: Rc<i_slint_core::window::Window>,
-    size: PhysicalSize,
-    devices: &mut dyn Devices,
-    initial_dirty_region: i_slint_core::item_rendering::DirtyRegion,
-    cache: &mut PartialRenderingCache,
-) -> Scene {
-    let prepare_scene_profiler = profiler::Timer::new(devices);
-    let mut compute_dirty_region_profiler = profiler::Timer::new_stopped();
-    let factor = ScaleFactor::new(runtime_window.scale_factor());
-    let prepare_scene = PrepareScene::new(size, factor, runtime_window.default_font_properties());
-    let mut renderer = i_slint_core::item_rendering::PartialRenderer::new(
-        cache,
-        initial_dirty_region,
-        prepare_scene,
-    );
-
-    let mut dirty_region = PhysicalRect::default();
-    runtime_window.draw_contents(|components| {
-        compute_dirty_region_profiler.start(devices);
-        for (component, origin) in components {
-            renderer.compute_dirty_regions(component, *origin);
-        }
-
-        dirty_region = (LogicalRect::from_untyped(&renderer.dirty_region.to_rect()).cast()
-            * factor)
-            .round_out()
-            .cast()
-            .intersection(&PhysicalRect { origin: euclid::point2(0, 0), size })
-            .unwrap_or_default();
-        dirty_region = devices.prepare_frame(dirty_region);
-
-        renderer.combine_clip((dirty_region.cast() / factor).to_untyped().cast(), 0 as _, 0 as _);
-        compute_dirty_region_profiler.stop(devices);
-        for (component, origin) in components {
-            i_slint_core::item_rendering::render_component_items(component, &mut renderer, *origin);
-        }
-    });
-
-    prepare_scene_profiler.stop_profiling(devices, "prepare_scene");
-    compute_dirty_region_profiler.stop_profiling(devices, "+    dirty_region");
-    let prepare_scene = renderer.into_inner();
-    Scene::new(
-        prepare_scene.items,
-        prepare_scene.textures,
-        prepare_scene.rounded_rectangles,
-        dirty_region,
-    )
-}
-
 struct PrepareScene {
     items: Vec<SceneItem>,
     textures: Vec<SceneTexture>,
@@ -395,6 +602,28 @@ fn new(size: PhysicalSize, scale_factor: ScaleFactor, default_font: FontRequest)
         }
     }
 
+    /// Maps the items from the window, whose size is `window_size`, to the rotated display.
+    /// The textures are rotated when they are drawn.
+    fn rotate(&mut self, rotation: RenderingRotation, window_size: PhysicalSize) {
+        for item in self.items.iter_mut() {
+            let rect =
+                rotation.map_rect_to_display(PhysicalRect::new(item.pos, item.size), window_size);
+            item.pos = rect.origin;
+            item.size = rect.size;
+        }
+        for rr in self.rounded_rectangles.iter_mut() {
+            // The clip of a side of the window moves to the side of the display it is shown on
+            let (left, top, right, bottom) =
+                (rr.left_clip, rr.top_clip, rr.right_clip, rr.bottom_clip);
+            (rr.left_clip, rr.top_clip, rr.right_clip, rr.bottom_clip) = match rotation {
+                RenderingRotation::NoRotation => (left, top, right, bottom),
+                RenderingRotation::Rotate90 => (bottom, left, top, right),
+                RenderingRotation::Rotate180 => (right, bottom, left, top),
+                RenderingRotation::Rotate270 => (top, right, bottom, left),
+            };
+        }
+    }
+
     fn should_draw(&self, rect: &LogicalRect) -> bool {
         !rect.size.is_empty()
             && self.current_state.alpha > 0.01
@@ -431,7 +660,7 @@ fn new_scene_item(&mut self, geometry: LogicalRect, command: SceneCommand) {
     fn draw_image_impl(
         &mut self,
         geom: LogicalRect,
-        source: &i_slint_core::graphics::Image,
+        source: &crate::graphics::Image,
         mut source_clip: IntRect,
         image_fit: ImageFit,
         colorize: Color,
@@ -442,7 +671,7 @@ fn draw_image_impl(
             ImageInner::AbsoluteFilePath(_) | ImageInner::EmbeddedData { .. } => {
                 unimplemented!()
             }
-            ImageInner::EmbeddedImage(_) => todo!(),
+            ImageInner::EmbeddedImage(_) | ImageInner::VideoStream(_) => todo!(),
             ImageInner::StaticTextures(StaticTextures { size, data, textures, .. }) => {
                 let phys_size = geom.size_length().cast() * self.scale_factor;
                 let sx = phys_size.width / (size.width as f32);
@@ -516,8 +745,8 @@ struct RenderState {
     clip: LogicalRect,
 }
 
-impl i_slint_core::item_rendering::ItemRenderer for PrepareScene {
-    fn draw_rectangle(&mut self, rect: Pin<&i_slint_core::items::Rectangle>) {
+impl crate::item_rendering::ItemRenderer for PrepareScene {
+    fn draw_rectangle(&mut self, rect: Pin<&crate::items::Rectangle>) {
         let geom = LogicalRect::new(LogicalPoint::default(), rect.logical_geometry().size_length());
         if self.should_draw(&geom) {
             let geom = match geom.intersection(&self.current_state.clip) {
@@ -534,7 +763,7 @@ fn draw_rectangle(&mut self, rect: Pin<&i_slint_core::items::Rectangle>) {
         }
     }
 
-    fn draw_border_rectangle(&mut self, rect: Pin<&i_slint_core::items::BorderRectangle>) {
+    fn draw_border_rectangle(&mut self, rect: Pin<&crate::items::BorderRectangle>) {
         let geom = LogicalRect::new(LogicalPoint::default(), rect.logical_geometry().size_length());
         if self.should_draw(&geom) {
             let border = rect.border_width();
@@ -600,7 +829,7 @@ fn draw_border_rectangle(&mut self, rect: Pin<&i_slint_core::items::BorderRectan
         }
     }
 
-    fn draw_image(&mut self, image: Pin<&i_slint_core::items::ImageItem>) {
+    fn draw_image(&mut self, image: Pin<&crate::items::ImageItem>) {
         let geom =
             LogicalRect::new(LogicalPoint::default(), image.logical_geometry().size_length());
         if self.should_draw(&geom) {
@@ -614,21 +843,22 @@ fn draw_image(&mut self, image: Pin<&i_slint_core::items::ImageItem>) {
         }
     }
 
-    fn draw_clipped_image(&mut self, image: Pin<&i_slint_core::items::ClippedImage>) {
+    fn draw_clipped_image(&mut self, image: Pin<&crate::items::ClippedImage>) {
         // when the source_clip size is empty, make it full
         let a = |v| if v == 0 { i32::MAX } else { v };
 
         let geom =
             LogicalRect::new(LogicalPoint::default(), image.logical_geometry().size_length());
         if self.should_draw(&geom) {
+            let source_clip = image.source_clip();
             self.draw_image_impl(
                 geom,
                 &image.source(),
                 euclid::rect(
-                    image.source_clip_x(),
-                    image.source_clip_y(),
-                    a(image.source_clip_width()),
-                    a(image.source_clip_height()),
+                    source_clip.min_x(),
+                    source_clip.min_y(),
+                    a(source_clip.width()),
+                    a(source_clip.height()),
                 ),
                 image.image_fit(),
                 image.colorize().color(),
@@ -636,7 +866,7 @@ fn draw_clipped_image(&mut self, image: Pin<&i_slint_core::items::ClippedImage>)
         }
     }
 
-    fn draw_text(&mut self, text: Pin<&i_slint_core::items::Text>) {
+    fn draw_text(&mut self, text: Pin<&crate::items::Text>) {
         let string = text.text();
         if string.trim().is_empty() {
             return;
@@ -647,10 +877,11 @@ fn draw_text(&mut self, text: Pin<&i_slint_core::items::Text>) {
         }
 
         let font_request = text.unresolved_font_request().merge(&self.default_font);
-        let font = crate::fonts::match_font(&font_request, self.scale_factor);
+        let font = fonts::match_font(&font_request, self.scale_factor);
 
         let color = text.color().color();
         let max_size = (geom.size.cast() * self.scale_factor).cast();
+        let ellipsis = text.ellipsis();
 
         let paragraph = TextParagraphLayout {
             string: &string,
@@ -662,7 +893,11 @@ fn draw_text(&mut self, text: Pin<&i_slint_core::items::Text>) {
             vertical_alignment: text.vertical_alignment(),
             wrap: text.wrap(),
             overflow: text.overflow(),
+            ellipsis: &ellipsis,
             single_line: false,
+            paragraph_spacing: (LogicalLength::new(text.paragraph_spacing()).cast()
+                * self.scale_factor)
+                .cast(),
         };
 
         paragraph.layout_lines(|glyphs, line_x, line_y| {
@@ -707,18 +942,18 @@ fn draw_text(&mut self, text: Pin<&i_slint_core::items::Text>) {
         });
     }
 
-    fn draw_text_input(&mut self, text_input: Pin<&i_slint_core::items::TextInput>) {
+    fn draw_text_input(&mut self, text_input: Pin<&crate::items::TextInput>) {
         text_input.logical_geometry();
         // TODO
     }
 
     #[cfg(feature = "std")]
-    fn draw_path(&mut self, path: Pin<&i_slint_core::items::Path>) {
+    fn draw_path(&mut self, path: Pin<&crate::items::Path>) {
         path.logical_geometry();
         // TODO
     }
 
-    fn draw_box_shadow(&mut self, box_shadow: Pin<&i_slint_core::items::BoxShadow>) {
+    fn draw_box_shadow(&mut self, box_shadow: Pin<&crate::items::BoxShadow>) {
         box_shadow.logical_geometry();
         // TODO
     }
@@ -735,7 +970,7 @@ fn combine_clip(&mut self, other: RectF, _radius: Coord, _border_width: Coord) {
         // TODO: handle radius and border
     }
 
-    fn get_current_clip(&self) -> i_slint_core::graphics::Rect {
+    fn get_current_clip(&self) -> crate::graphics::Rect {
         self.current_state.clip.to_untyped()
     }
 
@@ -767,7 +1002,7 @@ fn scale_factor(&self) -> f32 {
 
     fn draw_cached_pixmap(
         &mut self,
-        _item_cache: &i_slint_core::item_rendering::CachedRenderingData,
+        _item_cache: &crate::item_rendering::CachedRenderingData,
         _update_fn: &dyn Fn(&mut dyn FnMut(u32, u32, &[u8])),
     ) {
         todo!()
@@ -777,7 +1012,7 @@ fn draw_string(&mut self, _string: &str, _color: Color) {
         todo!()
     }
 
-    fn window(&self) -> i_slint_core::window::WindowRc {
+    fn window(&self) -> crate::window::WindowRc {
         unreachable!("this backend don't query the window")
     }
 
@@ -794,7 +1029,3 @@ fn bpp(format: PixelFormat) -> u16 {
         PixelFormat::AlphaMap => 1,
     }
 }
-
-pub fn to_rgb888_color_discard_alpha(col: Color) -> Rgb888 {
-    Rgb888::new(col.red(), col.green(), col.blue())
-}
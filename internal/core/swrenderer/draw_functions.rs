@@ -5,34 +5,43 @@
 //! on the line buffer
 
 use super::{SceneItem, SceneTexture};
-use crate::lengths::{PointLengths, SizeLengths};
-use crate::PhysicalLength;
+use crate::graphics::{PixelFormat, RenderingRotation};
+use crate::lengths::{PhysicalLength, PointLengths, SizeLengths};
+use crate::Color;
 use derive_more::{Add, Mul, Sub};
-use embedded_graphics::pixelcolor::{Rgb565, Rgb888};
-use embedded_graphics::prelude::*;
-use i_slint_core::graphics::PixelFormat;
-use i_slint_core::Color;
 use integer_sqrt::IntegerSquareRoot;
 
-/// Draw one line of the texture in the line buffer
+/// Draw one line of the texture in the line buffer, which starts at the x coordinate `offset`.
+/// The span is on the display, and the texture is rotated by `rotation` to fill it.
 pub(super) fn draw_texture_line(
     span: &SceneItem,
     line: PhysicalLength,
     texture: &super::SceneTexture,
+    rotation: RenderingRotation,
     line_buffer: &mut [impl TargetPixel],
+    offset: usize,
 ) {
     let SceneTexture { data, format, stride, source_size, color } = *texture;
     let source_size = source_size.cast::<usize>();
     let span_size = span.size.cast::<usize>();
+    // The size of the span in the window, before the rotation
+    let window_size = rotation.map_size(span_size);
     let bpp = super::bpp(format) as usize;
-    let y = (line - span.pos.y_length()).cast::<usize>();
-    let y_pos = (y.get() * source_size.height / span_size.height) * stride as usize;
-    for (x, pix) in line_buffer
-        [span.pos.x as usize..(span.pos.x_length() + span.size.width_length()).get() as usize]
+    let y = (line - span.pos.y_length()).cast::<usize>().get();
+    for (x, pix) in line_buffer[span.pos.x as usize - offset
+        ..(span.pos.x_length() + span.size.width_length()).get() as usize - offset]
         .iter_mut()
         .enumerate()
     {
-        let pos = y_pos + (x * source_size.width / span_size.width) * bpp;
+        // The pixel of the span in the window that is shown at (x, y) on the display
+        let (window_x, window_y) = match rotation {
+            RenderingRotation::NoRotation => (x, y),
+            RenderingRotation::Rotate90 => (y, window_size.height - 1 - x),
+            RenderingRotation::Rotate180 => (window_size.width - 1 - x, window_size.height - 1 - y),
+            RenderingRotation::Rotate270 => (window_size.width - 1 - y, x),
+        };
+        let pos = (window_y * source_size.height / window_size.height) * stride as usize
+            + (window_x * source_size.width / window_size.width) * bpp;
         let c = match format {
             PixelFormat::Rgb => {
                 Color::from_argb_u8(255, data[pos + 0], data[pos + 1], data[pos + 2])
@@ -52,12 +61,13 @@ pub(super) fn draw_texture_line(
     }
 }
 
-/// draw one line of the rounded rectangle in the line buffer
+/// draw one line of the rounded rectangle in the line buffer, which starts at the x coordinate `offset`
 pub(super) fn draw_rounded_rectangle_line(
     span: &SceneItem,
     line: PhysicalLength,
     rr: &super::RoundedRectangle,
     line_buffer: &mut [impl TargetPixel],
+    offset: usize,
 ) {
     /// This is an integer shifted by 4 bits.
     /// Note: this is not a "fixed point" because multiplication and sqrt operation operate to
@@ -88,7 +98,7 @@ fn mul(self, rhs: Self) -> Self::Output {
             Self(self.0 * rhs.0)
         }
     }
-    let pos_x = span.pos.x as usize;
+    let pos_x = span.pos.x as usize - offset;
     let y1 = (line - span.pos.y_length()) + rr.top_clip;
     let y2 = (span.pos.y_length() + span.size.height_length() - line) + rr.bottom_clip
         - PhysicalLength::new(1);
@@ -244,49 +254,133 @@ pub trait TargetPixel: Sized + Copy {
     /// blend a single pixel
     fn blend_pixel(pix: &mut Self, color: Color);
     /// Fill (or blend) the color in the buffer
-    fn blend_buffer(to_fill: &mut [Self], color: Color);
-}
-
-impl TargetPixel for Rgb888 {
-    fn blend_buffer(to_fill: &mut [Rgb888], color: Color) {
+    fn blend_buffer(to_fill: &mut [Self], color: Color) {
         if color.alpha() == u8::MAX {
-            to_fill.fill(super::to_rgb888_color_discard_alpha(color))
+            to_fill.fill(Self::from_rgb(color.red(), color.green(), color.blue()))
         } else {
             for pix in to_fill {
                 Self::blend_pixel(pix, color);
             }
         }
     }
+    /// Create a pixel from the red, green and blue components in the 0..255 range
+    fn from_rgb(red: u8, green: u8, blue: u8) -> Self;
+    /// Create a pixel from a color. The default implementation ignores the alpha component,
+    /// for the formats that have no alpha channel.
+    fn from_color(color: Color) -> Self {
+        Self::from_rgb(color.red(), color.green(), color.blue())
+    }
+}
 
-    fn blend_pixel(pix: &mut Rgb888, color: Color) {
+impl TargetPixel for rgb::RGB8 {
+    fn blend_pixel(pix: &mut Self, color: Color) {
         let a = (u8::MAX - color.alpha()) as u16;
         let b = color.alpha() as u16;
-        *pix = Rgb888::new(
-            ((pix.r() as u16 * a + color.red() as u16 * b) / 255) as u8,
-            ((pix.g() as u16 * a + color.green() as u16 * b) / 255) as u8,
-            ((pix.b() as u16 * a + color.blue() as u16 * b) / 255) as u8,
+        *pix = rgb::RGB8::new(
+            ((pix.r as u16 * a + color.red() as u16 * b) / 255) as u8,
+            ((pix.g as u16 * a + color.green() as u16 * b) / 255) as u8,
+            ((pix.b as u16 * a + color.blue() as u16 * b) / 255) as u8,
         );
     }
-}
 
-impl TargetPixel for Rgb565 {
-    fn blend_buffer(to_fill: &mut [Rgb565], color: Color) {
-        if color.alpha() == u8::MAX {
-            to_fill.fill(Rgb565::new(color.red() >> 3, color.green() >> 2, color.blue() >> 3))
-        } else {
-            for pix in to_fill {
-                Self::blend_pixel(pix, color);
-            }
-        }
+    fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        rgb::RGB8::new(red, green, blue)
     }
+}
 
-    fn blend_pixel(pix: &mut Rgb565, color: Color) {
+/// The components are premultiplied by the alpha, which is the usual format of the textures of game
+/// engines, so that a window with a transparent background can be drawn over their scene.
+impl TargetPixel for rgb::RGBA8 {
+    fn blend_pixel(pix: &mut Self, color: Color) {
         let a = (u8::MAX - color.alpha()) as u16;
         let b = color.alpha() as u16;
-        *pix = Rgb565::new(
-            ((((pix.r() as u16) << 3) * a + color.red() as u16 * b) / 2040) as u8,
-            ((((pix.g() as u16) << 2) * a + color.green() as u16 * b) / 1020) as u8,
-            ((((pix.b() as u16) << 3) * a + color.blue() as u16 * b) / 2040) as u8,
+        *pix = rgb::RGBA8::new(
+            ((pix.r as u16 * a + color.red() as u16 * b) / 255) as u8,
+            ((pix.g as u16 * a + color.green() as u16 * b) / 255) as u8,
+            ((pix.b as u16 * a + color.blue() as u16 * b) / 255) as u8,
+            (pix.a as u16 * a / 255 + b) as u8,
+        );
+    }
+
+    fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        rgb::RGBA8::new(red, green, blue, u8::MAX)
+    }
+
+    fn from_color(color: Color) -> Self {
+        let alpha = color.alpha() as u16;
+        let premultiply = |component: u8| (component as u16 * alpha / 255) as u8;
+        rgb::RGBA8::new(
+            premultiply(color.red()),
+            premultiply(color.green()),
+            premultiply(color.blue()),
+            color.alpha(),
         )
     }
 }
+
+/// A pixel in the RGB565 format, with 5 bits for the red, 6 bits for the green and 5 bits for
+/// the blue component, from the most to the least significant bits. This is the format of
+/// the frame buffer of many small displays.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Rgb565Pixel(pub u16);
+
+impl Rgb565Pixel {
+    const R_MASK: u16 = 0b1111_1000_0000_0000;
+    const G_MASK: u16 = 0b0000_0111_1110_0000;
+    const B_MASK: u16 = 0b0000_0000_0001_1111;
+
+    /// Returns the red component, in the 0..31 range
+    pub fn red(self) -> u8 {
+        ((self.0 & Self::R_MASK) >> 11) as u8
+    }
+    /// Returns the green component, in the 0..63 range
+    pub fn green(self) -> u8 {
+        ((self.0 & Self::G_MASK) >> 5) as u8
+    }
+    /// Returns the blue component, in the 0..31 range
+    pub fn blue(self) -> u8 {
+        (self.0 & Self::B_MASK) as u8
+    }
+}
+
+impl TargetPixel for Rgb565Pixel {
+    fn blend_pixel(pix: &mut Self, color: Color) {
+        let a = (u8::MAX - color.alpha()) as u16;
+        let b = color.alpha() as u16;
+        let red = (((pix.red() as u16) << 3) * a + color.red() as u16 * b) / 2040;
+        let green = (((pix.green() as u16) << 2) * a + color.green() as u16 * b) / 1020;
+        let blue = (((pix.blue() as u16) << 3) * a + color.blue() as u16 * b) / 2040;
+        *pix = Rgb565Pixel((red << 11) | (green << 5) | blue);
+    }
+
+    fn from_rgb(red: u8, green: u8, blue: u8) -> Self {
+        Rgb565Pixel(((red as u16 >> 3) << 11) | ((green as u16 >> 2) << 5) | (blue as u16 >> 3))
+    }
+}
+
+impl From<Rgb565Pixel> for rgb::RGB8 {
+    fn from(pixel: Rgb565Pixel) -> Self {
+        // Scale the 5 and 6 bit components to the 0..255 range, rounding to the nearest value
+        let scale = |value: u8, max: u16| ((value as u16 * 255 + max / 2) / max) as u8;
+        rgb::RGB8::new(scale(pixel.red(), 31), scale(pixel.green(), 63), scale(pixel.blue(), 31))
+    }
+}
+
+#[test]
+fn blend_premultiplied_rgba() {
+    let transparent = Color::from_argb_u8(0, 0, 0, 0);
+    let mut pixel = rgb::RGBA8::from_color(transparent);
+    assert_eq!(pixel, rgb::RGBA8::new(0, 0, 0, 0));
+
+    TargetPixel::blend_pixel(&mut pixel, Color::from_argb_u8(128, 255, 0, 0));
+    assert_eq!(pixel, rgb::RGBA8::new(128, 0, 0, 128));
+
+    TargetPixel::blend_pixel(&mut pixel, Color::from_argb_u8(255, 0, 0, 255));
+    assert_eq!(pixel, rgb::RGBA8::new(0, 0, 255, 255));
+
+    assert_eq!(
+        rgb::RGBA8::from_color(Color::from_argb_u8(51, 255, 100, 0)),
+        rgb::RGBA8::new(51, 20, 0, 51)
+    );
+}
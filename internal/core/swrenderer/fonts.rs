@@ -5,10 +5,10 @@
 use core::cell::RefCell;
 
 #[cfg(all(not(feature = "std"), feature = "unsafe_single_core"))]
-use i_slint_core::thread_local_ as thread_local;
+use crate::unsafe_single_core::thread_local;
 
-use crate::{LogicalLength, LogicalSize, PhysicalLength, PhysicalSize, ScaleFactor};
-use i_slint_core::{
+use crate::lengths::{LogicalLength, LogicalSize, PhysicalLength, PhysicalSize, ScaleFactor};
+use crate::{
     graphics::{BitmapFont, BitmapGlyph, BitmapGlyphs, FontRequest},
     slice::Slice,
     textlayout::TextShaper,
@@ -67,8 +67,6 @@ fn pixel_size(&self) -> PhysicalLength {
     }
 }
 
-pub const DEFAULT_FONT_SIZE: Coord = 12 as Coord;
-
 // A font that is resolved to a specific pixel size.
 pub struct PixelFont {
     bitmap_font: &'static BitmapFont,
@@ -147,7 +145,8 @@ pub fn match_font(request: &FontRequest, scale_factor: ScaleFactor) -> PixelFont
     });
 
     let requested_pixel_size: PhysicalLength =
-        (LogicalLength::new(request.pixel_size.unwrap_or(DEFAULT_FONT_SIZE)).cast() * scale_factor)
+        (LogicalLength::new(request.pixel_size.unwrap_or(crate::window::DEFAULT_FONT_SIZE)).cast()
+            * scale_factor)
             .cast();
 
     let nearest_pixel_size = font
@@ -167,6 +166,7 @@ pub fn match_font(request: &FontRequest, scale_factor: ScaleFactor) -> PixelFont
     }
 }
 
+/// Makes the pre-rendered glyphs of the font available to the text drawn by the renderer
 pub fn register_bitmap_font(font_data: &'static BitmapFont) {
     FONTS.with(|fonts| fonts.borrow_mut().push(font_data))
 }
@@ -179,7 +179,7 @@ pub fn text_size(
 ) -> LogicalSize {
     let font = match_font(&font_request, scale_factor);
 
-    let (longest_line_width, num_lines) = i_slint_core::textlayout::text_size(
+    let (longest_line_width, num_lines) = crate::textlayout::text_size(
         &font,
         text,
         max_width.map(|max_width| (LogicalLength::new(max_width).cast() * scale_factor).cast()),
@@ -189,3 +189,12 @@ pub fn text_size(
         / scale_factor)
         .cast()
 }
+
+pub fn text_min_content_width(
+    font_request: FontRequest,
+    text: &str,
+    scale_factor: ScaleFactor,
+) -> LogicalLength {
+    let font = match_font(&font_request, scale_factor);
+    (crate::textlayout::min_content_width(&font, text).cast() / scale_factor).cast()
+}
@@ -11,17 +11,14 @@
 use crate::Coord;
 use crate::SharedString;
 
-/// Slint animations do not use real time, but use a mocked time.
+/// Slint animations and timers do not use real time, but use a mocked time.
 /// Normally, the event loop update the time of the animation using
 /// real time, but in tests, it is more convenient to use the fake time.
-/// This function will add some milliseconds to the fake time
+/// This function will add some milliseconds to the fake time, update the animations, and
+/// activate the timers that expire in the meantime, in order.
 #[no_mangle]
 pub extern "C" fn slint_mock_elapsed_time(time_in_ms: u64) {
-    crate::animations::CURRENT_ANIMATION_DRIVER.with(|driver| {
-        let mut tick = driver.current_tick();
-        tick += core::time::Duration::from_millis(time_in_ms);
-        driver.update_animations(tick)
-    })
+    crate::animations::advance_mocked_clock(core::time::Duration::from_millis(time_in_ms));
 }
 
 /// Simulate a click on a position within the component.
@@ -41,6 +38,7 @@ pub extern "C" fn slint_send_mouse_click(
         window,
         state,
     );
+    window.update_hover(Some(pos));
     state = crate::input::process_mouse_input(
         component.clone(),
         MouseEvent::MousePressed { pos, button: crate::items::PointerEventButton::left },
@@ -75,11 +73,13 @@ pub extern "C" fn send_keyboard_string_sequence(
             event_type: KeyEventType::KeyPressed,
             text: text.clone(),
             modifiers,
+            ..Default::default()
         });
         window.clone().process_key_input(&KeyEvent {
             event_type: KeyEventType::KeyReleased,
             text,
             modifiers,
+            ..Default::default()
         });
     }
 }
@@ -51,6 +51,9 @@
 
 use crate::items::{TextHorizontalAlignment, TextOverflow, TextVerticalAlignment, TextWrap};
 
+#[cfg(all(feature = "std", feature = "unicode-script"))]
+pub mod font_fallback;
+
 #[cfg(feature = "unicode-linebreak")]
 mod linebreak_unicode;
 #[cfg(feature = "unicode-linebreak")]
@@ -191,6 +194,17 @@ pub fn line_text<'a>(&self, paragraph: &'a str) -> &'a str {
         &paragraph[self.byte_range.clone()]
     }
 
+    /// The range of the line in the paragraph, without the trailing whitespace. It is empty for the
+    /// blank lines.
+    pub fn byte_range(&self) -> Range<usize> {
+        self.byte_range.clone()
+    }
+
+    /// The width occupied by the glyphs, without the trailing whitespace
+    pub fn width(&self) -> Length {
+        self.text_width
+    }
+
     pub fn width_including_trailing_whitespace(&self) -> Length {
         self.text_width + self.trailing_whitespace.as_ref().map_or(Length::zero(), |ws| ws.width)
     }
@@ -551,6 +565,105 @@ pub fn text_size<Font: TextShaper>(
     (max_line_width, line_count)
 }
 
+// Returns the width of the widest sequence of glyphs between two break opportunities, which is
+// the narrowest width the text can be wrapped to without breaking a word (the min-content width).
+// The width of the text when it is not wrapped (the max-content width) is returned by `text_size`.
+pub fn min_content_width<Font: TextShaper>(font: &Font, text: &str) -> Font::Length {
+    let glyphs = RefCell::new(Vec::new());
+    let mut line_breaks = LineBreakIterator::new(text);
+    let mut next_break_opportunity = line_breaks.next();
+
+    let mut widest_word = Font::Length::zero();
+    let mut word_width = Font::Length::zero();
+    // Whitespace within a word, like a non-breaking space, only counts if the word goes on
+    let mut pending_whitespace_width = Font::Length::zero();
+    for grapheme in GraphemeCursor::new(text, font, &glyphs) {
+        while let Some((offset, _)) = next_break_opportunity {
+            if offset > grapheme.byte_range.start {
+                break;
+            }
+            word_width = Font::Length::zero();
+            pending_whitespace_width = Font::Length::zero();
+            next_break_opportunity = line_breaks.next();
+        }
+        if grapheme.is_whitespace {
+            pending_whitespace_width += grapheme.width;
+        } else {
+            word_width += pending_whitespace_width + grapheme.width;
+            pending_whitespace_width = Font::Length::zero();
+            widest_word = euclid::approxord::max(widest_word, word_width);
+        }
+    }
+
+    widest_word
+}
+
+/// Finds where the paragraphs start in a sequence of lines. The paragraphs are separated by one
+/// or more blank lines, or by the U+2029 PARAGRAPH SEPARATOR character.
+#[derive(Default)]
+pub struct ParagraphBreaks {
+    seen_text: bool,
+    after_blank_line: bool,
+}
+
+impl ParagraphBreaks {
+    /// Returns true if the line starts a paragraph, other than the first one. The blank lines
+    /// separate the paragraphs and are not part of them.
+    /// `after_paragraph_separator` is true if the line follows a U+2029 character.
+    pub fn is_new_paragraph(&mut self, is_blank: bool, after_paragraph_separator: bool) -> bool {
+        if is_blank {
+            self.after_blank_line = true;
+            return false;
+        }
+        let new_paragraph = self.seen_text && (self.after_blank_line || after_paragraph_separator);
+        self.seen_text = true;
+        self.after_blank_line = false;
+        new_paragraph
+    }
+
+    fn is_new_paragraph_line<Length: Default + Clone>(
+        &mut self,
+        text: &str,
+        line: &TextLine<Length>,
+    ) -> bool {
+        self.is_new_paragraph(
+            line.byte_range.is_empty(),
+            text[..line.byte_range.start].ends_with('\u{2029}'),
+        )
+    }
+}
+
+/// Returns the number of paragraph breaks in the text, after which the paragraph spacing is added.
+/// The paragraphs are separated by one or more blank lines, or by the U+2029 PARAGRAPH SEPARATOR
+/// character. This doesn't depend on how the lines are wrapped.
+pub fn paragraph_break_count(text: &str) -> usize {
+    let mut breaks = ParagraphBreaks::default();
+    let mut after_paragraph_separator = false;
+    let mut count = 0;
+    for line in text.split_inclusive(|c| matches!(c, '\n' | '\u{2028}' | '\u{2029}')) {
+        if breaks.is_new_paragraph(line.trim().is_empty(), after_paragraph_separator) {
+            count += 1;
+        }
+        after_paragraph_separator = line.ends_with('\u{2029}');
+    }
+    count
+}
+
+/// The position of a paragraph laid out by [`TextParagraphLayout`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParagraphMetrics<Length> {
+    /// The range of the paragraph in the string, without the blank lines that separate it from the
+    /// other paragraphs
+    pub byte_range: Range<usize>,
+    /// The y coordinate of the top of the first line, like the one passed to the callback of
+    /// [`TextParagraphLayout::layout_lines`]
+    pub y: Length,
+    /// The height of the lines of the paragraph
+    pub height: Length,
+    /// The number of lines, including the ones produced by wrapping the text
+    pub line_count: usize,
+}
+
 pub struct TextParagraphLayout<'a, Font: TextShaper> {
     pub string: &'a str,
     pub font: &'a Font,
@@ -561,7 +674,12 @@ pub struct TextParagraphLayout<'a, Font: TextShaper> {
     pub vertical_alignment: TextVerticalAlignment,
     pub wrap: TextWrap,
     pub overflow: TextOverflow,
+    /// The text drawn at the end of the elided lines, when `overflow` is `elide`. "..." is drawn
+    /// instead when the font has no glyph for it.
+    pub ellipsis: &'a str,
     pub single_line: bool,
+    /// The space added between the paragraphs, in addition to the blank lines separating them
+    pub paragraph_spacing: Font::Length,
 }
 
 impl<'a, Font: TextShaper> TextParagraphLayout<'a, Font> {
@@ -577,13 +695,19 @@ pub fn layout_lines(
         ),
     ) -> Font::Length {
         let wrap = self.wrap == TextWrap::word_wrap;
-        let elide_glyph = if self.overflow == TextOverflow::elide {
-            self.font.glyph_for_char('…')
+        let elide = self.overflow == TextOverflow::elide;
+        let elide_glyphs = if elide {
+            let glyphs_for = |text: &str| -> Option<Vec<_>> {
+                text.chars().map(|ch| self.font.glyph_for_char(ch)).collect()
+            };
+            glyphs_for(self.ellipsis).or_else(|| glyphs_for("...")).unwrap_or_default()
         } else {
-            None
+            Vec::new()
         };
         let max_width_without_elision = self.max_width
-            - elide_glyph.as_ref().map_or(Font::Length::zero(), |g| self.font.glyph_advance_x(g));
+            - elide_glyphs.iter().fold(Font::Length::zero(), |width, glyph| {
+                width + self.font.glyph_advance_x(glyph)
+            });
 
         let glyphs = RefCell::new(Vec::new());
 
@@ -601,8 +725,10 @@ pub fn layout_lines(
             if self.single_line {
                 self.font_height
             } else {
-                text_lines = Some(new_line_break_iter(glyphs).collect::<Vec<_>>());
-                self.font_height * (text_lines.as_ref().unwrap().len() as i16).into()
+                let lines = new_line_break_iter(glyphs).collect::<Vec<_>>();
+                let height = self.lines_height(&lines);
+                text_lines = Some(lines);
+                height
             }
         };
 
@@ -615,6 +741,7 @@ pub fn layout_lines(
         };
 
         let mut y = baseline_y;
+        let mut paragraph_breaks = ParagraphBreaks::default();
 
         let mut process_line =
             |line: &TextLine<Font::Length>, glyphs: &RefCell<Vec<(Font::Glyph, usize)>>| {
@@ -629,24 +756,43 @@ pub fn layout_lines(
                     }
                 };
 
-                let mut elide_glyph = elide_glyph.as_ref().clone();
+                if paragraph_breaks.is_new_paragraph_line(self.string, line) {
+                    y += self.paragraph_spacing;
+                }
 
                 let glyphs = glyphs.borrow();
-                let glyph_it = glyphs[line.glyph_range.clone()].iter();
-                let mut glyph_x = Font::Length::zero();
-                let mut positioned_glyph_it = glyph_it.filter_map(|(glyph, _)| {
-                    // TODO: cut off at grapheme boundaries
-                    if glyph_x > max_width_without_elision {
-                        if let Some(elide_glyph) = elide_glyph.take() {
-                            return Some((glyph_x, elide_glyph));
-                        } else {
-                            return None;
+                let line_glyphs = &glyphs[line.glyph_range.clone()];
+
+                // The number of glyphs drawn before the ellipsis, if the line is elided
+                let mut elided_glyph_count = None;
+                if elide {
+                    let mut glyph_x = Font::Length::zero();
+                    for (index, (glyph, _)) in line_glyphs.iter().enumerate() {
+                        if glyph_x > max_width_without_elision {
+                            elided_glyph_count = Some(index);
+                            break;
                         }
+                        glyph_x += self.font.glyph_advance_x(glyph);
+                    }
+                }
+                // Don't cut a grapheme: the glyphs of a grapheme share the byte offset of its cluster
+                if let Some(count) = elided_glyph_count.as_mut() {
+                    while *count > 0 && line_glyphs[*count].1 == line_glyphs[*count - 1].1 {
+                        *count -= 1;
                     }
-                    let positioned_glyph = (glyph_x, glyph);
-                    glyph_x += self.font.glyph_advance_x(glyph);
-                    Some(positioned_glyph)
-                });
+                }
+
+                let shown_glyphs =
+                    line_glyphs[..elided_glyph_count.unwrap_or(line_glyphs.len())].iter();
+                let ellipsis =
+                    elided_glyph_count.map(|_| elide_glyphs.iter()).into_iter().flatten();
+                let mut glyph_x = Font::Length::zero();
+                let mut positioned_glyph_it =
+                    shown_glyphs.map(|(glyph, _)| glyph).chain(ellipsis).map(|glyph| {
+                        let positioned_glyph = (glyph_x, glyph);
+                        glyph_x += self.font.glyph_advance_x(glyph);
+                        positioned_glyph
+                    });
 
                 line_callback(&mut positioned_glyph_it, x, y);
                 y += self.font_height;
@@ -664,6 +810,67 @@ pub fn layout_lines(
 
         baseline_y
     }
+
+    /// Returns the position of the paragraphs, which are separated by one or more blank lines or
+    /// by the U+2029 PARAGRAPH SEPARATOR character, when laid out by [`Self::layout_lines`].
+    pub fn paragraph_metrics(&self) -> Vec<ParagraphMetrics<Font::Length>> {
+        let glyphs = RefCell::new(Vec::new());
+        let lines = TextLineBreaker::new(
+            self.string,
+            self.font,
+            &glyphs,
+            if self.wrap == TextWrap::word_wrap { Some(self.max_width) } else { None },
+        )
+        .collect::<Vec<_>>();
+
+        let two = Font::LengthPrimitive::one() + Font::LengthPrimitive::one();
+        let text_height =
+            if self.single_line { self.font_height } else { self.lines_height(&lines) };
+        let mut y = match self.vertical_alignment {
+            TextVerticalAlignment::top => Font::Length::zero(),
+            TextVerticalAlignment::center => self.max_height / two - text_height / two,
+            TextVerticalAlignment::bottom => self.max_height - text_height,
+        };
+
+        let mut paragraphs: Vec<ParagraphMetrics<Font::Length>> = Vec::new();
+        let mut paragraph_breaks = ParagraphBreaks::default();
+        for line in &lines {
+            let new_paragraph = paragraph_breaks.is_new_paragraph_line(self.string, line);
+            if new_paragraph {
+                y += self.paragraph_spacing;
+            }
+            if !line.byte_range.is_empty() {
+                match paragraphs.last_mut() {
+                    Some(paragraph) if !new_paragraph => {
+                        paragraph.byte_range.end = line.byte_range.end;
+                        paragraph.height = y + self.font_height - paragraph.y;
+                        paragraph.line_count += 1;
+                    }
+                    _ => paragraphs.push(ParagraphMetrics {
+                        byte_range: line.byte_range.clone(),
+                        y,
+                        height: self.font_height,
+                        line_count: 1,
+                    }),
+                }
+            }
+            y += self.font_height;
+        }
+        paragraphs
+    }
+
+    /// The height of the lines, with the spacing between the paragraphs
+    fn lines_height(&self, lines: &[TextLine<Font::Length>]) -> Font::Length {
+        let mut paragraph_breaks = ParagraphBreaks::default();
+        lines.iter().fold(Font::Length::zero(), |height, line| {
+            let spacing = if paragraph_breaks.is_new_paragraph_line(self.string, line) {
+                self.paragraph_spacing
+            } else {
+                Font::Length::zero()
+            };
+            height + spacing + self.font_height
+        })
+    }
 }
 
 #[test]
@@ -852,6 +1059,10 @@ fn shape_text<GlyphStorage: std::iter::Extend<(ShapedGlyph, usize)>>(
         }
 
         fn glyph_for_char(&self, ch: char) -> Option<Self::Glyph> {
+            // A character that the font doesn't have
+            if ch == '⋯' {
+                return None;
+            }
             ShapedGlyph {
                 offset_x: 0.,
                 offset_y: 0.,
@@ -958,6 +1169,16 @@ fn test_basic_line_break_anywhere_fallback() {
         assert_eq!(lines[1].line_text(&text), "World");
     }
 
+    #[test]
+    fn test_min_content_width() {
+        let font = FixedTestFont;
+        assert_eq!(min_content_width(&font, ""), 0.);
+        assert_eq!(min_content_width(&font, "Hello World"), 50.);
+        assert_eq!(min_content_width(&font, "A longer\nword"), 60.);
+        assert_eq!(min_content_width(&font, "Not\u{00a0}broken here"), 100.);
+        assert_eq!(min_content_width(&font, "   "), 0.);
+    }
+
     #[test]
     fn test_elision() {
         let font = FixedTestFont;
@@ -975,7 +1196,9 @@ fn test_elision() {
             vertical_alignment: TextVerticalAlignment::top,
             wrap: TextWrap::no_wrap,
             overflow: TextOverflow::elide,
+            ellipsis: "…",
             single_line: true,
+            paragraph_spacing: 0.,
         };
         paragraph.layout_lines(|glyphs, _, _| {
             lines.push(glyphs.map(|(_, g)| g.clone()).collect::<Vec<_>>());
@@ -1003,7 +1226,9 @@ fn test_exact_fit() {
             vertical_alignment: TextVerticalAlignment::top,
             wrap: TextWrap::no_wrap,
             overflow: TextOverflow::elide,
+            ellipsis: "…",
             single_line: true,
+            paragraph_spacing: 0.,
         };
         paragraph.layout_lines(|glyphs, _, _| {
             lines.push(glyphs.map(|(_, g)| g.clone()).collect::<Vec<_>>());
@@ -1013,4 +1238,76 @@ fn test_exact_fit() {
         let rendered_text = lines[0].iter().map(|glyph| glyph.char.unwrap()).collect::<String>();
         debug_assert_eq!(rendered_text, "Fits")
     }
+
+    #[test]
+    fn test_elision_token() {
+        let font = FixedTestFont;
+        let text = "This is a longer piece of text";
+
+        let elide_with = |ellipsis| {
+            let mut lines = Vec::new();
+            let paragraph = TextParagraphLayout {
+                string: text,
+                font: &font,
+                font_height: 10.,
+                max_width: 13. * 10.,
+                max_height: 10.,
+                horizontal_alignment: TextHorizontalAlignment::left,
+                vertical_alignment: TextVerticalAlignment::top,
+                wrap: TextWrap::no_wrap,
+                overflow: TextOverflow::elide,
+                ellipsis,
+                single_line: true,
+                paragraph_spacing: 0.,
+            };
+            paragraph.layout_lines(|glyphs, _, _| {
+                lines.push(glyphs.map(|(_, g)| g.char.unwrap()).collect::<String>());
+            });
+            lines
+        };
+
+        assert_eq!(elide_with(" [more]"), vec!["This is [more]"]);
+        // The font has no glyph for '⋯'
+        assert_eq!(elide_with("⋯"), vec!["This is a l..."]);
+        assert_eq!(elide_with(""), vec!["This is a long"]);
+    }
+
+    #[test]
+    fn test_paragraph_spacing() {
+        let font = FixedTestFont;
+        let text = "Hello World\n\nSecond\u{2029}Third";
+
+        assert_eq!(paragraph_break_count(text), 2);
+        assert_eq!(paragraph_break_count("One\nparagraph"), 0);
+        assert_eq!(paragraph_break_count("\n\nOne\n\n"), 0);
+
+        let paragraph = TextParagraphLayout {
+            string: text,
+            font: &font,
+            font_height: 10.,
+            max_width: 6. * 10.,
+            max_height: 100.,
+            horizontal_alignment: TextHorizontalAlignment::left,
+            vertical_alignment: TextVerticalAlignment::top,
+            wrap: TextWrap::word_wrap,
+            overflow: TextOverflow::clip,
+            ellipsis: "…",
+            single_line: false,
+            paragraph_spacing: 5.,
+        };
+
+        let mut line_positions = Vec::new();
+        paragraph.layout_lines(|_, _, y| line_positions.push(y));
+        assert_eq!(line_positions, vec![0., 10., 20., 35., 50.]);
+
+        let metrics = paragraph.paragraph_metrics();
+        assert_eq!(
+            metrics,
+            vec![
+                ParagraphMetrics { byte_range: 0..11, y: 0., height: 20., line_count: 2 },
+                ParagraphMetrics { byte_range: 13..19, y: 35., height: 10., line_count: 1 },
+                ParagraphMetrics { byte_range: 22..27, y: 50., height: 10., line_count: 1 },
+            ]
+        );
+    }
 }
@@ -0,0 +1,268 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Selection of the fonts needed to render a text: the primary font of the request, followed by the
+fallback fonts that have glyphs for the characters that the fonts before them don't have.
+
+The renderers provide the fonts with the [`FontSource`] trait, and keep a [`GlyphCoverageCache`]
+of what is known about the glyphs of each face. The characters of a script are assumed to be
+covered by a face if it has a glyph for one of them. The characters that are shared between the
+scripts, like the digits or the punctuation, are checked one by one.
+
+The characters that none of the fonts has a glyph for are reported in the [`FontSelection`], so that
+the renderers can explain why a text is displayed with missing glyphs.
+*/
+
+use alloc::vec::Vec;
+use std::collections::{HashMap, HashSet};
+use unicode_script::{Script, UnicodeScript};
+
+/// The fonts of a renderer, and the fallback fonts of the platform
+pub trait FontSource {
+    /// A font that can be requested, such as a family with a size and a weight
+    type Font;
+    /// Identifies the face that a font resolves to
+    type FaceId: Copy + Eq + core::hash::Hash;
+
+    /// Returns the face of the font, loading it if needed
+    fn face_id(&mut self, font: &Self::Font) -> Self::FaceId;
+
+    /// Returns the fonts to try, in order, when `primary` doesn't have a glyph for all the
+    /// characters of `text`
+    fn fallback_fonts(&mut self, primary: &Self::Font, text: &str) -> Vec<Self::Font>;
+
+    /// Returns, for each of the characters, whether the face has a glyph for it
+    fn has_glyphs(&mut self, face: Self::FaceId, chars: &[char]) -> Vec<bool>;
+}
+
+#[derive(Default)]
+struct GlyphCoverage {
+    // Used to express script support for all scripts except Unknown, Common and Inherited
+    // For those the detailed glyph_coverage is used instead
+    supported_scripts: HashMap<Script, bool>,
+    // Especially in characters mapped to the common script, the support varies. For example
+    // '✓' and the digit '1' map to Common, but not all fonts providing digits also support the
+    // check mark glyph.
+    exact_glyph_coverage: HashMap<char, bool>,
+}
+
+/// What is known about the glyphs of the faces, so that they are only checked once
+pub struct GlyphCoverageCache<FaceId> {
+    faces: HashMap<FaceId, GlyphCoverage>,
+}
+
+impl<FaceId> Default for GlyphCoverageCache<FaceId> {
+    fn default() -> Self {
+        Self { faces: HashMap::new() }
+    }
+}
+
+/// The scripts and the characters of a text that no font covers yet
+struct RequiredCoverage {
+    // map from required script to sample character
+    scripts: HashMap<Script, char>,
+    chars: HashSet<char>,
+}
+
+impl RequiredCoverage {
+    fn new(text: &str) -> Self {
+        let mut scripts = HashMap::new();
+        let mut chars = HashSet::new();
+        for ch in text.chars() {
+            if ch.is_control() || ch.is_whitespace() {
+                continue;
+            }
+            let script = ch.script();
+            if script == Script::Common || script == Script::Inherited || script == Script::Unknown
+            {
+                chars.insert(ch);
+            } else {
+                scripts.insert(script, ch);
+            }
+        }
+        Self { scripts, chars }
+    }
+
+    fn is_complete(&self) -> bool {
+        self.scripts.is_empty() && self.chars.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.scripts.len() + self.chars.len()
+    }
+
+    fn into_missing_characters(self) -> Vec<char> {
+        let mut missing_characters =
+            self.scripts.into_values().chain(self.chars).collect::<Vec<_>>();
+        missing_characters.sort_unstable();
+        missing_characters
+    }
+}
+
+impl<FaceId: Copy + Eq + core::hash::Hash> GlyphCoverageCache<FaceId> {
+    /// Removes the scripts and the characters that the face covers from `required`, checking the
+    /// glyphs of the face that are not known yet. Returns true if anything was removed.
+    fn cover<Source: FontSource<FaceId = FaceId>>(
+        &mut self,
+        source: &mut Source,
+        face: FaceId,
+        required: &mut RequiredCoverage,
+    ) -> bool {
+        let old_len = required.len();
+        let coverage = self.faces.entry(face).or_default();
+
+        let mut scripts_that_need_checking = Vec::new();
+        let mut chars_that_need_checking = Vec::new();
+        required.scripts.retain(|script, sample| match coverage.supported_scripts.get(script) {
+            Some(has_coverage) => !has_coverage,
+            None => {
+                scripts_that_need_checking.push((*script, *sample));
+                true
+            }
+        });
+        required.chars.retain(|ch| match coverage.exact_glyph_coverage.get(ch) {
+            Some(has_coverage) => !has_coverage,
+            None => {
+                chars_that_need_checking.push(*ch);
+                true
+            }
+        });
+
+        if !scripts_that_need_checking.is_empty() || !chars_that_need_checking.is_empty() {
+            let chars = scripts_that_need_checking
+                .iter()
+                .map(|(_, sample)| *sample)
+                .chain(chars_that_need_checking.iter().copied())
+                .collect::<Vec<_>>();
+            let mut has_glyphs = source.has_glyphs(face, &chars).into_iter();
+            for (script, _) in scripts_that_need_checking {
+                let has_glyph = has_glyphs.next().unwrap_or(false);
+                coverage.supported_scripts.insert(script, has_glyph);
+                if has_glyph {
+                    required.scripts.remove(&script);
+                }
+            }
+            for ch in chars_that_need_checking {
+                let has_glyph = has_glyphs.next().unwrap_or(false);
+                coverage.exact_glyph_coverage.insert(ch, has_glyph);
+                if has_glyph {
+                    required.chars.remove(&ch);
+                }
+            }
+        }
+
+        required.len() < old_len
+    }
+}
+
+/// The fonts selected by [`select_fonts`] for a text
+#[derive(Debug, PartialEq)]
+pub struct FontSelection<Font> {
+    /// The primary font, followed by the fallback fonts that cover characters that the fonts
+    /// before them don't
+    pub fonts: Vec<Font>,
+    /// The characters of the text that none of the fonts has a glyph for, sorted. Only one
+    /// character is listed for each of the scripts that are not shared, like the Greek script.
+    pub missing_characters: Vec<char>,
+}
+
+/// Returns the fonts needed to render `text`: `primary`, followed by the fallback fonts of the source
+/// that cover characters that the fonts before them don't. The fallback fonts are only requested
+/// and loaded if the primary font doesn't cover the whole text.
+pub fn select_fonts<Source: FontSource>(
+    source: &mut Source,
+    coverage: &mut GlyphCoverageCache<Source::FaceId>,
+    primary: Source::Font,
+    text: &str,
+) -> FontSelection<Source::Font> {
+    let mut required = RequiredCoverage::new(text);
+    let primary_face = source.face_id(&primary);
+    coverage.cover(source, primary_face, &mut required);
+    if required.is_complete() {
+        return FontSelection { fonts: alloc::vec![primary], missing_characters: Vec::new() };
+    }
+
+    let fallbacks = source.fallback_fonts(&primary, text);
+    let mut fonts = alloc::vec![primary];
+    for fallback in fallbacks {
+        if required.is_complete() {
+            break;
+        }
+        let face = source.face_id(&fallback);
+        if coverage.cover(source, face, &mut required) {
+            fonts.push(fallback);
+        }
+    }
+    FontSelection { fonts, missing_characters: required.into_missing_characters() }
+}
+
+/// Returns the name of the script of the character, such as "Greek" or "Common", to describe
+/// the missing characters of a [`FontSelection`].
+pub fn script_name(ch: char) -> &'static str {
+    ch.script().full_name()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fonts named after the characters they have
+    struct TestSource {
+        fallbacks: Vec<&'static str>,
+        checked_chars: usize,
+    }
+
+    impl FontSource for TestSource {
+        type Font = &'static str;
+        type FaceId = &'static str;
+
+        fn face_id(&mut self, font: &Self::Font) -> Self::FaceId {
+            font
+        }
+
+        fn fallback_fonts(&mut self, _primary: &Self::Font, _text: &str) -> Vec<Self::Font> {
+            self.fallbacks.clone()
+        }
+
+        fn has_glyphs(&mut self, face: Self::FaceId, chars: &[char]) -> Vec<bool> {
+            self.checked_chars += chars.len();
+            chars.iter().map(|ch| face.contains(*ch)).collect()
+        }
+    }
+
+    #[test]
+    fn test_select_fonts() {
+        let mut source =
+            TestSource { fallbacks: vec!["xyz", "ab✓", "αβγδ", "✓"], checked_chars: 0 };
+        let mut coverage = GlyphCoverageCache::default();
+        let fonts = |source: &mut TestSource, coverage: &mut _, text| {
+            select_fonts(source, coverage, "abc", text).fonts
+        };
+
+        assert_eq!(fonts(&mut source, &mut coverage, "a b"), vec!["abc"]);
+        // The primary font is enough, the fallbacks are not checked
+        assert_eq!(source.checked_chars, 1);
+
+        // One character of the Greek script is enough to cover the script, but ✓ is checked exactly
+        assert_eq!(fonts(&mut source, &mut coverage, "a ✓ δ"), vec!["abc", "ab✓", "αβγδ"]);
+
+        // The coverage is cached
+        let checked_chars = source.checked_chars;
+        assert_eq!(fonts(&mut source, &mut coverage, "b ✓ ε"), vec!["abc", "ab✓", "αβγδ"]);
+        assert_eq!(source.checked_chars, checked_chars);
+    }
+
+    #[test]
+    fn test_missing_characters() {
+        let mut source = TestSource { fallbacks: vec!["ab✓", "αβγδ"], checked_chars: 0 };
+        let mut coverage = GlyphCoverageCache::default();
+
+        assert_eq!(
+            select_fonts(&mut source, &mut coverage, "abc", "a € ✓ ж"),
+            FontSelection { fonts: vec!["abc", "ab✓"], missing_characters: vec!['ж', '€'] }
+        );
+        assert_eq!(script_name('ж'), "Cyrillic");
+        assert_eq!(script_name('€'), "Common");
+    }
+}
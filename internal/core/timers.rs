@@ -254,6 +254,11 @@ pub fn maybe_activate_timers() -> bool {
                 }
             }
 
+            #[cfg(feature = "std")]
+            if any_activated {
+                crate::recording::record(|| crate::recording::RecordedEvent::Timers);
+            }
+
             any_activated
         })
     }
@@ -444,3 +449,29 @@ pub extern "C" fn slint_timer_running(id: i64) -> bool {
         running
     }
 }
+
+#[test]
+fn test_mocked_clock() {
+    use alloc::rc::Rc;
+    crate::animations::enable_mocked_clock();
+    let start = Instant::now();
+    let ticks = Rc::new(Cell::new(0));
+    let timer = Timer::default();
+    timer.start(TimerMode::Repeated, core::time::Duration::from_millis(100), {
+        let ticks = ticks.clone();
+        move || ticks.set(ticks.get() + 1)
+    });
+    let single_shot_time = Rc::new(Cell::new(None));
+    Timer::single_shot(core::time::Duration::from_millis(250), {
+        let single_shot_time = single_shot_time.clone();
+        move || single_shot_time.set(Some(Instant::now()))
+    });
+
+    crate::animations::advance_mocked_clock(core::time::Duration::from_millis(99));
+    assert_eq!(ticks.get(), 0);
+    crate::animations::advance_mocked_clock(core::time::Duration::from_millis(901));
+    assert_eq!(ticks.get(), 10);
+    assert_eq!(single_shot_time.get(), Some(start + core::time::Duration::from_millis(250)));
+    assert_eq!(Instant::now() - start, core::time::Duration::from_millis(1000));
+    assert_eq!(crate::animations::current_tick(), Instant::now());
+}
@@ -0,0 +1,200 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+    Two-way bindings between a value owned by the Rust code and a property of a component.
+*/
+
+#![warn(missing_docs)]
+
+use crate::api::{ComponentHandle, Weak};
+use crate::properties::{Property, PropertyTracker};
+use crate::timers::Timer;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use core::cell::{Cell, RefCell};
+use core::pin::Pin;
+
+/// A value owned by the Rust code, typically a field of a view-model, that can be kept in sync with
+/// a property of a component with a [`TwoWayBinding`].
+///
+/// Cloning a `SharedValue` returns another handle to the same value.
+pub struct SharedValue<T>(Pin<Rc<Property<T>>>);
+
+impl<T> Clone for SharedValue<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: Clone + Default> Default for SharedValue<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: Clone> SharedValue<T> {
+    /// Creates a new value
+    pub fn new(value: T) -> Self {
+        Self(Rc::pin(Property::new(value)))
+    }
+
+    /// Returns the value
+    pub fn get(&self) -> T {
+        self.0.as_ref().get()
+    }
+
+    /// Changes the value. The properties bound to it are updated on the next iteration of the event loop.
+    pub fn set(&self, value: T)
+    where
+        T: PartialEq,
+    {
+        self.0.as_ref().set(value)
+    }
+}
+
+type ChangeHandler = Box<dyn Fn()>;
+
+/// Runs the synchronization of a binding on the event loop, once for all the changes made until then
+#[derive(Default)]
+struct Scheduler {
+    pending: Cell<bool>,
+    sync: RefCell<Option<Box<dyn Fn()>>>,
+}
+
+impl Scheduler {
+    fn change_handler(self: &Rc<Self>) -> ChangeHandler {
+        let scheduler = Rc::downgrade(self);
+        Box::new(move || {
+            let scheduler = match scheduler.upgrade() {
+                Some(scheduler) => scheduler,
+                None => return,
+            };
+            if scheduler.pending.replace(true) {
+                return;
+            }
+            let scheduler = Rc::downgrade(&scheduler);
+            Timer::single_shot(core::time::Duration::ZERO, move || {
+                if let Some(scheduler) = scheduler.upgrade() {
+                    // The changes made by the synchronization itself don't need another one
+                    if let Some(sync) = &*scheduler.sync.borrow() {
+                        sync();
+                    }
+                    scheduler.pending.set(false);
+                }
+            });
+        })
+    }
+}
+
+struct TwoWayBindingInner<C: ComponentHandle, T> {
+    component: Weak<C>,
+    get: Box<dyn Fn(&C) -> T>,
+    set: Box<dyn Fn(&C, T)>,
+    value: SharedValue<T>,
+    /// The value of both sides after the last synchronization
+    last: RefCell<T>,
+    component_tracker: Pin<Box<PropertyTracker<ChangeHandler>>>,
+    value_tracker: Pin<Box<PropertyTracker<ChangeHandler>>>,
+    _scheduler: Rc<Scheduler>,
+}
+
+impl<C: ComponentHandle, T: Clone + PartialEq> TwoWayBindingInner<C, T> {
+    fn sync(&self) {
+        let component = match self.component.upgrade() {
+            Some(component) => component,
+            None => return,
+        };
+        let component_value = (self.get)(&component);
+        let value = self.value.0.as_ref().get_untracked();
+        let mut last = self.last.borrow_mut();
+        if component_value != *last {
+            self.value.set(component_value.clone());
+            *last = component_value;
+        } else if value != *last {
+            (self.set)(&component, value.clone());
+            *last = value;
+        }
+        drop(last);
+        self.track(&component);
+    }
+
+    /// Records the dependencies of both sides, so that the next change schedules a synchronization
+    fn track(&self, component: &C) {
+        self.component_tracker.as_ref().evaluate_as_dependency_root(|| (self.get)(component));
+        self.value_tracker.as_ref().evaluate_as_dependency_root(|| self.value.get());
+    }
+}
+
+/// Keeps a [`SharedValue`] and a property of a component in sync in both directions.
+///
+/// When the binding is created, the property is set to the value. Then, when one side changes, the
+/// other one is updated on the next iteration of the event loop. The changes made until then are
+/// coalesced into a single update. If both sides changed, the property of the component wins, since
+/// it's usually the last input of the user.
+///
+/// The property is accessed with the getter and the setter generated for it. The synchronization
+/// stops when the `TwoWayBinding` is dropped, or when the component is destroyed.
+///
+/// # Example
+/// ```rust
+/// # i_slint_backend_testing::init();
+/// slint::slint! { MyApp := Window { property <int> counter; } }
+/// let app = MyApp::new();
+/// let counter = slint::SharedValue::new(42);
+/// let _binding =
+///     slint::TwoWayBinding::new(app.as_weak(), MyApp::get_counter, MyApp::set_counter, &counter);
+/// assert_eq!(app.get_counter(), 42);
+///
+/// app.set_counter(43);
+/// # slint::testing::mock_elapsed_time(0);
+/// // ... on the next iteration of the event loop
+/// assert_eq!(counter.get(), 43);
+///
+/// counter.set(1);
+/// counter.set(2);
+/// # slint::testing::mock_elapsed_time(0);
+/// // ... on the next iteration of the event loop
+/// assert_eq!(app.get_counter(), 2);
+/// ```
+#[must_use = "the synchronization stops when the TwoWayBinding is dropped"]
+pub struct TwoWayBinding {
+    _inner: Rc<dyn core::any::Any>,
+}
+
+impl TwoWayBinding {
+    /// Creates a binding between `value` and the property of `component` accessed with `get` and `set`.
+    pub fn new<C: ComponentHandle + 'static, T: Clone + PartialEq + 'static>(
+        component: Weak<C>,
+        get: impl Fn(&C) -> T + 'static,
+        set: impl Fn(&C, T) + 'static,
+        value: &SharedValue<T>,
+    ) -> Self {
+        let scheduler = Rc::new(Scheduler::default());
+        let inner = Rc::new(TwoWayBindingInner {
+            component,
+            get: Box::new(get),
+            set: Box::new(set),
+            value: value.clone(),
+            last: RefCell::new(value.0.as_ref().get_untracked()),
+            component_tracker: Box::pin(PropertyTracker::new_with_change_handler(
+                scheduler.change_handler(),
+            )),
+            value_tracker: Box::pin(PropertyTracker::new_with_change_handler(
+                scheduler.change_handler(),
+            )),
+            _scheduler: scheduler.clone(),
+        });
+        if let Some(component) = inner.component.upgrade() {
+            (inner.set)(&component, value.0.as_ref().get_untracked());
+            inner.track(&component);
+        }
+        let weak_inner = Rc::downgrade(&inner);
+        *scheduler.sync.borrow_mut() = Some(Box::new(move || {
+            if let Some(inner) = weak_inner.upgrade() {
+                inner.sync();
+            }
+        }));
+        Self { _inner: inner }
+    }
+}
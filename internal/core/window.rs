@@ -6,27 +6,27 @@
 #![warn(missing_docs)]
 //! Exposed Window API
 
-use crate::api::CloseRequestResponse;
+use crate::api::{CloseRequestResponse, DisplayPower};
 use crate::component::{ComponentRc, ComponentWeak};
 use crate::graphics::{Point, Rect, Size};
 use crate::input::{key_codes, KeyEvent, MouseEvent, MouseInputState, TextCursorBlinker};
 use crate::item_tree::ItemRc;
-use crate::items::{ItemRef, KeyEventType, MouseCursor};
+use crate::items::{ColorScheme, FocusGroupPolicy, ItemRef, KeyEventType, MouseCursor};
 use crate::properties::{Property, PropertyTracker};
 use crate::{Callback, Coord};
 use alloc::boxed::Box;
 use alloc::rc::{Rc, Weak};
+use alloc::vec::Vec;
 use core::cell::{Cell, RefCell};
 use core::pin::Pin;
 
+/// The font size used when none is set on the Window, in logical pixels
+pub const DEFAULT_FONT_SIZE: Coord = 12 as Coord;
+
 fn next_focus_item(item: ItemRc) -> ItemRc {
     item.next_focus_item()
 }
 
-fn previous_focus_item(item: ItemRc) -> ItemRc {
-    item.previous_focus_item()
-}
-
 /// This trait represents the interface that the generated code and the run-time
 /// require in order to implement functionality such as device-independent pixels,
 /// window resizing and other typically windowing system related tasks.
@@ -83,6 +83,18 @@ fn text_size(
         max_width: Option<Coord>,
     ) -> Size;
 
+    /// Returns the width of the widest word of the given text in logical pixels, which is the
+    /// narrowest width the text can be wrapped to without breaking a word.
+    fn text_min_content_width(
+        &self,
+        font_request: crate::graphics::FontRequest,
+        text: &str,
+    ) -> Coord {
+        text.split_whitespace()
+            .map(|word| self.text_size(font_request.clone(), word, None).width)
+            .fold(0 as Coord, |widest, width| if width > widest { width } else { widest })
+    }
+
     /// Returns the (UTF-8) byte offset in the text property that refers to the character that contributed to
     /// the glyph cluster that's visually nearest to the given coordinate. This is used for hit-testing,
     /// for example when receiving a mouse click into a text field. Then this function returns the "cursor"
@@ -102,12 +114,37 @@ fn text_input_cursor_rect_for_byte_offset(
         byte_offset: usize,
     ) -> Rect;
 
+    /// Returns the outlines of the glyphs of the given line of text in logical pixels, as the text would
+    /// be drawn at the origin with the top of the line at y = 0. This is used to export the text as
+    /// paths, for example by [`crate::api::Window::export_svg()`]. Returns None if the backend can't
+    /// provide the outlines of its fonts.
+    #[cfg(feature = "std")]
+    fn text_outline(
+        &self,
+        _font_request: crate::graphics::FontRequest,
+        _text: &str,
+    ) -> Option<lyon_path::Path> {
+        None
+    }
+
     /// This is called when the virtual keyboard should be shown because a widget that
     /// uses input has the focus.
     fn show_virtual_keyboard(&self, _: crate::items::InputType) {}
     /// This is called when the widget that needed the keyboard loses focus
     fn hide_virtual_keyboard(&self) {}
 
+    /// This is called when the keyboard focus moves from the `_old` item to the `_new` one, for example
+    /// so that assistive technologies can follow it.
+    fn handle_focus_change(&self, _old: Option<ItemRc>, _new: Option<ItemRc>) {}
+
+    /// Rotates the rendering of the window on the display, see [`crate::api::Window::set_rendering_rotation()`].
+    /// The backends that can't rotate the rendering ignore it.
+    fn set_rendering_rotation(&self, _rotation: crate::graphics::RenderingRotation) {}
+
+    /// Turns the display on or off, or dims it, see [`crate::api::Window::set_display_power()`].
+    /// The backends that can't control the display ignore it.
+    fn set_display_power(&self, _power: DisplayPower) {}
+
     /// Return self as any so the backend can upcast
     fn as_any(&self) -> &dyn core::any::Any;
 }
@@ -163,6 +200,10 @@ pub struct Window {
     platform_window: once_cell::unsync::OnceCell<Rc<dyn PlatformWindow>>,
     component: RefCell<ComponentWeak>,
     mouse_input_state: Cell<MouseInputState>,
+    /// Set when a HoverArea was created in this window, to skip the hover tracking otherwise
+    hover_tracking: Cell<bool>,
+    /// The HoverArea items that are under the mouse
+    hovered_items: RefCell<Vec<crate::item_tree::ItemWeak>>,
     redraw_tracker: once_cell::unsync::OnceCell<Pin<Box<PropertyTracker<WindowRedrawTracker>>>>,
     window_properties_tracker:
         once_cell::unsync::OnceCell<Pin<Box<PropertyTracker<WindowPropertiesTracker>>>>,
@@ -173,9 +214,23 @@ pub struct Window {
     cursor_blinker: RefCell<pin_weak::rc::PinWeak<crate::input::TextCursorBlinker>>,
 
     scale_factor: Pin<Box<Property<f32>>>,
+    text_scale_factor: Pin<Box<Property<f32>>>,
     active: Pin<Box<Property<bool>>>,
     active_popup: RefCell<Option<PopupWindow>>,
+    /// The window that shows this window as a top-level popup, whose default font properties
+    /// are inherited by this one.
+    popup_parent: RefCell<Weak<Window>>,
     close_requested: Callback<(), CloseRequestResponse>,
+    color_scheme_changed: Callback<(ColorScheme,)>,
+    first_frame_rendered: Callback<()>,
+    /// True from the time the window is shown until its first frame is presented
+    first_frame_pending: Cell<bool>,
+    show_after_first_frame: Cell<bool>,
+    display_power: Cell<DisplayPower>,
+    inactivity_timer: crate::timers::Timer,
+    /// True when the inactivity timer fired and there was no input event since
+    inactive: Cell<bool>,
+    inactivity_changed: Callback<(bool,)>,
 }
 
 impl Drop for Window {
@@ -196,15 +251,30 @@ pub fn new(
             platform_window: Default::default(),
             component: Default::default(),
             mouse_input_state: Default::default(),
+            hover_tracking: Default::default(),
+            hovered_items: Default::default(),
             redraw_tracker: Default::default(),
             window_properties_tracker: Default::default(),
             meta_properties_tracker: Rc::pin(Default::default()),
             focus_item: Default::default(),
             cursor_blinker: Default::default(),
             scale_factor: Box::pin(Property::new_named(1., "i_slint_core::Window::scale_factor")),
+            text_scale_factor: Box::pin(Property::new_named(
+                1.,
+                "i_slint_core::Window::text_scale_factor",
+            )),
             active: Box::pin(Property::new_named(false, "i_slint_core::Window::active")),
             active_popup: Default::default(),
+            popup_parent: Default::default(),
             close_requested: Default::default(),
+            color_scheme_changed: Default::default(),
+            first_frame_rendered: Default::default(),
+            first_frame_pending: Default::default(),
+            show_after_first_frame: Default::default(),
+            display_power: Default::default(),
+            inactivity_timer: Default::default(),
+            inactive: Default::default(),
+            inactivity_changed: Default::default(),
         });
         let window_weak = Rc::downgrade(&window);
         window.platform_window.set(platform_window_fn(&window_weak)).ok().unwrap();
@@ -263,7 +333,13 @@ pub fn try_component(&self) -> Option<ComponentRc> {
     /// * `what`: The type of mouse event.
     /// * `component`: The Slint compiled component that provides the tree of items.
     pub fn process_mouse_input(self: Rc<Self>, mut event: MouseEvent) {
+        #[cfg(feature = "std")]
+        crate::recording::record(|| crate::recording::RecordedEvent::Mouse(event));
+        if !self.register_user_activity() {
+            return;
+        }
         crate::animations::update_animations();
+        let window_pos = event.pos();
 
         let embedded_popup_component =
             self.active_popup.borrow().as_ref().and_then(|popup| match popup.location {
@@ -314,6 +390,33 @@ pub fn process_mouse_input(self: Rc<Self>, mut event: MouseEvent) {
                 self.close_popup();
             }
         }
+
+        self.update_hover(window_pos);
+    }
+
+    /// Tells the window that it contains HoverArea items, which need to be updated after each
+    /// mouse event.
+    pub fn enable_hover_tracking(&self) {
+        self.hover_tracking.set(true);
+    }
+
+    /// Updates the HoverArea items for the mouse position, if the window contains any.
+    pub(crate) fn update_hover(&self, pos: Option<Point>) {
+        if !self.hover_tracking.get() {
+            return;
+        }
+        let mut components = alloc::vec::Vec::new();
+        if let Some(component) = self.component.borrow().upgrade() {
+            components.push((component, Point::default()));
+        }
+        if let Some(popup) = self.active_popup.borrow().as_ref() {
+            if let PopupWindowLocation::ChildWindow(coordinates) = popup.location {
+                components.push((popup.component.clone(), coordinates));
+            }
+        }
+        let mut hovered = self.hovered_items.take();
+        crate::input::update_hover(&components, pos, &mut hovered);
+        *self.hovered_items.borrow_mut() = hovered;
     }
     /// Receive a key event and pass it to the items of the component to
     /// change their state.
@@ -322,11 +425,17 @@ pub fn process_mouse_input(self: Rc<Self>, mut event: MouseEvent) {
     /// * `event`: The key event received by the windowing system.
     /// * `component`: The Slint compiled component that provides the tree of items.
     pub fn process_key_input(self: Rc<Self>, event: &KeyEvent) {
+        #[cfg(feature = "std")]
+        crate::recording::record(|| crate::recording::RecordedEvent::Key(event.clone()));
+        if !self.register_user_activity() {
+            return;
+        }
         let mut item = self.focus_item.borrow().clone().upgrade();
         while let Some(focus_item) = item {
             if !focus_item.is_visible() {
                 // Reset the focus... not great, but better than keeping it.
-                self.take_focus_item();
+                let old = self.take_focus_item();
+                self.notify_focus_change(old);
             } else {
                 if focus_item.borrow().as_ref().key_event(event, &self.clone())
                     == crate::input::KeyEventResult::EventAccepted
@@ -337,40 +446,90 @@ pub fn process_key_input(self: Rc<Self>, event: &KeyEvent) {
             item = focus_item.parent_item();
         }
 
+        if event.event_type != KeyEventType::KeyPressed {
+            return;
+        }
         // Make Tab/Backtab handle keyboard focus
-        if event.text.starts_with(key_codes::Tab) && event.event_type == KeyEventType::KeyPressed {
+        if event.text.starts_with(key_codes::Tab) {
             self.focus_next_item();
-        } else if event.text.starts_with(key_codes::Backtab)
-            && event.event_type == KeyEventType::KeyPressed
-        {
+        } else if event.text.starts_with(key_codes::Backtab) {
             self.focus_previous_item();
+        } else if event.text.starts_with(key_codes::RightArrow)
+            || event.text.starts_with(key_codes::DownArrow)
+        {
+            self.move_focus_with_arrow_key(true);
+        } else if event.text.starts_with(key_codes::LeftArrow)
+            || event.text.starts_with(key_codes::UpArrow)
+        {
+            self.move_focus_with_arrow_key(false);
         }
     }
 
     /// Installs a binding on the specified property that's toggled whenever the text cursor is supposed to be visible or not.
-    pub fn set_cursor_blink_binding(&self, prop: &crate::Property<bool>) {
+    /// The cursor is toggled every duration returned by `interval`, or always visible if it returns None. When a property
+    /// read by `interval` changes, the cursor of the focused text input is shown again with the new interval.
+    pub fn set_cursor_blink_binding(
+        self: &Rc<Self>,
+        prop: &crate::Property<bool>,
+        interval: impl FnOnce() -> Option<core::time::Duration>,
+    ) {
         let existing_blinker = self.cursor_blinker.borrow().clone();
 
         let blinker = existing_blinker.upgrade().unwrap_or_else(|| {
-            let new_blinker = TextCursorBlinker::new();
+            let window_weak = Rc::downgrade(self);
+            let new_blinker = TextCursorBlinker::new(Box::new(move || {
+                // The change handler is called while the property is set, so delay the restart
+                let window_weak = window_weak.clone();
+                crate::timers::Timer::single_shot(core::time::Duration::ZERO, move || {
+                    if let Some(window) = window_weak.upgrade() {
+                        window.restart_focused_text_cursor();
+                    }
+                });
+            }));
             *self.cursor_blinker.borrow_mut() =
                 pin_weak::rc::PinWeak::downgrade(new_blinker.clone());
             new_blinker
         });
 
-        TextCursorBlinker::set_binding(blinker, prop);
+        TextCursorBlinker::set_binding(blinker, prop, interval);
+    }
+
+    /// Shows the cursor of the focused text input again, so that it picks up the new blink interval.
+    fn restart_focused_text_cursor(self: &Rc<Self>) {
+        let focus_item = match self.focus_item.borrow().upgrade() {
+            Some(focus_item) => focus_item,
+            None => return,
+        };
+        if let Some(text_input) =
+            ItemRef::downcast_pin::<crate::items::TextInput>(focus_item.borrow())
+        {
+            if text_input.has_focus() {
+                text_input.show_cursor(self);
+            }
+        }
+    }
+
+    /// Stops the timer that toggles the text cursor, when no text cursor is shown anymore, so that
+    /// the window isn't woken up for nothing.
+    pub fn stop_cursor_blinking(&self) {
+        if let Some(blinker) = self.cursor_blinker.borrow().upgrade() {
+            blinker.stop();
+        }
     }
 
     /// Sets the focus to the item pointed to by item_ptr. This will remove the focus from any
     /// currently focused item.
     pub fn set_focus_item(self: Rc<Self>, focus_item: &ItemRc) {
-        self.take_focus_item();
-        self.move_focus(focus_item.clone(), next_focus_item);
+        let old = self.take_focus_item();
+        self.clone().move_focus(focus_item.clone(), next_focus_item);
+        self.notify_focus_change(old);
     }
 
     /// Sets the focus on the window to true or false, depending on the have_focus argument.
     /// This results in WindowFocusReceived and WindowFocusLost events.
     pub fn set_focus(self: Rc<Self>, have_focus: bool) {
+        #[cfg(feature = "std")]
+        crate::recording::record(|| crate::recording::RecordedEvent::Focus(have_focus));
         let event = if have_focus {
             crate::input::FocusEvent::WindowReceivedFocus
         } else {
@@ -396,6 +555,16 @@ fn take_focus_item(self: &Rc<Self>) -> Option<ItemRc> {
         }
     }
 
+    /// Report the change of the focus_item from `old` to the current one to the platform window
+    fn notify_focus_change(&self, old: Option<ItemRc>) {
+        let new = self.focus_item.borrow().upgrade();
+        if old != new {
+            if let Some(platform_window) = self.platform_window.get() {
+                platform_window.handle_focus_change(old, new);
+            }
+        }
+    }
+
     /// Publish the new focus_item to this Window and return the FocusEventResult
     ///
     /// This sends a FocusIn event!
@@ -432,23 +601,68 @@ fn move_focus(self: Rc<Self>, start_item: ItemRc, forward: impl Fn(ItemRc) -> It
         }
     }
 
-    /// Move keyboard focus to the next item
+    /// Moves the keyboard focus to the first item of `candidates` that accepts it.
+    fn move_focus_to_candidates(self: Rc<Self>, candidates: alloc::vec::Vec<ItemRc>) {
+        let old = self.take_focus_item();
+        for item in candidates {
+            if item.is_visible()
+                && self.clone().publish_focus_item(&Some(item))
+                    == crate::input::FocusEventResult::FocusAccepted
+            {
+                break;
+            }
+        }
+        self.notify_focus_change(old);
+    }
+
+    /// Moves the keyboard focus forward or backward in the tab order. The focus stays within the
+    /// `FocusScope` with the `trap` focus group policy that contains the focus item, if any.
+    fn move_focus_in_tab_order(self: Rc<Self>, forward: bool) {
+        let current = self.focus_item.borrow().upgrade();
+        let scope = current.as_ref().and_then(|current| {
+            crate::item_focus::enclosing_focus_scope(current, |focus_scope| {
+                focus_scope.focus_group() == FocusGroupPolicy::trap
+            })
+        });
+        let candidates = crate::item_focus::focus_chain_candidates(
+            ItemRc::new(self.component(), 0),
+            scope.as_ref(),
+            current.as_ref(),
+            forward,
+        );
+        self.move_focus_to_candidates(candidates);
+    }
+
+    /// Moves the keyboard focus forward or backward among the children of the closest `FocusScope`
+    /// with `arrow-navigation` enabled that contains the focus item. Does nothing if there is none.
+    fn move_focus_with_arrow_key(self: Rc<Self>, forward: bool) {
+        let current = match self.focus_item.borrow().upgrade() {
+            Some(current) => current,
+            None => return,
+        };
+        let scope = match crate::item_focus::enclosing_focus_scope(&current, |focus_scope| {
+            focus_scope.arrow_navigation()
+        }) {
+            Some(scope) => scope,
+            None => return,
+        };
+        let candidates = crate::item_focus::focus_chain_candidates(
+            ItemRc::new(self.component(), 0),
+            Some(&scope),
+            Some(&current),
+            forward,
+        );
+        self.move_focus_to_candidates(candidates);
+    }
+
+    /// Move keyboard focus to the next item, in the tab order
     pub fn focus_next_item(self: Rc<Self>) {
-        let component = self.component();
-        let start_item = self
-            .take_focus_item()
-            .map(next_focus_item)
-            .unwrap_or_else(|| ItemRc::new(component, 0));
-        self.move_focus(start_item, next_focus_item);
+        self.move_focus_in_tab_order(true);
     }
 
-    /// Move keyboard focus to the previous item.
+    /// Move keyboard focus to the previous item, in the tab order
     pub fn focus_previous_item(self: Rc<Self>) {
-        let component = self.component();
-        let start_item = previous_focus_item(
-            self.take_focus_item().unwrap_or_else(|| ItemRc::new(component, 0)),
-        );
-        self.move_focus(start_item, previous_focus_item);
+        self.move_focus_in_tab_order(false);
     }
 
     /// Marks the window to be the active window. This typically coincides with the keyboard
@@ -487,34 +701,9 @@ pub fn update_window_properties(&self) {
     /// Calls the render_components to render the main component and any sub-window components, tracked by a
     /// property dependency tracker.
     pub fn draw_contents(self: Rc<Self>, render_components: impl FnOnce(&[(&ComponentRc, Point)])) {
-        let draw_fn = || {
-            let component_rc = self.component();
-            let component = ComponentRc::borrow_pin(&component_rc);
-
-            self.meta_properties_tracker.as_ref().evaluate_if_dirty(|| {
-                self.apply_geometry_constraint(
-                    component.as_ref().layout_info(crate::layout::Orientation::Horizontal),
-                    component.as_ref().layout_info(crate::layout::Orientation::Vertical),
-                );
-            });
-
-            let popup_component =
-                self.active_popup.borrow().as_ref().and_then(|popup| match popup.location {
-                    PopupWindowLocation::TopLevel(_) => None,
-                    PopupWindowLocation::ChildWindow(coordinates) => {
-                        Some((popup.component.clone(), coordinates))
-                    }
-                });
-
-            if let Some((popup_component, popup_coordinates)) = popup_component {
-                render_components(&[
-                    (&component_rc, Point::default()),
-                    (&popup_component, popup_coordinates),
-                ])
-            } else {
-                render_components(&[(&component_rc, Point::default())]);
-            }
-        };
+        #[cfg(feature = "std")]
+        crate::parallel_binding::deliver_parallel_results();
+        let draw_fn = || self.draw_components(render_components);
 
         if let Some(redraw_tracker) = self.redraw_tracker.get() {
             redraw_tracker.as_ref().evaluate_as_dependency_root(draw_fn)
@@ -523,15 +712,65 @@ pub fn draw_contents(self: Rc<Self>, render_components: impl FnOnce(&[(&Componen
         }
     }
 
+    /// Calls the render_components like [`Self::draw_contents`], but without tracking the properties. This is
+    /// used by renderers that redraw only a part of the window, which must keep the dependencies of the rest.
+    pub fn draw_contents_untracked(
+        self: Rc<Self>,
+        render_components: impl FnOnce(&[(&ComponentRc, Point)]),
+    ) {
+        #[cfg(feature = "std")]
+        crate::parallel_binding::deliver_parallel_results();
+        crate::properties::evaluate_no_tracking(|| self.draw_components(render_components))
+    }
+
+    fn draw_components(&self, render_components: impl FnOnce(&[(&ComponentRc, Point)])) {
+        let component_rc = self.component();
+        let component = ComponentRc::borrow_pin(&component_rc);
+
+        self.meta_properties_tracker.as_ref().evaluate_if_dirty(|| {
+            self.apply_geometry_constraint(
+                component.as_ref().layout_info(crate::layout::Orientation::Horizontal),
+                component.as_ref().layout_info(crate::layout::Orientation::Vertical),
+            );
+        });
+
+        let popup_component =
+            self.active_popup.borrow().as_ref().and_then(|popup| match popup.location {
+                PopupWindowLocation::TopLevel(_) => None,
+                PopupWindowLocation::ChildWindow(coordinates) => {
+                    Some((popup.component.clone(), coordinates))
+                }
+            });
+
+        if let Some((popup_component, popup_coordinates)) = popup_component {
+            render_components(&[
+                (&component_rc, Point::default()),
+                (&popup_component, popup_coordinates),
+            ])
+        } else {
+            render_components(&[(&component_rc, Point::default())]);
+        }
+    }
+
     /// Registers the window with the windowing system, in order to render the component's items and react
     /// to input events once the event loop spins.
     pub fn show(&self) {
+        self.first_frame_pending.set(true);
         self.platform_window.get().unwrap().clone().show();
         self.update_window_properties();
+        if let Some(component) = self.try_component() {
+            crate::items::dispatch_lifecycle_event(&component, crate::items::LifecycleEvent::Shown);
+        }
     }
 
     /// De-registers the window with the windowing system.
     pub fn hide(&self) {
+        if let Some(component) = self.try_component() {
+            crate::items::dispatch_lifecycle_event(
+                &component,
+                crate::items::LifecycleEvent::Hidden,
+            );
+        }
         self.platform_window.get().unwrap().clone().hide();
     }
 
@@ -624,9 +863,23 @@ pub fn set_scale_factor(&self, factor: f32) {
         self.scale_factor.as_ref().set(factor)
     }
 
+    /// Returns the factor by which the pixel size of all fonts in the window is multiplied,
+    /// on top of the scale factor.
+    pub fn text_scale_factor(&self) -> f32 {
+        self.text_scale_factor.as_ref().get()
+    }
+
+    /// Sets the factor by which the pixel size of all fonts in the window is multiplied.
+    pub fn set_text_scale_factor(&self, factor: f32) {
+        self.text_scale_factor.as_ref().set(factor)
+    }
+
     /// Returns the font properties that are set on the root item if it's a Window item.
+    /// The properties that are not set are inherited from the window that shows this window as a
+    /// popup, if any.
     pub fn default_font_properties(&self) -> crate::graphics::FontRequest {
-        self.try_component()
+        let font_request = self
+            .try_component()
             .and_then(|component_rc| {
                 let component = ComponentRc::borrow_pin(&component_rc);
                 let root_item = component.as_ref().get_item_ref(0);
@@ -636,9 +889,41 @@ pub fn default_font_properties(&self) -> crate::graphics::FontRequest {
                     },
                 )
             })
+            .unwrap_or_default();
+        match self.popup_parent.borrow().upgrade() {
+            Some(parent) => font_request.merge(&parent.default_font_properties()),
+            None => font_request,
+        }
+    }
+
+    /// Sets the window that shows this window as a top-level popup, so that the popup inherits
+    /// its default font properties.
+    pub fn set_popup_parent(&self, parent: &Rc<Window>) {
+        *self.popup_parent.borrow_mut() = Rc::downgrade(parent);
+    }
+
+    /// Returns the size of the root item of the window, in logical pixels.
+    /// This is what the `vw` and `vh` units are relative to.
+    pub fn window_item_size(&self) -> Size {
+        self.try_component()
+            .map(|component_rc| {
+                ComponentRc::borrow_pin(&component_rc)
+                    .as_ref()
+                    .get_item_ref(0)
+                    .as_ref()
+                    .geometry()
+                    .size
+            })
             .unwrap_or_default()
     }
 
+    /// Returns the default font size of the window, in logical pixels, multiplied by the
+    /// text scale factor. This is what the `rem` unit is relative to.
+    pub fn default_font_size(&self) -> Coord {
+        self.default_font_properties().pixel_size.unwrap_or(DEFAULT_FONT_SIZE)
+            * self.text_scale_factor() as Coord
+    }
+
     /// Sets the size of the window item. This method is typically called in response to receiving a
     /// window resize event from the windowing system.
     /// Size is in logical pixels.
@@ -668,6 +953,106 @@ pub fn request_close(&self) -> bool {
             CloseRequestResponse::KeepWindowShown => false,
         }
     }
+
+    /// Sets the callback that is run when the color scheme preferred by the user changes.
+    pub fn on_color_scheme_changed(
+        self: &Rc<Self>,
+        mut callback: impl FnMut(ColorScheme) + 'static,
+    ) {
+        self.color_scheme_changed.set_handler(move |(scheme,)| callback(*scheme));
+        crate::color_scheme::register_listener(Rc::downgrade(self));
+    }
+
+    /// Runs the color_scheme_changed callback.
+    pub(crate) fn color_scheme_changed(&self, scheme: ColorScheme) {
+        self.color_scheme_changed.call(&(scheme,));
+    }
+
+    /// Sets the callback that is run once the first frame of the window was presented on the screen.
+    pub fn on_first_frame_rendered(&self, mut callback: impl FnMut() + 'static) {
+        self.first_frame_rendered.set_handler(move |()| callback());
+    }
+
+    /// Returns true if the platform window must be kept invisible until its first frame is
+    /// presented, see [`crate::api::Window::set_show_after_first_frame()`].
+    pub fn show_after_first_frame(&self) -> bool {
+        self.show_after_first_frame.get()
+    }
+
+    /// Sets whether the platform window is kept invisible until its first frame is presented.
+    /// This only applies to the next call to [`Self::show()`].
+    pub fn set_show_after_first_frame(&self, value: bool) {
+        self.show_after_first_frame.set(value);
+    }
+
+    /// Returns true if no frame was presented since the window was shown.
+    pub fn first_frame_pending(&self) -> bool {
+        self.first_frame_pending.get()
+    }
+
+    /// To be called by the backend after a frame was presented on the screen. The first time
+    /// since the window was shown, this runs the first_frame_rendered callback.
+    pub fn frame_presented(&self) {
+        if self.first_frame_pending.replace(false) {
+            self.first_frame_rendered.call(&());
+        }
+    }
+
+    /// Sets the power state of the display, see [`crate::api::Window::set_display_power()`].
+    pub fn set_display_power(&self, power: DisplayPower) {
+        self.display_power.set(power);
+        self.platform_window.get().unwrap().set_display_power(power);
+    }
+
+    /// Returns the power state of the display last set with [`Self::set_display_power()`].
+    pub fn display_power(&self) -> DisplayPower {
+        self.display_power.get()
+    }
+
+    /// Sets the callback that is run when the user didn't interact with the window for `timeout`,
+    /// and on the next input event after that. See [`crate::api::Window::set_inactivity_timeout()`].
+    pub fn set_inactivity_timeout(
+        self: &Rc<Self>,
+        timeout: Option<core::time::Duration>,
+        mut callback: impl FnMut(bool) + 'static,
+    ) {
+        self.inactive.set(false);
+        match timeout {
+            Some(timeout) => {
+                self.inactivity_changed.set_handler(move |(inactive,)| callback(*inactive));
+                let window_weak = Rc::downgrade(self);
+                self.inactivity_timer.start(
+                    crate::timers::TimerMode::SingleShot,
+                    timeout,
+                    move || {
+                        if let Some(window) = window_weak.upgrade() {
+                            window.inactive.set(true);
+                            window.inactivity_changed.call(&(true,));
+                        }
+                    },
+                );
+            }
+            None => {
+                self.inactivity_timer.stop();
+                self.inactivity_changed.set_handler(|_| ());
+            }
+        }
+    }
+
+    /// Restarts the inactivity timeout on an input event. Returns false if the display is off,
+    /// in which case the event must not be delivered to the items.
+    fn register_user_activity(&self) -> bool {
+        let display_on = self.display_power.get() != DisplayPower::Off;
+        // The timer is not running after it fired, but must be restarted then too. It is never
+        // restarted after it was stopped by removing the timeout.
+        if self.inactivity_timer.running() || self.inactive.get() {
+            self.inactivity_timer.restart();
+        }
+        if self.inactive.replace(false) {
+            self.inactivity_changed.call(&(false,));
+        }
+        display_on
+    }
 }
 
 impl core::ops::Deref for Window {
@@ -754,6 +1139,33 @@ pub enum GraphicsAPI {
         window.scale_factor()
     }
 
+    /// Returns the width of the root item of the window, in logical pixels.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_get_window_item_width(
+        handle: *const WindowRcOpaque,
+    ) -> Coord {
+        let window = &*(handle as *const WindowRc);
+        window.window_item_size().width
+    }
+
+    /// Returns the height of the root item of the window, in logical pixels.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_get_window_item_height(
+        handle: *const WindowRcOpaque,
+    ) -> Coord {
+        let window = &*(handle as *const WindowRc);
+        window.window_item_size().height
+    }
+
+    /// Returns the default font size of the window, in logical pixels.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_get_default_font_size(
+        handle: *const WindowRcOpaque,
+    ) -> Coord {
+        let window = &*(handle as *const WindowRc);
+        window.default_font_size()
+    }
+
     /// Sets the window scale factor, merely for testing purposes.
     #[no_mangle]
     pub unsafe extern "C" fn slint_windowrc_set_scale_factor(
@@ -764,6 +1176,25 @@ pub enum GraphicsAPI {
         window.set_scale_factor(value)
     }
 
+    /// Returns the text scale factor of the window.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_get_text_scale_factor(
+        handle: *const WindowRcOpaque,
+    ) -> f32 {
+        let window = &*(handle as *const WindowRc);
+        window.text_scale_factor()
+    }
+
+    /// Sets the text scale factor of the window.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_set_text_scale_factor(
+        handle: *const WindowRcOpaque,
+        value: f32,
+    ) {
+        let window = &*(handle as *const WindowRc);
+        window.set_text_scale_factor(value)
+    }
+
     /// Sets the window scale factor, merely for testing purposes.
     #[no_mangle]
     pub unsafe extern "C" fn slint_windowrc_free_graphics_resources<'a>(
@@ -896,6 +1327,84 @@ fn call(&self) -> CloseRequestResponse {
         window.on_close_requested(move || with_user_data.call());
     }
 
+    /// C binding to the on_color_scheme_changed() API of Window
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_on_color_scheme_changed(
+        handle: *const WindowRcOpaque,
+        callback: extern "C" fn(user_data: *mut c_void, scheme: ColorScheme),
+        drop_user_data: extern "C" fn(user_data: *mut c_void),
+        user_data: *mut c_void,
+    ) {
+        struct WithUserData {
+            callback: extern "C" fn(user_data: *mut c_void, scheme: ColorScheme),
+            drop_user_data: extern "C" fn(*mut c_void),
+            user_data: *mut c_void,
+        }
+
+        impl Drop for WithUserData {
+            fn drop(&mut self) {
+                (self.drop_user_data)(self.user_data)
+            }
+        }
+
+        let with_user_data = WithUserData { callback, drop_user_data, user_data };
+
+        let window = &*(handle as *const WindowRc);
+        window.on_color_scheme_changed(move |scheme| {
+            (with_user_data.callback)(with_user_data.user_data, scheme)
+        });
+    }
+
+    /// C binding to the on_first_frame_rendered() API of Window
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_on_first_frame_rendered(
+        handle: *const WindowRcOpaque,
+        callback: extern "C" fn(user_data: *mut c_void),
+        drop_user_data: extern "C" fn(user_data: *mut c_void),
+        user_data: *mut c_void,
+    ) {
+        struct WithUserData {
+            callback: extern "C" fn(user_data: *mut c_void),
+            drop_user_data: extern "C" fn(*mut c_void),
+            user_data: *mut c_void,
+        }
+
+        impl Drop for WithUserData {
+            fn drop(&mut self) {
+                (self.drop_user_data)(self.user_data)
+            }
+        }
+
+        let with_user_data = WithUserData { callback, drop_user_data, user_data };
+
+        let window = &*(handle as *const WindowRc);
+        window.on_first_frame_rendered(move || (with_user_data.callback)(with_user_data.user_data));
+    }
+
+    /// Sets whether the window is kept invisible until its first frame is presented.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_set_show_after_first_frame(
+        handle: *const WindowRcOpaque,
+        value: bool,
+    ) {
+        let window = &*(handle as *const WindowRc);
+        window.set_show_after_first_frame(value)
+    }
+
+    /// Moves the keyboard focus to the next item, in the tab order.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_focus_next_item(handle: *const WindowRcOpaque) {
+        let window = &*(handle as *const WindowRc);
+        window.clone().focus_next_item();
+    }
+
+    /// Moves the keyboard focus to the previous item, in the tab order.
+    #[no_mangle]
+    pub unsafe extern "C" fn slint_windowrc_focus_previous_item(handle: *const WindowRcOpaque) {
+        let window = &*(handle as *const WindowRc);
+        window.clone().focus_previous_item();
+    }
+
     /// This function issues a request to the windowing system to redraw the contents of the window.
     #[no_mangle]
     pub unsafe extern "C" fn slint_windowrc_request_redraw(handle: *const WindowRcOpaque) {
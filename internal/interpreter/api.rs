@@ -13,7 +13,7 @@
 use std::rc::Rc;
 
 #[doc(inline)]
-pub use i_slint_compiler::diagnostics::{Diagnostic, DiagnosticLevel};
+pub use i_slint_compiler::diagnostics::{Diagnostic, DiagnosticLevel, FixIt};
 
 pub use i_slint_core::api::*;
 
@@ -69,6 +69,118 @@ fn from(ty: LangType) -> Self {
     }
 }
 
+/// The detailed type of a property, or of an argument or the return value of a callback or a function,
+/// as returned by [`ComponentDefinition::property_types`] and [`ComponentDefinition::callback_signatures`].
+///
+/// Unlike [`ValueType`], it describes the fields of the structs, the type of the elements of the arrays,
+/// and the values of the enumerations.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum TypeInfo {
+    /// The return type of callbacks and functions that do not return a value
+    Void,
+    /// A number, with the name of its type in .slint, such as `int`, `float`, `length` or `duration`
+    Number(String),
+    /// Correspond to the `string` type in .slint
+    String,
+    /// Correspond to the `bool` type in .slint
+    Bool,
+    /// Correspond to the `color` type in .slint
+    Color,
+    /// Correspond to the `brush` type in .slint
+    Brush,
+    /// Correspond to the `image` type in .slint
+    Image,
+    /// An array of elements of the given type
+    Array(Box<TypeInfo>),
+    /// A struct, with its name if it was declared with one, and its fields in alphabetical order
+    Struct {
+        /// The name of the struct, or None for an anonymous struct such as `{ a: int }`
+        name: Option<String>,
+        /// The name and type of the fields
+        fields: Vec<(String, TypeInfo)>,
+    },
+    /// An enumeration, with its name and its values
+    Enumeration {
+        /// The name of the enumeration
+        name: String,
+        /// The values of the enumeration, in the order of their declaration
+        values: Vec<String>,
+    },
+    /// The type is not a public type but something internal.
+    #[doc(hidden)]
+    Other,
+}
+
+impl TypeInfo {
+    /// Returns the variant of [`Value`] that holds the values of this type.
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Self::Void => ValueType::Void,
+            Self::Number(_) => ValueType::Number,
+            Self::String => ValueType::String,
+            Self::Bool => ValueType::Bool,
+            Self::Color | Self::Brush => ValueType::Brush,
+            Self::Image => ValueType::Image,
+            Self::Array(_) => ValueType::Model,
+            Self::Struct { .. } => ValueType::Struct,
+            Self::Enumeration { .. } | Self::Other => ValueType::Other,
+        }
+    }
+}
+
+impl From<&LangType> for TypeInfo {
+    fn from(ty: &LangType) -> Self {
+        match ty {
+            LangType::Void => Self::Void,
+            LangType::Float32
+            | LangType::Int32
+            | LangType::Duration
+            | LangType::Angle
+            | LangType::PhysicalLength
+            | LangType::LogicalLength
+            | LangType::Percent
+            | LangType::UnitProduct(_) => Self::Number(ty.to_string()),
+            LangType::String => Self::String,
+            LangType::Bool => Self::Bool,
+            LangType::Color => Self::Color,
+            LangType::Brush => Self::Brush,
+            LangType::Image => Self::Image,
+            LangType::Array(ty) => Self::Array(Box::new(ty.as_ref().into())),
+            LangType::Struct { fields, name, .. } => Self::Struct {
+                name: name.clone(),
+                fields: fields.iter().map(|(name, ty)| (name.clone(), ty.into())).collect(),
+            },
+            LangType::Enumeration(e) => {
+                Self::Enumeration { name: e.name.clone(), values: e.values.clone() }
+            }
+            _ => Self::Other,
+        }
+    }
+}
+
+/// The signature of a callback or of a function, as returned by [`ComponentDefinition::callback_signatures`]
+/// and [`ComponentDefinition::functions`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signature {
+    /// The types of the arguments
+    pub arguments: Vec<TypeInfo>,
+    /// The type of the returned value, which is [`TypeInfo::Void`] if nothing is returned
+    pub return_type: TypeInfo,
+}
+
+impl Signature {
+    fn from_callback_type(ty: &LangType) -> Option<Self> {
+        match ty {
+            LangType::Callback { args, return_type } => Some(Self {
+                arguments: args.iter().map(Into::into).collect(),
+                return_type: return_type.as_ref().map_or(TypeInfo::Void, |ty| ty.as_ref().into()),
+            }),
+            _ => None,
+        }
+    }
+}
+
 /// This is a dynamically typed value used in the Slint interpreter.
 /// It can hold a value of different types, and you should use the
 /// [`From`] or [`TryFrom`] traits to access the value.
@@ -255,12 +367,15 @@ fn try_from(v: Value) -> Result<$name, Self::Error> {
 }
 
 declare_value_struct_conversion!(struct i_slint_core::model::StandardListViewItem { text });
+declare_value_struct_conversion!(struct i_slint_core::model::TableColumn { title, min_width, horizontal_stretch, sort_order, width });
+declare_value_struct_conversion!(struct i_slint_core::items::TextSpan { start, end, color, background, font_weight, underline });
 declare_value_struct_conversion!(struct i_slint_core::properties::StateInfo { current_state, previous_state, change_time });
 declare_value_struct_conversion!(struct i_slint_core::input::KeyboardModifiers { control, alt, shift, meta });
-declare_value_struct_conversion!(struct i_slint_core::input::KeyEvent { event_type, text, modifiers });
+declare_value_struct_conversion!(struct i_slint_core::input::KeyEvent { event_type, text, modifiers, scan_code, is_auto_repeat });
 declare_value_struct_conversion!(struct i_slint_core::layout::LayoutInfo { min, max, min_percent, max_percent, preferred, stretch });
 declare_value_struct_conversion!(struct i_slint_core::graphics::Point { x, y, ..Default::default()});
 declare_value_struct_conversion!(struct i_slint_core::items::PointerEvent { kind, button });
+declare_value_struct_conversion!(struct i_slint_core::items::ScrollEvent { delta_x, delta_y, phase });
 
 /// Implement From / TryFrom for Value that convert an `enum` to/from `Value::EnumerationValue`
 ///
@@ -416,6 +531,9 @@ fn from_iter<T: IntoIterator<Item = (String, Value)>>(iter: T) -> Self {
 pub struct ComponentCompiler {
     config: i_slint_compiler::CompilerConfiguration,
     diagnostics: Vec<Diagnostic>,
+    /// The files loaded by the last call to build_from_path, including the imported ones
+    #[cfg(feature = "hot-reload")]
+    loaded_files: Vec<PathBuf>,
 }
 
 impl Default for ComponentCompiler {
@@ -425,6 +543,8 @@ fn default() -> Self {
                 i_slint_compiler::generator::OutputFormat::Interpreter,
             ),
             diagnostics: vec![],
+            #[cfg(feature = "hot-reload")]
+            loaded_files: vec![],
         }
     }
 }
@@ -446,6 +566,11 @@ pub fn include_paths(&self) -> &Vec<std::path::PathBuf> {
     }
 
     /// Sets the style to be used for widgets.
+    ///
+    /// The style is either the name of a builtin style, such as `"fluent"`, or the path of a
+    /// directory that contains the `std-widgets.slint` file of a custom style, along with the other
+    /// `.slint` files and the images it uses. The style applies to the components compiled
+    /// afterwards, so a program can switch to another style by compiling its `.slint` files again.
     pub fn set_style(&mut self, style: String) {
         self.config.style = Some(style);
     }
@@ -501,6 +626,10 @@ pub async fn build_from_path<P: AsRef<Path>>(
         path: P,
     ) -> Option<ComponentDefinition> {
         let path = path.as_ref();
+        #[cfg(feature = "hot-reload")]
+        {
+            self.loaded_files = vec![path.into()];
+        }
         let source = match i_slint_compiler::diagnostics::load_from_path(path) {
             Ok(s) => s,
             Err(d) => {
@@ -512,10 +641,65 @@ pub async fn build_from_path<P: AsRef<Path>>(
         generativity::make_guard!(guard);
         let (c, diag) =
             crate::dynamic_component::load(source, path.into(), self.config.clone(), guard).await;
+        #[cfg(feature = "hot-reload")]
+        self.loaded_files.extend(diag.all_loaded_files.iter().filter(|f| *f != path).cloned());
         self.diagnostics = diag.into_iter().collect();
         c.ok().map(|inner| ComponentDefinition { inner: inner.into() })
     }
 
+    /// Compile a .slint file like [`Self::build_from_path`], and create an instance of its component
+    /// that is reloaded when the file, or one of the files it imports, changes.
+    ///
+    /// The files are checked for modifications while the event loop runs. When they changed, they
+    /// are compiled again with the same configuration, and a new instance of the component replaces
+    /// the previous one in the same window. The properties of the component and of its exported globals
+    /// that changed since the component was loaded keep their value in the new instance, if they still
+    /// exist with the same name and a compatible type, and are not bound to an expression.
+    /// If the new version doesn't compile, the diagnostics are printed and the previous instance stays.
+    ///
+    /// The callback handlers are set with [`HotReloadingInstance::set_setup_handler`], so that
+    /// they are set again on each new instance.
+    ///
+    /// This function is available when the `hot-reload` feature is enabled.
+    ///
+    /// ```no_run
+    /// use slint_interpreter::{ComponentCompiler, ComponentHandle, Value};
+    /// let mut compiler = ComponentCompiler::default();
+    /// let hot_reloading =
+    ///     spin_on::spin_on(compiler.build_from_path_with_hot_reload("ui/main.slint")).unwrap();
+    /// hot_reloading.set_setup_handler(|instance| {
+    ///     instance.set_callback("clicked", |_| {
+    ///         println!("clicked");
+    ///         Value::Void
+    ///     }).ok();
+    /// });
+    /// hot_reloading.run();
+    /// ```
+    #[cfg(feature = "hot-reload")]
+    pub async fn build_from_path_with_hot_reload<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+    ) -> Option<crate::hot_reload::HotReloadingInstance> {
+        let definition = self.build_from_path(path.as_ref()).await?;
+        let compiler = ComponentCompiler {
+            config: self.config.clone(),
+            diagnostics: vec![],
+            loaded_files: vec![],
+        };
+        Some(crate::hot_reload::HotReloadingInstance::new(
+            path.as_ref().into(),
+            compiler,
+            definition,
+            self.loaded_files(),
+        ))
+    }
+
+    /// The files loaded by the last call to [`Self::build_from_path`], including the imported ones
+    #[cfg(feature = "hot-reload")]
+    pub(crate) fn loaded_files(&self) -> &[PathBuf] {
+        &self.loaded_files
+    }
+
     /// Compile some .slint code into a ComponentDefinition
     ///
     /// The `path` argument will be used for diagnostics and to compute relative
@@ -560,11 +744,43 @@ pub struct ComponentDefinition {
 impl ComponentDefinition {
     /// Creates a new instance of the component and returns a shared handle to it.
     pub fn create(&self) -> ComponentInstance {
+        self.create_with_globals(|_| {})
+    }
+
+    /// Creates a new instance of the component, and calls `init_globals` to set the properties
+    /// and the callback handlers of its exported global singletons before the bindings of the
+    /// component are initialized. This way, the bindings that depend on the globals never see
+    /// their default values.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use slint_interpreter::{ComponentCompiler, Value, SharedString};
+    /// let code = r#"
+    ///     export global Settings := {
+    ///         property <string> user-name;
+    ///     }
+    ///     MyWin := Window {
+    ///         property <string> greeting: "Hello " + Settings.user-name;
+    ///     }
+    /// "#;
+    /// let definition = spin_on::spin_on(
+    ///     ComponentCompiler::default().build_from_source(code.into(), Default::default())).unwrap();
+    /// let instance = definition.create_with_globals(|globals| {
+    ///     globals.set_property("Settings", "user-name", SharedString::from("World").into()).unwrap();
+    /// });
+    /// assert_eq!(instance.get_property("greeting").unwrap(), Value::from(SharedString::from("Hello World")));
+    /// ```
+    pub fn create_with_globals(
+        &self,
+        init_globals: impl FnOnce(&ComponentGlobals),
+    ) -> ComponentInstance {
         generativity::make_guard!(guard);
         ComponentInstance {
             inner: self.inner.unerase(guard).clone().create(
                 #[cfg(target_arch = "wasm32")]
                 "canvas".into(),
+                |globals| init_globals(&ComponentGlobals { globals: globals.clone() }),
             ),
         }
     }
@@ -573,13 +789,26 @@ pub fn create(&self) -> ComponentInstance {
     #[cfg(target_arch = "wasm32")]
     pub fn create_with_canvas_id(&self, canvas_id: &str) -> ComponentInstance {
         generativity::make_guard!(guard);
-        ComponentInstance { inner: self.inner.unerase(guard).clone().create(canvas_id.into()) }
+        ComponentInstance {
+            inner: self.inner.unerase(guard).clone().create(canvas_id.into(), |_| {}),
+        }
     }
 
     /// Instantiate the component using an existing window.
     /// This method is internal because the WindowRc is not a public type
     #[doc(hidden)]
     pub fn create_with_existing_window(&self, window: &Window) -> ComponentInstance {
+        self.create_with_existing_window_and_globals(window, |_| {})
+    }
+
+    /// Instantiate the component using an existing window, see [`Self::create_with_globals`].
+    /// This method is internal because the WindowRc is not a public type
+    #[doc(hidden)]
+    pub fn create_with_existing_window_and_globals(
+        &self,
+        window: &Window,
+        init_globals: impl FnOnce(&ComponentGlobals),
+    ) -> ComponentInstance {
         use i_slint_core::window::WindowHandleAccess;
         generativity::make_guard!(guard);
         ComponentInstance {
@@ -587,7 +816,9 @@ pub fn create_with_existing_window(&self, window: &Window) -> ComponentInstance
                 .inner
                 .unerase(guard)
                 .clone()
-                .create_with_existing_window(window.window_handle()),
+                .create_with_existing_window(window.window_handle(), |globals| {
+                    init_globals(&ComponentGlobals { globals: globals.clone() })
+                }),
         }
     }
 
@@ -678,6 +909,123 @@ pub fn global_callbacks(&self, global_name: &str) -> Option<impl Iterator<Item =
         })
     }
 
+    /// List of publicly declared properties, with their detailed type.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use slint_interpreter::{ComponentCompiler, TypeInfo};
+    /// let code = r#"
+    ///     export struct Point := { x: length, y: length }
+    ///     MyWin := Window {
+    ///         property <[Point]> points;
+    ///     }
+    /// "#;
+    /// let definition = spin_on::spin_on(
+    ///     ComponentCompiler::default().build_from_source(code.into(), Default::default())).unwrap();
+    /// let (name, ty) = definition.property_types().next().unwrap();
+    /// assert_eq!(name, "points");
+    /// let point = TypeInfo::Struct {
+    ///     name: Some("Point".into()),
+    ///     fields: vec![("x".into(), TypeInfo::Number("length".into())), ("y".into(), TypeInfo::Number("length".into()))],
+    /// };
+    /// assert_eq!(ty, TypeInfo::Array(Box::new(point)));
+    /// ```
+    pub fn property_types(&self) -> impl Iterator<Item = (String, TypeInfo)> + '_ {
+        // We create here a 'static guard, because unfortunately the returned type would be restricted to the guard lifetime
+        // which is not required, but this is safe because there is only one instance of the unerased type
+        let guard = unsafe { generativity::Guard::new(generativity::Id::new()) };
+        self.inner.unerase(guard).properties().filter_map(|(prop_name, prop_type)| {
+            if prop_type.is_property_type() {
+                Some((prop_name, (&prop_type).into()))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// List of publicly declared callbacks, with their signature.
+    pub fn callback_signatures(&self) -> impl Iterator<Item = (String, Signature)> + '_ {
+        // We create here a 'static guard, because unfortunately the returned type would be restricted to the guard lifetime
+        // which is not required, but this is safe because there is only one instance of the unerased type
+        let guard = unsafe { generativity::Guard::new(generativity::Id::new()) };
+        self.inner.unerase(guard).properties().filter_map(|(callback_name, callback_type)| {
+            Some((callback_name, Signature::from_callback_type(&callback_type)?))
+        })
+    }
+
+    /// List of the functions declared in the component, with their signature.
+    ///
+    /// **Note:** Functions are private to the component, so they cannot be called through this API.
+    pub fn functions(&self) -> impl Iterator<Item = (String, Signature)> + '_ {
+        // We create here a 'static guard, because unfortunately the returned type would be restricted to the guard lifetime
+        // which is not required, but this is safe because there is only one instance of the unerased type
+        let guard = unsafe { generativity::Guard::new(generativity::Id::new()) };
+        self.inner.unerase(guard).functions().filter_map(|(function_name, function_type)| {
+            Some((function_name, Signature::from_callback_type(&function_type)?))
+        })
+    }
+
+    /// List of publicly declared properties in the exported global singleton specified by its name,
+    /// with their detailed type.
+    pub fn global_property_types(
+        &self,
+        global_name: &str,
+    ) -> Option<impl Iterator<Item = (String, TypeInfo)> + '_> {
+        // We create here a 'static guard, because unfortunately the returned type would be restricted to the guard lifetime
+        // which is not required, but this is safe because there is only one instance of the unerased type
+        let guard = unsafe { generativity::Guard::new(generativity::Id::new()) };
+        self.inner.unerase(guard).global_properties(global_name).map(|iter| {
+            iter.filter_map(|(prop_name, prop_type)| {
+                if prop_type.is_property_type() {
+                    Some((prop_name, (&prop_type).into()))
+                } else {
+                    None
+                }
+            })
+        })
+    }
+
+    /// List of publicly declared callbacks in the exported global singleton specified by its name,
+    /// with their signature.
+    pub fn global_callback_signatures(
+        &self,
+        global_name: &str,
+    ) -> Option<impl Iterator<Item = (String, Signature)> + '_> {
+        // We create here a 'static guard, because unfortunately the returned type would be restricted to the guard lifetime
+        // which is not required, but this is safe because there is only one instance of the unerased type
+        let guard = unsafe { generativity::Guard::new(generativity::Id::new()) };
+        self.inner.unerase(guard).global_properties(global_name).map(|iter| {
+            iter.filter_map(|(callback_name, callback_type)| {
+                Some((callback_name, Signature::from_callback_type(&callback_type)?))
+            })
+        })
+    }
+
+    /// List of the functions declared in the exported global singleton specified by its name,
+    /// with their signature.
+    pub fn global_functions(
+        &self,
+        global_name: &str,
+    ) -> Option<impl Iterator<Item = (String, Signature)> + '_> {
+        // We create here a 'static guard, because unfortunately the returned type would be restricted to the guard lifetime
+        // which is not required, but this is safe because there is only one instance of the unerased type
+        let guard = unsafe { generativity::Guard::new(generativity::Id::new()) };
+        self.inner.unerase(guard).global_functions(global_name).map(|iter| {
+            iter.filter_map(|(function_name, function_type)| {
+                Some((function_name, Signature::from_callback_type(&function_type)?))
+            })
+        })
+    }
+
+    /// Returns true if the property `name` of the component, or of the exported global `global` if it
+    /// is not empty, is bound to an expression that is not constant.
+    #[cfg(feature = "hot-reload")]
+    pub(crate) fn has_non_constant_binding(&self, global: &str, name: &str) -> bool {
+        generativity::make_guard!(guard);
+        self.inner.unerase(guard).has_non_constant_binding(global, &normalize_identifier(name))
+    }
+
     /// The name of this Component as written in the .slint file
     pub fn name(&self) -> &str {
         // We create here a 'static guard, because unfortunately the returned type would be restricted to the guard lifetime
@@ -701,6 +1049,14 @@ pub fn print_diagnostics(diagnostics: &[Diagnostic]) {
     build_diagnostics.print();
 }
 
+/// Serialize the diagnostics as a JSON array, for tools such as editors.
+///
+/// Each diagnostic is an object with its location, level and message, and the changes
+/// suggested to fix it, as described in [`Diagnostic::to_json`].
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    format!("[{}]", diagnostics.iter().map(|d| d.to_json()).collect::<Vec<_>>().join(","))
+}
+
 /// This represent an instance of a dynamic component
 ///
 /// You can create an instance with the [`ComponentDefinition::create`] function.
@@ -723,6 +1079,36 @@ pub fn definition(&self) -> ComponentDefinition {
         ComponentDefinition { inner: self.inner.unerase(guard).description().into() }
     }
 
+    /// Returns the names of the exported global singletons used by this component. Their properties
+    /// and callbacks can be accessed with [`Self::get_global_property`], [`Self::set_global_property`],
+    /// [`Self::set_global_callback`] and [`Self::invoke_global_callback`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use slint_interpreter::{ComponentCompiler, Value};
+    /// let code = r#"
+    ///     export global Palette := {
+    ///         property <color> background: #eee;
+    ///     }
+    ///     MyWin := Window {
+    ///         background: Palette.background;
+    ///     }
+    /// "#;
+    /// let definition = spin_on::spin_on(
+    ///     ComponentCompiler::default().build_from_source(code.into(), Default::default()));
+    /// let instance = definition.unwrap().create();
+    /// for global in instance.globals() {
+    ///     for (name, _) in instance.definition().global_property_types(&global).unwrap() {
+    ///         println!("{}.{} = {:?}", global, name, instance.get_global_property(&global, &name));
+    ///     }
+    /// }
+    /// ```
+    pub fn globals(&self) -> impl Iterator<Item = String> {
+        generativity::make_guard!(guard);
+        self.inner.unerase(guard).description().global_names().collect::<Vec<_>>().into_iter()
+    }
+
     /// Return the value for a public property of this component.
     ///
     /// ## Examples
@@ -942,6 +1328,71 @@ pub fn invoke_global_callback(
     }
 }
 
+/// The exported global singletons of a component that is being created, see
+/// [`ComponentDefinition::create_with_globals`].
+pub struct ComponentGlobals {
+    globals: crate::global_component::GlobalStorage,
+}
+
+impl ComponentGlobals {
+    fn get(
+        &self,
+        global: &str,
+    ) -> Option<&core::pin::Pin<Rc<dyn crate::global_component::GlobalComponent>>> {
+        self.globals.get(normalize_identifier(global).as_ref())
+    }
+
+    /// Return the value for a property within an exported global singleton.
+    pub fn get_property(&self, global: &str, property: &str) -> Result<Value, GetPropertyError> {
+        self.get(global)
+            .ok_or(GetPropertyError::NoSuchProperty)?
+            .as_ref()
+            .get_property(&normalize_identifier(property))
+            .map_err(|()| GetPropertyError::NoSuchProperty)
+    }
+
+    /// Set the value for a property within an exported global singleton.
+    pub fn set_property(
+        &self,
+        global: &str,
+        property: &str,
+        value: Value,
+    ) -> Result<(), SetPropertyError> {
+        self.get(global)
+            .ok_or(SetPropertyError::NoSuchProperty)?
+            .as_ref()
+            .set_property(&normalize_identifier(property), value)
+    }
+
+    /// Set a handler for the callback in the exported global singleton.
+    pub fn set_callback(
+        &self,
+        global: &str,
+        name: &str,
+        callback: impl Fn(&[Value]) -> Value + 'static,
+    ) -> Result<(), SetCallbackError> {
+        self.get(global)
+            .ok_or(SetCallbackError::NoSuchCallback)?
+            .as_ref()
+            .set_callback_handler(&normalize_identifier(name), Box::new(callback))
+            .map_err(|()| SetCallbackError::NoSuchCallback)
+    }
+
+    /// Call the given callback within an exported global singleton with the arguments
+    pub fn invoke_callback(
+        &self,
+        global: &str,
+        callback_name: &str,
+        args: &[Value],
+    ) -> Result<Value, InvokeCallbackError> {
+        self.get(global)
+            .ok_or(InvokeCallbackError::NoSuchCallback)?
+            .as_ref()
+            .invoke_callback(&normalize_identifier(callback_name), args)
+            .map_err(|()| InvokeCallbackError::NoSuchCallback)
+    }
+}
+
 impl ComponentHandle for ComponentInstance {
     type Inner = crate::dynamic_component::ErasedComponentBox;
 
@@ -997,7 +1448,18 @@ impl From<ComponentInstance>
     for vtable::VRc<i_slint_core::component::ComponentVTable, ErasedComponentBox>
 {
     fn from(value: ComponentInstance) -> Self {
-        value.inner
+        value.inner.clone()
+    }
+}
+
+impl Drop for ComponentInstance {
+    fn drop(&mut self) {
+        // The component is destroyed with its last handle
+        if vtable::VRc::strong_count(&self.inner) == 1 {
+            i_slint_core::component::will_destroy_component(&vtable::VRc::into_dyn(
+                self.inner.clone(),
+            ));
+        }
     }
 }
 
@@ -1020,6 +1482,9 @@ pub enum SetPropertyError {
     /// The property exist but does not have a type matching the dynamic value
     #[error("wrong type")]
     WrongType,
+    /// The property is an `out` property that cannot be set from outside the component
+    #[error("read-only property")]
+    ReadOnly,
 }
 
 /// Error returned by [`ComponentInstance::set_callback`]
@@ -1321,6 +1786,106 @@ fn component_definition_struct_properties() {
     );
 }
 
+#[test]
+fn component_definition_reflection() {
+    i_slint_backend_testing::init();
+    let mut compiler = ComponentCompiler::default();
+    compiler.set_style("fluent".into());
+    let definition = spin_on::spin_on(
+        compiler.build_from_source(
+            r#"
+    export struct Item := {
+        label: string,
+        size: length,
+    }
+    export global Logic := {
+        property <duration> delay;
+        callback compute(int, float) -> string;
+        function double(x: int) -> int { return 2 * x; }
+    }
+    export Dummy := Rectangle {
+        property <[Item]> items;
+        property <TextWrap> wrap;
+        property <{ a: bool }> anonymous;
+        callback selected(Item);
+        function select(index: int) { }
+    }"#
+            .into(),
+            "".into(),
+        ),
+    )
+    .unwrap();
+    assert!(compiler.diagnostics().is_empty(), "{:?}", compiler.diagnostics());
+
+    let item = TypeInfo::Struct {
+        name: Some("Item".into()),
+        fields: vec![
+            ("label".into(), TypeInfo::String),
+            ("size".into(), TypeInfo::Number("length".into())),
+        ],
+    };
+    let props = definition.property_types().collect::<Vec<_>>();
+    assert_eq!(props.len(), 3);
+    assert_eq!(
+        props[0],
+        (
+            "anonymous".into(),
+            TypeInfo::Struct { name: None, fields: vec![("a".into(), TypeInfo::Bool)] }
+        )
+    );
+    assert_eq!(props[1], ("items".into(), TypeInfo::Array(Box::new(item.clone()))));
+    assert_eq!(props[1].1.value_type(), ValueType::Model);
+    assert!(matches!(&props[2].1, TypeInfo::Enumeration { name, values }
+        if name == "TextWrap" && values == &["no-wrap", "word-wrap"]));
+
+    assert_eq!(
+        definition.callback_signatures().collect::<Vec<_>>(),
+        vec![("selected".into(), Signature { arguments: vec![item], return_type: TypeInfo::Void })]
+    );
+    assert_eq!(
+        definition.functions().collect::<Vec<_>>(),
+        vec![(
+            "select".into(),
+            Signature {
+                arguments: vec![TypeInfo::Number("int".into())],
+                return_type: TypeInfo::Void
+            }
+        )]
+    );
+    // Functions are not callbacks
+    assert_eq!(definition.callbacks().collect::<Vec<_>>(), vec!["selected"]);
+
+    assert_eq!(
+        definition.global_property_types("Logic").unwrap().collect::<Vec<_>>(),
+        vec![("delay".into(), TypeInfo::Number("duration".into()))]
+    );
+    assert_eq!(
+        definition.global_callback_signatures("Logic").unwrap().collect::<Vec<_>>(),
+        vec![(
+            "compute".into(),
+            Signature {
+                arguments: vec![TypeInfo::Number("int".into()), TypeInfo::Number("float".into())],
+                return_type: TypeInfo::String
+            }
+        )]
+    );
+    assert_eq!(
+        definition.global_functions("Logic").unwrap().collect::<Vec<_>>(),
+        vec![(
+            "double".into(),
+            Signature {
+                arguments: vec![TypeInfo::Number("int".into())],
+                return_type: TypeInfo::Number("int".into())
+            }
+        )]
+    );
+    assert!(definition.global_functions("not-there").is_none());
+
+    let instance = definition.create();
+    assert_eq!(instance.globals().collect::<Vec<_>>(), vec!["Logic"]);
+    assert_eq!(instance.get_global_property("Logic", "delay"), Ok(Value::Number(0.)));
+}
+
 #[test]
 fn component_definition_model_properties() {
     use i_slint_core::model::*;
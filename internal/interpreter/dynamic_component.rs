@@ -10,9 +10,10 @@
 use dynamic_type::{Instance, InstanceBox};
 use i_slint_compiler::expression_tree::{Expression, NamedReference};
 use i_slint_compiler::langtype::Type;
-use i_slint_compiler::object_tree::ElementRc;
+use i_slint_compiler::object_tree::{ElementRc, PropertyVisibility};
 use i_slint_compiler::*;
 use i_slint_compiler::{diagnostics::BuildDiagnostics, object_tree::PropertyDeclaration};
+use i_slint_core::accessibility::{AccessibilityAction, AccessibleRole, AccessibleStringProperty};
 use i_slint_core::api::Window;
 use i_slint_core::component::{
     Component, ComponentRef, ComponentRefPin, ComponentVTable, ComponentWeak, IndexRange,
@@ -25,7 +26,7 @@
 use i_slint_core::layout::{BoxLayoutCellData, LayoutInfo, Orientation};
 use i_slint_core::model::RepeatedComponent;
 use i_slint_core::model::Repeater;
-use i_slint_core::properties::InterpolatedPropertyValue;
+use i_slint_core::properties::{ChangeTracker, InterpolatedPropertyValue};
 use i_slint_core::rtti::{self, AnimatedBindingKind, FieldOffset, PropertyInfo};
 use i_slint_core::slice::Slice;
 use i_slint_core::window::{WindowHandleAccess, WindowRc};
@@ -199,6 +200,23 @@ fn parent_node(self: Pin<&Self>, result: &mut ItemWeak) {
     fn subtree_index(self: Pin<&Self>) -> usize {
         self.borrow().as_ref().subtree_index()
     }
+
+    fn accessible_role(self: Pin<&Self>, index: usize) -> AccessibleRole {
+        self.borrow().as_ref().accessible_role(index)
+    }
+
+    fn accessible_string_property(
+        self: Pin<&Self>,
+        index: usize,
+        what: AccessibleStringProperty,
+        result: &mut SharedString,
+    ) {
+        self.borrow().as_ref().accessible_string_property(index, what, result)
+    }
+
+    fn accessibility_action(self: Pin<&Self>, index: usize, action: &AccessibilityAction) {
+        self.borrow().as_ref().accessibility_action(index, action)
+    }
 }
 
 i_slint_core::ComponentVTable_static!(static COMPONENT_BOX_VT for ErasedComponentBox);
@@ -210,6 +228,30 @@ pub(crate) struct ComponentExtraData {
         once_cell::unsync::OnceCell<vtable::VWeak<ComponentVTable, ErasedComponentBox>>,
     // resource id -> file path
     pub(crate) embedded_file_resources: HashMap<usize, String>,
+    // resource id -> data of the image files that were processed by the compiler
+    pub(crate) processed_file_resources: HashMap<usize, &'static [u8]>,
+    pub(crate) change_trackers: once_cell::unsync::OnceCell<Vec<ChangeTracker>>,
+}
+
+/// Returns a static copy of data that was processed by the compiler, as needed by
+/// `ImageInner::EmbeddedData`. The data is leaked, but each content only once, so that
+/// compiling the same files again doesn't leak more memory.
+fn leak_processed_data(data: &[u8]) -> &'static [u8] {
+    thread_local! {
+        static PROCESSED_DATA: core::cell::RefCell<std::collections::HashSet<&'static [u8]>> =
+            Default::default();
+    }
+    PROCESSED_DATA.with(|processed| {
+        let mut processed = processed.borrow_mut();
+        match processed.get(data) {
+            Some(leaked) => *leaked,
+            None => {
+                let leaked: &'static [u8] = Box::leak(data.to_vec().into_boxed_slice());
+                processed.insert(leaked);
+                leaked
+            }
+        }
+    })
 }
 
 struct ErasedRepeaterWithinComponent<'id>(RepeaterWithinComponent<'id, 'static>);
@@ -317,22 +359,36 @@ pub struct ComponentDescription<'id> {
     exported_globals_by_name: BTreeMap<String, usize>,
 }
 
+/// The name of a declaration, with the dashes and underscore as written in the declaration
+fn declared_name(name: &str, decl: &PropertyDeclaration) -> String {
+    decl.node
+        .as_ref()
+        .and_then(|n| {
+            n.as_ref()
+                .either(
+                    |n| n.DeclaredIdentifier(),
+                    |n| n.as_ref().either(|n| n.DeclaredIdentifier(), |n| n.DeclaredIdentifier()),
+                )
+                .child_token(parser::SyntaxKind::Identifier)
+        })
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| name.to_string())
+}
+
 fn internal_properties_to_public<'a>(
     prop_iter: impl Iterator<Item = (&'a String, &'a PropertyDeclaration)> + 'a,
 ) -> impl Iterator<Item = (String, i_slint_compiler::langtype::Type)> + 'a {
-    prop_iter.filter(|(_, v)| v.expose_in_public_api).map(|(s, v)| {
-        let name = v
-            .node
-            .as_ref()
-            .and_then(|n| {
-                n.as_ref()
-                    .either(|n| n.DeclaredIdentifier(), |n| n.DeclaredIdentifier())
-                    .child_token(parser::SyntaxKind::Identifier)
-            })
-            .map(|n| n.to_string())
-            .unwrap_or_else(|| s.clone());
-        (name, v.property_type.clone())
-    })
+    prop_iter
+        .filter(|(_, v)| v.expose_in_public_api)
+        .map(|(s, v)| (declared_name(s, v), v.property_type.clone()))
+}
+
+fn internal_functions_to_public<'a>(
+    prop_iter: impl Iterator<Item = (&'a String, &'a PropertyDeclaration)> + 'a,
+) -> impl Iterator<Item = (String, i_slint_compiler::langtype::Type)> + 'a {
+    prop_iter
+        .filter(|(_, v)| v.is_function())
+        .map(|(s, v)| (declared_name(s, v), v.property_type.clone()))
 }
 
 impl<'id> ComponentDescription<'id> {
@@ -350,6 +406,13 @@ pub fn properties(
         internal_properties_to_public(self.public_properties.iter())
     }
 
+    /// List of the functions declared in the root element, with their type
+    pub fn functions(
+        &self,
+    ) -> impl Iterator<Item = (String, i_slint_compiler::langtype::Type)> + '_ {
+        internal_functions_to_public(self.public_properties.iter())
+    }
+
     /// List names of exported global singletons
     pub fn global_names(&self) -> impl Iterator<Item = String> + '_ {
         self.compiled_globals
@@ -368,10 +431,46 @@ pub fn global_properties(
             .map(|global| internal_properties_to_public(global.public_properties()))
     }
 
+    /// Returns true if the property `name` of the root element, or of the exported global `global` if
+    /// it is not empty, is bound to an expression that is not constant.
+    pub fn has_non_constant_binding(&self, global: &str, name: &str) -> bool {
+        let has_binding = |element: &ElementRc| {
+            element.borrow().bindings.get(name).map_or(false, |b| !b.borrow().is_constant())
+        };
+        if global.is_empty() {
+            return has_binding(&self.original.root_element);
+        }
+        match self
+            .exported_globals_by_name
+            .get(crate::normalize_identifier(global).as_ref())
+            .and_then(|global_idx| self.compiled_globals.get(*global_idx))
+        {
+            Some(crate::global_component::CompiledGlobal::Component { component, .. }) => {
+                generativity::make_guard!(guard);
+                has_binding(&component.unerase(guard).original.root_element)
+            }
+            _ => false,
+        }
+    }
+
+    pub fn global_functions(
+        &self,
+        name: &str,
+    ) -> Option<impl Iterator<Item = (String, i_slint_compiler::langtype::Type)> + '_> {
+        self.exported_globals_by_name
+            .get(crate::normalize_identifier(name).as_ref())
+            .and_then(|global_idx| self.compiled_globals.get(*global_idx))
+            .map(|global| internal_functions_to_public(global.public_properties()))
+    }
+
     /// Instantiate a runtime component from this ComponentDescription
+    ///
+    /// `init_globals` is called with the global singletons before the bindings of the component
+    /// are initialized.
     pub fn create(
         self: Rc<Self>,
         #[cfg(target_arch = "wasm32")] canvas_id: String,
+        init_globals: impl FnOnce(&crate::global_component::GlobalStorage),
     ) -> vtable::VRc<ComponentVTable, ErasedComponentBox> {
         #[cfg(not(target_arch = "wasm32"))]
         let window = i_slint_backend_selector::backend().create_window();
@@ -381,15 +480,21 @@ pub fn create(
             i_slint_backend_selector::backend();
             i_slint_backend_gl::create_gl_window_with_canvas_id(canvas_id)
         };
-        self.create_with_existing_window(&window)
+        self.create_with_existing_window(&window, init_globals)
     }
 
     #[doc(hidden)]
     pub fn create_with_existing_window(
         self: Rc<Self>,
         window: &i_slint_core::window::WindowRc,
+        init_globals: impl FnOnce(&crate::global_component::GlobalStorage),
     ) -> vtable::VRc<ComponentVTable, ErasedComponentBox> {
-        let component_ref = instantiate(self, None, Some(window), Default::default());
+        let mut globals = Default::default();
+        for g in &self.compiled_globals {
+            crate::global_component::instantiate(g, &mut globals);
+        }
+        init_globals(&globals);
+        let component_ref = instantiate(self, None, Some(window), globals);
         component_ref
             .as_pin_ref()
             .window()
@@ -402,7 +507,7 @@ pub fn create_with_existing_window(
     /// Set a value to property.
     ///
     /// Return an error if the property with this name does not exist in this component,
-    /// or if the value is the wrong type.
+    /// is private or is an `out` property, or if the value is the wrong type.
     /// Panics if the component is not an instance corresponding to this ComponentDescription,
     pub fn set_property(
         &self,
@@ -415,14 +520,17 @@ pub fn set_property(
         }
         generativity::make_guard!(guard);
         let c = unsafe { InstanceRef::from_pin_ref(component, guard) };
-        if let Some(alias) = self
-            .original
-            .root_element
-            .borrow()
-            .property_declarations
-            .get(name)
-            .and_then(|d| d.is_alias.as_ref())
-        {
+        let alias = match self.original.root_element.borrow().property_declarations.get(name) {
+            Some(d) if !d.visibility.is_public() => {
+                return Err(crate::api::SetPropertyError::NoSuchProperty)
+            }
+            Some(d) if d.visibility == PropertyVisibility::Output => {
+                return Err(crate::api::SetPropertyError::ReadOnly)
+            }
+            Some(d) => d.is_alias.clone(),
+            None => None,
+        };
+        if let Some(alias) = alias {
             eval::store_property(c, &alias.element(), alias.name(), value)
         } else {
             eval::store_property(c, &self.original.root_element, name, value)
@@ -467,14 +575,12 @@ pub fn get_property(&self, component: ComponentRefPin, name: &str) -> Result<Val
         generativity::make_guard!(guard);
         // Safety: we just verified that the component has the right vtable
         let c = unsafe { InstanceRef::from_pin_ref(component, guard) };
-        if let Some(alias) = self
-            .original
-            .root_element
-            .borrow()
-            .property_declarations
-            .get(name)
-            .and_then(|d| d.is_alias.as_ref())
-        {
+        let alias = match self.original.root_element.borrow().property_declarations.get(name) {
+            Some(d) if !d.visibility.is_public() => return Err(()),
+            Some(d) => d.is_alias.clone(),
+            None => None,
+        };
+        if let Some(alias) = alias {
             eval::load_property(c, &alias.element(), alias.name())
         } else {
             eval::load_property(c, &self.original.root_element, name)
@@ -672,6 +778,12 @@ fn ensure_repeater_updated<'id>(
             assume_property_f32(get_property_ptr(&lv.viewport_y, instance_ref)),
             get_prop(&lv.listview_width),
             assume_property_f32(get_property_ptr(&lv.listview_height, instance_ref)),
+            unsafe {
+                Pin::new_unchecked(
+                    &*(get_property_ptr(&lv.first_visible_item, instance_ref)
+                        as *const Property<i32>),
+                )
+            },
         );
     } else {
         repeater.ensure_updated(init);
@@ -756,6 +868,8 @@ pub(crate) fn generate_component<'id>(
                 rtti_for::<Rectangle>(),
                 rtti_for::<BorderRectangle>(),
                 rtti_for::<TouchArea>(),
+                rtti_for::<HoverArea>(),
+                rtti_for::<LifecycleHandler>(),
                 rtti_for::<FocusScope>(),
                 rtti_for::<Path>(),
                 rtti_for::<Flickable>(),
@@ -766,6 +880,7 @@ pub(crate) fn generate_component<'id>(
                 rtti_for::<Rotate>(),
                 rtti_for::<Opacity>(),
                 rtti_for::<Layer>(),
+                rtti_for::<CustomItem>(),
             ]
             .iter()
             .cloned(),
@@ -958,6 +1073,8 @@ fn animated_property_info<T: Clone + Default + InterpolatedPropertyValue + 'stat
             Type::Struct { .. } => property_info::<Value>(),
             Type::Array(_) => property_info::<Value>(),
             Type::Percent => property_info::<f32>(),
+            // enums declared in .slint files are stored as Value::EnumerationValue
+            Type::Enumeration(e) if e.node.is_some() => property_info::<Value>(),
             Type::Enumeration(e) => {
                 macro_rules! match_enum_type {
                     ($( $(#[$enum_doc:meta])* enum $Name:ident { $($body:tt)* })*) => {
@@ -1049,6 +1166,9 @@ macro_rules! match_enum_type {
         get_subtree_component,
         parent_node,
         subtree_index,
+        accessible_role,
+        accessible_string_property,
+        accessibility_action,
         drop_in_place,
         dealloc,
     };
@@ -1143,7 +1263,10 @@ pub fn instantiate(
             Some(parent);
     } else {
         for g in &component_type.compiled_globals {
-            crate::global_component::instantiate(g, &mut globals);
+            // The globals of the components created with `create_with_existing_window` already exist
+            if !globals.contains_key(crate::normalize_identifier(&g.names()[0]).as_ref()) {
+                crate::global_component::instantiate(g, &mut globals);
+            }
         }
         let extra_data = component_type.extra_data_offset.apply_mut(instance.as_mut());
         extra_data.globals = globals;
@@ -1155,6 +1278,18 @@ pub fn instantiate(
             .iter()
             .map(|(path, er)| (er.id, path.clone()))
             .collect();
+        extra_data.processed_file_resources = component_type
+            .original
+            .embedded_file_resources
+            .borrow()
+            .values()
+            .filter_map(|er| match &er.kind {
+                embedded_resources::EmbeddedResourcesKind::ProcessedData(data) => {
+                    Some((er.id, leak_processed_data(data)))
+                }
+                _ => None,
+            })
+            .collect();
     }
     *component_type.window_offset.apply_mut(instance.as_mut()) =
         window.map(|window| window.clone().into());
@@ -1173,6 +1308,7 @@ pub fn instantiate(
     // Some properties are generated as Value, but for which the default constructed Value must be initialized
     for (prop_name, decl) in &component_type.original.root_element.borrow().property_declarations {
         if !matches!(decl.property_type, Type::Struct { .. } | Type::Array(_))
+            && !matches!(&decl.property_type, Type::Enumeration(e) if e.node.is_some())
             || decl.is_alias.is_some()
         {
             continue;
@@ -1182,10 +1318,19 @@ pub fn instantiate(
                 continue;
             }
         }
+        let default_value = if let Type::Struct { node: Some(_), .. } = &decl.property_type {
+            // Structs declared in .slint may have default values for their fields
+            eval::eval_expression(
+                &Expression::default_value_for_type(&decl.property_type),
+                &mut eval::EvalLocalContext::from_component_instance(instance_ref),
+            )
+        } else {
+            eval::default_value_for_type(&decl.property_type)
+        };
         let p = component_type.custom_properties.get(prop_name).unwrap();
         unsafe {
             let item = Pin::new_unchecked(&*instance_ref.as_ptr().add(p.offset));
-            p.prop.set(item, eval::default_value_for_type(&decl.property_type), None).unwrap();
+            p.prop.set(item, default_value, None).unwrap();
         }
     }
 
@@ -1382,6 +1527,48 @@ pub fn instantiate(
         });
     }
 
+    let mut change_trackers = vec![];
+    for_each_change_callback(&component_type.original, |nr, handler| {
+        let c = unsafe {
+            Pin::new_unchecked(vtable::VRef::from_raw(
+                NonNull::from(&component_type.ct).cast(),
+                component_box.instance.as_ptr().cast(),
+            ))
+        };
+        let prop = Expression::PropertyReference(nr.clone());
+        let handler = handler.clone();
+        let tracker = ChangeTracker::default();
+        tracker.init(
+            c,
+            move |c| {
+                generativity::make_guard!(guard);
+                eval::eval_expression(
+                    &prop,
+                    &mut eval::EvalLocalContext::from_component_instance(unsafe {
+                        InstanceRef::from_pin_ref(*c, guard)
+                    }),
+                )
+            },
+            move |c| {
+                generativity::make_guard!(guard);
+                eval::eval_expression(
+                    &handler,
+                    &mut eval::EvalLocalContext::from_component_instance(unsafe {
+                        InstanceRef::from_pin_ref(*c, guard)
+                    }),
+                );
+            },
+        );
+        change_trackers.push(tracker);
+    });
+    component_type
+        .extra_data_offset
+        .apply(instance_ref.as_ref())
+        .change_trackers
+        .set(change_trackers)
+        .ok()
+        .unwrap();
+
     let comp_rc = vtable::VRc::new(ErasedComponentBox::from(component_box));
     {
         generativity::make_guard!(guard);
@@ -1394,6 +1581,18 @@ pub fn instantiate(
     comp_rc
 }
 
+/// Calls `f` with the property and the code of the change callbacks of the elements of the component
+fn for_each_change_callback(
+    component: &Rc<object_tree::Component>,
+    mut f: impl FnMut(&NamedReference, &Expression),
+) {
+    object_tree::recurse_elem(&component.root_element, &(), &mut |elem, _| {
+        for (nr, handler) in &elem.borrow().change_callbacks {
+            f(nr, handler);
+        }
+    });
+}
+
 pub(crate) fn get_property_ptr(nr: &NamedReference, instance: InstanceRef) -> *const () {
     let element = nr.element();
     generativity::make_guard!(guard);
@@ -1461,6 +1660,12 @@ pub fn run_setup_code(&self) {
                 &mut eval::EvalLocalContext::from_component_instance(instance_ref),
             );
         }
+        for_each_change_callback(&self.0.component_type.original, |_, handler| {
+            eval::eval_expression(
+                handler,
+                &mut eval::EvalLocalContext::from_component_instance(instance_ref),
+            );
+        });
     }
 }
 impl<'id> From<ComponentBox<'id>> for ErasedComponentBox {
@@ -1603,6 +1808,82 @@ extern "C" fn subtree_index(component: ComponentRefPin) -> usize {
     }
 }
 
+/// Returns the accessible property `name` of the item at `item_index`, if it is set
+fn accessible_property(
+    instance_ref: InstanceRef,
+    item_index: usize,
+    name: &str,
+) -> Option<NamedReference> {
+    let item =
+        instance_ref.component_type.items.values().find(|item| item.item_index() == item_index)?;
+    let elem = item.elem.borrow();
+    elem.accessibility_props.0.get(name).cloned()
+}
+
+extern "C" fn accessible_role(component: ComponentRefPin, item_index: usize) -> AccessibleRole {
+    generativity::make_guard!(guard);
+    let instance_ref = unsafe { InstanceRef::from_pin_ref(component, guard) };
+    match accessible_property(instance_ref, item_index, "accessible-role") {
+        Some(nr) => {
+            eval::load_property(instance_ref, &nr.element(), nr.name()).unwrap().try_into().unwrap()
+        }
+        None => AccessibleRole::default(),
+    }
+}
+
+extern "C" fn accessible_string_property(
+    component: ComponentRefPin,
+    item_index: usize,
+    what: AccessibleStringProperty,
+    result: &mut SharedString,
+) {
+    generativity::make_guard!(guard);
+    let instance_ref = unsafe { InstanceRef::from_pin_ref(component, guard) };
+    let name = match what {
+        AccessibleStringProperty::Checkable => "accessible-checkable",
+        AccessibleStringProperty::Checked => "accessible-checked",
+        AccessibleStringProperty::Description => "accessible-description",
+        AccessibleStringProperty::Label => "accessible-label",
+        AccessibleStringProperty::Value => "accessible-value",
+        AccessibleStringProperty::ValueMaximum => "accessible-value-maximum",
+        AccessibleStringProperty::ValueMinimum => "accessible-value-minimum",
+        AccessibleStringProperty::ValueStep => "accessible-value-step",
+    };
+    if let Some(nr) = accessible_property(instance_ref, item_index, name) {
+        *result = match eval::load_property(instance_ref, &nr.element(), nr.name()).unwrap() {
+            Value::String(s) => s,
+            Value::Bool(b) => b.to_string().into(),
+            Value::Number(n) => n.to_string().into(),
+            v => panic!("Invalid value for {}: {:?}", name, v),
+        };
+    }
+}
+
+extern "C" fn accessibility_action(
+    component: ComponentRefPin,
+    item_index: usize,
+    action: &AccessibilityAction,
+) {
+    generativity::make_guard!(guard);
+    let instance_ref = unsafe { InstanceRef::from_pin_ref(component, guard) };
+    let (name, args) = match action {
+        AccessibilityAction::Default => ("accessible-action-default", vec![]),
+        AccessibilityAction::Decrement => ("accessible-action-decrement", vec![]),
+        AccessibilityAction::Increment => ("accessible-action-increment", vec![]),
+        AccessibilityAction::SetValue(value) => {
+            ("accessible-action-set-value", vec![Value::String(value.clone())])
+        }
+    };
+    if let Some(nr) = accessible_property(instance_ref, item_index, name) {
+        eval::invoke_callback(
+            eval::ComponentInstance::InstanceRef(instance_ref),
+            &nr.element(),
+            nr.name(),
+            &args,
+        );
+    }
+}
+
 unsafe extern "C" fn drop_in_place(component: vtable::VRefMut<ComponentVTable>) -> vtable::Layout {
     let instance_ptr = component.as_ptr() as *mut Instance<'static>;
     let layout = (*instance_ptr).type_info().layout();
@@ -221,6 +221,34 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                     ComponentInstance::GlobalComponent(_) => panic!("Cannot get the window from a global component"),
                 }
             }
+            Expression::BuiltinFunctionReference(BuiltinFunction::Snap, _) => {
+                let length: f64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                match local_context.component_instance {
+                    ComponentInstance::InstanceRef(component) => {
+                        let scale_factor = window_ref(component).unwrap().scale_factor() as f64;
+                        Value::Number((length * scale_factor).round() / scale_factor)
+                    }
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot get the window from a global component"),
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::GetWindowWidth, _) => {
+                match local_context.component_instance {
+                    ComponentInstance::InstanceRef(component) => Value::Number(window_ref(component).unwrap().window_item_size().width as _),
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot get the window from a global component"),
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::GetWindowHeight, _) => {
+                match local_context.component_instance {
+                    ComponentInstance::InstanceRef(component) => Value::Number(window_ref(component).unwrap().window_item_size().height as _),
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot get the window from a global component"),
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::GetWindowDefaultFontSize, _) => {
+                match local_context.component_instance {
+                    ComponentInstance::InstanceRef(component) => Value::Number(window_ref(component).unwrap().default_font_size() as _),
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot get the window from a global component"),
+                }
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::Debug, _) => {
                 let to_print: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
                 corelib::debug_log!("{}", to_print);
@@ -310,6 +338,110 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                     panic!("internal error: argument to SetFocusItem must be an element")
                 }
             }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ScrollTo, _) => {
+                if arguments.len() != 4 {
+                    panic!("internal error: incorrect argument count to ScrollTo")
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot scroll a Flickable from a global component")
+                };
+                if let Expression::ElementReference(flickable) = &arguments[0] {
+                    let flickable = item_rc_for_element(&flickable.upgrade().unwrap(), component);
+                    let x: f64 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                    let y: f64 = eval_expression(&arguments[2], local_context).try_into().unwrap();
+                    let animated: bool = eval_expression(&arguments[3], local_context).try_into().unwrap();
+                    corelib::items::flickable_scroll_to(&flickable, x as _, y as _, animated);
+                    Value::Void
+                } else {
+                    panic!("internal error: first argument to ScrollTo must be an element")
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::EnsureVisible, _) => {
+                if arguments.len() != 2 {
+                    panic!("internal error: incorrect argument count to EnsureVisible")
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot scroll a Flickable from a global component")
+                };
+                if let (Expression::ElementReference(flickable), Expression::ElementReference(target)) = (&arguments[0], &arguments[1]) {
+                    let flickable = item_rc_for_element(&flickable.upgrade().unwrap(), component);
+                    let target = item_rc_for_element(&target.upgrade().unwrap(), component);
+                    corelib::items::flickable_ensure_visible(&flickable, &target);
+                    Value::Void
+                } else {
+                    panic!("internal error: arguments to EnsureVisible must be elements")
+                }
+            }
+            Expression::BuiltinFunctionReference(
+                f @ (BuiltinFunction::TextInputUndo | BuiltinFunction::TextInputRedo),
+                _,
+            ) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot undo changes in a TextInput from a global component")
+                };
+                if let Expression::ElementReference(text_input) = &arguments[0] {
+                    let text_input = item_rc_for_element(&text_input.upgrade().unwrap(), component);
+                    let window = window_ref(component).unwrap();
+                    if matches!(f, BuiltinFunction::TextInputUndo) {
+                        corelib::items::text_input_undo(&text_input, window);
+                    } else {
+                        corelib::items::text_input_redo(&text_input, window);
+                    }
+                    Value::Void
+                } else {
+                    panic!("internal error: argument to {:?} must be an element", f)
+                }
+            }
+            Expression::BuiltinFunctionReference(
+                f @ (BuiltinFunction::TextInputFindNext | BuiltinFunction::TextInputFindPrevious),
+                _,
+            ) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot search in a TextInput from a global component")
+                };
+                if let Expression::ElementReference(text_input) = &arguments[0] {
+                    let text_input = item_rc_for_element(&text_input.upgrade().unwrap(), component);
+                    let window = window_ref(component).unwrap();
+                    let forward = matches!(f, BuiltinFunction::TextInputFindNext);
+                    Value::Bool(corelib::items::text_input_find(&text_input, window, forward))
+                } else {
+                    panic!("internal error: argument to {:?} must be an element", f)
+                }
+            }
+            Expression::BuiltinFunctionReference(
+                f @ (BuiltinFunction::TextInputSearchMatchCount
+                | BuiltinFunction::TextInputCurrentMatch),
+                _,
+            ) => {
+                if arguments.len() != 1 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                let component = match  local_context.component_instance  {
+                    ComponentInstance::InstanceRef(c) => c,
+                    ComponentInstance::GlobalComponent(_) => panic!("Cannot search in a TextInput from a global component")
+                };
+                if let Expression::ElementReference(text_input) = &arguments[0] {
+                    let text_input = item_rc_for_element(&text_input.upgrade().unwrap(), component);
+                    let result = if matches!(f, BuiltinFunction::TextInputSearchMatchCount) {
+                        corelib::items::text_input_search_match_count(&text_input)
+                    } else {
+                        corelib::items::text_input_current_match(&text_input)
+                    };
+                    Value::Number(result as f64)
+                } else {
+                    panic!("internal error: argument to {:?} must be an element", f)
+                }
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::ShowPopupWindow, _) => {
                 if arguments.len() != 1 {
                     panic!("internal error: incorrect argument count to ShowPopupWindow")
@@ -365,6 +497,18 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                     panic!("Argument not a string");
                 }
             }
+            Expression::BuiltinFunctionReference(BuiltinFunction::StringFormatFloat, _) => {
+                let value: f64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                let precision: i32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                Value::String(corelib::string::format_float(value, precision))
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::StringPad, _) => {
+                let string: SharedString = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                let width: i32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                let fill: SharedString = eval_expression(&arguments[2], local_context).try_into().unwrap();
+                let alignment: i32 = eval_expression(&arguments[3], local_context).try_into().unwrap();
+                Value::String(corelib::string::pad_string(&string, width, &fill, alignment))
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::ColorBrighter, _) => {
                 if arguments.len() != 2 {
                     panic!("internal error: incorrect argument count to ColorBrighter")
@@ -393,6 +537,63 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                     panic!("First argument not a color");
                 }
             }
+            Expression::BuiltinFunctionReference(f @ (BuiltinFunction::ColorLighter | BuiltinFunction::ColorSaturate | BuiltinFunction::ColorWithAlpha), _) => {
+                if arguments.len() != 2 {
+                    panic!("internal error: incorrect argument count to {:?}", f)
+                }
+                if let Value::Brush(brush) = eval_expression(&arguments[0], local_context) {
+                    if let Value::Number(factor) = eval_expression(&arguments[1], local_context) {
+                        match f {
+                            BuiltinFunction::ColorLighter => brush.lighter(factor as _).into(),
+                            BuiltinFunction::ColorSaturate => brush.saturate(factor as _).into(),
+                            _ => brush.with_alpha(factor as _).into(),
+                        }
+                    } else {
+                        panic!("Second argument not a number");
+                    }
+                } else {
+                    panic!("First argument not a color");
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::ColorMix, _) => {
+                if arguments.len() != 3 {
+                    panic!("internal error: incorrect argument count to ColorMix")
+                }
+                match (
+                    eval_expression(&arguments[0], local_context),
+                    eval_expression(&arguments[1], local_context),
+                    eval_expression(&arguments[2], local_context),
+                ) {
+                    (Value::Brush(a), Value::Brush(b), Value::Number(factor)) => {
+                        a.mix(&b, factor as _).into()
+                    }
+                    _ => panic!("Invalid arguments to ColorMix"),
+                }
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::FormatNumber, _) => {
+                let value: f64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                let decimals: i32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                Value::String(corelib::locale::format_number(value, decimals))
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::FormatPercent, _) => {
+                let value: f64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                let decimals: i32 = eval_expression(&arguments[1], local_context).try_into().unwrap();
+                Value::String(corelib::locale::format_percent(value, decimals))
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::FormatDate, _) => {
+                let timestamp: i64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                Value::String(corelib::locale::format_date(timestamp))
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::FormatTime, _) => {
+                let timestamp: i64 = eval_expression(&arguments[0], local_context).try_into().unwrap();
+                Value::String(corelib::locale::format_time(timestamp))
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::GetColorScheme, _) => {
+                corelib::color_scheme::color_scheme().into()
+            }
+            Expression::BuiltinFunctionReference(BuiltinFunction::AnimationTick, _) => {
+                Value::Number(corelib::animations::animation_tick() as f64)
+            }
             Expression::BuiltinFunctionReference(BuiltinFunction::ImageSize, _) => {
                 if arguments.len() != 1 {
                     panic!("internal error: incorrect argument count to ImageSize")
@@ -531,23 +732,34 @@ pub fn eval_expression(expression: &Expression, local_context: &mut EvalLocalCon
                         ComponentInstance::GlobalComponent(_) => unimplemented!(),
                     };
                     let extra_data = toplevel_instance.component_type.extra_data_offset.apply(toplevel_instance.as_ref());
-                    let path = extra_data.embedded_file_resources.get(resource_id).expect("internal error: invalid resource id");
-
-                    let virtual_file = i_slint_compiler::fileaccess::load_file(std::path::Path::new(path)).unwrap();  // embedding pass ensured that the file exists
-
-                    if let (std::borrow::Cow::Borrowed(static_path), Some(static_data)) = (virtual_file.path, virtual_file.builtin_contents) {
-                        let virtual_file_extension = std::path::Path::new(static_path).extension().unwrap().to_str().unwrap();
-                        debug_assert_eq!(virtual_file_extension, extension);
+                    if let Some(&data) = extra_data.processed_file_resources.get(resource_id) {
+                        // The compiler only produces PNG and SVG files
+                        let format: &'static [u8] = if extension == "svg" { b"svg" } else { b"png" };
                         Ok(corelib::graphics::Image::from(
                             corelib::graphics::ImageInner::EmbeddedData {
-                                data: corelib::slice::Slice::from_slice(static_data),
-                                format: corelib::slice::Slice::from_slice(virtual_file_extension.as_bytes())
+                                data: corelib::slice::Slice::from_slice(data),
+                                format: corelib::slice::Slice::from_slice(format),
                             }
                         ))
                     } else {
-                        corelib::debug_log!("Cannot embed images from disk {}", path);
-                        Ok(corelib::graphics::Image::default())
+                        let path = extra_data.embedded_file_resources.get(resource_id).expect("internal error: invalid resource id");
+
+                        let virtual_file = i_slint_compiler::fileaccess::load_file(std::path::Path::new(path)).unwrap();  // embedding pass ensured that the file exists
 
+                        if let (std::borrow::Cow::Borrowed(static_path), Some(static_data)) = (virtual_file.path, virtual_file.builtin_contents) {
+                            let virtual_file_extension = std::path::Path::new(static_path).extension().unwrap().to_str().unwrap();
+                            debug_assert_eq!(virtual_file_extension, extension);
+                            Ok(corelib::graphics::Image::from(
+                                corelib::graphics::ImageInner::EmbeddedData {
+                                    data: corelib::slice::Slice::from_slice(static_data),
+                                    format: corelib::slice::Slice::from_slice(virtual_file_extension.as_bytes())
+                                }
+                            ))
+                        } else {
+                            corelib::debug_log!("Cannot embed images from disk {}", path);
+                            Ok(corelib::graphics::Image::default())
+
+                        }
                     }
                 }
                 i_slint_compiler::expression_tree::ImageReference::EmbeddedTexture { .. } => {
@@ -1020,6 +1232,15 @@ pub fn enclosing_component_for_element<'a, 'old_id, 'new_id>(
     }
 }
 
+/// Return the ItemRc of the given element, which must be in the component or in one of its parents.
+fn item_rc_for_element(element: &ElementRc, component: InstanceRef) -> corelib::items::ItemRc {
+    generativity::make_guard!(guard);
+    let enclosing_component = enclosing_component_for_element(element, component, guard);
+    let item_info = &enclosing_component.component_type.items[element.borrow().id.as_str()];
+    let item_comp = enclosing_component.self_weak().get().unwrap().upgrade().unwrap();
+    corelib::items::ItemRc::new(vtable::VRc::into_dyn(item_comp), item_info.item_index())
+}
+
 /// Return the component instance which hold the given element.
 /// The difference with enclosing_component_for_element is that it takes the GlobalComponent into account.
 pub(crate) fn enclosing_component_instance_for_element<'a, 'old_id, 'new_id>(
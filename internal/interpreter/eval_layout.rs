@@ -198,6 +198,17 @@ fn grid_layout_data(
             let (col_or_row, span) = cell.col_or_row_and_span(orientation);
             core_layout::GridLayoutCellData { col_or_row, span, constraint: layout_info }
         })
+        .chain(grid_layout.policies.iter().filter(|p| p.orientation == orientation).map(|p| {
+            // The sizing policy of a column or row is a cell with a span of 0
+            let mut layout_info =
+                core_layout::LayoutInfo { stretch: f32::MAX, ..Default::default() };
+            fill_layout_info_constraints(&mut layout_info, &p.constraints, orientation, &expr_eval);
+            core_layout::GridLayoutCellData {
+                col_or_row: p.index,
+                span: 0,
+                constraint: layout_info,
+            }
+        }))
         .collect::<Vec<_>>();
     cells
 }
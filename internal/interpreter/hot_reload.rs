@@ -0,0 +1,197 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Hot reload of a component loaded from a file, created with
+[`ComponentCompiler::build_from_path_with_hot_reload`].
+*/
+
+use crate::api::{
+    ComponentCompiler, ComponentDefinition, ComponentHandle, ComponentInstance, Diagnostic, Value,
+};
+use i_slint_core::timers::{Timer, TimerMode};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+/// How often the files are checked for modifications
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// The values of the properties of an instance, indexed by global name (empty for the component)
+/// and property name
+type PropertyValues = HashMap<(String, String), Value>;
+
+type SetupHandler = Box<dyn Fn(&ComponentInstance)>;
+
+struct State {
+    path: PathBuf,
+    compiler: RefCell<ComponentCompiler>,
+    /// The files the component depends on, and their modification time when it was loaded
+    files: RefCell<Vec<(PathBuf, Option<SystemTime>)>>,
+    /// The latest instance of the component
+    current: RefCell<ComponentInstance>,
+    /// The values of the properties of the latest instance when it was created
+    loaded_values: RefCell<PropertyValues>,
+    setup_handler: RefCell<Option<Rc<SetupHandler>>>,
+    timer: Timer,
+}
+
+/// An instance of a component that is reloaded when the files it was loaded from change.
+///
+/// It is created with [`ComponentCompiler::build_from_path_with_hot_reload`]. All the instances
+/// of the component are shown in the same window.
+#[derive(Clone)]
+pub struct HotReloadingInstance {
+    state: Rc<State>,
+}
+
+impl HotReloadingInstance {
+    pub(crate) fn new(
+        path: PathBuf,
+        compiler: ComponentCompiler,
+        definition: ComponentDefinition,
+        files: &[PathBuf],
+    ) -> Self {
+        let instance = definition.create();
+        let state = Rc::new(State {
+            path,
+            compiler: RefCell::new(compiler),
+            files: RefCell::new(with_modification_times(files)),
+            loaded_values: RefCell::new(property_values(&instance)),
+            current: RefCell::new(instance),
+            setup_handler: Default::default(),
+            timer: Default::default(),
+        });
+        let weak_state = Rc::downgrade(&state);
+        state.timer.start(TimerMode::Repeated, POLL_INTERVAL, move || {
+            if let Some(state) = weak_state.upgrade() {
+                if state.files.borrow().iter().any(|(file, time)| modification_time(file) != *time)
+                {
+                    state.reload();
+                }
+            }
+        });
+        Self { state }
+    }
+
+    /// Returns the latest instance of the component.
+    ///
+    /// The instance is replaced when the component is reloaded, so it should not be kept for
+    /// longer than needed.
+    pub fn instance(&self) -> ComponentInstance {
+        self.state.current.borrow().clone_strong()
+    }
+
+    /// Sets a function that is called to set up the current instance of the component, and each new
+    /// instance after the component is reloaded, for example to set the callback handlers.
+    pub fn set_setup_handler(&self, handler: impl Fn(&ComponentInstance) + 'static) {
+        let handler: Rc<SetupHandler> = Rc::new(Box::new(handler));
+        *self.state.setup_handler.borrow_mut() = Some(handler.clone());
+        handler(&self.instance());
+    }
+
+    /// Compiles the file again and replaces the instance with a new one, even if the files did not change.
+    ///
+    /// Returns false if the file could not be compiled. The diagnostics are then returned by
+    /// [`Self::diagnostics`], and the previous instance stays.
+    pub fn reload(&self) -> bool {
+        self.state.reload()
+    }
+
+    /// Returns the diagnostics of the last compilation of the file by [`Self::reload`], or when the
+    /// files changed.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.state.compiler.borrow().diagnostics().clone()
+    }
+
+    /// Shows the window of the component, runs the event loop, and hides the window again.
+    pub fn run(&self) {
+        self.instance().show();
+        crate::run_event_loop();
+        self.instance().hide();
+    }
+}
+
+impl State {
+    fn reload(&self) -> bool {
+        let definition = {
+            let mut compiler = self.compiler.borrow_mut();
+            // 'spin_on' is ok here because the compiler in single threaded and does not block if there is no blocking future
+            let definition = spin_on::spin_on(compiler.build_from_path(&self.path));
+            *self.files.borrow_mut() = with_modification_times(compiler.loaded_files());
+            for diagnostic in compiler.diagnostics() {
+                eprintln!("{}", diagnostic);
+            }
+            match definition {
+                Some(definition) => definition,
+                None => return false,
+            }
+        };
+
+        let previous = self.current.borrow().clone_strong();
+        let previous_definition = previous.definition();
+        let instance = definition.create_with_existing_window(previous.window());
+        let loaded_values = property_values(&instance);
+        // Keep the values that changed while the previous instance was running, such as the state
+        // changed by the user, unless they are computed from other properties
+        for ((global, name), loaded_value) in self.loaded_values.borrow().iter() {
+            if previous_definition.has_non_constant_binding(global, name) {
+                continue;
+            }
+            let value = if global.is_empty() {
+                previous.get_property(name)
+            } else {
+                previous.get_global_property(global, name)
+            };
+            match value {
+                Ok(value) if value != *loaded_value => {
+                    // The property might not exist anymore, or have another type
+                    let _ = if global.is_empty() {
+                        instance.set_property(name, value)
+                    } else {
+                        instance.set_global_property(global, name, value)
+                    };
+                }
+                _ => {}
+            }
+        }
+        *self.loaded_values.borrow_mut() = loaded_values;
+
+        let setup_handler = self.setup_handler.borrow().clone();
+        if let Some(setup_handler) = setup_handler {
+            setup_handler(&instance);
+        }
+        *self.current.borrow_mut() = instance;
+        previous.window().request_redraw();
+        true
+    }
+}
+
+/// Returns the values of all the public properties of the component and of its exported globals
+fn property_values(instance: &ComponentInstance) -> PropertyValues {
+    let definition = instance.definition();
+    let mut values: PropertyValues = definition
+        .properties()
+        .filter_map(|(name, _)| {
+            Some(((String::new(), name.clone()), instance.get_property(&name).ok()?))
+        })
+        .collect();
+    for global in definition.globals() {
+        for (name, _) in definition.global_properties(&global).into_iter().flatten() {
+            if let Ok(value) = instance.get_global_property(&global, &name) {
+                values.insert((global.clone(), name), value);
+            }
+        }
+    }
+    values
+}
+
+fn with_modification_times(files: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    files.iter().map(|f| (f.clone(), modification_time(f))).collect()
+}
+
+fn modification_time(path: impl AsRef<std::path::Path>) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
@@ -78,12 +78,23 @@
 mod eval;
 mod eval_layout;
 mod global_component;
+#[cfg(feature = "hot-reload")]
+mod hot_reload;
+#[cfg(feature = "live-reload")]
+#[doc(hidden)]
+pub mod live_reload;
 #[cfg(doc)]
 pub mod migration;
 mod value_model;
+#[cfg(feature = "serde")]
+mod value_serde;
 
 #[doc(inline)]
 pub use api::*;
+#[cfg(feature = "hot-reload")]
+pub use hot_reload::HotReloadingInstance;
+#[cfg(feature = "serde")]
+pub use value_serde::{from_value, to_value, ValueConversionError};
 
 /// This function can be used to register a custom TrueType font with Slint,
 /// for use with the `font-family` property. The provided path must refer to a valid TrueType
@@ -98,6 +109,22 @@ pub(crate) fn register_font_from_path<P: AsRef<std::path::Path>>(
 #[doc(inline)]
 pub use i_slint_core::{Brush, Color, SharedString, SharedVector};
 
+/// The types used to implement the content of the `CustomItem` element in Rust.
+///
+/// Register a [`CustomItemHandler`](custom_item::CustomItemHandler) for a type name with
+/// [`register_custom_item()`](custom_item::register_custom_item), and use that type name in
+/// the `type-name` property of `CustomItem` elements.
+pub mod custom_item {
+    pub use i_slint_core::graphics::Point;
+    pub use i_slint_core::input::{
+        InputEventResult, KeyEvent, KeyEventResult, KeyEventType, KeyboardModifiers, MouseEvent,
+    };
+    pub use i_slint_core::items::{
+        register_custom_item, CustomItemHandle, CustomItemHandler, PointerEventButton,
+    };
+    pub use i_slint_core::layout::{LayoutInfo, Orientation};
+}
+
 /// One need to use at least one function in each module in order to get them
 /// exported in the final binary.
 /// This only use functions from modules which are not otherwise used.
@@ -0,0 +1,326 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Support for the live-reload mode of the code generated by `slint-build`.
+
+In that mode, the generated component is a thin wrapper around a [`LiveReloadingComponent`],
+which loads the `.slint` file with the interpreter and loads it again when one of the files
+it depends on changes. The values of the properties and the callback handlers that were
+set from Rust are applied again to the new instance.
+*/
+
+use crate::api::{ComponentCompiler, ComponentHandle, ComponentInstance, Window};
+use i_slint_core::model::{Model, ModelRc, ModelTracker};
+use i_slint_core::timers::{Timer, TimerMode};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::{Rc, Weak};
+use std::time::SystemTime;
+
+pub use crate::api::{Struct, Value};
+
+/// How often the files are checked for modifications
+const POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// The `Inner` type of the `ComponentHandle` implementation of the generated component
+pub type Inner = <ComponentInstance as ComponentHandle>::Inner;
+
+type CallbackHandler = Rc<dyn Fn(&[Value]) -> Value>;
+
+struct State {
+    path: PathBuf,
+    include_paths: Vec<PathBuf>,
+    style: Option<String>,
+    /// The files the component depends on, and their modification time when it was loaded
+    files: RefCell<Vec<(PathBuf, Option<SystemTime>)>>,
+    /// The latest instance of the component
+    current: RefCell<ComponentInstance>,
+    /// The values set from Rust, indexed by global name (empty for the component) and property name
+    properties: RefCell<HashMap<(String, String), Value>>,
+    /// The callback handlers set from Rust, with the same key as `properties`
+    callbacks: RefCell<HashMap<(String, String), CallbackHandler>>,
+    /// The key of this state in `INSTANCES`
+    key: usize,
+    timer: Timer,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        let _ = INSTANCES.try_with(|instances| instances.borrow_mut().remove(&self.key));
+    }
+}
+
+thread_local! {
+    /// The state of the live-reloading components, indexed by the address of their window,
+    /// so that they can be found again when upgrading a weak reference.
+    static INSTANCES: RefCell<HashMap<usize, Weak<State>>> = Default::default();
+}
+
+/// A component that is loaded with the interpreter and that is reloaded when its source changes.
+pub struct LiveReloadingComponent {
+    /// The first instance. It owns the window shared by all the instances, and is used
+    /// as the target of the weak references.
+    original: ComponentInstance,
+    state: Rc<State>,
+}
+
+impl LiveReloadingComponent {
+    /// Load the main component of the `.slint` file at `path`.
+    ///
+    /// `files` are the files that are watched for changes. Panics if the file cannot be loaded.
+    pub fn new(path: &str, include_paths: &[&str], style: Option<&str>, files: &[&str]) -> Self {
+        let path = PathBuf::from(path);
+        let include_paths = include_paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+        let style = style.map(String::from);
+        let files = files.iter().map(|f| (PathBuf::from(f), modification_time(f))).collect();
+        let original = load(&path, &include_paths, &style)
+            .unwrap_or_else(|| panic!("Could not load {}", path.display()))
+            .create();
+        let key = original.window() as *const Window as usize;
+        let state = Rc::new(State {
+            path,
+            include_paths,
+            style,
+            files: RefCell::new(files),
+            current: RefCell::new(original.clone_strong()),
+            properties: Default::default(),
+            callbacks: Default::default(),
+            key,
+            timer: Default::default(),
+        });
+        INSTANCES.with(|instances| instances.borrow_mut().insert(key, Rc::downgrade(&state)));
+
+        let weak_state = Rc::downgrade(&state);
+        state.timer.start(TimerMode::Repeated, POLL_INTERVAL, move || {
+            if let Some(state) = weak_state.upgrade() {
+                state.reload_if_changed();
+            }
+        });
+        Self { original, state }
+    }
+
+    /// Return the value of a property of the component, or of an exported global if `global`
+    /// is not empty.
+    pub fn get_property(&self, global: &str, name: &str) -> Value {
+        let current = self.state.current.borrow();
+        let value = if global.is_empty() {
+            current.get_property(name).ok()
+        } else {
+            current.get_global_property(global, name).ok()
+        };
+        value.unwrap_or_default()
+    }
+
+    /// Set the value of a property. It is set again after the component is reloaded.
+    pub fn set_property(&self, global: &str, name: &str, value: Value) {
+        self.state.properties.borrow_mut().insert((global.into(), name.into()), value.clone());
+        set_property(&self.state.current.borrow(), global, name, value);
+    }
+
+    /// Set the handler of a callback. It is set again after the component is reloaded.
+    pub fn set_callback(
+        &self,
+        global: &str,
+        name: &str,
+        handler: impl Fn(&[Value]) -> Value + 'static,
+    ) {
+        let handler: CallbackHandler = Rc::new(handler);
+        self.state.callbacks.borrow_mut().insert((global.into(), name.into()), handler.clone());
+        set_callback(&self.state.current.borrow(), global, name, handler);
+    }
+
+    /// Invoke a callback of the component, or of an exported global if `global` is not empty.
+    pub fn invoke_callback(&self, global: &str, name: &str, args: &[Value]) -> Value {
+        // Don't keep the instance borrowed while the handler runs, it might reload the component
+        let current = self.state.current.borrow().clone_strong();
+        let result = if global.is_empty() {
+            current.invoke_callback(name, args).ok()
+        } else {
+            current.invoke_global_callback(global, name, args).ok()
+        };
+        result.unwrap_or_default()
+    }
+
+    /// The strong reference used to create weak references to the component
+    pub fn inner(&self) -> vtable::VRc<i_slint_core::component::ComponentVTable, Inner> {
+        self.original.clone_strong().into()
+    }
+
+    /// Return the component from the strong reference upgraded from a weak reference
+    pub fn from_inner(inner: vtable::VRc<i_slint_core::component::ComponentVTable, Inner>) -> Self {
+        let original = ComponentInstance::from_inner(inner);
+        let key = original.window() as *const Window as usize;
+        let state = INSTANCES
+            .with(|instances| instances.borrow().get(&key).and_then(Weak::upgrade))
+            .expect("the state of a live-reloading component lives as long as the component");
+        Self { original, state }
+    }
+
+    /// Returns a new handle to the same component
+    pub fn clone_strong(&self) -> Self {
+        Self { original: self.original.clone_strong(), state: self.state.clone() }
+    }
+
+    /// Returns the window shared by all the instances of the component
+    pub fn window(&self) -> &Window {
+        self.original.window()
+    }
+}
+
+impl State {
+    fn reload_if_changed(&self) {
+        let changed =
+            self.files.borrow().iter().any(|(file, time)| modification_time(file) != *time);
+        if !changed {
+            return;
+        }
+        for (file, time) in self.files.borrow_mut().iter_mut() {
+            *time = modification_time(file);
+        }
+        let definition = match load(&self.path, &self.include_paths, &self.style) {
+            Some(definition) => definition,
+            None => return,
+        };
+        let current = self.current.borrow().clone_strong();
+        // The globals are set before the bindings of the new instance are initialized
+        let instance =
+            definition.create_with_existing_window_and_globals(current.window(), |globals| {
+                for ((global, name), value) in self.properties.borrow().iter() {
+                    if !global.is_empty() {
+                        report_error(name, globals.set_property(global, name, value.clone()));
+                    }
+                }
+                for ((global, name), handler) in self.callbacks.borrow().iter() {
+                    if !global.is_empty() {
+                        let handler = handler.clone();
+                        let result = globals.set_callback(global, name, move |args| handler(args));
+                        report_error(name, result);
+                    }
+                }
+            });
+        for ((global, name), value) in self.properties.borrow().iter() {
+            if global.is_empty() {
+                set_property(&instance, global, name, value.clone());
+            }
+        }
+        for ((global, name), handler) in self.callbacks.borrow().iter() {
+            if global.is_empty() {
+                set_callback(&instance, global, name, handler.clone());
+            }
+        }
+        *self.current.borrow_mut() = instance;
+        current.window().request_redraw();
+        eprintln!("Reloaded {}", self.path.display());
+    }
+}
+
+fn modification_time(path: impl AsRef<std::path::Path>) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Compile the file and print the diagnostics
+fn load(
+    path: &std::path::Path,
+    include_paths: &[PathBuf],
+    style: &Option<String>,
+) -> Option<crate::api::ComponentDefinition> {
+    let mut compiler = ComponentCompiler::default();
+    compiler.set_include_paths(include_paths.to_vec());
+    if let Some(style) = style {
+        compiler.set_style(style.clone());
+    }
+    // 'spin_on' is ok here because the compiler in single threaded and does not block if there is no blocking future
+    let definition = spin_on::spin_on(compiler.build_from_path(path));
+    for diagnostic in compiler.diagnostics() {
+        eprintln!("{}", diagnostic);
+    }
+    definition
+}
+
+fn set_property(instance: &ComponentInstance, global: &str, name: &str, value: Value) {
+    let result = if global.is_empty() {
+        instance.set_property(name, value)
+    } else {
+        instance.set_global_property(global, name, value)
+    };
+    report_error(name, result);
+}
+
+fn set_callback(instance: &ComponentInstance, global: &str, name: &str, handler: CallbackHandler) {
+    let callback = move |args: &[Value]| handler(args);
+    let result = if global.is_empty() {
+        instance.set_callback(name, callback)
+    } else {
+        instance.set_global_callback(global, name, callback)
+    };
+    report_error(name, result);
+}
+
+/// Print the error of setting the property or the callback `name`, if any
+fn report_error(name: &str, result: Result<(), impl std::fmt::Display>) {
+    if let Err(err) = result {
+        eprintln!("Cannot set '{}': {}", name, err);
+    }
+}
+
+/// A model that converts the rows of the `source` model
+struct ConvertedModel<S, T> {
+    source: ModelRc<S>,
+    to: fn(S) -> T,
+    from: fn(T) -> S,
+}
+
+impl<S: 'static, T: 'static> Model for ConvertedModel<S, T> {
+    type Data = T;
+
+    fn row_count(&self) -> usize {
+        self.source.row_count()
+    }
+
+    fn row_data(&self, row: usize) -> Option<Self::Data> {
+        self.source.row_data(row).map(self.to)
+    }
+
+    fn set_row_data(&self, row: usize, data: Self::Data) {
+        self.source.set_row_data(row, (self.from)(data))
+    }
+
+    fn model_tracker(&self) -> &dyn ModelTracker {
+        self.source.model_tracker()
+    }
+
+    fn as_any(&self) -> &dyn core::any::Any {
+        self
+    }
+}
+
+/// Convert a model of the generated code to a [`Value`], using `to` and `from` to convert its rows
+pub fn model_to_value<T: 'static>(
+    model: ModelRc<T>,
+    to: fn(T) -> Value,
+    from: fn(Value) -> T,
+) -> Value {
+    if let Some(converted) = model.as_any().downcast_ref::<ConvertedModel<Value, T>>() {
+        return Value::Model(converted.source.clone());
+    }
+    Value::Model(ModelRc::new(ConvertedModel { source: model, to, from }))
+}
+
+/// Convert a [`Value`] to a model of the generated code, using `to` and `from` to convert its rows
+pub fn model_from_value<T: 'static>(
+    value: Value,
+    to: fn(Value) -> T,
+    from: fn(T) -> Value,
+) -> ModelRc<T> {
+    match value {
+        Value::Model(model) => {
+            if let Some(converted) = model.as_any().downcast_ref::<ConvertedModel<T, Value>>() {
+                return converted.source.clone();
+            }
+            ModelRc::new(ConvertedModel { source: model, to, from })
+        }
+        _ => ModelRc::default(),
+    }
+}
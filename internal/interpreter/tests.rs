@@ -43,3 +43,80 @@ fn reuse_window() {
         instance
     };
 }
+
+#[cfg(feature = "hot-reload")]
+#[test]
+fn hot_reload() {
+    i_slint_backend_testing::init();
+    use crate::{ComponentCompiler, SharedString, Value};
+    let dir = std::env::temp_dir().join(format!("slint-hot-reload-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("main.slint");
+    std::fs::write(
+        &dir.join("palette.slint"),
+        "export global Palette := { property <int> size: 1; }",
+    )
+    .unwrap();
+    std::fs::write(
+        &path,
+        r#"
+        import { Palette } from "palette.slint";
+        MainWindow := Window {
+            property <int> counter: 1;
+            property <string> label: "Count: " + counter;
+            property <int> default-value: 10;
+            property <string> removed;
+        }
+    "#,
+    )
+    .unwrap();
+
+    let mut compiler = ComponentCompiler::default();
+    compiler.set_style("fluent".into());
+    let hot_reloading = spin_on::spin_on(compiler.build_from_path_with_hot_reload(&path)).unwrap();
+    assert!(compiler.diagnostics().is_empty(), "{:?}", compiler.diagnostics());
+    assert!(compiler.loaded_files().iter().any(|f| f.ends_with("palette.slint")));
+
+    let setup_count = std::rc::Rc::new(std::cell::Cell::new(0));
+    hot_reloading.set_setup_handler({
+        let setup_count = setup_count.clone();
+        move |_| setup_count.set(setup_count.get() + 1)
+    });
+    assert_eq!(setup_count.get(), 1);
+    let instance = hot_reloading.instance();
+    instance.set_property("counter", Value::Number(5.)).unwrap();
+    instance.set_property("removed", Value::String("x".into())).unwrap();
+    drop(instance);
+
+    std::fs::write(
+        &path,
+        r#"
+        import { Palette } from "palette.slint";
+        MainWindow := Window {
+            property <int> counter: 1;
+            property <string> label: "Counter: " + counter;
+            property <int> default-value: 20;
+        }
+    "#,
+    )
+    .unwrap();
+    assert!(hot_reloading.reload());
+    assert_eq!(setup_count.get(), 2);
+    let instance = hot_reloading.instance();
+    // The value that was changed is kept, and the new bindings and default values are used
+    assert_eq!(instance.get_property("counter").unwrap(), Value::Number(5.));
+    assert_eq!(
+        instance.get_property("label").unwrap(),
+        Value::String(SharedString::from("Counter: 5"))
+    );
+    assert_eq!(instance.get_property("default-value").unwrap(), Value::Number(20.));
+    drop(instance);
+
+    // The previous instance stays when the file has errors
+    std::fs::write(&path, "MainWindow := Window { property <int> counter: ; }").unwrap();
+    assert!(!hot_reloading.reload());
+    assert!(!hot_reloading.diagnostics().is_empty());
+    assert_eq!(hot_reloading.instance().get_property("counter").unwrap(), Value::Number(5.));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,611 @@
+// Copyright © SixtyFPS GmbH <info@slint-ui.com>
+// SPDX-License-Identifier: GPL-3.0-only OR LicenseRef-Slint-commercial
+
+/*!
+Support for `serde`: [`Value`] implements `Serialize` and `Deserialize`, and the [`to_value`] and
+[`from_value`] functions convert between [`Value`] and the Rust types that implement `Serialize`
+and `Deserialize`.
+
+The values map to the serde data model as follows:
+
+ * Numbers are numbers. The numbers without a fractional part are serialized as integers.
+ * Strings and booleans are strings and booleans.
+ * Models are sequences, and structs are maps. The underscores of the field names of the Rust
+   structs correspond to the dashes of the field names of the `.slint` structs.
+ * Colors are strings such as `"#ff000080"`, and images are the path of their file.
+ * Enumeration values are strings. Unit variants of a Rust enum convert to an enumeration value of
+   the `.slint` enum with the same name.
+ * `Value::Void` is the unit, and `None`.
+
+Deserializing a [`Value`] from a format such as JSON, which does not know the types of the
+`.slint` file, only produces numbers, strings, booleans, models and structs.
+*/
+
+use crate::api::{Struct, Value};
+use i_slint_core::model::{Model, ModelRc, VecModel};
+use i_slint_core::{Brush, Color};
+use serde::de::{self, IntoDeserializer, Visitor};
+use serde::ser::{self, Impossible, SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Error returned by [`to_value`] and [`from_value`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{0}")]
+pub struct ValueConversionError(String);
+
+impl ser::Error for ValueConversionError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+impl de::Error for ValueConversionError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// Returns true if the number has no fractional part, and is exactly represented by a `f64` and an `i64`
+fn is_integer(n: f64) -> bool {
+    n.fract() == 0. && n.abs() <= (1u64 << 53) as f64
+}
+
+fn color_to_string(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}{:02x}", color.red(), color.green(), color.blue(), color.alpha())
+}
+
+impl Serialize for Value {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Value::Void => serializer.serialize_unit(),
+            Value::Number(n) if is_integer(*n) => serializer.serialize_i64(*n as i64),
+            Value::Number(n) => serializer.serialize_f64(*n),
+            Value::String(s) => serializer.serialize_str(s),
+            Value::Bool(b) => serializer.serialize_bool(*b),
+            Value::Image(image) => match image.path() {
+                Some(path) => serializer.serialize_str(&path.to_string_lossy()),
+                None => {
+                    Err(ser::Error::custom("only the images loaded from a file can be serialized"))
+                }
+            },
+            Value::Model(model) => {
+                let mut seq = serializer.serialize_seq(Some(model.row_count()))?;
+                for row in model.iter() {
+                    seq.serialize_element(&row)?;
+                }
+                seq.end()
+            }
+            Value::Struct(s) => {
+                let mut fields = s.iter().collect::<Vec<_>>();
+                fields.sort_by(|a, b| a.0.cmp(b.0));
+                let mut map = serializer.serialize_map(Some(fields.len()))?;
+                for (name, value) in fields {
+                    map.serialize_entry(name, value)?;
+                }
+                map.end()
+            }
+            Value::Brush(Brush::SolidColor(color)) => {
+                serializer.serialize_str(&color_to_string(*color))
+            }
+            Value::EnumerationValue(_, value) => serializer.serialize_str(value),
+            _ => Err(ser::Error::custom(format!("{:?} cannot be serialized", self))),
+        }
+    }
+}
+
+struct ValueVisitor;
+
+impl<'de> Visitor<'de> for ValueVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a value that can be stored in a Value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Value, E> {
+        Ok(Value::Bool(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Value, E> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Value, E> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Value, E> {
+        Ok(Value::Number(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Value, E> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Void)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Void)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Value::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut rows = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(row) = seq.next_element()? {
+            rows.push(row);
+        }
+        Ok(Value::Model(ModelRc::new(VecModel::from(rows))))
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut s = Struct::default();
+        while let Some((name, value)) = map.next_entry::<String, Value>()? {
+            s.set_field(name, value);
+        }
+        Ok(Value::Struct(s))
+    }
+}
+
+impl<'de> Deserialize<'de> for Value {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ValueVisitor)
+    }
+}
+
+/// Converts a Rust value to a [`Value`], for example a struct that derives `serde::Serialize` to a
+/// `Value::Struct` that can be set to a property of a struct type.
+///
+/// ```
+/// # use slint_interpreter::*;
+/// #[derive(serde::Serialize)]
+/// struct Item {
+///     title: String,
+///     done: bool,
+/// }
+/// let value = to_value(&Item { title: "Write the docs".into(), done: false }).unwrap();
+/// let item: Struct = value.try_into().unwrap();
+/// assert_eq!(item.get_field("title"), Some(&Value::String("Write the docs".into())));
+/// assert_eq!(item.get_field("done"), Some(&Value::Bool(false)));
+/// ```
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value, ValueConversionError> {
+    value.serialize(ValueSerializer)
+}
+
+/// Converts a [`Value`] to a Rust value, for example a `Value::Struct` read from a property to a struct
+/// that derives `serde::Deserialize`.
+///
+/// The fields of the structs that are missing in the Rust struct are ignored, unless the struct denies
+/// unknown fields.
+pub fn from_value<T: serde::de::DeserializeOwned>(value: Value) -> Result<T, ValueConversionError> {
+    T::deserialize(value)
+}
+
+/// The serializer used by [`to_value`]
+struct ValueSerializer;
+
+impl Serializer for ValueSerializer {
+    type Ok = Value;
+    type Error = ValueConversionError;
+    type SerializeSeq = SeqSerializer;
+    type SerializeTuple = SeqSerializer;
+    type SerializeTupleStruct = SeqSerializer;
+    type SerializeTupleVariant = Impossible<Value, ValueConversionError>;
+    type SerializeMap = StructSerializer;
+    type SerializeStruct = StructSerializer;
+    type SerializeStructVariant = Impossible<Value, ValueConversionError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Value, Self::Error> {
+        Ok(Value::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v as f64))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Value, Self::Error> {
+        Ok(Value::Number(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Value, Self::Error> {
+        Ok(Value::String(v.to_string().into()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Value, Self::Error> {
+        Ok(Value::String(v.into()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Value, Self::Error> {
+        let rows = v.iter().map(|b| Value::Number((*b).into())).collect::<Vec<_>>();
+        Ok(Value::Model(ModelRc::new(VecModel::from(rows))))
+    }
+
+    fn serialize_none(self) -> Result<Value, Self::Error> {
+        Ok(Value::Void)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Value, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Value, Self::Error> {
+        Ok(Value::Void)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Value, Self::Error> {
+        Ok(Value::Void)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Value, Self::Error> {
+        Ok(Value::EnumerationValue(name.into(), variant.replace('_', "-")))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Value, Self::Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Value, Self::Error> {
+        Err(ser::Error::custom(format!(
+            "the variant {}::{} cannot be converted: only the unit variants are supported",
+            name, variant
+        )))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(SeqSerializer(Vec::with_capacity(len.unwrap_or_default())))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(ser::Error::custom(format!(
+            "the variant {}::{} cannot be converted: only the unit variants are supported",
+            name, variant
+        )))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(StructSerializer::default())
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(StructSerializer::default())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(ser::Error::custom(format!(
+            "the variant {}::{} cannot be converted: only the unit variants are supported",
+            name, variant
+        )))
+    }
+}
+
+/// Serializes the sequences and the tuples to a `Value::Model`
+struct SeqSerializer(Vec<Value>);
+
+impl ser::SerializeSeq for SeqSerializer {
+    type Ok = Value;
+    type Error = ValueConversionError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        self.0.push(to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Model(ModelRc::new(VecModel::from(self.0))))
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer {
+    type Ok = Value;
+    type Error = ValueConversionError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer {
+    type Ok = Value;
+    type Error = ValueConversionError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        ser::SerializeSeq::serialize_element(self, value)
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        ser::SerializeSeq::end(self)
+    }
+}
+
+/// Serializes the structs and the maps to a `Value::Struct`
+#[derive(Default)]
+struct StructSerializer {
+    fields: Struct,
+    key: Option<String>,
+}
+
+impl ser::SerializeMap for StructSerializer {
+    type Ok = Value;
+    type Error = ValueConversionError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+        self.key = Some(match to_value(key)? {
+            Value::String(s) => s.into(),
+            Value::Number(n) => n.to_string(),
+            Value::EnumerationValue(_, value) => value,
+            key => {
+                return Err(ser::Error::custom(format!("{:?} cannot be the name of a field", key)))
+            }
+        });
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Self::Error> {
+        let key = self.key.take().ok_or_else(|| ser::Error::custom("value without a key"))?;
+        self.fields.set_field(key, to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Struct(self.fields))
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer {
+    type Ok = Value;
+    type Error = ValueConversionError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Self::Error> {
+        self.fields.set_field(key.into(), to_value(value)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Value, Self::Error> {
+        Ok(Value::Struct(self.fields))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, ValueConversionError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// The deserializer used by [`from_value`]
+impl<'de> Deserializer<'de> for Value {
+    type Error = ValueConversionError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::Void => visitor.visit_unit(),
+            Value::Number(n) if is_integer(n) => visitor.visit_i64(n as i64),
+            Value::Number(n) => visitor.visit_f64(n),
+            Value::String(s) => visitor.visit_str(&s),
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::Image(image) => match image.path() {
+                Some(path) => visitor.visit_string(path.to_string_lossy().into_owned()),
+                None => {
+                    Err(de::Error::custom("only the images loaded from a file can be converted"))
+                }
+            },
+            Value::Model(model) => visitor.visit_seq(de::value::SeqDeserializer::new(model.iter())),
+            Value::Struct(s) => {
+                visitor.visit_map(de::value::MapDeserializer::new(s.iter().map(|(name, value)| {
+                    // The Rust fields have underscores where the .slint fields have dashes
+                    (name.replace('-', "_"), value.clone())
+                })))
+            }
+            Value::Brush(Brush::SolidColor(color)) => visitor.visit_string(color_to_string(color)),
+            Value::EnumerationValue(_, value) => visitor.visit_string(value),
+            value => Err(de::Error::custom(format!("{:?} cannot be converted", value))),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self {
+            Value::Void => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = match self {
+            Value::EnumerationValue(_, value) => value,
+            Value::String(value) => value.into(),
+            value => {
+                return Err(de::Error::custom(format!("{:?} is not a value of {}", value, name)))
+            }
+        };
+        // The values of the .slint enums have dashes where the Rust variants might have underscores
+        let variant = variants
+            .iter()
+            .find(|variant| variant.replace('_', "-") == value)
+            .map_or(value, |variant| variant.to_string());
+        visitor.visit_enum(variant.into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+#[test]
+fn rust_struct_conversion() {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[serde(rename_all = "kebab-case")]
+    enum TextWrap {
+        NoWrap,
+        WordWrap,
+    }
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Item {
+        text_value: String,
+        count: i32,
+        ratio: f32,
+        tags: Vec<String>,
+        wrap: TextWrap,
+        note: Option<String>,
+    }
+    let item = Item {
+        text_value: "hello".into(),
+        count: 3,
+        ratio: 0.5,
+        tags: vec!["a".into(), "b".into()],
+        wrap: TextWrap::WordWrap,
+        note: None,
+    };
+    let value = to_value(&item).unwrap();
+    let s: Struct = value.clone().try_into().unwrap();
+    assert_eq!(s.get_field("text-value"), Some(&Value::String("hello".into())));
+    assert_eq!(s.get_field("count"), Some(&Value::Number(3.)));
+    assert_eq!(
+        s.get_field("wrap"),
+        Some(&Value::EnumerationValue("TextWrap".into(), "word-wrap".into()))
+    );
+    assert_eq!(s.get_field("note"), Some(&Value::Void));
+    assert_eq!(from_value::<Item>(value).unwrap(), item);
+
+    // Enumeration values convert to the variants with underscores
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    #[allow(non_camel_case_types)]
+    enum Wrap {
+        no_wrap,
+        word_wrap,
+    }
+    assert_eq!(
+        from_value::<Wrap>(Value::EnumerationValue("TextWrap".into(), "word-wrap".into())),
+        Ok(Wrap::word_wrap)
+    );
+    assert!(from_value::<i32>(Value::String("3".into())).is_err());
+}
+
+#[test]
+fn json_conversion() {
+    let value: Value = serde_json::from_str(
+        r#"[{ "title": "first", "done": true, "size": 1.5 }, { "title": "second" }]"#,
+    )
+    .unwrap();
+    let model = match &value {
+        Value::Model(model) => model.clone(),
+        _ => panic!("{:?} is not a model", value),
+    };
+    assert_eq!(model.row_count(), 2);
+    let first: Struct = model.row_data(0).unwrap().try_into().unwrap();
+    assert_eq!(first.get_field("title"), Some(&Value::String("first".into())));
+    assert_eq!(first.get_field("done"), Some(&Value::Bool(true)));
+    assert_eq!(first.get_field("size"), Some(&Value::Number(1.5)));
+
+    assert_eq!(
+        serde_json::to_string(&value).unwrap(),
+        r#"[{"done":true,"size":1.5,"title":"first"},{"title":"second"}]"#
+    );
+    assert_eq!(
+        serde_json::to_string(&Value::Brush(Brush::SolidColor(Color::from_rgb_u8(255, 0, 0))))
+            .unwrap(),
+        r#""#ff0000ff""#
+    );
+}
@@ -9,7 +9,7 @@
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
-    /// Set output format
+    /// Set output format (cpp, rust, interface or llr)
     #[clap(short = 'f', long = "format", default_value = "cpp")]
     format: generator::OutputFormat,
 
@@ -21,10 +21,18 @@ struct Cli {
     #[clap(name = "file", parse(from_os_str))]
     path: std::path::PathBuf,
 
-    /// The style name ('native' or 'fluent')
+    /// The style name ('native' or 'fluent'), or the path of the directory of a custom style
     #[clap(long, name = "style name")]
     style: Option<String>,
 
+    /// Font file to embed in the generated code and register at startup
+    #[clap(long = "embed-font", name = "font file", number_of_values = 1, parse(from_os_str))]
+    embed_fonts: Vec<std::path::PathBuf>,
+
+    /// Reduce the embedded fonts to the glyphs used by the design
+    #[clap(long)]
+    subset_fonts: bool,
+
     /// Generate a dependency file
     #[clap(name = "dependency file", long = "depfile", number_of_values = 1, parse(from_os_str))]
     depfile: Option<std::path::PathBuf>,
@@ -49,6 +57,8 @@ fn main() -> std::io::Result<()> {
     if let Some(style) = args.style {
         compiler_config.style = Some(style);
     }
+    compiler_config.embed_fonts = args.embed_fonts;
+    compiler_config.subset_fonts = args.subset_fonts;
     let syntax_node = syntax_node.expect("diags contained no compilation errors");
     let (doc, diag) = spin_on::spin_on(compile_syntax_node(syntax_node, diag, compiler_config));
 
@@ -261,6 +261,15 @@ fn resolve_element_scope(
                 c.kind = Some(CompletionItemKind::METHOD);
                 c
             }))
+            .chain(element.Function().map(|f| {
+                let mut c = CompletionItem::new_simple(
+                    i_slint_compiler::parser::identifier_text(&f.DeclaredIdentifier())
+                        .unwrap_or_default(),
+                    "function".into(),
+                );
+                c.kind = Some(CompletionItemKind::FUNCTION);
+                c
+            }))
             .chain(i_slint_compiler::typeregister::reserved_properties().filter_map(|(k, t)| {
                 if matches!(t, Type::Function { .. }) {
                     return None;
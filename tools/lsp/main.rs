@@ -48,7 +48,7 @@ struct Cli {
     )]
     include_paths: Vec<std::path::PathBuf>,
 
-    /// The style name for the preview ('native' or 'fluent')
+    /// The style name for the preview ('native' or 'fluent'), or the path of the directory of a custom style
     #[clap(long, name = "style name", default_value_t)]
     style: String,
 
@@ -258,8 +258,13 @@ fn handle_request(
         connection.sender.send(Message::Response(resp))?;*/
         connection.sender.send(Message::Response(Response::new_ok(id, None::<Hover>)))?;
     } else if let Some((id, params)) = cast::<CodeActionRequest>(&mut req) {
-        let result = token_descr(document_cache, params.text_document, params.range.start)
-            .and_then(|token| get_code_actions(document_cache, token.0.parent()));
+        let mut result = util::quick_fixes(&params.text_document.uri, &params.context.diagnostics);
+        result.extend(
+            token_descr(document_cache, params.text_document, params.range.start)
+                .and_then(|token| get_code_actions(document_cache, token.0.parent()))
+                .unwrap_or_default(),
+        );
+        let result = (!result.is_empty()).then(|| result);
         connection.sender.send(Message::Response(Response::new_ok(id, result)))?;
     } else if let Some((id, params)) = cast::<ExecuteCommand>(&mut req) {
         if params.command.as_str() == SHOW_PREVIEW_COMMAND {
@@ -584,6 +589,7 @@ fn get_document_symbols(
 
     let inner_components = doc.inner_components.clone();
     let inner_structs = doc.inner_structs.clone();
+    let inner_enums = doc.inner_enums.clone();
     let mut make_range = |node: &SyntaxNode| {
         let r = node.text_range();
         Some(Range::new(
@@ -617,6 +623,15 @@ fn get_document_symbols(
         _ => None,
     }));
 
+    r.extend(inner_enums.iter().filter_map(|e| {
+        Some(SymbolInformation {
+            location: Location::new(uri.clone(), make_range(e.node.as_ref()?)?),
+            name: e.name.clone(),
+            kind: lsp_types::SymbolKind::ENUM,
+            ..si.clone()
+        })
+    }));
+
     Some(r.into())
 
     // TODO: add the structs
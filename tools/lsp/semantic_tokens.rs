@@ -51,8 +51,10 @@ pub fn get_semantic_tokens(
                 SyntaxKind::RepeatedElement => Some((self::KEYWORD, 0)),
                 SyntaxKind::RepeatedIndex => Some((self::VARIABLE, 1 << self::DEFINITION)),
                 SyntaxKind::ConditionalElement => Some((self::KEYWORD, 0)),
+                SyntaxKind::ConditionalElse => Some((self::KEYWORD, 0)),
                 SyntaxKind::CallbackDeclaration => Some((self::KEYWORD, 0)),
                 SyntaxKind::CallbackConnection => Some((self::FUNCTION, 0)),
+                SyntaxKind::Function => Some((self::KEYWORD, 0)),
                 SyntaxKind::PropertyDeclaration => Some((self::KEYWORD, 0)),
                 SyntaxKind::PropertyAnimation => Some((self::KEYWORD, 0)),
                 SyntaxKind::QualifiedName => match token.parent().parent()?.kind() {
@@ -62,6 +64,7 @@ pub fn get_semantic_tokens(
                     // FIXME: we should do actual lookup
                     SyntaxKind::Expression => None,
                     SyntaxKind::StatePropertyChange => Some((self::PROPERTY, 0)),
+                    SyntaxKind::StateTrigger => Some((self::FUNCTION, 0)),
                     SyntaxKind::PropertyAnimation => Some((self::PROPERTY, 0)),
                     _ => None,
                 },
@@ -77,14 +80,19 @@ pub fn get_semantic_tokens(
                         SyntaxKind::CallbackConnection => {
                             Some((self::PARAMETER, 1 << self::DEFINITION))
                         }
+                        SyntaxKind::Function => Some((self::FUNCTION, 1 << self::DEFINITION)),
+                        SyntaxKind::ArgumentDeclaration => {
+                            Some((self::PARAMETER, 1 << self::DEFINITION))
+                        }
                         SyntaxKind::PropertyDeclaration => {
                             Some((self::PROPERTY, 1 << self::DEFINITION))
                         }
-                        SyntaxKind::State | SyntaxKind::Transition => {
+                        SyntaxKind::State | SyntaxKind::Transition | SyntaxKind::StateTrigger => {
                             // This is the state name, but what semantic type is that?
                             None
                         }
                         SyntaxKind::StructDeclaration => Some((self::TYPE, 1 << self::DEFINITION)),
+                        SyntaxKind::EnumDeclaration => Some((self::TYPE, 1 << self::DEFINITION)),
                         _ => None,
                     }
                 }
@@ -94,9 +102,13 @@ pub fn get_semantic_tokens(
                 SyntaxKind::AtImageUrl => Some((self::MACRO, 0)),
                 SyntaxKind::AtLinearGradient => Some((self::MACRO, 0)),
                 SyntaxKind::ConditionalExpression => Some((self::KEYWORD, 0)),
+                SyntaxKind::MatchExpression => Some((self::KEYWORD, 0)),
+                SyntaxKind::MatchArm => Some((self::KEYWORD, 0)),
                 SyntaxKind::ObjectMember => Some((self::PROPERTY, 1 << self::DECLARATION)),
+                SyntaxKind::ObjectDefault => Some((self::KEYWORD, 0)),
                 SyntaxKind::States => Some((self::KEYWORD, 0)),
                 SyntaxKind::State => Some((self::KEYWORD, 0)),
+                SyntaxKind::StateTrigger => Some((self::KEYWORD, 0)),
                 SyntaxKind::Transitions => Some((self::KEYWORD, 0)),
                 SyntaxKind::Transition => Some((self::KEYWORD, 0)),
                 SyntaxKind::ExportsList => Some((self::KEYWORD, 0)),
@@ -129,6 +141,8 @@ pub fn get_semantic_tokens(
                 SyntaxKind::InternalName => Some((self::TYPE, 1 << self::DECLARATION)),
                 SyntaxKind::ObjectTypeMember => Some((self::PROPERTY, 1 << self::DEFINITION)),
                 SyntaxKind::StructDeclaration => Some((self::KEYWORD, 0)),
+                SyntaxKind::EnumDeclaration => Some((self::KEYWORD, 0)),
+                SyntaxKind::EnumValue => Some((self::VARIABLE, 1 << self::DEFINITION)),
                 _ => None,
             },
             SyntaxKind::PlusEqual
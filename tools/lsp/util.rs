@@ -110,7 +110,15 @@ fn lookup_expression_context(mut n: SyntaxNode) -> Option<(syntax_nodes::Element
                 break (element, prop_name);
             }
             SyntaxKind::ConditionalElement | SyntaxKind::RepeatedElement => {
-                let element = syntax_nodes::Element::new(n.parent()?)?;
+                let mut parent = n.parent()?;
+                // skip the previous branches of a `if ... else if`
+                while matches!(
+                    parent.kind(),
+                    SyntaxKind::ConditionalElse | SyntaxKind::ConditionalElement
+                ) {
+                    parent = parent.parent()?;
+                }
+                let element = syntax_nodes::Element::new(parent)?;
                 break (element, "$model".to_string());
             }
             SyntaxKind::Element => {
@@ -126,7 +134,7 @@ fn lookup_expression_context(mut n: SyntaxNode) -> Option<(syntax_nodes::Element
 }
 
 pub fn to_lsp_diag(d: &i_slint_compiler::diagnostics::Diagnostic) -> lsp_types::Diagnostic {
-    lsp_types::Diagnostic::new(
+    let mut diag = lsp_types::Diagnostic::new(
         to_range(d.line_column()),
         Some(to_lsp_diag_level(d.level())),
         None,
@@ -134,14 +142,67 @@ pub fn to_lsp_diag(d: &i_slint_compiler::diagnostics::Diagnostic) -> lsp_types::
         d.message().to_owned(),
         None,
         None,
+    );
+    if !d.fix_its().is_empty() {
+        // The fix-its are kept in the diagnostic, so that they can be turned into quick fixes
+        // when the client requests the code actions for that diagnostic
+        let fix_its = d
+            .fix_its()
+            .iter()
+            .map(|fix_it| {
+                let (start, end) = d.fix_it_range(fix_it);
+                let range = lsp_types::Range::new(to_position(start), to_position(end));
+                (
+                    fix_it.message.clone(),
+                    lsp_types::TextEdit::new(range, fix_it.replacement.clone()),
+                )
+            })
+            .collect::<Vec<_>>();
+        diag.data = serde_json::to_value(fix_its).ok();
+    }
+    diag
+}
+
+/// Return the quick fixes for the fix-its stored in the diagnostics by [`to_lsp_diag`]
+pub fn quick_fixes(
+    uri: &lsp_types::Url,
+    diagnostics: &[lsp_types::Diagnostic],
+) -> Vec<lsp_types::CodeActionOrCommand> {
+    diagnostics
+        .iter()
+        .flat_map(|diag| {
+            let fix_its = diag
+                .data
+                .clone()
+                .and_then(|data| {
+                    serde_json::from_value::<Vec<(String, lsp_types::TextEdit)>>(data).ok()
+                })
+                .unwrap_or_default();
+            fix_its.into_iter().map(move |(title, edit)| {
+                lsp_types::CodeActionOrCommand::CodeAction(lsp_types::CodeAction {
+                    title,
+                    kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![diag.clone()]),
+                    edit: Some(lsp_types::WorkspaceEdit::new(
+                        std::iter::once((uri.clone(), vec![edit])).collect(),
+                    )),
+                    is_preferred: Some(true),
+                    ..Default::default()
+                })
+            })
+        })
+        .collect()
+}
+
+fn to_position(line_column: (usize, usize)) -> lsp_types::Position {
+    lsp_types::Position::new(
+        (line_column.0 as u32).saturating_sub(1),
+        (line_column.1 as u32).saturating_sub(1),
     )
 }
 
 fn to_range(span: (usize, usize)) -> lsp_types::Range {
-    let pos = lsp_types::Position::new(
-        (span.0 as u32).saturating_sub(1),
-        (span.1 as u32).saturating_sub(1),
-    );
+    let pos = to_position(span);
     lsp_types::Range::new(pos, pos)
 }
 
@@ -4,9 +4,15 @@
 #![doc = include_str!("README.md")]
 
 use i_slint_core::model::{Model, ModelRc};
+use i_slint_core::properties::PropertyTracker;
+use i_slint_core::timers::{Timer, TimerMode};
 use i_slint_core::SharedVector;
-use slint_interpreter::{ComponentHandle, ComponentInstance, SharedString, Value};
+use slint_interpreter::{ComponentHandle, ComponentInstance, SharedString, TypeInfo, Value};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::future::Future;
+use std::io::Write;
+use std::path::Path;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
@@ -32,7 +38,7 @@ struct Cli {
     #[clap(name = "path to .slint file", parse(from_os_str))]
     path: std::path::PathBuf,
 
-    /// The style name ('native' or 'fluent')
+    /// The style name ('native' or 'fluent'), or the path of the directory of a custom style
     #[clap(long, name = "style name")]
     style: Option<String>,
 
@@ -44,7 +50,7 @@ struct Cli {
     #[clap(long)]
     auto_reload: bool,
 
-    /// Load properties from a json file ('-' for stdin)
+    /// Load properties from a json file ('-' for stdin). The file is loaded again when it changes
     #[clap(long, name = "load data file", parse(from_os_str))]
     load_data: Option<std::path::PathBuf>,
 
@@ -52,6 +58,11 @@ struct Cli {
     #[clap(long, name = "save data file", parse(from_os_str))]
     save_data: Option<std::path::PathBuf>,
 
+    /// Write the callback invocations and the property changes as JSON, one line per event,
+    /// to a file ('-' for stdout)
+    #[clap(long, name = "event log file", parse(from_os_str))]
+    log_events: Option<std::path::PathBuf>,
+
     /// Specify callbacks handler.
     /// The first argument is the callback name, and the second argument is a string that is going
     /// to be passed to the shell to be executed. Occurences of `$1` will be replaced by the first argument,
@@ -61,6 +72,9 @@ struct Cli {
 }
 
 thread_local! {static CURRENT_INSTANCE: std::cell::RefCell<Option<ComponentInstance>> = Default::default();}
+thread_local! {static EVENT_LOG: RefCell<Option<Box<dyn Write>>> = Default::default();}
+/// The timer that checks the public properties for changes, for the event log
+thread_local! {static PROPERTY_WATCHER: RefCell<Option<Timer>> = Default::default();}
 static EXIT_CODE: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(0);
 
 fn main() -> Result<()> {
@@ -71,12 +85,32 @@ fn main() -> Result<()> {
         std::process::exit(-1);
     }
 
+    let stdout = Path::new("-");
+    if args.log_events.as_deref() == Some(stdout) && args.save_data.as_deref() == Some(stdout) {
+        eprintln!("Cannot pass both --log-events and --save-data to the standard output");
+        std::process::exit(-1);
+    }
+
     if let Some(backend) = &args.backend {
         std::env::set_var("SLINT_BACKEND", backend);
     }
 
-    let fswatcher = if args.auto_reload { Some(start_fswatch_thread(args.clone())?) } else { None };
-    let mut compiler = init_compiler(&args, fswatcher);
+    if let Some(log_path) = &args.log_events {
+        let log: Box<dyn Write> = if log_path == stdout {
+            Box::new(std::io::stdout())
+        } else {
+            Box::new(std::fs::File::create(log_path)?)
+        };
+        EVENT_LOG.with(|event_log| event_log.replace(Some(log)));
+    }
+
+    let fswatcher = if args.auto_reload || watched_data_path(&args).is_some() {
+        Some(start_fswatch_thread(args.clone())?)
+    } else {
+        None
+    };
+    let mut compiler =
+        init_compiler(&args, if args.auto_reload { fswatcher.clone() } else { None });
 
     let c = spin_on::spin_on(compiler.build_from_path(args.path));
     slint_interpreter::print_diagnostics(compiler.diagnostics());
@@ -87,14 +121,16 @@ fn main() -> Result<()> {
     };
 
     let component = c.create();
+    install_logging_callbacks(&component);
     init_dialog(&component);
 
-    if let Some(data_path) = args.load_data {
-        load_data(&component, &data_path)?;
+    if let Some(data_path) = &args.load_data {
+        load_data(&component, data_path)?;
     }
     install_callbacks(&component, &args.on);
+    watch_property_changes(&component);
 
-    if args.auto_reload {
+    if fswatcher.is_some() {
         CURRENT_INSTANCE.with(|current| current.replace(Some(component.clone_strong())));
     }
 
@@ -149,39 +185,29 @@ fn init_compiler(
         compiler.set_style(style.clone());
     }
     if let Some(watcher) = fswatcher {
-        notify::Watcher::watch(
-            &mut *watcher.lock().unwrap(),
-            &args.path,
-            notify::RecursiveMode::NonRecursive,
-        )
-        .unwrap_or_else(|err| {
-            eprintln!("Warning: error while watching {}: {:?}", args.path.display(), err)
-        });
-        if let Some(data_path) = &args.load_data {
-            notify::Watcher::watch(
-                &mut *watcher.lock().unwrap(),
-                data_path,
-                notify::RecursiveMode::NonRecursive,
-            )
-            .unwrap_or_else(|err| {
-                eprintln!("Warning: error while watching {}: {:?}", data_path.display(), err)
-            });
-        }
+        watch_path(&watcher, &args.path);
         compiler.set_file_loader(move |path| {
-            notify::Watcher::watch(
-                &mut *watcher.lock().unwrap(),
-                &path,
-                notify::RecursiveMode::NonRecursive,
-            )
-            .unwrap_or_else(|err| {
-                eprintln!("Warning: error while watching {}: {:?}", path.display(), err)
-            });
+            watch_path(&watcher, path);
             Box::pin(async { None })
         })
     }
     compiler
 }
 
+fn watch_path(watcher: &Mutex<notify::RecommendedWatcher>, path: &Path) {
+    notify::Watcher::watch(
+        &mut *watcher.lock().unwrap(),
+        path,
+        notify::RecursiveMode::NonRecursive,
+    )
+    .unwrap_or_else(|err| eprintln!("Warning: error while watching {}: {:?}", path.display(), err));
+}
+
+/// The data file that is loaded again when it changes, if any
+fn watched_data_path(args: &Cli) -> Option<&Path> {
+    args.load_data.as_deref().filter(|path| *path != Path::new("-"))
+}
+
 fn init_dialog(instance: &ComponentInstance) {
     for cb in instance.definition().callbacks() {
         let exit_code = match cb.as_str() {
@@ -192,7 +218,8 @@ fn init_dialog(instance: &ComponentInstance) {
         // this is a dialog, so clicking the "x" should cancel
         EXIT_CODE.store(1, std::sync::atomic::Ordering::Relaxed);
         instance
-            .set_callback(&cb, move |_| {
+            .set_callback(&cb.clone(), move |args| {
+                log_callback(&cb, args);
                 EXIT_CODE.store(exit_code, std::sync::atomic::Ordering::Relaxed);
                 i_slint_backend_selector::backend().quit_event_loop();
                 Default::default()
@@ -206,13 +233,30 @@ fn init_dialog(instance: &ComponentInstance) {
 fn start_fswatch_thread(args: Cli) -> Result<Arc<Mutex<notify::RecommendedWatcher>>> {
     let (tx, rx) = std::sync::mpsc::channel();
     let w = Arc::new(Mutex::new(notify::watcher(tx, Duration::from_millis(400))?));
+    let data_path = watched_data_path(&args).map(|path| {
+        watch_path(&w, path);
+        path.canonicalize().unwrap_or_else(|_| path.to_owned())
+    });
     let w2 = w.clone();
     std::thread::spawn(move || {
         while let Ok(event) = rx.recv() {
             use notify::DebouncedEvent::*;
-            if (matches!(event, Write(_) | Remove(_) | Create(_)))
-                && PENDING_EVENTS.load(Ordering::SeqCst) == 0
-            {
+            let path = match &event {
+                Write(path) | Remove(path) | Create(path) => path,
+                _ => continue,
+            };
+            if let Some(data_path) = data_path.as_ref().filter(|data_path| {
+                path == *data_path || path.canonicalize().map_or(false, |p| p == **data_path)
+            }) {
+                // Only the data changed: apply it to the current instance without recompiling
+                if !matches!(event, Remove(_)) {
+                    // The file might have been replaced, so watch it again
+                    watch_path(&w2, data_path);
+                    let data_path = data_path.clone();
+                    i_slint_backend_selector::backend()
+                        .post_event(Box::new(move || reload_data(&data_path)));
+                }
+            } else if args.auto_reload && PENDING_EVENTS.load(Ordering::SeqCst) == 0 {
                 PENDING_EVENTS.fetch_add(1, Ordering::SeqCst);
                 run_in_ui_thread(Box::pin(reload(args.clone(), w2.clone())));
             }
@@ -232,17 +276,20 @@ async fn reload(args: Cli, fswatcher: Arc<Mutex<notify::RecommendedWatcher>>) {
             if let Some(handle) = current.take() {
                 let window = handle.window();
                 let new_handle = c.create_with_existing_window(window);
-                init_dialog(&new_handle);
                 current.replace(new_handle);
             } else {
                 let handle = c.create();
-                init_dialog(&handle);
                 handle.show();
                 current.replace(handle);
             }
-            if let Some(data_path) = args.load_data {
-                let _ = load_data(current.as_ref().unwrap(), &data_path);
+            let instance = current.as_ref().unwrap();
+            install_logging_callbacks(instance);
+            init_dialog(instance);
+            if let Some(data_path) = &args.load_data {
+                let _ = load_data(instance, data_path);
             }
+            install_callbacks(instance, &args.on);
+            watch_property_changes(instance);
             eprintln!("Successful reload of {}", args.path.display());
         });
     }
@@ -250,6 +297,17 @@ async fn reload(args: Cli, fswatcher: Arc<Mutex<notify::RecommendedWatcher>>) {
     PENDING_EVENTS.fetch_sub(1, Ordering::SeqCst);
 }
 
+fn reload_data(data_path: &Path) {
+    CURRENT_INSTANCE.with(|current| {
+        if let Some(instance) = current.borrow().as_ref() {
+            match load_data(instance, data_path) {
+                Ok(()) => eprintln!("Successful reload of {}", data_path.display()),
+                Err(e) => eprintln!("Error while loading {}: {}", data_path.display(), e),
+            }
+        }
+    });
+}
+
 fn load_data(instance: &ComponentInstance, data_path: &std::path::Path) -> Result<()> {
     let json: serde_json::Value = if data_path == std::path::Path::new("-") {
         serde_json::from_reader(std::io::stdin())?
@@ -293,7 +351,9 @@ fn install_callbacks(instance: &ComponentInstance, callbacks: &[String]) {
     for chunk in callbacks.chunks(2) {
         if let [callback, cmd] = chunk {
             let cmd = cmd.clone();
+            let name = callback.clone();
             match instance.set_callback(callback, move |args| {
+                log_callback(&name, args);
                 match execute_cmd(&cmd, args) {
                     Ok(()) => (),
                     Err(e) => eprintln!("Error: {}", e),
@@ -309,6 +369,95 @@ fn install_callbacks(instance: &ComponentInstance, callbacks: &[String]) {
     }
 }
 
+/// Writes an event to the event log, as a line of JSON. `event` is only called if there is an event log.
+fn log_event(event: impl FnOnce() -> serde_json::Value) {
+    EVENT_LOG.with(|event_log| {
+        if let Some(log) = event_log.borrow_mut().as_mut() {
+            let result = serde_json::to_writer(&mut *log, &event())
+                .map_err(std::io::Error::from)
+                .and_then(|()| writeln!(log))
+                .and_then(|()| log.flush());
+            if let Err(e) = result {
+                eprintln!("Error while writing the event log: {}", e);
+            }
+        }
+    })
+}
+
+fn log_callback(name: &str, args: &[Value]) {
+    log_event(|| {
+        let arguments = args
+            .iter()
+            .map(|arg| serde_json::to_value(arg).unwrap_or_default())
+            .collect::<Vec<_>>();
+        serde_json::json!({ "callback": name, "arguments": arguments })
+    })
+}
+
+/// Logs the invocations of the callbacks that don't return a value. The handlers set by
+/// `init_dialog` and `install_callbacks` replace these, and log the invocations too.
+fn install_logging_callbacks(instance: &ComponentInstance) {
+    if EVENT_LOG.with(|event_log| event_log.borrow().is_none()) {
+        return;
+    }
+    for (name, signature) in instance.definition().callback_signatures() {
+        if signature.return_type != TypeInfo::Void {
+            continue;
+        }
+        instance
+            .set_callback(&name.clone(), move |args| {
+                log_callback(&name, args);
+                Value::Void
+            })
+            .unwrap();
+    }
+}
+
+/// The values of the public properties that can be represented in JSON
+fn property_values(instance: &ComponentInstance) -> Vec<(String, serde_json::Value)> {
+    instance
+        .definition()
+        .properties()
+        .filter_map(|(name, _)| {
+            let value = serde_json::to_value(instance.get_property(&name).ok()?).ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// Logs the changes of the public properties of the instance, replacing the watcher of the
+/// previous instance.
+fn watch_property_changes(instance: &ComponentInstance) {
+    if EVENT_LOG.with(|event_log| event_log.borrow().is_none()) {
+        return;
+    }
+    let tracker = Box::pin(PropertyTracker::default());
+    let mut values = tracker
+        .as_ref()
+        .evaluate(|| property_values(instance))
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+    let weak = instance.as_weak();
+    let timer = Timer::default();
+    timer.start(TimerMode::Repeated, Duration::from_millis(50), move || {
+        let instance = match weak.upgrade() {
+            Some(instance) => instance,
+            None => return,
+        };
+        let new_values = match tracker.as_ref().evaluate_if_dirty(|| property_values(&instance)) {
+            Some(new_values) => new_values,
+            None => return,
+        };
+        for (name, value) in new_values {
+            if values.get(&name) != Some(&value) {
+                log_event(|| serde_json::json!({ "property": &name, "value": &value }));
+                values.insert(name, value);
+            }
+        }
+    });
+    PROPERTY_WATCHER.with(|watcher| watcher.replace(Some(timer)));
+}
+
 fn execute_cmd(cmd: &str, callback_args: &[Value]) -> Result<()> {
     let cmd_args = shlex::split(cmd).ok_or("Could not parse the command string")?;
     let program_name = cmd_args.first().ok_or("Missing program name")?;